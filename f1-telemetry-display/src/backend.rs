@@ -0,0 +1,138 @@
+//! Terminals the display is drawn on.
+//!
+//! The views only go through the `Backend` trait: ncurses (the `ncurses`
+//! feature, on by default) or crossterm (the `crossterm` feature), which also
+//! works on Windows.
+
+use crate::config::BackendKind;
+
+#[cfg(feature = "crossterm")]
+pub use self::crossterm::CrosstermBackend;
+#[cfg(feature = "ncurses")]
+pub use self::ncurses::NcursesBackend;
+
+#[cfg(not(any(feature = "ncurses", feature = "crossterm")))]
+compile_error!("f1-telemetry-display needs the ncurses or the crossterm feature");
+
+#[cfg(feature = "crossterm")]
+mod crossterm;
+#[cfg(feature = "ncurses")]
+mod ncurses;
+
+/// Colour of the text or of its background.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum Color {
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+    Rgb(u8, u8, u8),
+}
+
+/// Attributes of the text. None leaves the colour to the terminal default.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash)]
+pub struct Style {
+    pub fg: Option<Color>,
+    pub bg: Option<Color>,
+    pub bold: bool,
+    pub reverse: bool,
+}
+
+impl Style {
+    pub fn fg(color: Color) -> Style {
+        Style {
+            fg: Some(color),
+            ..Style::default()
+        }
+    }
+
+    pub fn bold(self) -> Style {
+        Style { bold: true, ..self }
+    }
+
+    pub fn reverse(self) -> Style {
+        Style {
+            reverse: true,
+            ..self
+        }
+    }
+}
+
+/// Key pressed, as far as the display is concerned.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Key {
+    Char(char),
+    Tab,
+    BackTab,
+    Escape,
+    Up,
+    Down,
+    Left,
+    Right,
+    PageUp,
+    PageDown,
+    /// Ctrl+C, when it does not raise a signal.
+    Interrupt,
+    Other,
+}
+
+/// A terminal, drawn on like a grid of cells with a cursor. Rows and columns
+/// are counted from 0 at the top left corner.
+///
+/// What is drawn may only be shown after `flush`.
+pub trait Backend {
+    /// Size of the screen, as rows and columns.
+    fn size(&self) -> (i32, i32);
+
+    fn move_to(&self, y: i32, x: i32);
+
+    /// Print `s` at the cursor, in the current style.
+    fn print(&self, s: &str);
+
+    fn print_at(&self, y: i32, x: i32, s: &str) {
+        self.move_to(y, x);
+        self.print(s);
+    }
+
+    /// Clear from the cursor to the end of the line.
+    fn clear_line(&self);
+
+    /// Clear from the cursor to the end of the screen.
+    fn clear_below(&self);
+
+    /// Style of the text printed next, replacing the current one.
+    fn set_style(&self, style: Style);
+
+    fn reset_style(&self) {
+        self.set_style(Style::default());
+    }
+
+    fn flush(&self);
+
+    /// Key pressed since the last call, without waiting.
+    fn read_key(&self) -> Option<Key>;
+
+    /// Give the terminal back in the state it was found.
+    fn restore(&self);
+}
+
+/// Set up the terminal for the display with the backend `kind`.
+pub fn open(kind: BackendKind) -> Result<Box<dyn Backend>, String> {
+    match kind {
+        #[cfg(feature = "ncurses")]
+        BackendKind::Ncurses => Ok(Box::new(NcursesBackend::open())),
+        #[cfg(feature = "crossterm")]
+        BackendKind::Crossterm => CrosstermBackend::open()
+            .map(|b| Box::new(b) as Box<dyn Backend>)
+            .map_err(|e| format!("Unable to set up the terminal: {}", e)),
+        #[allow(unreachable_patterns)]
+        kind => Err(format!(
+            "The {:?} backend is not enabled in this build",
+            kind
+        )),
+    }
+}
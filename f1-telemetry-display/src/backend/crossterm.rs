@@ -0,0 +1,129 @@
+use crossterm::cursor::{Hide, MoveTo, Show};
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyModifiers};
+use crossterm::style::{
+    self, Attribute, Print, SetAttribute, SetBackgroundColor, SetForegroundColor,
+};
+use crossterm::terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen};
+use crossterm::{execute, queue};
+use std::io::{self, Stdout, Write};
+use std::time::Duration;
+
+use super::{Backend, Color, Key, Style};
+
+/// Terminal driven by crossterm, in raw mode on the alternate screen.
+///
+/// Errors writing to the terminal are ignored, like ncurses does.
+pub struct CrosstermBackend {
+    stdout: Stdout,
+}
+
+impl CrosstermBackend {
+    pub fn open() -> io::Result<CrosstermBackend> {
+        let mut stdout = io::stdout();
+
+        terminal::enable_raw_mode()?;
+        execute!(stdout, EnterAlternateScreen, Hide, Clear(ClearType::All))?;
+
+        Ok(CrosstermBackend { stdout })
+    }
+}
+
+fn color(color: Color) -> style::Color {
+    match color {
+        Color::Black => style::Color::Black,
+        Color::Red => style::Color::DarkRed,
+        Color::Green => style::Color::DarkGreen,
+        Color::Yellow => style::Color::DarkYellow,
+        Color::Blue => style::Color::DarkBlue,
+        Color::Magenta => style::Color::DarkMagenta,
+        Color::Cyan => style::Color::DarkCyan,
+        Color::White => style::Color::Grey,
+        Color::Rgb(r, g, b) => style::Color::Rgb { r, g, b },
+    }
+}
+
+impl Backend for CrosstermBackend {
+    fn size(&self) -> (i32, i32) {
+        let (columns, rows) = terminal::size().unwrap_or((0, 0));
+        (i32::from(rows), i32::from(columns))
+    }
+
+    fn move_to(&self, y: i32, x: i32) {
+        let _ = queue!(self.stdout.lock(), MoveTo(x.max(0) as u16, y.max(0) as u16));
+    }
+
+    fn print(&self, s: &str) {
+        let _ = queue!(self.stdout.lock(), Print(s));
+    }
+
+    fn clear_line(&self) {
+        let _ = queue!(self.stdout.lock(), Clear(ClearType::UntilNewLine));
+    }
+
+    fn clear_below(&self) {
+        let _ = queue!(self.stdout.lock(), Clear(ClearType::FromCursorDown));
+    }
+
+    fn set_style(&self, style: Style) {
+        let mut stdout = self.stdout.lock();
+
+        let _ = queue!(
+            stdout,
+            SetAttribute(Attribute::Reset),
+            SetForegroundColor(style.fg.map_or(style::Color::Reset, color)),
+            SetBackgroundColor(style.bg.map_or(style::Color::Reset, color))
+        );
+        if style.bold {
+            let _ = queue!(stdout, SetAttribute(Attribute::Bold));
+        }
+        if style.reverse {
+            let _ = queue!(stdout, SetAttribute(Attribute::Reverse));
+        }
+    }
+
+    fn flush(&self) {
+        let _ = self.stdout.lock().flush();
+    }
+
+    fn read_key(&self) -> Option<Key> {
+        self.flush();
+
+        if !event::poll(Duration::from_secs(0)).ok()? {
+            return None;
+        }
+
+        let KeyEvent {
+            code, modifiers, ..
+        } = match event::read().ok()? {
+            Event::Key(key) => key,
+            _ => return Some(Key::Other),
+        };
+
+        let key = match code {
+            KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => Key::Interrupt,
+            KeyCode::Char(c) => Key::Char(c),
+            KeyCode::Tab => Key::Tab,
+            KeyCode::BackTab => Key::BackTab,
+            KeyCode::Esc => Key::Escape,
+            KeyCode::Up => Key::Up,
+            KeyCode::Down => Key::Down,
+            KeyCode::Left => Key::Left,
+            KeyCode::Right => Key::Right,
+            KeyCode::PageUp => Key::PageUp,
+            KeyCode::PageDown => Key::PageDown,
+            _ => Key::Other,
+        };
+
+        Some(key)
+    }
+
+    fn restore(&self) {
+        let _ = execute!(
+            self.stdout.lock(),
+            SetAttribute(Attribute::Reset),
+            Show,
+            LeaveAlternateScreen
+        );
+        let _ = terminal::disable_raw_mode();
+    }
+}
@@ -0,0 +1,158 @@
+use ncurses::*;
+use std::cell::RefCell;
+
+use super::{Backend, Color, Key, Style};
+
+const KEY_INTERRUPT: u32 = 3;
+const KEY_TAB: u32 = 9;
+const KEY_ESCAPE: u32 = 27;
+/// First colour number used for the RGB colours, after the 16 of the terminal.
+const RGB_COLOUR_OFFSET: i16 = 16;
+
+/// Terminal driven by ncurses.
+///
+/// ncurses draws with numbered pairs of foreground and background colours,
+/// which are defined as the styles are used.
+pub struct NcursesBackend {
+    hwnd: WINDOW,
+    rgb_colors: RefCell<Vec<(u8, u8, u8)>>,
+    pairs: RefCell<Vec<(i16, i16)>>,
+}
+
+impl NcursesBackend {
+    pub fn open() -> NcursesBackend {
+        setlocale(LcCategory::all, "");
+
+        let hwnd = initscr();
+
+        curs_set(CURSOR_VISIBILITY::CURSOR_INVISIBLE);
+        cbreak();
+        noecho();
+        keypad(hwnd, true);
+        timeout(0);
+        start_color();
+
+        refresh();
+
+        NcursesBackend {
+            hwnd,
+            rgb_colors: RefCell::new(Vec::new()),
+            pairs: RefCell::new(Vec::new()),
+        }
+    }
+
+    fn color(&self, color: Color) -> i16 {
+        let (r, g, b) = match color {
+            Color::Black => return COLOR_BLACK,
+            Color::Red => return COLOR_RED,
+            Color::Green => return COLOR_GREEN,
+            Color::Yellow => return COLOR_YELLOW,
+            Color::Blue => return COLOR_BLUE,
+            Color::Magenta => return COLOR_MAGENTA,
+            Color::Cyan => return COLOR_CYAN,
+            Color::White => return COLOR_WHITE,
+            Color::Rgb(r, g, b) => (r, g, b),
+        };
+
+        let mut colors = self.rgb_colors.borrow_mut();
+        let index = match colors.iter().position(|&c| c == (r, g, b)) {
+            Some(index) => index,
+            None => {
+                // ncurses components go from 0 to 1000.
+                let scale = |c: u8| (i32::from(c) * 1000 / 255) as i16;
+                init_color(
+                    RGB_COLOUR_OFFSET + colors.len() as i16,
+                    scale(r),
+                    scale(g),
+                    scale(b),
+                );
+                colors.push((r, g, b));
+                colors.len() - 1
+            }
+        };
+
+        RGB_COLOUR_OFFSET + index as i16
+    }
+
+    /// Number of the pair of colours of the style, 0 for the default colours.
+    fn pair(&self, style: Style) -> i16 {
+        if style.fg.is_none() && style.bg.is_none() {
+            return 0;
+        }
+
+        let fg = style.fg.map(|c| self.color(c)).unwrap_or(COLOR_WHITE);
+        let bg = style.bg.map(|c| self.color(c)).unwrap_or(COLOR_BLACK);
+
+        let mut pairs = self.pairs.borrow_mut();
+        let index = match pairs.iter().position(|&p| p == (fg, bg)) {
+            Some(index) => index,
+            None => {
+                init_pair(pairs.len() as i16 + 1, fg, bg);
+                pairs.push((fg, bg));
+                pairs.len() - 1
+            }
+        };
+
+        index as i16 + 1
+    }
+}
+
+impl Backend for NcursesBackend {
+    fn size(&self) -> (i32, i32) {
+        (getmaxy(self.hwnd), getmaxx(self.hwnd))
+    }
+
+    fn move_to(&self, y: i32, x: i32) {
+        mv(y, x);
+    }
+
+    fn print(&self, s: &str) {
+        addstr(s);
+    }
+
+    fn clear_line(&self) {
+        clrtoeol();
+    }
+
+    fn clear_below(&self) {
+        clrtobot();
+    }
+
+    fn set_style(&self, style: Style) {
+        attrset(0);
+        color_set(self.pair(style));
+        if style.bold {
+            attron(A_BOLD());
+        }
+        if style.reverse {
+            attron(A_REVERSE());
+        }
+    }
+
+    fn flush(&self) {
+        refresh();
+    }
+
+    fn read_key(&self) -> Option<Key> {
+        let key = match get_wch()? {
+            WchResult::Char(KEY_INTERRUPT) => Key::Interrupt,
+            WchResult::Char(KEY_TAB) => Key::Tab,
+            WchResult::Char(KEY_ESCAPE) => Key::Escape,
+            WchResult::Char(c) => std::char::from_u32(c).map_or(Key::Other, Key::Char),
+            WchResult::KeyCode(KEY_BTAB) => Key::BackTab,
+            WchResult::KeyCode(KEY_UP) => Key::Up,
+            WchResult::KeyCode(KEY_DOWN) => Key::Down,
+            WchResult::KeyCode(KEY_LEFT) => Key::Left,
+            WchResult::KeyCode(KEY_RIGHT) => Key::Right,
+            WchResult::KeyCode(KEY_PPAGE) => Key::PageUp,
+            WchResult::KeyCode(KEY_NPAGE) => Key::PageDown,
+            WchResult::KeyCode(_) => Key::Other,
+        };
+
+        Some(key)
+    }
+
+    fn restore(&self) {
+        endwin();
+    }
+}
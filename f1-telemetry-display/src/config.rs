@@ -0,0 +1,307 @@
+use f1_telemetry::channels::Channel;
+use serde::Deserialize;
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::views::View;
+
+/// Settings of the display, read from a TOML file. Missing settings take
+/// their default value:
+///
+/// ```toml
+/// backend = "ncurses"        # or "crossterm"
+/// address = "0.0.0.0"
+/// port = 20777
+/// refresh_rate = 30
+/// ascii = false
+///
+/// [units]
+/// speed = "kmh"              # or "mph"
+/// temperature = "celsius"    # or "fahrenheit"
+///
+/// [panels]
+/// timing = true
+/// telemetry = true
+/// tyres = true
+/// session = true
+/// damage = true
+/// standings = true
+/// laps = true
+/// minimap = true
+/// race_control = true
+/// graph = true
+///
+/// [graph]
+/// channels = ["throttle", "brake"]  # keys of f1_telemetry::channels, overlaid
+/// span = "time"              # or "distance", over the current lap
+/// seconds = 10
+///
+/// [colors]
+/// team_colors = true
+/// ok = "green"
+/// caution = "yellow"
+/// warning = "orange"
+/// danger = "red"
+/// ```
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Terminal library the display is drawn with.
+    pub backend: BackendKind,
+    /// Address the UDP socket is bound to.
+    pub address: String,
+    pub port: u16,
+    /// Maximum number of times per second a panel is redrawn, 0 for every
+    /// packet.
+    pub refresh_rate: u32,
+    /// Transliterate names and draw the minimap with dots instead of braille.
+    pub ascii: bool,
+    pub units: Units,
+    pub panels: Panels,
+    pub graph: GraphConfig,
+    pub colors: Colors,
+}
+
+impl Default for Config {
+    fn default() -> Config {
+        Config {
+            backend: BackendKind::default(),
+            address: String::from("0.0.0.0"),
+            port: 20777,
+            refresh_rate: 30,
+            ascii: false,
+            units: Units::default(),
+            panels: Panels::default(),
+            graph: GraphConfig::default(),
+            colors: Colors::default(),
+        }
+    }
+}
+
+impl Config {
+    /// Read the config from `path`, or from the default location if None, in
+    /// which case a missing file gives the default config.
+    pub fn load(path: Option<&Path>) -> Result<Config, String> {
+        let (path, required) = match path {
+            Some(path) => (path.to_path_buf(), true),
+            None => match default_path() {
+                Some(path) => (path, false),
+                None => return Ok(Config::default()),
+            },
+        };
+
+        let content = match fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == io::ErrorKind::NotFound && !required => {
+                return Ok(Config::default())
+            }
+            Err(e) => return Err(format!("Unable to read {}: {}", path.display(), e)),
+        };
+
+        let config: Config = toml::from_str(&content)
+            .map_err(|e| format!("Invalid config {}: {}", path.display(), e))?;
+        config
+            .graph
+            .channels()
+            .map_err(|e| format!("Invalid config {}: {}", path.display(), e))?;
+
+        Ok(config)
+    }
+
+    pub fn listen_address(&self) -> String {
+        format!("{}:{}", self.address, self.port)
+    }
+}
+
+/// `$XDG_CONFIG_HOME/f1-telemetry-display/config.toml`, `$XDG_CONFIG_HOME`
+/// defaulting to `~/.config`.
+fn default_path() -> Option<PathBuf> {
+    let dir = match env::var_os("XDG_CONFIG_HOME") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => PathBuf::from(env::var_os("HOME")?).join(".config"),
+    };
+
+    Some(dir.join("f1-telemetry-display").join("config.toml"))
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BackendKind {
+    Ncurses,
+    Crossterm,
+}
+
+impl Default for BackendKind {
+    /// ncurses, unless only crossterm is built.
+    fn default() -> BackendKind {
+        if cfg!(feature = "ncurses") || !cfg!(feature = "crossterm") {
+            BackendKind::Ncurses
+        } else {
+            BackendKind::Crossterm
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Units {
+    pub speed: SpeedUnit,
+    pub temperature: TemperatureUnit,
+}
+
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SpeedUnit {
+    #[default]
+    Kmh,
+    Mph,
+}
+
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TemperatureUnit {
+    #[default]
+    Celsius,
+    Fahrenheit,
+}
+
+/// Views and panels of the Timing view shown. The Timing view is shown when
+/// every view is disabled.
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Panels {
+    pub timing: bool,
+    pub telemetry: bool,
+    pub tyres: bool,
+    pub session: bool,
+    pub damage: bool,
+    pub standings: bool,
+    pub laps: bool,
+    pub minimap: bool,
+    pub race_control: bool,
+    /// Graph of the Telemetry view.
+    pub graph: bool,
+}
+
+impl Default for Panels {
+    fn default() -> Panels {
+        Panels {
+            timing: true,
+            telemetry: true,
+            tyres: true,
+            session: true,
+            damage: true,
+            standings: true,
+            laps: true,
+            minimap: true,
+            race_control: true,
+            graph: true,
+        }
+    }
+}
+
+impl Panels {
+    /// Enabled views, in the order of the tabs.
+    pub fn views(&self) -> Vec<View> {
+        let views: Vec<View> = View::ALL
+            .iter()
+            .copied()
+            .filter(|&view| match view {
+                View::Timing => self.timing,
+                View::Telemetry => self.telemetry,
+                View::Tyres => self.tyres,
+                View::Session => self.session,
+                View::Damage => self.damage,
+                View::Standings => self.standings,
+                View::Laps => self.laps,
+            })
+            .collect();
+
+        if views.is_empty() {
+            vec![View::Timing]
+        } else {
+            views
+        }
+    }
+}
+
+/// Graph of the Telemetry view, plotting channels of the followed car.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GraphConfig {
+    /// Keys of the channels plotted, drawn over each other in this order.
+    pub channels: Vec<String>,
+    pub span: GraphSpan,
+    /// Width of the graph in seconds, when plotted against time.
+    pub seconds: f32,
+}
+
+impl Default for GraphConfig {
+    /// Throttle and brake over the last 10 seconds.
+    fn default() -> GraphConfig {
+        GraphConfig {
+            channels: vec![String::from("throttle"), String::from("brake")],
+            span: GraphSpan::default(),
+            seconds: 10.0,
+        }
+    }
+}
+
+impl GraphConfig {
+    pub fn channels(&self) -> Result<Vec<Channel>, String> {
+        self.channels
+            .iter()
+            .map(|key| Channel::from_key(key).ok_or_else(|| format!("Unknown channel {}", key)))
+            .collect()
+    }
+}
+
+/// What the horizontal axis of the graph shows.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GraphSpan {
+    /// The last seconds of the session.
+    #[default]
+    Time,
+    /// The distance into the current lap.
+    Distance,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Colors {
+    /// Draw the drivers in the colour of their team, plain text otherwise.
+    pub team_colors: bool,
+    pub ok: Color,
+    pub caution: Color,
+    pub warning: Color,
+    pub danger: Color,
+}
+
+impl Default for Colors {
+    fn default() -> Colors {
+        Colors {
+            team_colors: true,
+            ok: Color::Green,
+            caution: Color::Yellow,
+            warning: Color::Orange,
+            danger: Color::Red,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Color {
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Orange,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+}
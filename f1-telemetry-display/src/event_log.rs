@@ -0,0 +1,172 @@
+use std::collections::VecDeque;
+
+use f1_telemetry::packet::event::{Event, PacketEventData};
+use f1_telemetry::packet::participants::{PacketParticipantsData, Team};
+use f1_telemetry::packet::session::{PacketSessionData, SafetyCar};
+
+/// Number of entries kept, older ones are dropped.
+const CAPACITY: usize = 200;
+
+/// An entry of the race control feed.
+///
+/// ## Specification
+/// ```text
+/// time:    Session timestamp of the event
+/// driver:  Name and team of the driver concerned, if any
+/// message: What happened
+/// ```
+pub struct LogEntry {
+    pub time: f32,
+    pub driver: Option<(String, Team)>,
+    pub message: String,
+}
+
+/// Feed of the events of the session, as announced by race control: fastest
+/// laps, penalties, retirements, DRS and safety car periods.
+pub struct EventLog {
+    entries: VecDeque<LogEntry>,
+    scroll: usize,
+    safety_car: Option<SafetyCar>,
+}
+
+impl EventLog {
+    pub fn new() -> EventLog {
+        EventLog {
+            entries: VecDeque::with_capacity(CAPACITY),
+            scroll: 0,
+            safety_car: None,
+        }
+    }
+
+    /// Log an Event packet, returning whether an entry was added.
+    pub fn update_event(
+        &mut self,
+        packet: &PacketEventData,
+        participants: &Option<PacketParticipantsData>,
+    ) -> bool {
+        let message = match packet.event() {
+            Event::SessionStarted => String::from("Session started"),
+            Event::SessionEnded => String::from("Session ended"),
+            Event::FastestLap => match packet.lap_time() {
+                Some(lap_time) => format!("Fastest lap {}", format_lap_time(lap_time)),
+                None => String::from("Fastest lap"),
+            },
+            Event::Retirement => String::from("Retired"),
+            Event::DRSEnabled => String::from("DRS enabled"),
+            Event::DRSDisabled => String::from("DRS disabled"),
+            Event::TeamMateInPits => String::from("Team mate in the pits"),
+            Event::ChequeredFlag => String::from("Chequered flag"),
+            Event::RaceWinner => String::from("Wins the race"),
+            Event::Penalty => match packet.penalty() {
+                Some(penalty) => {
+                    let time = penalty
+                        .time()
+                        .filter(|&t| t > 0)
+                        .map(|t| format!(" {}s", t))
+                        .unwrap_or_default();
+
+                    format!(
+                        "{}{} - {}",
+                        words(&format!("{:?}", penalty.penalty_type())),
+                        time,
+                        words(&format!("{:?}", penalty.infringement_type()))
+                    )
+                }
+                None => String::from("Penalty"),
+            },
+            Event::SpeedTrap | Event::Unknown(_) => return false,
+        };
+
+        let driver = packet.vehicle_idx().and_then(|idx| {
+            let participant = participants.as_ref()?.participants().get(idx as usize)?;
+            Some((String::from(participant.name()), participant.team()))
+        });
+
+        self.push(LogEntry {
+            time: packet.header().session_time(),
+            driver,
+            message,
+        });
+
+        true
+    }
+
+    /// Log the changes of safety car status of a Session packet, returning
+    /// whether an entry was added.
+    pub fn update_session(&mut self, packet: &PacketSessionData) -> bool {
+        let status = packet.safety_car_status();
+        let previous = self.safety_car.replace(status);
+
+        // Nothing to announce when joining a session.
+        let previous = match previous {
+            Some(previous) if previous != status => previous,
+            _ => return false,
+        };
+
+        let message = match (previous, status) {
+            (_, SafetyCar::Full) => "Safety car deployed",
+            (_, SafetyCar::Virtual) => "Virtual safety car deployed",
+            (SafetyCar::Full, _) => "Safety car in this lap",
+            (SafetyCar::Virtual, _) => "Virtual safety car ending",
+            _ => return false,
+        };
+
+        self.push(LogEntry {
+            time: packet.header().session_time(),
+            driver: None,
+            message: String::from(message),
+        });
+
+        true
+    }
+
+    /// Scroll back in the feed by `lines` (forward if negative). The feed
+    /// follows the new entries when scrolled all the way forward.
+    pub fn scroll(&mut self, lines: isize) {
+        let max = self.entries.len().saturating_sub(1) as isize;
+        self.scroll = (self.scroll as isize + lines).clamp(0, max) as usize;
+    }
+
+    /// The `count` entries to show, oldest first.
+    pub fn visible(&self, count: usize) -> Vec<&LogEntry> {
+        let end = self.entries.len() - self.scroll.min(self.entries.len());
+        let start = end.saturating_sub(count);
+
+        self.entries.range(start..end).collect()
+    }
+
+    fn push(&mut self, entry: LogEntry) {
+        if self.entries.len() == CAPACITY {
+            self.entries.pop_front();
+        }
+
+        // Keep the same entries on screen when scrolled back.
+        if self.scroll > 0 {
+            self.scroll += 1;
+        }
+
+        self.entries.push_back(entry);
+    }
+}
+
+fn format_lap_time(lap_time: f32) -> String {
+    let minutes = (lap_time / 60.0) as u32;
+    format!("{}:{:06.3}", minutes, lap_time - minutes as f32 * 60.0)
+}
+
+/// Split a variant name in camel case into lowercase words, e.g.
+/// `PitLaneSpeeding` into `Pit lane speeding`.
+fn words(name: &str) -> String {
+    let mut out = String::with_capacity(name.len() + 8);
+
+    for (i, c) in name.chars().enumerate() {
+        if c.is_uppercase() && i > 0 {
+            out.push(' ');
+            out.extend(c.to_lowercase());
+        } else {
+            out.push(c);
+        }
+    }
+
+    out
+}
@@ -0,0 +1,153 @@
+use std::collections::VecDeque;
+
+use f1_telemetry::channels::Channel;
+use f1_telemetry::packet::Packet;
+
+use crate::config::GraphSpan;
+
+/// Number of points kept per channel, older ones are dropped.
+const CAPACITY: usize = 4096;
+
+/// Drop in session time, in seconds, telling that the session restarted, e.g.
+/// after a flashback. Packets of different types may be slightly out of order.
+const RESTART_DROP: f32 = 1.0;
+
+/// Drop in lap distance, in metres, telling that the car started a new lap.
+const NEW_LAP_DROP: f32 = 100.0;
+
+/// Recent values of channels of the followed car, against the session time or
+/// the distance into the current lap.
+pub struct Graph {
+    channels: Vec<Channel>,
+    span: GraphSpan,
+    seconds: f32,
+    car: Option<u8>,
+    track_length: Option<f32>,
+    lap_distance: Option<f32>,
+    session_time: f32,
+    series: Vec<VecDeque<(f32, f32)>>,
+}
+
+impl Graph {
+    pub fn new(channels: Vec<Channel>, span: GraphSpan, seconds: f32) -> Graph {
+        Graph {
+            series: channels.iter().map(|_| VecDeque::new()).collect(),
+            channels,
+            span,
+            seconds,
+            car: None,
+            track_length: None,
+            lap_distance: None,
+            session_time: 0.0,
+        }
+    }
+
+    pub fn channels(&self) -> &[Channel] {
+        &self.channels
+    }
+
+    pub fn span(&self) -> GraphSpan {
+        self.span
+    }
+
+    pub fn seconds(&self) -> f32 {
+        self.seconds
+    }
+
+    pub fn reset(&mut self) {
+        self.lap_distance = None;
+        self.session_time = 0.0;
+        for series in &mut self.series {
+            series.clear();
+        }
+    }
+
+    /// Record the values of the channels the packet holds for `car`, starting
+    /// over when the followed car changes.
+    pub fn update(&mut self, packet: &Packet, car: Option<u8>) {
+        if car != self.car {
+            self.car = car;
+            self.reset();
+        }
+
+        let session_time = match packet {
+            Packet::Session(p) => {
+                self.track_length = Some(f32::from(p.track_length()));
+                return;
+            }
+            Packet::Motion(p) => p.header().session_time(),
+            Packet::Lap(p) => p.header().session_time(),
+            Packet::CarTelemetry(p) => p.header().session_time(),
+            Packet::CarStatus(p) => p.header().session_time(),
+            Packet::CarSetups(p) => p.header().session_time(),
+            _ => return,
+        };
+        let index = match car {
+            Some(car) => car as usize,
+            None => return,
+        };
+
+        if let Some(x) = self.position(packet, session_time, index) {
+            for (channel, series) in self.channels.iter().zip(&mut self.series) {
+                if let Some(value) = channel.value(packet, index) {
+                    if series.len() == CAPACITY {
+                        series.pop_front();
+                    }
+                    series.push_back((x, value));
+                }
+            }
+        }
+
+        if self.span == GraphSpan::Time {
+            let start = self.session_time - self.seconds;
+            for series in &mut self.series {
+                while series.front().is_some_and(|&(t, _)| t < start) {
+                    series.pop_front();
+                }
+            }
+        }
+    }
+
+    /// Range of the horizontal axis: the last seconds, or the lap.
+    pub fn x_range(&self) -> (f32, f32) {
+        match self.span {
+            GraphSpan::Time => (self.session_time - self.seconds, self.session_time),
+            GraphSpan::Distance => (0.0, self.track_length.unwrap_or(0.0)),
+        }
+    }
+
+    /// Points of each channel, in the order of the channels.
+    pub fn series(&self) -> impl Iterator<Item = (Channel, &VecDeque<(f32, f32)>)> {
+        self.channels.iter().copied().zip(&self.series)
+    }
+
+    /// Horizontal position of the values of a packet, clearing the graph on
+    /// a restart or, by distance, on a new lap.
+    fn position(&mut self, packet: &Packet, session_time: f32, index: usize) -> Option<f32> {
+        if session_time + RESTART_DROP < self.session_time {
+            self.reset();
+        }
+        self.session_time = self.session_time.max(session_time);
+
+        match self.span {
+            GraphSpan::Time => Some(session_time),
+            GraphSpan::Distance => {
+                if let Packet::Lap(p) = packet {
+                    let distance = p.lap_data().get(index)?.lap_distance();
+                    if self
+                        .lap_distance
+                        .is_some_and(|d| distance < d - NEW_LAP_DROP)
+                    {
+                        for series in &mut self.series {
+                            series.clear();
+                        }
+                    }
+                    self.lap_distance = Some(distance);
+                }
+
+                // Before the line at the start of a session, the distance is negative.
+                self.lap_distance.filter(|&d| d >= 0.0)
+            }
+        }
+    }
+}
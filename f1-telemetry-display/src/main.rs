@@ -1,66 +1,249 @@
+use config::Config;
+use event_log::EventLog;
+use f1_telemetry::analysis::track_map::TrackMap;
+use f1_telemetry::packet::car_status::PacketCarStatusData;
+use f1_telemetry::packet::car_telemetry::PacketCarTelemetryData;
+use f1_telemetry::packet::header::Player;
 use f1_telemetry::packet::lap::{PacketLapData, PitStatus};
 use f1_telemetry::packet::participants::PacketParticipantsData;
 use f1_telemetry::packet::session::PacketSessionData;
-use f1_telemetry::packet::Packet;
-use f1_telemetry::Stream;
-use models::{LapInfo, SessionInfo};
-use std::thread::sleep;
+use f1_telemetry::packet::{Packet, PacketFilter, PacketType};
+use f1_telemetry::session::{LapTracker, PitTracker, SessionTracker, ViewTarget};
+use f1_telemetry::{Stream, StreamError};
+use graph::Graph;
+use models::{
+    CarStatusInfo, DamageInfo, LapHistoryInfo, LapInfo, MinimapCar, MinimapInfo, PlayerInfo,
+    SessionInfo, StandingInfo, TelemetryInfo, TyreInfo, WeatherInfo,
+};
+use std::path::PathBuf;
+use std::sync::mpsc::RecvTimeoutError;
 use std::time::Duration;
 use ui::Ui;
+use views::{Command, RedrawLimiter, View, ViewManager};
 
+mod backend;
+mod config;
+mod event_log;
+mod graph;
 mod models;
 mod ui;
+mod views;
+
+/// Number of entries scrolled at once in the race control feed.
+const EVENT_LOG_PAGE: isize = 5;
 
 fn main() {
-    let stream = Stream::new("0.0.0.0:20777").expect("Unable to bind socket");
+    let mut args = std::env::args().skip(1);
+    let mut config_path: Option<PathBuf> = None;
+    let mut ascii = false;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--ascii" => ascii = true,
+            "--config" => config_path = args.next().map(PathBuf::from),
+            _ => {}
+        }
+    }
+
+    let mut config = Config::load(config_path.as_deref()).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    });
+    config.ascii |= ascii;
+
+    let graph_channels = config.graph.channels().unwrap_or_default();
+    let mut filter = PacketFilter::none()
+        .with(PacketType::Motion)
+        .with(PacketType::Session)
+        .with(PacketType::Event)
+        .with(PacketType::LapData)
+        .with(PacketType::Participants)
+        .with(PacketType::CarStatus)
+        .with(PacketType::CarTelemetry);
+    for channel in &graph_channels {
+        filter = filter.with(channel.packet_type());
+    }
+
+    let mut stream = Stream::new(config.listen_address()).expect("Unable to bind socket");
+    stream.set_filter(filter);
     println!("Listening on {}", stream.socket().local_addr().unwrap());
 
     let mut participants: Option<PacketParticipantsData> = None;
+    let mut car_status: Option<PacketCarStatusData> = None;
     let mut current_lap: u8 = 0;
+    let mut tracker = SessionTracker::new();
+    let mut track_map = TrackMap::new();
+    let mut pit_tracker = PitTracker::new();
+    let mut lap_tracker = LapTracker::new();
+    let mut order: Vec<u8> = Vec::new();
+    let mut views = ViewManager::new(config.panels.views());
+    let mut redraw = RedrawLimiter::new(config.refresh_rate);
+    let mut event_log = EventLog::new();
+    let mut graph = Graph::new(graph_channels, config.graph.span, config.graph.seconds);
+
+    let term = backend::open(config.backend).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    });
+    let ui = Ui::new(term, &config);
+    ui.print_tabs(views.views(), views.current(), true);
 
-    let ui = Ui::init();
+    let (packets, handle) = stream.spawn().expect("Unable to start the stream thread");
 
     loop {
-        match stream.next() {
-            Ok(p) => match p {
-                Some(p) => match p {
+        match packets.recv_timeout(Duration::from_millis(5)) {
+            Ok(Ok(p)) => {
+                tracker.update(&p);
+                pit_tracker.update(&p);
+                let car = views.car(followed_car(&tracker));
+                if config.panels.graph {
+                    graph.update(&p, car);
+                }
+
+                match p {
+                    Packet::Motion(m) => {
+                        track_map.update(&m);
+                        if redraw.due(PacketType::Motion)
+                            && config.panels.minimap
+                            && views.is_visible(View::Timing)
+                        {
+                            if let Some(minimap) =
+                                parse_minimap(&track_map, &tracker, car, &participants)
+                            {
+                                ui.print_minimap(&minimap);
+                            }
+                        }
+                    }
                     Packet::Session(s) => {
-                        let sinfo = parse_session_data(&s, current_lap);
-                        ui.print_session_info(&sinfo);
+                        track_map.set_track(s.track());
+                        let logged = event_log.update_session(&s);
+                        if logged && config.panels.race_control && views.is_visible(View::Timing) {
+                            ui.print_event_log(&event_log);
+                        }
+
+                        if redraw.due(PacketType::Session) {
+                            let sinfo = parse_session_data(&s, current_lap);
+                            ui.print_session_info(&sinfo);
+
+                            if views.is_visible(View::Session) {
+                                ui.print_weather_info(&parse_weather_info(&s));
+                            }
+                        }
                     }
                     Packet::Lap(ld) => {
                         current_lap = get_current_lap(&ld);
-                        if let Some(lap_info) = parse_lap_data(&ld, &participants) {
-                            ui.print_lap_info(&lap_info);
+                        lap_tracker.update(&ld);
+                        track_map.update_lap(&ld);
+                        order = get_order(&ld);
+                        let due = redraw.due(PacketType::LapData);
+                        if due && views.is_visible(View::Timing) {
+                            if let Some(lap_info) =
+                                parse_lap_data(&ld, car, &participants, &car_status, &lap_tracker)
+                            {
+                                ui.print_lap_info(&lap_info);
+                            }
+                        }
+                        if due && views.is_visible(View::Standings) {
+                            if let Some(standings) =
+                                parse_standings(&ld, &participants, &car_status, &pit_tracker)
+                            {
+                                ui.print_standings(&standings);
+                            }
+                        }
+                        if due && views.is_visible(View::Laps) {
+                            if let Some(lap_history) =
+                                parse_lap_history(car, &lap_tracker, &participants)
+                            {
+                                ui.print_lap_history(&lap_history);
+                            }
+                        }
+                    }
+                    Packet::Event(e) => {
+                        let logged = event_log.update_event(&e, &participants);
+                        if logged && config.panels.race_control && views.is_visible(View::Timing) {
+                            ui.print_event_log(&event_log);
                         }
                     }
                     Packet::Participants(p) => participants = Some(p),
+                    Packet::CarStatus(cs) => {
+                        let due = redraw.due(PacketType::CarStatus);
+                        if due && views.is_visible(View::Damage) {
+                            if let Some(damage_info) = parse_damage_info(&cs, car, &participants) {
+                                ui.print_damage_info(&damage_info);
+                            }
+                        }
+                        car_status = Some(cs);
+                    }
+                    Packet::CarTelemetry(t) => {
+                        let due = redraw.due(PacketType::CarTelemetry);
+                        if due && views.is_visible(View::Timing) {
+                            if let Some(player_info) = parse_player_info(&tracker, &participants) {
+                                ui.print_player_info(&player_info);
+                            }
+                        }
+                        if due && views.is_visible(View::Telemetry) {
+                            if let Some(telemetry_info) =
+                                parse_telemetry_info(&t, car, &participants, &car_status)
+                            {
+                                ui.print_telemetry_info(&telemetry_info);
+                            }
+                            if config.panels.graph {
+                                ui.print_graph(&graph);
+                            }
+                        }
+                        if due && views.is_visible(View::Tyres) {
+                            if let Some(tyre_info) = parse_tyre_info(
+                                &t,
+                                car,
+                                &lap_tracker,
+                                &pit_tracker,
+                                &participants,
+                                &car_status,
+                            ) {
+                                ui.print_tyre_info(&tyre_info);
+                            }
+                        }
+                    }
                     _ => {}
-                },
-                None => sleep(Duration::from_millis(5)),
-            },
-            Err(_e) => {
+                }
+            }
+            Ok(Err(StreamError::Truncated(_))) => {} // The buffer has grown, wait for the next one.
+            Ok(Err(_e)) => {
                 panic!("{:?}", _e);
             }
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => break,
         }
 
-        let ch = ncurses::get_wch();
-        if let Some(ch) = ch {
-            match ch {
-                ncurses::WchResult::Char(113) => {
-                    break;
+        let command = ui.read_key().and_then(Command::from_key);
+        match command {
+            Some(Command::Quit) => break,
+            Some(Command::ScrollUp) | Some(Command::ScrollDown) => {
+                event_log.scroll(if command == Some(Command::ScrollUp) {
+                    EVENT_LOG_PAGE
+                } else {
+                    -EVENT_LOG_PAGE
+                });
+                if config.panels.race_control && views.is_visible(View::Timing) {
+                    ui.print_event_log(&event_log);
+                }
+            }
+            Some(command)
+                if views.apply(command)
+                    || views.select(command, &order, followed_car(&tracker)) =>
+            {
+                ui.clear_view();
+                ui.print_tabs(views.views(), views.current(), views.selected().is_none());
+                if views.is_help_visible() {
+                    ui.print_help();
+                } else if config.panels.race_control && views.is_visible(View::Timing) {
+                    ui.print_event_log(&event_log);
                 }
-                _ => {} // ncurses::WchResult::Char(c) => {
-                        //     ncurses::mvaddstr(23, 0, format!("Pressed Char: {}", c).as_str());
-                        // },
-                        // ncurses::WchResult::KeyCode(c) => {
-                        //     ncurses::mvaddstr(23, 0, format!("Pressed Key: {}", c).as_str());
-                        //     ncurses::clrtoeol();
-                        // }
             }
+            _ => {}
         }
     }
 
+    handle.shutdown().expect("Unable to stop the stream thread");
     ui.destroy();
 }
 
@@ -77,7 +260,10 @@ fn parse_session_data(session: &PacketSessionData, current_lap: u8) -> SessionIn
 
 fn parse_lap_data<'a>(
     lap_data: &'a PacketLapData,
+    selected: Option<u8>,
     participants: &'a Option<PacketParticipantsData>,
+    car_status: &Option<PacketCarStatusData>,
+    lap_tracker: &LapTracker,
 ) -> Option<Vec<LapInfo<'a>>> {
     if participants.is_none() {
         return None;
@@ -90,6 +276,9 @@ fn parse_lap_data<'a>(
     for (i, ld) in lap_data.lap_data().iter().enumerate() {
         let name = participants[i].name();
         let team = participants[i].team();
+        let tyre_compound = car_status
+            .as_ref()
+            .map(|cs| cs.car_status_data()[i].visual_tyre_compound());
 
         let li = LapInfo {
             position: ld.car_position(),
@@ -98,9 +287,12 @@ fn parse_lap_data<'a>(
             current_lap_time: ld.current_lap_time(),
             last_lap_time: ld.last_lap_time(),
             best_lap_time: ld.best_lap_time(),
+            last_lap_delta: lap_tracker.last_lap_delta(i as u8),
             status: ld.result_status(),
             in_pit: ld.pit_status() != PitStatus::None,
             lap_invalid: ld.current_lap_invalid(),
+            tyre_compound,
+            selected: selected == Some(i as u8),
         };
 
         lap_info.push(li);
@@ -109,6 +301,256 @@ fn parse_lap_data<'a>(
     Some(lap_info)
 }
 
+fn parse_player_info<'a>(
+    tracker: &SessionTracker,
+    participants: &'a Option<PacketParticipantsData>,
+) -> Option<Vec<PlayerInfo<'a>>> {
+    let participants = participants.as_ref()?.participants();
+
+    let player_info = tracker
+        .focused()
+        .into_iter()
+        .filter_map(|(player, focus)| {
+            let participant = participants.get(focus.car_index() as usize)?;
+            let position = focus.position();
+
+            Some(PlayerInfo {
+                label: match player {
+                    Player::Primary => "P1",
+                    Player::Secondary => "P2",
+                },
+                name: participant.name(),
+                team: participant.team(),
+                position: position.map(|p| p.position()).unwrap_or(0),
+                lap: position.map(|p| p.lap()).unwrap_or(0),
+                speed: focus.speed().unwrap_or(0),
+                gear: focus.gear().unwrap_or(0),
+            })
+        })
+        .collect();
+
+    Some(player_info)
+}
+
+fn parse_minimap(
+    track_map: &TrackMap,
+    tracker: &SessionTracker,
+    selected: Option<u8>,
+    participants: &Option<PacketParticipantsData>,
+) -> Option<MinimapInfo> {
+    let participants = participants.as_ref()?.participants();
+    let players: Vec<usize> = tracker
+        .focused()
+        .iter()
+        .map(|(_, focus)| focus.car_index())
+        .chain(selected)
+        .map(|index| index as usize)
+        .collect();
+
+    let cars = track_map
+        .car_positions()
+        .into_iter()
+        .enumerate()
+        .filter_map(|(i, position)| {
+            let (x, z) = position?;
+
+            Some(MinimapCar {
+                x,
+                z,
+                team: participants.get(i)?.team(),
+                player: players.contains(&i),
+            })
+        })
+        .collect();
+
+    Some(MinimapInfo {
+        outline: track_map.outline(),
+        closed: track_map.is_complete() || track_map.is_prebaked(),
+        cars,
+    })
+}
+
+fn parse_tyre_info<'a>(
+    telemetry: &PacketCarTelemetryData,
+    index: Option<u8>,
+    lap_tracker: &LapTracker,
+    pit_tracker: &PitTracker,
+    participants: &'a Option<PacketParticipantsData>,
+    car_status: &Option<PacketCarStatusData>,
+) -> Option<TyreInfo<'a>> {
+    let index = index?;
+    let participant = participants.as_ref()?.participants().get(index as usize)?;
+    let t = telemetry.car_telemetry_data().get(index as usize)?;
+    let status = car_status
+        .as_ref()
+        .and_then(|cs| cs.car_status_data().get(index as usize));
+
+    // The current set was fitted on the lap of the last stop, or at the start.
+    let age = lap_tracker.current_lap(index).map(|lap| {
+        let fitted = pit_tracker
+            .stops_for(index)
+            .iter()
+            .rev()
+            .find(|s| s.exit_time().is_some())
+            .map(|s| s.lap())
+            .unwrap_or(1);
+
+        lap.saturating_sub(fitted)
+    });
+
+    Some(TyreInfo {
+        name: participant.name(),
+        team: participant.team(),
+        compound: status.map(|s| s.visual_tyre_compound()),
+        age,
+        surface_temperature: t.tyres_surface_temperature(),
+        inner_temperature: t.tyres_inner_temperature(),
+        brake_temperature: t.brakes_temperature(),
+        wear: status.map(|s| s.tyres_wear()),
+    })
+}
+
+fn parse_telemetry_info<'a>(
+    telemetry: &PacketCarTelemetryData,
+    index: Option<u8>,
+    participants: &'a Option<PacketParticipantsData>,
+    car_status: &Option<PacketCarStatusData>,
+) -> Option<TelemetryInfo<'a>> {
+    let index = index? as usize;
+    let participant = participants.as_ref()?.participants().get(index)?;
+    let t = telemetry.car_telemetry_data().get(index)?;
+    let status = car_status
+        .as_ref()
+        .and_then(|cs| cs.car_status_data().get(index))
+        .map(|s| CarStatusInfo {
+            max_rpm: s.max_rpm(),
+            fuel_in_tank: s.fuel_in_tank(),
+            fuel_remaining_laps: s.fuel_remaining_laps(),
+            fuel_mix: s.fuel_mix(),
+            front_brake_bias: s.front_brake_bias(),
+            ers_store_energy: s.ers_store_energy(),
+            ers_deploy_mode: s.ers_deploy_mode(),
+        });
+
+    Some(TelemetryInfo {
+        name: participant.name(),
+        team: participant.team(),
+        speed: t.speed(),
+        gear: t.gear(),
+        engine_rpm: t.engine_rpm(),
+        rev_lights_percent: t.rev_lights_percent(),
+        throttle: t.throttle(),
+        brake: t.brake(),
+        clutch: t.clutch(),
+        steer: t.steer(),
+        drs: t.drs(),
+        status,
+    })
+}
+
+fn parse_weather_info(session: &PacketSessionData) -> WeatherInfo<'_> {
+    WeatherInfo {
+        weather: session.weather(),
+        track_temperature: session.track_temperature(),
+        air_temperature: session.air_temperature(),
+        pit_speed_limit: session.pit_speed_limit(),
+        safety_car: session.safety_car_status(),
+        forecast: session.weather_forecast_samples(),
+    }
+}
+
+fn parse_damage_info<'a>(
+    car_status: &PacketCarStatusData,
+    index: Option<u8>,
+    participants: &'a Option<PacketParticipantsData>,
+) -> Option<DamageInfo<'a>> {
+    let index = index? as usize;
+    let participant = participants.as_ref()?.participants().get(index)?;
+    let s = car_status.car_status_data().get(index)?;
+
+    Some(DamageInfo {
+        name: participant.name(),
+        team: participant.team(),
+        front_left_wing: s.front_left_wing_damage(),
+        front_right_wing: s.front_right_wing_damage(),
+        rear_wing: s.rear_wing_damage(),
+        engine: s.engine_damage(),
+        gearbox: s.gear_box_damage(),
+        tyres: s.tyres_damage(),
+    })
+}
+
+fn parse_standings<'a>(
+    lap_data: &PacketLapData,
+    participants: &'a Option<PacketParticipantsData>,
+    car_status: &Option<PacketCarStatusData>,
+    pit_tracker: &PitTracker,
+) -> Option<Vec<StandingInfo<'a>>> {
+    let participants = participants.as_ref()?.participants();
+
+    let standings = lap_data
+        .lap_data()
+        .iter()
+        .zip(participants)
+        .enumerate()
+        .filter(|(_, (ld, _))| ld.car_position() > 0)
+        .map(|(i, (ld, participant))| StandingInfo {
+            position: ld.car_position(),
+            name: participant.name(),
+            team: participant.team(),
+            lap: ld.current_lap_num(),
+            grid_position: ld.grid_position(),
+            pit_stops: pit_tracker.total_stops(i as u8),
+            penalties: ld.penalties(),
+            status: ld.result_status(),
+            tyre_compound: car_status
+                .as_ref()
+                .and_then(|cs| cs.car_status_data().get(i))
+                .map(|cs| cs.visual_tyre_compound()),
+        })
+        .collect();
+
+    Some(standings)
+}
+
+fn parse_lap_history<'a>(
+    index: Option<u8>,
+    lap_tracker: &'a LapTracker,
+    participants: &'a Option<PacketParticipantsData>,
+) -> Option<LapHistoryInfo<'a>> {
+    let index = index?;
+    let participant = participants.as_ref()?.participants().get(index as usize)?;
+
+    Some(LapHistoryInfo {
+        name: participant.name(),
+        team: participant.team(),
+        laps: lap_tracker.laps_for(index),
+        current_lap: lap_tracker.current_lap(index),
+        best_lap_time: lap_tracker.best_lap(index).map(|l| l.lap_time()),
+        best_sectors: lap_tracker.best_sectors(index),
+    })
+}
+
+/// Car followed when none is selected: the spectated one when spectating, the
+/// player's otherwise.
+fn followed_car(tracker: &SessionTracker) -> Option<u8> {
+    tracker.view_target().map(ViewTarget::car_index)
+}
+
+/// Vehicle indices of the cars, in the order of the positions.
+fn get_order(lap_data: &PacketLapData) -> Vec<u8> {
+    let mut cars: Vec<(u8, u8)> = lap_data
+        .lap_data()
+        .iter()
+        .enumerate()
+        .filter(|(_, l)| l.car_position() > 0)
+        .map(|(i, l)| (l.car_position(), i as u8))
+        .collect();
+    cars.sort_unstable();
+
+    cars.into_iter().map(|(_, i)| i).collect()
+}
+
 fn get_current_lap(lap_data: &PacketLapData) -> u8 {
     lap_data
         .lap_data()
@@ -1,5 +1,9 @@
+use f1_telemetry::packet::car_status::{ERSDeployMode, FuelMix, TyreCompoundVisual};
+use f1_telemetry::packet::generic::WheelData;
 use f1_telemetry::packet::lap::ResultStatus;
 use f1_telemetry::packet::participants::Team;
+use f1_telemetry::packet::session::{SafetyCar, Weather, WeatherForecastSample};
+use f1_telemetry::session::LapRecord;
 
 pub struct SessionInfo<'a> {
     pub session_name: &'a str,
@@ -17,7 +21,118 @@ pub struct LapInfo<'a> {
     pub current_lap_time: f32,
     pub last_lap_time: f32,
     pub best_lap_time: f32,
+    /// Last lap compared to the best lap before it.
+    pub last_lap_delta: Option<f32>,
     pub status: ResultStatus,
     pub in_pit: bool,
     pub lap_invalid: bool,
+    pub tyre_compound: Option<TyreCompoundVisual>,
+    /// Whether the car is the one shown in the views about a single car.
+    pub selected: bool,
+}
+
+pub struct PlayerInfo<'a> {
+    pub label: &'a str,
+    pub name: &'a str,
+    pub team: Team,
+    pub position: u8,
+    pub lap: u8,
+    pub speed: u16,
+    pub gear: i8,
+}
+
+pub struct MinimapInfo {
+    /// Normalised outline of the track.
+    pub outline: Vec<(f32, f32)>,
+    /// Whether the outline is a closed path rather than a set of positions.
+    pub closed: bool,
+    pub cars: Vec<MinimapCar>,
+}
+
+pub struct MinimapCar {
+    pub x: f32,
+    pub z: f32,
+    pub team: Team,
+    /// Whether the car is a player's or the selected one.
+    pub player: bool,
+}
+
+pub struct TyreInfo<'a> {
+    pub name: &'a str,
+    pub team: Team,
+    pub compound: Option<TyreCompoundVisual>,
+    /// Laps driven on the current set.
+    pub age: Option<u8>,
+    pub surface_temperature: WheelData<u16>,
+    pub inner_temperature: WheelData<u16>,
+    pub brake_temperature: WheelData<u16>,
+    pub wear: Option<WheelData<u8>>,
+}
+
+pub struct TelemetryInfo<'a> {
+    pub name: &'a str,
+    pub team: Team,
+    pub speed: u16,
+    pub gear: i8,
+    pub engine_rpm: u16,
+    pub rev_lights_percent: u8,
+    pub throttle: f32,
+    pub brake: f32,
+    pub clutch: u8,
+    pub steer: f32,
+    pub drs: bool,
+    pub status: Option<CarStatusInfo>,
+}
+
+/// The part of the telemetry coming from the Car Status packets.
+pub struct CarStatusInfo {
+    pub max_rpm: u16,
+    pub fuel_in_tank: f32,
+    pub fuel_remaining_laps: f32,
+    pub fuel_mix: FuelMix,
+    pub front_brake_bias: u8,
+    pub ers_store_energy: f32,
+    pub ers_deploy_mode: ERSDeployMode,
+}
+
+pub struct WeatherInfo<'a> {
+    pub weather: Weather,
+    pub track_temperature: i8,
+    pub air_temperature: i8,
+    pub pit_speed_limit: u8,
+    pub safety_car: SafetyCar,
+    pub forecast: &'a [WeatherForecastSample],
+}
+
+pub struct DamageInfo<'a> {
+    pub name: &'a str,
+    pub team: Team,
+    pub front_left_wing: u8,
+    pub front_right_wing: u8,
+    pub rear_wing: u8,
+    pub engine: u8,
+    pub gearbox: u8,
+    pub tyres: WheelData<u8>,
+}
+
+pub struct StandingInfo<'a> {
+    pub position: u8,
+    pub name: &'a str,
+    pub team: Team,
+    pub lap: u8,
+    pub grid_position: u8,
+    pub pit_stops: usize,
+    pub penalties: u8,
+    pub status: ResultStatus,
+    pub tyre_compound: Option<TyreCompoundVisual>,
+}
+
+pub struct LapHistoryInfo<'a> {
+    pub name: &'a str,
+    pub team: Team,
+    /// Completed laps, oldest first.
+    pub laps: &'a [LapRecord],
+    pub current_lap: Option<u8>,
+    pub best_lap_time: Option<f32>,
+    pub best_sectors: [Option<f32>; 3],
 }
@@ -1,40 +1,86 @@
-use crate::models::{LapInfo, SessionInfo};
+use crate::backend::{Backend, Key, Style};
+use crate::config::{Config, GraphSpan, Units};
+use crate::event_log::EventLog;
+use crate::graph::Graph;
+use crate::models::{
+    DamageInfo, LapHistoryInfo, LapInfo, MinimapInfo, PlayerInfo, SessionInfo, StandingInfo,
+    TelemetryInfo, TyreInfo, WeatherInfo,
+};
+use crate::views::{View, KEYBINDINGS};
+use f1_telemetry::packet::car_status::TyreCompoundVisual;
+use f1_telemetry::packet::generic::WheelData;
 use f1_telemetry::packet::lap::ResultStatus;
-use ncurses::*;
+use f1_telemetry::packet::participants::Team;
+use f1_telemetry::packet::session::SafetyCar;
+use f1_telemetry::units::{Celsius, KilometresPerHour};
+use fmt::Align;
+use minimap::Canvas;
+use theme::Theme;
 
 mod fmt;
+mod minimap;
+mod theme;
 
 const SESSION_Y_OFFSET: i32 = 0;
+const SESSION_PROGRESS_WIDTH: usize = 40;
 const LAP_DATA_HEADER_Y_OFFSET: i32 = 4;
 const LAP_DATA_Y_OFFSET: i32 = 6;
-// const CURRENT_CAR_DATA_Y_OFFSET: i32 = 26;
-// const CAR_X_OFFSET: i32 = 80;
+const NAME_WIDTH: usize = 20;
+const TIME_WIDTH: usize = 12;
+const DELTA_WIDTH: usize = 7;
+const CURRENT_CAR_DATA_Y_OFFSET: i32 = 29;
+const CAR_X_OFFSET: i32 = 40;
+const MINIMAP_X_OFFSET: i32 = 100;
+const MINIMAP_Y_OFFSET: i32 = 4;
+const MINIMAP_WIDTH: usize = 40;
+const MINIMAP_HEIGHT: usize = 20;
+const TYRE_X_OFFSET: i32 = 30;
+const TYRE_Y_OFFSET: i32 = 7;
+const TYRE_WIDTH: usize = 24;
+/// Optimal range and limit of the tyre temperatures, in degrees Celsius.
+const TYRE_TEMPERATURE: (u16, u16, u16) = (80, 105, 115);
+/// Optimal range and limit of the brake temperatures, in degrees Celsius.
+const BRAKE_TEMPERATURE: (u16, u16, u16) = (300, 900, 1100);
+const EVENTS_Y_OFFSET: i32 = MINIMAP_Y_OFFSET + MINIMAP_HEIGHT as i32 + 1;
+const EVENTS_WIDTH: usize = 60;
+const EVENT_NAME_WIDTH: usize = 16;
+const VIEW_Y_OFFSET: i32 = 5;
+const SECTOR_WIDTH: usize = 8;
+const BAR_WIDTH: usize = 40;
+const HELP_WIDTH: usize = 44;
+const GRAPH_Y_OFFSET: i32 = VIEW_Y_OFFSET + 13;
+const GRAPH_WIDTH: usize = 60;
+const GRAPH_HEIGHT: usize = 8;
+/// Capacity of the ERS store, in joules.
+const ERS_CAPACITY: f32 = 4_000_000.0;
 
 pub struct Ui {
-    hwnd: WINDOW,
+    term: Box<dyn Backend>,
+    theme: Theme,
+    ascii: bool,
+    units: Units,
 }
 
 impl Ui {
-    pub fn init() -> Ui {
-        setlocale(ncurses::LcCategory::all, "");
-
-        let hwnd = initscr();
-
-        curs_set(CURSOR_VISIBILITY::CURSOR_INVISIBLE);
-        cbreak();
-        noecho();
-        keypad(hwnd, true);
-        timeout(0);
-
-        fmt::init_colors();
-
-        refresh();
-
-        Ui { hwnd }
+    /// In `ascii` mode, names are transliterated, ellipses and bars are drawn
+    /// with plain characters and the minimap with dots instead of braille.
+    pub fn new(term: Box<dyn Backend>, config: &Config) -> Ui {
+        Ui {
+            term,
+            theme: Theme::new(&config.colors),
+            ascii: config.ascii,
+            units: config.units,
+        }
     }
 
     pub fn destroy(&self) {
-        ncurses::endwin();
+        self.term.restore();
+    }
+
+    /// Show what was drawn, and return the key pressed since the last call.
+    pub fn read_key(&self) -> Option<Key> {
+        self.term.flush();
+        self.term.read_key()
     }
 
     pub fn print_session_info(&self, sinfo: &SessionInfo) {
@@ -46,52 +92,937 @@ impl Ui {
             fmt::format_time(sinfo.duration)
         );
 
-        addstr_center(self.hwnd, SESSION_Y_OFFSET, session_name);
-        addstr_center(self.hwnd, SESSION_Y_OFFSET + 1, lap_info);
-        addstr_center(self.hwnd, SESSION_Y_OFFSET + 2, session_time);
+        self.print_center(SESSION_Y_OFFSET, session_name);
+        self.print_center(SESSION_Y_OFFSET + 1, lap_info);
+        self.print_center(SESSION_Y_OFFSET + 2, session_time);
+
+        if sinfo.duration > 0 {
+            let progress = f32::from(sinfo.elapsed_time) / f32::from(sinfo.duration);
+            self.print_center(
+                SESSION_Y_OFFSET + 3,
+                &fmt::percentage_bar(progress, SESSION_PROGRESS_WIDTH, self.ascii),
+            );
+        }
     }
 
     pub fn print_lap_info(&self, lap_info: &[LapInfo]) {
-        mvaddstr(
-            LAP_DATA_HEADER_Y_OFFSET,
-            2,
-            "  P. NAME                 | CURRENT LAP  | LAST LAP     | BEST LAP     | STATUS",
+        let header = format!(
+            "{}. {} | {} | {} | {} | {} | {} | {}",
+            fmt::field("P", 3, Align::Right, self.ascii),
+            fmt::field("NAME", NAME_WIDTH, Align::Left, self.ascii),
+            fmt::field("CURRENT LAP", TIME_WIDTH, Align::Left, self.ascii),
+            fmt::field("LAST LAP", TIME_WIDTH, Align::Left, self.ascii),
+            fmt::field("BEST LAP", TIME_WIDTH, Align::Left, self.ascii),
+            fmt::field("TYRE", 4, Align::Center, self.ascii),
+            fmt::field("ST", 2, Align::Left, self.ascii),
+            fmt::field("DELTA", DELTA_WIDTH, Align::Right, self.ascii),
         );
-
-        fmt::set_bold();
+        self.term
+            .print_at(LAP_DATA_HEADER_Y_OFFSET, 2, header.as_str());
 
         for li in lap_info {
             let pos = match li.status {
                 ResultStatus::Retired => String::from("RET"),
                 ResultStatus::NotClassified => String::from("N/C"),
                 ResultStatus::Disqualified => String::from("DSQ"),
-                _ => format!("{:3}", li.position),
+                _ => fmt::field(&li.position.to_string(), 3, Align::Right, self.ascii),
             };
-            let name = li.name;
+            let name = fmt::format_name(li.name, NAME_WIDTH, self.ascii);
             let team = li.team;
+            let tyre = li
+                .tyre_compound
+                .map(TyreCompoundVisual::abbreviation)
+                .unwrap_or("");
 
             let s = format!(
-                "{}. {:20} | {} | {} | {} | {}{}     ",
+                "{}. {} | {} | {} | {} | {} | {}{} | ",
                 pos,
                 name,
                 fmt::format_time_ms(li.current_lap_time),
                 fmt::format_time_ms(li.last_lap_time),
                 fmt::format_time_ms(li.best_lap_time),
+                fmt::field(tyre, 4, Align::Center, self.ascii),
                 if li.in_pit { "P" } else { " " },
                 if li.lap_invalid { "!" } else { " " },
             );
 
-            fmt::set_team_color(team);
-            mvaddstr(LAP_DATA_Y_OFFSET + li.position as i32 - 1, 2, s.as_str());
-            clrtoeol();
+            let style = self.theme.team(team).bold();
+            self.term
+                .set_style(if li.selected { style.reverse() } else { style });
+            self.term
+                .print_at(LAP_DATA_Y_OFFSET + li.position as i32 - 1, 2, s.as_str());
+
+            // Last lap compared to the driver's best lap before it, which the
+            // best lap time of the packet already includes.
+            if let Some(delta) = li.last_lap_delta {
+                let (delta, status) = fmt::format_delta(delta);
+                self.term.set_style(self.theme.status(status).bold());
+                self.term
+                    .print(fmt::field(&delta, DELTA_WIDTH, Align::Right, self.ascii).as_str());
+            } else {
+                // Blank the column rather than the end of the line, which has
+                // the minimap.
+                self.term.reset_style();
+                self.term.print(&" ".repeat(DELTA_WIDTH));
+            }
+        }
+
+        self.term.reset_style();
+    }
+
+    /// Print a HUD for each player, side by side in split-screen sessions.
+    pub fn print_player_info(&self, players: &[PlayerInfo]) {
+        for (i, pi) in players.iter().enumerate() {
+            let x = 2 + CAR_X_OFFSET * i as i32;
+            let gear = match pi.gear {
+                -1 => String::from("R"),
+                0 => String::from("N"),
+                g => g.to_string(),
+            };
+
+            self.term.set_style(self.theme.team(pi.team));
+            self.term.print_at(
+                CURRENT_CAR_DATA_Y_OFFSET,
+                x,
+                &fmt::field(
+                    &format!(
+                        "{} - {}",
+                        pi.label,
+                        fmt::format_name(pi.name, NAME_WIDTH, self.ascii).trim_end()
+                    ),
+                    CAR_X_OFFSET as usize - 2,
+                    Align::Left,
+                    self.ascii,
+                ),
+            );
+            self.term.reset_style();
+
+            let lines = [
+                format!("Position: P{} | Lap: {}", pi.position, pi.lap),
+                format!(
+                    "Speed: {} | Gear: {}",
+                    fmt::field(
+                        &fmt::speed(KilometresPerHour(f32::from(pi.speed)), self.units.speed),
+                        8,
+                        Align::Right,
+                        self.ascii
+                    ),
+                    gear
+                ),
+            ];
+            for (j, line) in lines.iter().enumerate() {
+                self.term.print_at(
+                    CURRENT_CAR_DATA_Y_OFFSET + 1 + j as i32,
+                    x,
+                    &fmt::field(line, CAR_X_OFFSET as usize - 2, Align::Left, self.ascii),
+                );
+            }
+        }
+
+        // Clear the second HUD when the split-screen session is over.
+        if players.len() < 2 {
+            for j in 0..3 {
+                self.term
+                    .move_to(CURRENT_CAR_DATA_Y_OFFSET + j, 2 + CAR_X_OFFSET);
+                self.term.clear_line();
+            }
+        }
+    }
+
+    /// Print the names of the enabled views on the last line, the current one
+    /// highlighted, and whether the views about a single car follow the
+    /// player's car or a selected one.
+    pub fn print_tabs(&self, views: &[View], current: View, following: bool) {
+        let y = self.term.size().0 - 1;
+        self.term.move_to(y, 0);
+        self.term.clear_line();
+
+        for view in views {
+            if *view == current {
+                self.term.set_style(Style::default().reverse());
+            }
+            self.term
+                .print(&format!(" {} {} ", view.key(), view.title()));
+            self.term.reset_style();
+            self.term.print(" ");
+        }
+
+        self.term.print(if following {
+            "  Up/Down Select car"
+        } else {
+            "  p Follow player"
+        });
+        self.term.print("  h Help  q Quit");
+    }
+
+    /// Print the keybindings in a box over the middle of the screen.
+    pub fn print_help(&self) {
+        let (top, bottom, side) = if self.ascii {
+            ("+", "+", "|")
+        } else {
+            ("┌", "└", "│")
+        };
+        let (top_end, bottom_end, line) = if self.ascii {
+            ("+", "+", "-")
+        } else {
+            ("┐", "┘", "─")
+        };
+
+        let height = KEYBINDINGS.len() as i32 + 4;
+        let y = (self.term.size().0 - height) / 2;
+        let x = (self.term.size().1 - HELP_WIDTH as i32) / 2;
+        let inner = HELP_WIDTH - 2;
+
+        let mut lines = vec![
+            format!("{}{}{}", top, line.repeat(inner), top_end),
+            format!(
+                "{}{}{}",
+                side,
+                fmt::field("Keys", inner, Align::Center, self.ascii),
+                side
+            ),
+            format!("{}{}{}", side, " ".repeat(inner), side),
+        ];
+        for (key, action) in KEYBINDINGS {
+            let text = format!(
+                " {} {}",
+                fmt::field(key, 16, Align::Left, self.ascii),
+                action
+            );
+            lines.push(format!(
+                "{}{}{}",
+                side,
+                fmt::field(&text, inner, Align::Left, self.ascii),
+                side
+            ));
+        }
+        lines.push(format!("{}{}{}", bottom, line.repeat(inner), bottom_end));
+
+        self.term.set_style(Style::default().bold());
+        for (i, l) in lines.iter().enumerate() {
+            self.term.print_at(y + i as i32, x, l);
+        }
+        self.term.reset_style();
+    }
+
+    /// Clear everything below the session info, when switching views.
+    pub fn clear_view(&self) {
+        self.term.move_to(SESSION_Y_OFFSET + 4, 0);
+        self.term.clear_below();
+    }
+
+    /// Print the inputs, engine, fuel and ERS of the selected car.
+    pub fn print_telemetry_info(&self, ti: &TelemetryInfo) {
+        let gear = match ti.gear {
+            -1 => String::from("R"),
+            0 => String::from("N"),
+            g => g.to_string(),
+        };
+        let rpm = match &ti.status {
+            Some(status) => format!("{} / {} rpm", ti.engine_rpm, status.max_rpm),
+            None => format!("{} rpm", ti.engine_rpm),
+        };
+
+        self.print_view_title(ti.name, ti.team);
+
+        let mut lines = vec![
+            format!(
+                "Speed: {} | Gear: {} | {} | DRS: {}",
+                fmt::field(
+                    &fmt::speed(KilometresPerHour(f32::from(ti.speed)), self.units.speed),
+                    8,
+                    Align::Right,
+                    self.ascii
+                ),
+                gear,
+                rpm,
+                if ti.drs { "Open" } else { "Closed" }
+            ),
+            format!(
+                "Revs     {}",
+                fmt::percentage_bar(
+                    f32::from(ti.rev_lights_percent) / 100.0,
+                    BAR_WIDTH,
+                    self.ascii
+                )
+            ),
+            String::new(),
+            format!(
+                "Throttle {} {:>3}%",
+                fmt::percentage_bar(ti.throttle, BAR_WIDTH, self.ascii),
+                (ti.throttle * 100.0).round()
+            ),
+            format!(
+                "Brake    {} {:>3}%",
+                fmt::percentage_bar(ti.brake, BAR_WIDTH, self.ascii),
+                (ti.brake * 100.0).round()
+            ),
+            format!(
+                "Clutch   {} {:>3}%",
+                fmt::percentage_bar(f32::from(ti.clutch) / 100.0, BAR_WIDTH, self.ascii),
+                ti.clutch
+            ),
+            format!("Steer    {:+.2}", ti.steer),
+            String::new(),
+        ];
+
+        if let Some(status) = &ti.status {
+            lines.push(format!(
+                "Fuel: {:.1} kg ({:+.1} laps) | Mix: {:?} | Brake bias: {}%",
+                status.fuel_in_tank,
+                status.fuel_remaining_laps,
+                status.fuel_mix,
+                status.front_brake_bias
+            ));
+            lines.push(format!(
+                "ERS      {} {:.2} MJ | {:?}",
+                fmt::percentage_bar(
+                    status.ers_store_energy / ERS_CAPACITY,
+                    BAR_WIDTH,
+                    self.ascii
+                ),
+                status.ers_store_energy / 1_000_000.0,
+                status.ers_deploy_mode
+            ));
+        }
+
+        self.print_view_lines(&lines);
+    }
+
+    /// Print the weather, the forecast and the state of the session.
+    pub fn print_weather_info(&self, wi: &WeatherInfo) {
+        let safety_car = match wi.safety_car {
+            SafetyCar::None => "-",
+            SafetyCar::Full => "Safety car",
+            SafetyCar::Virtual => "Virtual safety car",
+            SafetyCar::Unknown(_) => "?",
+        };
+
+        let mut lines = vec![
+            format!("Weather: {}", fmt::weather(wi.weather)),
+            format!(
+                "Track: {} | Air: {}",
+                fmt::temperature(
+                    Celsius(f32::from(wi.track_temperature)),
+                    self.units.temperature
+                ),
+                fmt::temperature(
+                    Celsius(f32::from(wi.air_temperature)),
+                    self.units.temperature
+                )
+            ),
+            format!(
+                "Pit speed limit: {}",
+                fmt::speed(
+                    KilometresPerHour(f32::from(wi.pit_speed_limit)),
+                    self.units.speed
+                )
+            ),
+            format!("Safety car: {}", safety_car),
+            String::new(),
+        ];
+
+        if !wi.forecast.is_empty() {
+            lines.push(String::from("Forecast"));
+        }
+        for sample in wi.forecast {
+            let rain = sample
+                .rain_percentage()
+                .map(|r| format!(" | Rain: {:>3}%", r))
+                .unwrap_or_default();
+
+            lines.push(format!(
+                "  +{:>2} min  {} | Track: {} | Air: {}{}",
+                sample.time_offset(),
+                fmt::field(fmt::weather(sample.weather()), 11, Align::Left, self.ascii),
+                fmt::temperature(
+                    Celsius(f32::from(sample.track_temperature())),
+                    self.units.temperature
+                ),
+                fmt::temperature(
+                    Celsius(f32::from(sample.air_temperature())),
+                    self.units.temperature
+                ),
+                rain
+            ));
+        }
+
+        self.print_view_lines(&lines);
+    }
+
+    /// Print the damage of the selected car.
+    pub fn print_damage_info(&self, di: &DamageInfo) {
+        self.print_view_title(di.name, di.team);
+
+        let parts = [
+            ("Front left wing", di.front_left_wing),
+            ("Front right wing", di.front_right_wing),
+            ("Rear wing", di.rear_wing),
+            ("Engine", di.engine),
+            ("Gearbox", di.gearbox),
+            ("Front left tyre", di.tyres.front_left()),
+            ("Front right tyre", di.tyres.front_right()),
+            ("Rear left tyre", di.tyres.rear_left()),
+            ("Rear right tyre", di.tyres.rear_right()),
+        ];
+
+        for (i, (part, damage)) in parts.iter().enumerate() {
+            self.term.print_at(
+                VIEW_Y_OFFSET + 2 + i as i32,
+                2,
+                &fmt::field(part, 18, Align::Left, self.ascii),
+            );
+            self.term
+                .set_style(self.theme.status(fmt::percentage_status(*damage)));
+            self.term.print(&format!(
+                "{} {:>3}%",
+                fmt::percentage_bar(f32::from(*damage) / 100.0, BAR_WIDTH, self.ascii),
+                damage
+            ));
+            self.term.reset_style();
+        }
+    }
+
+    /// Print the standings, in the order of the positions.
+    pub fn print_standings(&self, standings: &[StandingInfo]) {
+        let header = format!(
+            "{}. {} | {} | {} | {} | {} | {} | {}",
+            fmt::field("P", 3, Align::Right, self.ascii),
+            fmt::field("NAME", NAME_WIDTH, Align::Left, self.ascii),
+            fmt::field("LAP", 3, Align::Right, self.ascii),
+            fmt::field("GRID", 4, Align::Right, self.ascii),
+            fmt::field("+/-", 3, Align::Right, self.ascii),
+            fmt::field("PITS", 4, Align::Right, self.ascii),
+            fmt::field("TYRE", 4, Align::Center, self.ascii),
+            fmt::field("PEN", 3, Align::Right, self.ascii),
+        );
+        self.term.print_at(LAP_DATA_HEADER_Y_OFFSET, 2, &header);
+
+        for si in standings {
+            let pos = match si.status {
+                ResultStatus::Retired => String::from("RET"),
+                ResultStatus::NotClassified => String::from("N/C"),
+                ResultStatus::Disqualified => String::from("DSQ"),
+                _ => fmt::field(&si.position.to_string(), 3, Align::Right, self.ascii),
+            };
+            let gained = i16::from(si.grid_position) - i16::from(si.position);
+            let gained = if si.grid_position == 0 {
+                String::new()
+            } else {
+                format!("{:+}", gained)
+            };
+            let tyre = si
+                .tyre_compound
+                .map(TyreCompoundVisual::abbreviation)
+                .unwrap_or("");
+
+            let s = format!(
+                "{}. {} | {} | {} | {} | {} | {} | {}",
+                pos,
+                fmt::format_name(si.name, NAME_WIDTH, self.ascii),
+                fmt::field(&si.lap.to_string(), 3, Align::Right, self.ascii),
+                fmt::field(&si.grid_position.to_string(), 4, Align::Right, self.ascii),
+                fmt::field(&gained, 3, Align::Right, self.ascii),
+                fmt::field(&si.pit_stops.to_string(), 4, Align::Right, self.ascii),
+                fmt::field(tyre, 4, Align::Center, self.ascii),
+                fmt::field(&format!("{}s", si.penalties), 3, Align::Right, self.ascii),
+            );
+
+            self.term.set_style(self.theme.team(si.team).bold());
+            self.term
+                .print_at(LAP_DATA_Y_OFFSET + si.position as i32 - 1, 2, &s);
         }
 
-        fmt::reset();
+        self.term.reset_style();
+    }
+
+    /// Print the laps completed by the selected car, the most recent ones that
+    /// fit on the screen. The best lap and sectors are highlighted.
+    pub fn print_lap_history(&self, lh: &LapHistoryInfo) {
+        self.print_view_title(lh.name, lh.team);
+
+        let header = format!(
+            "{} | {} | {} | {} | {} | {}",
+            fmt::field("LAP", 3, Align::Right, self.ascii),
+            fmt::field("S1", SECTOR_WIDTH, Align::Right, self.ascii),
+            fmt::field("S2", SECTOR_WIDTH, Align::Right, self.ascii),
+            fmt::field("S3", SECTOR_WIDTH, Align::Right, self.ascii),
+            fmt::field("TIME", TIME_WIDTH, Align::Left, self.ascii),
+            fmt::field("DELTA", DELTA_WIDTH, Align::Right, self.ascii),
+        );
+        self.term.print_at(VIEW_Y_OFFSET + 2, 2, &header);
+
+        // Down to the line above the tabs, keeping one for the current lap.
+        let height = (self.term.size().0 - 2 - (VIEW_Y_OFFSET + 3)).max(0) as usize;
+        let laps = &lh.laps[lh.laps.len().saturating_sub(height)..];
+
+        for (i, lap) in laps.iter().enumerate() {
+            let y = VIEW_Y_OFFSET + 3 + i as i32;
+            self.term.move_to(y, 2);
+            self.term.clear_line();
+            self.term.print(&format!(
+                "{} | ",
+                fmt::field(&lap.lap().to_string(), 3, Align::Right, self.ascii)
+            ));
+
+            for (j, time) in lap.sector_times().iter().enumerate() {
+                if lh.best_sectors[j] == Some(*time) {
+                    self.term.set_style(self.theme.status(fmt::Status::OK));
+                }
+                self.term.print(&fmt::field(
+                    &format!("{:.3}", time),
+                    SECTOR_WIDTH,
+                    Align::Right,
+                    self.ascii,
+                ));
+                self.term.reset_style();
+                self.term.print(" | ");
+            }
+
+            if !lap.valid() {
+                self.term.set_style(self.theme.status(fmt::Status::DANGER));
+            } else if lh.best_lap_time == Some(lap.lap_time()) {
+                self.term.set_style(self.theme.status(fmt::Status::OK));
+            }
+            self.term.print(&fmt::format_time_ms(lap.lap_time()));
+            self.term.reset_style();
+            self.term.print(" | ");
+
+            if let Some(best) = lh.best_lap_time {
+                let (delta, status) = fmt::format_delta(lap.lap_time() - best);
+                self.term.set_style(self.theme.status(status));
+                self.term
+                    .print(&fmt::field(&delta, DELTA_WIDTH, Align::Right, self.ascii));
+                self.term.reset_style();
+            }
+        }
+
+        let y = VIEW_Y_OFFSET + 3 + laps.len() as i32;
+        self.term.move_to(y, 2);
+        self.term.clear_line();
+        if let Some(lap) = lh.current_lap {
+            self.term.print(&format!(
+                "{} | In progress",
+                fmt::field(&lap.to_string(), 3, Align::Right, self.ascii)
+            ));
+        }
+    }
+
+    fn print_view_title(&self, name: &str, team: Team) {
+        self.term.set_style(self.theme.team(team));
+        self.term.print_at(
+            VIEW_Y_OFFSET,
+            2,
+            &fmt::field(
+                fmt::format_name(name, NAME_WIDTH, self.ascii).trim_end(),
+                NAME_WIDTH,
+                Align::Left,
+                self.ascii,
+            ),
+        );
+        self.term.reset_style();
+    }
+
+    fn print_view_lines(&self, lines: &[String]) {
+        for (i, line) in lines.iter().enumerate() {
+            self.term.move_to(VIEW_Y_OFFSET + 2 + i as i32, 2);
+            self.term.clear_line();
+            self.term.print(line);
+        }
+    }
+
+    /// Print the temperatures and wear of the selected car's tyres and brakes, one
+    /// block per wheel laid out like the car seen from above.
+    pub fn print_tyre_info(&self, ti: &TyreInfo) {
+        let compound = ti
+            .compound
+            .map(TyreCompoundVisual::abbreviation)
+            .unwrap_or("?");
+        let age = ti
+            .age
+            .map(|age| format!("{} laps", age))
+            .unwrap_or_else(|| String::from("-"));
+
+        self.term.set_style(self.theme.team(ti.team));
+        self.term.print_at(
+            VIEW_Y_OFFSET,
+            2,
+            &fmt::field(
+                &format!(
+                    "{} - Tyres: {} ({})",
+                    fmt::format_name(ti.name, NAME_WIDTH, self.ascii).trim_end(),
+                    compound,
+                    age
+                ),
+                TYRE_X_OFFSET as usize * 2 - 2,
+                Align::Left,
+                self.ascii,
+            ),
+        );
+        self.term.reset_style();
+
+        let surface = ti.surface_temperature.to_array();
+        let inner = ti.inner_temperature.to_array();
+        let brake = ti.brake_temperature.to_array();
+        let wear = ti.wear.map(WheelData::to_array);
+
+        // Label, column and row of each wheel, in the order of the wire format.
+        let wheels = [("RL", 0, 1), ("RR", 1, 1), ("FL", 0, 0), ("FR", 1, 0)];
+
+        for (w, (label, col, row)) in wheels.iter().enumerate() {
+            let x = 2 + TYRE_X_OFFSET * col;
+            let y = TYRE_Y_OFFSET + 6 * row;
+            let (surface, inner, brake) = (surface[w], inner[w], brake[w]);
+
+            self.term.set_style(Style::default().bold());
+            self.term.print_at(y, x, label);
+            self.term.reset_style();
+
+            let lines = [
+                (
+                    "Surface",
+                    fmt::temperature(Celsius(f32::from(surface)), self.units.temperature),
+                    Some(fmt::temperature_status(surface, TYRE_TEMPERATURE)),
+                ),
+                (
+                    "Inner",
+                    fmt::temperature(Celsius(f32::from(inner)), self.units.temperature),
+                    Some(fmt::temperature_status(inner, TYRE_TEMPERATURE)),
+                ),
+                (
+                    "Brake",
+                    fmt::temperature(Celsius(f32::from(brake)), self.units.temperature),
+                    Some(fmt::temperature_status(brake, BRAKE_TEMPERATURE)),
+                ),
+                match wear {
+                    Some(wear) => (
+                        "Wear",
+                        format!("{} %", wear[w]),
+                        Some(fmt::percentage_status(wear[w])),
+                    ),
+                    None => ("Wear", String::from("-"), None),
+                },
+            ];
+
+            for (i, (name, value, status)) in lines.iter().enumerate() {
+                let y = y + 1 + i as i32;
+                self.term
+                    .print_at(y, x, &fmt::field(name, 8, Align::Left, self.ascii));
+                if let Some(status) = status {
+                    self.term.set_style(self.theme.status(*status));
+                }
+                self.term
+                    .print(&fmt::field(value, TYRE_WIDTH - 8, Align::Right, self.ascii));
+                self.term.reset_style();
+            }
+        }
+    }
+
+    /// Print the latest entries of the race control feed under the minimap,
+    /// down to the line above the tabs.
+    pub fn print_event_log(&self, log: &EventLog) {
+        let height = self.term.size().0 - 1 - (EVENTS_Y_OFFSET + 1);
+        if height <= 0 {
+            return;
+        }
+
+        self.term.set_style(Style::default().bold());
+        self.term.print_at(
+            EVENTS_Y_OFFSET,
+            MINIMAP_X_OFFSET,
+            &fmt::field("RACE CONTROL", EVENTS_WIDTH, Align::Left, self.ascii),
+        );
+        self.term.reset_style();
+
+        let entries = log.visible(height as usize);
+        for i in 0..height as usize {
+            let y = EVENTS_Y_OFFSET + 1 + i as i32;
+            self.term
+                .print_at(y, MINIMAP_X_OFFSET, &" ".repeat(EVENTS_WIDTH));
+
+            let entry = match entries.get(i) {
+                Some(entry) => entry,
+                None => continue,
+            };
+
+            let time = format!("{} ", fmt::format_time(entry.time as u16));
+            self.term.print_at(y, MINIMAP_X_OFFSET, &time);
+
+            let mut width = EVENTS_WIDTH - time.len();
+            if let Some((name, team)) = &entry.driver {
+                self.term.set_style(self.theme.team(*team));
+                self.term
+                    .print(&fmt::format_name(name, EVENT_NAME_WIDTH, self.ascii));
+                self.term.reset_style();
+                self.term.print(" ");
+                width -= EVENT_NAME_WIDTH + 1;
+            }
+
+            self.term
+                .print(&fmt::truncate(&entry.message, width, self.ascii));
+        }
+    }
+
+    /// Draw the outline of the track with a marker in the team colour for each
+    /// car, the players' cars being diamonds.
+    pub fn print_minimap(&self, minimap: &MinimapInfo) {
+        let mut canvas = Canvas::new(MINIMAP_WIDTH, MINIMAP_HEIGHT, self.ascii);
+        if minimap.closed {
+            canvas.draw_loop(&minimap.outline);
+        } else {
+            for &(x, z) in &minimap.outline {
+                canvas.set(x, z);
+            }
+        }
+
+        for (y, row) in canvas.rows().iter().enumerate() {
+            self.term
+                .print_at(MINIMAP_Y_OFFSET + y as i32, MINIMAP_X_OFFSET, row);
+        }
+
+        // Players last, so that they are drawn on top of the other cars.
+        let mut cars: Vec<_> = minimap.cars.iter().collect();
+        cars.sort_by_key(|c| c.player);
+
+        for car in cars {
+            let (x, y) = canvas.cell(car.x, car.z);
+            let marker = match (car.player, self.ascii) {
+                (true, true) => "@",
+                (true, false) => "◆",
+                (false, true) => "o",
+                (false, false) => "●",
+            };
+
+            self.term.set_style(self.theme.team(car.team));
+            self.term.print_at(
+                MINIMAP_Y_OFFSET + y as i32,
+                MINIMAP_X_OFFSET + x as i32,
+                marker,
+            );
+        }
+
+        self.term.reset_style();
+    }
+
+    /// Draw the channels of the graph over each other, each in a colour of the
+    /// theme, with their names and the range of the values above.
+    pub fn print_graph(&self, graph: &Graph) {
+        let (x_min, x_max) = graph.x_range();
+        let values = graph
+            .series()
+            .flat_map(|(_, points)| points.iter().map(|p| p.1));
+        let (mut low, mut high) = values.fold((0.0f32, f32::MIN), |(low, high), v| {
+            (low.min(v), high.max(v))
+        });
+        // Pedals and steering keep their full range, to be read at a glance.
+        if graph.channels().iter().all(|c| c.unit() == "ratio") {
+            high = high.max(1.0);
+            if low < 0.0 {
+                low = low.min(-1.0);
+            }
+        }
+        if high <= low {
+            high = low + 1.0;
+        }
+
+        let unit = match graph.channels() {
+            [first, rest @ ..] if rest.iter().all(|c| c.unit() == first.unit()) => first.unit(),
+            _ => "",
+        };
+        let span = match graph.span() {
+            GraphSpan::Time => format!("last {} s", graph.seconds()),
+            GraphSpan::Distance => String::from("lap"),
+        };
+
+        self.term.move_to(GRAPH_Y_OFFSET, 2);
+        self.term.clear_line();
+        for (i, (channel, _)) in graph.series().enumerate() {
+            if i > 0 {
+                self.term.print(" / ");
+            }
+            self.term.set_style(self.graph_style(i));
+            self.term.print(channel.name());
+            self.term.reset_style();
+        }
+        self.term.print(&format!(" | {}", span));
+
+        let blank = " ".repeat(GRAPH_WIDTH);
+        for y in 0..GRAPH_HEIGHT {
+            self.term.print_at(GRAPH_Y_OFFSET + 1 + y as i32, 2, &blank);
+        }
+        self.term.print_at(
+            GRAPH_Y_OFFSET + 1,
+            3 + GRAPH_WIDTH as i32,
+            &format!(
+                "{:<12}",
+                format!("{} {}", fmt::axis_value(high), unit).trim_end()
+            ),
+        );
+        self.term.print_at(
+            GRAPH_Y_OFFSET + GRAPH_HEIGHT as i32,
+            3 + GRAPH_WIDTH as i32,
+            &format!(
+                "{:<12}",
+                format!("{} {}", fmt::axis_value(low), unit).trim_end()
+            ),
+        );
+
+        if x_max <= x_min {
+            return;
+        }
+
+        for (i, (_, points)) in graph.series().enumerate() {
+            let points: Vec<(f32, f32)> = points
+                .iter()
+                .map(|&(x, v)| ((x - x_min) / (x_max - x_min), (high - v) / (high - low)))
+                .collect();
+            if points.is_empty() {
+                continue;
+            }
+
+            let mut canvas = Canvas::new(GRAPH_WIDTH, GRAPH_HEIGHT, self.ascii);
+            canvas.draw_line(&points);
+
+            self.term.set_style(self.graph_style(i));
+            for (x, y, dots) in canvas.dots() {
+                self.term.print_at(
+                    GRAPH_Y_OFFSET + 1 + y as i32,
+                    2 + x as i32,
+                    &dots.to_string(),
+                );
+            }
+        }
+
+        self.term.reset_style();
+    }
+
+    /// Colour of the n-th channel of the graph: throttle and brake come out
+    /// green and red by default.
+    fn graph_style(&self, n: usize) -> Style {
+        const STATUSES: [fmt::Status; 4] = [
+            fmt::Status::OK,
+            fmt::Status::DANGER,
+            fmt::Status::CAUTION,
+            fmt::Status::WARNING,
+        ];
+
+        self.theme.status(STATUSES[n % STATUSES.len()])
+    }
+
+    fn print_center(&self, y: i32, s: &str) {
+        self.term.move_to(y, 0);
+        self.term.clear_line();
+        self.term.print_at(y, fmt::center(self.term.size().1, s), s);
     }
 }
 
-fn addstr_center(w: WINDOW, y: i32, str_: &str) {
-    mv(y, 0);
-    clrtoeol();
-    mvaddstr(y, fmt::center(w, str_), str_);
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{CarStatusInfo, MinimapCar};
+    use f1_telemetry::packet::car_status::{ERSDeployMode, FuelMix};
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    /// Terminal recording everything printed on it.
+    struct Recorder(Rc<RefCell<String>>);
+
+    impl Backend for Recorder {
+        fn size(&self) -> (i32, i32) {
+            (50, 160)
+        }
+
+        fn move_to(&self, _y: i32, _x: i32) {}
+
+        fn print(&self, s: &str) {
+            self.0.borrow_mut().push_str(s);
+        }
+
+        fn clear_line(&self) {}
+
+        fn clear_below(&self) {}
+
+        fn set_style(&self, _style: Style) {}
+
+        fn flush(&self) {}
+
+        fn read_key(&self) -> Option<Key> {
+            None
+        }
+
+        fn restore(&self) {}
+    }
+
+    #[test]
+    fn draws_only_ascii_in_ascii_mode() {
+        let output = Rc::new(RefCell::new(String::new()));
+        let config = Config {
+            ascii: true,
+            ..Config::default()
+        };
+        let ui = Ui::new(Box::new(Recorder(Rc::clone(&output))), &config);
+        let name = "Kimi Räikkönen-Longname Über Driver";
+
+        ui.print_session_info(&SessionInfo {
+            session_name: "Race",
+            track_name: "Interlagos",
+            elapsed_time: 600,
+            duration: 3600,
+            current_lap: 3,
+            number_of_laps: 71,
+        });
+        ui.print_lap_info(&[LapInfo {
+            position: 1,
+            name,
+            team: Team::Ferrari,
+            current_lap_time: 12.5,
+            last_lap_time: 71.25,
+            best_lap_time: 70.5,
+            last_lap_delta: Some(0.75),
+            status: ResultStatus::Active,
+            in_pit: false,
+            lap_invalid: false,
+            tyre_compound: None,
+            selected: true,
+        }]);
+        ui.print_telemetry_info(&TelemetryInfo {
+            name,
+            team: Team::Ferrari,
+            speed: 300,
+            gear: 7,
+            engine_rpm: 11_000,
+            rev_lights_percent: 80,
+            throttle: 1.0,
+            brake: 0.0,
+            clutch: 0,
+            steer: 0.0,
+            drs: true,
+            status: Some(CarStatusInfo {
+                max_rpm: 12_000,
+                fuel_in_tank: 50.0,
+                fuel_remaining_laps: 1.5,
+                fuel_mix: FuelMix::Standard,
+                front_brake_bias: 56,
+                ers_store_energy: 2_000_000.0,
+                ers_deploy_mode: ERSDeployMode::Medium,
+            }),
+        });
+        ui.print_tabs(&View::ALL, View::Timing, true);
+        ui.print_help();
+        ui.print_event_log(&EventLog::new());
+        ui.print_minimap(&MinimapInfo {
+            outline: vec![(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)],
+            closed: true,
+            cars: vec![MinimapCar {
+                x: 0.5,
+                z: 0.0,
+                team: Team::Ferrari,
+                player: true,
+            }],
+        });
+
+        let output = output.borrow();
+        assert!(output.contains("Raikkonen"));
+        assert!(output.is_ascii(), "{}", output);
+    }
 }
@@ -1,66 +1,15 @@
-const TEAM_COLOUR_OFFSET: i16 = 100;
-const STATUS_COLOUR_OFFSET: i16 = 200;
-
-use f1_telemetry::packet::participants::Team;
-use ncurses::*;
+use crate::config::{SpeedUnit, TemperatureUnit};
+pub use f1_telemetry::fmt::{transliterate, truncate};
+use f1_telemetry::packet::session::Weather;
+use f1_telemetry::units::{Celsius, Fahrenheit, KilometresPerHour, MilesPerHour};
+use unicode_width::UnicodeWidthStr;
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
 pub enum Status {
-    OK = (STATUS_COLOUR_OFFSET + 1) as isize,
-    CAUTION = (STATUS_COLOUR_OFFSET + 2) as isize,
-    WARNING = (STATUS_COLOUR_OFFSET + 3) as isize,
-    DANGER = (STATUS_COLOUR_OFFSET + 4) as isize,
-}
-
-pub fn init_colors() {
-    start_color();
-
-    init_team_colors();
-    init_status_colors()
-}
-
-fn init_team_colors() {
-    for (t, c) in &[
-        (Team::Mercedes, (0, 210, 190)),
-        (Team::Ferrari, (220, 0, 0)),
-        (Team::RedBullRacing, (30, 65, 255)),
-        (Team::Williams, (255, 255, 255)),
-        (Team::RacingPoint, (245, 150, 200)),
-        (Team::Renault, (255, 245, 0)),
-        (Team::ToroRosso, (70, 155, 255)),
-        (Team::Haas, (240, 215, 135)),
-        (Team::McLaren, (255, 135, 0)),
-        (Team::AlfaRomeo, (155, 0, 0)),
-    ] {
-        let idx = TEAM_COLOUR_OFFSET + t.id() as i16;
-        init_color(idx, c.0, c.1, c.2);
-        init_pair(idx, COLOR_WHITE, idx);
-    }
-}
-
-fn init_status_colors() {
-    let color_orange = TEAM_COLOUR_OFFSET + Team::McLaren.id() as i16;
-
-    for (status, c) in &[
-        (Status::OK, COLOR_GREEN),
-        (Status::CAUTION, COLOR_YELLOW),
-        (Status::WARNING, color_orange),
-        (Status::DANGER, COLOR_RED),
-    ] {
-        init_pair(*status as i16, *c, COLOR_BLACK);
-    }
-}
-
-pub fn set_bold() {
-    attron(A_BOLD());
-}
-
-pub fn set_team_color(team: Team) {
-    color_set(TEAM_COLOUR_OFFSET + team.id() as i16);
-}
-
-pub fn reset() {
-    attrset(0);
+    OK,
+    CAUTION,
+    WARNING,
+    DANGER,
 }
 
 pub fn format_time(ts: u16) -> String {
@@ -82,7 +31,167 @@ pub fn format_time_ms(ts: f32) -> String {
     format!("{:02}:{:02}:{:02}.{:03}", hours, minutes, seconds, millis)
 }
 
-pub fn center(hwnd: WINDOW, s: &str) -> i32 {
-    let w = getmaxx(hwnd);
-    (w - s.len() as i32) / 2
+/// Format a speed in `unit`.
+pub fn speed(speed: KilometresPerHour, unit: SpeedUnit) -> String {
+    match unit {
+        SpeedUnit::Kmh => format!("{:.0}", speed),
+        SpeedUnit::Mph => format!("{:.0}", MilesPerHour::from(speed)),
+    }
+}
+
+/// Format a temperature in `unit`.
+pub fn temperature(temperature: Celsius, unit: TemperatureUnit) -> String {
+    match unit {
+        TemperatureUnit::Celsius => format!("{:.0}", temperature),
+        TemperatureUnit::Fahrenheit => format!("{:.0}", Fahrenheit::from(temperature)),
+    }
+}
+
+/// Column at which `s` is centred on a screen `width` columns wide.
+pub fn center(width: i32, s: &str) -> i32 {
+    (width - UnicodeWidthStr::width(s) as i32) / 2
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Align {
+    Left,
+    Right,
+    Center,
+}
+
+/// Fit `s` in exactly `width` terminal columns, truncating it with an ellipsis
+/// if it is too long and aligning it with spaces otherwise.
+pub fn field(s: &str, width: usize, align: Align, ascii: bool) -> String {
+    let s = truncate(s, width, ascii);
+    let missing = width.saturating_sub(UnicodeWidthStr::width(s.as_str()));
+
+    let (left, right) = match align {
+        Align::Left => (0, missing),
+        Align::Right => (missing, 0),
+        Align::Center => (missing / 2, missing - missing / 2),
+    };
+
+    format!("{}{}{}", " ".repeat(left), s, " ".repeat(right))
+}
+
+/// Fit a name in exactly `width` terminal columns, left-aligned.
+///
+/// If `ascii` is set, the name is transliterated first.
+pub fn format_name(name: &str, width: usize, ascii: bool) -> String {
+    if ascii {
+        field(&transliterate(name), width, Align::Left, ascii)
+    } else {
+        field(name, width, Align::Left, ascii)
+    }
+}
+
+/// Format a time delta in seconds with an explicit sign, along with the status
+/// colour hinting whether it is a gain or a loss.
+pub fn format_delta(delta: f32) -> (String, Status) {
+    let status = if delta < 0.0 {
+        Status::OK
+    } else if delta < 0.1 {
+        Status::CAUTION
+    } else if delta < 1.0 {
+        Status::WARNING
+    } else {
+        Status::DANGER
+    };
+
+    let sign = if delta < 0.0 { '-' } else { '+' };
+
+    (format!("{}{:.3}", sign, delta.abs()), status)
+}
+
+/// Status colour of a temperature, given the `(min, max)` optimal range and the
+/// limit above which it is dangerous. Too cold is a caution, too hot a warning.
+pub fn temperature_status(temperature: u16, (min, max, limit): (u16, u16, u16)) -> Status {
+    if temperature < min {
+        Status::CAUTION
+    } else if temperature <= max {
+        Status::OK
+    } else if temperature <= limit {
+        Status::WARNING
+    } else {
+        Status::DANGER
+    }
+}
+
+/// Status colour of a wear or damage percentage.
+pub fn percentage_status(percentage: u8) -> Status {
+    if percentage < 25 {
+        Status::OK
+    } else if percentage < 50 {
+        Status::CAUTION
+    } else if percentage < 75 {
+        Status::WARNING
+    } else {
+        Status::DANGER
+    }
+}
+
+pub fn weather(weather: Weather) -> &'static str {
+    match weather {
+        Weather::Clear => "Clear",
+        Weather::LightCloud => "Light cloud",
+        Weather::Overcast => "Overcast",
+        Weather::LightRain => "Light rain",
+        Weather::HeavyRain => "Heavy rain",
+        Weather::Storm => "Storm",
+        Weather::Unknown(_) => "?",
+    }
+}
+
+/// Draw a horizontal bar of `width` columns, filled to `value` (`0.0..=1.0`).
+///
+/// In `ascii` mode, the bar is drawn with `#` and `-` instead of blocks.
+pub fn percentage_bar(value: f32, width: usize, ascii: bool) -> String {
+    let value = if value.is_nan() {
+        0.0
+    } else {
+        value.clamp(0.0, 1.0)
+    };
+    let filled = (value * width as f32).round() as usize;
+
+    let (full, empty) = if ascii { ("#", "-") } else { ("█", "░") };
+
+    format!("{}{}", full.repeat(filled), empty.repeat(width - filled))
+}
+
+/// Value of a graph axis, with decimals only for small values.
+pub fn axis_value(value: f32) -> String {
+    if value.abs() < 10.0 {
+        format!("{:.2}", value)
+    } else {
+        format!("{:.0}", value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_a_gain_as_ok() {
+        assert_eq!(format_delta(-0.25), (String::from("-0.250"), Status::OK));
+    }
+
+    #[test]
+    fn formats_a_loss_by_how_much_time_was_lost() {
+        assert_eq!(format_delta(0.0), (String::from("+0.000"), Status::CAUTION));
+        assert_eq!(format_delta(0.5), (String::from("+0.500"), Status::WARNING));
+        assert_eq!(format_delta(1.5), (String::from("+1.500"), Status::DANGER));
+    }
+
+    #[test]
+    fn formats_an_ascii_name() {
+        assert_eq!(format_name("Räikkönen-Longname", 8, true), "Raikk...");
+        assert_eq!(format_name("Räikkönen", 10, true), "Raikkonen ");
+    }
+
+    #[test]
+    fn draws_an_ascii_percentage_bar() {
+        assert_eq!(percentage_bar(0.5, 4, true), "##--");
+        assert_eq!(percentage_bar(0.5, 4, false), "██░░");
+    }
 }
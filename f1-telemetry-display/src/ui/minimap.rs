@@ -0,0 +1,122 @@
+/// First of the 256 braille patterns, the dots of a cell being its low 8 bits.
+const BRAILLE_BLANK: u32 = 0x2800;
+
+/// Bits of the dots of a braille cell, indexed by row then column.
+const BRAILLE_DOTS: [[u8; 2]; 4] = [[0x01, 0x08], [0x02, 0x10], [0x04, 0x20], [0x40, 0x80]];
+
+/// Grid of terminal cells to draw dots on, with a resolution of 2x4 dots per
+/// cell in braille and 1x1 in ASCII.
+pub struct Canvas {
+    width: usize,
+    height: usize,
+    ascii: bool,
+    cells: Vec<u8>,
+}
+
+impl Canvas {
+    pub fn new(width: usize, height: usize, ascii: bool) -> Canvas {
+        Canvas {
+            width,
+            height,
+            ascii,
+            cells: vec![0; width * height],
+        }
+    }
+
+    /// Terminal cell of a point in normalised map space (`0.0..=1.0`).
+    pub fn cell(&self, x: f32, y: f32) -> (usize, usize) {
+        let (dot_x, dot_y) = self.dot(x, y);
+        let (dots_w, dots_h) = self.dots_per_cell();
+
+        (dot_x / dots_w, dot_y / dots_h)
+    }
+
+    /// Set the dot of a point in normalised map space.
+    pub fn set(&mut self, x: f32, y: f32) {
+        let (dot_x, dot_y) = self.dot(x, y);
+        let (dots_w, dots_h) = self.dots_per_cell();
+
+        let cell = (dot_y / dots_h) * self.width + dot_x / dots_w;
+        self.cells[cell] |= if self.ascii {
+            1
+        } else {
+            BRAILLE_DOTS[dot_y % dots_h][dot_x % dots_w]
+        };
+    }
+
+    /// Set the dots of the segments joining consecutive points, and the last
+    /// point to the first one.
+    pub fn draw_loop(&mut self, points: &[(f32, f32)]) {
+        for (i, &from) in points.iter().enumerate() {
+            self.draw_segment(from, points[(i + 1) % points.len()]);
+        }
+    }
+
+    /// Set the dots of the segments joining consecutive points.
+    pub fn draw_line(&mut self, points: &[(f32, f32)]) {
+        match points {
+            [point] => self.set(point.0, point.1),
+            _ => {
+                for pair in points.windows(2) {
+                    self.draw_segment(pair[0], pair[1]);
+                }
+            }
+        }
+    }
+
+    /// The lines of the canvas.
+    pub fn rows(&self) -> Vec<String> {
+        self.cells
+            .chunks(self.width)
+            .map(|row| row.iter().map(|&dots| self.glyph(dots)).collect())
+            .collect()
+    }
+
+    /// The cells with dots set, as (column, row, character).
+    pub fn dots(&self) -> impl Iterator<Item = (usize, usize, char)> + '_ {
+        self.cells
+            .iter()
+            .enumerate()
+            .filter(|(_, &dots)| dots != 0)
+            .map(move |(i, &dots)| (i % self.width, i / self.width, self.glyph(dots)))
+    }
+
+    fn draw_segment(&mut self, (x0, y0): (f32, f32), (x1, y1): (f32, f32)) {
+        let (dots_w, dots_h) = self.dots_per_cell();
+        let dots = (self.width * dots_w).max(self.height * dots_h) as f32;
+
+        let length = (x1 - x0).hypot(y1 - y0);
+        let steps = (length * dots).ceil().max(1.0) as usize;
+        for s in 0..=steps {
+            let t = s as f32 / steps as f32;
+            self.set(x0 + (x1 - x0) * t, y0 + (y1 - y0) * t);
+        }
+    }
+
+    fn glyph(&self, dots: u8) -> char {
+        match (self.ascii, dots) {
+            (true, 0) => ' ',
+            (true, _) => '.',
+            (false, _) => std::char::from_u32(BRAILLE_BLANK + dots as u32).unwrap(),
+        }
+    }
+
+    fn dots_per_cell(&self) -> (usize, usize) {
+        if self.ascii {
+            (1, 1)
+        } else {
+            (2, 4)
+        }
+    }
+
+    fn dot(&self, x: f32, y: f32) -> (usize, usize) {
+        let (dots_w, dots_h) = self.dots_per_cell();
+        let max_x = self.width * dots_w - 1;
+        let max_y = self.height * dots_h - 1;
+
+        (
+            (x.clamp(0.0, 1.0) * max_x as f32).round() as usize,
+            (y.clamp(0.0, 1.0) * max_y as f32).round() as usize,
+        )
+    }
+}
@@ -0,0 +1,68 @@
+use f1_telemetry::meta::teams::TeamInfo;
+use f1_telemetry::packet::participants::Team;
+
+use super::fmt::Status;
+use crate::backend::{Color, Style};
+use crate::config::{self, Colors};
+
+/// Styles of the drivers and of the values, from the colours of the config.
+pub struct Theme {
+    team_colors: bool,
+    ok: Color,
+    caution: Color,
+    warning: Color,
+    danger: Color,
+}
+
+impl Theme {
+    pub fn new(colors: &Colors) -> Theme {
+        Theme {
+            team_colors: colors.team_colors,
+            ok: color(colors.ok),
+            caution: color(colors.caution),
+            warning: color(colors.warning),
+            danger: color(colors.danger),
+        }
+    }
+
+    /// White on the colour of the team.
+    pub fn team(&self, team: Team) -> Style {
+        if !self.team_colors {
+            return Style::default();
+        }
+
+        Style {
+            fg: Some(Color::White),
+            bg: team_color(team),
+            ..Style::default()
+        }
+    }
+
+    pub fn status(&self, status: Status) -> Style {
+        Style::fg(match status {
+            Status::OK => self.ok,
+            Status::CAUTION => self.caution,
+            Status::WARNING => self.warning,
+            Status::DANGER => self.danger,
+        })
+    }
+}
+
+fn team_color(team: Team) -> Option<Color> {
+    let (r, g, b) = TeamInfo::get(team)?.color();
+    Some(Color::Rgb(r, g, b))
+}
+
+fn color(color: config::Color) -> Color {
+    match color {
+        config::Color::Black => Color::Black,
+        config::Color::Red => Color::Red,
+        config::Color::Green => Color::Green,
+        config::Color::Yellow => Color::Yellow,
+        config::Color::Orange => Color::Rgb(255, 135, 0),
+        config::Color::Blue => Color::Blue,
+        config::Color::Magenta => Color::Magenta,
+        config::Color::Cyan => Color::Cyan,
+        config::Color::White => Color::White,
+    }
+}
@@ -0,0 +1,249 @@
+use std::time::{Duration, Instant};
+
+use crate::backend::Key;
+use f1_telemetry::packet::PacketType;
+
+/// Screens of the display.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum View {
+    /// Timing tower, minimap and players' HUD.
+    Timing,
+    /// Inputs, engine, fuel and ERS of the selected car.
+    Telemetry,
+    /// Tyres and brakes of the selected car.
+    Tyres,
+    /// Weather, forecast and state of the session.
+    Session,
+    /// Damage of the selected car.
+    Damage,
+    /// Positions, grid, pit stops and penalties of every driver.
+    Standings,
+    /// Laps completed by the selected car.
+    Laps,
+}
+
+impl View {
+    pub const ALL: [View; 7] = [
+        View::Timing,
+        View::Telemetry,
+        View::Tyres,
+        View::Session,
+        View::Damage,
+        View::Standings,
+        View::Laps,
+    ];
+
+    pub fn title(self) -> &'static str {
+        match self {
+            View::Timing => "Timing",
+            View::Telemetry => "Telemetry",
+            View::Tyres => "Tyres",
+            View::Session => "Session",
+            View::Damage => "Damage",
+            View::Standings => "Standings",
+            View::Laps => "Laps",
+        }
+    }
+
+    /// Key showing the view: its number, from 1.
+    pub fn key(self) -> char {
+        let index = View::ALL.iter().position(|&v| v == self).unwrap();
+        std::char::from_digit(index as u32 + 1, 10).unwrap()
+    }
+}
+
+/// Action bound to a key.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Command {
+    Quit,
+    Show(View),
+    NextView,
+    PreviousView,
+    ToggleHelp,
+    CloseHelp,
+    /// Scroll back in the race control feed.
+    ScrollUp,
+    /// Scroll forward in the race control feed.
+    ScrollDown,
+    /// Select the car ahead of the selected one.
+    PreviousCar,
+    /// Select the car behind the selected one.
+    NextCar,
+    /// Follow the player's car, or the spectated one.
+    FollowPlayer,
+}
+
+/// Keys and what they do, for the help overlay.
+pub const KEYBINDINGS: &[(&str, &str)] = &[
+    ("1-7", "Show a view"),
+    ("Tab, Right", "Next view"),
+    ("Shift+Tab, Left", "Previous view"),
+    ("Up, Down", "Select the car ahead, behind"),
+    ("p", "Follow the player's car"),
+    ("PgUp, PgDn", "Scroll race control"),
+    ("h, ?", "Show or hide this help"),
+    ("Esc", "Hide this help"),
+    ("q", "Quit"),
+];
+
+impl Command {
+    pub fn from_key(key: Key) -> Option<Command> {
+        match key {
+            Key::Char('q') | Key::Interrupt => Some(Command::Quit),
+            Key::Char('h') | Key::Char('?') => Some(Command::ToggleHelp),
+            Key::Char('p') => Some(Command::FollowPlayer),
+            Key::Char(c) => View::ALL
+                .iter()
+                .find(|v| v.key() == c)
+                .map(|&v| Command::Show(v)),
+            Key::Tab | Key::Right => Some(Command::NextView),
+            Key::BackTab | Key::Left => Some(Command::PreviousView),
+            Key::Escape => Some(Command::CloseHelp),
+            Key::Up => Some(Command::PreviousCar),
+            Key::Down => Some(Command::NextCar),
+            Key::PageUp => Some(Command::ScrollUp),
+            Key::PageDown => Some(Command::ScrollDown),
+            Key::Other => None,
+        }
+    }
+}
+
+/// Keeps track of the view shown, of the help overlay and of the car shown in
+/// the views about a single car.
+#[derive(Debug)]
+pub struct ViewManager {
+    views: Vec<View>,
+    current: View,
+    help: bool,
+    selected: Option<u8>,
+}
+
+impl ViewManager {
+    /// Cycle through the enabled `views`, which must not be empty, starting
+    /// with the first one.
+    pub fn new(views: Vec<View>) -> ViewManager {
+        ViewManager {
+            current: views[0],
+            views,
+            help: false,
+            selected: None,
+        }
+    }
+
+    /// Enabled views, in the order of the tabs.
+    pub fn views(&self) -> &[View] {
+        &self.views
+    }
+
+    /// Car selected with the keyboard, or None when following the player.
+    pub fn selected(&self) -> Option<u8> {
+        self.selected
+    }
+
+    /// Car to show: the selected one, or `followed` when following the player
+    /// or the spectated car.
+    pub fn car(&self, followed: Option<u8>) -> Option<u8> {
+        self.selected.or(followed)
+    }
+
+    /// Apply a command about the selected car, given the vehicle indices of the
+    /// cars in the order of the positions. Returns whether the selection
+    /// changed.
+    pub fn select(&mut self, command: Command, order: &[u8], followed: Option<u8>) -> bool {
+        let current = self.car(followed);
+        let index = current.and_then(|car| order.iter().position(|&c| c == car));
+
+        let selected = match (command, index) {
+            (Command::FollowPlayer, _) => None,
+            (Command::PreviousCar, Some(i)) => Some(order[i.saturating_sub(1)]),
+            (Command::NextCar, Some(i)) => Some(order[(i + 1).min(order.len() - 1)]),
+            (Command::PreviousCar, None) | (Command::NextCar, None) => order.first().copied(),
+            _ => return false,
+        };
+
+        let changed = selected != self.selected;
+        self.selected = selected;
+
+        changed
+    }
+
+    pub fn current(&self) -> View {
+        self.current
+    }
+
+    pub fn is_help_visible(&self) -> bool {
+        self.help
+    }
+
+    /// Whether `view` should be drawn, i.e. it is shown and not under the help.
+    pub fn is_visible(&self, view: View) -> bool {
+        self.current == view && !self.help
+    }
+
+    /// Apply a command, returning whether the view has to be redrawn. Only
+    /// the commands about the views are handled.
+    pub fn apply(&mut self, command: Command) -> bool {
+        let (current, help) = match command {
+            Command::Quit
+            | Command::ScrollUp
+            | Command::ScrollDown
+            | Command::PreviousCar
+            | Command::NextCar
+            | Command::FollowPlayer => return false,
+            Command::Show(view) if self.views.contains(&view) => (view, false),
+            Command::Show(_) => return false,
+            Command::NextView => (self.step(1), false),
+            Command::PreviousView => (self.step(self.views.len() - 1), false),
+            Command::ToggleHelp => (self.current, !self.help),
+            Command::CloseHelp => (self.current, false),
+        };
+
+        let changed = current != self.current || help != self.help;
+        self.current = current;
+        self.help = help;
+
+        changed
+    }
+
+    /// The enabled view `offset` tabs after the current one, wrapping around.
+    fn step(&self, offset: usize) -> View {
+        let index = self.views.iter().position(|&v| v == self.current).unwrap();
+        self.views[(index + offset) % self.views.len()]
+    }
+}
+
+/// Limits how often the panels drawn from each type of packet are redrawn,
+/// the high frequency packets coming faster than the terminal needs.
+#[derive(Debug)]
+pub struct RedrawLimiter {
+    interval: Duration,
+    last: [Option<Instant>; 16],
+}
+
+impl RedrawLimiter {
+    /// Redraw at most `rate` times per second, every time if 0.
+    pub fn new(rate: u32) -> RedrawLimiter {
+        RedrawLimiter {
+            interval: match rate {
+                0 => Duration::from_secs(0),
+                rate => Duration::from_secs(1) / rate,
+            },
+            last: [None; 16],
+        }
+    }
+
+    /// Whether the panels drawn from a packet of this type are due a redraw,
+    /// in which case it is counted as done.
+    pub fn due(&mut self, packet_type: PacketType) -> bool {
+        let now = Instant::now();
+        let last = &mut self.last[packet_type.id() as usize];
+
+        match *last {
+            Some(t) if now.duration_since(t) < self.interval => false,
+            _ => {
+                *last = Some(now);
+                true
+            }
+        }
+    }
+}
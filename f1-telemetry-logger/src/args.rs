@@ -0,0 +1,163 @@
+use f1_telemetry::packet::{PacketFilter, PacketType};
+use std::path::PathBuf;
+use std::time::Duration;
+
+pub const USAGE: &str = "\
+Usage: f1-telemetry-logger [OPTIONS]
+
+Record the telemetry of the game to files, without a UI.
+
+Options:
+  --listen <ADDR>      Address to listen on [default: 0.0.0.0:20777]
+  --raw <PATH>         Record every datagram received to a raw capture
+  --pcapng <PATH>      Record every datagram received to a pcapng capture
+  --csv <PATH>         Write the telemetry of the cars as CSV
+  --jsonl <PATH>       Write the telemetry of the cars and the events as JSON lines
+  --packets <TYPES>    Comma-separated packet types to record, e.g. lap,car_telemetry
+                       [default: all]
+  --cars <CARS>        Cars exported to CSV and JSON lines: player, all, or
+                       comma-separated vehicle indices [default: player]
+  --ascii              Write the driver names of the CSV in ASCII
+  --duration <SECS>    Stop after this many seconds
+  --split-sessions     Roll over to new files at each session, named after its
+                       track, type and start time
+  -h, --help           Print this help";
+
+/// Cars whose telemetry is exported.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Cars {
+    Player,
+    All,
+    Indices(Vec<u8>),
+}
+
+impl Cars {
+    pub fn contains(&self, index: u8, player_index: u8) -> bool {
+        match self {
+            Cars::Player => index == player_index,
+            Cars::All => true,
+            Cars::Indices(indices) => indices.contains(&index),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Args {
+    pub listen: String,
+    pub raw: Option<PathBuf>,
+    pub pcapng: Option<PathBuf>,
+    pub csv: Option<PathBuf>,
+    pub jsonl: Option<PathBuf>,
+    pub packets: PacketFilter,
+    pub cars: Cars,
+    pub ascii: bool,
+    pub duration: Option<Duration>,
+    pub split_sessions: bool,
+}
+
+impl Args {
+    /// Parse the command line arguments, without the program name. Returns
+    /// None when the help is asked for.
+    pub fn parse<I: IntoIterator<Item = String>>(args: I) -> Result<Option<Args>, String> {
+        let mut parsed = Args {
+            listen: String::from("0.0.0.0:20777"),
+            raw: None,
+            pcapng: None,
+            csv: None,
+            jsonl: None,
+            packets: PacketFilter::all(),
+            cars: Cars::Player,
+            ascii: false,
+            duration: None,
+            split_sessions: false,
+        };
+
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let mut value = || {
+                args.next()
+                    .ok_or_else(|| format!("Missing value for {}", arg))
+            };
+
+            match arg.as_str() {
+                "--listen" => parsed.listen = value()?,
+                "--raw" => parsed.raw = Some(PathBuf::from(value()?)),
+                "--pcapng" => parsed.pcapng = Some(PathBuf::from(value()?)),
+                "--csv" => parsed.csv = Some(PathBuf::from(value()?)),
+                "--jsonl" => parsed.jsonl = Some(PathBuf::from(value()?)),
+                "--packets" => parsed.packets = parse_packets(&value()?)?,
+                "--cars" => parsed.cars = parse_cars(&value()?)?,
+                "--ascii" => parsed.ascii = true,
+                "--duration" => {
+                    let value = value()?;
+                    let secs: u64 = value
+                        .parse()
+                        .map_err(|_| format!("Invalid duration: {}", value))?;
+                    parsed.duration = Some(Duration::from_secs(secs));
+                }
+                "--split-sessions" => parsed.split_sessions = true,
+                "-h" | "--help" => return Ok(None),
+                _ => return Err(format!("Unknown argument: {}", arg)),
+            }
+        }
+
+        if parsed.raw.is_none()
+            && parsed.pcapng.is_none()
+            && parsed.csv.is_none()
+            && parsed.jsonl.is_none()
+        {
+            return Err(String::from(
+                "Nothing to record: pass at least one of --raw, --pcapng, --csv or --jsonl",
+            ));
+        }
+
+        Ok(Some(parsed))
+    }
+}
+
+fn parse_packets(value: &str) -> Result<PacketFilter, String> {
+    if value == "all" {
+        return Ok(PacketFilter::all());
+    }
+
+    value
+        .split(',')
+        .try_fold(PacketFilter::none(), |filter, name| {
+            let packet_type = match name.trim() {
+                "motion" => PacketType::Motion,
+                "session" => PacketType::Session,
+                "lap" => PacketType::LapData,
+                "event" => PacketType::Event,
+                "participants" => PacketType::Participants,
+                "car_setups" => PacketType::CarSetups,
+                "car_telemetry" => PacketType::CarTelemetry,
+                "car_status" => PacketType::CarStatus,
+                "final_classification" => PacketType::FinalClassification,
+                "lobby_info" => PacketType::LobbyInfo,
+                "car_damage" => PacketType::CarDamage,
+                "tyre_sets" => PacketType::TyreSets,
+                "motion_ex" => PacketType::MotionEx,
+                "time_trial" => PacketType::TimeTrial,
+                name => return Err(format!("Unknown packet type: {}", name)),
+            };
+
+            Ok(filter.with(packet_type))
+        })
+}
+
+fn parse_cars(value: &str) -> Result<Cars, String> {
+    match value {
+        "player" => Ok(Cars::Player),
+        "all" => Ok(Cars::All),
+        _ => value
+            .split(',')
+            .map(|index| {
+                index
+                    .trim()
+                    .parse()
+                    .map_err(|_| format!("Invalid car index: {}", index))
+            })
+            .collect::<Result<_, _>>()
+            .map(Cars::Indices),
+    }
+}
@@ -0,0 +1,51 @@
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::time::Instant;
+
+/// Magic bytes and version at the start of a capture.
+const MAGIC: &[u8; 4] = b"F1TC";
+const VERSION: u16 = 1;
+
+/// Writes the datagrams received, as they are, to a raw capture file, from
+/// which a session can be replayed or parsed again later.
+///
+/// ## Specification
+/// ```text
+/// magic:    "F1TC"
+/// version:  u16, little endian (1)
+///
+/// Then for each datagram:
+/// time:     u64, little endian: microseconds since the start of the capture
+/// length:   u32, little endian: length of the datagram in bytes
+/// data:     The datagram
+/// ```
+pub struct CaptureWriter {
+    out: BufWriter<File>,
+    start: Instant,
+}
+
+impl CaptureWriter {
+    pub fn create<P: AsRef<Path>>(path: P) -> io::Result<CaptureWriter> {
+        let mut out = BufWriter::new(File::create(path)?);
+        out.write_all(MAGIC)?;
+        out.write_all(&VERSION.to_le_bytes())?;
+
+        Ok(CaptureWriter {
+            out,
+            start: Instant::now(),
+        })
+    }
+
+    pub fn write(&mut self, datagram: &[u8]) -> io::Result<()> {
+        let time = self.start.elapsed().as_micros() as u64;
+
+        self.out.write_all(&time.to_le_bytes())?;
+        self.out.write_all(&(datagram.len() as u32).to_le_bytes())?;
+        self.out.write_all(datagram)
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.out.flush()
+    }
+}
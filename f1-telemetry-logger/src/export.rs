@@ -0,0 +1,231 @@
+use f1_telemetry::fmt::transliterate;
+use f1_telemetry::packet::car_telemetry::PacketCarTelemetryData;
+use f1_telemetry::packet::event::PacketEventData;
+use f1_telemetry::packet::lap::PacketLapData;
+use f1_telemetry::packet::Packet;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use crate::args::Cars;
+
+const CSV_HEADER: &str = "session_uid,session_time,frame,car,driver,position,lap,\
+                          lap_distance,speed,throttle,brake,steer,gear,engine_rpm,drs";
+
+/// Telemetry of a car at a frame, completed with its latest lap data.
+struct Sample {
+    session_uid: String,
+    session_time: f32,
+    frame: u32,
+    car: u8,
+    driver: String,
+    position: u8,
+    lap: u8,
+    lap_distance: f32,
+    speed: u16,
+    throttle: f32,
+    brake: f32,
+    steer: f32,
+    gear: i8,
+    engine_rpm: u16,
+    drs: bool,
+}
+
+/// Writes a row per car and Car Telemetry packet to CSV and/or JSON lines
+/// files. The JSON lines also get the events of the session.
+///
+/// The CSV names the driver of each car, once the participants are received,
+/// transliterated to ASCII in `ascii` mode.
+pub struct Exporter {
+    csv: Option<BufWriter<File>>,
+    jsonl: Option<BufWriter<File>>,
+    cars: Cars,
+    ascii: bool,
+    lap_data: Option<PacketLapData>,
+    drivers: Vec<String>,
+}
+
+impl Exporter {
+    pub fn create(
+        csv: Option<&Path>,
+        jsonl: Option<&Path>,
+        cars: Cars,
+        ascii: bool,
+    ) -> io::Result<Exporter> {
+        let csv = match csv {
+            Some(path) => {
+                let mut out = BufWriter::new(File::create(path)?);
+                writeln!(out, "{}", CSV_HEADER)?;
+                Some(out)
+            }
+            None => None,
+        };
+        let jsonl = match jsonl {
+            Some(path) => Some(BufWriter::new(File::create(path)?)),
+            None => None,
+        };
+
+        Ok(Exporter {
+            csv,
+            jsonl,
+            cars,
+            ascii,
+            lap_data: None,
+            drivers: Vec::new(),
+        })
+    }
+
+    pub fn update(&mut self, packet: Packet) -> io::Result<()> {
+        match packet {
+            Packet::Lap(l) => self.lap_data = Some(l),
+            Packet::Participants(p) => {
+                self.drivers = p
+                    .participants()
+                    .iter()
+                    .map(|p| {
+                        if self.ascii {
+                            transliterate(p.name())
+                        } else {
+                            p.name().clone()
+                        }
+                    })
+                    .collect();
+            }
+            Packet::CarTelemetry(t) => {
+                for sample in self.samples(&t) {
+                    self.write_sample(&sample)?;
+                }
+            }
+            Packet::Event(e) => self.write_event(&e)?,
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        if let Some(out) = self.csv.as_mut() {
+            out.flush()?;
+        }
+        if let Some(out) = self.jsonl.as_mut() {
+            out.flush()?;
+        }
+
+        Ok(())
+    }
+
+    fn samples(&self, telemetry: &PacketCarTelemetryData) -> Vec<Sample> {
+        let header = telemetry.header();
+
+        telemetry
+            .car_telemetry_data()
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| self.cars.contains(*i as u8, header.player_car_index()))
+            .map(|(i, t)| {
+                let lap = self.lap_data.as_ref().and_then(|l| l.lap_data().get(i));
+
+                Sample {
+                    session_uid: header.session_id().to_string(),
+                    session_time: header.session_time(),
+                    frame: header.frame_identifier(),
+                    car: i as u8,
+                    driver: self.drivers.get(i).cloned().unwrap_or_default(),
+                    position: lap.map(|l| l.car_position()).unwrap_or(0),
+                    lap: lap.map(|l| l.current_lap_num()).unwrap_or(0),
+                    lap_distance: lap.map(|l| l.lap_distance()).unwrap_or(0.0),
+                    speed: t.speed(),
+                    throttle: t.throttle(),
+                    brake: t.brake(),
+                    steer: t.steer(),
+                    gear: t.gear(),
+                    engine_rpm: t.engine_rpm(),
+                    drs: t.drs(),
+                }
+            })
+            .collect()
+    }
+
+    fn write_sample(&mut self, s: &Sample) -> io::Result<()> {
+        if let Some(out) = self.csv.as_mut() {
+            writeln!(
+                out,
+                "{},{:.3},{},{},{},{},{},{:.1},{},{:.3},{:.3},{:.3},{},{},{}",
+                s.session_uid,
+                s.session_time,
+                s.frame,
+                s.car,
+                csv_field(&s.driver),
+                s.position,
+                s.lap,
+                s.lap_distance,
+                s.speed,
+                s.throttle,
+                s.brake,
+                s.steer,
+                s.gear,
+                s.engine_rpm,
+                s.drs as u8,
+            )?;
+        }
+
+        if let Some(out) = self.jsonl.as_mut() {
+            writeln!(
+                out,
+                "{{\"type\":\"telemetry\",\"session_uid\":\"{}\",\"session_time\":{:.3},\
+                 \"frame\":{},\"car\":{},\"position\":{},\"lap\":{},\"lap_distance\":{:.1},\
+                 \"speed\":{},\"throttle\":{:.3},\"brake\":{:.3},\"steer\":{:.3},\"gear\":{},\
+                 \"engine_rpm\":{},\"drs\":{}}}",
+                s.session_uid,
+                s.session_time,
+                s.frame,
+                s.car,
+                s.position,
+                s.lap,
+                s.lap_distance,
+                s.speed,
+                s.throttle,
+                s.brake,
+                s.steer,
+                s.gear,
+                s.engine_rpm,
+                s.drs,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    fn write_event(&mut self, e: &PacketEventData) -> io::Result<()> {
+        let out = match self.jsonl.as_mut() {
+            Some(out) => out,
+            None => return Ok(()),
+        };
+
+        let header = e.header();
+        let optional = |value: Option<String>| value.unwrap_or_else(|| String::from("null"));
+
+        writeln!(
+            out,
+            "{{\"type\":\"event\",\"session_uid\":\"{}\",\"session_time\":{:.3},\
+             \"frame\":{},\"event\":\"{:?}\",\"code\":\"{}\",\"car\":{},\"lap_time\":{},\
+             \"speed\":{}}}",
+            header.session_id(),
+            header.session_time(),
+            header.frame_identifier(),
+            e.event(),
+            e.event().code(),
+            optional(e.vehicle_idx().map(|i| i.to_string())),
+            optional(e.lap_time().map(|t| format!("{:.3}", t))),
+            optional(e.speed().map(|s| format!("{:.1}", s))),
+        )
+    }
+}
+
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
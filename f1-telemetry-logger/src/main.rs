@@ -0,0 +1,97 @@
+//! Headless logger: listens for the telemetry of the game and records it to
+//! files, for users who want the data without a UI.
+//!
+//! Run with `--help` for the options.
+
+use args::{Args, USAGE};
+use f1_telemetry::packet::peek_packet_id;
+use outputs::Outputs;
+use std::io::ErrorKind;
+use std::net::UdpSocket;
+use std::process;
+use std::time::{Duration, Instant};
+
+mod args;
+mod capture;
+mod export;
+mod outputs;
+
+/// How often the files are flushed, bounding what is lost when the logger is
+/// killed.
+const FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+/// Largest UDP payload, so that the captures never hold truncated datagrams.
+const MAX_DATAGRAM_SIZE: usize = 65507;
+
+fn main() {
+    let args = match Args::parse(std::env::args().skip(1)) {
+        Ok(Some(args)) => args,
+        Ok(None) => {
+            println!("{}", USAGE);
+            return;
+        }
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, USAGE);
+            process::exit(2);
+        }
+    };
+
+    if let Err(e) = run(args) {
+        eprintln!("{}", e);
+        process::exit(1);
+    }
+}
+
+fn run(args: Args) -> std::io::Result<()> {
+    let socket = UdpSocket::bind(&args.listen)?;
+    socket.set_read_timeout(Some(Duration::from_millis(100)))?;
+
+    let local_addr = socket.local_addr()?;
+    let mut outputs = Outputs::create(&args, local_addr)?;
+
+    println!("Logging packets from {}", local_addr);
+
+    let start = Instant::now();
+    let mut last_flush = Instant::now();
+    let mut received = 0u64;
+    let mut buf = vec![0; MAX_DATAGRAM_SIZE];
+
+    loop {
+        if let Some(duration) = args.duration {
+            if start.elapsed() >= duration {
+                break;
+            }
+        }
+
+        match socket.recv_from(&mut buf) {
+            Ok((len, source)) => {
+                let datagram = &buf[..len];
+                let accepted = match peek_packet_id(datagram) {
+                    Some(id) => args.packets.accepts_id(id),
+                    None => false,
+                };
+
+                if accepted {
+                    received += 1;
+                    outputs.write(datagram, source)?;
+                }
+            }
+            Err(e) if e.kind() == ErrorKind::WouldBlock || e.kind() == ErrorKind::TimedOut => {}
+            Err(e) => return Err(e),
+        }
+
+        if last_flush.elapsed() >= FLUSH_INTERVAL {
+            outputs.flush()?;
+            last_flush = Instant::now();
+        }
+    }
+
+    outputs.finish()?;
+
+    println!(
+        "Recorded {} packets, {} could not be parsed",
+        received,
+        outputs.errors()
+    );
+
+    Ok(())
+}
@@ -0,0 +1,296 @@
+use f1_telemetry::export::pcapng::PcapngWriter;
+use f1_telemetry::packet::header::SessionIdentifier;
+use f1_telemetry::packet::{
+    parse_packet_fuzz, peek_packet_id, peek_session_id, Packet, PacketType,
+};
+use std::ffi::OsString;
+use std::fs::File;
+use std::io::{self, BufWriter};
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::args::{Args, Cars};
+use crate::capture::CaptureWriter;
+use crate::export::Exporter;
+
+/// Datagrams held back while waiting for the Session packet naming the files
+/// of a new session. Session packets are sent twice a second, so this is only
+/// reached when they are filtered out or lost.
+const MAX_PENDING: usize = 4096;
+
+/// A datagram as received from the game.
+struct Received {
+    data: Vec<u8>,
+    source: SocketAddr,
+    /// Time since the Unix epoch.
+    timestamp: Duration,
+}
+
+/// The files the logger writes to. When split, they are rolled over to new
+/// files at each session, named after its track, type and start time.
+pub struct Outputs {
+    raw: Option<PathBuf>,
+    pcapng: Option<PathBuf>,
+    csv: Option<PathBuf>,
+    jsonl: Option<PathBuf>,
+    cars: Cars,
+    ascii: bool,
+    split: bool,
+    local_addr: SocketAddr,
+    capture: Option<CaptureWriter>,
+    pcapng_writer: Option<PcapngWriter<BufWriter<File>>>,
+    exporter: Option<Exporter>,
+    session: Option<SessionIdentifier>,
+    pending: Vec<Received>,
+    errors: u64,
+}
+
+impl Outputs {
+    /// Outputs of the datagrams received on `local_addr`.
+    pub fn create(args: &Args, local_addr: SocketAddr) -> io::Result<Outputs> {
+        let mut outputs = Outputs {
+            raw: args.raw.clone(),
+            pcapng: args.pcapng.clone(),
+            csv: args.csv.clone(),
+            jsonl: args.jsonl.clone(),
+            cars: args.cars.clone(),
+            ascii: args.ascii,
+            split: args.split_sessions,
+            local_addr,
+            capture: None,
+            pcapng_writer: None,
+            exporter: None,
+            session: None,
+            pending: Vec::new(),
+            errors: 0,
+        };
+
+        if !outputs.split {
+            outputs.open(None)?;
+        }
+
+        Ok(outputs)
+    }
+
+    /// Number of datagrams which could not be parsed for the exports.
+    pub fn errors(&self) -> u64 {
+        self.errors
+    }
+
+    pub fn write(&mut self, datagram: &[u8], source: SocketAddr) -> io::Result<()> {
+        let received = Received {
+            data: datagram.to_vec(),
+            source,
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default(),
+        };
+
+        if !self.split {
+            return self.write_open(&received);
+        }
+
+        // Datagrams sent outside of a session, e.g. in the menus, have no
+        // session identifier and stay with the current files.
+        if let Some(session) = peek_session_id(datagram).filter(|s| s.value() != 0) {
+            if self.session != Some(session) {
+                self.close()?;
+                self.session = Some(session);
+            }
+        }
+
+        if self.is_open() {
+            return self.write_open(&received);
+        }
+
+        let name = if peek_packet_id(datagram) == Some(PacketType::Session.id()) {
+            match parse_packet_fuzz(datagram) {
+                Ok(Packet::Session(s)) => {
+                    Some(session_name(s.track().name(), s.session_type().name()))
+                }
+                _ => None,
+            }
+        } else {
+            None
+        };
+
+        self.pending.push(received);
+
+        if name.is_some() || self.pending.len() >= MAX_PENDING {
+            let name = name.unwrap_or_else(|| session_name("Unknown", "Unknown"));
+            self.open(Some(&name))?;
+
+            for received in std::mem::take(&mut self.pending) {
+                self.write_open(&received)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        if let Some(capture) = self.capture.as_mut() {
+            capture.flush()?;
+        }
+        if let Some(pcapng) = self.pcapng_writer.as_mut() {
+            pcapng.flush()?;
+        }
+        if let Some(exporter) = self.exporter.as_mut() {
+            exporter.flush()?;
+        }
+
+        Ok(())
+    }
+
+    /// Flush the files, writing the datagrams still waiting for their session
+    /// to files of an unknown one.
+    pub fn finish(&mut self) -> io::Result<()> {
+        if !self.pending.is_empty() {
+            self.open(Some(&session_name("Unknown", "Unknown")))?;
+
+            for received in std::mem::take(&mut self.pending) {
+                self.write_open(&received)?;
+            }
+        }
+
+        self.flush()
+    }
+
+    fn is_open(&self) -> bool {
+        self.capture.is_some() || self.pcapng_writer.is_some() || self.exporter.is_some()
+    }
+
+    /// Open the files, with the session name appended to their stem when split.
+    fn open(&mut self, session: Option<&str>) -> io::Result<()> {
+        let path = |path: &Option<PathBuf>| match session {
+            Some(session) => path.as_deref().map(|p| session_path(p, session)),
+            None => path.clone(),
+        };
+
+        self.capture = match path(&self.raw) {
+            Some(path) => Some(CaptureWriter::create(path)?),
+            None => None,
+        };
+
+        self.pcapng_writer = match path(&self.pcapng) {
+            Some(path) => Some(PcapngWriter::new(BufWriter::new(File::create(path)?))?),
+            None => None,
+        };
+
+        let csv = path(&self.csv);
+        let jsonl = path(&self.jsonl);
+        self.exporter = if csv.is_some() || jsonl.is_some() {
+            Some(Exporter::create(
+                csv.as_deref(),
+                jsonl.as_deref(),
+                self.cars.clone(),
+                self.ascii,
+            )?)
+        } else {
+            None
+        };
+
+        if let Some(session) = session {
+            println!("Recording session {}", session);
+        }
+
+        Ok(())
+    }
+
+    fn close(&mut self) -> io::Result<()> {
+        self.flush()?;
+        self.capture = None;
+        self.pcapng_writer = None;
+        self.exporter = None;
+
+        Ok(())
+    }
+
+    fn write_open(&mut self, received: &Received) -> io::Result<()> {
+        if let Some(capture) = self.capture.as_mut() {
+            capture.write(&received.data)?;
+        }
+
+        if let Some(pcapng) = self.pcapng_writer.as_mut() {
+            pcapng.write(
+                received.timestamp,
+                received.source,
+                self.local_addr,
+                &received.data,
+            )?;
+        }
+
+        if let Some(exporter) = self.exporter.as_mut() {
+            match parse_packet_fuzz(&received.data) {
+                Ok(packet) => exporter.update(packet)?,
+                Err(_) => self.errors += 1,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Name of the files of a session, e.g. `Silverstone-Circuit_Race_20200712-140312`.
+fn session_name(track: &str, session_type: &str) -> String {
+    format!(
+        "{}_{}_{}",
+        slug(track),
+        slug(session_type),
+        current_timestamp()
+    )
+}
+
+/// `telemetry.csv` becomes `telemetry_<session>.csv`.
+fn session_path(path: &Path, session: &str) -> PathBuf {
+    let mut name = OsString::from(path.file_stem().unwrap_or_default());
+    name.push("_");
+    name.push(session);
+    if let Some(extension) = path.extension() {
+        name.push(".");
+        name.push(extension);
+    }
+
+    path.with_file_name(name)
+}
+
+/// Words of a name joined with dashes, for use in file names.
+fn slug(name: &str) -> String {
+    name.split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// Current UTC date and time as `yyyymmdd-hhmmss`.
+fn current_timestamp() -> String {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+
+    let days = (secs / 86400) as i64;
+    let seconds = secs % 86400;
+
+    // Civil date from days since the epoch (Howard Hinnant's algorithm).
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!(
+        "{:04}{:02}{:02}-{:02}{:02}{:02}",
+        year,
+        month,
+        day,
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
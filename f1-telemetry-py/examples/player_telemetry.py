@@ -0,0 +1,16 @@
+"""Print the speed and gear of the player's car.
+
+Build and install the module in a virtualenv with `maturin develop` first.
+"""
+
+import f1_telemetry
+
+stream = f1_telemetry.Stream("0.0.0.0:20777")
+
+for packet in stream:
+    if packet["type"] != "car_telemetry":
+        continue
+
+    player = packet["header"]["player_car_index"]
+    telemetry = packet["data"]["cars"][player]
+    print(f"{telemetry['speed']:3d} km/h  gear {telemetry['gear']}  {telemetry['engine_rpm']} rpm")
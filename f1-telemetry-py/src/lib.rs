@@ -0,0 +1,369 @@
+//! Python bindings of the packet parser and of the UDP stream, for analysts
+//! working in Python.
+//!
+//! Packets are returned as dicts with the `header` of the packet, its `type` and
+//! either its `data` or, for the packets without a dedicated representation, its
+//! `raw` bytes. As in the other bindings, enumerated values are the names of the
+//! Rust variants (e.g. `"Soft"`, `"Melbourne"`).
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use pyo3::exceptions::{PyRuntimeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::{PyBytes, PyDict, PyList};
+
+use f1_telemetry::packet::car_status::PacketCarStatusData;
+use f1_telemetry::packet::car_telemetry::PacketCarTelemetryData;
+use f1_telemetry::packet::encode::encode_packet;
+use f1_telemetry::packet::event::PacketEventData;
+use f1_telemetry::packet::generic::WheelData;
+use f1_telemetry::packet::header::PacketHeader;
+use f1_telemetry::packet::lap::PacketLapData;
+use f1_telemetry::packet::motion::PacketMotionData;
+use f1_telemetry::packet::participants::PacketParticipantsData;
+use f1_telemetry::packet::session::PacketSessionData;
+use f1_telemetry::packet::{parse_packet_fuzz, Packet, UnpackError};
+use f1_telemetry::{Stream, StreamError};
+
+/// How long a read blocks before checking for a timeout or a KeyboardInterrupt.
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+macro_rules! dict {
+    ($py:expr, { $($key:literal: $value:expr),* $(,)? }) => {{
+        let dict = PyDict::new($py);
+        $(dict.set_item($key, $value)?;)*
+        dict
+    }};
+}
+
+#[pymodule]
+#[pyo3(name = "f1_telemetry")]
+fn python_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(parse_packet, m)?)?;
+    m.add_class::<PyStream>()?;
+
+    Ok(())
+}
+
+/// Parse a datagram of the game, raising a ValueError when it is not a valid
+/// packet.
+#[pyfunction]
+fn parse_packet(py: Python<'_>, data: &[u8]) -> PyResult<PyObject> {
+    let packet = parse_packet_fuzz(data).map_err(unpack_error)?;
+
+    Ok(packet_dict(py, &packet)?.into())
+}
+
+/// Stream of the packets sent by the game, iterating over them as dicts.
+#[pyclass(name = "Stream")]
+struct PyStream {
+    stream: Mutex<Stream>,
+}
+
+#[pymethods]
+impl PyStream {
+    /// Listen on `addr`. In lenient mode, packets that cannot be parsed are
+    /// returned as `raw` packets instead of raising errors.
+    #[new]
+    #[pyo3(signature = (addr = "0.0.0.0:20777", lenient = false))]
+    fn new(addr: &str, lenient: bool) -> PyResult<PyStream> {
+        let mut stream = Stream::new(addr)?;
+        stream.set_lenient(lenient);
+
+        // Block for a while rather than spin, the GIL is released meanwhile.
+        stream.socket().set_nonblocking(false)?;
+        stream.socket().set_read_timeout(Some(POLL_INTERVAL))?;
+
+        Ok(PyStream {
+            stream: Mutex::new(stream),
+        })
+    }
+
+    /// Wait for the next packet, at most `timeout` seconds if given. Returns
+    /// None on timeout.
+    #[pyo3(signature = (timeout = None))]
+    fn recv(&self, py: Python<'_>, timeout: Option<f64>) -> PyResult<Option<PyObject>> {
+        let deadline = timeout.map(|t| Instant::now() + Duration::from_secs_f64(t.max(0.0)));
+
+        loop {
+            py.check_signals()?;
+
+            let result = py.allow_threads(|| self.stream.lock().unwrap().next());
+            match result {
+                Ok(Some(packet)) => return Ok(Some(packet_dict(py, &packet)?.into())),
+                Ok(None) => {
+                    if deadline.is_some_and(|d| Instant::now() >= d) {
+                        return Ok(None);
+                    }
+                }
+                Err(e) => return Err(stream_error(e)),
+            }
+        }
+    }
+
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__(&self, py: Python<'_>) -> PyResult<Option<PyObject>> {
+        self.recv(py, None)
+    }
+}
+
+fn unpack_error(e: UnpackError) -> PyErr {
+    PyValueError::new_err(e.0)
+}
+
+fn stream_error(e: StreamError) -> PyErr {
+    match e {
+        StreamError::Io(e) => e.into(),
+        StreamError::Truncated(len) => {
+            PyValueError::new_err(format!("Datagram truncated at {} bytes", len))
+        }
+        StreamError::Unpack(e) => unpack_error(e),
+        StreamError::Desync(d) => PyRuntimeError::new_err(d.to_string()),
+    }
+}
+
+fn packet_dict<'py>(py: Python<'py>, packet: &Packet) -> PyResult<Bound<'py, PyDict>> {
+    let (header, packet_type, data) = match packet {
+        Packet::Motion(p) => (p.header(), "motion", Some(motion(py, p)?)),
+        Packet::Session(p) => (p.header(), "session", Some(session(py, p)?)),
+        Packet::Lap(p) => (p.header(), "lap", Some(lap(py, p)?)),
+        Packet::Event(p) => (p.header(), "event", Some(event(py, p)?)),
+        Packet::Participants(p) => (p.header(), "participants", Some(participants(py, p)?)),
+        Packet::CarTelemetry(p) => (p.header(), "car_telemetry", Some(car_telemetry(py, p)?)),
+        Packet::CarStatus(p) => (p.header(), "car_status", Some(car_status(py, p)?)),
+        Packet::CarSetups(p) => (p.header(), "car_setups", None),
+        Packet::FinalClassification(p) => (p.header(), "final_classification", None),
+        Packet::LobbyInfo(p) => (p.header(), "lobby_info", None),
+        Packet::CarDamage(p) => (p.header(), "car_damage", None),
+        Packet::TyreSets(p) => (p.header(), "tyre_sets", None),
+        Packet::MotionEx(p) => (p.header(), "motion_ex", None),
+        Packet::TimeTrial(p) => (p.header(), "time_trial", None),
+        // Unparsed datagrams have no header to mirror, it is part of the data.
+        Packet::Raw(p) => {
+            return Ok(dict!(py, {
+                "type": "raw",
+                "raw": PyBytes::new(py, p.data()),
+            }));
+        }
+    };
+
+    let dict = dict!(py, {
+        "header": header_dict(py, header)?,
+        "type": packet_type,
+    });
+
+    match data {
+        Some(data) => dict.set_item("data", data)?,
+        None => dict.set_item("raw", PyBytes::new(py, &encode_packet(packet)))?,
+    }
+
+    Ok(dict)
+}
+
+fn header_dict<'py>(py: Python<'py>, header: &PacketHeader) -> PyResult<Bound<'py, PyDict>> {
+    Ok(dict!(py, {
+        "packet_format": header.packet_format(),
+        "game_major_version": header.game_major_version(),
+        "game_minor_version": header.game_minor_version(),
+        "packet_version": header.packet_version(),
+        "packet_id": header.packet_id(),
+        "session_uid": header.session_uid(),
+        "session_time": header.session_time(),
+        "frame_identifier": header.frame_identifier(),
+        "player_car_index": header.player_car_index(),
+        "secondary_player_car_index": header.secondary_player_car_index(),
+    }))
+}
+
+fn name<T: std::fmt::Debug>(value: T) -> String {
+    format!("{:?}", value)
+}
+
+fn list<'py, T, F>(py: Python<'py>, items: &[T], f: F) -> PyResult<Bound<'py, PyList>>
+where
+    F: Fn(&T) -> PyResult<Bound<'py, PyDict>>,
+{
+    let items = items.iter().map(f).collect::<PyResult<Vec<_>>>()?;
+
+    PyList::new(py, items)
+}
+
+fn wheels<T: for<'py> IntoPyObject<'py> + Copy>(
+    py: Python<'_>,
+    data: WheelData<T>,
+) -> PyResult<Bound<'_, PyDict>> {
+    Ok(dict!(py, {
+        "rear_left": data.rear_left(),
+        "rear_right": data.rear_right(),
+        "front_left": data.front_left(),
+        "front_right": data.front_right(),
+    }))
+}
+
+fn motion<'py>(py: Python<'py>, p: &PacketMotionData) -> PyResult<Bound<'py, PyDict>> {
+    let cars = list(py, p.motion_data(), |m| {
+        Ok(dict!(py, {
+            "world_position_x": m.world_position_x(),
+            "world_position_y": m.world_position_y(),
+            "world_position_z": m.world_position_z(),
+            "world_velocity_x": m.world_velocity_x(),
+            "world_velocity_y": m.world_velocity_y(),
+            "world_velocity_z": m.world_velocity_z(),
+            "g_force_lateral": m.g_force_lateral(),
+            "g_force_longitudinal": m.g_force_longitudinal(),
+            "g_force_vertical": m.g_force_vertical(),
+            "yaw": m.yaw(),
+            "pitch": m.pitch(),
+            "roll": m.roll(),
+        }))
+    })?;
+
+    Ok(dict!(py, { "cars": cars }))
+}
+
+fn session<'py>(py: Python<'py>, p: &PacketSessionData) -> PyResult<Bound<'py, PyDict>> {
+    Ok(dict!(py, {
+        "weather": name(p.weather()),
+        "track_temperature": p.track_temperature(),
+        "air_temperature": p.air_temperature(),
+        "total_laps": p.total_laps(),
+        "track_length": p.track_length(),
+        "session_type": name(p.session_type()),
+        "track": name(p.track()),
+        "session_time_left": p.session_time_left(),
+        "session_duration": p.session_duration(),
+        "pit_speed_limit": p.pit_speed_limit(),
+        "safety_car_status": name(p.safety_car_status()),
+        "network_game": p.network_game(),
+    }))
+}
+
+fn lap<'py>(py: Python<'py>, p: &PacketLapData) -> PyResult<Bound<'py, PyDict>> {
+    let cars = list(py, p.lap_data(), |l| {
+        Ok(dict!(py, {
+            "last_lap_time": l.last_lap_time(),
+            "current_lap_time": l.current_lap_time(),
+            "best_lap_time": l.best_lap_time(),
+            "sector_1_time": l.sector_1_time(),
+            "sector_2_time": l.sector_2_time(),
+            "lap_distance": l.lap_distance(),
+            "total_distance": l.total_distance(),
+            "safety_car_delta": l.safety_car_delta(),
+            "car_position": l.car_position(),
+            "current_lap_num": l.current_lap_num(),
+            "pit_status": name(l.pit_status()),
+            "sector": l.sector(),
+            "current_lap_invalid": l.current_lap_invalid(),
+            "penalties": l.penalties(),
+            "grid_position": l.grid_position(),
+            "driver_status": name(l.driver_status()),
+            "result_status": name(l.result_status()),
+        }))
+    })?;
+
+    Ok(dict!(py, { "cars": cars }))
+}
+
+fn event<'py>(py: Python<'py>, p: &PacketEventData) -> PyResult<Bound<'py, PyDict>> {
+    let penalty = match p.penalty() {
+        Some(penalty) => Some(dict!(py, {
+            "penalty_type": name(penalty.penalty_type()),
+            "infringement_type": name(penalty.infringement_type()),
+            "vehicle_idx": penalty.vehicle_idx(),
+            "other_vehicle_idx": penalty.other_vehicle_idx(),
+            "time": penalty.time(),
+            "lap_num": penalty.lap_num(),
+            "places_gained": penalty.places_gained(),
+        })),
+        None => None,
+    };
+
+    Ok(dict!(py, {
+        "code": p.event().code(),
+        "vehicle_idx": p.vehicle_idx(),
+        "lap_time": p.lap_time(),
+        "penalty": penalty,
+        "speed": p.speed(),
+    }))
+}
+
+fn participants<'py>(py: Python<'py>, p: &PacketParticipantsData) -> PyResult<Bound<'py, PyDict>> {
+    let participants = list(py, p.participants(), |participant| {
+        Ok(dict!(py, {
+            "ai_controlled": participant.ai_controlled(),
+            "driver": name(participant.driver()),
+            "team": name(participant.team()),
+            "race_number": participant.race_number(),
+            "nationality": name(participant.nationality()),
+            "name": participant.name().as_str(),
+        }))
+    })?;
+
+    Ok(dict!(py, {
+        "num_active_cars": p.num_active_cars(),
+        "participants": participants,
+    }))
+}
+
+fn car_telemetry<'py>(py: Python<'py>, p: &PacketCarTelemetryData) -> PyResult<Bound<'py, PyDict>> {
+    let cars = list(py, p.car_telemetry_data(), |t| {
+        Ok(dict!(py, {
+            "speed": t.speed(),
+            "throttle": t.throttle(),
+            "steer": t.steer(),
+            "brake": t.brake(),
+            "clutch": t.clutch(),
+            "gear": t.gear(),
+            "engine_rpm": t.engine_rpm(),
+            "drs": t.drs(),
+            "rev_lights_percent": t.rev_lights_percent(),
+            "brakes_temperature": wheels(py, t.brakes_temperature())?,
+            "tyres_surface_temperature": wheels(py, t.tyres_surface_temperature())?,
+            "tyres_inner_temperature": wheels(py, t.tyres_inner_temperature())?,
+            "engine_temperature": t.engine_temperature(),
+            "tyre_pressures": wheels(py, t.tyre_pressures())?,
+        }))
+    })?;
+
+    Ok(dict!(py, {
+        "cars": cars,
+        "button_status": p.button_status(),
+        "mfd_panel": p.mfd_panel().map(name),
+        "suggested_gear": p.suggested_gear(),
+    }))
+}
+
+fn car_status<'py>(py: Python<'py>, p: &PacketCarStatusData) -> PyResult<Bound<'py, PyDict>> {
+    let cars = list(py, p.car_status_data(), |s| {
+        Ok(dict!(py, {
+            "traction_control": name(s.traction_control()),
+            "anti_lock_brakes": s.anti_lock_brakes(),
+            "fuel_mix": name(s.fuel_mix()),
+            "front_brake_bias": s.front_brake_bias(),
+            "pit_limiter": s.pit_limiter(),
+            "fuel_in_tank": s.fuel_in_tank(),
+            "fuel_capacity": s.fuel_capacity(),
+            "fuel_remaining_laps": s.fuel_remaining_laps(),
+            "max_rpm": s.max_rpm(),
+            "idle_rpm": s.idle_rpm(),
+            "max_gears": s.max_gears(),
+            "drs_allowed": name(s.drs_allowed()),
+            "tyres_wear": wheels(py, s.tyres_wear())?,
+            "actual_tyre_compound": name(s.actual_tyre_compound()),
+            "visual_tyre_compound": name(s.visual_tyre_compound()),
+            "tyres_damage": wheels(py, s.tyres_damage())?,
+            "vehicle_fia_flags": name(s.vehicle_fia_flags()),
+            "ers_store_energy": s.ers_store_energy(),
+            "ers_deploy_mode": name(s.ers_deploy_mode()),
+            "ers_harvested_this_lap_mguk": s.ers_harvested_this_lap_mguk(),
+            "ers_harvested_this_lap_mguh": s.ers_harvested_this_lap_mguh(),
+            "ers_deployed_this_lap": s.ers_deployed_this_lap(),
+        }))
+    })?;
+
+    Ok(dict!(py, { "cars": cars }))
+}
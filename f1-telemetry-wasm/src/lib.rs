@@ -0,0 +1,284 @@
+//! JavaScript bindings of the packet parser, for browser apps receiving the
+//! datagrams of the game over a WebSocket or a WebRTC data channel.
+//!
+//! Packets are returned as plain JavaScript objects with camelCase keys. As in
+//! the gRPC service of f1-telemetry, enumerated values are the names of the Rust
+//! variants (e.g. `"Soft"`, `"Melbourne"`), and the packets without a dedicated
+//! representation only carry their datagram in `raw`.
+
+use js_sys::{Array, Object, Reflect, Uint8Array};
+use wasm_bindgen::prelude::*;
+
+use f1_telemetry::packet::car_status::PacketCarStatusData;
+use f1_telemetry::packet::car_telemetry::PacketCarTelemetryData;
+use f1_telemetry::packet::encode::encode_packet;
+use f1_telemetry::packet::event::PacketEventData;
+use f1_telemetry::packet::generic::WheelData;
+use f1_telemetry::packet::header::PacketHeader;
+use f1_telemetry::packet::lap::PacketLapData;
+use f1_telemetry::packet::motion::PacketMotionData;
+use f1_telemetry::packet::participants::PacketParticipantsData;
+use f1_telemetry::packet::session::PacketSessionData;
+use f1_telemetry::packet::{parse_packet_fuzz, Packet};
+
+/// Parse a datagram of the game.
+///
+/// Returns an object with the `header` of the packet, its `type` and either its
+/// `data` or, for the other packet types, its `raw` bytes. Throws an `Error`
+/// when the datagram is not a valid packet.
+#[wasm_bindgen]
+pub fn parse_packet_js(data: &[u8]) -> Result<JsValue, JsValue> {
+    let packet = parse_packet_fuzz(data).map_err(|e| js_sys::Error::new(&e.0))?;
+
+    Ok(packet_object(&packet).into())
+}
+
+/// Builder of JavaScript objects.
+struct JsObject(Object);
+
+impl JsObject {
+    fn new() -> JsObject {
+        JsObject(Object::new())
+    }
+
+    fn with<V: Into<JsValue>>(self, key: &str, value: V) -> JsObject {
+        Reflect::set(&self.0, &JsValue::from_str(key), &value.into()).unwrap();
+        self
+    }
+}
+
+impl From<JsObject> for JsValue {
+    fn from(object: JsObject) -> JsValue {
+        object.0.into()
+    }
+}
+
+fn packet_object(packet: &Packet) -> JsObject {
+    let (header, packet_type, data) = match packet {
+        Packet::Motion(p) => (p.header(), "motion", Some(motion(p))),
+        Packet::Session(p) => (p.header(), "session", Some(session(p))),
+        Packet::Lap(p) => (p.header(), "lap", Some(lap(p))),
+        Packet::Event(p) => (p.header(), "event", Some(event(p))),
+        Packet::Participants(p) => (p.header(), "participants", Some(participants(p))),
+        Packet::CarTelemetry(p) => (p.header(), "carTelemetry", Some(car_telemetry(p))),
+        Packet::CarStatus(p) => (p.header(), "carStatus", Some(car_status(p))),
+        Packet::CarSetups(p) => (p.header(), "carSetups", None),
+        Packet::FinalClassification(p) => (p.header(), "finalClassification", None),
+        Packet::LobbyInfo(p) => (p.header(), "lobbyInfo", None),
+        Packet::CarDamage(p) => (p.header(), "carDamage", None),
+        Packet::TyreSets(p) => (p.header(), "tyreSets", None),
+        Packet::MotionEx(p) => (p.header(), "motionEx", None),
+        Packet::TimeTrial(p) => (p.header(), "timeTrial", None),
+        // Unparsed datagrams have no header to mirror, it is part of the data.
+        Packet::Raw(p) => {
+            return JsObject::new()
+                .with("type", "raw")
+                .with("raw", Uint8Array::from(&p.data()[..]));
+        }
+    };
+
+    let object = JsObject::new()
+        .with("header", header_object(header))
+        .with("type", packet_type);
+
+    match data {
+        Some(data) => object.with("data", data),
+        None => object.with("raw", Uint8Array::from(&encode_packet(packet)[..])),
+    }
+}
+
+fn header_object(header: &PacketHeader) -> JsObject {
+    JsObject::new()
+        .with("packetFormat", header.packet_format())
+        .with("gameMajorVersion", header.game_major_version())
+        .with("gameMinorVersion", header.game_minor_version())
+        .with("packetVersion", header.packet_version())
+        .with("packetId", header.packet_id())
+        // Session UIDs do not fit in a JavaScript number.
+        .with("sessionUid", format!("{:016x}", header.session_uid()))
+        .with("sessionTime", header.session_time())
+        .with("frameIdentifier", header.frame_identifier())
+        .with("playerCarIndex", header.player_car_index())
+        .with(
+            "secondaryPlayerCarIndex",
+            optional(header.secondary_player_car_index()),
+        )
+}
+
+fn name<T: std::fmt::Debug>(value: T) -> String {
+    format!("{:?}", value)
+}
+
+fn optional<T: Into<JsValue>>(value: Option<T>) -> JsValue {
+    value.map_or(JsValue::NULL, Into::into)
+}
+
+fn array<T, F: Fn(&T) -> JsObject>(items: &[T], f: F) -> Array {
+    items.iter().map(|item| JsValue::from(f(item))).collect()
+}
+
+fn wheels<T: Into<JsValue> + Copy>(data: WheelData<T>) -> JsObject {
+    JsObject::new()
+        .with("rearLeft", data.rear_left())
+        .with("rearRight", data.rear_right())
+        .with("frontLeft", data.front_left())
+        .with("frontRight", data.front_right())
+}
+
+fn motion(p: &PacketMotionData) -> JsObject {
+    let cars = array(p.motion_data(), |m| {
+        JsObject::new()
+            .with("worldPositionX", m.world_position_x())
+            .with("worldPositionY", m.world_position_y())
+            .with("worldPositionZ", m.world_position_z())
+            .with("worldVelocityX", m.world_velocity_x())
+            .with("worldVelocityY", m.world_velocity_y())
+            .with("worldVelocityZ", m.world_velocity_z())
+            .with("gForceLateral", m.g_force_lateral())
+            .with("gForceLongitudinal", m.g_force_longitudinal())
+            .with("gForceVertical", m.g_force_vertical())
+            .with("yaw", m.yaw())
+            .with("pitch", m.pitch())
+            .with("roll", m.roll())
+    });
+
+    JsObject::new().with("cars", cars)
+}
+
+fn session(p: &PacketSessionData) -> JsObject {
+    JsObject::new()
+        .with("weather", name(p.weather()))
+        .with("trackTemperature", p.track_temperature())
+        .with("airTemperature", p.air_temperature())
+        .with("totalLaps", p.total_laps())
+        .with("trackLength", p.track_length())
+        .with("sessionType", name(p.session_type()))
+        .with("track", name(p.track()))
+        .with("sessionTimeLeft", p.session_time_left())
+        .with("sessionDuration", p.session_duration())
+        .with("pitSpeedLimit", p.pit_speed_limit())
+        .with("safetyCarStatus", name(p.safety_car_status()))
+        .with("networkGame", p.network_game())
+}
+
+fn lap(p: &PacketLapData) -> JsObject {
+    let cars = array(p.lap_data(), |l| {
+        JsObject::new()
+            .with("lastLapTime", l.last_lap_time())
+            .with("currentLapTime", l.current_lap_time())
+            .with("bestLapTime", l.best_lap_time())
+            .with("sector1Time", l.sector_1_time())
+            .with("sector2Time", l.sector_2_time())
+            .with("lapDistance", l.lap_distance())
+            .with("totalDistance", l.total_distance())
+            .with("safetyCarDelta", l.safety_car_delta())
+            .with("carPosition", l.car_position())
+            .with("currentLapNum", l.current_lap_num())
+            .with("pitStatus", name(l.pit_status()))
+            .with("sector", l.sector())
+            .with("currentLapInvalid", l.current_lap_invalid())
+            .with("penalties", l.penalties())
+            .with("gridPosition", l.grid_position())
+            .with("driverStatus", name(l.driver_status()))
+            .with("resultStatus", name(l.result_status()))
+    });
+
+    JsObject::new().with("cars", cars)
+}
+
+fn event(p: &PacketEventData) -> JsObject {
+    let penalty = p.penalty().map(|penalty| {
+        JsValue::from(
+            JsObject::new()
+                .with("penaltyType", name(penalty.penalty_type()))
+                .with("infringementType", name(penalty.infringement_type()))
+                .with("vehicleIdx", penalty.vehicle_idx())
+                .with("otherVehicleIdx", optional(penalty.other_vehicle_idx()))
+                .with("time", optional(penalty.time()))
+                .with("lapNum", penalty.lap_num())
+                .with("placesGained", penalty.places_gained()),
+        )
+    });
+
+    JsObject::new()
+        .with("code", p.event().code())
+        .with("vehicleIdx", optional(p.vehicle_idx()))
+        .with("lapTime", optional(p.lap_time()))
+        .with("penalty", optional(penalty))
+        .with("speed", optional(p.speed()))
+}
+
+fn participants(p: &PacketParticipantsData) -> JsObject {
+    let participants = array(p.participants(), |participant| {
+        JsObject::new()
+            .with("aiControlled", participant.ai_controlled())
+            .with("driver", name(participant.driver()))
+            .with("team", name(participant.team()))
+            .with("raceNumber", participant.race_number())
+            .with("nationality", name(participant.nationality()))
+            .with("name", participant.name().as_str())
+    });
+
+    JsObject::new()
+        .with("numActiveCars", p.num_active_cars())
+        .with("participants", participants)
+}
+
+fn car_telemetry(p: &PacketCarTelemetryData) -> JsObject {
+    let cars = array(p.car_telemetry_data(), |t| {
+        JsObject::new()
+            .with("speed", t.speed())
+            .with("throttle", t.throttle())
+            .with("steer", t.steer())
+            .with("brake", t.brake())
+            .with("clutch", t.clutch())
+            .with("gear", t.gear())
+            .with("engineRpm", t.engine_rpm())
+            .with("drs", t.drs())
+            .with("revLightsPercent", t.rev_lights_percent())
+            .with("brakesTemperature", wheels(t.brakes_temperature()))
+            .with(
+                "tyresSurfaceTemperature",
+                wheels(t.tyres_surface_temperature()),
+            )
+            .with("tyresInnerTemperature", wheels(t.tyres_inner_temperature()))
+            .with("engineTemperature", t.engine_temperature())
+            .with("tyrePressures", wheels(t.tyre_pressures()))
+    });
+
+    JsObject::new()
+        .with("cars", cars)
+        .with("buttonStatus", optional(p.button_status()))
+        .with("mfdPanel", optional(p.mfd_panel().map(name)))
+        .with("suggestedGear", optional(p.suggested_gear()))
+}
+
+fn car_status(p: &PacketCarStatusData) -> JsObject {
+    let cars = array(p.car_status_data(), |s| {
+        JsObject::new()
+            .with("tractionControl", name(s.traction_control()))
+            .with("antiLockBrakes", s.anti_lock_brakes())
+            .with("fuelMix", name(s.fuel_mix()))
+            .with("frontBrakeBias", s.front_brake_bias())
+            .with("pitLimiter", s.pit_limiter())
+            .with("fuelInTank", s.fuel_in_tank())
+            .with("fuelCapacity", s.fuel_capacity())
+            .with("fuelRemainingLaps", s.fuel_remaining_laps())
+            .with("maxRpm", s.max_rpm())
+            .with("idleRpm", s.idle_rpm())
+            .with("maxGears", s.max_gears())
+            .with("drsAllowed", name(s.drs_allowed()))
+            .with("tyresWear", wheels(s.tyres_wear()))
+            .with("actualTyreCompound", name(s.actual_tyre_compound()))
+            .with("visualTyreCompound", name(s.visual_tyre_compound()))
+            .with("tyresDamage", wheels(s.tyres_damage()))
+            .with("vehicleFiaFlags", name(s.vehicle_fia_flags()))
+            .with("ersStoreEnergy", s.ers_store_energy())
+            .with("ersDeployMode", name(s.ers_deploy_mode()))
+            .with("ersHarvestedThisLapMguk", s.ers_harvested_this_lap_mguk())
+            .with("ersHarvestedThisLapMguh", s.ers_harvested_this_lap_mguh())
+            .with("ersDeployedThisLap", s.ers_deployed_this_lap())
+    });
+
+    JsObject::new().with("cars", cars)
+}
@@ -0,0 +1,122 @@
+//! Parsing throughput of every packet type and format.
+//!
+//! Usage: `cargo bench -p f1-telemetry --bench parse`
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use f1_telemetry::packet::car_telemetry::PacketCarTelemetryData;
+use f1_telemetry::packet::header::PacketHeader;
+use f1_telemetry::packet::motion::PacketMotionData;
+use f1_telemetry::packet::{header_size, parse_packet_fuzz, PacketType, MAX_PACKET_SIZE};
+use std::io::Cursor;
+
+const PACKET_FORMATS: [u16; 6] = [2019, 2020, 2021, 2022, 2023, 2024];
+
+const PACKET_TYPES: [(&str, PacketType); 13] = [
+    ("motion", PacketType::Motion),
+    ("session", PacketType::Session),
+    ("lap_data", PacketType::LapData),
+    ("participants", PacketType::Participants),
+    ("car_setups", PacketType::CarSetups),
+    ("car_telemetry", PacketType::CarTelemetry),
+    ("car_status", PacketType::CarStatus),
+    ("final_classification", PacketType::FinalClassification),
+    ("lobby_info", PacketType::LobbyInfo),
+    ("car_damage", PacketType::CarDamage),
+    ("tyre_sets", PacketType::TyreSets),
+    ("motion_ex", PacketType::MotionEx),
+    ("time_trial", PacketType::TimeTrial),
+];
+
+/// Bytes of a packet with the given header fields, and a body filled with
+/// printable characters so that names are valid.
+fn synthetic(packet_format: u16, packet_id: u8) -> Vec<u8> {
+    let mut buf: Vec<u8> = (0..MAX_PACKET_SIZE)
+        .map(|i| 0x20 + (i % 95) as u8)
+        .collect();
+
+    buf[..2].copy_from_slice(&packet_format.to_le_bytes());
+    // The game year moved the packet id one byte further in F1 2023
+    if packet_format >= 2023 {
+        buf[6] = packet_id;
+    } else {
+        buf[5] = packet_id;
+    }
+    // Keep the player car index in range, without a secondary player
+    let header_size = header_size(packet_format);
+    if packet_format >= 2020 {
+        buf[header_size - 2] = 0;
+        buf[header_size - 1] = 255;
+    } else {
+        buf[header_size - 1] = 0;
+    }
+
+    buf
+}
+
+/// Parse every packet type with the public entry point, which validates the
+/// packet length.
+fn parse_packet(c: &mut Criterion) {
+    for &(name, packet_type) in PACKET_TYPES.iter() {
+        let mut group = c.benchmark_group(format!("parse_packet/{}", name));
+
+        for &packet_format in PACKET_FORMATS.iter() {
+            let buf = synthetic(packet_format, packet_type.id());
+            // Packet types that do not exist in this format
+            if parse_packet_fuzz(&buf).is_err() {
+                continue;
+            }
+
+            group.throughput(Throughput::Bytes(buf.len() as u64));
+            group.bench_with_input(
+                BenchmarkId::from_parameter(packet_format),
+                &buf,
+                |b, buf| b.iter(|| parse_packet_fuzz(black_box(buf)).unwrap()),
+            );
+        }
+
+        group.finish();
+    }
+}
+
+/// Parse the bodies of the high frequency packets straight from the bytes, as a
+/// bulk replay does.
+fn parse_body(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parse_body/motion");
+    for &packet_format in PACKET_FORMATS.iter() {
+        let buf = synthetic(packet_format, PacketType::Motion.id());
+        group.throughput(Throughput::Bytes(buf.len() as u64));
+        group.bench_with_input(
+            BenchmarkId::from_parameter(packet_format),
+            &buf,
+            |b, buf| {
+                b.iter(|| {
+                    let mut cursor = Cursor::new(black_box(&buf[..]));
+                    let header = PacketHeader::new(&mut cursor);
+                    PacketMotionData::new(&mut cursor, header).unwrap()
+                })
+            },
+        );
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("parse_body/car_telemetry");
+    for &packet_format in PACKET_FORMATS.iter() {
+        let buf = synthetic(packet_format, PacketType::CarTelemetry.id());
+        group.throughput(Throughput::Bytes(buf.len() as u64));
+        group.bench_with_input(
+            BenchmarkId::from_parameter(packet_format),
+            &buf,
+            |b, buf| {
+                b.iter(|| {
+                    let mut cursor = Cursor::new(black_box(&buf[..]));
+                    let header = PacketHeader::new(&mut cursor);
+                    PacketCarTelemetryData::new(&mut cursor, header).unwrap()
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, parse_packet, parse_body);
+criterion_main!(benches);
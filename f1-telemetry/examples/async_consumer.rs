@@ -0,0 +1,118 @@
+//! Consume the telemetry stream from async code, comparing each lap to the best one.
+//!
+//! The stream is non-blocking, so it can be polled from a future. This example uses
+//! a minimal executor to stay free of dependencies; the `NextPacket` future works
+//! the same with any runtime.
+//!
+//! Usage: `cargo run --example async_consumer [address]`
+
+use f1_telemetry::analysis::ghost::Ghost;
+use f1_telemetry::packet::{Packet, PacketFilter, PacketType};
+use f1_telemetry::{Stream, StreamError};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread::{self, Thread};
+use std::time::Duration;
+
+/// Resolves to the next packet of the stream.
+struct NextPacket<'a> {
+    stream: &'a Stream,
+}
+
+impl<'a> Future for NextPacket<'a> {
+    type Output = Result<Packet, StreamError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        match self.stream.next() {
+            Ok(Some(p)) => Poll::Ready(Ok(p)),
+            Ok(None) => {
+                // No reactor here: ask to be polled again.
+                cx.waker().wake_by_ref();
+                Poll::Pending
+            }
+            Err(e) => Poll::Ready(Err(e)),
+        }
+    }
+}
+
+fn next_packet(stream: &Stream) -> NextPacket<'_> {
+    NextPacket { stream }
+}
+
+async fn consume(stream: Stream) -> Result<(), StreamError> {
+    let mut ghost = Ghost::new();
+    let mut best_lap_time = None;
+
+    loop {
+        let packet = match next_packet(&stream).await {
+            Ok(p) => p,
+            Err(StreamError::Truncated(_)) => continue,
+            Err(e) => return Err(e),
+        };
+
+        match packet {
+            Packet::Lap(ld) => {
+                ghost.update_lap(&ld);
+
+                if ghost.best_lap_time() != best_lap_time {
+                    best_lap_time = ghost.best_lap_time();
+                    println!("New best lap: {:.3}s", best_lap_time.unwrap_or(0.0));
+                }
+            }
+            Packet::CarTelemetry(t) => {
+                ghost.update_telemetry(&t);
+
+                if let Some(c) = ghost.comparison() {
+                    println!(
+                        "delta: {:+.3}s, speed: {:+} km/h",
+                        c.delta(),
+                        c.speed_delta()
+                    );
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+/// Run a future to completion on the current thread.
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = Box::pin(future);
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut cx = Context::from_waker(&waker);
+
+    loop {
+        match future.as_mut().poll(&mut cx) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => thread::park_timeout(Duration::from_millis(5)),
+        }
+    }
+}
+
+fn main() {
+    let addr = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| String::from("0.0.0.0:20777"));
+
+    let mut stream = Stream::new(addr).expect("Unable to bind socket");
+    stream.set_filter(
+        PacketFilter::none()
+            .with(PacketType::LapData)
+            .with(PacketType::CarTelemetry),
+    );
+    println!("Listening on {}", stream.socket().local_addr().unwrap());
+
+    if let Err(e) = block_on(consume(stream)) {
+        eprintln!("Stream error: {:?}", e);
+    }
+}
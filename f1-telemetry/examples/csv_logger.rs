@@ -0,0 +1,91 @@
+//! Log the player's telemetry to a CSV file, one row per Car Telemetry packet.
+//!
+//! Usage: `cargo run --example csv_logger <output.csv> [address]`
+
+use f1_telemetry::packet::header::Player;
+use f1_telemetry::packet::{Packet, PacketFilter, PacketType};
+use f1_telemetry::session::SessionTracker;
+use f1_telemetry::watchdog::Watchdog;
+use f1_telemetry::{Stream, StreamError};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::thread::sleep;
+use std::time::Duration;
+
+const HEADER: &str =
+    "session_uid,session_time,frame,lap,lap_distance,speed,throttle,brake,steer,gear,engine_rpm,drs";
+
+fn main() -> std::io::Result<()> {
+    let mut args = std::env::args().skip(1);
+    let path = args
+        .next()
+        .expect("Usage: csv_logger <output.csv> [address]");
+    let addr = args.next().unwrap_or_else(|| String::from("0.0.0.0:20777"));
+
+    let mut stream = Stream::new(addr)?;
+    stream.set_filter(
+        PacketFilter::none()
+            .with(PacketType::LapData)
+            .with(PacketType::CarTelemetry),
+    );
+    stream.set_watchdog(Watchdog::new(10));
+
+    let mut out = BufWriter::new(File::create(&path)?);
+    writeln!(out, "{}", HEADER)?;
+
+    println!(
+        "Logging packets from {} to {}",
+        stream.socket().local_addr()?,
+        path
+    );
+
+    let mut tracker = SessionTracker::new();
+
+    loop {
+        let packet = match stream.next() {
+            Ok(Some(p)) => p,
+            Ok(None) => {
+                out.flush()?;
+                sleep(Duration::from_millis(5));
+                continue;
+            }
+            Err(StreamError::Truncated(_)) => continue,
+            Err(StreamError::Desync(d)) => {
+                eprintln!("{}", d);
+                continue;
+            }
+            Err(e) => {
+                eprintln!("Skipping packet: {:?}", e);
+                continue;
+            }
+        };
+
+        tracker.update(&packet);
+
+        if let Packet::CarTelemetry(t) = packet {
+            let header = t.header();
+            let telemetry = match t.player_car_telemetry() {
+                Some(telemetry) => telemetry,
+                None => continue,
+            };
+            let position = tracker.focus(Player::Primary).and_then(|f| f.position());
+
+            writeln!(
+                out,
+                "{},{:.3},{},{},{:.1},{},{:.3},{:.3},{:.3},{},{},{}",
+                header.session_id(),
+                header.session_time(),
+                header.frame_identifier(),
+                position.map(|p| p.lap()).unwrap_or(0),
+                position.map(|p| p.lap_distance()).unwrap_or(0.0),
+                telemetry.speed(),
+                telemetry.throttle(),
+                telemetry.brake(),
+                telemetry.steer(),
+                telemetry.gear(),
+                telemetry.engine_rpm(),
+                telemetry.drs() as u8,
+            )?;
+        }
+    }
+}
@@ -0,0 +1,130 @@
+//! Generate a seed corpus for the `parse_packet` fuzz target, with one packet of
+//! every type and packet format serialized by the encoder.
+//!
+//! Usage: `cargo run --example fuzz_corpus [directory]`
+
+use f1_telemetry::packet::encode::encode_packet;
+use f1_telemetry::packet::event::Event;
+use f1_telemetry::packet::{header_size, parse_packet_fuzz, PacketType, MAX_PACKET_SIZE};
+use std::fs;
+use std::path::Path;
+
+const PACKET_FORMATS: [u16; 6] = [2019, 2020, 2021, 2022, 2023, 2024];
+
+const PACKET_TYPES: [PacketType; 14] = [
+    PacketType::Motion,
+    PacketType::Session,
+    PacketType::LapData,
+    PacketType::Event,
+    PacketType::Participants,
+    PacketType::CarSetups,
+    PacketType::CarTelemetry,
+    PacketType::CarStatus,
+    PacketType::FinalClassification,
+    PacketType::LobbyInfo,
+    PacketType::CarDamage,
+    PacketType::TyreSets,
+    PacketType::MotionEx,
+    PacketType::TimeTrial,
+];
+
+const EVENTS: [Event; 11] = [
+    Event::SessionStarted,
+    Event::SessionEnded,
+    Event::FastestLap,
+    Event::Retirement,
+    Event::DRSEnabled,
+    Event::DRSDisabled,
+    Event::TeamMateInPits,
+    Event::ChequeredFlag,
+    Event::RaceWinner,
+    Event::Penalty,
+    Event::SpeedTrap,
+];
+
+/// Bytes of a packet with the given header fields, and a body either zeroed or
+/// filled with printable characters so that names are valid.
+fn synthetic(packet_format: u16, packet_id: u8, filled: bool) -> Vec<u8> {
+    let mut buf = vec![0u8; MAX_PACKET_SIZE];
+    if filled {
+        for (i, b) in buf.iter_mut().enumerate() {
+            *b = 0x20 + (i % 95) as u8;
+        }
+    }
+
+    buf[..2].copy_from_slice(&packet_format.to_le_bytes());
+    // The game year moved the packet id one byte further in F1 2023
+    if packet_format >= 2023 {
+        buf[6] = packet_id;
+    } else {
+        buf[5] = packet_id;
+    }
+    // Keep the player car index in range, without a secondary player
+    let header_size = header_size(packet_format);
+    if packet_format >= 2020 {
+        buf[header_size - 2] = 0;
+        buf[header_size - 1] = 255;
+    } else {
+        buf[header_size - 1] = 0;
+    }
+
+    buf
+}
+
+/// Write the encoding of a packet parsed from `buf`, returning whether it parsed.
+fn write_seed(dir: &Path, name: &str, buf: &[u8]) -> std::io::Result<bool> {
+    match parse_packet_fuzz(buf) {
+        Ok(packet) => {
+            fs::write(dir.join(name), encode_packet(&packet))?;
+            Ok(true)
+        }
+        Err(e) => {
+            eprintln!("Skipping {}: {}", name, e.0);
+            Ok(false)
+        }
+    }
+}
+
+fn main() -> std::io::Result<()> {
+    let dir = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| String::from("fuzz/corpus/parse_packet"));
+    let dir = Path::new(&dir);
+    fs::create_dir_all(dir)?;
+
+    let mut count = 0;
+    for &packet_format in PACKET_FORMATS.iter() {
+        for &packet_type in PACKET_TYPES.iter() {
+            for &filled in [false, true].iter() {
+                let mut buf = synthetic(packet_format, packet_type.id(), filled);
+
+                if packet_type == PacketType::Event {
+                    // The event code comes right after the header
+                    let offset = header_size(packet_format);
+                    for event in EVENTS.iter() {
+                        buf[offset..offset + 4].copy_from_slice(event.code().as_bytes());
+                        let name = format!(
+                            "{}-{}-{}-{}",
+                            packet_format,
+                            packet_type.id(),
+                            event.code(),
+                            filled
+                        );
+                        if write_seed(dir, &name, &buf)? {
+                            count += 1;
+                        }
+                    }
+                } else {
+                    let name = format!("{}-{}-{}", packet_format, packet_type.id(), filled);
+                    if write_seed(dir, &name, &buf)? {
+                        count += 1;
+                    }
+                }
+            }
+        }
+    }
+
+    println!("Wrote {} seeds to {}", count, dir.display());
+
+    Ok(())
+}
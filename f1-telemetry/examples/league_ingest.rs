@@ -0,0 +1,155 @@
+//! Collect the results of a league race and write them as JSON when the final
+//! classification is received, along with the penalties and speed traps of the
+//! session.
+//!
+//! Usage: `cargo run --example league_ingest <results directory> [address]`
+
+use f1_telemetry::packet::final_classification::{
+    FinalClassificationData, PacketFinalClassificationData,
+};
+use f1_telemetry::packet::lap::ResultStatus;
+use f1_telemetry::packet::participants::PacketParticipantsData;
+use f1_telemetry::packet::{Packet, PacketFilter, PacketType};
+use f1_telemetry::session::{PenaltyLedger, SpeedTrapBoard};
+use f1_telemetry::{Stream, StreamError};
+use std::fs;
+use std::path::Path;
+use std::thread::sleep;
+use std::time::Duration;
+
+fn main() -> std::io::Result<()> {
+    let mut args = std::env::args().skip(1);
+    let dir = args
+        .next()
+        .expect("Usage: league_ingest <results directory> [address]");
+    let addr = args.next().unwrap_or_else(|| String::from("0.0.0.0:20777"));
+
+    fs::create_dir_all(&dir)?;
+
+    let mut stream = Stream::new(addr)?;
+    stream.set_filter(
+        PacketFilter::none()
+            .with(PacketType::Event)
+            .with(PacketType::Participants)
+            .with(PacketType::FinalClassification),
+    );
+    println!(
+        "Waiting for results on {}, writing them to {}",
+        stream.socket().local_addr()?,
+        dir
+    );
+
+    let mut participants: Option<PacketParticipantsData> = None;
+    let mut penalties = PenaltyLedger::new();
+    let mut speed_traps = SpeedTrapBoard::new();
+
+    loop {
+        let packet = match stream.next() {
+            Ok(Some(p)) => p,
+            Ok(None) => {
+                sleep(Duration::from_millis(20));
+                continue;
+            }
+            Err(StreamError::Truncated(_)) => continue,
+            Err(e) => {
+                eprintln!("Skipping packet: {:?}", e);
+                continue;
+            }
+        };
+
+        match packet {
+            Packet::Event(e) => {
+                penalties.update(&e);
+                speed_traps.update(&e);
+            }
+            Packet::Participants(p) => participants = Some(p),
+            Packet::FinalClassification(fc) => {
+                let path = Path::new(&dir).join(format!("{}.json", fc.header().session_id()));
+                let json = results_json(&fc, &participants, &penalties, &speed_traps);
+                fs::write(&path, json)?;
+
+                println!("Results written to {}", path.display());
+
+                penalties.reset();
+                speed_traps.reset();
+            }
+            _ => {}
+        }
+    }
+}
+
+fn results_json(
+    fc: &PacketFinalClassificationData,
+    participants: &Option<PacketParticipantsData>,
+    penalties: &PenaltyLedger,
+    speed_traps: &SpeedTrapBoard,
+) -> String {
+    // Keep the vehicle index of every classified driver, to look up their
+    // participant data and session records.
+    let mut classified: Vec<(usize, &FinalClassificationData)> = fc
+        .classification_data()
+        .iter()
+        .enumerate()
+        .filter(|(_, c)| {
+            c.position() > 0
+                && c.result_status() != ResultStatus::Invalid
+                && c.result_status() != ResultStatus::Inactive
+        })
+        .collect();
+    classified.sort_by_key(|(_, c)| c.position());
+
+    let results: Vec<String> = classified
+        .into_iter()
+        .map(|(i, c)| {
+            let participant = participants.as_ref().and_then(|p| p.participants().get(i));
+
+            format!(
+                "{{\"position\":{},\"name\":{},\"team\":{},\"grid\":{},\"points\":{},\
+                 \"laps\":{},\"pit_stops\":{},\"status\":\"{:?}\",\"best_lap\":{:.3},\
+                 \"race_time\":{:.3},\"penalty_time\":{},\"penalties\":{},\
+                 \"speed_trap\":{}}}",
+                c.position(),
+                participant
+                    .map(|p| json_string(p.name()))
+                    .unwrap_or_else(|| String::from("null")),
+                participant
+                    .map(|p| json_string(&format!("{:?}", p.team())))
+                    .unwrap_or_else(|| String::from("null")),
+                c.grid_position(),
+                c.points(),
+                c.num_laps(),
+                c.num_pit_stops(),
+                c.result_status(),
+                c.best_lap_time(),
+                c.total_race_time(),
+                c.penalties_time(),
+                penalties.penalties_for(i as u8).len(),
+                speed_traps
+                    .best_for(i as u8)
+                    .map(|s| format!("{:.1}", s))
+                    .unwrap_or_else(|| String::from("null")),
+            )
+        })
+        .collect();
+
+    format!(
+        "{{\"session_uid\":\"{}\",\"results\":[{}]}}\n",
+        fc.header().session_id(),
+        results.join(",")
+    )
+}
+
+fn json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            c if c.is_control() => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
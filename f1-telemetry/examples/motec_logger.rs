@@ -0,0 +1,66 @@
+//! Write every lap driven by the player to a MoTeC i2 log file.
+//!
+//! Usage: `cargo run --example motec_logger [output directory] [address]`
+
+use f1_telemetry::export::motec::MotecLog;
+use f1_telemetry::packet::{PacketFilter, PacketType};
+use f1_telemetry::transform::downsample::{resample, ResampleGrid};
+use f1_telemetry::transform::lap_buffer::TelemetryLapBuffer;
+use f1_telemetry::{Stream, StreamError};
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::PathBuf;
+use std::thread::sleep;
+use std::time::Duration;
+
+const FREQUENCY: u16 = 20;
+
+fn main() -> std::io::Result<()> {
+    let mut args = std::env::args().skip(1);
+    let dir = PathBuf::from(args.next().unwrap_or_else(|| String::from(".")));
+    let addr = args.next().unwrap_or_else(|| String::from("0.0.0.0:20777"));
+
+    let mut stream = Stream::new(addr)?;
+    stream.set_filter(
+        PacketFilter::none()
+            .with(PacketType::Motion)
+            .with(PacketType::LapData)
+            .with(PacketType::CarTelemetry),
+    );
+
+    println!(
+        "Logging laps from {} to {}",
+        stream.socket().local_addr()?,
+        dir.display()
+    );
+
+    let mut laps = TelemetryLapBuffer::new().with_max_laps(1);
+
+    loop {
+        let packet = match stream.next() {
+            Ok(Some(p)) => p,
+            Ok(None) => {
+                sleep(Duration::from_millis(5));
+                continue;
+            }
+            Err(StreamError::Truncated(_)) => continue,
+            Err(e) => {
+                eprintln!("Skipping packet: {:?}", e);
+                continue;
+            }
+        };
+
+        let lap = match laps.update(&packet) {
+            Some(lap) => lap,
+            None => continue,
+        };
+
+        let samples = resample(lap.samples(), ResampleGrid::rate(FREQUENCY as f32));
+        let log =
+            MotecLog::from_samples(&samples, FREQUENCY).with_comment(&format!("Lap {}", lap.lap()));
+
+        let path = dir.join(format!("lap_{:03}.ld", lap.lap()));
+        log.write(&mut BufWriter::new(File::create(&path)?))?;
+        println!("Wrote lap {} to {}", lap.lap(), path.display());
+    }
+}
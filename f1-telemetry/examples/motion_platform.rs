@@ -0,0 +1,65 @@
+//! Forward the player's motion to a motion platform.
+//!
+//! Every Motion packet is turned into a platform command sent to the platform
+//! controller, as six little-endian `f32` (heave, surge, sway, pitch, roll, yaw)
+//! between -1 and 1, or as text with `--text`.
+//!
+//! Usage: `cargo run --example motion_platform <platform address> [address] [--text]`
+
+use f1_telemetry::output::motion_platform::{
+    MotionCueing, PlatformConfig, PlatformFormat, PlatformSender,
+};
+use f1_telemetry::packet::{Packet, PacketFilter, PacketType};
+use f1_telemetry::{Stream, StreamError};
+use std::thread::sleep;
+use std::time::Duration;
+
+fn main() -> std::io::Result<()> {
+    let text = std::env::args().any(|a| a == "--text");
+    let mut args = std::env::args().skip(1).filter(|a| a != "--text");
+    let platform = args
+        .next()
+        .expect("Usage: motion_platform <platform address> [address] [--text]");
+    let addr = args.next().unwrap_or_else(|| String::from("0.0.0.0:20777"));
+
+    let mut stream = Stream::new(addr)?;
+    stream.set_filter(PacketFilter::none().with(PacketType::Motion));
+
+    let format = if text {
+        PlatformFormat::Text
+    } else {
+        PlatformFormat::Binary
+    };
+    let sender = PlatformSender::new(&platform, format)?;
+    let mut cueing = MotionCueing::new(PlatformConfig::default());
+
+    println!(
+        "Forwarding motion from {} to {}",
+        stream.socket().local_addr()?,
+        platform
+    );
+
+    loop {
+        let motion = match stream.next() {
+            Ok(Some(Packet::Motion(m))) => m,
+            Ok(Some(_)) | Err(StreamError::Truncated(_)) => continue,
+            Ok(None) => {
+                sleep(Duration::from_millis(1));
+                continue;
+            }
+            Err(e) => {
+                eprintln!("Skipping packet: {:?}", e);
+                continue;
+            }
+        };
+
+        let command = match cueing.update_packet(&motion) {
+            Some(command) => command,
+            None => continue,
+        };
+
+        if let Err(e) = sender.send(&command) {
+            eprintln!("Unable to reach the platform: {}", e);
+        }
+    }
+}
@@ -0,0 +1,20 @@
+//! Send a synthetic race to a client listening on the local machine.
+//!
+//! Usage: `cargo run --example simulator [address] [speed]`
+
+use f1_telemetry::simulator::Simulator;
+
+fn main() -> std::io::Result<()> {
+    let mut args = std::env::args().skip(1);
+    let addr = args
+        .next()
+        .unwrap_or_else(|| String::from("127.0.0.1:20777"));
+    let speed = args.next().and_then(|s| s.parse().ok()).unwrap_or(1.0);
+
+    let mut simulator = Simulator::new();
+    println!("Sending a {}x race to {}", speed, addr);
+    simulator.run(addr, speed)?;
+    println!("Race finished after {:.0}s", simulator.session_time());
+
+    Ok(())
+}
@@ -0,0 +1,273 @@
+//! Serve the players' live data to browser overlays (e.g. an OBS browser source)
+//! over WebSocket.
+//!
+//! Every connected client receives a JSON message per Car Telemetry packet, with
+//! the focused context of each player and the delta to the best lap.
+//!
+//! Usage: `cargo run --example websocket_overlay [websocket address] [address]`
+
+use f1_telemetry::analysis::ghost::Ghost;
+use f1_telemetry::packet::header::Player;
+use f1_telemetry::packet::{Packet, PacketFilter, PacketType};
+use f1_telemetry::session::{FocusContext, SessionTracker};
+use f1_telemetry::{Stream, StreamError};
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+const WEBSOCKET_GUID: &str = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+
+type Clients = Arc<Mutex<Vec<TcpStream>>>;
+
+fn main() -> std::io::Result<()> {
+    let mut args = std::env::args().skip(1);
+    let ws_addr = args
+        .next()
+        .unwrap_or_else(|| String::from("127.0.0.1:9001"));
+    let addr = args.next().unwrap_or_else(|| String::from("0.0.0.0:20777"));
+
+    let mut stream = Stream::new(addr)?;
+    stream.set_filter(
+        PacketFilter::none()
+            .with(PacketType::Motion)
+            .with(PacketType::LapData)
+            .with(PacketType::CarTelemetry),
+    );
+
+    let listener = TcpListener::bind(&ws_addr)?;
+    println!(
+        "Serving packets from {} on ws://{}",
+        stream.socket().local_addr()?,
+        ws_addr
+    );
+
+    let clients: Clients = Arc::new(Mutex::new(Vec::new()));
+    {
+        let clients = Arc::clone(&clients);
+        thread::spawn(move || accept(listener, clients));
+    }
+
+    let mut tracker = SessionTracker::new();
+    let mut ghost = Ghost::new();
+
+    loop {
+        let packet = match stream.next() {
+            Ok(Some(p)) => p,
+            Ok(None) => {
+                thread::sleep(Duration::from_millis(5));
+                continue;
+            }
+            Err(StreamError::Truncated(_)) => continue,
+            Err(e) => {
+                eprintln!("Skipping packet: {:?}", e);
+                continue;
+            }
+        };
+
+        tracker.update(&packet);
+
+        match &packet {
+            Packet::Lap(ld) => ghost.update_lap(ld),
+            Packet::CarTelemetry(t) => {
+                ghost.update_telemetry(t);
+
+                let players: Vec<String> = tracker
+                    .focused()
+                    .into_iter()
+                    .map(|(player, focus)| player_json(player, focus))
+                    .collect();
+                let message = format!(
+                    "{{\"session_time\":{:.3},\"delta\":{},\"players\":[{}]}}",
+                    t.header().session_time(),
+                    ghost
+                        .delta()
+                        .map(|d| format!("{:.3}", d))
+                        .unwrap_or_else(|| String::from("null")),
+                    players.join(","),
+                );
+
+                broadcast(&clients, &message);
+            }
+            _ => {}
+        }
+    }
+}
+
+fn player_json(player: Player, focus: &FocusContext) -> String {
+    let position = focus.position();
+
+    format!(
+        "{{\"player\":\"{:?}\",\"car_index\":{},\"position\":{},\"lap\":{},\"speed\":{},\"gear\":{}}}",
+        player,
+        focus.car_index(),
+        position.map(|p| p.position()).unwrap_or(0),
+        position.map(|p| p.lap()).unwrap_or(0),
+        focus.speed().unwrap_or(0),
+        focus.gear().unwrap_or(0),
+    )
+}
+
+fn accept(listener: TcpListener, clients: Clients) {
+    for conn in listener.incoming() {
+        let conn = match conn {
+            Ok(conn) => conn,
+            Err(_) => continue,
+        };
+
+        match handshake(conn) {
+            Ok(conn) => clients.lock().unwrap().push(conn),
+            Err(e) => eprintln!("WebSocket handshake failed: {}", e),
+        }
+    }
+}
+
+/// Answer the HTTP upgrade request of a WebSocket client.
+fn handshake(mut conn: TcpStream) -> std::io::Result<TcpStream> {
+    let mut key = None;
+
+    {
+        let mut reader = BufReader::new(&conn);
+        loop {
+            let mut line = String::new();
+            if reader.read_line(&mut line)? == 0 || line.trim().is_empty() {
+                break;
+            }
+
+            let mut parts = line.splitn(2, ':');
+            let name = parts.next().unwrap_or("").trim();
+            if name.eq_ignore_ascii_case("Sec-WebSocket-Key") {
+                key = parts.next().map(|k| k.trim().to_string());
+            }
+        }
+    }
+
+    let key = key.ok_or_else(|| {
+        std::io::Error::new(std::io::ErrorKind::InvalidData, "Missing Sec-WebSocket-Key")
+    })?;
+    let accept = base64(&sha1(format!("{}{}", key, WEBSOCKET_GUID).as_bytes()));
+
+    write!(
+        conn,
+        "HTTP/1.1 101 Switching Protocols\r\n\
+         Upgrade: websocket\r\n\
+         Connection: Upgrade\r\n\
+         Sec-WebSocket-Accept: {}\r\n\r\n",
+        accept
+    )?;
+
+    Ok(conn)
+}
+
+/// Send a text frame to every client, dropping the ones that went away.
+fn broadcast(clients: &Clients, message: &str) {
+    let payload = message.as_bytes();
+
+    let mut frame = vec![0x81];
+    match payload.len() {
+        len if len < 126 => frame.push(len as u8),
+        len if len <= 0xffff => {
+            frame.push(126);
+            frame.extend_from_slice(&(len as u16).to_be_bytes());
+        }
+        len => {
+            frame.push(127);
+            frame.extend_from_slice(&(len as u64).to_be_bytes());
+        }
+    }
+    frame.extend_from_slice(payload);
+
+    clients
+        .lock()
+        .unwrap()
+        .retain(|mut c| c.write_all(&frame).is_ok());
+}
+
+fn sha1(data: &[u8]) -> [u8; 20] {
+    let mut h: [u32; 5] = [
+        0x6745_2301,
+        0xefcd_ab89,
+        0x98ba_dcfe,
+        0x1032_5476,
+        0xc3d2_e1f0,
+    ];
+
+    let mut message = data.to_vec();
+    message.push(0x80);
+    while message.len() % 64 != 56 {
+        message.push(0);
+    }
+    message.extend_from_slice(&((data.len() as u64) * 8).to_be_bytes());
+
+    for chunk in message.chunks(64) {
+        let mut w = [0u32; 80];
+        for i in 0..16 {
+            w[i] = u32::from_be_bytes([
+                chunk[i * 4],
+                chunk[i * 4 + 1],
+                chunk[i * 4 + 2],
+                chunk[i * 4 + 3],
+            ]);
+        }
+        for i in 16..80 {
+            w[i] = (w[i - 3] ^ w[i - 8] ^ w[i - 14] ^ w[i - 16]).rotate_left(1);
+        }
+
+        let [mut a, mut b, mut c, mut d, mut e] = h;
+        for (i, wi) in w.iter().enumerate() {
+            let (f, k) = match i {
+                0..=19 => ((b & c) | (!b & d), 0x5a82_7999),
+                20..=39 => (b ^ c ^ d, 0x6ed9_eba1),
+                40..=59 => ((b & c) | (b & d) | (c & d), 0x8f1b_bcdc),
+                _ => (b ^ c ^ d, 0xca62_c1d6),
+            };
+            let temp = a
+                .rotate_left(5)
+                .wrapping_add(f)
+                .wrapping_add(e)
+                .wrapping_add(k)
+                .wrapping_add(*wi);
+            e = d;
+            d = c;
+            c = b.rotate_left(30);
+            b = a;
+            a = temp;
+        }
+
+        h[0] = h[0].wrapping_add(a);
+        h[1] = h[1].wrapping_add(b);
+        h[2] = h[2].wrapping_add(c);
+        h[3] = h[3].wrapping_add(d);
+        h[4] = h[4].wrapping_add(e);
+    }
+
+    let mut digest = [0u8; 20];
+    for (i, v) in h.iter().enumerate() {
+        digest[i * 4..i * 4 + 4].copy_from_slice(&v.to_be_bytes());
+    }
+    digest
+}
+
+fn base64(data: &[u8]) -> String {
+    const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut out = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let b = [
+            chunk[0],
+            *chunk.get(1).unwrap_or(&0),
+            *chunk.get(2).unwrap_or(&0),
+        ];
+        let n = (u32::from(b[0]) << 16) | (u32::from(b[1]) << 8) | u32::from(b[2]);
+
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
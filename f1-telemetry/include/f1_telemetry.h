@@ -0,0 +1,177 @@
+#ifndef F1_TELEMETRY_H
+#define F1_TELEMETRY_H
+
+/* Generated with cbindgen from src/ffi.rs, do not edit. */
+
+#include <stdbool.h>
+#include <stdint.h>
+#include <stddef.h>
+
+/**
+ * Parsed packet, owned by the caller until passed to `f1t_packet_free`.
+ */
+typedef struct F1tPacket F1tPacket;
+
+/**
+ * Header of a packet.
+ *
+ * `secondary_player_car_index` is 255 outside of split-screen sessions.
+ */
+typedef struct {
+  uint16_t packet_format;
+  uint8_t game_major_version;
+  uint8_t game_minor_version;
+  uint8_t packet_version;
+  uint8_t packet_id;
+  uint64_t session_uid;
+  float session_time;
+  uint32_t frame_identifier;
+  uint8_t player_car_index;
+  uint8_t secondary_player_car_index;
+} F1tHeader;
+
+/**
+ * Motion data of a car, from a Motion packet.
+ */
+typedef struct {
+  float world_position_x;
+  float world_position_y;
+  float world_position_z;
+  float world_velocity_x;
+  float world_velocity_y;
+  float world_velocity_z;
+  float g_force_lateral;
+  float g_force_longitudinal;
+  float g_force_vertical;
+  float yaw;
+  float pitch;
+  float roll;
+} F1tCarMotion;
+
+/**
+ * Lap data of a car, from a Lap Data packet.
+ */
+typedef struct {
+  float last_lap_time;
+  float current_lap_time;
+  float best_lap_time;
+  float sector_1_time;
+  float sector_2_time;
+  float lap_distance;
+  float total_distance;
+  uint8_t car_position;
+  uint8_t current_lap_num;
+  uint8_t pit_status;
+  uint8_t sector;
+  bool current_lap_invalid;
+  uint8_t penalties;
+  uint8_t grid_position;
+  uint8_t driver_status;
+  uint8_t result_status;
+} F1tLapData;
+
+/**
+ * Telemetry of a car, from a Car Telemetry packet.
+ *
+ * The wheel arrays are in the order RL, RR, FL, FR.
+ */
+typedef struct {
+  uint16_t speed;
+  float throttle;
+  float steer;
+  float brake;
+  uint8_t clutch;
+  int8_t gear;
+  uint16_t engine_rpm;
+  bool drs;
+  uint8_t rev_lights_percent;
+  uint16_t brakes_temperature[4];
+  uint16_t tyres_surface_temperature[4];
+  uint16_t tyres_inner_temperature[4];
+  uint16_t engine_temperature;
+  float tyre_pressures[4];
+} F1tCarTelemetry;
+
+/**
+ * Parse the `len` bytes of a datagram at `data`.
+ *
+ * Returns null if the datagram is not a valid packet.
+ *
+ * # Safety
+ *
+ * `data` must point to `len` readable bytes.
+ */
+F1tPacket *f1t_parse_packet(const uint8_t *data, size_t len);
+
+/**
+ * Release a packet returned by `f1t_parse_packet`. Null is ignored.
+ *
+ * # Safety
+ *
+ * `packet` must come from `f1t_parse_packet` and not have been freed.
+ */
+void f1t_packet_free(F1tPacket *packet);
+
+/**
+ * Id of the packet, or -1 for datagrams kept unparsed.
+ *
+ * # Safety
+ *
+ * `packet` must be a live packet returned by `f1t_parse_packet`.
+ */
+int f1t_packet_id(const F1tPacket *packet);
+
+/**
+ * Copy the header of the packet to `out`, returning false for datagrams kept
+ * unparsed.
+ *
+ * # Safety
+ *
+ * `packet` must be a live packet returned by `f1t_parse_packet`, and `out`
+ * must be valid for writes.
+ */
+bool f1t_packet_header(const F1tPacket *packet, F1tHeader *out);
+
+/**
+ * Number of cars in the per-car arrays of the packet: 20, or 22 from F1 2020.
+ *
+ * # Safety
+ *
+ * `packet` must be a live packet returned by `f1t_parse_packet`.
+ */
+size_t f1t_packet_num_cars(const F1tPacket *packet);
+
+/**
+ * Copy the motion data of the car at `car_index` to `out`, returning false if
+ * the packet is not a Motion packet or the index is out of range.
+ *
+ * # Safety
+ *
+ * `packet` must be a live packet returned by `f1t_parse_packet`, and `out`
+ * must be valid for writes.
+ */
+bool f1t_packet_car_motion(const F1tPacket *packet, size_t car_index, F1tCarMotion *out);
+
+/**
+ * Copy the lap data of the car at `car_index` to `out`, returning false if
+ * the packet is not a Lap Data packet or the index is out of range.
+ *
+ * # Safety
+ *
+ * `packet` must be a live packet returned by `f1t_parse_packet`, and `out`
+ * must be valid for writes.
+ */
+bool f1t_packet_lap_data(const F1tPacket *packet, size_t car_index, F1tLapData *out);
+
+/**
+ * Copy the telemetry of the car at `car_index` to `out`, returning false if
+ * the packet is not a Car Telemetry packet or the index is out of range.
+ *
+ * # Safety
+ *
+ * `packet` must be a live packet returned by `f1t_parse_packet`, and `out`
+ * must be valid for writes.
+ */
+bool f1t_packet_car_telemetry(const F1tPacket *packet, size_t car_index, F1tCarTelemetry *out);
+
+#endif /* F1_TELEMETRY_H */
@@ -0,0 +1,14 @@
+pub mod compare;
+pub mod consistency;
+pub mod corners;
+pub mod events;
+pub mod gaps;
+pub mod ghost;
+pub mod lap_diff;
+pub mod progress;
+pub mod track_map;
+pub mod undercut;
+
+pub use compare::compare;
+pub use consistency::consistency;
+pub use lap_diff::lap_diff;
@@ -0,0 +1,134 @@
+use getset::CopyGetters;
+
+use crate::session::{LapTracker, PitTracker, SpeedTrapBoard};
+
+/// Head-to-head comparison of two drivers.
+///
+/// All deltas are the value of driver A minus the value of driver B, so a
+/// negative time delta means that driver A is faster.
+///
+/// ## Specification
+/// ```text
+/// driver_a:          Vehicle index of driver A
+/// driver_b:          Vehicle index of driver B
+/// best_lap_delta:    Delta between the best laps in seconds
+/// last_lap_delta:    Delta between the last laps in seconds
+/// sector_deltas:     Delta between the best times in each sector in seconds
+/// speed_trap_delta:  Delta between the best speed trap speeds in kilometres per hour
+/// tyre_age_a:        Laps driven on the current tyres by driver A
+/// tyre_age_b:        Laps driven on the current tyres by driver B
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, CopyGetters)]
+#[getset(get_copy = "pub")]
+pub struct DriverComparison {
+    driver_a: u8,
+    driver_b: u8,
+    best_lap_delta: Option<f32>,
+    last_lap_delta: Option<f32>,
+    sector_deltas: [Option<f32>; 3],
+    speed_trap_delta: Option<f32>,
+    tyre_age_a: Option<u8>,
+    tyre_age_b: Option<u8>,
+}
+
+impl DriverComparison {
+    /// Difference of tyre age in laps, positive when driver A's tyres are older.
+    pub fn tyre_age_delta(&self) -> Option<i16> {
+        Some(self.tyre_age_a? as i16 - self.tyre_age_b? as i16)
+    }
+}
+
+/// Compares drivers using the data recorded by the session trackers.
+///
+/// Speed traps and tyre ages are only compared when the corresponding trackers
+/// are given.
+#[derive(Debug, Clone, Copy)]
+pub struct Comparator<'a> {
+    laps: &'a LapTracker,
+    speed_traps: Option<&'a SpeedTrapBoard>,
+    pit_stops: Option<&'a PitTracker>,
+}
+
+impl<'a> Comparator<'a> {
+    pub fn new(laps: &'a LapTracker) -> Comparator<'a> {
+        Comparator {
+            laps,
+            speed_traps: None,
+            pit_stops: None,
+        }
+    }
+
+    pub fn with_speed_traps(mut self, speed_traps: &'a SpeedTrapBoard) -> Comparator<'a> {
+        self.speed_traps = Some(speed_traps);
+        self
+    }
+
+    pub fn with_pit_stops(mut self, pit_stops: &'a PitTracker) -> Comparator<'a> {
+        self.pit_stops = Some(pit_stops);
+        self
+    }
+
+    pub fn compare(&self, driver_a: u8, driver_b: u8) -> DriverComparison {
+        let laps = self.laps;
+
+        let best_lap_delta = delta(
+            laps.best_lap(driver_a).map(|l| l.lap_time()),
+            laps.best_lap(driver_b).map(|l| l.lap_time()),
+        );
+        let last_lap_delta = delta(
+            laps.last_lap(driver_a).map(|l| l.lap_time()),
+            laps.last_lap(driver_b).map(|l| l.lap_time()),
+        );
+
+        let sectors_a = laps.best_sectors(driver_a);
+        let sectors_b = laps.best_sectors(driver_b);
+        let mut sector_deltas = [None; 3];
+        for (i, d) in sector_deltas.iter_mut().enumerate() {
+            *d = delta(sectors_a[i], sectors_b[i]);
+        }
+
+        let speed_trap_delta = self
+            .speed_traps
+            .and_then(|st| delta(st.best_for(driver_a), st.best_for(driver_b)));
+
+        DriverComparison {
+            driver_a,
+            driver_b,
+            best_lap_delta,
+            last_lap_delta,
+            sector_deltas,
+            speed_trap_delta,
+            tyre_age_a: self.tyre_age(driver_a),
+            tyre_age_b: self.tyre_age(driver_b),
+        }
+    }
+
+    /// Laps since the last pit stop, or since the start of the session. Every
+    /// stop is assumed to change the tyres.
+    fn tyre_age(&self, driver: u8) -> Option<u8> {
+        let pit_stops = self.pit_stops?;
+        let current_lap = self.laps.current_lap(driver)?;
+
+        let fitted_lap = pit_stops
+            .stops_for(driver)
+            .iter()
+            .rev()
+            .find(|s| s.exit_time().is_some())
+            .map(|s| s.lap())
+            .unwrap_or(1);
+
+        Some(current_lap.saturating_sub(fitted_lap))
+    }
+}
+
+/// Compare two drivers with only their lap times, see [`Comparator`] to also
+/// compare speed traps and tyre ages.
+///
+/// [`Comparator`]: ./struct.Comparator.html
+pub fn compare(laps: &LapTracker, driver_a: u8, driver_b: u8) -> DriverComparison {
+    Comparator::new(laps).compare(driver_a, driver_b)
+}
+
+fn delta(a: Option<f32>, b: Option<f32>) -> Option<f32> {
+    Some(a? - b?)
+}
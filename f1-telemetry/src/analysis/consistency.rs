@@ -0,0 +1,248 @@
+use getset::CopyGetters;
+use std::collections::HashMap;
+
+use crate::analysis::corners::corners;
+use crate::session::{LapRecord, LapTracker, PitTracker};
+use crate::transform::lap_buffer::TelemetryLap;
+
+/// Laps slower than this ratio of the driver's best lap, e.g. behind a safety
+/// car or in traffic, are left out of the metrics.
+const MAX_LAP_TIME_RATIO: f32 = 1.07;
+/// Coefficient of variation of the lap or sector times scoring 0.
+const MAX_TIME_VARIATION: f32 = 0.02;
+/// Standard deviation of a braking point scoring 0, in metres.
+const MAX_BRAKING_DEVIATION: f32 = 20.0;
+/// Brake pressure from which the car is braking (0.0 to 1.0).
+const BRAKE_THRESHOLD: f32 = 0.2;
+/// Distance before a corner within which the braking point is searched, in metres.
+const BRAKING_ZONE: f32 = 250.0;
+
+/// Spread of the braking points of a driver for a corner.
+///
+/// ## Specification
+/// ```text
+/// corner:        Number of the corner, from 1 after the line
+/// laps:          Number of laps the driver braked for the corner on
+/// mean_distance: Mean distance around the lap of the braking point in metres
+/// std_dev:       Standard deviation of the braking point in metres
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, CopyGetters)]
+#[getset(get_copy = "pub")]
+pub struct BrakingConsistency {
+    corner: u8,
+    laps: usize,
+    mean_distance: f32,
+    std_dev: f32,
+}
+
+/// Consistency of a driver over the laps of a session.
+///
+/// ## Specification
+/// ```text
+/// driver:            Vehicle index of the driver
+/// laps:              Number of laps the metrics are computed over
+/// mean_lap_time:     Mean lap time in seconds
+/// lap_time_std_dev:  Standard deviation of the lap times in seconds
+/// sector_std_devs:   Standard deviation of the times in each sector in seconds
+/// braking_std_dev:   Mean standard deviation of the braking points over the
+///                    corners in metres, when the telemetry of the driver is given
+/// score:             Overall consistency, from 0 (erratic) to 100 (identical laps)
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, CopyGetters)]
+#[getset(get_copy = "pub")]
+pub struct DriverConsistency {
+    driver: u8,
+    laps: usize,
+    mean_lap_time: f32,
+    lap_time_std_dev: f32,
+    sector_std_devs: [f32; 3],
+    braking_std_dev: Option<f32>,
+    score: f32,
+}
+
+/// Measures how consistent drivers are from the laps recorded by the session
+/// trackers.
+///
+/// Only valid laps within 107% of the driver's best lap count. In and out laps
+/// are left out too when the pit stops are given, and the braking points are
+/// only measured for the drivers whose telemetry laps are given.
+#[derive(Debug, Clone)]
+pub struct ConsistencyAnalyzer<'a> {
+    laps: &'a LapTracker,
+    pit_stops: Option<&'a PitTracker>,
+    telemetry: HashMap<u8, Vec<&'a TelemetryLap>>,
+}
+
+impl<'a> ConsistencyAnalyzer<'a> {
+    pub fn new(laps: &'a LapTracker) -> ConsistencyAnalyzer<'a> {
+        ConsistencyAnalyzer {
+            laps,
+            pit_stops: None,
+            telemetry: HashMap::new(),
+        }
+    }
+
+    pub fn with_pit_stops(mut self, pit_stops: &'a PitTracker) -> ConsistencyAnalyzer<'a> {
+        self.pit_stops = Some(pit_stops);
+        self
+    }
+
+    /// Measure the braking points of a driver on these laps, e.g. the laps of
+    /// their current stint from a [`TelemetryLapBuffer`].
+    ///
+    /// [`TelemetryLapBuffer`]: ../../transform/lap_buffer/struct.TelemetryLapBuffer.html
+    pub fn with_telemetry<I>(mut self, driver: u8, laps: I) -> ConsistencyAnalyzer<'a>
+    where
+        I: IntoIterator<Item = &'a TelemetryLap>,
+    {
+        self.telemetry.insert(driver, laps.into_iter().collect());
+        self
+    }
+
+    /// Consistency of a driver, once they completed at least two laps that count.
+    pub fn analyze(&self, driver: u8) -> Option<DriverConsistency> {
+        let laps = self.counted_laps(driver);
+        if laps.len() < 2 {
+            return None;
+        }
+
+        let lap_times: Vec<f32> = laps.iter().map(|l| l.lap_time()).collect();
+        let (mean_lap_time, lap_time_std_dev) = mean_std_dev(&lap_times)?;
+
+        let mut sector_std_devs = [0.0; 3];
+        let mut sector_variation = 0.0;
+        for (i, std_dev) in sector_std_devs.iter_mut().enumerate() {
+            let times: Vec<f32> = laps.iter().map(|l| l.sector_times()[i]).collect();
+            let (mean, sd) = mean_std_dev(&times)?;
+            *std_dev = sd;
+            sector_variation += sd / mean / 3.0;
+        }
+
+        let braking_std_dev = self
+            .telemetry
+            .get(&driver)
+            .map(|laps| braking_consistency(laps.iter().copied()))
+            .filter(|corners| !corners.is_empty())
+            .map(|corners| corners.iter().map(|c| c.std_dev).sum::<f32>() / corners.len() as f32);
+
+        let mut scores = vec![
+            score(lap_time_std_dev / mean_lap_time, MAX_TIME_VARIATION),
+            score(sector_variation, MAX_TIME_VARIATION),
+        ];
+        if let Some(braking_std_dev) = braking_std_dev {
+            scores.push(score(braking_std_dev, MAX_BRAKING_DEVIATION));
+        }
+
+        Some(DriverConsistency {
+            driver,
+            laps: laps.len(),
+            mean_lap_time,
+            lap_time_std_dev,
+            sector_std_devs,
+            braking_std_dev,
+            score: scores.iter().sum::<f32>() / scores.len() as f32,
+        })
+    }
+
+    fn counted_laps(&self, driver: u8) -> Vec<LapRecord> {
+        let best = match self.laps.best_lap(driver) {
+            Some(best) => best.lap_time(),
+            None => return Vec::new(),
+        };
+
+        let pit_laps: Vec<u8> = self
+            .pit_stops
+            .map(|p| p.stops_for(driver))
+            .unwrap_or(&[])
+            .iter()
+            .flat_map(|s| [s.lap(), s.lap().saturating_add(1)])
+            .collect();
+
+        self.laps
+            .laps_for(driver)
+            .iter()
+            .filter(|l| l.valid() && l.lap_time() > 0.0)
+            .filter(|l| l.sector_times().iter().all(|t| *t > 0.0))
+            .filter(|l| l.lap_time() <= best * MAX_LAP_TIME_RATIO)
+            .filter(|l| !pit_laps.contains(&l.lap()))
+            .copied()
+            .collect()
+    }
+}
+
+/// Consistency of a driver with only their lap and sector times, see
+/// [`ConsistencyAnalyzer`] to also leave out the pit laps and measure the
+/// braking points.
+///
+/// [`ConsistencyAnalyzer`]: ./struct.ConsistencyAnalyzer.html
+pub fn consistency(laps: &LapTracker, driver: u8) -> Option<DriverConsistency> {
+    ConsistencyAnalyzer::new(laps).analyze(driver)
+}
+
+/// Spread of the braking points for each corner over the laps of a car.
+///
+/// The corners are found on the fastest lap, and the braking point of a lap is
+/// where the brake is first pressed in the 250 metres before the slowest point
+/// of the corner. Corners taken flat out are left out.
+pub fn braking_consistency<'a, I>(laps: I) -> Vec<BrakingConsistency>
+where
+    I: IntoIterator<Item = &'a TelemetryLap>,
+{
+    let laps: Vec<&TelemetryLap> = laps.into_iter().collect();
+    let reference = match laps
+        .iter()
+        .filter(|l| !l.samples().is_empty())
+        .min_by(|a, b| a.duration().total_cmp(&b.duration()))
+    {
+        Some(reference) => reference,
+        None => return Vec::new(),
+    };
+
+    corners(reference.samples())
+        .iter()
+        .filter_map(|corner| {
+            let apex = corner.min_speed_distance();
+            let points: Vec<f32> = laps
+                .iter()
+                .filter_map(|lap| {
+                    lap.samples()
+                        .iter()
+                        .filter(|s| s.lap_distance() >= apex - BRAKING_ZONE)
+                        .take_while(|s| s.lap_distance() <= apex)
+                        .find(|s| s.brake() >= BRAKE_THRESHOLD)
+                        .map(|s| s.lap_distance())
+                })
+                .collect();
+
+            if points.len() < 2 {
+                return None;
+            }
+
+            let (mean_distance, std_dev) = mean_std_dev(&points)?;
+            Some(BrakingConsistency {
+                corner: corner.number(),
+                laps: points.len(),
+                mean_distance,
+                std_dev,
+            })
+        })
+        .collect()
+}
+
+/// Mean and population standard deviation of the values.
+fn mean_std_dev(values: &[f32]) -> Option<(f32, f32)> {
+    if values.is_empty() {
+        return None;
+    }
+
+    let n = values.len() as f32;
+    let mean = values.iter().sum::<f32>() / n;
+    let variance = values.iter().map(|v| (v - mean) * (v - mean)).sum::<f32>() / n;
+
+    Some((mean, variance.sqrt()))
+}
+
+/// Score from 100 for no spread down to 0 for `max` or more.
+fn score(spread: f32, max: f32) -> f32 {
+    (100.0 * (1.0 - spread / max)).clamp(0.0, 100.0)
+}
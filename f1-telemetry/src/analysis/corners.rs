@@ -0,0 +1,217 @@
+use getset::CopyGetters;
+use std::f32::consts::PI;
+
+use crate::packet::generic::Vec3;
+use crate::transform::downsample::{resample, ResampleGrid};
+use crate::transform::sample::TelemetrySample;
+
+/// Distance between the points the lap is resampled on, in metres.
+const STEP: f32 = 5.0;
+/// Points on each side of a point used to measure the curvature of the track.
+const CURVATURE_WINDOW: usize = 2;
+/// Curvature above which the track is a corner, in radians per metre: a radius
+/// of 250 metres.
+const MIN_CURVATURE: f32 = 1.0 / 250.0;
+/// Steering above which the car is cornering, when the positions are missing.
+const MIN_STEER: f32 = 0.1;
+/// Straights shorter than this are part of the corners around them, in metres.
+const MIN_STRAIGHT_LENGTH: f32 = 30.0;
+/// Corners shorter than this are part of the straights around them, in metres.
+const MIN_CORNER_LENGTH: f32 = 20.0;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum SegmentKind {
+    Corner,
+    Straight,
+}
+
+/// A corner or a straight of the track, as driven on a lap.
+///
+/// ## Specification
+/// ```text
+/// kind:                Corner or straight
+/// number:              Number of the corner or straight, from 1 after the line
+/// start_distance:      Distance around the lap where the segment starts in metres
+/// end_distance:        Distance around the lap where the segment ends in metres
+/// entry_speed:         Speed at the start of the segment in kilometres per hour
+/// exit_speed:          Speed at the end of the segment in kilometres per hour
+/// min_speed:           Lowest speed in the segment in kilometres per hour
+/// min_speed_distance:  Distance around the lap of the lowest speed in metres
+/// gear:                Gear selected at the lowest speed (1-8, N=0, R=-1)
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, CopyGetters)]
+#[getset(get_copy = "pub")]
+pub struct TrackSegment {
+    kind: SegmentKind,
+    number: u8,
+    start_distance: f32,
+    end_distance: f32,
+    entry_speed: f32,
+    exit_speed: f32,
+    min_speed: f32,
+    min_speed_distance: f32,
+    gear: i8,
+}
+
+impl TrackSegment {
+    pub fn is_corner(&self) -> bool {
+        self.kind == SegmentKind::Corner
+    }
+
+    /// Length of the segment in metres.
+    pub fn length(&self) -> f32 {
+        self.end_distance - self.start_distance
+    }
+
+    /// Whether `lap_distance` is within the segment.
+    pub fn contains(&self, lap_distance: f32) -> bool {
+        lap_distance >= self.start_distance && lap_distance < self.end_distance
+    }
+}
+
+/// Split the samples of a lap into corners and straights, in the order they are
+/// driven.
+///
+/// Corners are where the track bends tighter than a 250 metres radius, measured
+/// from the world positions of the car, or where the car steers when the
+/// positions are missing. Short straights between two corners, e.g. in a
+/// chicane, are merged into a single corner.
+pub fn segment(samples: &[TelemetrySample]) -> Vec<TrackSegment> {
+    let points = resample(samples, ResampleGrid::Distance(STEP));
+    if points.len() < 2 {
+        return Vec::new();
+    }
+
+    let has_positions = points.iter().any(|p| p.world_position() != Vec3::default());
+    let cornering: Vec<bool> = (0..points.len())
+        .map(|i| match has_positions {
+            true => curvature(&points, i).abs() >= MIN_CURVATURE,
+            false => points[i].steer().abs() >= MIN_STEER,
+        })
+        .collect();
+
+    let mut runs = runs(&cornering);
+    merge_short_runs(&mut runs, &points);
+
+    let mut corners = 0;
+    let mut straights = 0;
+    runs.iter()
+        .map(|&(start, end, is_corner)| {
+            let (kind, number) = if is_corner {
+                corners += 1;
+                (SegmentKind::Corner, corners)
+            } else {
+                straights += 1;
+                (SegmentKind::Straight, straights)
+            };
+
+            // Segments end where the next one starts.
+            let end_distance = points.get(end).unwrap_or(&points[end - 1]).lap_distance();
+
+            measure(kind, number, &points[start..end], end_distance)
+        })
+        .collect()
+}
+
+/// The corners of a lap, see [`segment`].
+///
+/// [`segment`]: ./fn.segment.html
+pub fn corners(samples: &[TelemetrySample]) -> Vec<TrackSegment> {
+    segment(samples)
+        .into_iter()
+        .filter(|s| s.is_corner())
+        .collect()
+}
+
+/// Heading change per metre around a point, positive to the right.
+fn curvature(points: &[TelemetrySample], i: usize) -> f32 {
+    let (before, after) = match (
+        i.checked_sub(CURVATURE_WINDOW),
+        points.get(i + CURVATURE_WINDOW),
+    ) {
+        (Some(before), Some(after)) => (points[before], *after),
+        _ => return 0.0,
+    };
+
+    let a = points[i].world_position() - before.world_position();
+    let b = after.world_position() - points[i].world_position();
+    let length = a.length() + b.length();
+    if length <= f32::EPSILON {
+        return 0.0;
+    }
+
+    let mut turn = b.x().atan2(b.z()) - a.x().atan2(a.z());
+    if turn > PI {
+        turn -= 2.0 * PI;
+    } else if turn < -PI {
+        turn += 2.0 * PI;
+    }
+
+    turn / length
+}
+
+/// Runs of consecutive equal flags, as `(start, end, flag)` with `end` exclusive.
+fn runs(flags: &[bool]) -> Vec<(usize, usize, bool)> {
+    let mut runs: Vec<(usize, usize, bool)> = Vec::new();
+
+    for (i, &flag) in flags.iter().enumerate() {
+        match runs.last_mut() {
+            Some(run) if run.2 == flag => run.1 = i + 1,
+            _ => runs.push((i, i + 1, flag)),
+        }
+    }
+
+    runs
+}
+
+/// Fold the runs too short to be a corner or a straight into their neighbours.
+fn merge_short_runs(runs: &mut Vec<(usize, usize, bool)>, points: &[TelemetrySample]) {
+    let length = |run: &(usize, usize, bool)| {
+        points[run.1 - 1].lap_distance() - points[run.0].lap_distance() + STEP
+    };
+
+    // Short corners first, so that the straights around them are joined before
+    // their own length is checked.
+    for (is_corner, min_length) in [(true, MIN_CORNER_LENGTH), (false, MIN_STRAIGHT_LENGTH)] {
+        let mut i = 0;
+        while i < runs.len() {
+            let run = runs[i];
+            let is_inner = i > 0 && i + 1 < runs.len();
+
+            if run.2 == is_corner && is_inner && length(&run) < min_length {
+                // Between two runs of the other kind: all three become one.
+                let next = runs.remove(i + 1);
+                runs.remove(i);
+                runs[i - 1].1 = next.1;
+            } else {
+                i += 1;
+            }
+        }
+    }
+}
+
+fn measure(
+    kind: SegmentKind,
+    number: u8,
+    points: &[TelemetrySample],
+    end_distance: f32,
+) -> TrackSegment {
+    let first = points[0];
+    let last = points[points.len() - 1];
+    let slowest = points
+        .iter()
+        .min_by(|a, b| a.speed().total_cmp(&b.speed()))
+        .unwrap_or(&first);
+
+    TrackSegment {
+        kind,
+        number,
+        start_distance: first.lap_distance(),
+        end_distance,
+        entry_speed: first.speed(),
+        exit_speed: last.speed(),
+        min_speed: slowest.speed(),
+        min_speed_distance: slowest.lap_distance(),
+        gear: slowest.gear(),
+    }
+}
@@ -0,0 +1,242 @@
+use std::collections::HashSet;
+
+use crate::meta::tracks::TrackInfo;
+use crate::packet::header::SessionIdentifier;
+use crate::packet::lap::{LapData, PacketLapData, PitStatus, ResultStatus};
+use crate::packet::session::{PacketSessionData, SessionType};
+
+/// Distance (in metres) behind a lapped car at which a faster car triggers a blue flag.
+const BLUE_FLAG_DISTANCE: f32 = 150.0;
+
+/// An event that is not sent by the game, but deduced from consecutive Lap Data
+/// packets.
+///
+/// ## Specification
+/// ```text
+/// Overtake:  A car took the position of another one on track
+/// PitEntry:  A car entered the pit lane
+/// PitExit:   A car left the pit lane
+/// OffTrack:  A car went off-track, invalidating its lap
+/// BlueFlag:  A car is about to be lapped by a faster one
+/// ```
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum SyntheticEvent {
+    Overtake {
+        attacker_idx: u8,
+        defender_idx: u8,
+        position: u8,
+    },
+    PitEntry {
+        vehicle_idx: u8,
+        lap: u8,
+    },
+    PitExit {
+        vehicle_idx: u8,
+        lap: u8,
+    },
+    OffTrack {
+        vehicle_idx: u8,
+        lap: u8,
+    },
+    BlueFlag {
+        vehicle_idx: u8,
+        lapping_vehicle_idx: u8,
+    },
+}
+
+#[derive(Debug, Clone, Copy)]
+struct CarState {
+    position: u8,
+    lap: u8,
+    pit_status: PitStatus,
+    lap_invalid: bool,
+    distance: f32,
+}
+
+impl CarState {
+    fn new(ld: &LapData) -> Option<CarState> {
+        match ld.result_status() {
+            ResultStatus::Invalid | ResultStatus::Inactive => return None,
+            _ => {}
+        }
+
+        Some(CarState {
+            position: ld.car_position(),
+            lap: ld.current_lap_num(),
+            pit_status: ld.pit_status(),
+            lap_invalid: ld.current_lap_invalid(),
+            distance: ld.total_distance(),
+        })
+    }
+
+    fn in_pits(&self) -> bool {
+        self.pit_status != PitStatus::None
+    }
+}
+
+/// Synthesizes [`SyntheticEvent`]s by comparing each Lap Data packet to the
+/// previous one.
+///
+/// Blue flags are only raised in races, once the track length is known from a
+/// Session packet.
+///
+/// [`SyntheticEvent`]: ./enum.SyntheticEvent.html
+#[derive(Debug, Default)]
+pub struct EventSynthesizer {
+    session: Option<SessionIdentifier>,
+    track_length: Option<f32>,
+    is_race: bool,
+    previous: Vec<Option<CarState>>,
+    blue_flags: HashSet<(u8, u8)>,
+}
+
+impl EventSynthesizer {
+    pub fn new() -> EventSynthesizer {
+        EventSynthesizer::default()
+    }
+
+    pub fn reset(&mut self) {
+        self.session = None;
+        self.previous.clear();
+        self.blue_flags.clear();
+    }
+
+    pub fn update_session(&mut self, session: &PacketSessionData) {
+        self.track_length = match session.track_length() {
+            0 => TrackInfo::get(session.track()).map(|t| t.length() as f32),
+            length => Some(length as f32),
+        };
+        self.is_race = matches!(
+            session.session_type(),
+            SessionType::Race | SessionType::Race2
+        );
+    }
+
+    /// Compare a Lap Data packet to the previous one, returning the events that
+    /// happened in between.
+    pub fn update(&mut self, packet: &PacketLapData) -> Vec<SyntheticEvent> {
+        let session = packet.header().session_id();
+        if self.session != Some(session) {
+            self.reset();
+            self.session = Some(session);
+        }
+
+        let current: Vec<Option<CarState>> = packet.lap_data().iter().map(CarState::new).collect();
+
+        let mut events = Vec::new();
+        if self.previous.len() == current.len() {
+            self.pit_and_track_events(&current, &mut events);
+            self.overtakes(&current, &mut events);
+        }
+        self.blue_flag_events(&current, &mut events);
+
+        self.previous = current;
+        events
+    }
+
+    fn pit_and_track_events(&self, current: &[Option<CarState>], events: &mut Vec<SyntheticEvent>) {
+        for (idx, (before, now)) in self.previous.iter().zip(current).enumerate() {
+            let (before, now) = match (before, now) {
+                (Some(before), Some(now)) => (before, now),
+                _ => continue,
+            };
+            let vehicle_idx = idx as u8;
+
+            if !before.in_pits() && now.in_pits() {
+                events.push(SyntheticEvent::PitEntry {
+                    vehicle_idx,
+                    lap: now.lap,
+                });
+            } else if before.in_pits() && !now.in_pits() {
+                events.push(SyntheticEvent::PitExit {
+                    vehicle_idx,
+                    lap: now.lap,
+                });
+            }
+
+            // A new lap starts valid, so only an invalidation during the same lap counts.
+            if before.lap == now.lap && !before.lap_invalid && now.lap_invalid {
+                events.push(SyntheticEvent::OffTrack {
+                    vehicle_idx,
+                    lap: now.lap,
+                });
+            }
+        }
+    }
+
+    fn overtakes(&self, current: &[Option<CarState>], events: &mut Vec<SyntheticEvent>) {
+        for (attacker, (before, now)) in self.previous.iter().zip(current).enumerate() {
+            let (before, now) = match (before, now) {
+                (Some(before), Some(now)) => (before, now),
+                _ => continue,
+            };
+            if now.position >= before.position || now.position == 0 || now.in_pits() {
+                continue;
+            }
+
+            // Every car that was between the new and old positions of the attacker
+            // and lost a place on track was overtaken.
+            for (defender, (d_before, d_now)) in self.previous.iter().zip(current).enumerate() {
+                let (d_before, d_now) = match (d_before, d_now) {
+                    (Some(d_before), Some(d_now)) => (d_before, d_now),
+                    _ => continue,
+                };
+
+                if defender == attacker
+                    || d_before.position < now.position
+                    || d_before.position >= before.position
+                    || d_now.position <= d_before.position
+                    || d_before.in_pits()
+                    || d_now.in_pits()
+                {
+                    continue;
+                }
+
+                events.push(SyntheticEvent::Overtake {
+                    attacker_idx: attacker as u8,
+                    defender_idx: defender as u8,
+                    position: d_before.position,
+                });
+            }
+        }
+    }
+
+    fn blue_flag_events(&mut self, current: &[Option<CarState>], events: &mut Vec<SyntheticEvent>) {
+        let track_length = match self.track_length {
+            Some(length) if self.is_race => length,
+            _ => return,
+        };
+
+        let mut blue_flags = HashSet::new();
+        for (lapped, car) in current.iter().enumerate() {
+            let car = match car {
+                Some(car) if !car.in_pits() => car,
+                _ => continue,
+            };
+
+            for (lapping, other) in current.iter().enumerate() {
+                let other = match other {
+                    Some(other) if lapping != lapped && !other.in_pits() => other,
+                    _ => continue,
+                };
+
+                // The faster car is a lap ahead in distance, and just behind on track.
+                let gap = other.distance - car.distance;
+                if gap > track_length - BLUE_FLAG_DISTANCE && gap < track_length {
+                    blue_flags.insert((lapped as u8, lapping as u8));
+                }
+            }
+        }
+
+        let mut new_flags: Vec<&(u8, u8)> = blue_flags.difference(&self.blue_flags).collect();
+        new_flags.sort();
+        for &(vehicle_idx, lapping_vehicle_idx) in new_flags {
+            events.push(SyntheticEvent::BlueFlag {
+                vehicle_idx,
+                lapping_vehicle_idx,
+            });
+        }
+
+        self.blue_flags = blue_flags;
+    }
+}
@@ -0,0 +1,157 @@
+use crate::packet::header::SessionIdentifier;
+use crate::packet::lap::{PacketLapData, ResultStatus};
+
+/// Distance between the timing loops along the race distance, in metres.
+const LOOP_SPACING: f32 = 25.0;
+
+/// Session times at which a car crossed the timing loops.
+#[derive(Debug, Clone)]
+struct CarTiming {
+    /// Index of the loop of `passes[0]`, for cars first seen mid-race.
+    first_loop: usize,
+    passes: Vec<f32>,
+    distance: f32,
+    session_time: f32,
+}
+
+impl CarTiming {
+    fn new(distance: f32, session_time: f32) -> CarTiming {
+        CarTiming {
+            first_loop: next_loop(distance),
+            passes: Vec::new(),
+            distance,
+            session_time,
+        }
+    }
+
+    fn update(&mut self, distance: f32, session_time: f32) {
+        // Flashbacks take the car back in time, forget the loops it did not reach yet.
+        if distance < self.distance || session_time < self.session_time {
+            let kept = next_loop(distance).saturating_sub(self.first_loop);
+            self.passes.truncate(kept);
+            self.distance = distance;
+            self.session_time = session_time;
+            return;
+        }
+
+        let travelled = distance - self.distance;
+        let mut k = self.first_loop + self.passes.len();
+        while travelled > 0.0 && loop_distance(k) <= distance {
+            // Interpolate the crossing time between the two packets.
+            let ratio = ((loop_distance(k) - self.distance) / travelled).max(0.0);
+            let time = self.session_time + ratio * (session_time - self.session_time);
+            self.passes.push(time);
+            k += 1;
+        }
+
+        self.distance = distance;
+        self.session_time = session_time;
+    }
+
+    /// Index of the last loop crossed.
+    fn last_loop(&self) -> Option<usize> {
+        (self.first_loop + self.passes.len()).checked_sub(1)
+    }
+
+    fn pass(&self, k: usize) -> Option<f32> {
+        self.passes.get(k.checked_sub(self.first_loop)?).copied()
+    }
+}
+
+/// Measures the time gaps between the cars in a race, from the total distance
+/// of the Lap Data packets.
+///
+/// The race distance is split by virtual timing loops every 25 metres, and the
+/// gap between two cars is the time between their crossings of the last loop
+/// both went through, the way the timing screens compute intervals. Gaps are
+/// thus up to date within a few metres, and count the laps a car is behind.
+/// Drivers are identified by their vehicle index.
+#[derive(Debug, Default)]
+pub struct GapTracker {
+    session: Option<SessionIdentifier>,
+    cars: Vec<Option<CarTiming>>,
+}
+
+impl GapTracker {
+    pub fn new() -> GapTracker {
+        GapTracker::default()
+    }
+
+    pub fn reset(&mut self) {
+        self.session = None;
+        self.cars.clear();
+    }
+
+    pub fn update(&mut self, packet: &PacketLapData) {
+        let session = packet.header().session_id();
+        if self.session != Some(session) {
+            self.reset();
+            self.session = Some(session);
+        }
+
+        let session_time = packet.header().session_time();
+        let lap_data = packet.lap_data();
+        self.cars.resize(lap_data.len(), None);
+
+        for (car, ld) in self.cars.iter_mut().zip(lap_data.iter()) {
+            match ld.result_status() {
+                ResultStatus::Invalid | ResultStatus::Inactive => {
+                    *car = None;
+                    continue;
+                }
+                _ => {}
+            }
+
+            // The total distance is negative on the grid, before the line is crossed.
+            let distance = ld.total_distance().max(0.0);
+            match car {
+                Some(car) => car.update(distance, session_time),
+                None => *car = Some(CarTiming::new(distance, session_time)),
+            }
+        }
+    }
+
+    /// Time in seconds that `behind` is behind `ahead`, at the last point of
+    /// the race both cars went through. Negative when `behind` is in fact
+    /// ahead.
+    pub fn interval(&self, ahead: u8, behind: u8) -> Option<f32> {
+        let ahead = self.car(ahead)?;
+        let behind = self.car(behind)?;
+
+        let k = ahead.last_loop()?.min(behind.last_loop()?);
+        Some(behind.pass(k)? - ahead.pass(k)?)
+    }
+
+    /// Time in seconds that a car is behind the car furthest into the race.
+    pub fn gap_to_leader(&self, driver_index: u8) -> Option<f32> {
+        self.interval(self.leader()?, driver_index)
+    }
+
+    /// Vehicle index of the car furthest into the race.
+    pub fn leader(&self) -> Option<u8> {
+        self.cars
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, car)| car.as_ref().map(|c| (idx, c.distance)))
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(idx, _)| idx as u8)
+    }
+
+    /// Distance covered since the start of the race, in metres.
+    pub fn distance(&self, driver_index: u8) -> Option<f32> {
+        self.car(driver_index).map(|c| c.distance)
+    }
+
+    fn car(&self, driver_index: u8) -> Option<&CarTiming> {
+        self.cars.get(driver_index as usize)?.as_ref()
+    }
+}
+
+fn loop_distance(k: usize) -> f32 {
+    k as f32 * LOOP_SPACING
+}
+
+/// Index of the first loop at or after `distance`.
+fn next_loop(distance: f32) -> usize {
+    (distance / LOOP_SPACING).ceil().max(0.0) as usize
+}
@@ -0,0 +1,242 @@
+use getset::CopyGetters;
+
+use crate::packet::car_telemetry::PacketCarTelemetryData;
+use crate::packet::lap::PacketLapData;
+
+/// Farthest from the line a lap can be first sampled at, in metres, for it to
+/// be recorded whole, e.g. not when joining a session halfway around a lap.
+const MAX_START_DISTANCE: f32 = 50.0;
+
+/// A single point of the player's telemetry, recorded at a given distance
+/// around the lap.
+///
+/// ## Specification
+/// ```text
+/// lap_distance: Distance around the lap in metres
+/// lap_time:     Time since the start of the lap in seconds
+/// speed:        Speed of car in kilometres per hour
+/// throttle:     Amount of throttle applied (0.0 to 1.0)
+/// brake:        Amount of brake applied (0.0 to 1.0)
+/// gear:         Gear selected (1-8, N=0, R=-1)
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, CopyGetters)]
+#[getset(get_copy = "pub")]
+pub struct GhostSample {
+    lap_distance: f32,
+    lap_time: f32,
+    speed: u16,
+    throttle: f32,
+    brake: f32,
+    gear: i8,
+}
+
+/// Channel comparison between the current lap and the best lap, at the
+/// current distance around the lap.
+#[derive(Debug, Clone, Copy, PartialEq, CopyGetters)]
+#[getset(get_copy = "pub")]
+pub struct GhostComparison {
+    current: GhostSample,
+    best: GhostSample,
+}
+
+impl GhostComparison {
+    /// Time gained (negative) or lost (positive) against the best lap.
+    pub fn delta(&self) -> f32 {
+        self.current.lap_time - self.best.lap_time
+    }
+
+    pub fn speed_delta(&self) -> i32 {
+        i32::from(self.current.speed) - i32::from(self.best.speed)
+    }
+
+    pub fn throttle_delta(&self) -> f32 {
+        self.current.throttle - self.best.throttle
+    }
+
+    pub fn brake_delta(&self) -> f32 {
+        self.current.brake - self.best.brake
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Channels {
+    speed: u16,
+    throttle: f32,
+    brake: f32,
+    gear: i8,
+}
+
+/// Records the player's telemetry around each lap and keeps the best valid lap
+/// as a ghost to compare the current lap against.
+///
+/// Feed it with every Lap and Car Telemetry packet; the samples are indexed by
+/// lap distance so that laps can be compared at the same point on track. Laps
+/// that were not followed from the line, such as the one in progress when the
+/// recording starts, never become the best lap.
+#[derive(Debug, Default)]
+pub struct Ghost {
+    best: Vec<GhostSample>,
+    best_lap_time: Option<f32>,
+    current: Vec<GhostSample>,
+    current_lap_num: u8,
+    current_lap_invalid: bool,
+    channels: Option<Channels>,
+}
+
+impl Ghost {
+    pub fn new() -> Ghost {
+        Ghost::default()
+    }
+
+    /// Discard both the best lap and the lap in progress.
+    pub fn reset(&mut self) {
+        *self = Ghost::default();
+    }
+
+    /// Keep the player's latest channels, to be recorded with the next lap sample.
+    pub fn update_telemetry(&mut self, packet: &PacketCarTelemetryData) {
+        let idx = packet.header().player_car_index() as usize;
+
+        if let Some(t) = packet.car_telemetry_data().get(idx) {
+            self.channels = Some(Channels {
+                speed: t.speed(),
+                throttle: t.throttle(),
+                brake: t.brake(),
+                gear: t.gear(),
+            });
+        }
+    }
+
+    /// Record a new sample for the lap in progress, and promote the previous lap
+    /// to best lap when a faster valid lap has been completed.
+    pub fn update_lap(&mut self, packet: &PacketLapData) {
+        let idx = packet.header().player_car_index() as usize;
+        let ld = match packet.lap_data().get(idx) {
+            Some(ld) => ld,
+            None => return,
+        };
+
+        if ld.current_lap_num() != self.current_lap_num {
+            if self.current_lap_num != 0 {
+                self.complete_lap(ld.last_lap_time());
+            }
+
+            self.current.clear();
+            self.current_lap_num = ld.current_lap_num();
+            self.current_lap_invalid = false;
+        }
+
+        self.current_lap_invalid |= ld.current_lap_invalid();
+
+        let lap_distance = ld.lap_distance();
+        if lap_distance < 0.0 {
+            return;
+        }
+
+        // Only keep samples moving forward around the lap.
+        if let Some(last) = self.current.last() {
+            if lap_distance <= last.lap_distance {
+                return;
+            }
+        }
+
+        if let Some(c) = self.channels {
+            self.current.push(GhostSample {
+                lap_distance,
+                lap_time: ld.current_lap_time(),
+                speed: c.speed,
+                throttle: c.throttle,
+                brake: c.brake,
+                gear: c.gear,
+            });
+        }
+    }
+
+    fn complete_lap(&mut self, lap_time: f32) {
+        let started_at_line = self
+            .current
+            .first()
+            .is_some_and(|s| s.lap_distance <= MAX_START_DISTANCE);
+        if self.current_lap_invalid || !started_at_line || lap_time <= 0.0 {
+            return;
+        }
+
+        let is_best = match self.best_lap_time {
+            Some(best) => lap_time < best,
+            None => true,
+        };
+
+        if is_best {
+            self.best = std::mem::take(&mut self.current);
+            self.best_lap_time = Some(lap_time);
+        }
+    }
+
+    /// Lap time of the best lap, if one has been recorded.
+    pub fn best_lap_time(&self) -> Option<f32> {
+        self.best_lap_time
+    }
+
+    /// Samples of the best lap, ordered by lap distance.
+    pub fn best_lap(&self) -> &[GhostSample] {
+        &self.best
+    }
+
+    /// Samples of the lap in progress, ordered by lap distance.
+    pub fn current_lap(&self) -> &[GhostSample] {
+        &self.current
+    }
+
+    /// Sample of the best lap at `lap_distance`, interpolated between the two
+    /// closest recorded samples.
+    pub fn best_at(&self, lap_distance: f32) -> Option<GhostSample> {
+        sample_at(&self.best, lap_distance)
+    }
+
+    /// Live delta to the best lap, in seconds. Negative when the current lap is
+    /// faster.
+    pub fn delta(&self) -> Option<f32> {
+        self.comparison().map(|c| c.delta())
+    }
+
+    /// Compare the latest sample of the current lap with the best lap at the
+    /// same distance.
+    pub fn comparison(&self) -> Option<GhostComparison> {
+        let current = *self.current.last()?;
+        let best = self.best_at(current.lap_distance)?;
+
+        Some(GhostComparison { current, best })
+    }
+}
+
+fn sample_at(samples: &[GhostSample], lap_distance: f32) -> Option<GhostSample> {
+    let first = samples.first()?;
+    let last = samples.last()?;
+
+    if lap_distance < first.lap_distance || lap_distance > last.lap_distance {
+        return None;
+    }
+
+    let i = samples.partition_point(|s| s.lap_distance < lap_distance);
+    if i == 0 {
+        return Some(*first);
+    }
+
+    let a = samples[i - 1];
+    let b = samples[i];
+    let f = (lap_distance - a.lap_distance) / (b.lap_distance - a.lap_distance);
+    let nearest = if f < 0.5 { a } else { b };
+
+    Some(GhostSample {
+        lap_distance,
+        lap_time: lerp(a.lap_time, b.lap_time, f),
+        speed: lerp(f32::from(a.speed), f32::from(b.speed), f).round() as u16,
+        throttle: lerp(a.throttle, b.throttle, f),
+        brake: lerp(a.brake, b.brake, f),
+        gear: nearest.gear,
+    })
+}
+
+fn lerp(a: f32, b: f32, f: f32) -> f32 {
+    a + (b - a) * f
+}
@@ -0,0 +1,227 @@
+use getset::CopyGetters;
+
+use crate::transform::downsample::{resample, ResampleGrid};
+use crate::transform::lap_buffer::TelemetryLap;
+use crate::transform::sample::TelemetrySample;
+
+/// Distance between the points of a diff, in metres.
+pub const DEFAULT_STEP: f32 = 5.0;
+
+/// Change of the time delta below which a loss or a gain is ignored, in seconds.
+const MIN_TIME_LOSS: f32 = 0.02;
+
+/// Difference between two laps at a distance around the lap.
+///
+/// All deltas are the value of lap A minus the value of lap B, so a positive
+/// time delta means that lap A is behind.
+///
+/// ## Specification
+/// ```text
+/// lap_distance:    Distance around the lap in metres
+/// time_delta:      Cumulative time delta since the start of the laps in seconds
+/// speed_delta:     Delta of speed in kilometres per hour
+/// throttle_delta:  Delta of throttle applied (-1.0 to 1.0)
+/// brake_delta:     Delta of brake applied (-1.0 to 1.0)
+/// gear_delta:      Delta of gear selected
+/// steer_delta:     Delta of steering (-2.0 to 2.0)
+/// speed_a:         Speed on lap A in kilometres per hour
+/// speed_b:         Speed on lap B in kilometres per hour
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, CopyGetters)]
+#[getset(get_copy = "pub")]
+pub struct LapDiffPoint {
+    lap_distance: f32,
+    time_delta: f32,
+    speed_delta: f32,
+    throttle_delta: f32,
+    brake_delta: f32,
+    gear_delta: i8,
+    steer_delta: f32,
+    speed_a: f32,
+    speed_b: f32,
+}
+
+/// A stretch of track where lap A loses time to lap B, usually a corner.
+///
+/// ## Specification
+/// ```text
+/// start_distance:  Distance around the lap where the loss starts in metres
+/// end_distance:    Distance around the lap where the loss ends in metres
+/// apex_distance:   Distance of the lowest speed of lap A in the stretch in metres
+/// time_lost:       Time lost by lap A over the stretch in seconds
+/// min_speed_a:     Lowest speed of lap A in the stretch in kilometres per hour
+/// min_speed_b:     Lowest speed of lap B in the stretch in kilometres per hour
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, CopyGetters)]
+#[getset(get_copy = "pub")]
+pub struct TimeLoss {
+    start_distance: f32,
+    end_distance: f32,
+    apex_distance: f32,
+    time_lost: f32,
+    min_speed_a: f32,
+    min_speed_b: f32,
+}
+
+/// Channels of two laps compared at the same distances around the lap.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LapDiff {
+    points: Vec<LapDiffPoint>,
+}
+
+impl LapDiff {
+    /// Points of the diff, ordered by lap distance.
+    pub fn points(&self) -> &[LapDiffPoint] {
+        &self.points
+    }
+
+    /// Time delta at the end of the distance covered by both laps, in seconds.
+    pub fn final_delta(&self) -> Option<f32> {
+        self.points.last().map(|p| p.time_delta)
+    }
+
+    /// Point of the diff closest to `lap_distance`.
+    pub fn at(&self, lap_distance: f32) -> Option<&LapDiffPoint> {
+        let i = self
+            .points
+            .partition_point(|p| p.lap_distance < lap_distance);
+
+        match (
+            i.checked_sub(1).map(|i| &self.points[i]),
+            self.points.get(i),
+        ) {
+            (Some(a), Some(b)) if lap_distance - a.lap_distance < b.lap_distance - lap_distance => {
+                Some(a)
+            }
+            (a, b) => b.or(a),
+        }
+    }
+
+    /// The `count` stretches where lap A loses the most time to lap B, worst
+    /// first.
+    ///
+    /// A stretch runs from a low of the time delta to the next high, ignoring
+    /// changes of less than 20 milliseconds, so that the noise of the delta
+    /// does not split a corner in several stretches.
+    pub fn worst_corners(&self, count: usize) -> Vec<TimeLoss> {
+        let mut losses: Vec<TimeLoss> = self
+            .losing_stretches()
+            .into_iter()
+            .map(|(start, end)| self.time_loss(start, end))
+            .collect();
+
+        losses.sort_by(|a, b| b.time_lost.total_cmp(&a.time_lost));
+        losses.truncate(count);
+        losses
+    }
+
+    /// Index ranges over which the time delta rises by more than the threshold.
+    fn losing_stretches(&self) -> Vec<(usize, usize)> {
+        let mut stretches = Vec::new();
+        let mut low = 0;
+        let mut high: Option<usize> = None;
+
+        for (i, point) in self.points.iter().enumerate() {
+            match high {
+                None => {
+                    if point.time_delta < self.points[low].time_delta {
+                        low = i;
+                    } else if point.time_delta - self.points[low].time_delta >= MIN_TIME_LOSS {
+                        high = Some(i);
+                    }
+                }
+                Some(h) => {
+                    if point.time_delta > self.points[h].time_delta {
+                        high = Some(i);
+                    } else if self.points[h].time_delta - point.time_delta >= MIN_TIME_LOSS {
+                        stretches.push((low, h));
+                        low = i;
+                        high = None;
+                    }
+                }
+            }
+        }
+
+        if let Some(h) = high {
+            stretches.push((low, h));
+        }
+
+        stretches
+    }
+
+    fn time_loss(&self, start: usize, end: usize) -> TimeLoss {
+        let stretch = &self.points[start..=end];
+        let apex = stretch
+            .iter()
+            .min_by(|a, b| a.speed_a.total_cmp(&b.speed_a))
+            .unwrap_or(&stretch[0]);
+        let min_speed_b = stretch
+            .iter()
+            .map(|p| p.speed_b)
+            .fold(f32::INFINITY, f32::min);
+
+        TimeLoss {
+            start_distance: stretch[0].lap_distance,
+            end_distance: stretch[stretch.len() - 1].lap_distance,
+            apex_distance: apex.lap_distance,
+            time_lost: stretch[stretch.len() - 1].time_delta - stretch[0].time_delta,
+            min_speed_a: apex.speed_a,
+            min_speed_b,
+        }
+    }
+}
+
+/// Compare two laps every 5 metres around the lap, see [`lap_diff_with_step`]
+/// for another distance between the points.
+///
+/// [`lap_diff_with_step`]: ./fn.lap_diff_with_step.html
+pub fn lap_diff(lap_a: &TelemetryLap, lap_b: &TelemetryLap) -> LapDiff {
+    lap_diff_with_step(lap_a, lap_b, DEFAULT_STEP)
+}
+
+/// Compare two laps every `step` metres around the lap, over the distance
+/// covered by both.
+///
+/// The channels are interpolated at each distance. The time delta is counted
+/// from the first sample of each lap, which the game sends within a few metres
+/// of the line.
+pub fn lap_diff_with_step(lap_a: &TelemetryLap, lap_b: &TelemetryLap, step: f32) -> LapDiff {
+    let grid = ResampleGrid::Distance(step);
+    let a = resample(lap_a.samples(), grid);
+    let b = resample(lap_b.samples(), grid);
+
+    let (start_a, start_b) = match (lap_a.samples().first(), lap_b.samples().first()) {
+        (Some(a), Some(b)) => (a.session_time(), b.session_time()),
+        _ => return LapDiff { points: Vec::new() },
+    };
+
+    // Both grids are multiples of the step, so the points are aligned once the
+    // one starting earlier is skipped ahead.
+    let index = |s: &TelemetrySample| (s.lap_distance() / step).round() as i64;
+    let (skip_a, skip_b) = match (a.first(), b.first()) {
+        (Some(first_a), Some(first_b)) => {
+            let offset = index(first_b) - index(first_a);
+            (offset.max(0) as usize, (-offset).max(0) as usize)
+        }
+        _ => return LapDiff { points: Vec::new() },
+    };
+
+    let points = a
+        .iter()
+        .skip(skip_a)
+        .zip(b.iter().skip(skip_b))
+        .map(|(a, b)| LapDiffPoint {
+            lap_distance: a.lap_distance(),
+            time_delta: (a.session_time() - start_a) - (b.session_time() - start_b),
+            speed_delta: a.speed() - b.speed(),
+            throttle_delta: a.throttle() - b.throttle(),
+            brake_delta: a.brake() - b.brake(),
+            gear_delta: a.gear() - b.gear(),
+            steer_delta: a.steer() - b.steer(),
+            speed_a: a.speed(),
+            speed_b: b.speed(),
+        })
+        .collect();
+
+    LapDiff { points }
+}
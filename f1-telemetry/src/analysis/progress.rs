@@ -0,0 +1,142 @@
+use getset::CopyGetters;
+
+use crate::meta::tracks::TrackInfo;
+use crate::packet::lap::{LapData, PacketLapData, ResultStatus};
+use crate::packet::session::{PacketSessionData, SessionType};
+
+/// How far a driver is into the race.
+///
+/// ## Specification
+/// ```text
+/// distance:        Distance covered since the start line in metres
+/// fraction:        Fraction of the race distance covered (0.0 to 1.0)
+/// laps_remaining:  Estimated laps left to drive, assuming the race ends when
+///                  the leader crosses the line after the last lap and this
+///                  driver keeps the leader's pace
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, CopyGetters)]
+#[getset(get_copy = "pub")]
+pub struct DriverProgress {
+    distance: f32,
+    fraction: f32,
+    laps_remaining: f32,
+}
+
+/// Computes the progress of the drivers through a race, from the total laps and
+/// track length of the Session packets and the lap data of every car.
+///
+/// Both races of a Formula 2 weekend (the feature race and the sprint race, sent
+/// as `Race` and `Race2`) are handled as races, each with its own number of laps.
+/// In the other sessions only the elapsed fraction of the session time is known.
+#[derive(Debug, Default)]
+pub struct ProgressCalculator {
+    session_type: Option<SessionType>,
+    total_laps: u8,
+    track_length: u16,
+    session_duration: u16,
+    session_time_left: u16,
+}
+
+impl ProgressCalculator {
+    pub fn new() -> ProgressCalculator {
+        ProgressCalculator::default()
+    }
+
+    pub fn update_session(&mut self, session: &PacketSessionData) {
+        self.session_type = Some(session.session_type());
+        self.total_laps = session.total_laps();
+        // Fall back to the known length of the track when the game sends none.
+        self.track_length = match session.track_length() {
+            0 => TrackInfo::get(session.track()).map_or(0, |t| t.length()),
+            length => length,
+        };
+        self.session_duration = session.session_duration();
+        self.session_time_left = session.session_time_left();
+    }
+
+    /// Whether the current session is a race, with a distance to cover.
+    pub fn is_race(&self) -> bool {
+        matches!(
+            self.session_type,
+            Some(SessionType::Race) | Some(SessionType::Race2)
+        )
+    }
+
+    /// Distance of the race in metres, when in a race.
+    pub fn race_distance(&self) -> Option<f32> {
+        if !self.is_race() || self.total_laps == 0 || self.track_length == 0 {
+            return None;
+        }
+
+        Some(self.total_laps as f32 * self.track_length as f32)
+    }
+
+    /// Fraction of the session time elapsed (0.0 to 1.0), for timed sessions.
+    pub fn session_fraction(&self) -> Option<f32> {
+        if self.session_type.is_none() || self.session_duration == 0 {
+            return None;
+        }
+
+        let elapsed = self.session_duration.saturating_sub(self.session_time_left);
+        Some(elapsed as f32 / self.session_duration as f32)
+    }
+
+    /// Progress of every car, in the same order as `lap_data`. Cars that are
+    /// not taking part in the race are `None`, as are all cars outside of races.
+    pub fn drivers(&self, packet: &PacketLapData) -> Vec<Option<DriverProgress>> {
+        let race_distance = match self.race_distance() {
+            Some(d) => d,
+            None => return packet.lap_data().iter().map(|_| None).collect(),
+        };
+        let track_length = self.track_length as f32;
+
+        let distances: Vec<Option<f32>> = packet
+            .lap_data()
+            .iter()
+            .map(|ld| self.distance(ld).map(|d| d.min(race_distance)))
+            .collect();
+
+        let leader_distance = distances.iter().flatten().fold(0.0f32, |a, &b| a.max(b));
+        let leader_laps_remaining = (race_distance - leader_distance) / track_length;
+
+        distances
+            .into_iter()
+            .map(|distance| {
+                let distance = distance?;
+                let laps = distance / track_length;
+                let laps_remaining = if distance >= race_distance {
+                    0.0
+                } else {
+                    (laps + leader_laps_remaining).ceil() - laps
+                };
+
+                Some(DriverProgress {
+                    distance,
+                    fraction: distance / race_distance,
+                    laps_remaining,
+                })
+            })
+            .collect()
+    }
+
+    /// Progress of the race leader.
+    pub fn leader(&self, packet: &PacketLapData) -> Option<DriverProgress> {
+        self.drivers(packet)
+            .into_iter()
+            .flatten()
+            .max_by(|a, b| a.distance.total_cmp(&b.distance))
+    }
+
+    fn distance(&self, ld: &LapData) -> Option<f32> {
+        match ld.result_status() {
+            ResultStatus::Invalid | ResultStatus::Inactive => return None,
+            _ => {}
+        }
+
+        let completed_laps = ld.current_lap_num().saturating_sub(1) as f32;
+        // The lap distance is negative on the grid, before the line is crossed.
+        let distance = completed_laps * self.track_length as f32 + ld.lap_distance().max(0.0);
+
+        Some(distance)
+    }
+}
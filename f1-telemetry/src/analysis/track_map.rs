@@ -0,0 +1,326 @@
+use std::collections::HashSet;
+
+use crate::meta::tracks::TrackInfo;
+use crate::packet::lap::{PacketLapData, PitStatus};
+use crate::packet::motion::PacketMotionData;
+use crate::packet::session::Track;
+
+/// Size of the grid cells (in metres) used to deduplicate recorded positions.
+const CELL_SIZE: f32 = 5.0;
+
+/// Minimum number of recorded points before the recorded outline is preferred
+/// over the pre-baked one.
+const MIN_OUTLINE_POINTS: usize = 200;
+
+/// Axis-aligned bounds of the positions seen so far, in world space.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bounds {
+    pub min_x: f32,
+    pub max_x: f32,
+    pub min_z: f32,
+    pub max_z: f32,
+}
+
+impl Bounds {
+    fn from_point(x: f32, z: f32) -> Bounds {
+        Bounds {
+            min_x: x,
+            max_x: x,
+            min_z: z,
+            max_z: z,
+        }
+    }
+
+    fn extend(&mut self, x: f32, z: f32) {
+        self.min_x = self.min_x.min(x);
+        self.max_x = self.max_x.max(x);
+        self.min_z = self.min_z.min(z);
+        self.max_z = self.max_z.max(z);
+    }
+
+    pub fn width(&self) -> f32 {
+        self.max_x - self.min_x
+    }
+
+    pub fn height(&self) -> f32 {
+        self.max_z - self.min_z
+    }
+}
+
+/// Builds a 2D outline of the current track from the world positions found in
+/// the Motion packets, and keeps track of where every car is on it.
+///
+/// The map is drawn on the X/Z plane (Y is the vertical axis in the game's world
+/// space). All coordinates returned by this type are normalised to `0.0..=1.0`,
+/// with the aspect ratio of the track preserved and the shorter axis centred, so
+/// that a UI only has to scale them to the size of its widget.
+///
+/// Positions are recorded with the lap distance of the car from the latest Lap
+/// Data packet, so that [`outline`] goes around the track in order whichever car
+/// drove where first. Cars in the pit lane, or before the line, are left out of
+/// the outline.
+///
+/// Until enough positions have been recorded, [`outline`] falls back to a coarse
+/// pre-baked outline when one is available for the current track, and the cars
+/// are drawn along it at the fraction of the lap they have driven.
+///
+/// [`outline`]: #method.outline
+#[derive(Debug, Default)]
+pub struct TrackMap {
+    track: Option<Track>,
+    cells: HashSet<(i32, i32)>,
+    /// Lap distance and position, ordered by lap distance.
+    points: Vec<(f32, f32, f32)>,
+    bounds: Option<Bounds>,
+    cars: Vec<Option<(f32, f32)>>,
+    /// Lap distance of every car, and whether it is on track.
+    laps: Vec<(f32, bool)>,
+}
+
+impl TrackMap {
+    pub fn new() -> TrackMap {
+        TrackMap::default()
+    }
+
+    /// Set the track being driven. Changing track discards the recorded outline.
+    pub fn set_track(&mut self, track: Track) {
+        if self.track != Some(track) {
+            self.reset();
+            self.track = Some(track);
+        }
+    }
+
+    pub fn track(&self) -> Option<Track> {
+        self.track
+    }
+
+    /// Discard all recorded positions.
+    pub fn reset(&mut self) {
+        self.track = None;
+        self.cells.clear();
+        self.points.clear();
+        self.bounds = None;
+        self.cars.clear();
+        self.laps.clear();
+    }
+
+    /// Keep the lap distance of every car, to record the next positions of the
+    /// cars on track with.
+    pub fn update_lap(&mut self, packet: &PacketLapData) {
+        self.laps = packet
+            .lap_data()
+            .iter()
+            .map(|ld| {
+                let lap_distance = ld.lap_distance();
+                (
+                    lap_distance,
+                    lap_distance >= 0.0 && ld.pit_status() == PitStatus::None,
+                )
+            })
+            .collect();
+    }
+
+    /// Record the positions of all the cars in a Motion packet.
+    pub fn update(&mut self, packet: &PacketMotionData) {
+        self.cars.clear();
+
+        for (i, md) in packet.motion_data().iter().enumerate() {
+            let x = md.world_position_x();
+            let z = md.world_position_z();
+
+            // Inactive cars are reported at the origin.
+            if x == 0.0 && z == 0.0 {
+                self.cars.push(None);
+                continue;
+            }
+
+            self.cars.push(Some((x, z)));
+            match self.bounds.as_mut() {
+                Some(b) => b.extend(x, z),
+                None => self.bounds = Some(Bounds::from_point(x, z)),
+            }
+
+            if let Some(&(lap_distance, true)) = self.laps.get(i) {
+                self.record(lap_distance, x, z);
+            }
+        }
+    }
+
+    fn record(&mut self, lap_distance: f32, x: f32, z: f32) {
+        let cell = (
+            (x / CELL_SIZE).floor() as i32,
+            (z / CELL_SIZE).floor() as i32,
+        );
+        if self.cells.insert(cell) {
+            let i = self.points.partition_point(|p| p.0 < lap_distance);
+            self.points.insert(i, (lap_distance, x, z));
+        }
+    }
+
+    /// World-space bounds of all the positions recorded so far.
+    pub fn bounds(&self) -> Option<Bounds> {
+        self.bounds
+    }
+
+    /// Number of distinct positions recorded so far.
+    pub fn num_points(&self) -> usize {
+        self.points.len()
+    }
+
+    /// Whether enough positions have been recorded to draw the track from them.
+    pub fn is_complete(&self) -> bool {
+        self.points.len() >= MIN_OUTLINE_POINTS
+    }
+
+    /// Normalise a world-space position to the `0.0..=1.0` map space.
+    pub fn normalize(&self, x: f32, z: f32) -> Option<(f32, f32)> {
+        let b = self.bounds?;
+        let size = b.width().max(b.height());
+
+        if size <= 0.0 {
+            return Some((0.5, 0.5));
+        }
+
+        let offset_x = (size - b.width()) / 2.0;
+        let offset_z = (size - b.height()) / 2.0;
+
+        Some((
+            (x - b.min_x + offset_x) / size,
+            (z - b.min_z + offset_z) / size,
+        ))
+    }
+
+    /// Pre-baked outline of the current track, while the recorded one is not
+    /// complete.
+    fn fallback(&self) -> Option<&'static [(f32, f32)]> {
+        if self.is_complete() {
+            return None;
+        }
+
+        self.track.and_then(prebaked_outline)
+    }
+
+    /// Whether the outline is the pre-baked one of the track, as not enough
+    /// positions have been recorded yet.
+    pub fn is_prebaked(&self) -> bool {
+        self.fallback().is_some()
+    }
+
+    /// Normalised outline of the track, from the line around the lap.
+    ///
+    /// The recorded outline is used once it is complete; before that, the
+    /// pre-baked outline for the current track is returned if there is one,
+    /// otherwise whatever has been recorded so far.
+    pub fn outline(&self) -> Vec<(f32, f32)> {
+        if let Some(outline) = self.fallback() {
+            return outline.to_vec();
+        }
+
+        self.points
+            .iter()
+            .filter_map(|&(_, x, z)| self.normalize(x, z))
+            .collect()
+    }
+
+    /// Normalised position of the car at `index`, if it is active.
+    ///
+    /// On a pre-baked outline, the car is placed by its lap distance.
+    pub fn car_position(&self, index: usize) -> Option<(f32, f32)> {
+        let (x, z) = (*self.cars.get(index)?)?;
+
+        match self.fallback() {
+            Some(outline) => {
+                let (lap_distance, _) = *self.laps.get(index)?;
+                let length = TrackInfo::get(self.track?)?.length();
+                point_along(outline, lap_distance / f32::from(length))
+            }
+            None => self.normalize(x, z),
+        }
+    }
+
+    /// Normalised positions of all the cars, indexed like the Motion packet.
+    pub fn car_positions(&self) -> Vec<Option<(f32, f32)>> {
+        (0..self.cars.len()).map(|i| self.car_position(i)).collect()
+    }
+}
+
+/// Point at `fraction` of the length of `outline`, wrapping around the lap.
+fn point_along(outline: &[(f32, f32)], fraction: f32) -> Option<(f32, f32)> {
+    let segment_length = |a: (f32, f32), b: (f32, f32)| (b.0 - a.0).hypot(b.1 - a.1);
+    let length: f32 = outline.windows(2).map(|w| segment_length(w[0], w[1])).sum();
+
+    if !fraction.is_finite() || fraction < 0.0 {
+        return None;
+    }
+
+    let mut left = fraction.fract() * length;
+    for w in outline.windows(2) {
+        let segment = segment_length(w[0], w[1]);
+        if left <= segment && segment > 0.0 {
+            let t = left / segment;
+            return Some((
+                w[0].0 + (w[1].0 - w[0].0) * t,
+                w[0].1 + (w[1].1 - w[0].1) * t,
+            ));
+        }
+        left -= segment;
+    }
+
+    outline.last().copied()
+}
+
+/// Coarse, hand-traced outlines for some of the tracks, in normalised map space.
+///
+/// The points go around the lap in the driving direction from the line, and the
+/// outlines are closed. They are only meant as a placeholder until enough
+/// positions have been recorded.
+pub fn prebaked_outline(track: Track) -> Option<&'static [(f32, f32)]> {
+    match track {
+        Track::Austria => Some(&AUSTRIA),
+        Track::Monza => Some(&MONZA),
+        Track::Sakhir => Some(&SAKHIR),
+        _ => None,
+    }
+}
+
+const AUSTRIA: [(f32, f32); 10] = [
+    (0.10, 0.70),
+    (0.45, 0.85),
+    (0.90, 0.55),
+    (0.85, 0.40),
+    (0.55, 0.45),
+    (0.50, 0.30),
+    (0.35, 0.20),
+    (0.20, 0.30),
+    (0.15, 0.50),
+    (0.10, 0.70),
+];
+
+const MONZA: [(f32, f32); 12] = [
+    (0.20, 0.90),
+    (0.25, 0.40),
+    (0.30, 0.20),
+    (0.45, 0.10),
+    (0.60, 0.15),
+    (0.55, 0.35),
+    (0.70, 0.45),
+    (0.85, 0.50),
+    (0.80, 0.70),
+    (0.60, 0.75),
+    (0.35, 0.95),
+    (0.20, 0.90),
+];
+
+const SAKHIR: [(f32, f32); 11] = [
+    (0.30, 0.90),
+    (0.35, 0.20),
+    (0.55, 0.10),
+    (0.60, 0.30),
+    (0.45, 0.45),
+    (0.70, 0.55),
+    (0.80, 0.35),
+    (0.90, 0.55),
+    (0.75, 0.85),
+    (0.50, 0.80),
+    (0.30, 0.90),
+];
@@ -0,0 +1,223 @@
+use getset::CopyGetters;
+
+use crate::analysis::gaps::GapTracker;
+use crate::packet::header::SessionIdentifier;
+use crate::packet::lap::{PacketLapData, PitStatus, ResultStatus};
+use crate::packet::Packet;
+use crate::session::{LapTracker, PitTracker};
+use crate::strategy::DEFAULT_PIT_LOSS;
+
+/// Laps slower than this ratio of the driver's best lap, e.g. behind a safety
+/// car, are not used as the reference to measure the pit loss.
+const MAX_LAP_TIME_RATIO: f32 = 1.07;
+
+/// Where the player would rejoin the race relative to a rival, when pitting now.
+///
+/// ## Specification
+/// ```text
+/// rival:   Vehicle index of the rival
+/// gap:     Time the player is behind the rival in seconds, negative when ahead
+/// margin:  Time the player would be ahead of the rival after the stop in
+///          seconds, negative when they would rejoin behind
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, CopyGetters)]
+#[getset(get_copy = "pub")]
+pub struct UndercutWindow {
+    rival: u8,
+    gap: f32,
+    margin: f32,
+}
+
+impl UndercutWindow {
+    /// Whether the player would rejoin ahead of the rival.
+    pub fn emerges_ahead(&self) -> bool {
+        self.margin > 0.0
+    }
+
+    /// Whether the rival is ahead of the player now, so that pitting first
+    /// would be an undercut rather than an overcut.
+    pub fn is_undercut(&self) -> bool {
+        self.gap > 0.0
+    }
+}
+
+/// Reports, for every rival, whether the player would rejoin ahead or behind
+/// them when pitting now.
+///
+/// The windows are computed again each time the player completes a lap, from
+/// the gaps to the rivals and the average time lost by a stop on this track.
+/// The pit loss is measured on the stops of all the drivers in the session,
+/// as the time their in and out laps lost to their usual pace, and is 22
+/// seconds until the first stop is completed. Rivals in the pit lane are left
+/// out, as their gap is about to change.
+#[derive(Debug, Default)]
+pub struct UndercutCalculator {
+    vehicle_idx: Option<u8>,
+    pit_loss: Option<f32>,
+    session: Option<SessionIdentifier>,
+    gaps: GapTracker,
+    laps: LapTracker,
+    pit_stops: PitTracker,
+    current_lap: Option<u8>,
+    windows: Vec<UndercutWindow>,
+}
+
+impl UndercutCalculator {
+    /// Calculator of the windows of the player's car.
+    pub fn new() -> UndercutCalculator {
+        UndercutCalculator::default()
+    }
+
+    /// Compute the windows of the car with the given index instead of the
+    /// player's car.
+    pub fn with_vehicle(mut self, vehicle_idx: u8) -> UndercutCalculator {
+        self.vehicle_idx = Some(vehicle_idx);
+        self
+    }
+
+    /// Use this pit loss, in seconds, instead of measuring it.
+    pub fn with_pit_loss(mut self, pit_loss: f32) -> UndercutCalculator {
+        self.pit_loss = Some(pit_loss);
+        self
+    }
+
+    pub fn reset(&mut self) {
+        self.session = None;
+        self.gaps.reset();
+        self.laps.reset();
+        self.pit_stops.reset();
+        self.current_lap = None;
+        self.windows.clear();
+    }
+
+    /// Update the calculator with the Lap Data and Car Status packets, ignoring
+    /// the others. Returns whether the windows were computed again.
+    pub fn update(&mut self, packet: &Packet) -> bool {
+        self.pit_stops.update(packet);
+
+        match packet {
+            Packet::Lap(p) => self.update_lap_data(p),
+            _ => false,
+        }
+    }
+
+    /// The windows computed at the end of the player's last lap, ordered by vehicle index.
+    pub fn windows(&self) -> &[UndercutWindow] {
+        &self.windows
+    }
+
+    /// The window of a rival, as of the end of the player's last lap.
+    pub fn window_for(&self, rival: u8) -> Option<&UndercutWindow> {
+        self.windows.iter().find(|w| w.rival == rival)
+    }
+
+    /// Average time lost by a stop in seconds.
+    pub fn pit_loss(&self) -> f32 {
+        self.pit_loss
+            .or_else(|| self.measured_pit_loss())
+            .unwrap_or(DEFAULT_PIT_LOSS)
+    }
+
+    fn update_lap_data(&mut self, packet: &PacketLapData) -> bool {
+        let session = packet.header().session_id();
+        if self.session != Some(session) {
+            self.reset();
+            self.session = Some(session);
+        }
+
+        self.gaps.update(packet);
+        self.laps.update(packet);
+
+        let player = self
+            .vehicle_idx
+            .unwrap_or_else(|| packet.header().player_car_index());
+        let lap = match packet.lap_data().get(player as usize) {
+            Some(ld) => ld.current_lap_num(),
+            None => return false,
+        };
+
+        let new_lap = self.current_lap.is_some_and(|current| lap > current);
+        self.current_lap = Some(lap);
+        if !new_lap {
+            return false;
+        }
+
+        let pit_loss = self.pit_loss();
+        self.windows = packet
+            .lap_data()
+            .iter()
+            .enumerate()
+            .filter(|(idx, _)| *idx != player as usize)
+            .filter(|(_, ld)| ld.pit_status() == PitStatus::None)
+            .filter(|(_, ld)| ld.result_status() == ResultStatus::Active)
+            .filter_map(|(idx, _)| {
+                let rival = idx as u8;
+                let gap = self.gaps.interval(rival, player)?;
+
+                Some(UndercutWindow {
+                    rival,
+                    gap,
+                    margin: -(gap + pit_loss),
+                })
+            })
+            .collect();
+
+        true
+    }
+
+    /// Mean time lost over the in and out laps of the completed stops.
+    fn measured_pit_loss(&self) -> Option<f32> {
+        let losses: Vec<f32> = self
+            .pit_stops
+            .drivers()
+            .into_iter()
+            .flat_map(|driver| {
+                let stops = self.pit_stops.stops_for(driver);
+                let pit_laps: Vec<u8> = stops
+                    .iter()
+                    .flat_map(|s| [s.lap(), s.lap().saturating_add(1)])
+                    .collect();
+                let pace = self.pace(driver, &pit_laps);
+
+                stops
+                    .iter()
+                    .filter(|s| s.exit_time().is_some())
+                    .filter_map(move |s| {
+                        let laps = self.laps.laps_for(driver);
+                        let lap_time =
+                            |lap| laps.iter().find(|l| l.lap() == lap).map(|l| l.lap_time());
+                        let in_lap = lap_time(s.lap())?;
+                        let out_lap = lap_time(s.lap().saturating_add(1))?;
+
+                        Some(in_lap + out_lap - 2.0 * pace?)
+                    })
+            })
+            .filter(|loss| *loss > 0.0)
+            .collect();
+
+        if losses.is_empty() {
+            return None;
+        }
+
+        Some(losses.iter().sum::<f32>() / losses.len() as f32)
+    }
+
+    /// Mean lap time of a driver away from the pit lane.
+    fn pace(&self, driver: u8, pit_laps: &[u8]) -> Option<f32> {
+        let best = self.laps.best_lap(driver)?.lap_time();
+        let times: Vec<f32> = self
+            .laps
+            .laps_for(driver)
+            .iter()
+            .filter(|l| l.lap_time() > 0.0 && l.lap_time() <= best * MAX_LAP_TIME_RATIO)
+            .filter(|l| !pit_laps.contains(&l.lap()))
+            .map(|l| l.lap_time())
+            .collect();
+
+        if times.is_empty() {
+            return None;
+        }
+
+        Some(times.iter().sum::<f32>() / times.len() as f32)
+    }
+}
@@ -0,0 +1,386 @@
+//! Driver and constructor standings over several sessions, e.g. of a league.
+//!
+//! A [`Championship`] adds up the points of the final classifications of its
+//! rounds with a [`PointsSystem`], which defaults to the one of Formula 1 and
+//! can be changed for the rules of a league: points per position in races and
+//! sprints, and a bonus for the fastest lap.
+//!
+//! With the `sqlite` feature, the classifications saved in a [`SqliteStore`]
+//! are loaded as the rounds of a championship, so that the standings carry
+//! over from a session to the next:
+//!
+//! ```no_run
+//! # #[cfg(feature = "sqlite")]
+//! # fn main() -> rusqlite::Result<()> {
+//! use f1_telemetry::championship::{Championship, PointsSystem};
+//! use f1_telemetry::storage::sqlite::SqliteStore;
+//!
+//! let store = SqliteStore::open("league.db")?;
+//! let championship = Championship::load(&store, PointsSystem::default())?;
+//!
+//! for standing in championship.driver_standings() {
+//!     println!("{} {} {}", standing.position, standing.name, standing.points);
+//! }
+//! # Ok(())
+//! # }
+//! # #[cfg(not(feature = "sqlite"))]
+//! # fn main() {}
+//! ```
+//!
+//! [`Championship`]: ./struct.Championship.html
+//! [`PointsSystem`]: ./struct.PointsSystem.html
+//! [`SqliteStore`]: ../storage/sqlite/struct.SqliteStore.html
+
+use std::cmp::Ordering;
+
+use crate::export::results::{ClassifiedDriver, SessionResults};
+use crate::packet::header::SessionIdentifier;
+use crate::packet::lap::ResultStatus;
+use crate::packet::participants::Team;
+use crate::packet::session::SessionType;
+#[cfg(feature = "sqlite")]
+use crate::storage::sqlite::SqliteStore;
+
+/// Kind of a round, scoring with its own points.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default)]
+pub enum RoundKind {
+    #[default]
+    Race,
+    Sprint,
+}
+
+impl RoundKind {
+    /// Kind of the rounds of a type of session: the second race of F1 2020
+    /// is scored as a sprint. None for practice and qualifying sessions.
+    pub fn of(session_type: SessionType) -> Option<RoundKind> {
+        match session_type {
+            SessionType::Race => Some(RoundKind::Race),
+            SessionType::Race2 => Some(RoundKind::Sprint),
+            _ => None,
+        }
+    }
+}
+
+/// Points scored in the rounds.
+///
+/// Only the drivers who finished score the points of their position.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PointsSystem {
+    /// Points of the positions in races, from the winner.
+    pub race: Vec<u16>,
+    /// Points of the positions in sprints, from the winner.
+    pub sprint: Vec<u16>,
+    /// Bonus for the fastest lap of a race.
+    pub fastest_lap: u16,
+    /// Lowest position scoring the fastest lap bonus, None for any.
+    pub fastest_lap_top: Option<u8>,
+    /// Whether the fastest lap of a sprint scores the bonus too.
+    pub fastest_lap_in_sprint: bool,
+}
+
+impl Default for PointsSystem {
+    /// Formula 1 from 2022 to 2024: 25, 18, 15, 12, 10, 8, 6, 4, 2 and 1
+    /// points in races, 8 to 1 in sprints, and 1 for the fastest lap of a race
+    /// when finishing in the top 10.
+    fn default() -> PointsSystem {
+        PointsSystem {
+            race: vec![25, 18, 15, 12, 10, 8, 6, 4, 2, 1],
+            sprint: vec![8, 7, 6, 5, 4, 3, 2, 1],
+            fastest_lap: 1,
+            fastest_lap_top: Some(10),
+            fastest_lap_in_sprint: false,
+        }
+    }
+}
+
+impl PointsSystem {
+    /// Points of a driver in a round.
+    pub fn points(&self, kind: RoundKind, driver: &ClassifiedDriver) -> u16 {
+        if driver.result_status != ResultStatus::Finished {
+            return 0;
+        }
+
+        let table = match kind {
+            RoundKind::Race => &self.race,
+            RoundKind::Sprint => &self.sprint,
+        };
+        let position = driver
+            .position
+            .checked_sub(1)
+            .and_then(|i| table.get(i as usize))
+            .copied()
+            .unwrap_or(0);
+
+        let fastest_lap = driver.fastest_lap
+            && (kind == RoundKind::Race || self.fastest_lap_in_sprint)
+            && self
+                .fastest_lap_top
+                .is_none_or(|top| driver.position <= top);
+
+        position + if fastest_lap { self.fastest_lap } else { 0 }
+    }
+}
+
+/// A round of a championship.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Round {
+    pub session_id: Option<SessionIdentifier>,
+    pub kind: RoundKind,
+    pub results: SessionResults,
+}
+
+/// Standing of a driver, the drivers being told apart by their name.
+///
+/// ## Specification
+/// ```text
+/// position:     Position in the championship
+/// name:         Name of the driver
+/// team:         Team of the driver in their last round
+/// points:       Points scored
+/// wins:         Races won
+/// podiums:      Races finished in the top 3
+/// fastest_laps: Fastest laps set in races
+/// rounds:       Points scored in each round, None when the driver was not in it
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct DriverStanding {
+    pub position: u8,
+    pub name: String,
+    pub team: Option<Team>,
+    pub points: u16,
+    pub wins: u16,
+    pub podiums: u16,
+    pub fastest_laps: u16,
+    pub rounds: Vec<Option<u16>>,
+}
+
+/// Standing of a team, scoring the points of both its drivers.
+///
+/// ## Specification
+/// ```text
+/// position: Position in the championship
+/// team:     The team
+/// points:   Points scored
+/// wins:     Races won
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConstructorStanding {
+    pub position: u8,
+    pub team: Team,
+    pub points: u16,
+    pub wins: u16,
+}
+
+/// Rounds of a championship and their standings.
+#[derive(Debug, Clone, Default)]
+pub struct Championship {
+    points_system: PointsSystem,
+    rounds: Vec<Round>,
+}
+
+impl Championship {
+    pub fn new(points_system: PointsSystem) -> Championship {
+        Championship {
+            points_system,
+            rounds: Vec::new(),
+        }
+    }
+
+    /// Championship of the races and sprints saved in a store, in the order
+    /// they were recorded.
+    #[cfg(feature = "sqlite")]
+    pub fn load(
+        store: &SqliteStore,
+        points_system: PointsSystem,
+    ) -> rusqlite::Result<Championship> {
+        let mut championship = Championship::new(points_system);
+
+        let mut sessions = store.sessions()?;
+        sessions.reverse();
+        for session in sessions {
+            let kind = if session.session_type() == SessionType::Race.name() {
+                RoundKind::Race
+            } else if session.session_type() == SessionType::Race2.name() {
+                RoundKind::Sprint
+            } else {
+                continue;
+            };
+
+            if let Some(results) = store.results_for_session(session.session_id())? {
+                championship.add_round(Some(session.session_id()), kind, results);
+            }
+        }
+
+        Ok(championship)
+    }
+
+    pub fn points_system(&self) -> &PointsSystem {
+        &self.points_system
+    }
+
+    pub fn rounds(&self) -> &[Round] {
+        &self.rounds
+    }
+
+    /// Add the classification of a round, replacing the one of the same
+    /// session if it was already added, e.g. as the game sends it several
+    /// times.
+    pub fn add_round(
+        &mut self,
+        session_id: Option<SessionIdentifier>,
+        kind: RoundKind,
+        results: SessionResults,
+    ) {
+        let round = Round {
+            session_id,
+            kind,
+            results,
+        };
+
+        let existing = self
+            .rounds
+            .iter_mut()
+            .find(|r| session_id.is_some() && r.session_id == session_id);
+        match existing {
+            Some(existing) => *existing = round,
+            None => self.rounds.push(round),
+        }
+    }
+
+    /// Standings of the drivers, ties broken by the number of best results.
+    pub fn driver_standings(&self) -> Vec<DriverStanding> {
+        let mut standings: Vec<DriverStanding> = Vec::new();
+        let mut finishes: Vec<Vec<u16>> = Vec::new();
+
+        for (i, round) in self.rounds.iter().enumerate() {
+            for d in round.results.drivers() {
+                let index = match standings.iter().position(|s| s.name == d.name) {
+                    Some(index) => index,
+                    None => {
+                        standings.push(DriverStanding {
+                            position: 0,
+                            name: d.name.clone(),
+                            team: None,
+                            points: 0,
+                            wins: 0,
+                            podiums: 0,
+                            fastest_laps: 0,
+                            rounds: vec![None; self.rounds.len()],
+                        });
+                        finishes.push(Vec::new());
+                        standings.len() - 1
+                    }
+                };
+
+                let points = self.points_system.points(round.kind, d);
+                let standing = &mut standings[index];
+                standing.team = d.team.or(standing.team);
+                standing.points += points;
+                standing.rounds[i] = Some(points);
+
+                if round.kind == RoundKind::Race && d.result_status == ResultStatus::Finished {
+                    standing.wins += (d.position == 1) as u16;
+                    standing.podiums += (d.position <= 3) as u16;
+                    count_finish(&mut finishes[index], d.position);
+                }
+                if round.kind == RoundKind::Race && d.fastest_lap {
+                    standing.fastest_laps += 1;
+                }
+            }
+        }
+
+        let mut order: Vec<usize> = (0..standings.len()).collect();
+        order.sort_by(|&a, &b| {
+            standings[b]
+                .points
+                .cmp(&standings[a].points)
+                .then_with(|| countback(&finishes[a], &finishes[b]))
+        });
+
+        order
+            .into_iter()
+            .enumerate()
+            .map(|(position, i)| DriverStanding {
+                position: position as u8 + 1,
+                ..standings[i].clone()
+            })
+            .collect()
+    }
+
+    /// Standings of the teams, ties broken by the number of best results.
+    pub fn constructor_standings(&self) -> Vec<ConstructorStanding> {
+        let mut standings: Vec<ConstructorStanding> = Vec::new();
+        let mut finishes: Vec<Vec<u16>> = Vec::new();
+
+        for round in &self.rounds {
+            for d in round.results.drivers() {
+                let team = match d.team {
+                    Some(team) => team,
+                    None => continue,
+                };
+                let index = match standings.iter().position(|s| s.team == team) {
+                    Some(index) => index,
+                    None => {
+                        standings.push(ConstructorStanding {
+                            position: 0,
+                            team,
+                            points: 0,
+                            wins: 0,
+                        });
+                        finishes.push(Vec::new());
+                        standings.len() - 1
+                    }
+                };
+
+                standings[index].points += self.points_system.points(round.kind, d);
+                if round.kind == RoundKind::Race && d.result_status == ResultStatus::Finished {
+                    standings[index].wins += (d.position == 1) as u16;
+                    count_finish(&mut finishes[index], d.position);
+                }
+            }
+        }
+
+        let mut order: Vec<usize> = (0..standings.len()).collect();
+        order.sort_by(|&a, &b| {
+            standings[b]
+                .points
+                .cmp(&standings[a].points)
+                .then_with(|| countback(&finishes[a], &finishes[b]))
+        });
+
+        order
+            .into_iter()
+            .enumerate()
+            .map(|(position, i)| ConstructorStanding {
+                position: position as u8 + 1,
+                ..standings[i].clone()
+            })
+            .collect()
+    }
+}
+
+/// Count a race finish, indexed by position.
+fn count_finish(finishes: &mut Vec<u16>, position: u8) {
+    let index = match position.checked_sub(1) {
+        Some(index) => index as usize,
+        None => return,
+    };
+    if finishes.len() <= index {
+        finishes.resize(index + 1, 0);
+    }
+    finishes[index] += 1;
+}
+
+/// Order of two tied competitors: more wins first, then more second places,
+/// and so on.
+fn countback(a: &[u16], b: &[u16]) -> Ordering {
+    let len = a.len().max(b.len());
+    (0..len)
+        .map(|i| {
+            let a = a.get(i).copied().unwrap_or(0);
+            let b = b.get(i).copied().unwrap_or(0);
+            b.cmp(&a)
+        })
+        .find(|o| *o != Ordering::Equal)
+        .unwrap_or(Ordering::Equal)
+}
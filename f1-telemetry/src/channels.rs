@@ -0,0 +1,243 @@
+//! Numeric telemetry values addressable by a key, e.g. to plot or export any
+//! of them without matching on the packets.
+//!
+//! Each [`Channel`] reads one value of a car from the packet type it comes
+//! from, with its unit and the rate the game sends it at:
+//!
+//! ```
+//! use f1_telemetry::channels::Channel;
+//!
+//! let channel: Channel = "tyre_temp_fl".parse().unwrap();
+//! assert_eq!(channel, Channel::TyreTempFl);
+//! assert_eq!(channel.unit(), "°C");
+//!
+//! let speed_channels = Channel::ALL.iter().filter(|c| c.unit() == "km/h").count();
+//! assert_eq!(speed_channels, 1);
+//! ```
+//!
+//! [`LiveChannels`] adds channels derived from the others with expressions,
+//! e.g. defined in a config file.
+//!
+//! [`Channel`]: ./enum.Channel.html
+//! [`LiveChannels`]: ./derived/struct.LiveChannels.html
+
+use core::fmt;
+use core::str::FromStr;
+
+use crate::packet::{Packet, PacketType};
+
+pub use derived::{DerivedChannel, Expression, ExpressionError, LiveChannels};
+
+pub mod derived;
+
+/// How often the game sends the values of a channel.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum ChannelRate {
+    /// The rate set in the telemetry settings of the game.
+    Menu,
+    /// A fixed number of packets per second.
+    PerSecond(u8),
+}
+
+macro_rules! channels {
+    ($(
+        $source:ident / $packet_type:ident ($data:ident, $rate:expr) {
+            $($variant:ident, $key:expr, $name:expr, $unit:expr, |$d:ident| $value:expr;)*
+        }
+    )*) => {
+        /// A numeric value of a car.
+        ///
+        /// The wheel channels end with the wheel: `_fl` (front left), `_fr`,
+        /// `_rl` and `_rr`. Boolean values read as 0 or 1.
+        #[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+        pub enum Channel {
+            $($($variant,)*)*
+        }
+
+        impl Channel {
+            /// Every channel, grouped by packet type.
+            pub const ALL: &'static [Channel] = &[$($(Channel::$variant,)*)*];
+
+            /// Key of the channel, e.g. `"speed"`.
+            pub fn key(self) -> &'static str {
+                match self {
+                    $($(Channel::$variant => $key,)*)*
+                }
+            }
+
+            /// Human readable name of the channel, e.g. `"Speed"`.
+            pub fn name(self) -> &'static str {
+                match self {
+                    $($(Channel::$variant => $name,)*)*
+                }
+            }
+
+            /// Unit of the values, or an empty string for counts and states.
+            pub fn unit(self) -> &'static str {
+                match self {
+                    $($(Channel::$variant => $unit,)*)*
+                }
+            }
+
+            /// Type of the packets holding the values.
+            pub fn packet_type(self) -> PacketType {
+                match self {
+                    $($(Channel::$variant)|* => PacketType::$packet_type,)*
+                }
+            }
+
+            pub fn rate(self) -> ChannelRate {
+                match self {
+                    $($(Channel::$variant)|* => $rate,)*
+                }
+            }
+
+            /// Value of the channel for the car at `vehicle_index`, if the
+            /// packet holds it.
+            pub fn value(self, packet: &Packet, vehicle_index: usize) -> Option<f32> {
+                match packet {
+                    $(Packet::$source(p) => {
+                        let data = p.$data().get(vehicle_index)?;
+                        match self {
+                            $(Channel::$variant => {
+                                let $d = data;
+                                Some($value as f32)
+                            })*
+                            _ => None,
+                        }
+                    })*
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+channels! {
+    Motion / Motion(motion_data, ChannelRate::Menu) {
+        PositionX, "position_x", "World Position X", "m", |m| m.world_position_x();
+        PositionY, "position_y", "World Position Y", "m", |m| m.world_position_y();
+        PositionZ, "position_z", "World Position Z", "m", |m| m.world_position_z();
+        VelocityX, "velocity_x", "World Velocity X", "m/s", |m| m.world_velocity_x();
+        VelocityY, "velocity_y", "World Velocity Y", "m/s", |m| m.world_velocity_y();
+        VelocityZ, "velocity_z", "World Velocity Z", "m/s", |m| m.world_velocity_z();
+        GForceLateral, "g_lat", "G Force Lateral", "G", |m| m.g_force_lateral();
+        GForceLongitudinal, "g_long", "G Force Longitudinal", "G", |m| m.g_force_longitudinal();
+        GForceVertical, "g_vert", "G Force Vertical", "G", |m| m.g_force_vertical();
+        Yaw, "yaw", "Yaw", "rad", |m| m.yaw();
+        Pitch, "pitch", "Pitch", "rad", |m| m.pitch();
+        Roll, "roll", "Roll", "rad", |m| m.roll();
+    }
+    Lap / LapData(lap_data, ChannelRate::Menu) {
+        LastLapTime, "last_lap_time", "Last Lap Time", "s", |l| l.last_lap_time();
+        CurrentLapTime, "current_lap_time", "Current Lap Time", "s", |l| l.current_lap_time();
+        BestLapTime, "best_lap_time", "Best Lap Time", "s", |l| l.best_lap_time();
+        LapDistance, "lap_distance", "Lap Distance", "m", |l| l.lap_distance();
+        TotalDistance, "total_distance", "Total Distance", "m", |l| l.total_distance();
+        Position, "position", "Position", "", |l| l.car_position();
+        Lap, "lap", "Lap", "", |l| l.current_lap_num();
+        Sector, "sector", "Sector", "", |l| l.sector();
+        Penalties, "penalties", "Penalties", "s", |l| l.penalties();
+    }
+    CarTelemetry / CarTelemetry(car_telemetry_data, ChannelRate::Menu) {
+        Speed, "speed", "Speed", "km/h", |t| t.speed();
+        Throttle, "throttle", "Throttle", "ratio", |t| t.throttle();
+        Brake, "brake", "Brake", "ratio", |t| t.brake();
+        Steer, "steer", "Steer", "ratio", |t| t.steer();
+        Clutch, "clutch", "Clutch", "%", |t| t.clutch();
+        Gear, "gear", "Gear", "", |t| t.gear();
+        EngineRpm, "rpm", "Engine RPM", "rpm", |t| t.engine_rpm();
+        Drs, "drs", "DRS", "", |t| t.drs() as u8;
+        RevLights, "rev_lights", "Rev Lights", "%", |t| t.rev_lights_percent();
+        EngineTemp, "engine_temp", "Engine Temperature", "°C", |t| t.engine_temperature();
+        BrakeTempFl, "brake_temp_fl", "Brake Temperature FL", "°C", |t| t.brakes_temperature().front_left();
+        BrakeTempFr, "brake_temp_fr", "Brake Temperature FR", "°C", |t| t.brakes_temperature().front_right();
+        BrakeTempRl, "brake_temp_rl", "Brake Temperature RL", "°C", |t| t.brakes_temperature().rear_left();
+        BrakeTempRr, "brake_temp_rr", "Brake Temperature RR", "°C", |t| t.brakes_temperature().rear_right();
+        TyreTempFl, "tyre_temp_fl", "Tyre Surface Temperature FL", "°C", |t| t.tyres_surface_temperature().front_left();
+        TyreTempFr, "tyre_temp_fr", "Tyre Surface Temperature FR", "°C", |t| t.tyres_surface_temperature().front_right();
+        TyreTempRl, "tyre_temp_rl", "Tyre Surface Temperature RL", "°C", |t| t.tyres_surface_temperature().rear_left();
+        TyreTempRr, "tyre_temp_rr", "Tyre Surface Temperature RR", "°C", |t| t.tyres_surface_temperature().rear_right();
+        TyreInnerTempFl, "tyre_inner_temp_fl", "Tyre Inner Temperature FL", "°C", |t| t.tyres_inner_temperature().front_left();
+        TyreInnerTempFr, "tyre_inner_temp_fr", "Tyre Inner Temperature FR", "°C", |t| t.tyres_inner_temperature().front_right();
+        TyreInnerTempRl, "tyre_inner_temp_rl", "Tyre Inner Temperature RL", "°C", |t| t.tyres_inner_temperature().rear_left();
+        TyreInnerTempRr, "tyre_inner_temp_rr", "Tyre Inner Temperature RR", "°C", |t| t.tyres_inner_temperature().rear_right();
+        TyrePressureFl, "tyre_pressure_fl", "Tyre Pressure FL", "psi", |t| t.tyre_pressures().front_left();
+        TyrePressureFr, "tyre_pressure_fr", "Tyre Pressure FR", "psi", |t| t.tyre_pressures().front_right();
+        TyrePressureRl, "tyre_pressure_rl", "Tyre Pressure RL", "psi", |t| t.tyre_pressures().rear_left();
+        TyrePressureRr, "tyre_pressure_rr", "Tyre Pressure RR", "psi", |t| t.tyre_pressures().rear_right();
+    }
+    CarStatus / CarStatus(car_status_data, ChannelRate::Menu) {
+        FuelInTank, "fuel", "Fuel In Tank", "kg", |s| s.fuel_in_tank();
+        FuelRemainingLaps, "fuel_laps", "Fuel Remaining Laps", "laps", |s| s.fuel_remaining_laps();
+        BrakeBias, "brake_bias", "Front Brake Bias", "%", |s| s.front_brake_bias();
+        PitLimiter, "pit_limiter", "Pit Limiter", "", |s| s.pit_limiter() as u8;
+        ErsStore, "ers_store", "ERS Store Energy", "J", |s| s.ers_store_energy();
+        ErsDeployed, "ers_deployed", "ERS Deployed This Lap", "J", |s| s.ers_deployed_this_lap();
+        ErsHarvestedMguk, "ers_harvested_mguk", "ERS Harvested This Lap MGU-K", "J", |s| s.ers_harvested_this_lap_mguk();
+        ErsHarvestedMguh, "ers_harvested_mguh", "ERS Harvested This Lap MGU-H", "J", |s| s.ers_harvested_this_lap_mguh();
+    }
+    CarSetups / CarSetups(car_setups, ChannelRate::PerSecond(2)) {
+        FrontWing, "front_wing", "Front Wing", "", |s| s.front_wing();
+        RearWing, "rear_wing", "Rear Wing", "", |s| s.rear_wing();
+        Ballast, "ballast", "Ballast", "", |s| s.ballast();
+        FuelLoad, "fuel_load", "Fuel Load", "kg", |s| s.fuel_load();
+    }
+}
+
+impl Channel {
+    /// Channel of a key, e.g. `"speed"`.
+    pub fn from_key(key: &str) -> Option<Channel> {
+        Channel::ALL.iter().copied().find(|c| c.key() == key)
+    }
+
+    /// Position of the channel in [`ALL`].
+    ///
+    /// [`ALL`]: #associatedconstant.ALL
+    pub(crate) fn index(self) -> usize {
+        self as usize
+    }
+
+    /// Channels held by a type of packet.
+    pub fn of(packet_type: PacketType) -> impl Iterator<Item = Channel> {
+        Channel::ALL
+            .iter()
+            .copied()
+            .filter(move |c| c.packet_type() == packet_type)
+    }
+
+    /// Values of every channel the packet holds for the car at
+    /// `vehicle_index`.
+    pub fn values(
+        packet: &Packet,
+        vehicle_index: usize,
+    ) -> impl Iterator<Item = (Channel, f32)> + '_ {
+        Channel::ALL
+            .iter()
+            .filter_map(move |c| Some((*c, c.value(packet, vehicle_index)?)))
+    }
+}
+
+impl fmt::Display for Channel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.key())
+    }
+}
+
+/// Error of parsing an unknown channel key.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct UnknownChannel;
+
+impl fmt::Display for UnknownChannel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Unknown channel")
+    }
+}
+
+impl FromStr for Channel {
+    type Err = UnknownChannel;
+
+    fn from_str(key: &str) -> Result<Channel, UnknownChannel> {
+        Channel::from_key(key).ok_or(UnknownChannel)
+    }
+}
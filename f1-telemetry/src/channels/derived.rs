@@ -0,0 +1,462 @@
+use alloc::boxed::Box;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::channels::Channel;
+use crate::packet::Packet;
+
+/// Error of parsing the definition of a derived channel.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExpressionError {
+    /// The definition is not of the form `name = expression`.
+    MissingName,
+    /// The name is not made of letters, digits and underscores.
+    InvalidName(String),
+    /// The name is already the one of a channel.
+    DuplicateName(String),
+    /// The expression refers to a channel that is not defined.
+    UnknownChannel(String),
+    /// The expression calls a function that is not defined, or with the wrong
+    /// number of arguments.
+    UnknownFunction(String),
+    /// Unexpected character, at a byte offset of the expression.
+    UnexpectedChar(char, usize),
+    /// The expression ends early.
+    UnexpectedEnd,
+}
+
+impl fmt::Display for ExpressionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExpressionError::MissingName => write!(f, "Expected `name = expression`"),
+            ExpressionError::InvalidName(name) => write!(f, "Invalid channel name: {}", name),
+            ExpressionError::DuplicateName(name) => {
+                write!(f, "Channel is already defined: {}", name)
+            }
+            ExpressionError::UnknownChannel(name) => write!(f, "Unknown channel: {}", name),
+            ExpressionError::UnknownFunction(name) => write!(f, "Unknown function: {}", name),
+            ExpressionError::UnexpectedChar(c, offset) => {
+                write!(f, "Unexpected character {:?} at {}", c, offset)
+            }
+            ExpressionError::UnexpectedEnd => write!(f, "Unexpected end of expression"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ExpressionError {}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Op {
+    Add,
+    Sub,
+    Mul,
+    Div,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Function {
+    Abs,
+    Min,
+    Max,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Node {
+    Number(f32),
+    Channel(Channel),
+    /// Index of a derived channel defined before.
+    Derived(usize),
+    Neg(Box<Node>),
+    Binary(Op, Box<Node>, Box<Node>),
+    Call(Function, Vec<Node>),
+}
+
+impl Node {
+    fn evaluate(&self, values: &[Option<f32>], derived: &[Option<f32>]) -> Option<f32> {
+        match self {
+            Node::Number(n) => Some(*n),
+            Node::Channel(c) => values[c.index()],
+            Node::Derived(i) => derived[*i],
+            Node::Neg(node) => Some(-node.evaluate(values, derived)?),
+            Node::Binary(op, a, b) => {
+                let (a, b) = (a.evaluate(values, derived)?, b.evaluate(values, derived)?);
+                let value = match op {
+                    Op::Add => a + b,
+                    Op::Sub => a - b,
+                    Op::Mul => a * b,
+                    Op::Div => a / b,
+                };
+                Some(value).filter(|v| v.is_finite())
+            }
+            Node::Call(function, args) => {
+                let mut args = args.iter().map(|a| a.evaluate(values, derived));
+                match function {
+                    Function::Abs => {
+                        let v = args.next()??;
+                        Some(if v < 0.0 { -v } else { v })
+                    }
+                    Function::Min => args.try_fold(f32::INFINITY, |m, v| Some(m.min(v?))),
+                    Function::Max => args.try_fold(f32::NEG_INFINITY, |m, v| Some(m.max(v?))),
+                }
+            }
+        }
+    }
+}
+
+/// Arithmetic expression of channels.
+///
+/// ## Specification
+/// ```text
+/// Numbers:    e.g. 100 or 0.5
+/// Channels:   keys of the native channels, e.g. brake_bias, and names of the
+///             derived channels defined before
+/// Operators:  + - * / and parentheses, with the usual precedence
+/// Functions:  abs(x), min(x, y, ...), max(x, y, ...)
+/// ```
+///
+/// An expression has no value while one of its channels has none, or when it
+/// is not finite, e.g. divided by 0.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Expression(Node);
+
+struct Parser<'a> {
+    source: &'a str,
+    offset: usize,
+    derived: &'a [DerivedChannel],
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&mut self) -> Option<char> {
+        self.source[self.offset..]
+            .chars()
+            .find(|c| !c.is_whitespace())
+    }
+
+    /// Consume the next non-blank character.
+    fn next(&mut self) -> Option<char> {
+        let rest = &self.source[self.offset..];
+        let (i, c) = rest.char_indices().find(|(_, c)| !c.is_whitespace())?;
+        self.offset += i + c.len_utf8();
+        Some(c)
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), ExpressionError> {
+        let offset = self.offset;
+        match self.next() {
+            Some(c) if c == expected => Ok(()),
+            Some(c) => Err(ExpressionError::UnexpectedChar(c, offset)),
+            None => Err(ExpressionError::UnexpectedEnd),
+        }
+    }
+
+    fn expression(&mut self) -> Result<Node, ExpressionError> {
+        let mut node = self.term()?;
+        loop {
+            let op = match self.peek() {
+                Some('+') => Op::Add,
+                Some('-') => Op::Sub,
+                _ => return Ok(node),
+            };
+            self.next();
+            node = Node::Binary(op, Box::new(node), Box::new(self.term()?));
+        }
+    }
+
+    fn term(&mut self) -> Result<Node, ExpressionError> {
+        let mut node = self.unary()?;
+        loop {
+            let op = match self.peek() {
+                Some('*') => Op::Mul,
+                Some('/') => Op::Div,
+                _ => return Ok(node),
+            };
+            self.next();
+            node = Node::Binary(op, Box::new(node), Box::new(self.unary()?));
+        }
+    }
+
+    fn unary(&mut self) -> Result<Node, ExpressionError> {
+        if self.peek() == Some('-') {
+            self.next();
+            return Ok(Node::Neg(Box::new(self.unary()?)));
+        }
+
+        self.primary()
+    }
+
+    fn primary(&mut self) -> Result<Node, ExpressionError> {
+        // Skip the blanks to point at the token.
+        let blanks =
+            self.source[self.offset..].len() - self.source[self.offset..].trim_start().len();
+        self.offset += blanks;
+        let start = self.offset;
+
+        match self.peek() {
+            Some('(') => {
+                self.next();
+                let node = self.expression()?;
+                self.expect(')')?;
+                Ok(node)
+            }
+            Some(c) if c.is_ascii_digit() || c == '.' => {
+                let token = self.token(|c| c.is_ascii_digit() || c == '.');
+                token
+                    .parse()
+                    .map(Node::Number)
+                    .map_err(|_| ExpressionError::UnexpectedChar(c, start))
+            }
+            Some(c) if is_name_char(c) => {
+                let name = self.token(is_name_char);
+                if self.peek() == Some('(') {
+                    self.call(name)
+                } else {
+                    self.channel(name)
+                }
+            }
+            Some(c) => Err(ExpressionError::UnexpectedChar(c, start)),
+            None => Err(ExpressionError::UnexpectedEnd),
+        }
+    }
+
+    fn token<F: Fn(char) -> bool>(&mut self, accepts: F) -> &'a str {
+        let rest = &self.source[self.offset..];
+        let len = rest.find(|c| !accepts(c)).unwrap_or(rest.len());
+        self.offset += len;
+        &rest[..len]
+    }
+
+    fn call(&mut self, name: &str) -> Result<Node, ExpressionError> {
+        self.expect('(')?;
+        let mut args = vec![self.expression()?];
+        while self.peek() == Some(',') {
+            self.next();
+            args.push(self.expression()?);
+        }
+        self.expect(')')?;
+
+        let function = match (name, args.len()) {
+            ("abs", 1) => Function::Abs,
+            ("min", _) => Function::Min,
+            ("max", _) => Function::Max,
+            _ => return Err(ExpressionError::UnknownFunction(name.to_string())),
+        };
+
+        Ok(Node::Call(function, args))
+    }
+
+    fn channel(&self, name: &str) -> Result<Node, ExpressionError> {
+        if let Some(i) = self.derived.iter().position(|d| d.name == name) {
+            return Ok(Node::Derived(i));
+        }
+
+        Channel::from_key(name)
+            .map(Node::Channel)
+            .ok_or_else(|| ExpressionError::UnknownChannel(name.to_string()))
+    }
+}
+
+fn is_name_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
+
+impl Expression {
+    /// Parse an expression of native channels only.
+    pub fn parse(source: &str) -> Result<Expression, ExpressionError> {
+        Expression::parse_with(source, &[])
+    }
+
+    fn parse_with(source: &str, derived: &[DerivedChannel]) -> Result<Expression, ExpressionError> {
+        let mut parser = Parser {
+            source,
+            offset: 0,
+            derived,
+        };
+
+        let node = parser.expression()?;
+        let offset = parser.offset;
+        match parser.next() {
+            Some(c) => Err(ExpressionError::UnexpectedChar(c, offset)),
+            None => Ok(Expression(node)),
+        }
+    }
+
+    /// Native channels the expression reads, without the derived ones.
+    pub fn channels(&self) -> Vec<Channel> {
+        fn visit(node: &Node, channels: &mut Vec<Channel>) {
+            match node {
+                Node::Channel(c) if !channels.contains(c) => channels.push(*c),
+                Node::Neg(node) => visit(node, channels),
+                Node::Binary(_, a, b) => {
+                    visit(a, channels);
+                    visit(b, channels);
+                }
+                Node::Call(_, args) => args.iter().for_each(|a| visit(a, channels)),
+                _ => {}
+            }
+        }
+
+        let mut channels = Vec::new();
+        visit(&self.0, &mut channels);
+        channels
+    }
+}
+
+/// A channel computed from other channels, defined as `name = expression`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DerivedChannel {
+    name: String,
+    expression: Expression,
+}
+
+impl DerivedChannel {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn expression(&self) -> &Expression {
+        &self.expression
+    }
+}
+
+/// Latest values of the native and derived channels of a car.
+///
+/// The derived channels are defined one per line, e.g. from a config file,
+/// and can use the ones defined before them:
+///
+/// ```
+/// use f1_telemetry::channels::LiveChannels;
+///
+/// let mut channels = LiveChannels::new();
+/// channels
+///     .define_all(
+///         "# Braking force on the front axle
+///          brake_front = brake * brake_bias / 100
+///          brake_rear = brake - brake_front",
+///     )
+///     .unwrap();
+///
+/// assert_eq!(channels.get("brake_rear"), None);
+/// ```
+#[derive(Debug, Clone)]
+pub struct LiveChannels {
+    values: Vec<Option<f32>>,
+    derived: Vec<DerivedChannel>,
+    derived_values: Vec<Option<f32>>,
+}
+
+impl Default for LiveChannels {
+    fn default() -> Self {
+        LiveChannels {
+            values: vec![None; Channel::ALL.len()],
+            derived: Vec::new(),
+            derived_values: Vec::new(),
+        }
+    }
+}
+
+impl LiveChannels {
+    pub fn new() -> LiveChannels {
+        LiveChannels::default()
+    }
+
+    /// Define a derived channel as `name = expression`.
+    pub fn define(&mut self, definition: &str) -> Result<(), ExpressionError> {
+        let (name, expression) = definition
+            .split_once('=')
+            .ok_or(ExpressionError::MissingName)?;
+        let name = name.trim();
+
+        if name.is_empty() {
+            return Err(ExpressionError::MissingName);
+        }
+        if !name.chars().all(is_name_char) {
+            return Err(ExpressionError::InvalidName(name.to_string()));
+        }
+        if Channel::from_key(name).is_some() || self.derived.iter().any(|d| d.name == name) {
+            return Err(ExpressionError::DuplicateName(name.to_string()));
+        }
+
+        let expression = Expression::parse_with(expression, &self.derived)?;
+        self.derived.push(DerivedChannel {
+            name: name.to_string(),
+            expression,
+        });
+        self.derived_values.push(None);
+        self.evaluate();
+
+        Ok(())
+    }
+
+    /// Define a derived channel per line, skipping the blank lines and the
+    /// comments starting with `#`.
+    pub fn define_all(&mut self, definitions: &str) -> Result<(), ExpressionError> {
+        definitions
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .try_for_each(|line| self.define(line))
+    }
+
+    pub fn derived(&self) -> &[DerivedChannel] {
+        &self.derived
+    }
+
+    /// Forget the values, e.g. when a new session starts.
+    pub fn reset(&mut self) {
+        self.values.iter_mut().for_each(|v| *v = None);
+        self.derived_values.iter_mut().for_each(|v| *v = None);
+    }
+
+    /// Record the values of the car at `vehicle_index` that the packet holds,
+    /// and compute the derived channels again.
+    pub fn update(&mut self, packet: &Packet, vehicle_index: usize) {
+        let mut updated = false;
+        for (channel, value) in Channel::values(packet, vehicle_index) {
+            self.values[channel.index()] = Some(value);
+            updated = true;
+        }
+
+        if updated {
+            self.evaluate();
+        }
+    }
+
+    fn evaluate(&mut self) {
+        for i in 0..self.derived.len() {
+            let (before, _) = self.derived_values.split_at(i);
+            let value = self.derived[i].expression.0.evaluate(&self.values, before);
+            self.derived_values[i] = value;
+        }
+    }
+
+    /// Value of a native or derived channel.
+    pub fn get(&self, key: &str) -> Option<f32> {
+        match Channel::from_key(key) {
+            Some(channel) => self.values[channel.index()],
+            None => {
+                let i = self.derived.iter().position(|d| d.name == key)?;
+                self.derived_values[i]
+            }
+        }
+    }
+
+    /// Keys of the native channels, then names of the derived ones, e.g. for
+    /// the header of a CSV file.
+    pub fn keys(&self) -> impl Iterator<Item = &str> + '_ {
+        Channel::ALL
+            .iter()
+            .map(|c| c.key())
+            .chain(self.derived.iter().map(|d| d.name.as_str()))
+    }
+
+    /// Values of every channel in the order of the keys.
+    pub fn values(&self) -> impl Iterator<Item = Option<f32>> + '_ {
+        self.values
+            .iter()
+            .chain(self.derived_values.iter())
+            .copied()
+    }
+}
@@ -0,0 +1,199 @@
+//! Time-shifted playback of the live stream.
+//!
+//! A [`Dvr`] keeps the packets received in the last minutes in memory, and
+//! each [`Playback`] reads them back on its own: following the live stream,
+//! paused, or some time behind it after a rewind. Dashboards can show instant
+//! replays this way while the stream is still being recorded.
+//!
+//! [`Dvr`]: ./struct.Dvr.html
+//! [`Playback`]: ./struct.Playback.html
+
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::packet::Packet;
+
+/// Ring buffer of the packets received during the retention period.
+#[derive(Debug)]
+pub struct Dvr {
+    retention: Duration,
+    records: VecDeque<(Instant, Arc<Packet>)>,
+    /// Index of the oldest record since the buffer was created, so that the
+    /// positions of the playbacks stay valid as records are dropped.
+    first_index: u64,
+}
+
+impl Dvr {
+    /// Buffer keeping the packets received in the last `retention`.
+    pub fn new(retention: Duration) -> Dvr {
+        Dvr {
+            retention,
+            records: VecDeque::new(),
+            first_index: 0,
+        }
+    }
+
+    pub fn retention(&self) -> Duration {
+        self.retention
+    }
+
+    /// Record a packet received now, dropping the ones older than the retention.
+    pub fn push<P: Into<Arc<Packet>>>(&mut self, packet: P) {
+        let now = Instant::now();
+
+        self.records.push_back((now, packet.into()));
+        self.prune(now);
+    }
+
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    /// Time between the oldest packet held and now.
+    pub fn duration(&self) -> Duration {
+        self.records
+            .front()
+            .map(|(time, _)| time.elapsed())
+            .unwrap_or_default()
+    }
+
+    pub fn clear(&mut self) {
+        self.first_index += self.records.len() as u64;
+        self.records.clear();
+    }
+
+    /// A playback following the live stream from now on.
+    pub fn playback(&self) -> Playback {
+        Playback {
+            position: self.end_index(),
+            delay: Duration::default(),
+            paused_at: None,
+        }
+    }
+
+    fn prune(&mut self, now: Instant) {
+        while let Some((time, _)) = self.records.front() {
+            if now.duration_since(*time) <= self.retention {
+                break;
+            }
+
+            self.records.pop_front();
+            self.first_index += 1;
+        }
+    }
+
+    fn end_index(&self) -> u64 {
+        self.first_index + self.records.len() as u64
+    }
+
+    /// Index of the first record received after `time`.
+    fn index_after(&self, time: Instant) -> u64 {
+        self.first_index + self.records.partition_point(|(t, _)| *t <= time) as u64
+    }
+
+    fn get(&self, index: u64) -> Option<&(Instant, Arc<Packet>)> {
+        let offset = index.checked_sub(self.first_index)?;
+        self.records.get(offset as usize)
+    }
+}
+
+/// Position of a consumer in a [`Dvr`], some delay behind the live stream.
+///
+/// Seeking skips the packets in between instead of replaying them, so
+/// consumers keeping state, e.g. the current lap, may have to reset it.
+///
+/// [`Dvr`]: ./struct.Dvr.html
+#[derive(Debug, Clone)]
+pub struct Playback {
+    /// Index of the next record to return.
+    position: u64,
+    /// Delay behind the live stream, not counting the current pause.
+    delay: Duration,
+    paused_at: Option<Instant>,
+}
+
+impl Playback {
+    /// The next packet of the playback, or `None` when caught up with its
+    /// delay or paused.
+    pub fn next(&mut self, dvr: &Dvr) -> Option<Arc<Packet>> {
+        if self.paused_at.is_some() {
+            return None;
+        }
+
+        // Records dropped from the buffer are skipped.
+        self.position = self.position.max(dvr.first_index);
+
+        let playback_time = Instant::now().checked_sub(self.delay)?;
+        let (time, packet) = dvr.get(self.position)?;
+        if *time > playback_time {
+            return None;
+        }
+
+        self.position += 1;
+        Some(Arc::clone(packet))
+    }
+
+    /// Whether the playback shows the packets as they are received.
+    pub fn is_live(&self) -> bool {
+        self.paused_at.is_none() && self.delay == Duration::default()
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused_at.is_some()
+    }
+
+    /// How far behind the live stream the playback is.
+    pub fn delay(&self) -> Duration {
+        self.delay + self.paused_at.map(|t| t.elapsed()).unwrap_or_default()
+    }
+
+    pub fn pause(&mut self) {
+        if self.paused_at.is_none() {
+            self.paused_at = Some(Instant::now());
+        }
+    }
+
+    /// Resume from where the playback was paused, further behind the live stream.
+    pub fn resume(&mut self) {
+        if let Some(paused_at) = self.paused_at.take() {
+            self.delay += paused_at.elapsed();
+        }
+    }
+
+    /// Go back in time, up to the oldest packet held by the buffer.
+    pub fn rewind(&mut self, dvr: &Dvr, by: Duration) {
+        let delay = (self.delay() + by).min(dvr.duration());
+        self.seek(dvr, delay);
+    }
+
+    /// Go forward in time, up to the live stream.
+    pub fn fast_forward(&mut self, dvr: &Dvr, by: Duration) {
+        let delay = self.delay().saturating_sub(by);
+        self.seek(dvr, delay);
+    }
+
+    /// Catch up with the live stream, resuming the playback if paused.
+    pub fn go_live(&mut self, dvr: &Dvr) {
+        self.paused_at = None;
+        self.seek(dvr, Duration::default());
+    }
+
+    fn seek(&mut self, dvr: &Dvr, delay: Duration) {
+        let now = Instant::now();
+
+        self.delay = delay;
+        if self.paused_at.is_some() {
+            self.paused_at = Some(now);
+        }
+
+        self.position = match now.checked_sub(delay) {
+            Some(time) => dvr.index_after(time),
+            None => dvr.first_index,
+        };
+    }
+}
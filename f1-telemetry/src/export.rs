@@ -0,0 +1,7 @@
+//! Exports recorded telemetry to files for other analysis tools.
+
+#[cfg(feature = "arrow")]
+pub mod arrow;
+pub mod motec;
+pub mod pcapng;
+pub mod results;
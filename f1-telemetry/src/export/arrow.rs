@@ -0,0 +1,162 @@
+//! Apache Arrow record batches and Parquet files.
+//!
+//! Telemetry samples are written with one row per sample and one typed column
+//! per channel (see [`schema`]), tagged with the session they were recorded in,
+//! so that the files can be loaded directly into pandas or polars.
+//!
+//! Enabled with the `arrow` feature.
+//!
+//! [`schema`]: ./fn.schema.html
+
+use arrow::array::{ArrayRef, BooleanArray, Float32Array, Int8Array, UInt64Array, UInt8Array};
+use arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+use arrow::error::ArrowError;
+use arrow::record_batch::RecordBatch;
+use parquet::arrow::ArrowWriter;
+use parquet::errors::ParquetError;
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::packet::header::SessionIdentifier;
+use crate::transform::sample::TelemetrySample;
+
+/// Schema of the telemetry record batches.
+///
+/// ## Specification
+/// ```text
+/// session_uid:          UInt64   Unique identifier of the session
+/// session_time:         Float32  Session timestamp in seconds
+/// lap:                  UInt8    Lap number
+/// lap_distance:         Float32  Distance around the lap in metres
+/// speed:                Float32  Speed of car in kilometres per hour
+/// throttle:             Float32  Amount of throttle applied (0.0 to 1.0)
+/// brake:                Float32  Amount of brake applied (0.0 to 1.0)
+/// steer:                Float32  Steering (-1.0 to 1.0)
+/// gear:                 Int8     Gear selected (1-8, N=0, R=-1)
+/// engine_rpm:           Float32  Engine RPM
+/// drs:                  Boolean  Whether DRS is open
+/// world_position_x:     Float32  World space X position
+/// world_position_y:     Float32  World space Y position
+/// world_position_z:     Float32  World space Z position
+/// g_force_lateral:      Float32  Lateral g-force
+/// g_force_vertical:     Float32  Vertical g-force
+/// g_force_longitudinal: Float32  Longitudinal g-force
+/// ```
+pub fn schema() -> SchemaRef {
+    Arc::new(Schema::new(vec![
+        Field::new("session_uid", DataType::UInt64, false),
+        Field::new("session_time", DataType::Float32, false),
+        Field::new("lap", DataType::UInt8, false),
+        Field::new("lap_distance", DataType::Float32, false),
+        Field::new("speed", DataType::Float32, false),
+        Field::new("throttle", DataType::Float32, false),
+        Field::new("brake", DataType::Float32, false),
+        Field::new("steer", DataType::Float32, false),
+        Field::new("gear", DataType::Int8, false),
+        Field::new("engine_rpm", DataType::Float32, false),
+        Field::new("drs", DataType::Boolean, false),
+        Field::new("world_position_x", DataType::Float32, false),
+        Field::new("world_position_y", DataType::Float32, false),
+        Field::new("world_position_z", DataType::Float32, false),
+        Field::new("g_force_lateral", DataType::Float32, false),
+        Field::new("g_force_vertical", DataType::Float32, false),
+        Field::new("g_force_longitudinal", DataType::Float32, false),
+    ]))
+}
+
+/// Record batch of the samples of a session.
+pub fn record_batch(
+    session: SessionIdentifier,
+    samples: &[TelemetrySample],
+) -> Result<RecordBatch, ArrowError> {
+    let f32_column = |f: fn(&TelemetrySample) -> f32| -> ArrayRef {
+        Arc::new(Float32Array::from(
+            samples.iter().map(f).collect::<Vec<f32>>(),
+        ))
+    };
+
+    let columns: Vec<ArrayRef> = vec![
+        Arc::new(UInt64Array::from(vec![session.value(); samples.len()])),
+        f32_column(|s| s.session_time()),
+        Arc::new(UInt8Array::from(
+            samples.iter().map(|s| s.lap()).collect::<Vec<u8>>(),
+        )),
+        f32_column(|s| s.lap_distance()),
+        f32_column(|s| s.speed()),
+        f32_column(|s| s.throttle()),
+        f32_column(|s| s.brake()),
+        f32_column(|s| s.steer()),
+        Arc::new(Int8Array::from(
+            samples.iter().map(|s| s.gear()).collect::<Vec<i8>>(),
+        )),
+        f32_column(|s| s.engine_rpm()),
+        Arc::new(BooleanArray::from(
+            samples.iter().map(|s| s.drs()).collect::<Vec<bool>>(),
+        )),
+        f32_column(|s| s.world_position().x()),
+        f32_column(|s| s.world_position().y()),
+        f32_column(|s| s.world_position().z()),
+        f32_column(|s| s.g_force().x()),
+        f32_column(|s| s.g_force().y()),
+        f32_column(|s| s.g_force().z()),
+    ];
+
+    RecordBatch::try_new(schema(), columns)
+}
+
+/// Writes telemetry samples to a Parquet file, one row group per call to
+/// [`write`].
+///
+/// The file is only valid once [`close`] has been called.
+///
+/// [`write`]: #method.write
+/// [`close`]: #method.close
+pub struct ParquetExporter<W: Write + Send> {
+    writer: ArrowWriter<W>,
+    rows: usize,
+}
+
+impl ParquetExporter<File> {
+    /// Create the Parquet file at `path`, replacing it if it exists.
+    pub fn create<P: AsRef<Path>>(path: P) -> Result<ParquetExporter<File>, ParquetError> {
+        ParquetExporter::new(File::create(path)?)
+    }
+}
+
+impl<W: Write + Send> ParquetExporter<W> {
+    pub fn new(writer: W) -> Result<ParquetExporter<W>, ParquetError> {
+        Ok(ParquetExporter {
+            writer: ArrowWriter::try_new(writer, schema(), None)?,
+            rows: 0,
+        })
+    }
+
+    /// Write the samples of a session.
+    pub fn write(
+        &mut self,
+        session: SessionIdentifier,
+        samples: &[TelemetrySample],
+    ) -> Result<(), ParquetError> {
+        if samples.is_empty() {
+            return Ok(());
+        }
+
+        self.writer.write(&record_batch(session, samples)?)?;
+        self.rows += samples.len();
+
+        Ok(())
+    }
+
+    /// Number of rows written so far.
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// Write the file footer.
+    pub fn close(self) -> Result<(), ParquetError> {
+        self.writer.close()?;
+        Ok(())
+    }
+}
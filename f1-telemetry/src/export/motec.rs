@@ -0,0 +1,335 @@
+//! MoTeC i2 log files.
+//!
+//! [`MotecLog`] writes channels in the subset of the LD format that MoTeC i2
+//! reads: a file header with the session details, an event block, and for
+//! every channel a metadata block followed by its samples. All channels are
+//! written as 32-bit floats, sampled at a fixed frequency.
+//!
+//! [`MotecLog`]: ./struct.MotecLog.html
+
+use byteorder::{LittleEndian, WriteBytesExt};
+use std::io::{self, Write};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::transform::sample::TelemetrySample;
+
+const LD_MARKER: u32 = 0x40;
+const PRO_LOGGING_MAGIC: u32 = 0xC81A4;
+const HEADER_SIZE: u32 = 1762;
+const EVENT_SIZE: u32 = 64 + 64 + 1024 + 2;
+const CHANNEL_META_SIZE: u32 = 124;
+
+/// Data type of the samples: 32-bit float.
+const DATA_TYPE_FLOAT: u16 = 0x07;
+const DATA_SIZE_FLOAT: u16 = 4;
+
+/// A channel of a MoTeC log, sampled at a fixed frequency.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MotecChannel {
+    name: String,
+    short_name: String,
+    unit: String,
+    frequency: u16,
+    data: Vec<f32>,
+}
+
+impl MotecChannel {
+    /// A channel sampled `frequency` times per second. The name is truncated to
+    /// 32 bytes and the unit to 12 bytes.
+    pub fn new(name: &str, unit: &str, frequency: u16, data: Vec<f32>) -> MotecChannel {
+        MotecChannel {
+            name: name.to_string(),
+            short_name: String::new(),
+            unit: unit.to_string(),
+            frequency,
+            data,
+        }
+    }
+
+    /// Abbreviation of the name, truncated to 8 bytes.
+    pub fn with_short_name(mut self, short_name: &str) -> MotecChannel {
+        self.short_name = short_name.to_string();
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn unit(&self) -> &str {
+        &self.unit
+    }
+
+    pub fn frequency(&self) -> u16 {
+        self.frequency
+    }
+
+    pub fn data(&self) -> &[f32] {
+        &self.data
+    }
+}
+
+/// A MoTeC i2 log (`.ld` file).
+///
+/// The date and time of the log default to the current UTC time.
+#[derive(Debug, Clone)]
+pub struct MotecLog {
+    driver: String,
+    vehicle: String,
+    venue: String,
+    event: String,
+    session: String,
+    comment: String,
+    date: String,
+    time: String,
+    channels: Vec<MotecChannel>,
+}
+
+impl Default for MotecLog {
+    fn default() -> MotecLog {
+        MotecLog::new()
+    }
+}
+
+impl MotecLog {
+    pub fn new() -> MotecLog {
+        let (date, time) = current_date_time();
+
+        MotecLog {
+            driver: String::new(),
+            vehicle: String::new(),
+            venue: String::new(),
+            event: String::new(),
+            session: String::new(),
+            comment: String::new(),
+            date,
+            time,
+            channels: Vec::new(),
+        }
+    }
+
+    /// Log of the telemetry channels of samples taken at a fixed `frequency`,
+    /// e.g. resampled on a [`ResampleGrid::rate`] grid.
+    ///
+    /// [`ResampleGrid::rate`]: ../../transform/downsample/enum.ResampleGrid.html#method.rate
+    pub fn from_samples(samples: &[TelemetrySample], frequency: u16) -> MotecLog {
+        let channel = |name: &str, short_name: &str, unit: &str, f: fn(&TelemetrySample) -> f32| {
+            MotecChannel::new(name, unit, frequency, samples.iter().map(f).collect())
+                .with_short_name(short_name)
+        };
+
+        MotecLog::new()
+            .with_channel(channel("Ground Speed", "Speed", "km/h", |s| s.speed()))
+            .with_channel(channel("Throttle Pos", "Throttle", "%", |s| {
+                s.throttle() * 100.0
+            }))
+            .with_channel(channel("Brake Pos", "Brake", "%", |s| s.brake() * 100.0))
+            .with_channel(channel("Steered Angle", "Steer", "ratio", |s| s.steer()))
+            .with_channel(channel("Gear", "Gear", "", |s| s.gear() as f32))
+            .with_channel(channel("Engine RPM", "RPM", "rpm", |s| s.engine_rpm()))
+            .with_channel(channel("DRS", "DRS", "", |s| s.drs() as u8 as f32))
+            .with_channel(channel("Lap Number", "Lap", "", |s| s.lap() as f32))
+            .with_channel(channel("Lap Distance", "LapDist", "m", |s| {
+                s.lap_distance()
+            }))
+            .with_channel(channel("G Force Lat", "GLat", "G", |s| s.g_force().x()))
+            .with_channel(channel("G Force Vert", "GVert", "G", |s| s.g_force().y()))
+            .with_channel(channel("G Force Long", "GLong", "G", |s| s.g_force().z()))
+            .with_channel(channel("Car Pos X", "PosX", "m", |s| {
+                s.world_position().x()
+            }))
+            .with_channel(channel("Car Pos Y", "PosY", "m", |s| {
+                s.world_position().y()
+            }))
+            .with_channel(channel("Car Pos Z", "PosZ", "m", |s| {
+                s.world_position().z()
+            }))
+    }
+
+    pub fn with_driver(mut self, driver: &str) -> MotecLog {
+        self.driver = driver.to_string();
+        self
+    }
+
+    pub fn with_vehicle(mut self, vehicle: &str) -> MotecLog {
+        self.vehicle = vehicle.to_string();
+        self
+    }
+
+    pub fn with_venue(mut self, venue: &str) -> MotecLog {
+        self.venue = venue.to_string();
+        self
+    }
+
+    pub fn with_event(mut self, event: &str) -> MotecLog {
+        self.event = event.to_string();
+        self
+    }
+
+    pub fn with_session(mut self, session: &str) -> MotecLog {
+        self.session = session.to_string();
+        self
+    }
+
+    pub fn with_comment(mut self, comment: &str) -> MotecLog {
+        self.comment = comment.to_string();
+        self
+    }
+
+    /// Date (`dd/mm/yyyy`) and time (`hh:mm:ss`) of the log.
+    pub fn with_date_time(mut self, date: &str, time: &str) -> MotecLog {
+        self.date = date.to_string();
+        self.time = time.to_string();
+        self
+    }
+
+    pub fn with_channel(mut self, channel: MotecChannel) -> MotecLog {
+        self.channels.push(channel);
+        self
+    }
+
+    pub fn add_channel(&mut self, channel: MotecChannel) {
+        self.channels.push(channel);
+    }
+
+    pub fn channels(&self) -> &[MotecChannel] {
+        &self.channels
+    }
+
+    /// Write the log in the LD format.
+    pub fn write<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        let event_ptr = HEADER_SIZE;
+        let meta_ptr = event_ptr + EVENT_SIZE;
+        let data_ptr = meta_ptr + CHANNEL_META_SIZE * self.channels.len() as u32;
+
+        self.write_header(writer, meta_ptr, data_ptr, event_ptr)?;
+        self.write_event(writer)?;
+
+        let mut channel_data_ptr = data_ptr;
+        for (i, channel) in self.channels.iter().enumerate() {
+            let ptr = meta_ptr + CHANNEL_META_SIZE * i as u32;
+            let prev_ptr = if i == 0 { 0 } else { ptr - CHANNEL_META_SIZE };
+            let next_ptr = if i + 1 == self.channels.len() {
+                0
+            } else {
+                ptr + CHANNEL_META_SIZE
+            };
+
+            writer.write_u32::<LittleEndian>(prev_ptr)?;
+            writer.write_u32::<LittleEndian>(next_ptr)?;
+            writer.write_u32::<LittleEndian>(channel_data_ptr)?;
+            writer.write_u32::<LittleEndian>(channel.data.len() as u32)?;
+            writer.write_u16::<LittleEndian>(0x2EE1 + i as u16)?;
+            writer.write_u16::<LittleEndian>(DATA_TYPE_FLOAT)?;
+            writer.write_u16::<LittleEndian>(DATA_SIZE_FLOAT)?;
+            writer.write_u16::<LittleEndian>(channel.frequency)?;
+            // Shift, multiplier, scale and decimal places: the raw value as is.
+            writer.write_i16::<LittleEndian>(0)?;
+            writer.write_i16::<LittleEndian>(1)?;
+            writer.write_i16::<LittleEndian>(1)?;
+            writer.write_i16::<LittleEndian>(0)?;
+            write_str(writer, &channel.name, 32)?;
+            write_str(writer, &channel.short_name, 8)?;
+            write_str(writer, &channel.unit, 12)?;
+            writer.write_all(&[0; 40])?;
+
+            channel_data_ptr += DATA_SIZE_FLOAT as u32 * channel.data.len() as u32;
+        }
+
+        for channel in &self.channels {
+            for value in &channel.data {
+                writer.write_f32::<LittleEndian>(*value)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn write_header<W: Write>(
+        &self,
+        writer: &mut W,
+        meta_ptr: u32,
+        data_ptr: u32,
+        event_ptr: u32,
+    ) -> io::Result<()> {
+        writer.write_u32::<LittleEndian>(LD_MARKER)?;
+        writer.write_all(&[0; 4])?;
+        writer.write_u32::<LittleEndian>(meta_ptr)?;
+        writer.write_u32::<LittleEndian>(data_ptr)?;
+        writer.write_all(&[0; 20])?;
+        writer.write_u32::<LittleEndian>(event_ptr)?;
+        writer.write_all(&[0; 24])?;
+        writer.write_u16::<LittleEndian>(1)?;
+        writer.write_u16::<LittleEndian>(0x4240)?;
+        writer.write_u16::<LittleEndian>(0xF)?;
+        writer.write_u32::<LittleEndian>(0x1F44)?;
+        write_str(writer, "ADL", 8)?;
+        writer.write_u16::<LittleEndian>(420)?;
+        writer.write_u16::<LittleEndian>(0xADB0)?;
+        writer.write_u32::<LittleEndian>(self.channels.len() as u32)?;
+        writer.write_all(&[0; 4])?;
+        write_str(writer, &self.date, 16)?;
+        writer.write_all(&[0; 16])?;
+        write_str(writer, &self.time, 16)?;
+        writer.write_all(&[0; 16])?;
+        write_str(writer, &self.driver, 64)?;
+        write_str(writer, &self.vehicle, 64)?;
+        writer.write_all(&[0; 64])?;
+        write_str(writer, &self.venue, 64)?;
+        writer.write_all(&[0; 64])?;
+        writer.write_all(&[0; 1024])?;
+        writer.write_u32::<LittleEndian>(PRO_LOGGING_MAGIC)?;
+        writer.write_all(&[0; 66])?;
+        write_str(writer, &self.comment, 64)?;
+        writer.write_all(&[0; 126])
+    }
+
+    fn write_event<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        write_str(writer, &self.event, 64)?;
+        write_str(writer, &self.session, 64)?;
+        write_str(writer, &self.comment, 1024)?;
+        // No venue block.
+        writer.write_u16::<LittleEndian>(0)
+    }
+}
+
+/// Write a string in a fixed-size, zero-padded field.
+fn write_str<W: Write>(writer: &mut W, value: &str, size: usize) -> io::Result<()> {
+    let bytes = value.as_bytes();
+    let len = bytes.len().min(size);
+
+    writer.write_all(&bytes[..len])?;
+    writer.write_all(&vec![0; size - len])
+}
+
+/// Current UTC date (`dd/mm/yyyy`) and time (`hh:mm:ss`).
+fn current_date_time() -> (String, String) {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+
+    let days = (secs / 86400) as i64;
+    let seconds = secs % 86400;
+
+    // Civil date from days since the epoch (Howard Hinnant's algorithm).
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    (
+        format!("{:02}/{:02}/{:04}", day, month, year),
+        format!(
+            "{:02}:{:02}:{:02}",
+            seconds / 3600,
+            seconds % 3600 / 60,
+            seconds % 60
+        ),
+    )
+}
@@ -0,0 +1,218 @@
+//! pcapng captures of the raw datagrams.
+//!
+//! [`PcapngWriter`] writes the datagrams received from the game to a standard
+//! pcapng capture, which Wireshark and its F1 dissectors open and tools like
+//! `tcpreplay` replay. The datagrams are wrapped in the IP and UDP headers of
+//! their source and destination, on a raw IP interface. The captures are read
+//! back with [`PcapReader`].
+//!
+//! [`PcapngWriter`]: ./struct.PcapngWriter.html
+//! [`PcapReader`]: ../../input/pcap/struct.PcapReader.html
+
+use byteorder::{LittleEndian, WriteBytesExt};
+use std::io::{self, Write};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::time::Duration;
+
+const SECTION_HEADER: u32 = 0x0a0d_0d0a;
+const BYTE_ORDER_MAGIC: u32 = 0x1a2b_3c4d;
+const INTERFACE_DESCRIPTION: u32 = 1;
+const ENHANCED_PACKET: u32 = 6;
+
+const LINKTYPE_RAW: u16 = 101;
+const IP_PROTOCOL_UDP: u8 = 17;
+const IPV4_HEADER_SIZE: usize = 20;
+const IPV6_HEADER_SIZE: usize = 40;
+const UDP_HEADER_SIZE: usize = 8;
+
+/// Writes datagrams to a pcapng capture, with microsecond timestamps.
+///
+/// ## Specification
+/// ```text
+/// Section Header Block:       little endian, version 1.0, unknown length
+/// Interface Description Block: LINKTYPE_RAW (101), no snapshot length
+///
+/// Then for each datagram, an Enhanced Packet Block:
+/// timestamp:  microseconds since the Unix epoch
+/// data:       IPv4 or IPv6 header, UDP header, then the datagram
+/// ```
+pub struct PcapngWriter<W: Write> {
+    writer: W,
+}
+
+impl<W: Write> PcapngWriter<W> {
+    /// Write the header of the capture.
+    pub fn new(mut writer: W) -> io::Result<PcapngWriter<W>> {
+        write_block(&mut writer, SECTION_HEADER, |body| {
+            body.write_u32::<LittleEndian>(BYTE_ORDER_MAGIC)?;
+            body.write_u16::<LittleEndian>(1)?;
+            body.write_u16::<LittleEndian>(0)?;
+            body.write_i64::<LittleEndian>(-1)
+        })?;
+
+        write_block(&mut writer, INTERFACE_DESCRIPTION, |body| {
+            body.write_u16::<LittleEndian>(LINKTYPE_RAW)?;
+            body.write_u16::<LittleEndian>(0)?;
+            body.write_u32::<LittleEndian>(0)
+        })?;
+
+        Ok(PcapngWriter { writer })
+    }
+
+    /// Write a datagram sent from `source` to `destination`, received at
+    /// `timestamp` since the Unix epoch. IPv4 addresses are mapped to IPv6
+    /// when the other one is IPv6.
+    pub fn write(
+        &mut self,
+        timestamp: Duration,
+        source: SocketAddr,
+        destination: SocketAddr,
+        datagram: &[u8],
+    ) -> io::Result<()> {
+        let packet = ip_packet(source, destination, datagram)?;
+        let micros = timestamp.as_micros() as u64;
+
+        write_block(&mut self.writer, ENHANCED_PACKET, |body| {
+            body.write_u32::<LittleEndian>(0)?;
+            body.write_u32::<LittleEndian>((micros >> 32) as u32)?;
+            body.write_u32::<LittleEndian>(micros as u32)?;
+            body.write_u32::<LittleEndian>(packet.len() as u32)?;
+            body.write_u32::<LittleEndian>(packet.len() as u32)?;
+            body.write_all(&packet)
+        })
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+/// Write a block: its type, length, body padded to 32 bits and length again.
+fn write_block<W: Write, F>(writer: &mut W, block_type: u32, body: F) -> io::Result<()>
+where
+    F: FnOnce(&mut Vec<u8>) -> io::Result<()>,
+{
+    let mut data = Vec::new();
+    body(&mut data)?;
+    data.resize(data.len().div_ceil(4) * 4, 0);
+
+    let length = (data.len() + 12) as u32;
+    writer.write_u32::<LittleEndian>(block_type)?;
+    writer.write_u32::<LittleEndian>(length)?;
+    writer.write_all(&data)?;
+    writer.write_u32::<LittleEndian>(length)
+}
+
+/// The datagram with its IP and UDP headers, checksums included.
+fn ip_packet(source: SocketAddr, destination: SocketAddr, datagram: &[u8]) -> io::Result<Vec<u8>> {
+    let udp_length = UDP_HEADER_SIZE + datagram.len();
+    if udp_length > u16::MAX as usize - IPV4_HEADER_SIZE {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "Datagram too large for a UDP packet",
+        ));
+    }
+
+    let mut udp = Vec::with_capacity(udp_length);
+    udp.extend_from_slice(&source.port().to_be_bytes());
+    udp.extend_from_slice(&destination.port().to_be_bytes());
+    udp.extend_from_slice(&(udp_length as u16).to_be_bytes());
+    udp.extend_from_slice(&[0, 0]);
+    udp.extend_from_slice(datagram);
+
+    let mut packet = match (source.ip(), destination.ip()) {
+        (IpAddr::V4(src), IpAddr::V4(dst)) => {
+            let pseudo_header = [
+                &src.octets()[..],
+                &dst.octets(),
+                &[0, IP_PROTOCOL_UDP],
+                &(udp_length as u16).to_be_bytes(),
+            ]
+            .concat();
+            set_udp_checksum(&mut udp, &pseudo_header);
+
+            ipv4_header(src, dst, udp_length)
+        }
+        (src, dst) => {
+            let (src, dst) = (to_ipv6(src), to_ipv6(dst));
+            let pseudo_header = [
+                &src.octets()[..],
+                &dst.octets(),
+                &(udp_length as u32).to_be_bytes(),
+                &[0, 0, 0, IP_PROTOCOL_UDP],
+            ]
+            .concat();
+            set_udp_checksum(&mut udp, &pseudo_header);
+
+            ipv6_header(src, dst, udp_length)
+        }
+    };
+
+    packet.extend_from_slice(&udp);
+    Ok(packet)
+}
+
+fn ipv4_header(src: Ipv4Addr, dst: Ipv4Addr, udp_length: usize) -> Vec<u8> {
+    let total_length = (IPV4_HEADER_SIZE + udp_length) as u16;
+
+    let mut header = Vec::with_capacity(IPV4_HEADER_SIZE);
+    header.extend_from_slice(&[0x45, 0]);
+    header.extend_from_slice(&total_length.to_be_bytes());
+    // Identification, then the don't fragment flag.
+    header.extend_from_slice(&[0, 0, 0x40, 0]);
+    header.extend_from_slice(&[64, IP_PROTOCOL_UDP, 0, 0]);
+    header.extend_from_slice(&src.octets());
+    header.extend_from_slice(&dst.octets());
+
+    let checksum = checksum(&header);
+    header[10..12].copy_from_slice(&checksum.to_be_bytes());
+
+    header
+}
+
+fn ipv6_header(src: Ipv6Addr, dst: Ipv6Addr, udp_length: usize) -> Vec<u8> {
+    let mut header = Vec::with_capacity(IPV6_HEADER_SIZE);
+    header.extend_from_slice(&[0x60, 0, 0, 0]);
+    header.extend_from_slice(&(udp_length as u16).to_be_bytes());
+    header.extend_from_slice(&[IP_PROTOCOL_UDP, 64]);
+    header.extend_from_slice(&src.octets());
+    header.extend_from_slice(&dst.octets());
+
+    header
+}
+
+fn to_ipv6(addr: IpAddr) -> Ipv6Addr {
+    match addr {
+        IpAddr::V4(addr) => addr.to_ipv6_mapped(),
+        IpAddr::V6(addr) => addr,
+    }
+}
+
+fn set_udp_checksum(udp: &mut [u8], pseudo_header: &[u8]) {
+    let checksum = match checksum(&[pseudo_header, udp].concat()) {
+        // Zero means no checksum, so it is sent as all ones.
+        0 => 0xffff,
+        checksum => checksum,
+    };
+
+    udp[6..8].copy_from_slice(&checksum.to_be_bytes());
+}
+
+/// Internet checksum: one's complement of the one's complement sum of the
+/// 16-bit words.
+fn checksum(data: &[u8]) -> u16 {
+    let mut sum: u32 = data
+        .chunks(2)
+        .map(|word| u16::from_be_bytes([word[0], *word.get(1).unwrap_or(&0)]) as u32)
+        .sum();
+
+    while sum > 0xffff {
+        sum = (sum & 0xffff) + (sum >> 16);
+    }
+
+    !(sum as u16)
+}
@@ -0,0 +1,622 @@
+//! Final classification reports, e.g. to post the results of a league race.
+//!
+//! [`SessionResults`] holds the classification of a session with the names of
+//! the drivers, the gaps and the fastest lap, and writes it as a Markdown
+//! table ready to paste into a forum, as CSV or as JSON.
+//!
+//! [`ResultsExporter`] does it on its own: as a [`PacketSink`], it keeps the
+//! session and its participants, and writes the reports to a directory when
+//! the Final Classification packet is received.
+//!
+//! [`SessionResults`]: ./struct.SessionResults.html
+//! [`ResultsExporter`]: ./struct.ResultsExporter.html
+//! [`PacketSink`]: ../../pipeline/trait.PacketSink.html
+
+use std::fmt::{self, Write as _};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::fmt::transliterate;
+use crate::json::{json_float, json_option, json_string};
+use crate::packet::final_classification::PacketFinalClassificationData;
+use crate::packet::header::SessionIdentifier;
+use crate::packet::lap::ResultStatus;
+use crate::packet::participants::{PacketParticipantsData, Team};
+use crate::packet::session::{SessionType, Track};
+use crate::packet::Packet;
+use crate::pipeline::PacketSink;
+
+/// Format of a report.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum ResultsFormat {
+    Markdown,
+    Csv,
+    Json,
+}
+
+impl ResultsFormat {
+    pub const ALL: [ResultsFormat; 3] = [
+        ResultsFormat::Markdown,
+        ResultsFormat::Csv,
+        ResultsFormat::Json,
+    ];
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            ResultsFormat::Markdown => "md",
+            ResultsFormat::Csv => "csv",
+            ResultsFormat::Json => "json",
+        }
+    }
+}
+
+/// Gap of a driver to the winner, or to the pole sitter outside races.
+///
+/// ## Specification
+/// ```text
+/// Leader:   The driver won the session
+/// Time:     Seconds behind, penalties included
+/// Laps:     Number of laps behind
+/// Out:      The driver did not finish, or was not classified or disqualified
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Gap {
+    Leader,
+    Time(f64),
+    Laps(u8),
+    Out(ResultStatus),
+}
+
+impl fmt::Display for Gap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Gap::Leader => f.write_str("-"),
+            Gap::Time(seconds) => write!(f, "+{:.3}", seconds),
+            Gap::Laps(1) => f.write_str("+1 lap"),
+            Gap::Laps(laps) => write!(f, "+{} laps", laps),
+            Gap::Out(ResultStatus::Disqualified) => f.write_str("DSQ"),
+            Gap::Out(ResultStatus::NotClassified) => f.write_str("NC"),
+            Gap::Out(_) => f.write_str("DNF"),
+        }
+    }
+}
+
+/// A classified driver.
+///
+/// ## Specification
+/// ```text
+/// position:        Finishing position
+/// vehicle_index:   Index of the car in the packets
+/// name:            Name of the driver, or "Car" and the index when unknown
+/// team:            Team of the driver, when known
+/// grid_position:   Grid position
+/// num_laps:        Number of laps completed
+/// result_status:   Result status
+/// race_time:       Total race time in seconds, penalties included
+/// gap:             Gap to the winner
+/// best_lap_time:   Best lap time in seconds, if the driver set one
+/// fastest_lap:     Whether the best lap is the fastest of the session
+/// num_pit_stops:   Number of pit stops
+/// penalties_time:  Time penalties in seconds
+/// num_penalties:   Number of penalties
+/// points:          Points scored
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClassifiedDriver {
+    pub position: u8,
+    pub vehicle_index: u8,
+    pub name: String,
+    pub team: Option<Team>,
+    pub grid_position: u8,
+    pub num_laps: u8,
+    pub result_status: ResultStatus,
+    pub race_time: f64,
+    pub gap: Gap,
+    pub best_lap_time: Option<f32>,
+    pub fastest_lap: bool,
+    pub num_pit_stops: u8,
+    pub penalties_time: u8,
+    pub num_penalties: u8,
+    pub points: u8,
+}
+
+/// Final classification of a session.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SessionResults {
+    session: Option<(SessionType, Track)>,
+    drivers: Vec<ClassifiedDriver>,
+    ascii: bool,
+}
+
+impl SessionResults {
+    /// Classification of a packet, the drivers being named after their car
+    /// until the participants are given.
+    pub fn new(packet: &PacketFinalClassificationData) -> SessionResults {
+        let mut results = SessionResults {
+            session: None,
+            drivers: Vec::new(),
+            ascii: false,
+        };
+
+        for (i, c) in packet.classification_data().iter().enumerate() {
+            if c.position() == 0
+                || matches!(
+                    c.result_status(),
+                    ResultStatus::Invalid | ResultStatus::Inactive
+                )
+            {
+                continue;
+            }
+
+            results.drivers.push(ClassifiedDriver {
+                position: c.position(),
+                vehicle_index: i as u8,
+                name: format!("Car {}", i),
+                team: None,
+                grid_position: c.grid_position(),
+                num_laps: c.num_laps(),
+                result_status: c.result_status(),
+                race_time: c.total_race_time() + f64::from(c.penalties_time()),
+                gap: Gap::Leader,
+                best_lap_time: Some(c.best_lap_time()).filter(|&t| t > 0.0),
+                fastest_lap: false,
+                num_pit_stops: c.num_pit_stops(),
+                penalties_time: c.penalties_time(),
+                num_penalties: c.num_penalties(),
+                points: c.points(),
+            });
+        }
+        results.drivers.sort_by_key(|d| d.position);
+
+        let fastest = results
+            .drivers
+            .iter()
+            .filter_map(|d| d.best_lap_time)
+            .fold(f32::INFINITY, f32::min);
+        for driver in &mut results.drivers {
+            driver.fastest_lap = driver.best_lap_time == Some(fastest);
+        }
+
+        results.update_gaps();
+        results
+    }
+
+    /// Classification of drivers saved beforehand, e.g. in a store. The gaps
+    /// are computed again.
+    pub fn from_drivers(mut drivers: Vec<ClassifiedDriver>) -> SessionResults {
+        drivers.sort_by_key(|d| d.position);
+
+        let mut results = SessionResults {
+            session: None,
+            drivers,
+            ascii: false,
+        };
+        results.update_gaps();
+        results
+    }
+
+    /// Names and teams of the drivers.
+    pub fn with_participants(mut self, participants: &PacketParticipantsData) -> SessionResults {
+        let drivers: Vec<(&str, Team)> = participants
+            .participants()
+            .iter()
+            .map(|p| (p.name().as_str(), p.team()))
+            .collect();
+        self.name_drivers(&drivers);
+        self
+    }
+
+    /// Type of the session and its track, used in the title and to tell races,
+    /// classified by race time, from the other sessions, classified by best
+    /// lap.
+    pub fn with_session(mut self, session_type: SessionType, track: Track) -> SessionResults {
+        self.session = Some((session_type, track));
+        self.update_gaps();
+        self
+    }
+
+    /// Transliterate the names of the drivers to ASCII in the CSV report, for
+    /// the tools that do not read UTF-8.
+    pub fn with_ascii(mut self, ascii: bool) -> SessionResults {
+        self.ascii = ascii;
+        self
+    }
+
+    pub fn session(&self) -> Option<(SessionType, Track)> {
+        self.session
+    }
+
+    /// The classified drivers, by position.
+    pub fn drivers(&self) -> &[ClassifiedDriver] {
+        &self.drivers
+    }
+
+    /// Driver who set the fastest lap.
+    pub fn fastest_lap(&self) -> Option<&ClassifiedDriver> {
+        self.drivers.iter().find(|d| d.fastest_lap)
+    }
+
+    /// Title of the reports, e.g. "Race - Monza".
+    pub fn title(&self) -> String {
+        match self.session {
+            Some((session_type, track)) => format!("{} - {}", session_type.name(), track.name()),
+            None => String::from("Final classification"),
+        }
+    }
+
+    /// The classification as a report in a format.
+    pub fn report(&self, format: ResultsFormat) -> String {
+        match format {
+            ResultsFormat::Markdown => self.to_markdown(),
+            ResultsFormat::Csv => self.to_csv(),
+            ResultsFormat::Json => self.to_json(),
+        }
+    }
+
+    /// A Markdown table, the fastest lap in bold.
+    pub fn to_markdown(&self) -> String {
+        let mut out = format!("## {}\n\n", self.title());
+        out.push_str(
+            "| Pos | Driver | Team | Grid | Laps | Time | Best lap | Stops | Penalties | Pts |\n",
+        );
+        out.push_str(
+            "|----:|--------|------|-----:|-----:|-----:|---------:|------:|----------:|----:|\n",
+        );
+
+        for d in &self.drivers {
+            let best_lap = match d.best_lap_time {
+                Some(t) if d.fastest_lap => format!("**{}**", format_lap_time(t)),
+                Some(t) => format_lap_time(t),
+                None => String::from("-"),
+            };
+            let penalties = match d.num_penalties {
+                0 => String::from("-"),
+                n => format!("{}s ({})", d.penalties_time, n),
+            };
+
+            let _ = writeln!(
+                out,
+                "| {} | {} | {} | {} | {} | {} | {} | {} | {} | {} |",
+                d.position,
+                markdown_escape(&d.name),
+                d.team.map(|t| format!("{:?}", t)).unwrap_or_default(),
+                d.grid_position,
+                d.num_laps,
+                self.time(d),
+                best_lap,
+                d.num_pit_stops,
+                penalties,
+                d.points
+            );
+        }
+
+        if let Some(d) = self.fastest_lap() {
+            let _ = writeln!(
+                out,
+                "\nFastest lap: {} {}",
+                markdown_escape(&d.name),
+                format_lap_time(d.best_lap_time.unwrap_or_default())
+            );
+        }
+
+        out
+    }
+
+    /// One line per driver, times in seconds. The names are in ASCII if asked
+    /// for with [`with_ascii`].
+    ///
+    /// [`with_ascii`]: #method.with_ascii
+    pub fn to_csv(&self) -> String {
+        let mut out = String::from(
+            "position,driver,team,grid_position,laps,status,race_time,gap,gap_seconds,laps_down,\
+             best_lap_time,fastest_lap,pit_stops,penalties_time,penalties,points\n",
+        );
+
+        for d in &self.drivers {
+            let (gap_seconds, laps_down) = gap_values(d.gap);
+            let name = if self.ascii {
+                transliterate(&d.name)
+            } else {
+                d.name.clone()
+            };
+            let _ = writeln!(
+                out,
+                "{},{},{},{},{},{:?},{:.3},{},{},{},{},{},{},{},{},{}",
+                d.position,
+                csv_field(&name),
+                d.team.map(|t| format!("{:?}", t)).unwrap_or_default(),
+                d.grid_position,
+                d.num_laps,
+                d.result_status,
+                d.race_time,
+                csv_field(&d.gap.to_string()),
+                gap_seconds.map(|s| format!("{:.3}", s)).unwrap_or_default(),
+                laps_down.map(|l| l.to_string()).unwrap_or_default(),
+                d.best_lap_time
+                    .map(|t| format!("{:.3}", t))
+                    .unwrap_or_default(),
+                d.fastest_lap as u8,
+                d.num_pit_stops,
+                d.penalties_time,
+                d.num_penalties,
+                d.points
+            );
+        }
+
+        out
+    }
+
+    /// An object with the session and an array of the drivers, times in
+    /// seconds.
+    pub fn to_json(&self) -> String {
+        let drivers: Vec<String> = self
+            .drivers
+            .iter()
+            .map(|d| {
+                let (gap_seconds, laps_down) = gap_values(d.gap);
+                format!(
+                    "{{\"position\":{},\"vehicle_index\":{},\"driver\":{},\"team\":{},\
+                     \"grid_position\":{},\"laps\":{},\"status\":\"{:?}\",\"race_time\":{},\
+                     \"gap\":{},\"gap_seconds\":{},\"laps_down\":{},\"best_lap_time\":{},\
+                     \"fastest_lap\":{},\"pit_stops\":{},\"penalties_time\":{},\
+                     \"penalties\":{},\"points\":{}}}",
+                    d.position,
+                    d.vehicle_index,
+                    json_string(&d.name),
+                    d.team
+                        .map(|t| json_string(&format!("{:?}", t)))
+                        .unwrap_or_else(|| String::from("null")),
+                    d.grid_position,
+                    d.num_laps,
+                    d.result_status,
+                    json_float(Some(d.race_time), 3),
+                    json_string(&d.gap.to_string()),
+                    json_float(gap_seconds, 3),
+                    json_option(laps_down),
+                    json_float(d.best_lap_time, 3),
+                    d.fastest_lap,
+                    d.num_pit_stops,
+                    d.penalties_time,
+                    d.num_penalties,
+                    d.points
+                )
+            })
+            .collect();
+
+        let (session_type, track) = match self.session {
+            Some((session_type, track)) => {
+                (json_string(session_type.name()), json_string(track.name()))
+            }
+            None => (String::from("null"), String::from("null")),
+        };
+
+        format!(
+            "{{\"title\":{},\"session_type\":{},\"track\":{},\"drivers\":[{}]}}",
+            json_string(&self.title()),
+            session_type,
+            track,
+            drivers.join(",")
+        )
+    }
+
+    fn name_drivers<S: AsRef<str>>(&mut self, drivers: &[(S, Team)]) {
+        for d in &mut self.drivers {
+            if let Some((name, team)) = drivers.get(d.vehicle_index as usize) {
+                if !name.as_ref().is_empty() {
+                    d.name = name.as_ref().to_string();
+                }
+                d.team = Some(*team);
+            }
+        }
+    }
+
+    fn is_race(&self) -> bool {
+        match self.session {
+            Some((session_type, _)) => {
+                matches!(session_type, SessionType::Race | SessionType::Race2)
+            }
+            None => true,
+        }
+    }
+
+    /// Race time of the winner and gap of the others in races, best lap and
+    /// gap to pole otherwise.
+    fn time(&self, d: &ClassifiedDriver) -> String {
+        match d.gap {
+            Gap::Leader if self.is_race() => format_race_time(d.race_time),
+            Gap::Leader => d.best_lap_time.map(format_lap_time).unwrap_or_default(),
+            Gap::Out(_) if !self.is_race() && d.best_lap_time.is_none() => String::from("No time"),
+            gap => gap.to_string(),
+        }
+    }
+
+    fn update_gaps(&mut self) {
+        let race = self.is_race();
+        let leader = match self.drivers.first() {
+            Some(leader) => (leader.num_laps, leader.race_time, leader.best_lap_time),
+            None => return,
+        };
+
+        for (i, d) in self.drivers.iter_mut().enumerate() {
+            d.gap = if i == 0 {
+                Gap::Leader
+            } else if race {
+                match d.result_status {
+                    ResultStatus::Finished => match leader.0.saturating_sub(d.num_laps) {
+                        0 => Gap::Time(d.race_time - leader.1),
+                        laps => Gap::Laps(laps),
+                    },
+                    status => Gap::Out(status),
+                }
+            } else {
+                match (d.best_lap_time, leader.2) {
+                    (Some(time), Some(pole)) => Gap::Time(f64::from(time - pole)),
+                    _ => Gap::Out(d.result_status),
+                }
+            };
+        }
+    }
+}
+
+/// Writes the reports of a session to a directory when its Final
+/// Classification packet is received, named after the track, the session and
+/// its identifier, e.g. `Monza-Race-0123456789abcdef.md`.
+///
+/// The game sends the packet several times; the reports are written once per
+/// session. As a [`PacketSink`], the write errors are counted rather than
+/// returned.
+///
+/// [`PacketSink`]: ../../pipeline/trait.PacketSink.html
+#[derive(Debug)]
+pub struct ResultsExporter {
+    dir: PathBuf,
+    formats: Vec<ResultsFormat>,
+    ascii: bool,
+    drivers: Vec<(String, Team)>,
+    session: Option<(SessionType, Track)>,
+    exported: Option<SessionIdentifier>,
+    written: Vec<PathBuf>,
+    errors: u64,
+}
+
+impl ResultsExporter {
+    /// Exporter writing every format to `dir`.
+    pub fn new<P: AsRef<Path>>(dir: P) -> ResultsExporter {
+        ResultsExporter {
+            dir: dir.as_ref().to_path_buf(),
+            formats: ResultsFormat::ALL.to_vec(),
+            ascii: false,
+            drivers: Vec::new(),
+            session: None,
+            exported: None,
+            written: Vec::new(),
+            errors: 0,
+        }
+    }
+
+    /// Only write these formats.
+    pub fn with_formats(mut self, formats: &[ResultsFormat]) -> ResultsExporter {
+        self.formats = formats.to_vec();
+        self
+    }
+
+    /// Write the names of the drivers in ASCII in the CSV reports.
+    pub fn with_ascii(mut self, ascii: bool) -> ResultsExporter {
+        self.ascii = ascii;
+        self
+    }
+
+    /// Files written so far.
+    pub fn written(&self) -> &[PathBuf] {
+        &self.written
+    }
+
+    /// Number of reports that could not be written.
+    pub fn errors(&self) -> u64 {
+        self.errors
+    }
+
+    /// Update the exporter with a packet, writing the reports on the first
+    /// Final Classification packet of a session. Returns the results written,
+    /// if any.
+    pub fn update(&mut self, packet: &Packet) -> io::Result<Option<SessionResults>> {
+        let classification = match packet {
+            Packet::Session(p) => {
+                self.session = Some((p.session_type(), p.track()));
+                return Ok(None);
+            }
+            Packet::Participants(p) => {
+                self.drivers = p
+                    .participants()
+                    .iter()
+                    .map(|p| (p.name().clone(), p.team()))
+                    .collect();
+                return Ok(None);
+            }
+            Packet::FinalClassification(p) => p,
+            _ => return Ok(None),
+        };
+
+        let session = classification.header().session_id();
+        if self.exported == Some(session) {
+            return Ok(None);
+        }
+        self.exported = Some(session);
+
+        let mut results = SessionResults::new(classification).with_ascii(self.ascii);
+        results.name_drivers(&self.drivers);
+        if let Some((session_type, track)) = self.session {
+            results = results.with_session(session_type, track);
+        }
+
+        let stem = match self.session {
+            Some((session_type, track)) => format!(
+                "{}-{}-{}",
+                file_name(track.name()),
+                file_name(session_type.name()),
+                session
+            ),
+            None => format!("results-{}", session),
+        };
+
+        fs::create_dir_all(&self.dir)?;
+        for format in &self.formats {
+            let path = self.dir.join(format!("{}.{}", stem, format.extension()));
+            fs::write(&path, results.report(*format))?;
+            self.written.push(path);
+        }
+
+        Ok(Some(results))
+    }
+}
+
+impl PacketSink for ResultsExporter {
+    fn handle(&mut self, packet: &Packet) {
+        if self.update(packet).is_err() {
+            self.errors += 1;
+        }
+    }
+}
+
+/// Seconds and laps behind, in the columns of the CSV and JSON reports.
+fn gap_values(gap: Gap) -> (Option<f64>, Option<u8>) {
+    match gap {
+        Gap::Leader => (Some(0.0), Some(0)),
+        Gap::Time(seconds) => (Some(seconds), Some(0)),
+        Gap::Laps(laps) => (None, Some(laps)),
+        Gap::Out(_) => (None, None),
+    }
+}
+
+fn format_race_time(time: f64) -> String {
+    let hours = (time / 3600.0) as u32;
+    let minutes = ((time - f64::from(hours) * 3600.0) / 60.0) as u32;
+    let seconds = time - f64::from(hours) * 3600.0 - f64::from(minutes) * 60.0;
+    format!("{}:{:02}:{:06.3}", hours, minutes, seconds)
+}
+
+fn format_lap_time(lap_time: f32) -> String {
+    let minutes = (lap_time / 60.0) as u32;
+    format!("{}:{:06.3}", minutes, lap_time - minutes as f32 * 60.0)
+}
+
+/// Name made of letters, digits and dashes, e.g. `Free-Practice-1`.
+fn file_name(name: &str) -> String {
+    name.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+fn markdown_escape(s: &str) -> String {
+    s.replace('|', "\\|")
+}
+
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
@@ -0,0 +1,351 @@
+//! C ABI, for sim rig software written in C or C++.
+//!
+//! A datagram is parsed with `f1t_parse_packet` into an opaque handle, which
+//! is released with `f1t_packet_free`. The header and the per-car data of the
+//! high frequency packets are copied out into flat C structs; enumerated values
+//! are given as their wire format values.
+//!
+//! The declarations are in `include/f1_telemetry.h`, generated with cbindgen:
+//!
+//! ```text
+//! cbindgen --config cbindgen.toml --output include/f1_telemetry.h
+//! ```
+//!
+//! Enabled with the `ffi` feature. Build the library to link against with
+//! `cargo rustc --release --features ffi --crate-type staticlib` (or `cdylib`).
+
+use std::os::raw::c_int;
+use std::ptr;
+use std::slice;
+
+use crate::packet::header::PacketHeader;
+use crate::packet::{parse_packet_fuzz, Packet};
+
+/// Parsed packet, owned by the caller until passed to `f1t_packet_free`.
+pub struct F1tPacket(Packet);
+
+/// Header of a packet.
+///
+/// `secondary_player_car_index` is 255 outside of split-screen sessions.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct F1tHeader {
+    pub packet_format: u16,
+    pub game_major_version: u8,
+    pub game_minor_version: u8,
+    pub packet_version: u8,
+    pub packet_id: u8,
+    pub session_uid: u64,
+    pub session_time: f32,
+    pub frame_identifier: u32,
+    pub player_car_index: u8,
+    pub secondary_player_car_index: u8,
+}
+
+/// Motion data of a car, from a Motion packet.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct F1tCarMotion {
+    pub world_position_x: f32,
+    pub world_position_y: f32,
+    pub world_position_z: f32,
+    pub world_velocity_x: f32,
+    pub world_velocity_y: f32,
+    pub world_velocity_z: f32,
+    pub g_force_lateral: f32,
+    pub g_force_longitudinal: f32,
+    pub g_force_vertical: f32,
+    pub yaw: f32,
+    pub pitch: f32,
+    pub roll: f32,
+}
+
+/// Lap data of a car, from a Lap Data packet.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct F1tLapData {
+    pub last_lap_time: f32,
+    pub current_lap_time: f32,
+    pub best_lap_time: f32,
+    pub sector_1_time: f32,
+    pub sector_2_time: f32,
+    pub lap_distance: f32,
+    pub total_distance: f32,
+    pub car_position: u8,
+    pub current_lap_num: u8,
+    pub pit_status: u8,
+    pub sector: u8,
+    pub current_lap_invalid: bool,
+    pub penalties: u8,
+    pub grid_position: u8,
+    pub driver_status: u8,
+    pub result_status: u8,
+}
+
+/// Telemetry of a car, from a Car Telemetry packet.
+///
+/// The wheel arrays are in the order RL, RR, FL, FR.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct F1tCarTelemetry {
+    pub speed: u16,
+    pub throttle: f32,
+    pub steer: f32,
+    pub brake: f32,
+    pub clutch: u8,
+    pub gear: i8,
+    pub engine_rpm: u16,
+    pub drs: bool,
+    pub rev_lights_percent: u8,
+    pub brakes_temperature: [u16; 4],
+    pub tyres_surface_temperature: [u16; 4],
+    pub tyres_inner_temperature: [u16; 4],
+    pub engine_temperature: u16,
+    pub tyre_pressures: [f32; 4],
+}
+
+/// Parse the `len` bytes of a datagram at `data`.
+///
+/// Returns null if the datagram is not a valid packet.
+///
+/// # Safety
+///
+/// `data` must point to `len` readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn f1t_parse_packet(data: *const u8, len: usize) -> *mut F1tPacket {
+    if data.is_null() {
+        return ptr::null_mut();
+    }
+
+    match parse_packet_fuzz(slice::from_raw_parts(data, len)) {
+        Ok(packet) => Box::into_raw(Box::new(F1tPacket(packet))),
+        Err(_) => ptr::null_mut(),
+    }
+}
+
+/// Release a packet returned by `f1t_parse_packet`. Null is ignored.
+///
+/// # Safety
+///
+/// `packet` must come from `f1t_parse_packet` and not have been freed.
+#[no_mangle]
+pub unsafe extern "C" fn f1t_packet_free(packet: *mut F1tPacket) {
+    if !packet.is_null() {
+        drop(Box::from_raw(packet));
+    }
+}
+
+/// Id of the packet, or -1 for datagrams kept unparsed.
+///
+/// # Safety
+///
+/// `packet` must be a live packet returned by `f1t_parse_packet`.
+#[no_mangle]
+pub unsafe extern "C" fn f1t_packet_id(packet: *const F1tPacket) -> c_int {
+    match packet.as_ref().and_then(|p| header(&p.0)) {
+        Some(header) => header.packet_id() as c_int,
+        None => -1,
+    }
+}
+
+/// Copy the header of the packet to `out`, returning false for datagrams kept
+/// unparsed.
+///
+/// # Safety
+///
+/// `packet` must be a live packet returned by `f1t_parse_packet`, and `out`
+/// must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn f1t_packet_header(packet: *const F1tPacket, out: *mut F1tHeader) -> bool {
+    let header = match packet.as_ref().and_then(|p| header(&p.0)) {
+        Some(header) => header,
+        None => return false,
+    };
+
+    write(
+        out,
+        F1tHeader {
+            packet_format: header.packet_format(),
+            game_major_version: header.game_major_version(),
+            game_minor_version: header.game_minor_version(),
+            packet_version: header.packet_version(),
+            packet_id: header.packet_id(),
+            session_uid: header.session_uid(),
+            session_time: header.session_time(),
+            frame_identifier: header.frame_identifier(),
+            player_car_index: header.player_car_index(),
+            secondary_player_car_index: header.secondary_player_car_index().unwrap_or(255),
+        },
+    )
+}
+
+/// Number of cars in the per-car arrays of the packet: 20, or 22 from F1 2020.
+///
+/// # Safety
+///
+/// `packet` must be a live packet returned by `f1t_parse_packet`.
+#[no_mangle]
+pub unsafe extern "C" fn f1t_packet_num_cars(packet: *const F1tPacket) -> usize {
+    match packet.as_ref().and_then(|p| header(&p.0)) {
+        Some(header) => crate::packet::num_cars(header.packet_format()),
+        None => 0,
+    }
+}
+
+/// Copy the motion data of the car at `car_index` to `out`, returning false if
+/// the packet is not a Motion packet or the index is out of range.
+///
+/// # Safety
+///
+/// `packet` must be a live packet returned by `f1t_parse_packet`, and `out`
+/// must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn f1t_packet_car_motion(
+    packet: *const F1tPacket,
+    car_index: usize,
+    out: *mut F1tCarMotion,
+) -> bool {
+    let m = match packet.as_ref().map(|p| &p.0) {
+        Some(Packet::Motion(p)) => match p.motion_data().get(car_index) {
+            Some(m) => m,
+            None => return false,
+        },
+        _ => return false,
+    };
+
+    write(
+        out,
+        F1tCarMotion {
+            world_position_x: m.world_position_x(),
+            world_position_y: m.world_position_y(),
+            world_position_z: m.world_position_z(),
+            world_velocity_x: m.world_velocity_x(),
+            world_velocity_y: m.world_velocity_y(),
+            world_velocity_z: m.world_velocity_z(),
+            g_force_lateral: m.g_force_lateral(),
+            g_force_longitudinal: m.g_force_longitudinal(),
+            g_force_vertical: m.g_force_vertical(),
+            yaw: m.yaw(),
+            pitch: m.pitch(),
+            roll: m.roll(),
+        },
+    )
+}
+
+/// Copy the lap data of the car at `car_index` to `out`, returning false if
+/// the packet is not a Lap Data packet or the index is out of range.
+///
+/// # Safety
+///
+/// `packet` must be a live packet returned by `f1t_parse_packet`, and `out`
+/// must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn f1t_packet_lap_data(
+    packet: *const F1tPacket,
+    car_index: usize,
+    out: *mut F1tLapData,
+) -> bool {
+    let (l, packet_format) = match packet.as_ref().map(|p| &p.0) {
+        Some(Packet::Lap(p)) => match p.lap_data().get(car_index) {
+            Some(l) => (l, p.header().packet_format()),
+            None => return false,
+        },
+        _ => return false,
+    };
+
+    write(
+        out,
+        F1tLapData {
+            last_lap_time: l.last_lap_time(),
+            current_lap_time: l.current_lap_time(),
+            best_lap_time: l.best_lap_time(),
+            sector_1_time: l.sector_1_time(),
+            sector_2_time: l.sector_2_time(),
+            lap_distance: l.lap_distance(),
+            total_distance: l.total_distance(),
+            car_position: l.car_position(),
+            current_lap_num: l.current_lap_num(),
+            pit_status: l.pit_status().into(),
+            sector: l.sector(),
+            current_lap_invalid: l.current_lap_invalid(),
+            penalties: l.penalties(),
+            grid_position: l.grid_position(),
+            driver_status: l.driver_status().into(),
+            result_status: l.result_status().to_format(packet_format),
+        },
+    )
+}
+
+/// Copy the telemetry of the car at `car_index` to `out`, returning false if
+/// the packet is not a Car Telemetry packet or the index is out of range.
+///
+/// # Safety
+///
+/// `packet` must be a live packet returned by `f1t_parse_packet`, and `out`
+/// must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn f1t_packet_car_telemetry(
+    packet: *const F1tPacket,
+    car_index: usize,
+    out: *mut F1tCarTelemetry,
+) -> bool {
+    let t = match packet.as_ref().map(|p| &p.0) {
+        Some(Packet::CarTelemetry(p)) => match p.car_telemetry_data().get(car_index) {
+            Some(t) => t,
+            None => return false,
+        },
+        _ => return false,
+    };
+
+    write(
+        out,
+        F1tCarTelemetry {
+            speed: t.speed(),
+            throttle: t.throttle(),
+            steer: t.steer(),
+            brake: t.brake(),
+            clutch: t.clutch(),
+            gear: t.gear(),
+            engine_rpm: t.engine_rpm(),
+            drs: t.drs(),
+            rev_lights_percent: t.rev_lights_percent(),
+            brakes_temperature: t.brakes_temperature().to_array(),
+            tyres_surface_temperature: t.tyres_surface_temperature().to_array(),
+            tyres_inner_temperature: t.tyres_inner_temperature().to_array(),
+            engine_temperature: t.engine_temperature(),
+            tyre_pressures: t.tyre_pressures().to_array(),
+        },
+    )
+}
+
+unsafe fn write<T>(out: *mut T, value: T) -> bool {
+    match out.as_mut() {
+        Some(out) => {
+            *out = value;
+            true
+        }
+        None => false,
+    }
+}
+
+fn header(packet: &Packet) -> Option<&PacketHeader> {
+    let header = match packet {
+        Packet::Motion(p) => p.header(),
+        Packet::Session(p) => p.header(),
+        Packet::Lap(p) => p.header(),
+        Packet::Event(p) => p.header(),
+        Packet::Participants(p) => p.header(),
+        Packet::CarSetups(p) => p.header(),
+        Packet::CarTelemetry(p) => p.header(),
+        Packet::CarStatus(p) => p.header(),
+        Packet::FinalClassification(p) => p.header(),
+        Packet::LobbyInfo(p) => p.header(),
+        Packet::CarDamage(p) => p.header(),
+        Packet::TyreSets(p) => p.header(),
+        Packet::MotionEx(p) => p.header(),
+        Packet::TimeTrial(p) => p.header(),
+        Packet::Raw(_) => return None,
+    };
+
+    Some(header)
+}
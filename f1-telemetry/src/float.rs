@@ -0,0 +1,16 @@
+//! Float methods of `std` missing from `core`, provided by libm.
+
+pub(crate) trait F32Ext {
+    fn sqrt(self) -> Self;
+    fn round(self) -> Self;
+}
+
+impl F32Ext for f32 {
+    fn sqrt(self) -> f32 {
+        libm::sqrtf(self)
+    }
+
+    fn round(self) -> f32 {
+        libm::roundf(self)
+    }
+}
@@ -0,0 +1,116 @@
+//! Formatting of the text sent by the game, shared by the terminal UIs and the
+//! exports.
+//!
+//! Driver names may hold accented characters, or characters wider than a
+//! column, that break the alignment of a table or that a plain ASCII file
+//! cannot hold. [`truncate`] fits a name in a number of terminal columns, and
+//! [`transliterate`] writes it in ASCII:
+//!
+//! ```
+//! use f1_telemetry::fmt::{transliterate, truncate};
+//!
+//! assert_eq!(truncate("Räikkönen", 6, false), "Räikk…");
+//! assert_eq!(transliterate("Räikkönen"), "Raikkonen");
+//! ```
+//!
+//! [`truncate`]: ./fn.truncate.html
+//! [`transliterate`]: ./fn.transliterate.html
+
+use alloc::string::String;
+
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+/// Truncate `s` so that it fits in `width` terminal columns, ending it with an
+/// ellipsis when something had to be cut.
+///
+/// In `ascii` mode, the ellipsis is written `...`, and left out when `width` is
+/// too narrow to hold it.
+pub fn truncate(s: &str, width: usize, ascii: bool) -> String {
+    if UnicodeWidthStr::width(s) <= width {
+        return String::from(s);
+    }
+
+    let ellipsis = if ascii { "..." } else { "…" };
+    let (width, ellipsis) = match width.checked_sub(UnicodeWidthStr::width(ellipsis)) {
+        Some(width) => (width, ellipsis),
+        None => (width, ""),
+    };
+
+    let mut out = String::with_capacity(s.len());
+    let mut used = 0;
+
+    for c in s.chars() {
+        let w = UnicodeWidthChar::width(c).unwrap_or(0);
+        if used + w > width {
+            break;
+        }
+        used += w;
+        out.push(c);
+    }
+
+    out.push_str(ellipsis);
+    out
+}
+
+/// Replace the accented latin characters commonly found in driver names with
+/// their closest ASCII equivalent.
+///
+/// Characters without a known equivalent are replaced with `?` so that the
+/// result is always plain ASCII.
+pub fn transliterate(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+
+    for c in s.chars() {
+        if c.is_ascii() {
+            out.push(c);
+            continue;
+        }
+
+        let r = match c {
+            'À' | 'Á' | 'Â' | 'Ã' | 'Ä' | 'Å' | 'Ā' | 'Ą' => "A",
+            'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' | 'ā' | 'ą' => "a",
+            'Æ' => "AE",
+            'æ' => "ae",
+            'Ç' | 'Ć' | 'Č' => "C",
+            'ç' | 'ć' | 'č' => "c",
+            'Ď' | 'Đ' => "D",
+            'ď' | 'đ' => "d",
+            'È' | 'É' | 'Ê' | 'Ë' | 'Ē' | 'Ė' | 'Ę' | 'Ě' => "E",
+            'è' | 'é' | 'ê' | 'ë' | 'ē' | 'ė' | 'ę' | 'ě' => "e",
+            'Ğ' => "G",
+            'ğ' => "g",
+            'Ì' | 'Í' | 'Î' | 'Ï' | 'Ī' | 'İ' => "I",
+            'ì' | 'í' | 'î' | 'ï' | 'ī' | 'ı' => "i",
+            'Ł' => "L",
+            'ł' => "l",
+            'Ñ' | 'Ń' | 'Ň' => "N",
+            'ñ' | 'ń' | 'ň' => "n",
+            'Ò' | 'Ó' | 'Ô' | 'Õ' | 'Ö' | 'Ø' | 'Ō' | 'Ő' => "O",
+            'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' | 'ō' | 'ő' => "o",
+            'Œ' => "OE",
+            'œ' => "oe",
+            'Ř' => "R",
+            'ř' => "r",
+            'Ś' | 'Š' | 'Ş' => "S",
+            'ś' | 'š' | 'ş' => "s",
+            'ß' => "ss",
+            'Ť' | 'Ţ' => "T",
+            'ť' | 'ţ' => "t",
+            'Ù' | 'Ú' | 'Û' | 'Ü' | 'Ū' | 'Ů' | 'Ű' => "U",
+            'ù' | 'ú' | 'û' | 'ü' | 'ū' | 'ů' | 'ű' => "u",
+            'Ý' | 'Ÿ' => "Y",
+            'ý' | 'ÿ' => "y",
+            'Ź' | 'Ż' | 'Ž' => "Z",
+            'ź' | 'ż' | 'ž' => "z",
+            '…' => "...",
+            '‘' | '’' => "'",
+            '“' | '”' => "\"",
+            '–' | '—' => "-",
+            _ => "?",
+        };
+
+        out.push_str(r);
+    }
+
+    out
+}
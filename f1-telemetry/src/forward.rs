@@ -0,0 +1,325 @@
+//! Forwarding of the telemetry from a rig to a remote server, e.g. the server
+//! of a league, optionally sealed with a pre-shared key.
+//!
+//! A [`Forwarder`] relays the datagrams of the game to the server. With a
+//! [`Key`], each datagram is encrypted and authenticated with
+//! ChaCha20-Poly1305 (RFC 8439), and an [`Opener`] with the same key on the
+//! server rejects the datagrams that were not sealed with it or that were
+//! replayed, e.g. with [`MultiStream::set_key`].
+//!
+//! A sealed datagram holds a 4 byte magic, the 12 byte nonce, the encrypted
+//! datagram and the 16 byte tag. The nonce is a random id drawn for each
+//! sealer, followed by a counter, so that restarting a forwarder with the same
+//! key never reuses a nonce.
+//!
+//! [`Forwarder`]: ./struct.Forwarder.html
+//! [`Key`]: ./struct.Key.html
+//! [`Opener`]: ./struct.Opener.html
+//! [`MultiStream::set_key`]: ../struct.MultiStream.html#method.set_key
+
+use std::collections::HashMap;
+use std::fmt;
+use std::net::{ToSocketAddrs, UdpSocket};
+
+use chacha20poly1305::aead::{AeadInPlace, KeyInit};
+use chacha20poly1305::{ChaCha20Poly1305, Nonce, Tag};
+
+use crate::packet::MAX_PACKET_SIZE;
+
+const KEY_SIZE: usize = 32;
+const NONCE_SIZE: usize = 12;
+const TAG_SIZE: usize = 16;
+
+/// First bytes of a sealed datagram.
+const MAGIC: [u8; 4] = *b"F1S\x01";
+
+const HEADER_SIZE: usize = MAGIC.len() + NONCE_SIZE;
+
+/// Bytes added to a datagram when it is sealed.
+pub const SEAL_OVERHEAD: usize = HEADER_SIZE + TAG_SIZE;
+
+/// Most senders an opener keeps the counters of, so that a flood of forged
+/// sender ids cannot grow it without bound.
+const MAX_SENDERS: usize = 256;
+
+/// Number of datagrams a sender can be out of order by and still be accepted.
+const REPLAY_WINDOW: u32 = 64;
+
+/// A 256-bit key shared by a forwarder and the server, e.g. generated with
+/// `openssl rand -hex 32`.
+#[derive(Clone, Eq, PartialEq)]
+pub struct Key([u8; KEY_SIZE]);
+
+impl Key {
+    pub fn new(bytes: [u8; KEY_SIZE]) -> Key {
+        Key(bytes)
+    }
+
+    /// Key written as 64 hexadecimal digits, or `None` if it is not.
+    pub fn from_hex(hex: &str) -> Option<Key> {
+        let hex = hex.trim();
+        if hex.len() != KEY_SIZE * 2 || !hex.is_ascii() {
+            return None;
+        }
+
+        let mut bytes = [0; KEY_SIZE];
+        for (i, byte) in bytes.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16).ok()?;
+        }
+
+        Some(Key(bytes))
+    }
+
+    fn cipher(&self) -> ChaCha20Poly1305 {
+        ChaCha20Poly1305::new(&self.0.into())
+    }
+}
+
+impl fmt::Debug for Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Key(..)")
+    }
+}
+
+/// Why a sealed datagram was rejected.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum OpenError {
+    /// The datagram is not sealed, e.g. sent straight by the game.
+    NotSealed,
+    /// The datagram was not sealed with the key, or was altered.
+    Forged,
+    /// The datagram was already received.
+    Replayed,
+}
+
+impl fmt::Display for OpenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OpenError::NotSealed => write!(f, "Datagram is not sealed"),
+            OpenError::Forged => write!(f, "Datagram is not authentic"),
+            OpenError::Replayed => write!(f, "Datagram was replayed"),
+        }
+    }
+}
+
+impl std::error::Error for OpenError {}
+
+/// Seals the datagrams of a forwarder.
+#[derive(Debug)]
+pub struct Sealer {
+    key: Key,
+    sender: u64,
+    counter: u32,
+}
+
+impl Sealer {
+    pub fn new(key: Key) -> Sealer {
+        Sealer {
+            key,
+            sender: random_sender(),
+            counter: 0,
+        }
+    }
+
+    /// Encrypt and sign a datagram.
+    pub fn seal(&mut self, datagram: &[u8]) -> Vec<u8> {
+        if self.counter == u32::MAX {
+            // Draw another id rather than reuse a nonce.
+            self.sender = random_sender();
+            self.counter = 0;
+        }
+
+        let mut nonce = [0; NONCE_SIZE];
+        nonce[..8].copy_from_slice(&self.sender.to_le_bytes());
+        nonce[8..].copy_from_slice(&self.counter.to_le_bytes());
+        self.counter += 1;
+
+        let mut sealed = Vec::with_capacity(datagram.len() + SEAL_OVERHEAD);
+        sealed.extend_from_slice(&MAGIC);
+        sealed.extend_from_slice(&nonce);
+        sealed.extend_from_slice(datagram);
+
+        let (header, data) = sealed.split_at_mut(HEADER_SIZE);
+        let tag = self
+            .key
+            .cipher()
+            .encrypt_in_place_detached(Nonce::from_slice(&nonce), header, data)
+            .expect("Datagram too long to seal");
+        sealed.extend_from_slice(&tag);
+
+        sealed
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Window {
+    /// Highest counter received.
+    last: u32,
+    /// Counters received below the last one, bit `n` for `last - 1 - n`.
+    seen: u64,
+    /// Order the sender was last heard from in, to evict the oldest one.
+    heard: u64,
+}
+
+/// Checks and decrypts the datagrams sealed by the forwarders sharing its key.
+#[derive(Debug)]
+pub struct Opener {
+    key: Key,
+    windows: HashMap<u64, Window>,
+    heard: u64,
+}
+
+impl Opener {
+    pub fn new(key: Key) -> Opener {
+        Opener {
+            key,
+            windows: HashMap::new(),
+            heard: 0,
+        }
+    }
+
+    /// Whether the datagram looks sealed, without checking it.
+    pub fn is_sealed(datagram: &[u8]) -> bool {
+        datagram.len() >= SEAL_OVERHEAD && datagram[..MAGIC.len()] == MAGIC
+    }
+
+    /// Check and decrypt a sealed datagram, returning the datagram of the game.
+    pub fn open(&mut self, sealed: &[u8]) -> Result<Vec<u8>, OpenError> {
+        if !Opener::is_sealed(sealed) {
+            return Err(OpenError::NotSealed);
+        }
+
+        let mut nonce = [0; NONCE_SIZE];
+        nonce.copy_from_slice(&sealed[MAGIC.len()..HEADER_SIZE]);
+        let mut sender = [0; 8];
+        sender.copy_from_slice(&nonce[..8]);
+        let sender = u64::from_le_bytes(sender);
+        let mut counter = [0; 4];
+        counter.copy_from_slice(&nonce[8..]);
+        let counter = u32::from_le_bytes(counter);
+
+        // Check the counter before the tag, but only record it once authentic.
+        if self.is_replayed(sender, counter) {
+            return Err(OpenError::Replayed);
+        }
+
+        let (header, rest) = sealed.split_at(HEADER_SIZE);
+        let (data, tag) = rest.split_at(rest.len() - TAG_SIZE);
+        let mut datagram = data.to_vec();
+        self.key
+            .cipher()
+            .decrypt_in_place_detached(
+                Nonce::from_slice(&nonce),
+                header,
+                &mut datagram,
+                Tag::from_slice(tag),
+            )
+            .map_err(|_| OpenError::Forged)?;
+
+        self.record(sender, counter);
+        Ok(datagram)
+    }
+
+    fn is_replayed(&self, sender: u64, counter: u32) -> bool {
+        match self.windows.get(&sender) {
+            Some(w) if counter > w.last => false,
+            Some(w) if counter == w.last => true,
+            Some(w) => {
+                let age = w.last - counter - 1;
+                age >= REPLAY_WINDOW || w.seen & (1 << age) != 0
+            }
+            None => false,
+        }
+    }
+
+    fn record(&mut self, sender: u64, counter: u32) {
+        self.heard += 1;
+
+        if !self.windows.contains_key(&sender) && self.windows.len() >= MAX_SENDERS {
+            let oldest = self
+                .windows
+                .iter()
+                .min_by_key(|(_, w)| w.heard)
+                .map(|(sender, _)| *sender);
+            if let Some(oldest) = oldest {
+                self.windows.remove(&oldest);
+            }
+        }
+
+        let heard = self.heard;
+        let window = self.windows.entry(sender).or_insert(Window {
+            last: counter,
+            seen: 0,
+            heard,
+        });
+        window.heard = heard;
+
+        if counter > window.last {
+            let shift = counter - window.last;
+            window.seen = if shift > REPLAY_WINDOW {
+                0
+            } else {
+                // The previous last counter becomes bit `shift - 1`.
+                (window.seen << 1 | 1) << (shift - 1)
+            };
+            window.last = counter;
+        } else if counter < window.last {
+            window.seen |= 1 << (window.last - counter - 1);
+        }
+    }
+}
+
+/// Relays the datagrams of the game to a remote server, sealed if given a key.
+#[derive(Debug)]
+pub struct Forwarder {
+    socket: UdpSocket,
+    sealer: Option<Sealer>,
+}
+
+impl Forwarder {
+    /// Forwarder sending from an ephemeral port to `server`.
+    pub fn new<T: ToSocketAddrs>(server: T) -> std::io::Result<Forwarder> {
+        let socket = UdpSocket::bind("0.0.0.0:0")?;
+        socket.connect(server)?;
+
+        Ok(Forwarder {
+            socket,
+            sealer: None,
+        })
+    }
+
+    /// Seal the datagrams with a key shared with the server.
+    pub fn with_key(mut self, key: Key) -> Forwarder {
+        self.sealer = Some(Sealer::new(key));
+        self
+    }
+
+    /// Send a datagram of the game to the server.
+    pub fn send(&mut self, datagram: &[u8]) -> std::io::Result<()> {
+        match self.sealer.as_mut() {
+            Some(sealer) => self.socket.send(&sealer.seal(datagram))?,
+            None => self.socket.send(datagram)?,
+        };
+
+        Ok(())
+    }
+
+    /// Relay every datagram received on `socket`, e.g. bound to the telemetry
+    /// port of the game, until an error occurs.
+    pub fn relay(&mut self, socket: &UdpSocket) -> std::io::Result<()> {
+        let mut buf = vec![0; MAX_PACKET_SIZE + 1];
+
+        loop {
+            let len = socket.recv(&mut buf)?;
+            self.send(&buf[..len])?;
+        }
+    }
+}
+
+fn random_sender() -> u64 {
+    // A guessable id could repeat a nonce: better fail than seal with one.
+    let mut bytes = [0; 8];
+    getrandom::getrandom(&mut bytes).expect("No random numbers from the system");
+
+    u64::from_le_bytes(bytes)
+}
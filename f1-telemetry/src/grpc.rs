@@ -0,0 +1,361 @@
+//! gRPC service streaming the parsed packets to remote clients.
+//!
+//! The messages are defined in `proto/telemetry.proto`, so that clients in any
+//! language can generate their bindings from it. [`TelemetryService`] is fed
+//! from a [`Pipeline`] through the sink returned by [`TelemetryService::sink`],
+//! and every subscribed client receives the packets as they arrive.
+//!
+//! Enabled with the `grpc` feature.
+//!
+//! [`TelemetryService`]: ./struct.TelemetryService.html
+//! [`TelemetryService::sink`]: ./struct.TelemetryService.html#method.sink
+//! [`Pipeline`]: ../pipeline/struct.Pipeline.html
+
+use std::collections::HashSet;
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::sync::Arc;
+use tokio::sync::broadcast;
+use tokio_stream::wrappers::BroadcastStream;
+use tokio_stream::{Stream, StreamExt};
+use tonic::{Request, Response, Status};
+
+use crate::packet::car_status::PacketCarStatusData;
+use crate::packet::car_telemetry::PacketCarTelemetryData;
+use crate::packet::encode::encode_packet;
+use crate::packet::event::PacketEventData;
+use crate::packet::generic::WheelData;
+use crate::packet::header::PacketHeader;
+use crate::packet::lap::PacketLapData;
+use crate::packet::motion::PacketMotionData;
+use crate::packet::participants::PacketParticipantsData;
+use crate::packet::session::PacketSessionData;
+use crate::packet::Packet;
+use crate::pipeline::PacketSink;
+
+/// Messages and service generated from `proto/telemetry.proto`.
+pub mod proto {
+    tonic::include_proto!("f1telemetry");
+}
+
+use proto::packet::Data;
+use proto::telemetry_server::{Telemetry, TelemetryServer};
+
+/// Serves the packets to the clients subscribed over gRPC.
+///
+/// Up to `capacity` packets are queued for each client; a client that falls
+/// further behind skips the oldest packets.
+#[derive(Debug, Clone)]
+pub struct TelemetryService {
+    sender: broadcast::Sender<Arc<proto::Packet>>,
+}
+
+impl TelemetryService {
+    pub fn new(capacity: usize) -> TelemetryService {
+        let (sender, _) = broadcast::channel(capacity);
+
+        TelemetryService { sender }
+    }
+
+    /// Sink sending the packets it handles to the subscribed clients.
+    pub fn sink(&self) -> GrpcSink {
+        GrpcSink {
+            sender: self.sender.clone(),
+        }
+    }
+
+    /// Number of clients currently subscribed.
+    pub fn subscribers(&self) -> usize {
+        self.sender.receiver_count()
+    }
+
+    /// Serve the service on `addr` until the server fails.
+    pub async fn serve(self, addr: SocketAddr) -> Result<(), tonic::transport::Error> {
+        tonic::transport::Server::builder()
+            .add_service(TelemetryServer::new(self))
+            .serve(addr)
+            .await
+    }
+}
+
+type PacketStream = Pin<Box<dyn Stream<Item = Result<proto::Packet, Status>> + Send>>;
+
+#[tonic::async_trait]
+impl Telemetry for TelemetryService {
+    type SubscribeStream = PacketStream;
+
+    async fn subscribe(
+        &self,
+        request: Request<proto::SubscribeRequest>,
+    ) -> Result<Response<Self::SubscribeStream>, Status> {
+        let packet_ids: HashSet<u32> = request.into_inner().packet_ids.into_iter().collect();
+
+        let stream = BroadcastStream::new(self.sender.subscribe()).filter_map(move |packet| {
+            // Packets skipped by a slow client are reported as lagged, ignore them.
+            let packet = packet.ok()?;
+            let packet_id = packet.header.as_ref().map(|h| h.packet_id);
+
+            if packet_ids.is_empty() || packet_id.is_some_and(|id| packet_ids.contains(&id)) {
+                Some(Ok(proto::Packet::clone(&packet)))
+            } else {
+                None
+            }
+        });
+
+        Ok(Response::new(Box::pin(stream)))
+    }
+}
+
+/// Converts the packets to their gRPC messages for a [`TelemetryService`].
+///
+/// [`TelemetryService`]: ./struct.TelemetryService.html
+#[derive(Debug, Clone)]
+pub struct GrpcSink {
+    sender: broadcast::Sender<Arc<proto::Packet>>,
+}
+
+impl PacketSink for GrpcSink {
+    fn handle(&mut self, packet: &Packet) {
+        // Nothing to convert when no client is subscribed.
+        if self.sender.receiver_count() > 0 {
+            let _ = self.sender.send(Arc::new(proto::Packet::from(packet)));
+        }
+    }
+}
+
+impl From<&Packet> for proto::Packet {
+    fn from(packet: &Packet) -> proto::Packet {
+        let (header, data) = match packet {
+            Packet::Motion(p) => (p.header(), Data::Motion(motion(p))),
+            Packet::Session(p) => (p.header(), Data::Session(session(p))),
+            Packet::Lap(p) => (p.header(), Data::Lap(lap(p))),
+            Packet::Event(p) => (p.header(), Data::Event(event(p))),
+            Packet::Participants(p) => (p.header(), Data::Participants(participants(p))),
+            Packet::CarTelemetry(p) => (p.header(), Data::CarTelemetry(car_telemetry(p))),
+            Packet::CarStatus(p) => (p.header(), Data::CarStatus(car_status(p))),
+            Packet::CarSetups(p) => (p.header(), raw(packet)),
+            Packet::FinalClassification(p) => (p.header(), raw(packet)),
+            Packet::LobbyInfo(p) => (p.header(), raw(packet)),
+            Packet::CarDamage(p) => (p.header(), raw(packet)),
+            Packet::TyreSets(p) => (p.header(), raw(packet)),
+            Packet::MotionEx(p) => (p.header(), raw(packet)),
+            Packet::TimeTrial(p) => (p.header(), raw(packet)),
+            // Unparsed datagrams have no header to mirror, it is part of the data.
+            Packet::Raw(p) => {
+                return proto::Packet {
+                    header: None,
+                    data: Some(Data::Raw(p.data().clone())),
+                }
+            }
+        };
+
+        proto::Packet {
+            header: Some(header_message(header)),
+            data: Some(data),
+        }
+    }
+}
+
+fn header_message(header: &PacketHeader) -> proto::Header {
+    proto::Header {
+        packet_format: header.packet_format() as u32,
+        game_major_version: header.game_major_version() as u32,
+        game_minor_version: header.game_minor_version() as u32,
+        packet_version: header.packet_version() as u32,
+        packet_id: header.packet_id() as u32,
+        session_uid: header.session_uid(),
+        session_time: header.session_time(),
+        frame_identifier: header.frame_identifier(),
+        player_car_index: header.player_car_index() as u32,
+        secondary_player_car_index: header.secondary_player_car_index().map(u32::from),
+    }
+}
+
+fn raw(packet: &Packet) -> Data {
+    Data::Raw(encode_packet(packet))
+}
+
+fn name<T: std::fmt::Debug>(value: T) -> String {
+    format!("{:?}", value)
+}
+
+fn wheel_uint<T: Copy + Into<u32>>(data: WheelData<T>) -> proto::WheelUint {
+    proto::WheelUint {
+        rear_left: data.rear_left().into(),
+        rear_right: data.rear_right().into(),
+        front_left: data.front_left().into(),
+        front_right: data.front_right().into(),
+    }
+}
+
+fn wheel_float(data: WheelData<f32>) -> proto::WheelFloat {
+    proto::WheelFloat {
+        rear_left: data.rear_left(),
+        rear_right: data.rear_right(),
+        front_left: data.front_left(),
+        front_right: data.front_right(),
+    }
+}
+
+fn motion(p: &PacketMotionData) -> proto::MotionPacket {
+    proto::MotionPacket {
+        cars: p
+            .motion_data()
+            .iter()
+            .map(|m| proto::CarMotion {
+                world_position_x: m.world_position_x(),
+                world_position_y: m.world_position_y(),
+                world_position_z: m.world_position_z(),
+                world_velocity_x: m.world_velocity_x(),
+                world_velocity_y: m.world_velocity_y(),
+                world_velocity_z: m.world_velocity_z(),
+                g_force_lateral: m.g_force_lateral(),
+                g_force_longitudinal: m.g_force_longitudinal(),
+                g_force_vertical: m.g_force_vertical(),
+                yaw: m.yaw(),
+                pitch: m.pitch(),
+                roll: m.roll(),
+            })
+            .collect(),
+    }
+}
+
+fn session(p: &PacketSessionData) -> proto::SessionPacket {
+    proto::SessionPacket {
+        weather: name(p.weather()),
+        track_temperature: p.track_temperature() as i32,
+        air_temperature: p.air_temperature() as i32,
+        total_laps: p.total_laps() as u32,
+        track_length: p.track_length() as u32,
+        session_type: name(p.session_type()),
+        track: name(p.track()),
+        session_time_left: p.session_time_left() as u32,
+        session_duration: p.session_duration() as u32,
+        pit_speed_limit: p.pit_speed_limit() as u32,
+        safety_car_status: name(p.safety_car_status()),
+        network_game: p.network_game(),
+    }
+}
+
+fn lap(p: &PacketLapData) -> proto::LapPacket {
+    proto::LapPacket {
+        cars: p
+            .lap_data()
+            .iter()
+            .map(|l| proto::LapData {
+                last_lap_time: l.last_lap_time(),
+                current_lap_time: l.current_lap_time(),
+                best_lap_time: l.best_lap_time(),
+                sector_1_time: l.sector_1_time(),
+                sector_2_time: l.sector_2_time(),
+                lap_distance: l.lap_distance(),
+                total_distance: l.total_distance(),
+                safety_car_delta: l.safety_car_delta(),
+                car_position: l.car_position() as u32,
+                current_lap_num: l.current_lap_num() as u32,
+                pit_status: name(l.pit_status()),
+                sector: l.sector() as u32,
+                current_lap_invalid: l.current_lap_invalid(),
+                penalties: l.penalties() as u32,
+                grid_position: l.grid_position() as u32,
+                driver_status: name(l.driver_status()),
+                result_status: name(l.result_status()),
+            })
+            .collect(),
+    }
+}
+
+fn event(p: &PacketEventData) -> proto::EventPacket {
+    proto::EventPacket {
+        code: p.event().code().to_string(),
+        vehicle_idx: p.vehicle_idx().map(u32::from),
+        lap_time: p.lap_time(),
+        penalty: p.penalty().map(|penalty| proto::Penalty {
+            penalty_type: name(penalty.penalty_type()),
+            infringement_type: name(penalty.infringement_type()),
+            vehicle_idx: penalty.vehicle_idx() as u32,
+            other_vehicle_idx: penalty.other_vehicle_idx().map(u32::from),
+            time: penalty.time().map(u32::from),
+            lap_num: penalty.lap_num() as u32,
+            places_gained: penalty.places_gained() as u32,
+        }),
+        speed: p.speed(),
+    }
+}
+
+fn participants(p: &PacketParticipantsData) -> proto::ParticipantsPacket {
+    proto::ParticipantsPacket {
+        num_active_cars: p.num_active_cars() as u32,
+        participants: p
+            .participants()
+            .iter()
+            .map(|participant| proto::Participant {
+                ai_controlled: participant.ai_controlled(),
+                driver: name(participant.driver()),
+                team: name(participant.team()),
+                race_number: participant.race_number() as u32,
+                nationality: name(participant.nationality()),
+                name: participant.name().clone(),
+            })
+            .collect(),
+    }
+}
+
+fn car_telemetry(p: &PacketCarTelemetryData) -> proto::CarTelemetryPacket {
+    proto::CarTelemetryPacket {
+        cars: p
+            .car_telemetry_data()
+            .iter()
+            .map(|t| proto::CarTelemetry {
+                speed: t.speed() as u32,
+                throttle: t.throttle(),
+                steer: t.steer(),
+                brake: t.brake(),
+                clutch: t.clutch() as u32,
+                gear: t.gear() as i32,
+                engine_rpm: t.engine_rpm() as u32,
+                drs: t.drs(),
+                rev_lights_percent: t.rev_lights_percent() as u32,
+                brakes_temperature: Some(wheel_uint(t.brakes_temperature())),
+                tyres_surface_temperature: Some(wheel_uint(t.tyres_surface_temperature())),
+                tyres_inner_temperature: Some(wheel_uint(t.tyres_inner_temperature())),
+                engine_temperature: t.engine_temperature() as u32,
+                tyre_pressures: Some(wheel_float(t.tyre_pressures())),
+            })
+            .collect(),
+        button_status: p.button_status(),
+    }
+}
+
+fn car_status(p: &PacketCarStatusData) -> proto::CarStatusPacket {
+    proto::CarStatusPacket {
+        cars: p
+            .car_status_data()
+            .iter()
+            .map(|s| proto::CarStatus {
+                traction_control: name(s.traction_control()),
+                anti_lock_brakes: s.anti_lock_brakes(),
+                fuel_mix: name(s.fuel_mix()),
+                front_brake_bias: s.front_brake_bias() as u32,
+                pit_limiter: s.pit_limiter(),
+                fuel_in_tank: s.fuel_in_tank(),
+                fuel_capacity: s.fuel_capacity(),
+                fuel_remaining_laps: s.fuel_remaining_laps(),
+                max_rpm: s.max_rpm() as u32,
+                idle_rpm: s.idle_rpm() as u32,
+                max_gears: s.max_gears() as u32,
+                drs_allowed: name(s.drs_allowed()),
+                tyres_wear: Some(wheel_uint(s.tyres_wear())),
+                actual_tyre_compound: name(s.actual_tyre_compound()),
+                visual_tyre_compound: name(s.visual_tyre_compound()),
+                tyres_damage: Some(wheel_uint(s.tyres_damage())),
+                vehicle_fia_flags: name(s.vehicle_fia_flags()),
+                ers_store_energy: s.ers_store_energy(),
+                ers_deploy_mode: name(s.ers_deploy_mode()),
+                ers_harvested_this_lap_mguk: s.ers_harvested_this_lap_mguk(),
+                ers_harvested_this_lap_mguh: s.ers_harvested_this_lap_mguh(),
+                ers_deployed_this_lap: s.ers_deployed_this_lap(),
+            })
+            .collect(),
+    }
+}
@@ -0,0 +1,3 @@
+//! Inputs reading the telemetry from other sources than the game's UDP stream.
+
+pub mod pcap;
@@ -0,0 +1,544 @@
+//! Telemetry captured with Wireshark or tcpdump.
+//!
+//! [`PcapReader`] reads the UDP datagrams sent to the telemetry port from a
+//! pcap or pcapng capture and parses them as packets. Ethernet, Linux cooked
+//! (SLL and SLL2), loopback and raw IP captures are supported, over IPv4 and
+//! IPv6. Fragmented datagrams are skipped, which the game does not send with
+//! the usual MTU.
+//!
+//! [`PcapReader`]: ./struct.PcapReader.html
+
+use std::fs::File;
+use std::io::{self, BufReader, ErrorKind, Read};
+use std::path::Path;
+use std::time::Duration;
+
+use crate::packet::{parse_packet_fuzz, Packet, UnpackError};
+
+/// Port the game sends the telemetry to by default.
+pub const DEFAULT_PORT: u16 = 20777;
+
+const PCAP_MAGIC_MICROS: u32 = 0xa1b2_c3d4;
+const PCAP_MAGIC_NANOS: u32 = 0xa1b2_3c4d;
+const PCAPNG_SECTION_HEADER: u32 = 0x0a0d_0d0a;
+const PCAPNG_BYTE_ORDER_MAGIC: u32 = 0x1a2b_3c4d;
+
+const PCAPNG_INTERFACE_DESCRIPTION: u32 = 1;
+const PCAPNG_PACKET: u32 = 2;
+const PCAPNG_SIMPLE_PACKET: u32 = 3;
+const PCAPNG_ENHANCED_PACKET: u32 = 6;
+const PCAPNG_OPTION_END: u16 = 0;
+const PCAPNG_OPTION_TSRESOL: u16 = 9;
+
+/// Largest block read from a capture, so that a corrupt length does not
+/// exhaust the memory.
+const MAX_BLOCK_SIZE: usize = 16 * 1024 * 1024;
+
+const LINKTYPE_NULL: u16 = 0;
+const LINKTYPE_ETHERNET: u16 = 1;
+const LINKTYPE_RAW: u16 = 101;
+const LINKTYPE_LOOP: u16 = 108;
+const LINKTYPE_LINUX_SLL: u16 = 113;
+const LINKTYPE_IPV4: u16 = 228;
+const LINKTYPE_IPV6: u16 = 229;
+const LINKTYPE_LINUX_SLL2: u16 = 276;
+
+const ETHERTYPE_IPV4: u16 = 0x0800;
+const ETHERTYPE_IPV6: u16 = 0x86dd;
+const ETHERTYPE_VLAN: u16 = 0x8100;
+const ETHERTYPE_QINQ: u16 = 0x88a8;
+
+const IP_PROTOCOL_UDP: u8 = 17;
+
+#[derive(Debug)]
+pub enum PcapError {
+    /// The capture could not be read.
+    Io(io::Error),
+    /// The capture is not a pcap or pcapng file, or is corrupt.
+    Format(String),
+    /// The datagram could not be parsed as a packet.
+    Unpack(UnpackError),
+}
+
+impl From<io::Error> for PcapError {
+    fn from(e: io::Error) -> Self {
+        PcapError::Io(e)
+    }
+}
+
+impl From<UnpackError> for PcapError {
+    fn from(e: UnpackError) -> Self {
+        PcapError::Unpack(e)
+    }
+}
+
+/// A UDP datagram sent to the telemetry port.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Datagram {
+    /// Time of capture, since the Unix epoch.
+    pub timestamp: Duration,
+    pub payload: Vec<u8>,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Format {
+    Pcap {
+        big_endian: bool,
+        nanos: bool,
+        link_type: u16,
+    },
+    Pcapng {
+        big_endian: bool,
+    },
+}
+
+/// Interface of a pcapng section, which the packets refer to by index.
+#[derive(Debug, Clone, Copy)]
+struct Interface {
+    link_type: u16,
+    /// Timestamp units per second.
+    resolution: u64,
+}
+
+/// Reads the telemetry datagrams from a pcap or pcapng capture.
+///
+/// Iterating over the reader yields the parsed packets; datagrams which cannot
+/// be parsed are returned as errors and the iteration goes on.
+pub struct PcapReader<R> {
+    reader: R,
+    format: Format,
+    interfaces: Vec<Interface>,
+    port: u16,
+}
+
+impl PcapReader<BufReader<File>> {
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<PcapReader<BufReader<File>>, PcapError> {
+        PcapReader::new(BufReader::new(File::open(path)?))
+    }
+}
+
+impl<R: Read> PcapReader<R> {
+    /// Read the header of the capture, detecting its format.
+    pub fn new(mut reader: R) -> Result<PcapReader<R>, PcapError> {
+        let mut magic = [0; 4];
+        reader.read_exact(&mut magic)?;
+
+        let format = if u32::from_le_bytes(magic) == PCAPNG_SECTION_HEADER {
+            let big_endian = read_section_header(&mut reader)?;
+            Format::Pcapng { big_endian }
+        } else {
+            let (big_endian, nanos) = match (u32::from_le_bytes(magic), u32::from_be_bytes(magic)) {
+                (PCAP_MAGIC_MICROS, _) => (false, false),
+                (PCAP_MAGIC_NANOS, _) => (false, true),
+                (_, PCAP_MAGIC_MICROS) => (true, false),
+                (_, PCAP_MAGIC_NANOS) => (true, true),
+                _ => {
+                    return Err(PcapError::Format(String::from(
+                        "Not a pcap or pcapng capture",
+                    )))
+                }
+            };
+
+            // Version, time zone, accuracy and snapshot length, then the link type.
+            let mut header = [0; 20];
+            reader.read_exact(&mut header)?;
+            let link_type = read_u32(&header[16..], big_endian) as u16;
+
+            Format::Pcap {
+                big_endian,
+                nanos,
+                link_type,
+            }
+        };
+
+        Ok(PcapReader {
+            reader,
+            format,
+            interfaces: Vec::new(),
+            port: DEFAULT_PORT,
+        })
+    }
+
+    /// Only read the datagrams sent to this port, instead of the default one.
+    pub fn with_port(mut self, port: u16) -> PcapReader<R> {
+        self.port = port;
+        self
+    }
+
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+
+    /// Read the next datagram sent to the telemetry port, or `None` at the end
+    /// of the capture. Other traffic is skipped.
+    pub fn next_datagram(&mut self) -> Result<Option<Datagram>, PcapError> {
+        loop {
+            let frame = match self.format {
+                Format::Pcap {
+                    big_endian,
+                    nanos,
+                    link_type,
+                } => self.read_pcap_record(big_endian, nanos, link_type)?,
+                Format::Pcapng { big_endian } => self.read_pcapng_block(big_endian)?,
+            };
+
+            let (timestamp, link_type, data) = match frame {
+                Frame::End => return Ok(None),
+                Frame::Skipped => continue,
+                Frame::Data(timestamp, link_type, data) => (timestamp, link_type, data),
+            };
+
+            if let Some(payload) = udp_payload(link_type, &data, self.port) {
+                return Ok(Some(Datagram {
+                    timestamp,
+                    payload: payload.to_vec(),
+                }));
+            }
+        }
+    }
+
+    fn read_pcap_record(
+        &mut self,
+        big_endian: bool,
+        nanos: bool,
+        link_type: u16,
+    ) -> Result<Frame, PcapError> {
+        let mut header = [0; 16];
+        if !read_or_end(&mut self.reader, &mut header)? {
+            return Ok(Frame::End);
+        }
+
+        let secs = read_u32(&header[0..], big_endian) as u64;
+        let fraction = read_u32(&header[4..], big_endian);
+        let captured = read_u32(&header[8..], big_endian) as usize;
+
+        let data = read_block(&mut self.reader, captured)?;
+        let timestamp = if nanos {
+            Duration::new(secs, fraction)
+        } else {
+            Duration::new(secs, 0) + Duration::from_micros(fraction as u64)
+        };
+
+        Ok(Frame::Data(timestamp, link_type, data))
+    }
+
+    fn read_pcapng_block(&mut self, big_endian: bool) -> Result<Frame, PcapError> {
+        let mut header = [0; 8];
+        if !read_or_end(&mut self.reader, &mut header)? {
+            return Ok(Frame::End);
+        }
+
+        let block_type = read_u32(&header[0..], big_endian);
+        let length = read_u32(&header[4..], big_endian) as usize;
+
+        if block_type == PCAPNG_SECTION_HEADER {
+            // A new section may change the byte order, and has its own
+            // interfaces.
+            let big_endian = read_section_header_body(&mut self.reader, &header[4..])?;
+            self.format = Format::Pcapng { big_endian };
+            self.interfaces.clear();
+            return Ok(Frame::Skipped);
+        }
+
+        if length < 12 || !length.is_multiple_of(4) {
+            return Err(PcapError::Format(format!(
+                "Invalid pcapng block length: {}",
+                length
+            )));
+        }
+
+        // The body, followed by the length repeated.
+        let block = read_block(&mut self.reader, length - 8)?;
+        let body = &block[..block.len() - 4];
+
+        match block_type {
+            PCAPNG_INTERFACE_DESCRIPTION => {
+                self.interfaces.push(read_interface(body, big_endian)?);
+                Ok(Frame::Skipped)
+            }
+            PCAPNG_ENHANCED_PACKET | PCAPNG_PACKET => {
+                if body.len() < 20 {
+                    return Err(PcapError::Format(String::from("Truncated pcapng packet")));
+                }
+
+                // The obsolete Packet Block has a 16-bit interface id followed
+                // by the drops count, with the same layout otherwise.
+                let interface_id = if block_type == PCAPNG_PACKET {
+                    read_u16(body, big_endian) as usize
+                } else {
+                    read_u32(body, big_endian) as usize
+                };
+                let interface = self.interface(interface_id)?;
+
+                let high = read_u32(&body[4..], big_endian) as u64;
+                let low = read_u32(&body[8..], big_endian) as u64;
+                let captured = read_u32(&body[12..], big_endian) as usize;
+                let data = body
+                    .get(20..20 + captured)
+                    .ok_or_else(|| PcapError::Format(String::from("Truncated pcapng packet")))?;
+
+                let ticks = high << 32 | low;
+                let timestamp = Duration::from_secs(ticks / interface.resolution)
+                    + Duration::from_nanos(
+                        (ticks % interface.resolution) * 1_000_000_000 / interface.resolution,
+                    );
+
+                Ok(Frame::Data(timestamp, interface.link_type, data.to_vec()))
+            }
+            PCAPNG_SIMPLE_PACKET => {
+                let interface = self.interface(0)?;
+                let data = body.get(4..).unwrap_or_default();
+                let original = read_u32(body, big_endian) as usize;
+                let data = &data[..original.min(data.len())];
+
+                // Simple Packet Blocks have no timestamp.
+                Ok(Frame::Data(
+                    Duration::default(),
+                    interface.link_type,
+                    data.to_vec(),
+                ))
+            }
+            _ => Ok(Frame::Skipped),
+        }
+    }
+
+    fn interface(&self, id: usize) -> Result<Interface, PcapError> {
+        self.interfaces
+            .get(id)
+            .copied()
+            .ok_or_else(|| PcapError::Format(format!("Unknown pcapng interface: {}", id)))
+    }
+}
+
+impl<R: Read> Iterator for PcapReader<R> {
+    type Item = Result<(Duration, Packet), PcapError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.next_datagram() {
+            Ok(Some(datagram)) => Some(
+                parse_packet_fuzz(&datagram.payload)
+                    .map(|packet| (datagram.timestamp, packet))
+                    .map_err(PcapError::from),
+            ),
+            Ok(None) => None,
+            Err(e) => Some(Err(e)),
+        }
+    }
+}
+
+enum Frame {
+    End,
+    Skipped,
+    Data(Duration, u16, Vec<u8>),
+}
+
+/// Read the rest of a Section Header Block after its type, returning whether
+/// the section is big endian.
+fn read_section_header<R: Read>(reader: &mut R) -> Result<bool, PcapError> {
+    let mut length = [0; 4];
+    reader.read_exact(&mut length)?;
+    read_section_header_body(reader, &length)
+}
+
+fn read_section_header_body<R: Read>(reader: &mut R, length: &[u8]) -> Result<bool, PcapError> {
+    let mut magic = [0; 4];
+    reader.read_exact(&mut magic)?;
+
+    let big_endian = match u32::from_le_bytes(magic) {
+        PCAPNG_BYTE_ORDER_MAGIC => false,
+        m if m.swap_bytes() == PCAPNG_BYTE_ORDER_MAGIC => true,
+        _ => {
+            return Err(PcapError::Format(String::from(
+                "Invalid pcapng byte-order magic",
+            )))
+        }
+    };
+
+    let length = read_u32(length, big_endian) as usize;
+    if length < 16 || !length.is_multiple_of(4) {
+        return Err(PcapError::Format(format!(
+            "Invalid pcapng block length: {}",
+            length
+        )));
+    }
+
+    // Versions, section length, options and the length repeated.
+    read_block(reader, length - 12)?;
+
+    Ok(big_endian)
+}
+
+fn read_interface(body: &[u8], big_endian: bool) -> Result<Interface, PcapError> {
+    if body.len() < 8 {
+        return Err(PcapError::Format(String::from(
+            "Truncated pcapng interface",
+        )));
+    }
+
+    let link_type = read_u16(body, big_endian);
+    let mut resolution = 1_000_000;
+
+    let mut options = &body[8..];
+    while options.len() >= 4 {
+        let code = read_u16(options, big_endian);
+        let length = read_u16(&options[2..], big_endian) as usize;
+        let value = options.get(4..4 + length).unwrap_or_default();
+
+        if code == PCAPNG_OPTION_END {
+            break;
+        }
+        if code == PCAPNG_OPTION_TSRESOL {
+            if let Some(&tsresol) = value.first() {
+                // Negative power of 10, or of 2 when the high bit is set.
+                let exponent = (tsresol & 0x7f) as u32;
+                let base: u64 = if tsresol & 0x80 == 0 { 10 } else { 2 };
+                resolution = base
+                    .checked_pow(exponent)
+                    .filter(|r| *r > 0)
+                    .ok_or_else(|| {
+                        PcapError::Format(format!(
+                            "Invalid pcapng timestamp resolution: {}",
+                            tsresol
+                        ))
+                    })?;
+            }
+        }
+
+        let padded = length.div_ceil(4) * 4;
+        options = options.get(4 + padded..).unwrap_or_default();
+    }
+
+    Ok(Interface {
+        link_type,
+        resolution,
+    })
+}
+
+/// Payload of a UDP datagram sent to `port`, from a frame of the given link type.
+fn udp_payload(link_type: u16, frame: &[u8], port: u16) -> Option<&[u8]> {
+    let (ether_type, packet) = match link_type {
+        LINKTYPE_ETHERNET => {
+            let mut ether_type = u16::from_be_bytes([*frame.get(12)?, *frame.get(13)?]);
+            let mut offset = 14;
+            while ether_type == ETHERTYPE_VLAN || ether_type == ETHERTYPE_QINQ {
+                ether_type = u16::from_be_bytes([*frame.get(offset + 2)?, *frame.get(offset + 3)?]);
+                offset += 4;
+            }
+            (ether_type, frame.get(offset..)?)
+        }
+        LINKTYPE_LINUX_SLL => (
+            u16::from_be_bytes([*frame.get(14)?, *frame.get(15)?]),
+            frame.get(16..)?,
+        ),
+        LINKTYPE_LINUX_SLL2 => (
+            u16::from_be_bytes([*frame.first()?, *frame.get(1)?]),
+            frame.get(20..)?,
+        ),
+        LINKTYPE_NULL | LINKTYPE_LOOP => {
+            // The address family, in the byte order of the capturing host
+            // for NULL and big endian for LOOP. IPv4 is 2 everywhere, IPv6
+            // differs between systems so the IP version is looked at instead.
+            (ip_ether_type(frame.get(4..)?)?, frame.get(4..)?)
+        }
+        LINKTYPE_RAW | LINKTYPE_IPV4 | LINKTYPE_IPV6 => (ip_ether_type(frame)?, frame),
+        _ => return None,
+    };
+
+    let udp = match ether_type {
+        ETHERTYPE_IPV4 => ipv4_udp(packet)?,
+        ETHERTYPE_IPV6 => ipv6_udp(packet)?,
+        _ => return None,
+    };
+
+    if udp.len() < 8 || u16::from_be_bytes([udp[2], udp[3]]) != port {
+        return None;
+    }
+
+    let length = u16::from_be_bytes([udp[4], udp[5]]) as usize;
+    udp.get(8..length.max(8).min(udp.len()))
+}
+
+/// Ether type of a raw IP packet, from its version.
+fn ip_ether_type(packet: &[u8]) -> Option<u16> {
+    match packet.first()? >> 4 {
+        4 => Some(ETHERTYPE_IPV4),
+        6 => Some(ETHERTYPE_IPV6),
+        _ => None,
+    }
+}
+
+fn ipv4_udp(packet: &[u8]) -> Option<&[u8]> {
+    let header_length = ((*packet.first()? & 0x0f) as usize) * 4;
+    let total_length = u16::from_be_bytes([*packet.get(2)?, *packet.get(3)?]) as usize;
+    let flags_offset = u16::from_be_bytes([*packet.get(6)?, *packet.get(7)?]);
+    let protocol = *packet.get(9)?;
+
+    // More fragments, or a fragment offset.
+    if protocol != IP_PROTOCOL_UDP || flags_offset & 0x3fff != 0 {
+        return None;
+    }
+
+    let end = total_length.min(packet.len());
+    packet.get(header_length..end)
+}
+
+fn ipv6_udp(packet: &[u8]) -> Option<&[u8]> {
+    let payload_length = u16::from_be_bytes([*packet.get(4)?, *packet.get(5)?]) as usize;
+    let next_header = *packet.get(6)?;
+
+    // Extension headers, including fragments, are not followed.
+    if next_header != IP_PROTOCOL_UDP {
+        return None;
+    }
+
+    let end = (40 + payload_length).min(packet.len());
+    packet.get(40..end)
+}
+
+/// Fill `buf`, returning false when the reader is at its end.
+fn read_or_end<R: Read>(reader: &mut R, buf: &mut [u8]) -> Result<bool, PcapError> {
+    let mut read = 0;
+    while read < buf.len() {
+        match reader.read(&mut buf[read..]) {
+            Ok(0) if read == 0 => return Ok(false),
+            Ok(0) => return Err(PcapError::Io(ErrorKind::UnexpectedEof.into())),
+            Ok(n) => read += n,
+            Err(e) if e.kind() == ErrorKind::Interrupted => {}
+            Err(e) => return Err(e.into()),
+        }
+    }
+
+    Ok(true)
+}
+
+fn read_block<R: Read>(reader: &mut R, length: usize) -> Result<Vec<u8>, PcapError> {
+    if length > MAX_BLOCK_SIZE {
+        return Err(PcapError::Format(format!(
+            "Block too large: {} bytes",
+            length
+        )));
+    }
+
+    let mut block = vec![0; length];
+    reader.read_exact(&mut block)?;
+
+    Ok(block)
+}
+
+fn read_u16(bytes: &[u8], big_endian: bool) -> u16 {
+    let bytes = [bytes[0], bytes[1]];
+    if big_endian {
+        u16::from_be_bytes(bytes)
+    } else {
+        u16::from_le_bytes(bytes)
+    }
+}
+
+fn read_u32(bytes: &[u8], big_endian: bool) -> u32 {
+    let bytes = [bytes[0], bytes[1], bytes[2], bytes[3]];
+    if big_endian {
+        u32::from_be_bytes(bytes)
+    } else {
+        u32::from_le_bytes(bytes)
+    }
+}
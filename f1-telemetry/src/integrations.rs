@@ -0,0 +1,4 @@
+//! Posts the events of a session to third-party services.
+
+pub mod discord;
+pub mod simhub;
@@ -0,0 +1,340 @@
+use std::fmt::Write as _;
+use std::io;
+
+use crate::json::json_string;
+use crate::packet::event::{Event, PacketEventData};
+use crate::packet::final_classification::{FinalClassificationData, PacketFinalClassificationData};
+use crate::packet::header::SessionIdentifier;
+use crate::packet::lap::ResultStatus;
+use crate::packet::session::{PacketSessionData, SessionType, Track};
+use crate::packet::Packet;
+use crate::pipeline::PacketSink;
+
+/// Maximum length of the content of a Discord message.
+const MAX_CONTENT_LENGTH: usize = 2000;
+
+/// Events of a session that can be posted.
+///
+/// ## Specification
+/// ```text
+/// SessionStart:    The session started, e.g. a race
+/// FastestLap:      A driver set the fastest lap
+/// Penalty:         A driver was given a penalty
+/// Retirement:      A driver retired
+/// Classification:  The final classification table, at the end of the session
+/// ```
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum DiscordEvent {
+    SessionStart,
+    FastestLap,
+    Penalty,
+    Retirement,
+    Classification,
+}
+
+const ALL_EVENTS: [DiscordEvent; 5] = [
+    DiscordEvent::SessionStart,
+    DiscordEvent::FastestLap,
+    DiscordEvent::Penalty,
+    DiscordEvent::Retirement,
+    DiscordEvent::Classification,
+];
+
+/// Delivers JSON payloads to a webhook.
+///
+/// The crate has no HTTP client: implement it with the one of the
+/// application, posting the payload to the webhook URL with the
+/// `Content-Type: application/json` header, and failing when Discord does not
+/// answer with a success status.
+pub trait Webhook: Send {
+    fn post(&mut self, payload: &str) -> io::Result<()>;
+}
+
+/// Posts the events of a session to a Discord webhook, e.g. for the channel
+/// of a league.
+///
+/// The notifier is a [`PacketSink`]; posting blocks until the webhook answers,
+/// so it is best run on a worker of the pipeline. Drivers are named from the
+/// Participants packets, and the session from the Session packets. Mentions in
+/// driver names are not pinged.
+///
+/// [`PacketSink`]: ../../pipeline/trait.PacketSink.html
+#[derive(Debug)]
+pub struct DiscordNotifier<W: Webhook> {
+    webhook: W,
+    events: Vec<DiscordEvent>,
+    username: Option<String>,
+    names: Vec<String>,
+    session: Option<(SessionType, Track)>,
+    classified: Option<SessionIdentifier>,
+    errors: usize,
+}
+
+impl<W: Webhook> DiscordNotifier<W> {
+    /// Notifier posting all the events to a webhook.
+    pub fn new(webhook: W) -> DiscordNotifier<W> {
+        DiscordNotifier {
+            webhook,
+            events: ALL_EVENTS.to_vec(),
+            username: None,
+            names: Vec::new(),
+            session: None,
+            classified: None,
+            errors: 0,
+        }
+    }
+
+    /// Only post these events.
+    pub fn with_events(mut self, events: &[DiscordEvent]) -> DiscordNotifier<W> {
+        self.events = events.to_vec();
+        self
+    }
+
+    /// Post as this user instead of the name set for the webhook in Discord.
+    pub fn with_username<S: Into<String>>(mut self, username: S) -> DiscordNotifier<W> {
+        self.username = Some(username.into());
+        self
+    }
+
+    /// Number of messages that could not be posted.
+    pub fn errors(&self) -> usize {
+        self.errors
+    }
+
+    /// Update the notifier with a packet, returning the content of the message
+    /// to post, if any.
+    pub fn message(&mut self, packet: &Packet) -> Option<String> {
+        match packet {
+            Packet::Session(s) => {
+                self.update_session(s);
+                None
+            }
+            Packet::Participants(p) => {
+                self.names = p.participants().iter().map(|p| p.name().clone()).collect();
+                None
+            }
+            Packet::Event(e) => self.event_message(e),
+            Packet::FinalClassification(fc) => self.classification_message(fc),
+            _ => None,
+        }
+    }
+
+    fn update_session(&mut self, packet: &PacketSessionData) {
+        self.session = Some((packet.session_type(), packet.track()));
+    }
+
+    fn event_message(&self, packet: &PacketEventData) -> Option<String> {
+        let driver = || {
+            packet
+                .vehicle_idx()
+                .map(|idx| self.name(idx))
+                .unwrap_or_else(|| String::from("A driver"))
+        };
+
+        match packet.event() {
+            Event::SessionStarted if self.is_enabled(DiscordEvent::SessionStart) => {
+                Some(match self.session {
+                    Some((SessionType::Race, track)) | Some((SessionType::Race2, track)) => {
+                        format!(":checkered_flag: **Race started** at {}", track.name())
+                    }
+                    Some((session_type, track)) => format!(
+                        ":stopwatch: **{}** started at {}",
+                        session_type.name(),
+                        track.name()
+                    ),
+                    None => String::from(":stopwatch: **Session started**"),
+                })
+            }
+            Event::FastestLap if self.is_enabled(DiscordEvent::FastestLap) => {
+                let time = packet.lap_time().map(format_lap_time).unwrap_or_default();
+                Some(format!(
+                    ":purple_circle: **Fastest lap** {} {}",
+                    driver(),
+                    time
+                ))
+            }
+            Event::Penalty if self.is_enabled(DiscordEvent::Penalty) => {
+                let penalty = packet.penalty()?;
+                let time = penalty
+                    .time()
+                    .filter(|&t| t > 0)
+                    .map(|t| format!(" {}s", t))
+                    .unwrap_or_default();
+
+                Some(format!(
+                    ":warning: **{}{}** for {} on lap {}: {}",
+                    words(&format!("{:?}", penalty.penalty_type())),
+                    time,
+                    driver(),
+                    penalty.lap_num(),
+                    words(&format!("{:?}", penalty.infringement_type())).to_lowercase()
+                ))
+            }
+            Event::Retirement if self.is_enabled(DiscordEvent::Retirement) => {
+                Some(format!(":x: **{}** retired", driver()))
+            }
+            _ => None,
+        }
+    }
+
+    fn classification_message(&mut self, packet: &PacketFinalClassificationData) -> Option<String> {
+        // The game sends the classification several times.
+        let session = packet.header().session_id();
+        if !self.is_enabled(DiscordEvent::Classification) || self.classified == Some(session) {
+            return None;
+        }
+        self.classified = Some(session);
+
+        let mut classified: Vec<(usize, &FinalClassificationData)> = packet
+            .classification_data()
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| {
+                c.position() > 0
+                    && !matches!(
+                        c.result_status(),
+                        ResultStatus::Invalid | ResultStatus::Inactive
+                    )
+            })
+            .collect();
+        classified.sort_by_key(|(_, c)| c.position());
+
+        let title = match self.session {
+            Some((session_type, track)) => format!("{} - {}", session_type.name(), track.name()),
+            None => String::from("Final classification"),
+        };
+        let leader = classified.first().map(|(_, c)| *c);
+
+        let mut out = format!(":trophy: **{}**\n```\n", title);
+        let _ = writeln!(
+            out,
+            "{:>3}  {:<18} {:>4} {:>11} {:>9} {:>3}",
+            "Pos", "Driver", "Laps", "Time", "Best", "Pts"
+        );
+        for (idx, c) in &classified {
+            let mut name = self.name(*idx as u8);
+            name.truncate(name.char_indices().nth(18).map_or(name.len(), |(i, _)| i));
+
+            let _ = writeln!(
+                out,
+                "{:>3}  {:<18} {:>4} {:>11} {:>9} {:>3}",
+                c.position(),
+                name,
+                c.num_laps(),
+                race_time(c, leader),
+                if c.best_lap_time() > 0.0 {
+                    format_lap_time(c.best_lap_time())
+                } else {
+                    String::from("-")
+                },
+                c.points()
+            );
+        }
+        out.push_str("```");
+
+        Some(out)
+    }
+
+    fn is_enabled(&self, event: DiscordEvent) -> bool {
+        self.events.contains(&event)
+    }
+
+    fn name(&self, idx: u8) -> String {
+        self.names
+            .get(idx as usize)
+            .filter(|n| !n.is_empty())
+            .cloned()
+            .unwrap_or_else(|| format!("Car {}", idx))
+    }
+
+    fn payload(&self, content: &str) -> String {
+        let mut content = String::from(content);
+        if content.len() > MAX_CONTENT_LENGTH {
+            let end = (0..=MAX_CONTENT_LENGTH - 4)
+                .rev()
+                .find(|i| content.is_char_boundary(*i))
+                .unwrap_or(0);
+            content.truncate(end);
+            content.push_str("\n```");
+        }
+
+        let username = self
+            .username
+            .as_ref()
+            .map(|u| format!("\"username\":{},", json_string(u)))
+            .unwrap_or_default();
+
+        format!(
+            "{{{}\"content\":{},\"allowed_mentions\":{{\"parse\":[]}}}}",
+            username,
+            json_string(&content)
+        )
+    }
+}
+
+impl<W: Webhook> PacketSink for DiscordNotifier<W> {
+    fn handle(&mut self, packet: &Packet) {
+        if let Some(content) = self.message(packet) {
+            let payload = self.payload(&content);
+            if self.webhook.post(&payload).is_err() {
+                self.errors += 1;
+            }
+        }
+    }
+}
+
+/// Race time of the winner, and the gap to them of the others.
+fn race_time(c: &FinalClassificationData, leader: Option<&FinalClassificationData>) -> String {
+    match c.result_status() {
+        ResultStatus::Finished => {}
+        ResultStatus::Disqualified => return String::from("DSQ"),
+        ResultStatus::NotClassified => return String::from("NC"),
+        ResultStatus::Retired => return String::from("DNF"),
+        _ => return String::from("-"),
+    }
+
+    let total = c.total_race_time() + f64::from(c.penalties_time());
+    let leader = match leader {
+        Some(leader) if leader.position() != c.position() => leader,
+        _ => return format_race_time(total),
+    };
+
+    let laps_down = leader.num_laps().saturating_sub(c.num_laps());
+    match laps_down {
+        0 => format!(
+            "+{:.3}",
+            total - leader.total_race_time() - f64::from(leader.penalties_time())
+        ),
+        1 => String::from("+1 lap"),
+        n => format!("+{} laps", n),
+    }
+}
+
+fn format_race_time(time: f64) -> String {
+    let hours = (time / 3600.0) as u32;
+    let minutes = ((time - f64::from(hours) * 3600.0) / 60.0) as u32;
+    let seconds = time - f64::from(hours) * 3600.0 - f64::from(minutes) * 60.0;
+    format!("{}:{:02}:{:06.3}", hours, minutes, seconds)
+}
+
+fn format_lap_time(lap_time: f32) -> String {
+    let minutes = (lap_time / 60.0) as u32;
+    format!("{}:{:06.3}", minutes, lap_time - minutes as f32 * 60.0)
+}
+
+/// Split a variant name in camel case into words, e.g. `DriveThrough` into
+/// `Drive through`.
+fn words(name: &str) -> String {
+    let mut out = String::with_capacity(name.len() + 8);
+
+    for (i, c) in name.chars().enumerate() {
+        if c.is_uppercase() && i > 0 {
+            out.push(' ');
+            out.extend(c.to_lowercase());
+        } else {
+            out.push(c);
+        }
+    }
+
+    out
+}
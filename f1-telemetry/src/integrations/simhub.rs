@@ -0,0 +1,297 @@
+//! Feed for SimHub dashboards.
+//!
+//! SimHub reads the telemetry of the game on its own, so the simplest way to
+//! keep using its dashboards next to this crate is to relay the datagrams of
+//! the game to the port SimHub listens on with a [`Forwarder`], without a key.
+//!
+//! When SimHub cannot receive the datagrams of the game, e.g. with a league
+//! server in between, [`SimHubSender`] sends the decoded values of the
+//! player's car instead, as JSON objects whose keys are the names of SimHub's
+//! `DataCorePlugin.GameData.NewData` properties, e.g. `SpeedKmh` or `Gear`,
+//! one object per UDP datagram. A JSON UDP input plugin of SimHub maps them
+//! to the same properties, so that the existing dashboards show them as is.
+//!
+//! [`Forwarder`]: ../../forward/struct.Forwarder.html
+//! [`SimHubSender`]: ./struct.SimHubSender.html
+
+use std::io;
+use std::net::{ToSocketAddrs, UdpSocket};
+use std::time::{Duration, Instant};
+
+use crate::packet::car_status::DRS;
+use crate::packet::generic::Flag;
+use crate::packet::lap::PitStatus;
+use crate::packet::Packet;
+use crate::pipeline::PacketSink;
+
+/// Energy of a full ERS store, in Joules.
+const ERS_CAPACITY: f32 = 4_000_000.0;
+
+const WHEELS: [&str; 4] = ["FrontLeft", "FrontRight", "RearLeft", "RearRight"];
+
+/// Values of the player's car, named after SimHub's properties.
+///
+/// ## Specification
+/// ```text
+/// GameName:                         "F1" and the year of the packet format, e.g. "F120"
+/// SpeedKmh:                         Speed (km/h)
+/// Gear:                             "R", "N" or the gear number
+/// Rpms:                             Engine RPM
+/// MaxRpm:                           Rev limiter
+/// CarSettings_CurrentDisplayedRPMPercent: Rev lights (percentage)
+/// Throttle, Brake, Clutch:          Pedals (percentage)
+/// Fuel, MaxFuel:                    Fuel in tank and capacity (kg)
+/// FuelPercent:                      Fuel in tank (percentage of the capacity)
+/// EstimatedFuelRemaingLaps:         Laps left with the fuel in tank, with
+///                                   SimHub's spelling
+/// ERSPercent:                       ERS store (percentage)
+/// DRSAvailable, DRSEnabled:         DRS allowed and open (0 or 1)
+/// PitLimiterOn, IsInPitLane, IsInPit: Pit status (0 or 1)
+/// Position, CurrentLap, TotalLaps:  Race position and laps
+/// CurrentLapTime, LastLapTime, BestLapTime: Lap times (seconds)
+/// TyreTemperatureFrontLeft, ...:    Tyre surface temperatures (°C)
+/// BrakeTemperatureFrontLeft, ...:   Brake temperatures (°C)
+/// Flag_Green, Flag_Blue, Flag_Yellow: FIA flag shown to the car (0 or 1)
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SimHubData {
+    packet_format: u16,
+    speed: u16,
+    gear: i8,
+    rpm: u16,
+    max_rpm: u16,
+    rev_lights_percent: u8,
+    throttle: f32,
+    brake: f32,
+    clutch: u8,
+    fuel: f32,
+    max_fuel: f32,
+    fuel_remaining_laps: f32,
+    ers_store_energy: f32,
+    drs_available: bool,
+    drs_enabled: bool,
+    pit_limiter: bool,
+    pit_status: Option<PitStatus>,
+    position: u8,
+    current_lap: u8,
+    total_laps: u8,
+    current_lap_time: f32,
+    last_lap_time: f32,
+    best_lap_time: f32,
+    tyre_temperatures: [u16; 4],
+    brake_temperatures: [u16; 4],
+    flag: Option<Flag>,
+}
+
+impl SimHubData {
+    pub fn new() -> SimHubData {
+        SimHubData::default()
+    }
+
+    /// Update the values from a packet of the player's car, returning whether
+    /// it held any.
+    pub fn update(&mut self, packet: &Packet) -> bool {
+        match packet {
+            Packet::CarTelemetry(p) => {
+                let t = match p.player_car_telemetry() {
+                    Some(t) => t,
+                    None => return false,
+                };
+                self.packet_format = p.header().packet_format();
+                self.speed = t.speed();
+                self.gear = t.gear();
+                self.rpm = t.engine_rpm();
+                self.rev_lights_percent = t.rev_lights_percent();
+                self.throttle = t.throttle();
+                self.brake = t.brake();
+                self.clutch = t.clutch();
+                self.drs_enabled = t.drs();
+                let tyres = t.tyres_surface_temperature();
+                self.tyre_temperatures = [
+                    tyres.front_left(),
+                    tyres.front_right(),
+                    tyres.rear_left(),
+                    tyres.rear_right(),
+                ];
+                let brakes = t.brakes_temperature();
+                self.brake_temperatures = [
+                    brakes.front_left(),
+                    brakes.front_right(),
+                    brakes.rear_left(),
+                    brakes.rear_right(),
+                ];
+            }
+            Packet::CarStatus(p) => {
+                let index = p.header().player_car_index() as usize;
+                let s = match p.car_status_data().get(index) {
+                    Some(s) => s,
+                    None => return false,
+                };
+                self.max_rpm = s.max_rpm();
+                self.fuel = s.fuel_in_tank();
+                self.max_fuel = s.fuel_capacity();
+                self.fuel_remaining_laps = s.fuel_remaining_laps();
+                self.ers_store_energy = s.ers_store_energy();
+                self.drs_available = s.drs_allowed() == DRS::Allowed;
+                self.pit_limiter = s.pit_limiter();
+                self.flag = Some(s.vehicle_fia_flags());
+            }
+            Packet::Lap(p) => {
+                let index = p.header().player_car_index() as usize;
+                let l = match p.lap_data().get(index) {
+                    Some(l) => l,
+                    None => return false,
+                };
+                self.pit_status = Some(l.pit_status());
+                self.position = l.car_position();
+                self.current_lap = l.current_lap_num();
+                self.current_lap_time = l.current_lap_time();
+                self.last_lap_time = l.last_lap_time();
+                self.best_lap_time = l.best_lap_time();
+            }
+            Packet::Session(p) => self.total_laps = p.total_laps(),
+            _ => return false,
+        }
+
+        true
+    }
+
+    /// The values as a JSON object.
+    pub fn to_json(&self) -> String {
+        let flag = |f: Flag| (self.flag == Some(f)) as u8;
+        let gear = match self.gear {
+            -1 => String::from("R"),
+            0 => String::from("N"),
+            g => g.to_string(),
+        };
+        let fuel_percent = if self.max_fuel > 0.0 {
+            self.fuel / self.max_fuel * 100.0
+        } else {
+            0.0
+        };
+        let in_pit_lane = matches!(
+            self.pit_status,
+            Some(PitStatus::Pitting) | Some(PitStatus::PitLane)
+        );
+        let in_pit = self.pit_status == Some(PitStatus::Pitting);
+
+        let mut fields = Vec::new();
+        let mut field = |key: &str, value: String| fields.push(format!("\"{}\":{}", key, value));
+
+        field("GameName", format!("\"F1{}\"", self.packet_format % 100));
+        field("SpeedKmh", self.speed.to_string());
+        field("Gear", format!("\"{}\"", gear));
+        field("Rpms", self.rpm.to_string());
+        field("MaxRpm", self.max_rpm.to_string());
+        field(
+            "CarSettings_CurrentDisplayedRPMPercent",
+            self.rev_lights_percent.to_string(),
+        );
+        field("Throttle", format!("{:.1}", self.throttle * 100.0));
+        field("Brake", format!("{:.1}", self.brake * 100.0));
+        field("Clutch", self.clutch.to_string());
+        field("Fuel", format!("{:.2}", self.fuel));
+        field("MaxFuel", format!("{:.2}", self.max_fuel));
+        field("FuelPercent", format!("{:.1}", fuel_percent));
+        field(
+            "EstimatedFuelRemaingLaps",
+            format!("{:.2}", self.fuel_remaining_laps),
+        );
+        field(
+            "ERSPercent",
+            format!("{:.1}", self.ers_store_energy / ERS_CAPACITY * 100.0),
+        );
+        field("DRSAvailable", (self.drs_available as u8).to_string());
+        field("DRSEnabled", (self.drs_enabled as u8).to_string());
+        field("PitLimiterOn", (self.pit_limiter as u8).to_string());
+        field("IsInPitLane", (in_pit_lane as u8).to_string());
+        field("IsInPit", (in_pit as u8).to_string());
+        field("Position", self.position.to_string());
+        field("CurrentLap", self.current_lap.to_string());
+        field("TotalLaps", self.total_laps.to_string());
+        field("CurrentLapTime", format!("{:.3}", self.current_lap_time));
+        field("LastLapTime", format!("{:.3}", self.last_lap_time));
+        field("BestLapTime", format!("{:.3}", self.best_lap_time));
+        field("Flag_Green", flag(Flag::Green).to_string());
+        field("Flag_Blue", flag(Flag::Blue).to_string());
+        field("Flag_Yellow", flag(Flag::Yellow).to_string());
+        for (wheel, t) in WHEELS.iter().zip(self.tyre_temperatures.iter()) {
+            field(&format!("TyreTemperature{}", wheel), t.to_string());
+        }
+        for (wheel, t) in WHEELS.iter().zip(self.brake_temperatures.iter()) {
+            field(&format!("BrakeTemperature{}", wheel), t.to_string());
+        }
+
+        format!("{{{}}}", fields.join(","))
+    }
+}
+
+/// Sends the values of the player's car to SimHub over UDP, at most `rate`
+/// times per second.
+///
+/// As a [`PacketSink`], the sender updates its values with every packet and
+/// sends them when due; the send errors are counted rather than returned.
+///
+/// [`PacketSink`]: ../../pipeline/trait.PacketSink.html
+#[derive(Debug)]
+pub struct SimHubSender {
+    socket: UdpSocket,
+    data: SimHubData,
+    interval: Duration,
+    last_sent: Option<Instant>,
+    errors: u64,
+}
+
+impl SimHubSender {
+    pub fn new<T: ToSocketAddrs>(simhub: T, rate: u32) -> io::Result<SimHubSender> {
+        let socket = UdpSocket::bind("0.0.0.0:0")?;
+        socket.connect(simhub)?;
+
+        Ok(SimHubSender {
+            socket,
+            data: SimHubData::default(),
+            interval: Duration::from_secs(1) / rate.max(1),
+            last_sent: None,
+            errors: 0,
+        })
+    }
+
+    pub fn data(&self) -> &SimHubData {
+        &self.data
+    }
+
+    /// Number of datagrams that could not be sent.
+    pub fn errors(&self) -> u64 {
+        self.errors
+    }
+
+    /// Update the values with a packet, and send them if they are due.
+    pub fn update(&mut self, packet: &Packet) -> io::Result<()> {
+        if !self.data.update(packet) {
+            return Ok(());
+        }
+
+        let now = Instant::now();
+        match self.last_sent {
+            Some(last) if now.duration_since(last) < self.interval => Ok(()),
+            _ => {
+                self.last_sent = Some(now);
+                self.send()
+            }
+        }
+    }
+
+    /// Send the values right away.
+    pub fn send(&self) -> io::Result<()> {
+        self.socket.send(self.data.to_json().as_bytes())?;
+        Ok(())
+    }
+}
+
+impl PacketSink for SimHubSender {
+    fn handle(&mut self, packet: &Packet) {
+        if self.update(packet).is_err() {
+            self.errors += 1;
+        }
+    }
+}
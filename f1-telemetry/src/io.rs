@@ -0,0 +1,167 @@
+//! The subset of `std::io` used by the packet parsers and encoders.
+//!
+//! With the `std` feature these are the types of `std::io` and the extension
+//! traits of byteorder. Without it, the parsers read from in-memory cursors
+//! and the encoders write to vectors, through minimal equivalents built on
+//! `core` and `alloc`.
+
+#[cfg(feature = "std")]
+pub use byteorder::{ReadBytesExt, WriteBytesExt};
+#[cfg(feature = "std")]
+pub use std::io::{BufRead, Cursor, Read, Result, Write};
+
+#[cfg(not(feature = "std"))]
+pub use self::core_io::*;
+
+#[cfg(not(feature = "std"))]
+mod core_io {
+    use alloc::vec::Vec;
+    use byteorder::ByteOrder;
+
+    /// The end of the data was reached before the value was read.
+    #[derive(Debug, Clone, Copy, Eq, PartialEq)]
+    pub struct Error;
+
+    pub type Result<T> = core::result::Result<T, Error>;
+
+    pub trait Read {
+        fn read_exact(&mut self, buf: &mut [u8]) -> Result<()>;
+    }
+
+    /// Marker for the readers the parsers accept, mirroring `std::io::BufRead`.
+    pub trait BufRead: Read {}
+
+    pub trait Write {
+        fn write_all(&mut self, buf: &[u8]) -> Result<()>;
+    }
+
+    /// Reader over bytes held in memory.
+    #[derive(Debug, Clone)]
+    pub struct Cursor<T> {
+        inner: T,
+        pos: u64,
+    }
+
+    impl<T> Cursor<T> {
+        pub fn new(inner: T) -> Cursor<T> {
+            Cursor { inner, pos: 0 }
+        }
+
+        pub fn position(&self) -> u64 {
+            self.pos
+        }
+
+        pub fn set_position(&mut self, pos: u64) {
+            self.pos = pos;
+        }
+
+        pub fn get_ref(&self) -> &T {
+            &self.inner
+        }
+
+        pub fn into_inner(self) -> T {
+            self.inner
+        }
+    }
+
+    impl<T: AsRef<[u8]>> Read for Cursor<T> {
+        fn read_exact(&mut self, buf: &mut [u8]) -> Result<()> {
+            let data = self.inner.as_ref();
+            let start = (self.pos as usize).min(data.len());
+            let end = start.checked_add(buf.len()).ok_or(Error)?;
+            let src = data.get(start..end).ok_or(Error)?;
+
+            buf.copy_from_slice(src);
+            self.pos = end as u64;
+
+            Ok(())
+        }
+    }
+
+    impl<T: AsRef<[u8]>> BufRead for Cursor<T> {}
+
+    impl<R: Read + ?Sized> Read for &mut R {
+        fn read_exact(&mut self, buf: &mut [u8]) -> Result<()> {
+            (**self).read_exact(buf)
+        }
+    }
+
+    impl<R: BufRead + ?Sized> BufRead for &mut R {}
+
+    impl Write for Vec<u8> {
+        fn write_all(&mut self, buf: &[u8]) -> Result<()> {
+            self.extend_from_slice(buf);
+            Ok(())
+        }
+    }
+
+    impl<W: Write + ?Sized> Write for &mut W {
+        fn write_all(&mut self, buf: &[u8]) -> Result<()> {
+            (**self).write_all(buf)
+        }
+    }
+
+    macro_rules! read_bytes {
+        ($name:ident, $t:ty, $size:expr, $read:ident) => {
+            fn $name<B: ByteOrder>(&mut self) -> Result<$t> {
+                let mut buf = [0u8; $size];
+                self.read_exact(&mut buf)?;
+                Ok(B::$read(&buf))
+            }
+        };
+    }
+
+    macro_rules! write_bytes {
+        ($name:ident, $t:ty, $size:expr, $write:ident) => {
+            fn $name<B: ByteOrder>(&mut self, n: $t) -> Result<()> {
+                let mut buf = [0u8; $size];
+                B::$write(&mut buf, n);
+                self.write_all(&buf)
+            }
+        };
+    }
+
+    /// Same methods as the `ReadBytesExt` trait of byteorder.
+    pub trait ReadBytesExt: Read {
+        fn read_u8(&mut self) -> Result<u8> {
+            let mut buf = [0u8; 1];
+            self.read_exact(&mut buf)?;
+            Ok(buf[0])
+        }
+
+        fn read_i8(&mut self) -> Result<i8> {
+            Ok(self.read_u8()? as i8)
+        }
+
+        read_bytes!(read_u16, u16, 2, read_u16);
+        read_bytes!(read_i16, i16, 2, read_i16);
+        read_bytes!(read_u32, u32, 4, read_u32);
+        read_bytes!(read_i32, i32, 4, read_i32);
+        read_bytes!(read_u64, u64, 8, read_u64);
+        read_bytes!(read_f32, f32, 4, read_f32);
+        read_bytes!(read_f64, f64, 8, read_f64);
+    }
+
+    impl<R: Read + ?Sized> ReadBytesExt for R {}
+
+    /// Same methods as the `WriteBytesExt` trait of byteorder.
+    pub trait WriteBytesExt: Write {
+        fn write_u8(&mut self, n: u8) -> Result<()> {
+            self.write_all(&[n])
+        }
+
+        fn write_i8(&mut self, n: i8) -> Result<()> {
+            self.write_all(&[n as u8])
+        }
+
+        write_bytes!(write_u16, u16, 2, write_u16);
+        write_bytes!(write_i16, i16, 2, write_i16);
+        write_bytes!(write_u32, u32, 4, write_u32);
+        write_bytes!(write_i32, i32, 4, write_i32);
+        write_bytes!(write_u64, u64, 8, write_u64);
+        write_bytes!(write_f32, f32, 4, write_f32);
+        write_bytes!(write_f64, f64, 8, write_f64);
+    }
+
+    impl<W: Write + ?Sized> WriteBytesExt for W {}
+}
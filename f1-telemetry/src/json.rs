@@ -0,0 +1,37 @@
+//! Encoding of the values of the JSON documents written by the exporters and
+//! served by the integrations.
+
+use std::fmt;
+
+/// The value, or `null` when there is none.
+pub(crate) fn json_option<T: fmt::Display>(value: Option<T>) -> String {
+    value
+        .map(|v| v.to_string())
+        .unwrap_or_else(|| String::from("null"))
+}
+
+/// A number with `precision` decimals, or `null` when there is none or it is
+/// not finite: JSON has no NaN nor infinity.
+pub(crate) fn json_float<T: Into<f64>>(value: Option<T>, precision: usize) -> String {
+    match value.map(Into::into) {
+        Some(v) if v.is_finite() => format!("{:.*}", precision, v),
+        _ => String::from("null"),
+    }
+}
+
+/// A quoted string, with the quotes, backslashes and control characters escaped.
+pub(crate) fn json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            c if c.is_control() => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
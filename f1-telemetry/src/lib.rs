@@ -1,40 +1,73 @@
-use packet::{parse_packet, Packet, UnpackError};
-use std::io::ErrorKind;
-use std::net::{ToSocketAddrs, UdpSocket};
+#![cfg_attr(not(feature = "std"), no_std)]
 
-pub mod packet;
-
-pub struct Stream {
-    socket: UdpSocket,
-}
-
-impl Stream {
-    pub fn new<T: ToSocketAddrs>(addr: T) -> std::io::Result<Stream> {
-        let socket = UdpSocket::bind(addr)?;
-        socket.set_nonblocking(true)?;
+extern crate alloc;
 
-        Ok(Stream { socket })
-    }
+#[cfg(feature = "std")]
+pub use multi_stream::{MultiStream, MultiStreamHandle, SourceId, SourceInfo, SourceResult};
+#[cfg(feature = "std")]
+pub use stream::{Stream, StreamError, StreamHandle};
 
-    pub fn next(&self) -> Result<Option<Packet>, UnpackError> {
-        let mut buf = [0; 2048]; // All packets fit in 2048 bytes
-
-        match self.socket.recv(&mut buf) {
-            Ok(len) => match parse_packet(len, &buf) {
-                Ok(p) => Ok(Some(p)),
-                Err(e) => Err(e),
-            },
-            Err(e) => {
-                if e.kind() == ErrorKind::WouldBlock {
-                    Ok(None)
-                } else {
-                    Err(UnpackError(format!("Error reading from socket: {:?}", e)))
-                }
-            }
-        }
-    }
+#[cfg(feature = "std")]
+pub mod analysis;
+#[cfg(feature = "std")]
+pub mod championship;
+pub mod channels;
+#[cfg(feature = "std")]
+pub mod dvr;
+#[cfg(feature = "std")]
+pub mod export;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod fmt;
+#[cfg(feature = "std")]
+pub mod forward;
+#[cfg(feature = "grpc")]
+pub mod grpc;
+#[cfg(feature = "std")]
+pub mod input;
+#[cfg(feature = "std")]
+pub mod integrations;
+pub mod io;
+pub mod meta;
+#[cfg(feature = "metrics")]
+pub mod metrics;
+#[cfg(feature = "std")]
+pub mod output;
+#[cfg(feature = "overlay")]
+pub mod overlay;
+pub mod packet;
+#[cfg(feature = "std")]
+pub mod pipeline;
+#[cfg(feature = "std")]
+pub mod redact;
+pub mod sequence;
+#[cfg(feature = "std")]
+pub mod session;
+#[cfg(feature = "std")]
+pub mod simulator;
+#[cfg(feature = "std")]
+pub mod socket;
+#[cfg(feature = "std")]
+pub mod stats;
+#[cfg(feature = "std")]
+pub mod storage;
+#[cfg(feature = "std")]
+pub mod strategy;
+#[cfg(feature = "std")]
+pub mod sync;
+#[cfg(feature = "std")]
+pub mod transform;
+pub mod units;
+#[cfg(feature = "std")]
+pub mod validate;
+#[cfg(feature = "std")]
+pub mod watchdog;
 
-    pub fn socket(&self) -> &UdpSocket {
-        &self.socket
-    }
-}
+#[cfg(not(feature = "std"))]
+mod float;
+#[cfg(feature = "std")]
+mod json;
+#[cfg(feature = "std")]
+mod multi_stream;
+#[cfg(feature = "std")]
+mod stream;
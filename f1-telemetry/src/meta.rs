@@ -0,0 +1,6 @@
+//! Static data about the game: its tracks, e.g. to split laps into sectors or
+//! find the DRS zones without measuring them, and its teams, with their names
+//! and colours.
+
+pub mod teams;
+pub mod tracks;
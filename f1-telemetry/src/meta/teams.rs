@@ -0,0 +1,565 @@
+use getset::CopyGetters;
+
+use crate::packet::participants::Team;
+
+/// Kind of a team of the game.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum TeamKind {
+    /// A team of the season of the game.
+    Current,
+    /// A classic car, raced in the invitational events.
+    Classic,
+    /// A team of the Formula 2 championship.
+    F2,
+    /// The team created in the career mode of F1 2020 and later.
+    MyTeam,
+    /// The generic car of the online lobbies, e.g. with equal performance.
+    Generic,
+}
+
+/// Names and colours of a team.
+///
+/// The colours are the main colour of the livery, e.g. to tell the cars apart
+/// on a timing screen; the team of the career mode is grey as its livery is
+/// chosen by the player.
+///
+/// ## Specification
+/// ```text
+/// team:        The team
+/// name:        Display name of the team, with the year of the classic cars
+/// short_code:  Three letter code of the team, as on a timing screen
+/// kind:        Kind of the team
+/// color:       Colour of the team, in RGB
+/// ```
+#[derive(Debug, Clone, Copy, Eq, PartialEq, CopyGetters)]
+#[getset(get_copy = "pub")]
+pub struct TeamInfo {
+    team: Team,
+    name: &'static str,
+    short_code: &'static str,
+    kind: TeamKind,
+    color: (u8, u8, u8),
+}
+
+impl TeamInfo {
+    /// Names and colours of a team, None for the teams unknown to this crate.
+    ///
+    /// ```
+    /// use f1_telemetry::meta::teams::{TeamInfo, TeamKind};
+    /// use f1_telemetry::packet::participants::Team;
+    ///
+    /// let team = TeamInfo::get(Team::from(255)).unwrap();
+    /// assert_eq!(team.name(), "My Team");
+    /// assert_eq!(team.kind(), TeamKind::MyTeam);
+    /// ```
+    pub fn get(team: Team) -> Option<&'static TeamInfo> {
+        TEAMS.iter().find(|t| t.team == team)
+    }
+
+    /// Names and colours of a team, by the identifier sent in the
+    /// Participants packets.
+    pub fn from_id(team_id: u8) -> Option<&'static TeamInfo> {
+        TeamInfo::get(Team::from(team_id))
+    }
+}
+
+/// Names and colours of the teams of the game.
+pub static TEAMS: [TeamInfo; 71] = [
+    TeamInfo {
+        team: Team::Mercedes,
+        name: "Mercedes",
+        short_code: "MER",
+        kind: TeamKind::Current,
+        color: (0, 210, 190),
+    },
+    TeamInfo {
+        team: Team::Ferrari,
+        name: "Ferrari",
+        short_code: "FER",
+        kind: TeamKind::Current,
+        color: (220, 0, 0),
+    },
+    TeamInfo {
+        team: Team::RedBullRacing,
+        name: "Red Bull Racing",
+        short_code: "RBR",
+        kind: TeamKind::Current,
+        color: (30, 65, 255),
+    },
+    TeamInfo {
+        team: Team::Williams,
+        name: "Williams",
+        short_code: "WIL",
+        kind: TeamKind::Current,
+        color: (255, 255, 255),
+    },
+    TeamInfo {
+        team: Team::RacingPoint,
+        name: "Racing Point",
+        short_code: "RPT",
+        kind: TeamKind::Current,
+        color: (245, 150, 200),
+    },
+    TeamInfo {
+        team: Team::Renault,
+        name: "Renault",
+        short_code: "REN",
+        kind: TeamKind::Current,
+        color: (255, 245, 0),
+    },
+    TeamInfo {
+        team: Team::ToroRosso,
+        name: "Toro Rosso",
+        short_code: "STR",
+        kind: TeamKind::Current,
+        color: (70, 155, 255),
+    },
+    TeamInfo {
+        team: Team::Haas,
+        name: "Haas",
+        short_code: "HAA",
+        kind: TeamKind::Current,
+        color: (240, 215, 135),
+    },
+    TeamInfo {
+        team: Team::McLaren,
+        name: "McLaren",
+        short_code: "MCL",
+        kind: TeamKind::Current,
+        color: (255, 135, 0),
+    },
+    TeamInfo {
+        team: Team::AlfaRomeo,
+        name: "Alfa Romeo",
+        short_code: "ALF",
+        kind: TeamKind::Current,
+        color: (155, 0, 0),
+    },
+    TeamInfo {
+        team: Team::McLaren1988,
+        name: "McLaren 1988",
+        short_code: "MCL",
+        kind: TeamKind::Classic,
+        color: (230, 20, 40),
+    },
+    TeamInfo {
+        team: Team::McLaren1991,
+        name: "McLaren 1991",
+        short_code: "MCL",
+        kind: TeamKind::Classic,
+        color: (230, 20, 40),
+    },
+    TeamInfo {
+        team: Team::Williams1992,
+        name: "Williams 1992",
+        short_code: "WIL",
+        kind: TeamKind::Classic,
+        color: (20, 60, 160),
+    },
+    TeamInfo {
+        team: Team::Ferrari1995,
+        name: "Ferrari 1995",
+        short_code: "FER",
+        kind: TeamKind::Classic,
+        color: (220, 0, 0),
+    },
+    TeamInfo {
+        team: Team::Williams1996,
+        name: "Williams 1996",
+        short_code: "WIL",
+        kind: TeamKind::Classic,
+        color: (20, 60, 160),
+    },
+    TeamInfo {
+        team: Team::McLaren1998,
+        name: "McLaren 1998",
+        short_code: "MCL",
+        kind: TeamKind::Classic,
+        color: (150, 155, 165),
+    },
+    TeamInfo {
+        team: Team::Ferrari2002,
+        name: "Ferrari 2002",
+        short_code: "FER",
+        kind: TeamKind::Classic,
+        color: (220, 0, 0),
+    },
+    TeamInfo {
+        team: Team::Ferrari2004,
+        name: "Ferrari 2004",
+        short_code: "FER",
+        kind: TeamKind::Classic,
+        color: (220, 0, 0),
+    },
+    TeamInfo {
+        team: Team::Renault2006,
+        name: "Renault 2006",
+        short_code: "REN",
+        kind: TeamKind::Classic,
+        color: (20, 80, 190),
+    },
+    TeamInfo {
+        team: Team::Ferrari2007,
+        name: "Ferrari 2007",
+        short_code: "FER",
+        kind: TeamKind::Classic,
+        color: (220, 0, 0),
+    },
+    TeamInfo {
+        team: Team::McLaren2008,
+        name: "McLaren 2008",
+        short_code: "MCL",
+        kind: TeamKind::Classic,
+        color: (150, 155, 165),
+    },
+    TeamInfo {
+        team: Team::RedBull2010,
+        name: "Red Bull 2010",
+        short_code: "RBR",
+        kind: TeamKind::Classic,
+        color: (20, 30, 90),
+    },
+    TeamInfo {
+        team: Team::Ferrari1976,
+        name: "Ferrari 1976",
+        short_code: "FER",
+        kind: TeamKind::Classic,
+        color: (220, 0, 0),
+    },
+    TeamInfo {
+        team: Team::ARTGrandPrix,
+        name: "ART Grand Prix",
+        short_code: "ART",
+        kind: TeamKind::F2,
+        color: (130, 130, 130),
+    },
+    TeamInfo {
+        team: Team::CamposVexatecRacing,
+        name: "Campos Vexatec Racing",
+        short_code: "CAM",
+        kind: TeamKind::F2,
+        color: (235, 105, 30),
+    },
+    TeamInfo {
+        team: Team::Carlin,
+        name: "Carlin",
+        short_code: "CAR",
+        kind: TeamKind::F2,
+        color: (20, 50, 120),
+    },
+    TeamInfo {
+        team: Team::CharouzRacingSystem,
+        name: "Charouz Racing System",
+        short_code: "CHA",
+        kind: TeamKind::F2,
+        color: (150, 30, 40),
+    },
+    TeamInfo {
+        team: Team::DAMS,
+        name: "DAMS",
+        short_code: "DAM",
+        kind: TeamKind::F2,
+        color: (40, 140, 210),
+    },
+    TeamInfo {
+        team: Team::RussianTime,
+        name: "Russian Time",
+        short_code: "RUS",
+        kind: TeamKind::F2,
+        color: (200, 20, 40),
+    },
+    TeamInfo {
+        team: Team::MPMotorsport,
+        name: "MP Motorsport",
+        short_code: "MPM",
+        kind: TeamKind::F2,
+        color: (240, 120, 40),
+    },
+    TeamInfo {
+        team: Team::Pertamina,
+        name: "Pertamina",
+        short_code: "PER",
+        kind: TeamKind::F2,
+        color: (210, 20, 30),
+    },
+    TeamInfo {
+        team: Team::McLaren1990,
+        name: "McLaren 1990",
+        short_code: "MCL",
+        kind: TeamKind::Classic,
+        color: (230, 20, 40),
+    },
+    TeamInfo {
+        team: Team::Trident,
+        name: "Trident",
+        short_code: "TRI",
+        kind: TeamKind::F2,
+        color: (30, 60, 150),
+    },
+    TeamInfo {
+        team: Team::BWTArden,
+        name: "BWT Arden",
+        short_code: "ARD",
+        kind: TeamKind::F2,
+        color: (240, 110, 190),
+    },
+    TeamInfo {
+        team: Team::McLaren1976,
+        name: "McLaren 1976",
+        short_code: "MCL",
+        kind: TeamKind::Classic,
+        color: (230, 20, 40),
+    },
+    TeamInfo {
+        team: Team::Lotus1972,
+        name: "Lotus 1972",
+        short_code: "LOT",
+        kind: TeamKind::Classic,
+        color: (20, 20, 20),
+    },
+    TeamInfo {
+        team: Team::Ferrari1979,
+        name: "Ferrari 1979",
+        short_code: "FER",
+        kind: TeamKind::Classic,
+        color: (220, 0, 0),
+    },
+    TeamInfo {
+        team: Team::McLaren1982,
+        name: "McLaren 1982",
+        short_code: "MCL",
+        kind: TeamKind::Classic,
+        color: (230, 20, 40),
+    },
+    TeamInfo {
+        team: Team::Williams2003,
+        name: "Williams 2003",
+        short_code: "WIL",
+        kind: TeamKind::Classic,
+        color: (20, 60, 160),
+    },
+    TeamInfo {
+        team: Team::Brawn2009,
+        name: "Brawn 2009",
+        short_code: "BGP",
+        kind: TeamKind::Classic,
+        color: (220, 240, 40),
+    },
+    TeamInfo {
+        team: Team::Lotus1978,
+        name: "Lotus 1978",
+        short_code: "LOT",
+        kind: TeamKind::Classic,
+        color: (20, 20, 20),
+    },
+    TeamInfo {
+        team: Team::F1Generic,
+        name: "F1 Generic",
+        short_code: "GEN",
+        kind: TeamKind::Generic,
+        color: (130, 130, 130),
+    },
+    TeamInfo {
+        team: Team::ArtGP2019,
+        name: "ART Grand Prix '19",
+        short_code: "ART",
+        kind: TeamKind::F2,
+        color: (130, 130, 130),
+    },
+    TeamInfo {
+        team: Team::Campos2019,
+        name: "Campos Racing '19",
+        short_code: "CAM",
+        kind: TeamKind::F2,
+        color: (235, 105, 30),
+    },
+    TeamInfo {
+        team: Team::Carlin2019,
+        name: "Carlin '19",
+        short_code: "CAR",
+        kind: TeamKind::F2,
+        color: (20, 50, 120),
+    },
+    TeamInfo {
+        team: Team::SauberJuniorCharouz2019,
+        name: "Sauber Junior Team by Charouz '19",
+        short_code: "SJC",
+        kind: TeamKind::F2,
+        color: (150, 30, 40),
+    },
+    TeamInfo {
+        team: Team::Dams2019,
+        name: "DAMS '19",
+        short_code: "DAM",
+        kind: TeamKind::F2,
+        color: (40, 140, 210),
+    },
+    TeamInfo {
+        team: Team::UniVirtuosi2019,
+        name: "UNI-Virtuosi '19",
+        short_code: "UNI",
+        kind: TeamKind::F2,
+        color: (240, 200, 40),
+    },
+    TeamInfo {
+        team: Team::MPMotorsport2019,
+        name: "MP Motorsport '19",
+        short_code: "MPM",
+        kind: TeamKind::F2,
+        color: (240, 120, 40),
+    },
+    TeamInfo {
+        team: Team::Prema2019,
+        name: "Prema '19",
+        short_code: "PRE",
+        kind: TeamKind::F2,
+        color: (210, 20, 30),
+    },
+    TeamInfo {
+        team: Team::Trident2019,
+        name: "Trident '19",
+        short_code: "TRI",
+        kind: TeamKind::F2,
+        color: (30, 60, 150),
+    },
+    TeamInfo {
+        team: Team::Arden2019,
+        name: "BWT Arden '19",
+        short_code: "ARD",
+        kind: TeamKind::F2,
+        color: (240, 110, 190),
+    },
+    TeamInfo {
+        team: Team::Benetton1994,
+        name: "Benetton 1994",
+        short_code: "BEN",
+        kind: TeamKind::Classic,
+        color: (30, 140, 80),
+    },
+    TeamInfo {
+        team: Team::Benetton1995,
+        name: "Benetton 1995",
+        short_code: "BEN",
+        kind: TeamKind::Classic,
+        color: (30, 110, 190),
+    },
+    TeamInfo {
+        team: Team::Ferrari2000,
+        name: "Ferrari 2000",
+        short_code: "FER",
+        kind: TeamKind::Classic,
+        color: (220, 0, 0),
+    },
+    TeamInfo {
+        team: Team::Jordan1991,
+        name: "Jordan 1991",
+        short_code: "JOR",
+        kind: TeamKind::Classic,
+        color: (20, 120, 80),
+    },
+    TeamInfo {
+        team: Team::Ferrari1990,
+        name: "Ferrari 1990",
+        short_code: "FER",
+        kind: TeamKind::Classic,
+        color: (220, 0, 0),
+    },
+    TeamInfo {
+        team: Team::McLaren2010,
+        name: "McLaren 2010",
+        short_code: "MCL",
+        kind: TeamKind::Classic,
+        color: (150, 155, 165),
+    },
+    TeamInfo {
+        team: Team::Ferrari2010,
+        name: "Ferrari 2010",
+        short_code: "FER",
+        kind: TeamKind::Classic,
+        color: (220, 0, 0),
+    },
+    TeamInfo {
+        team: Team::ArtGP2020,
+        name: "ART Grand Prix '20",
+        short_code: "ART",
+        kind: TeamKind::F2,
+        color: (130, 130, 130),
+    },
+    TeamInfo {
+        team: Team::Campos2020,
+        name: "Campos Racing '20",
+        short_code: "CAM",
+        kind: TeamKind::F2,
+        color: (235, 105, 30),
+    },
+    TeamInfo {
+        team: Team::Carlin2020,
+        name: "Carlin '20",
+        short_code: "CAR",
+        kind: TeamKind::F2,
+        color: (20, 50, 120),
+    },
+    TeamInfo {
+        team: Team::Charouz2020,
+        name: "Charouz Racing System '20",
+        short_code: "CHA",
+        kind: TeamKind::F2,
+        color: (150, 30, 40),
+    },
+    TeamInfo {
+        team: Team::Dams2020,
+        name: "DAMS '20",
+        short_code: "DAM",
+        kind: TeamKind::F2,
+        color: (40, 140, 210),
+    },
+    TeamInfo {
+        team: Team::UniVirtuosi2020,
+        name: "UNI-Virtuosi '20",
+        short_code: "UNI",
+        kind: TeamKind::F2,
+        color: (240, 200, 40),
+    },
+    TeamInfo {
+        team: Team::MPMotorsport2020,
+        name: "MP Motorsport '20",
+        short_code: "MPM",
+        kind: TeamKind::F2,
+        color: (240, 120, 40),
+    },
+    TeamInfo {
+        team: Team::Prema2020,
+        name: "Prema '20",
+        short_code: "PRE",
+        kind: TeamKind::F2,
+        color: (210, 20, 30),
+    },
+    TeamInfo {
+        team: Team::Trident2020,
+        name: "Trident '20",
+        short_code: "TRI",
+        kind: TeamKind::F2,
+        color: (30, 60, 150),
+    },
+    TeamInfo {
+        team: Team::BWT2020,
+        name: "BWT HWA Racelab '20",
+        short_code: "HWA",
+        kind: TeamKind::F2,
+        color: (240, 110, 190),
+    },
+    TeamInfo {
+        team: Team::Hitech2020,
+        name: "Hitech Grand Prix '20",
+        short_code: "HIT",
+        kind: TeamKind::F2,
+        color: (200, 200, 200),
+    },
+    TeamInfo {
+        team: Team::MyTeam,
+        name: "My Team",
+        short_code: "MYT",
+        kind: TeamKind::MyTeam,
+        color: (130, 130, 130),
+    },
+];
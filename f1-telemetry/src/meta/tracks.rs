@@ -0,0 +1,396 @@
+use getset::CopyGetters;
+
+use crate::packet::session::Track;
+
+/// A DRS zone, by distance around the lap in metres.
+///
+/// The zone of the main straight usually crosses the line, its activation
+/// point being further around the lap than its end.
+///
+/// ## Specification
+/// ```text
+/// detection:   Point where the gap to the car ahead is measured
+/// activation:  Point after which DRS can be opened
+/// end:         Braking point closing the zone
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, CopyGetters)]
+#[getset(get_copy = "pub")]
+pub struct DrsZone {
+    detection: f32,
+    activation: f32,
+    end: f32,
+}
+
+impl DrsZone {
+    const fn new(detection: f32, activation: f32, end: f32) -> DrsZone {
+        DrsZone {
+            detection,
+            activation,
+            end,
+        }
+    }
+
+    /// Whether `lap_distance` is between the activation point and the end.
+    pub fn contains(&self, lap_distance: f32) -> bool {
+        if self.activation <= self.end {
+            lap_distance >= self.activation && lap_distance < self.end
+        } else {
+            lap_distance >= self.activation || lap_distance < self.end
+        }
+    }
+
+    /// Length of the zone in metres, on a track of `track_length` metres.
+    pub fn length(&self, track_length: f32) -> f32 {
+        if self.activation <= self.end {
+            self.end - self.activation
+        } else {
+            track_length - self.activation + self.end
+        }
+    }
+}
+
+/// Layout of a track.
+///
+/// The distances are approximate, measured around the racing line of the
+/// game's tracks of the 2019 and 2020 seasons.
+///
+/// ## Specification
+/// ```text
+/// track:            The track
+/// country:          ISO 3166-1 alpha-2 code of the country
+/// location:         Town or city of the track
+/// length:           Length of the lap in metres
+/// corners:          Number of corners
+/// sector_starts:    Distance around the lap where sectors 2 and 3 start in
+///                   metres
+/// drs_zones:        DRS zones, in the order they are driven from the line
+/// pit_lane_length:  Length of the pit lane, entry to exit, in metres
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, CopyGetters)]
+#[getset(get_copy = "pub")]
+pub struct TrackInfo {
+    track: Track,
+    country: &'static str,
+    location: &'static str,
+    length: u16,
+    corners: u8,
+    sector_starts: [f32; 2],
+    drs_zones: &'static [DrsZone],
+    pit_lane_length: u16,
+}
+
+impl TrackInfo {
+    /// Layout of a track, None for the short layouts and unknown tracks.
+    ///
+    /// ```
+    /// use f1_telemetry::meta::tracks::TrackInfo;
+    /// use f1_telemetry::packet::session::Track;
+    ///
+    /// let monza = TrackInfo::get(Track::Monza).unwrap();
+    /// assert_eq!(monza.country(), "IT");
+    /// assert_eq!(monza.sector(100.0), 0);
+    /// assert!(monza.drs_zone(5700.0).is_some());
+    /// ```
+    pub fn get(track: Track) -> Option<&'static TrackInfo> {
+        TRACKS.iter().find(|t| t.track == track)
+    }
+
+    /// Layout of a track, by the identifier sent in the Session packets.
+    pub fn from_id(track_id: i8) -> Option<&'static TrackInfo> {
+        TrackInfo::get(Track::from(track_id))
+    }
+
+    /// Name of the track.
+    pub fn name(&self) -> &'static str {
+        self.track.name()
+    }
+
+    /// Sector of a distance around the lap, from 0 as in the Lap Data packets.
+    pub fn sector(&self, lap_distance: f32) -> u8 {
+        self.sector_starts
+            .iter()
+            .filter(|&&start| lap_distance >= start)
+            .count() as u8
+    }
+
+    /// DRS zone a distance around the lap is in, if any.
+    pub fn drs_zone(&self, lap_distance: f32) -> Option<&'static DrsZone> {
+        self.drs_zones.iter().find(|z| z.contains(lap_distance))
+    }
+}
+
+/// Layouts of the tracks of the game.
+pub static TRACKS: [TrackInfo; 21] = [
+    TrackInfo {
+        track: Track::Melbourne,
+        country: "AU",
+        location: "Melbourne",
+        length: 5303,
+        corners: 16,
+        sector_starts: [1810.0, 3660.0],
+        drs_zones: &[
+            DrsZone::new(4890.0, 5090.0, 260.0),
+            DrsZone::new(260.0, 600.0, 1020.0),
+        ],
+        pit_lane_length: 280,
+    },
+    TrackInfo {
+        track: Track::PaulRicard,
+        country: "FR",
+        location: "Le Castellet",
+        length: 5842,
+        corners: 15,
+        sector_starts: [2060.0, 4000.0],
+        drs_zones: &[
+            DrsZone::new(5210.0, 5580.0, 320.0),
+            DrsZone::new(2060.0, 2140.0, 3080.0),
+        ],
+        pit_lane_length: 450,
+    },
+    TrackInfo {
+        track: Track::Shanghai,
+        country: "CN",
+        location: "Shanghai",
+        length: 5451,
+        corners: 16,
+        sector_starts: [1640.0, 3800.0],
+        drs_zones: &[
+            DrsZone::new(4900.0, 5200.0, 250.0),
+            DrsZone::new(3180.0, 3600.0, 4330.0),
+        ],
+        pit_lane_length: 380,
+    },
+    TrackInfo {
+        track: Track::Sakhir,
+        country: "BH",
+        location: "Sakhir",
+        length: 5412,
+        corners: 15,
+        sector_starts: [1830.0, 3560.0],
+        drs_zones: &[
+            DrsZone::new(5110.0, 5250.0, 250.0),
+            DrsZone::new(880.0, 1020.0, 1450.0),
+            DrsZone::new(3440.0, 3600.0, 4000.0),
+        ],
+        pit_lane_length: 410,
+    },
+    TrackInfo {
+        track: Track::Catalunya,
+        country: "ES",
+        location: "Montmeló",
+        length: 4655,
+        corners: 16,
+        sector_starts: [1700.0, 3300.0],
+        drs_zones: &[
+            DrsZone::new(4250.0, 4410.0, 600.0),
+            DrsZone::new(3300.0, 3470.0, 3860.0),
+        ],
+        pit_lane_length: 340,
+    },
+    TrackInfo {
+        track: Track::Monaco,
+        country: "MC",
+        location: "Monte Carlo",
+        length: 3337,
+        corners: 19,
+        sector_starts: [1050.0, 2250.0],
+        drs_zones: &[DrsZone::new(2990.0, 3130.0, 320.0)],
+        pit_lane_length: 300,
+    },
+    TrackInfo {
+        track: Track::Montreal,
+        country: "CA",
+        location: "Montreal",
+        length: 4361,
+        corners: 14,
+        sector_starts: [1200.0, 2980.0],
+        drs_zones: &[
+            DrsZone::new(1750.0, 1950.0, 2380.0),
+            DrsZone::new(2700.0, 2900.0, 3950.0),
+            DrsZone::new(3650.0, 20.0, 360.0),
+        ],
+        pit_lane_length: 390,
+    },
+    TrackInfo {
+        track: Track::Silverstone,
+        country: "GB",
+        location: "Silverstone",
+        length: 5891,
+        corners: 18,
+        sector_starts: [1650.0, 3900.0],
+        drs_zones: &[
+            DrsZone::new(5570.0, 5700.0, 370.0),
+            DrsZone::new(3350.0, 3650.0, 4300.0),
+        ],
+        pit_lane_length: 390,
+    },
+    TrackInfo {
+        track: Track::Hockenheim,
+        country: "DE",
+        location: "Hockenheim",
+        length: 4574,
+        corners: 17,
+        sector_starts: [1500.0, 3350.0],
+        drs_zones: &[
+            DrsZone::new(900.0, 1150.0, 1750.0),
+            DrsZone::new(1900.0, 2050.0, 2550.0),
+            DrsZone::new(4250.0, 4400.0, 130.0),
+        ],
+        pit_lane_length: 350,
+    },
+    TrackInfo {
+        track: Track::Hungaroring,
+        country: "HU",
+        location: "Mogyoród",
+        length: 4381,
+        corners: 14,
+        sector_starts: [1450.0, 3150.0],
+        drs_zones: &[
+            DrsZone::new(4000.0, 4150.0, 500.0),
+            DrsZone::new(560.0, 700.0, 1000.0),
+        ],
+        pit_lane_length: 350,
+    },
+    TrackInfo {
+        track: Track::Spa,
+        country: "BE",
+        location: "Stavelot",
+        length: 7004,
+        corners: 19,
+        sector_starts: [2200.0, 5100.0],
+        drs_zones: &[
+            DrsZone::new(6650.0, 6850.0, 200.0),
+            DrsZone::new(400.0, 1100.0, 1900.0),
+        ],
+        pit_lane_length: 380,
+    },
+    TrackInfo {
+        track: Track::Monza,
+        country: "IT",
+        location: "Monza",
+        length: 5793,
+        corners: 11,
+        sector_starts: [1850.0, 3950.0],
+        drs_zones: &[
+            DrsZone::new(5250.0, 5550.0, 250.0),
+            DrsZone::new(1400.0, 1620.0, 2000.0),
+        ],
+        pit_lane_length: 420,
+    },
+    TrackInfo {
+        track: Track::Singapore,
+        country: "SG",
+        location: "Singapore",
+        length: 5063,
+        corners: 23,
+        sector_starts: [1650.0, 3300.0],
+        drs_zones: &[
+            DrsZone::new(4700.0, 4850.0, 130.0),
+            DrsZone::new(600.0, 750.0, 1150.0),
+            DrsZone::new(1350.0, 1500.0, 2050.0),
+        ],
+        pit_lane_length: 420,
+    },
+    TrackInfo {
+        track: Track::Suzuka,
+        country: "JP",
+        location: "Suzuka",
+        length: 5807,
+        corners: 18,
+        sector_starts: [2250.0, 4150.0],
+        drs_zones: &[DrsZone::new(5500.0, 5650.0, 300.0)],
+        pit_lane_length: 390,
+    },
+    TrackInfo {
+        track: Track::AbuDhabi,
+        country: "AE",
+        location: "Abu Dhabi",
+        length: 5554,
+        corners: 21,
+        sector_starts: [1650.0, 3500.0],
+        drs_zones: &[
+            DrsZone::new(1750.0, 2050.0, 3100.0),
+            DrsZone::new(3100.0, 3250.0, 3900.0),
+        ],
+        pit_lane_length: 380,
+    },
+    TrackInfo {
+        track: Track::Texas,
+        country: "US",
+        location: "Austin",
+        length: 5513,
+        corners: 20,
+        sector_starts: [1550.0, 3900.0],
+        drs_zones: &[
+            DrsZone::new(5150.0, 5350.0, 150.0),
+            DrsZone::new(2400.0, 2600.0, 3500.0),
+        ],
+        pit_lane_length: 400,
+    },
+    TrackInfo {
+        track: Track::Brazil,
+        country: "BR",
+        location: "São Paulo",
+        length: 4309,
+        corners: 15,
+        sector_starts: [950.0, 2950.0],
+        drs_zones: &[
+            DrsZone::new(3600.0, 3800.0, 100.0),
+            DrsZone::new(600.0, 800.0, 1400.0),
+        ],
+        pit_lane_length: 390,
+    },
+    TrackInfo {
+        track: Track::Austria,
+        country: "AT",
+        location: "Spielberg",
+        length: 4318,
+        corners: 10,
+        sector_starts: [1300.0, 2900.0],
+        drs_zones: &[
+            DrsZone::new(3900.0, 4100.0, 250.0),
+            DrsZone::new(500.0, 700.0, 1300.0),
+            DrsZone::new(1400.0, 1550.0, 2000.0),
+        ],
+        pit_lane_length: 380,
+    },
+    TrackInfo {
+        track: Track::Sochi,
+        country: "RU",
+        location: "Sochi",
+        length: 5848,
+        corners: 18,
+        sector_starts: [1300.0, 3650.0],
+        drs_zones: &[
+            DrsZone::new(5350.0, 5550.0, 150.0),
+            DrsZone::new(580.0, 760.0, 1630.0),
+        ],
+        pit_lane_length: 360,
+    },
+    TrackInfo {
+        track: Track::Mexico,
+        country: "MX",
+        location: "Mexico City",
+        length: 4304,
+        corners: 17,
+        sector_starts: [1400.0, 3100.0],
+        drs_zones: &[
+            DrsZone::new(3900.0, 4100.0, 800.0),
+            DrsZone::new(1000.0, 1200.0, 1900.0),
+        ],
+        pit_lane_length: 460,
+    },
+    TrackInfo {
+        track: Track::Baku,
+        country: "AZ",
+        location: "Baku",
+        length: 6003,
+        corners: 20,
+        sector_starts: [1600.0, 3700.0],
+        drs_zones: &[
+            DrsZone::new(5050.0, 5250.0, 50.0),
+            DrsZone::new(1900.0, 2150.0, 2550.0),
+        ],
+        pit_lane_length: 380,
+    },
+];
@@ -0,0 +1,177 @@
+//! Prometheus metrics for long-running telemetry consumers.
+//!
+//! Enabled with the `metrics` feature.
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, ToSocketAddrs};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+
+use crate::packet::Packet;
+use crate::StreamError;
+
+#[derive(Debug, Default)]
+struct Registry {
+    packets: BTreeMap<&'static str, u64>,
+    errors: BTreeMap<&'static str, u64>,
+    speed: BTreeMap<usize, u16>,
+    gear: BTreeMap<usize, i8>,
+    last_lap_time: BTreeMap<usize, f32>,
+}
+
+/// Counters and gauges fed from the packets and errors of a stream, rendered in
+/// the Prometheus text exposition format.
+///
+/// `Metrics` is cheap to clone: clones share the same values, so one can be kept
+/// by the receive loop while another is served with [`serve`].
+///
+/// [`serve`]: #method.serve
+#[derive(Debug, Clone, Default)]
+pub struct Metrics {
+    registry: Arc<Mutex<Registry>>,
+}
+
+impl Metrics {
+    pub fn new() -> Metrics {
+        Metrics::default()
+    }
+
+    /// Count a received packet, and update the per-driver gauges from it.
+    pub fn record(&self, packet: &Packet) {
+        let mut registry = self.registry.lock().unwrap();
+
+        *registry.packets.entry(packet_label(packet)).or_insert(0) += 1;
+
+        match packet {
+            Packet::CarTelemetry(t) => {
+                for (i, td) in t.car_telemetry_data().iter().enumerate() {
+                    registry.speed.insert(i, td.speed());
+                    registry.gear.insert(i, td.gear());
+                }
+            }
+            Packet::Lap(l) => {
+                for (i, ld) in l.lap_data().iter().enumerate() {
+                    registry.last_lap_time.insert(i, ld.last_lap_time());
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Count an error returned by the stream.
+    pub fn record_error(&self, error: &StreamError) {
+        let label = match error {
+            StreamError::Io(_) => "io",
+            StreamError::Truncated(_) => "truncated",
+            StreamError::Unpack(_) => "unpack",
+            StreamError::Desync(_) => "desync",
+        };
+
+        let mut registry = self.registry.lock().unwrap();
+        *registry.errors.entry(label).or_insert(0) += 1;
+    }
+
+    /// Render the metrics in the Prometheus text exposition format.
+    pub fn render(&self) -> String {
+        let registry = self.registry.lock().unwrap();
+        let mut out = String::new();
+
+        out.push_str("# HELP f1_packets_received_total Packets received, by type.\n");
+        out.push_str("# TYPE f1_packets_received_total counter\n");
+        for (label, count) in &registry.packets {
+            let _ = writeln!(
+                out,
+                "f1_packets_received_total{{type=\"{}\"}} {}",
+                label, count
+            );
+        }
+
+        out.push_str("# HELP f1_stream_errors_total Errors returned by the stream, by kind.\n");
+        out.push_str("# TYPE f1_stream_errors_total counter\n");
+        for (label, count) in &registry.errors {
+            let _ = writeln!(
+                out,
+                "f1_stream_errors_total{{kind=\"{}\"}} {}",
+                label, count
+            );
+        }
+
+        out.push_str("# HELP f1_driver_speed_kph Last known speed of each car.\n");
+        out.push_str("# TYPE f1_driver_speed_kph gauge\n");
+        for (car, speed) in &registry.speed {
+            let _ = writeln!(out, "f1_driver_speed_kph{{car=\"{}\"}} {}", car, speed);
+        }
+
+        out.push_str("# HELP f1_driver_gear Last known gear of each car.\n");
+        out.push_str("# TYPE f1_driver_gear gauge\n");
+        for (car, gear) in &registry.gear {
+            let _ = writeln!(out, "f1_driver_gear{{car=\"{}\"}} {}", car, gear);
+        }
+
+        out.push_str("# HELP f1_driver_last_lap_time_seconds Last lap time of each car.\n");
+        out.push_str("# TYPE f1_driver_last_lap_time_seconds gauge\n");
+        for (car, time) in &registry.last_lap_time {
+            let _ = writeln!(
+                out,
+                "f1_driver_last_lap_time_seconds{{car=\"{}\"}} {}",
+                car, time
+            );
+        }
+
+        out
+    }
+
+    /// Serve the metrics over HTTP on `addr`, for Prometheus to scrape. Every
+    /// request is answered with the metrics, whatever its path.
+    pub fn serve<A: ToSocketAddrs>(&self, addr: A) -> std::io::Result<JoinHandle<()>> {
+        let listener = TcpListener::bind(addr)?;
+        let metrics = self.clone();
+
+        Ok(thread::spawn(move || {
+            for mut conn in listener.incoming().flatten() {
+                // Read the request headers before answering.
+                let mut reader = BufReader::new(&conn);
+                let mut line = String::new();
+                while let Ok(n) = reader.read_line(&mut line) {
+                    if n == 0 || line.trim().is_empty() {
+                        break;
+                    }
+                    line.clear();
+                }
+
+                let body = metrics.render();
+                let _ = write!(
+                    conn,
+                    "HTTP/1.1 200 OK\r\n\
+                     Content-Type: text/plain; version=0.0.4\r\n\
+                     Content-Length: {}\r\n\
+                     Connection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                );
+            }
+        }))
+    }
+}
+
+fn packet_label(packet: &Packet) -> &'static str {
+    match packet {
+        Packet::Motion(_) => "motion",
+        Packet::Session(_) => "session",
+        Packet::Lap(_) => "lap_data",
+        Packet::Event(_) => "event",
+        Packet::Participants(_) => "participants",
+        Packet::CarSetups(_) => "car_setups",
+        Packet::CarTelemetry(_) => "car_telemetry",
+        Packet::CarStatus(_) => "car_status",
+        Packet::FinalClassification(_) => "final_classification",
+        Packet::LobbyInfo(_) => "lobby_info",
+        Packet::CarDamage(_) => "car_damage",
+        Packet::TyreSets(_) => "tyre_sets",
+        Packet::MotionEx(_) => "motion_ex",
+        Packet::TimeTrial(_) => "time_trial",
+        Packet::Raw(_) => "raw",
+    }
+}
@@ -0,0 +1,381 @@
+use getset::CopyGetters;
+use std::cell::{Cell, RefCell};
+use std::fmt;
+use std::io::ErrorKind;
+use std::net::{SocketAddr, ToSocketAddrs, UdpSocket};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use crate::forward::{Key, Opener, SEAL_OVERHEAD};
+use crate::packet::header::SessionIdentifier;
+use crate::packet::raw::RawPacket;
+use crate::packet::{
+    parse_packet_fuzz, peek_packet_id, peek_session_id, Packet, PacketFilter, MAX_PACKET_SIZE,
+};
+use crate::socket::SocketOptions;
+use crate::StreamError;
+
+/// Largest payload a UDP datagram can carry.
+const MAX_DATAGRAM_SIZE: usize = 65507;
+
+/// How long a spawned stream sleeps when none of its sockets has a packet.
+const SPAWN_IDLE_INTERVAL: Duration = Duration::from_millis(1);
+
+/// Identifies where a packet came from: the socket it was received on, and the
+/// address of the rig or forwarder that sent it.
+///
+/// ## Specification
+/// ```text
+/// socket:  Index of the socket, in the order they were added
+/// peer:    Address the datagram was sent from
+/// ```
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Ord, PartialOrd, CopyGetters)]
+#[getset(get_copy = "pub")]
+pub struct SourceId {
+    socket: usize,
+    peer: SocketAddr,
+}
+
+impl SourceId {
+    pub fn new(socket: usize, peer: SocketAddr) -> SourceId {
+        SourceId { socket, peer }
+    }
+}
+
+impl fmt::Display for SourceId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}@{}", self.peer, self.socket)
+    }
+}
+
+/// What is known of a source from the packets it sent.
+///
+/// ## Specification
+/// ```text
+/// id:         Socket and address of the source
+/// session:    Session of the last packet received, if it had a header
+/// packets:    Number of datagrams received
+/// errors:     Number of datagrams that could not be parsed
+/// rejected:   Number of datagrams that were not sealed with the key
+/// last_seen:  When the last datagram was received
+/// ```
+#[derive(Debug, Clone, Copy, CopyGetters)]
+#[getset(get_copy = "pub")]
+pub struct SourceInfo {
+    id: SourceId,
+    session: Option<SessionIdentifier>,
+    packets: u64,
+    errors: u64,
+    rejected: u64,
+    last_seen: Instant,
+}
+
+/// A packet tagged with its source, or an error, as sent by
+/// [`MultiStream::spawn`].
+///
+/// [`MultiStream::spawn`]: ./struct.MultiStream.html#method.spawn
+pub type SourceResult = Result<(SourceId, Packet), StreamError>;
+
+/// Listens on several UDP sockets at once and tags every packet with its
+/// [`SourceId`], e.g. on a league server receiving the telemetry forwarded from
+/// the rigs of many players, to one port or one port each.
+///
+/// Packets are read from the sockets in turn so that a busy source does not
+/// starve the others. Several rigs forwarding to the same port are told apart
+/// by their address.
+///
+/// [`SourceId`]: ./struct.SourceId.html
+pub struct MultiStream {
+    sockets: Vec<UdpSocket>,
+    buf: RefCell<Vec<u8>>,
+    next_socket: Cell<usize>,
+    filter: PacketFilter,
+    lenient: Cell<bool>,
+    sources: RefCell<Vec<SourceInfo>>,
+    opener: RefCell<Option<Opener>>,
+}
+
+impl Default for MultiStream {
+    fn default() -> MultiStream {
+        MultiStream {
+            sockets: Vec::new(),
+            // One spare byte lets us tell a packet of the maximum size from a truncated one.
+            buf: RefCell::new(vec![0; MAX_PACKET_SIZE + 1]),
+            next_socket: Cell::new(0),
+            filter: PacketFilter::all(),
+            lenient: Cell::new(false),
+            sources: RefCell::new(Vec::new()),
+            opener: RefCell::new(None),
+        }
+    }
+}
+
+impl MultiStream {
+    /// Stream without sockets, to be added with [`add`].
+    ///
+    /// [`add`]: #method.add
+    pub fn new() -> MultiStream {
+        MultiStream::default()
+    }
+
+    /// Listen on all the addresses, e.g. one port per rig.
+    pub fn bind<T: ToSocketAddrs>(addrs: &[T]) -> std::io::Result<MultiStream> {
+        let mut stream = MultiStream::new();
+        for addr in addrs {
+            stream.add(addr)?;
+        }
+
+        Ok(stream)
+    }
+
+    /// Also listen on `addr`, returning the index of the socket in the
+    /// [`SourceId`]s.
+    ///
+    /// [`SourceId`]: ./struct.SourceId.html
+    pub fn add<T: ToSocketAddrs>(&mut self, addr: T) -> std::io::Result<usize> {
+        self.add_socket(UdpSocket::bind(addr)?)
+    }
+
+    /// Also listen on `addr` with the given socket options.
+    pub fn add_with_options<T: ToSocketAddrs>(
+        &mut self,
+        addr: T,
+        options: &SocketOptions,
+    ) -> std::io::Result<usize> {
+        self.add_socket(options.bind(addr)?)
+    }
+
+    fn add_socket(&mut self, socket: UdpSocket) -> std::io::Result<usize> {
+        socket.set_nonblocking(true)?;
+        self.sockets.push(socket);
+
+        Ok(self.sockets.len() - 1)
+    }
+
+    /// In lenient mode, packets that cannot be parsed are returned as
+    /// [`Packet::Raw`] instead of errors.
+    ///
+    /// [`Packet::Raw`]: ./packet/enum.Packet.html#variant.Raw
+    pub fn set_lenient(&mut self, lenient: bool) {
+        self.lenient.set(lenient);
+    }
+
+    pub fn is_lenient(&self) -> bool {
+        self.lenient.get()
+    }
+
+    /// Only accept the datagrams sealed with `key` by a [`Forwarder`], e.g. on
+    /// a server open to the internet. The others are dropped, and counted as
+    /// rejected in the [`sources`].
+    ///
+    /// [`Forwarder`]: ./forward/struct.Forwarder.html
+    /// [`sources`]: #method.sources
+    pub fn set_key(&mut self, key: Key) {
+        self.opener = RefCell::new(Some(Opener::new(key)));
+
+        let mut buf = self.buf.borrow_mut();
+        let len = buf.len().max(MAX_PACKET_SIZE + SEAL_OVERHEAD + 1);
+        buf.resize(len, 0);
+    }
+
+    /// Only parse the packets accepted by `filter`; the others are skipped
+    /// after reading their id.
+    pub fn set_filter(&mut self, filter: PacketFilter) {
+        self.filter = filter;
+    }
+
+    pub fn filter(&self) -> PacketFilter {
+        self.filter
+    }
+
+    pub fn sockets(&self) -> &[UdpSocket] {
+        &self.sockets
+    }
+
+    /// Sources that sent packets so far, in the order they were first seen.
+    pub fn sources(&self) -> Vec<SourceInfo> {
+        self.sources.borrow().clone()
+    }
+
+    /// Sources whose last packet was sent during `session`, e.g. the rigs of
+    /// the players of an online session.
+    pub fn sources_in(&self, session: SessionIdentifier) -> Vec<SourceId> {
+        self.sources
+            .borrow()
+            .iter()
+            .filter(|s| s.session == Some(session))
+            .map(|s| s.id)
+            .collect()
+    }
+
+    /// Read the next packet of any socket, or `None` if none has one waiting.
+    pub fn next(&self) -> Result<Option<(SourceId, Packet)>, StreamError> {
+        let mut buf = self.buf.borrow_mut();
+        let mut sources = self.sources.borrow_mut();
+        let mut idle = 0;
+
+        while idle < self.sockets.len() {
+            let index = self.next_socket.get() % self.sockets.len();
+            self.next_socket.set(index + 1);
+
+            let (len, peer) = match self.sockets[index].recv_from(&mut buf) {
+                Ok(received) => received,
+                Err(e) if e.kind() == ErrorKind::WouldBlock || e.kind() == ErrorKind::TimedOut => {
+                    idle += 1;
+                    continue;
+                }
+                Err(e) => return Err(StreamError::Io(e)),
+            };
+            idle = 0;
+
+            let id = SourceId::new(index, peer);
+            let source = record(&mut sources, id);
+
+            if len == buf.len() && len < MAX_DATAGRAM_SIZE {
+                source.errors += 1;
+
+                let new_len = (buf.len() * 2).min(MAX_DATAGRAM_SIZE);
+                buf.resize(new_len, 0);
+
+                return Err(StreamError::Truncated(len));
+            }
+
+            let opened;
+            let datagram = match self.opener.borrow_mut().as_mut() {
+                Some(opener) => match opener.open(&buf[..len]) {
+                    Ok(datagram) => {
+                        opened = datagram;
+                        &opened[..]
+                    }
+                    Err(_) => {
+                        source.rejected += 1;
+                        continue;
+                    }
+                },
+                None => &buf[..len],
+            };
+
+            if let Some(session) = peek_session_id(datagram) {
+                source.session = Some(session);
+            }
+
+            if let Some(packet_id) = peek_packet_id(datagram) {
+                if !self.filter.accepts_id(packet_id) {
+                    continue;
+                }
+            }
+
+            return match parse_packet_fuzz(datagram) {
+                Ok(p) => Ok(Some((id, p))),
+                Err(e) => {
+                    source.errors += 1;
+
+                    if self.lenient.get() {
+                        Ok(Some((id, Packet::Raw(RawPacket::new(datagram)))))
+                    } else {
+                        Err(e.into())
+                    }
+                }
+            };
+        }
+
+        Ok(None)
+    }
+
+    /// Read the sockets on a background thread, which sends the packets and
+    /// errors on the returned channel. The thread stops when the receiver is
+    /// dropped or the handle is shut down.
+    pub fn spawn(self) -> std::io::Result<(Receiver<SourceResult>, MultiStreamHandle)> {
+        let (sender, receiver) = mpsc::channel();
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let stop = Arc::clone(&shutdown);
+
+        let thread = thread::Builder::new()
+            .name(String::from("f1-telemetry-multi-stream"))
+            .spawn(move || {
+                while !stop.load(Ordering::Relaxed) {
+                    let result = match self.next() {
+                        Ok(Some(p)) => Ok(p),
+                        Ok(None) => {
+                            thread::sleep(SPAWN_IDLE_INTERVAL);
+                            continue;
+                        }
+                        Err(e) => Err(e),
+                    };
+
+                    if sender.send(result).is_err() {
+                        break;
+                    }
+                }
+
+                self
+            })?;
+
+        let handle = MultiStreamHandle {
+            shutdown,
+            thread: Some(thread),
+        };
+
+        Ok((receiver, handle))
+    }
+}
+
+/// Count a datagram received from a source.
+fn record(sources: &mut Vec<SourceInfo>, id: SourceId) -> &mut SourceInfo {
+    let index = match sources.iter().position(|s| s.id == id) {
+        Some(index) => index,
+        None => {
+            sources.push(SourceInfo {
+                id,
+                session: None,
+                packets: 0,
+                errors: 0,
+                rejected: 0,
+                last_seen: Instant::now(),
+            });
+            sources.len() - 1
+        }
+    };
+
+    let source = &mut sources[index];
+    source.packets += 1;
+    source.last_seen = Instant::now();
+
+    source
+}
+
+/// Handle on a multi-stream read by a background thread, see
+/// [`MultiStream::spawn`].
+///
+/// Dropping the handle stops the thread without waiting for it.
+///
+/// [`MultiStream::spawn`]: ./struct.MultiStream.html#method.spawn
+pub struct MultiStreamHandle {
+    shutdown: Arc<AtomicBool>,
+    thread: Option<JoinHandle<MultiStream>>,
+}
+
+impl MultiStreamHandle {
+    /// Stop the background thread and give the stream back.
+    pub fn shutdown(mut self) -> MultiStream {
+        self.shutdown.store(true, Ordering::Relaxed);
+
+        let thread = self
+            .thread
+            .take()
+            .expect("Multi-stream thread already joined");
+        match thread.join() {
+            Ok(stream) => stream,
+            Err(e) => std::panic::resume_unwind(e),
+        }
+    }
+}
+
+impl Drop for MultiStreamHandle {
+    fn drop(&mut self) {
+        self.shutdown.store(true, Ordering::Relaxed);
+    }
+}
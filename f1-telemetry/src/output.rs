@@ -0,0 +1,5 @@
+//! Outputs driving hardware from the telemetry.
+
+pub mod dashboard;
+pub mod ledbar;
+pub mod motion_platform;
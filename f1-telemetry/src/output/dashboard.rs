@@ -0,0 +1,287 @@
+//! Compact binary feed for microcontroller dashboards, e.g. an Arduino driving
+//! a display over USB serial.
+//!
+//! [`DashboardFrame`] gathers the values of the player's car a dashboard
+//! usually shows from the Car Telemetry and Car Status packets, and encodes
+//! them in a fixed 21 byte message. [`DashboardSender`] writes the messages to
+//! a serial device at a configurable rate.
+//!
+//! On Linux and macOS, the serial device is opened as a file, once its speed
+//! is set, e.g. with `stty -F /dev/ttyACM0 115200 raw`; on Windows, with the
+//! port name, e.g. `COM3`, once set with `mode COM3 BAUD=115200`. A message
+//! takes 210 bits on the line with 8N1 framing, so 60 messages per second
+//! need 12600 bauds, i.e. 19200 or more: at 9600 bauds, the rate must be 45 Hz
+//! at most, see [`max_rate`].
+//!
+//! [`max_rate`]: ./fn.max_rate.html
+//!
+//! [`DashboardFrame`]: ./struct.DashboardFrame.html
+//! [`DashboardSender`]: ./struct.DashboardSender.html
+
+use getset::CopyGetters;
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use crate::packet::car_status::{CarStatusData, ERSDeployMode, DRS};
+use crate::packet::car_telemetry::CarTelemetryData;
+use crate::packet::generic::Flag;
+use crate::packet::Packet;
+use crate::pipeline::PacketSink;
+
+/// First byte of a message.
+pub const SYNC: u8 = 0xF1;
+
+/// Version of the message layout.
+pub const VERSION: u8 = 1;
+
+/// Size of an encoded message.
+pub const MESSAGE_SIZE: usize = 21;
+
+const PAYLOAD_SIZE: u8 = 16;
+
+/// Bits sent on a serial line for each byte, with a start and a stop bit.
+const BITS_PER_BYTE: u32 = 10;
+
+/// Energy of a full ERS store, in Joules.
+const ERS_CAPACITY: f32 = 4_000_000.0;
+
+/// Values shown by a dashboard, for the player's car.
+///
+/// ## Specification
+/// The message is little-endian:
+/// ```text
+/// Offset  Type  Field
+/// 0       u8    Sync byte, 0xF1
+/// 1       u8    Version, 1
+/// 2       u8    Sequence number, wrapping, to detect lost messages
+/// 3       u8    Payload length, 16
+/// 4       u16   Speed (km/h)
+/// 6       i8    Gear (1-8, N=0, R=-1)
+/// 7       u16   Engine RPM
+/// 9       u16   Max RPM, i.e. the rev limiter
+/// 11      u8    Rev lights (percentage)
+/// 12      u8    Status bits: 0 = DRS open, 1 = DRS allowed, 2 = pit limiter
+/// 13      u8    ERS store (percentage)
+/// 14      u8    ERS deploy mode (0 = none, 1 = low, 2 = medium, 3 = high,
+///               4 = overtake, 5 = hotlap)
+/// 15      u16   Fuel in tank (hundredths of kg)
+/// 17      i16   Fuel remaining laps (hundredths of a lap)
+/// 19      i8    FIA flag (-1 = invalid/unknown, 0 = none, 1 = green,
+///               2 = blue, 3 = yellow, 4 = red)
+/// 20      u8    Checksum, XOR of the bytes 1 to 19
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, CopyGetters)]
+#[getset(get_copy = "pub")]
+pub struct DashboardFrame {
+    speed: u16,
+    gear: i8,
+    engine_rpm: u16,
+    max_rpm: u16,
+    rev_lights_percent: u8,
+    drs: bool,
+    drs_allowed: bool,
+    pit_limiter: bool,
+    ers_store_percent: u8,
+    ers_deploy_mode: ERSDeployMode,
+    fuel_in_tank: f32,
+    fuel_remaining_laps: f32,
+    flag: Flag,
+}
+
+impl Default for DashboardFrame {
+    fn default() -> Self {
+        DashboardFrame {
+            speed: 0,
+            gear: 0,
+            engine_rpm: 0,
+            max_rpm: 0,
+            rev_lights_percent: 0,
+            drs: false,
+            drs_allowed: false,
+            pit_limiter: false,
+            ers_store_percent: 0,
+            ers_deploy_mode: ERSDeployMode::None,
+            fuel_in_tank: 0.0,
+            fuel_remaining_laps: 0.0,
+            flag: Flag::None,
+        }
+    }
+}
+
+impl DashboardFrame {
+    pub fn new() -> DashboardFrame {
+        DashboardFrame::default()
+    }
+
+    /// Update the values from the telemetry of the car.
+    pub fn update_telemetry(&mut self, telemetry: &CarTelemetryData) {
+        self.speed = telemetry.speed();
+        self.gear = telemetry.gear();
+        self.engine_rpm = telemetry.engine_rpm();
+        self.rev_lights_percent = telemetry.rev_lights_percent();
+        self.drs = telemetry.drs();
+    }
+
+    /// Update the values from the status of the car.
+    pub fn update_status(&mut self, status: &CarStatusData) {
+        self.max_rpm = status.max_rpm();
+        self.drs_allowed = status.drs_allowed() == DRS::Allowed;
+        self.pit_limiter = status.pit_limiter();
+        let store = status.ers_store_energy() / ERS_CAPACITY * 100.0;
+        self.ers_store_percent = store.round().clamp(0.0, 100.0) as u8;
+        self.ers_deploy_mode = status.ers_deploy_mode();
+        self.fuel_in_tank = status.fuel_in_tank();
+        self.fuel_remaining_laps = status.fuel_remaining_laps();
+        self.flag = status.vehicle_fia_flags();
+    }
+
+    /// Update the values from a packet of the player's car, returning whether
+    /// it held any.
+    pub fn update(&mut self, packet: &Packet) -> bool {
+        match packet {
+            Packet::CarTelemetry(p) => match p.player_car_telemetry() {
+                Some(telemetry) => self.update_telemetry(telemetry),
+                None => return false,
+            },
+            Packet::CarStatus(p) => {
+                let index = p.header().player_car_index() as usize;
+                match p.car_status_data().get(index) {
+                    Some(status) => self.update_status(status),
+                    None => return false,
+                }
+            }
+            _ => return false,
+        }
+
+        true
+    }
+
+    /// Encode the values as a message.
+    pub fn encode(&self, sequence: u8) -> [u8; MESSAGE_SIZE] {
+        let mut message = [0; MESSAGE_SIZE];
+        message[0] = SYNC;
+        message[1] = VERSION;
+        message[2] = sequence;
+        message[3] = PAYLOAD_SIZE;
+        message[4..6].copy_from_slice(&self.speed.to_le_bytes());
+        message[6] = self.gear as u8;
+        message[7..9].copy_from_slice(&self.engine_rpm.to_le_bytes());
+        message[9..11].copy_from_slice(&self.max_rpm.to_le_bytes());
+        message[11] = self.rev_lights_percent;
+        message[12] =
+            self.drs as u8 | (self.drs_allowed as u8) << 1 | (self.pit_limiter as u8) << 2;
+        message[13] = self.ers_store_percent;
+        message[14] = self.ers_deploy_mode.into();
+        let fuel_in_tank = (self.fuel_in_tank * 100.0).round().clamp(0.0, 65535.0) as u16;
+        message[15..17].copy_from_slice(&fuel_in_tank.to_le_bytes());
+        let fuel_laps = (self.fuel_remaining_laps * 100.0)
+            .round()
+            .clamp(-32768.0, 32767.0) as i16;
+        message[17..19].copy_from_slice(&fuel_laps.to_le_bytes());
+        message[19] = match self.flag {
+            Flag::Unknown(_) => -1i8,
+            flag => flag.into(),
+        } as u8;
+        message[20] = message[1..20].iter().fold(0, |checksum, b| checksum ^ b);
+
+        message
+    }
+}
+
+/// Highest rate, in messages per second, that a serial line at `baud` bauds
+/// can carry with 8N1 framing, e.g. 45 at 9600 bauds.
+pub fn max_rate(baud: u32) -> u32 {
+    baud / (MESSAGE_SIZE as u32 * BITS_PER_BYTE)
+}
+
+/// Writes the dashboard messages of the player's car to a device, at most
+/// `rate` times per second, which must not exceed the [`max_rate`] of its
+/// speed.
+///
+/// As a [`PacketSink`], the sender updates its frame with every packet and
+/// sends it when due; the write errors are counted rather than returned.
+///
+/// [`max_rate`]: ./fn.max_rate.html
+/// [`PacketSink`]: ../../pipeline/trait.PacketSink.html
+#[derive(Debug)]
+pub struct DashboardSender<W: Write> {
+    writer: W,
+    frame: DashboardFrame,
+    interval: Duration,
+    last_sent: Option<Instant>,
+    sequence: u8,
+    errors: u64,
+}
+
+impl DashboardSender<File> {
+    /// Sender writing to a serial device, e.g. `/dev/ttyACM0` or `COM3`.
+    pub fn open<P: AsRef<Path>>(device: P, rate: u32) -> io::Result<DashboardSender<File>> {
+        let file = OpenOptions::new().write(true).open(device)?;
+        Ok(DashboardSender::new(file, rate))
+    }
+}
+
+impl<W: Write> DashboardSender<W> {
+    pub fn new(writer: W, rate: u32) -> DashboardSender<W> {
+        DashboardSender {
+            writer,
+            frame: DashboardFrame::default(),
+            interval: Duration::from_secs(1) / rate.max(1),
+            last_sent: None,
+            sequence: 0,
+            errors: 0,
+        }
+    }
+
+    pub fn frame(&self) -> &DashboardFrame {
+        &self.frame
+    }
+
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    /// Number of messages that could not be written.
+    pub fn errors(&self) -> u64 {
+        self.errors
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+
+    /// Update the frame with a packet, and send it if it is due.
+    pub fn update(&mut self, packet: &Packet) -> io::Result<()> {
+        if !self.frame.update(packet) {
+            return Ok(());
+        }
+
+        let now = Instant::now();
+        match self.last_sent {
+            Some(last) if now.duration_since(last) < self.interval => Ok(()),
+            _ => {
+                self.last_sent = Some(now);
+                self.send()
+            }
+        }
+    }
+
+    /// Send the frame right away.
+    pub fn send(&mut self) -> io::Result<()> {
+        let message = self.frame.encode(self.sequence);
+        self.sequence = self.sequence.wrapping_add(1);
+
+        self.writer.write_all(&message)?;
+        self.writer.flush()
+    }
+}
+
+impl<W: Write + Send> PacketSink for DashboardSender<W> {
+    fn handle(&mut self, packet: &Packet) {
+        if self.update(packet).is_err() {
+            self.errors += 1;
+        }
+    }
+}
@@ -0,0 +1,274 @@
+//! Rev lights for LED bars, e.g. DIY strips driven by an Arduino.
+//!
+//! [`LedBar`] turns the rev lights of the player's car into a [`Frame`] of
+//! colours, lighting the LEDs from left to right and blinking them past the
+//! shift point, and [`LedWriter`] writes the frames to a device in one of the
+//! [`LedFormat`]s, e.g. to a serial port opened as a file.
+//!
+//! [`LedBar`]: ./struct.LedBar.html
+//! [`Frame`]: ./type.Frame.html
+//! [`LedWriter`]: ./struct.LedWriter.html
+//! [`LedFormat`]: ./enum.LedFormat.html
+
+use std::io::{self, Write};
+
+use crate::packet::car_telemetry::PacketCarTelemetryData;
+
+/// Number of LEDs of the rev lights bit values of the game.
+pub const REV_LIGHTS_BITS: usize = 15;
+
+/// Colour of a LED.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash)]
+pub struct Rgb {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+impl Rgb {
+    pub const OFF: Rgb = Rgb::new(0, 0, 0);
+    pub const GREEN: Rgb = Rgb::new(0, 255, 0);
+    pub const RED: Rgb = Rgb::new(255, 0, 0);
+    pub const BLUE: Rgb = Rgb::new(0, 0, 255);
+    pub const PURPLE: Rgb = Rgb::new(160, 0, 255);
+
+    pub const fn new(r: u8, g: u8, b: u8) -> Rgb {
+        Rgb { r, g, b }
+    }
+}
+
+/// Colours of the LEDs of a bar, from left to right.
+pub type Frame = Vec<Rgb>;
+
+/// Layout and colours of a LED bar.
+///
+/// ## Specification
+/// ```text
+/// count:        Number of LEDs of the bar
+/// colors:       Colours of the LEDs from left to right, each one covering an
+///               equal share of the bar, e.g. green, red and blue thirds
+/// shift_point:  Rev lights percentage from which the bar blinks, above 100 to
+///               never blink
+/// shift_color:  Colour of every LED while blinking, or None to keep their own
+/// blink_period: Period of the blinking in seconds
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct LedBarConfig {
+    pub count: usize,
+    pub colors: Vec<Rgb>,
+    pub shift_point: u8,
+    pub shift_color: Option<Rgb>,
+    pub blink_period: f32,
+}
+
+impl LedBarConfig {
+    /// Colour of the LED at `index`.
+    pub fn color(&self, index: usize) -> Rgb {
+        if self.colors.is_empty() || self.count == 0 {
+            return Rgb::OFF;
+        }
+
+        self.colors[index * self.colors.len() / self.count]
+    }
+}
+
+impl Default for LedBarConfig {
+    /// 15 LEDs in green, red and blue thirds like the wheels of the game,
+    /// blinking in blue at full revs.
+    fn default() -> Self {
+        LedBarConfig {
+            count: REV_LIGHTS_BITS,
+            colors: vec![Rgb::GREEN, Rgb::RED, Rgb::BLUE],
+            shift_point: 100,
+            shift_color: Some(Rgb::BLUE),
+            blink_period: 0.2,
+        }
+    }
+}
+
+/// Turns rev lights into LED frames, keeping the phase of the blinking
+/// between packets.
+#[derive(Debug, Clone, Default)]
+pub struct LedBar {
+    config: LedBarConfig,
+    blink_time: Option<f32>,
+    last_session_time: Option<f32>,
+}
+
+impl LedBar {
+    pub fn new(config: LedBarConfig) -> LedBar {
+        LedBar {
+            config,
+            ..LedBar::default()
+        }
+    }
+
+    pub fn config(&self) -> &LedBarConfig {
+        &self.config
+    }
+
+    /// Stop blinking, e.g. when a new session starts.
+    pub fn reset(&mut self) {
+        self.blink_time = None;
+        self.last_session_time = None;
+    }
+
+    /// Frame for a rev lights percentage, `dt` seconds after the previous one.
+    pub fn update(&mut self, rev_lights_percent: u8, dt: f32) -> Frame {
+        let percent = usize::from(rev_lights_percent.min(100));
+        // Round up, so that the first LED lights as soon as the revs rise.
+        let lit = (percent * self.config.count).div_ceil(100);
+
+        self.frame(
+            |i| i < lit,
+            rev_lights_percent >= self.config.shift_point,
+            dt,
+        )
+    }
+
+    /// Frame for the rev lights bit values of the game, bit 0 being the
+    /// leftmost of its 15 LEDs, `dt` seconds after the previous one.
+    ///
+    /// The bar blinks when every LED is lit.
+    pub fn update_bits(&mut self, bits: u16, dt: f32) -> Frame {
+        let count = self.config.count;
+        let all = (1 << REV_LIGHTS_BITS) - 1;
+
+        self.frame(
+            |i| bits & (1 << (i * REV_LIGHTS_BITS / count)) != 0,
+            bits & all == all,
+            dt,
+        )
+    }
+
+    /// Frame for the player's car, timed with the session time of the packet.
+    pub fn update_packet(&mut self, packet: &PacketCarTelemetryData) -> Option<Frame> {
+        let telemetry = packet.player_car_telemetry()?;
+        let session_time = packet.header().session_time();
+
+        let dt = match self.last_session_time {
+            Some(last) if session_time >= last => session_time - last,
+            // The session restarted
+            Some(_) => {
+                self.reset();
+                0.0
+            }
+            None => 0.0,
+        };
+        self.last_session_time = Some(session_time);
+
+        Some(self.update(telemetry.rev_lights_percent(), dt))
+    }
+
+    fn frame<F: Fn(usize) -> bool>(&mut self, is_lit: F, shifting: bool, dt: f32) -> Frame {
+        let config = &self.config;
+
+        if !shifting {
+            self.blink_time = None;
+
+            return (0..config.count)
+                .map(|i| if is_lit(i) { config.color(i) } else { Rgb::OFF })
+                .collect();
+        }
+
+        let blink_time = match self.blink_time {
+            Some(t) => t + dt.max(0.0),
+            None => 0.0,
+        };
+        self.blink_time = Some(blink_time);
+
+        // On for the first half of each period.
+        let on = config.blink_period <= 0.0
+            || blink_time % config.blink_period < config.blink_period / 2.0;
+
+        (0..config.count)
+            .map(|i| match (on, config.shift_color) {
+                (false, _) => Rgb::OFF,
+                (true, Some(color)) => color,
+                (true, None) => config.color(i),
+            })
+            .collect()
+    }
+}
+
+/// Wire format of the frames sent to a LED device.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum LedFormat {
+    /// The Adalight protocol of Arduino sketches such as FastLED's: `Ada`, the
+    /// number of LEDs minus one as a big-endian `u16`, a checksum, then the
+    /// colours.
+    Adalight,
+    /// The colours only, three bytes per LED.
+    Raw,
+    /// The DRGB realtime protocol of WLED, sent over UDP, with the timeout
+    /// in seconds after which the device resumes its own effects.
+    Wled(u8),
+}
+
+impl LedFormat {
+    pub fn encode(self, frame: &[Rgb]) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(6 + frame.len() * 3);
+
+        match self {
+            LedFormat::Adalight => {
+                let [hi, lo] = (frame.len().saturating_sub(1) as u16).to_be_bytes();
+                bytes.extend_from_slice(b"Ada");
+                bytes.extend_from_slice(&[hi, lo, hi ^ lo ^ 0x55]);
+            }
+            LedFormat::Raw => {}
+            LedFormat::Wled(timeout) => bytes.extend_from_slice(&[2, timeout]),
+        }
+
+        for led in frame {
+            bytes.extend_from_slice(&[led.r, led.g, led.b]);
+        }
+
+        bytes
+    }
+}
+
+/// Writes LED frames to a device, e.g. a serial port or a `UdpSocket` wrapped
+/// in a writer.
+///
+/// A frame equal to the previous one is not written again, to spare the
+/// bandwidth of slow serial links.
+#[derive(Debug)]
+pub struct LedWriter<W: Write> {
+    writer: W,
+    format: LedFormat,
+    last: Option<Frame>,
+}
+
+impl<W: Write> LedWriter<W> {
+    pub fn new(writer: W, format: LedFormat) -> LedWriter<W> {
+        LedWriter {
+            writer,
+            format,
+            last: None,
+        }
+    }
+
+    pub fn format(&self) -> LedFormat {
+        self.format
+    }
+
+    pub fn get_ref(&self) -> &W {
+        &self.writer
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+
+    pub fn send(&mut self, frame: &[Rgb]) -> io::Result<()> {
+        if self.last.as_deref() == Some(frame) {
+            return Ok(());
+        }
+
+        self.writer.write_all(&self.format.encode(frame))?;
+        self.writer.flush()?;
+        self.last = Some(frame.to_vec());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,243 @@
+//! Motion cueing for 6-DOF motion platforms.
+//!
+//! [`MotionCueing`] turns the player's motion data into a [`PlatformCommand`],
+//! scaling each axis and washing out the sustained values so that the platform
+//! drifts back to its neutral position, and [`PlatformSender`] sends the commands
+//! to a platform controller over UDP.
+//!
+//! [`MotionCueing`]: ./struct.MotionCueing.html
+//! [`PlatformCommand`]: ./struct.PlatformCommand.html
+//! [`PlatformSender`]: ./struct.PlatformSender.html
+
+use getset::CopyGetters;
+use std::f32::consts::PI;
+use std::io;
+use std::net::{ToSocketAddrs, UdpSocket};
+
+use crate::packet::motion::{MotionData, PacketMotionData};
+
+/// Position of a 6-DOF platform, each axis between -1 and 1.
+///
+/// ## Specification
+/// ```text
+/// heave: Vertical translation, from the vertical G-force
+/// surge: Longitudinal translation, from the longitudinal G-force
+/// sway:  Lateral translation, from the lateral G-force
+/// pitch: Rotation around the lateral axis, from the pitch angle
+/// roll:  Rotation around the longitudinal axis, from the roll angle
+/// yaw:   Rotation around the vertical axis, from the yaw angle
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, CopyGetters)]
+#[getset(get_copy = "pub")]
+pub struct PlatformCommand {
+    heave: f32,
+    surge: f32,
+    sway: f32,
+    pitch: f32,
+    roll: f32,
+    yaw: f32,
+}
+
+impl PlatformCommand {
+    /// Axes in the order heave, surge, sway, pitch, roll, yaw.
+    pub fn to_array(self) -> [f32; 6] {
+        [
+            self.heave, self.surge, self.sway, self.pitch, self.roll, self.yaw,
+        ]
+    }
+}
+
+/// Cueing of one axis.
+///
+/// The input (in G or radians) is multiplied by `scale` to give the command, and
+/// high-pass filtered with a time constant of `washout` seconds when it is not 0.
+#[derive(Debug, Clone, Copy, PartialEq, CopyGetters)]
+#[getset(get_copy = "pub")]
+pub struct AxisConfig {
+    scale: f32,
+    washout: f32,
+}
+
+impl AxisConfig {
+    pub fn new(scale: f32, washout: f32) -> AxisConfig {
+        AxisConfig { scale, washout }
+    }
+}
+
+/// Cueing of the six axes of a platform.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PlatformConfig {
+    pub heave: AxisConfig,
+    pub surge: AxisConfig,
+    pub sway: AxisConfig,
+    pub pitch: AxisConfig,
+    pub roll: AxisConfig,
+    pub yaw: AxisConfig,
+}
+
+impl Default for PlatformConfig {
+    /// Full travel at 3G, a quarter turn of pitch or roll and a half turn of
+    /// yaw, with the sustained heave and yaw washed out.
+    fn default() -> Self {
+        PlatformConfig {
+            heave: AxisConfig::new(1.0 / 3.0, 1.0),
+            surge: AxisConfig::new(1.0 / 3.0, 0.0),
+            sway: AxisConfig::new(1.0 / 3.0, 0.0),
+            pitch: AxisConfig::new(2.0 / PI, 0.0),
+            roll: AxisConfig::new(2.0 / PI, 0.0),
+            yaw: AxisConfig::new(1.0 / PI, 0.5),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct Washout {
+    input: f32,
+    output: f32,
+}
+
+impl Washout {
+    fn filter(&mut self, input: f32, dt: f32, config: AxisConfig) -> f32 {
+        let output = if config.washout <= 0.0 {
+            input
+        } else if dt <= 0.0 {
+            self.output
+        } else {
+            let alpha = config.washout / (config.washout + dt);
+            alpha * (self.output + input - self.input)
+        };
+
+        self.input = input;
+        self.output = output;
+
+        (output * config.scale).clamp(-1.0, 1.0)
+    }
+}
+
+/// Turns motion data into platform commands, keeping the state of the washout
+/// filters between packets.
+#[derive(Debug, Clone, Default)]
+pub struct MotionCueing {
+    config: PlatformConfig,
+    filters: [Washout; 6],
+    last_session_time: Option<f32>,
+    yaw: Option<f32>,
+}
+
+impl MotionCueing {
+    pub fn new(config: PlatformConfig) -> MotionCueing {
+        MotionCueing {
+            config,
+            ..MotionCueing::default()
+        }
+    }
+
+    pub fn config(&self) -> &PlatformConfig {
+        &self.config
+    }
+
+    /// Bring the platform back to neutral, e.g. when a new session starts.
+    pub fn reset(&mut self) {
+        self.filters = [Washout::default(); 6];
+        self.last_session_time = None;
+        self.yaw = None;
+    }
+
+    /// Command for the motion of a car, `dt` seconds after the previous one.
+    pub fn update(&mut self, motion: &MotionData, dt: f32) -> PlatformCommand {
+        // Unwrap the yaw so that crossing ±π does not jolt the platform.
+        let yaw = match self.yaw {
+            Some(previous) => {
+                let delta = (motion.yaw() - previous + PI).rem_euclid(2.0 * PI) - PI;
+                previous + delta
+            }
+            None => motion.yaw(),
+        };
+        self.yaw = Some(yaw);
+
+        let config = self.config;
+        let [heave, surge, sway, pitch, roll, yaw_filter] = &mut self.filters;
+
+        PlatformCommand {
+            heave: heave.filter(motion.g_force_vertical(), dt, config.heave),
+            surge: surge.filter(motion.g_force_longitudinal(), dt, config.surge),
+            sway: sway.filter(motion.g_force_lateral(), dt, config.sway),
+            pitch: pitch.filter(motion.pitch(), dt, config.pitch),
+            roll: roll.filter(motion.roll(), dt, config.roll),
+            yaw: yaw_filter.filter(yaw, dt, config.yaw),
+        }
+    }
+
+    /// Command for the player's car, timed with the session time of the packet.
+    pub fn update_packet(&mut self, packet: &PacketMotionData) -> Option<PlatformCommand> {
+        let motion = packet.player_motion_data()?;
+        let session_time = packet.header().session_time();
+
+        let dt = match self.last_session_time {
+            Some(last) if session_time >= last => session_time - last,
+            // The session restarted
+            Some(_) => {
+                self.reset();
+                0.0
+            }
+            None => 0.0,
+        };
+        self.last_session_time = Some(session_time);
+
+        Some(self.update(motion, dt))
+    }
+}
+
+/// Wire format of the commands sent to a platform.
+///
+/// Both list the axes in the order heave, surge, sway, pitch, roll, yaw, which
+/// is how generic UDP inputs of motion software are usually set up.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum PlatformFormat {
+    /// Six little-endian `f32`.
+    Binary,
+    /// Six decimal numbers separated by commas, ending with a newline.
+    Text,
+}
+
+impl PlatformFormat {
+    pub fn encode(self, command: &PlatformCommand) -> Vec<u8> {
+        let values = command.to_array();
+
+        match self {
+            PlatformFormat::Binary => values.iter().flat_map(|v| v.to_le_bytes()).collect(),
+            PlatformFormat::Text => {
+                let values: Vec<String> = values.iter().map(|v| format!("{:.4}", v)).collect();
+                format!("{}\n", values.join(",")).into_bytes()
+            }
+        }
+    }
+}
+
+/// Sends platform commands to a controller over UDP.
+#[derive(Debug)]
+pub struct PlatformSender {
+    socket: UdpSocket,
+    format: PlatformFormat,
+}
+
+impl PlatformSender {
+    pub fn new<T: ToSocketAddrs>(
+        platform: T,
+        format: PlatformFormat,
+    ) -> io::Result<PlatformSender> {
+        let socket = UdpSocket::bind("0.0.0.0:0")?;
+        socket.connect(platform)?;
+
+        Ok(PlatformSender { socket, format })
+    }
+
+    pub fn format(&self) -> PlatformFormat {
+        self.format
+    }
+
+    pub fn send(&self, command: &PlatformCommand) -> io::Result<()> {
+        self.socket.send(&self.format.encode(command))?;
+        Ok(())
+    }
+}
@@ -0,0 +1,560 @@
+//! HTTP API for broadcast overlays.
+//!
+//! Serves the standings, gaps, last laps and tyres of a session as JSON, for
+//! browser sources of OBS and other broadcast software:
+//!
+//! ```text
+//! GET /standings       All the classified drivers, by position
+//! GET /driver/{index}  A driver, by vehicle index
+//! GET /events          Server-sent events of the changes, as they happen
+//! ```
+//!
+//! The `/events` stream sends `position`, `lap`, `flag` and `safety_car`
+//! events, each with a JSON object as data, so that lightweight overlays can
+//! update without polling:
+//!
+//! ```text
+//! event: position
+//! data: {"index":3,"from":5,"to":4}
+//!
+//! event: lap
+//! data: {"index":3,"lap":12,"time":91.204,"position":4}
+//!
+//! event: flag
+//! data: {"zone":7,"flag":"Yellow"}
+//!
+//! event: safety_car
+//! data: {"status":"Virtual"}
+//! ```
+//!
+//! Enabled with the `overlay` feature.
+
+use std::fmt::Write as _;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{Shutdown, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use crate::analysis::gaps::GapTracker;
+use crate::json::{json_float, json_option, json_string};
+use crate::packet::car_status::TyreCompoundVisual;
+use crate::packet::generic::Flag;
+use crate::packet::header::SessionIdentifier;
+use crate::packet::lap::{LapData, PacketLapData, PitStatus, ResultStatus};
+use crate::packet::participants::{Nationality, Team};
+use crate::packet::session::{PacketSessionData, SafetyCar};
+use crate::packet::Packet;
+
+/// Time after which an idle event stream gets a comment, so that proxies and
+/// browsers keep it open.
+const KEEP_ALIVE: Duration = Duration::from_secs(15);
+
+/// Time a client has to send its request, or to take a response, before the
+/// connection is closed.
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Size of the longest request line and headers read.
+const MAX_REQUEST_SIZE: u64 = 8 * 1024;
+
+#[derive(Debug, Clone)]
+struct Participant {
+    name: String,
+    team: Team,
+    race_number: u8,
+    nationality: Nationality,
+}
+
+/// The fields of the Lap Data shown by the overlays.
+#[derive(Debug, Clone, Copy)]
+struct Timing {
+    position: u8,
+    lap: u8,
+    last_lap_time: f32,
+    best_lap_time: f32,
+    current_lap_time: f32,
+    sector: u8,
+    sector_1_time: f32,
+    sector_2_time: f32,
+    in_pits: bool,
+    penalties: u8,
+    status: ResultStatus,
+}
+
+impl Timing {
+    fn new(ld: &LapData) -> Timing {
+        Timing {
+            position: ld.car_position(),
+            lap: ld.current_lap_num(),
+            last_lap_time: ld.last_lap_time(),
+            best_lap_time: ld.best_lap_time(),
+            current_lap_time: ld.current_lap_time(),
+            sector: ld.sector(),
+            sector_1_time: ld.sector_1_time(),
+            sector_2_time: ld.sector_2_time(),
+            in_pits: ld.pit_status() != PitStatus::None,
+            penalties: ld.penalties(),
+            status: ld.result_status(),
+        }
+    }
+
+    /// Whether the driver is taking part in the session, and has a position.
+    fn is_classified(&self) -> bool {
+        self.position > 0 && !matches!(self.status, ResultStatus::Invalid | ResultStatus::Inactive)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Tyres {
+    compound: TyreCompoundVisual,
+    wear: f32,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    session: Option<SessionIdentifier>,
+    session_time: f32,
+    total_laps: Option<u8>,
+    participants: Vec<Participant>,
+    timing: Vec<Timing>,
+    tyres: Vec<Option<Tyres>>,
+    gaps: GapTracker,
+    flags: Vec<Flag>,
+    safety_car: Option<SafetyCar>,
+    subscribers: Vec<(u64, Sender<String>)>,
+    next_subscriber: u64,
+}
+
+impl State {
+    fn update(&mut self, packet: &Packet) {
+        match packet {
+            Packet::Session(s) => {
+                self.check_session(s.header().session_id());
+                self.total_laps = Some(s.total_laps());
+                self.update_flags(s);
+            }
+            Packet::Participants(p) => {
+                self.participants = p
+                    .participants()
+                    .iter()
+                    .map(|p| Participant {
+                        name: p.name().clone(),
+                        team: p.team(),
+                        race_number: p.race_number(),
+                        nationality: p.nationality(),
+                    })
+                    .collect();
+            }
+            Packet::Lap(l) => self.update_timing(l),
+            Packet::CarStatus(s) => {
+                self.tyres.resize(s.car_status_data().len(), None);
+                for (tyres, cs) in self.tyres.iter_mut().zip(s.car_status_data()) {
+                    let wear = cs.tyres_wear().to_array();
+                    *tyres = Some(Tyres {
+                        compound: cs.visual_tyre_compound(),
+                        wear: wear.iter().map(|w| f32::from(*w)).fold(0.0, f32::max),
+                    });
+                }
+            }
+            Packet::CarDamage(d) => {
+                for (tyres, cd) in self.tyres.iter_mut().zip(d.car_damage_data()) {
+                    if let Some(tyres) = tyres {
+                        tyres.wear = cd
+                            .tyres_wear()
+                            .to_array()
+                            .iter()
+                            .copied()
+                            .fold(0.0, f32::max);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn update_timing(&mut self, packet: &PacketLapData) {
+        self.check_session(packet.header().session_id());
+        self.session_time = packet.header().session_time();
+        self.gaps.update(packet);
+
+        let timing: Vec<Timing> = packet.lap_data().iter().map(Timing::new).collect();
+        let mut events = Vec::new();
+        if timing.len() == self.timing.len() {
+            for (idx, (before, now)) in self.timing.iter().zip(&timing).enumerate() {
+                if !before.is_classified() || !now.is_classified() {
+                    continue;
+                }
+
+                if now.position != before.position {
+                    let data = format!(
+                        "{{\"index\":{},\"from\":{},\"to\":{}}}",
+                        idx, before.position, now.position
+                    );
+                    events.push(("position", data));
+                }
+                if now.lap == before.lap + 1 && before.lap > 0 {
+                    let data = format!(
+                        "{{\"index\":{},\"lap\":{},\"time\":{},\"position\":{}}}",
+                        idx,
+                        before.lap,
+                        json_float(Some(now.last_lap_time).filter(|t| *t > 0.0), 3),
+                        now.position
+                    );
+                    events.push(("lap", data));
+                }
+            }
+        }
+
+        self.timing = timing;
+        self.broadcast(events);
+    }
+
+    fn update_flags(&mut self, packet: &PacketSessionData) {
+        let flags: Vec<Flag> = packet
+            .active_marshal_zones()
+            .iter()
+            .map(|z| z.zone_flag())
+            .collect();
+        let mut events = Vec::new();
+        if flags.len() == self.flags.len() {
+            for (zone, (before, now)) in self.flags.iter().zip(&flags).enumerate() {
+                if before != now {
+                    let data = format!("{{\"zone\":{},\"flag\":\"{:?}\"}}", zone, now);
+                    events.push(("flag", data));
+                }
+            }
+        }
+        self.flags = flags;
+
+        let safety_car = packet.safety_car_status();
+        if self.safety_car.is_some_and(|sc| sc != safety_car) {
+            let data = format!("{{\"status\":\"{:?}\"}}", safety_car);
+            events.push(("safety_car", data));
+        }
+        self.safety_car = Some(safety_car);
+        self.broadcast(events);
+    }
+
+    /// Forget the previous session, keeping the event streams open.
+    fn check_session(&mut self, session: SessionIdentifier) {
+        if self.session != Some(session) {
+            self.session = Some(session);
+            self.total_laps = None;
+            self.timing.clear();
+            self.tyres.clear();
+            self.flags.clear();
+            self.safety_car = None;
+        }
+    }
+
+    /// Send the events to the open streams, dropping the closed ones.
+    fn broadcast(&mut self, events: Vec<(&str, String)>) {
+        for (event, data) in events {
+            let message = format!("event: {}\ndata: {}\n\n", event, data);
+            self.subscribers
+                .retain(|(_, s)| s.send(message.clone()).is_ok());
+        }
+    }
+
+    fn standings_json(&self) -> String {
+        let mut drivers: Vec<(usize, &Timing)> = self
+            .timing
+            .iter()
+            .enumerate()
+            .filter(|(_, t)| t.is_classified())
+            .collect();
+        drivers.sort_by_key(|(_, t)| t.position);
+
+        // The interval of each driver is to the car classified ahead of them.
+        let mut ahead: Option<u8> = None;
+        let standings: Vec<String> = drivers
+            .into_iter()
+            .map(|(idx, timing)| {
+                let idx = idx as u8;
+                let interval = ahead.and_then(|a| self.gaps.interval(a, idx));
+                ahead = Some(idx);
+                self.driver_json(idx, timing, interval)
+            })
+            .collect();
+
+        format!(
+            "{{\"session_time\":{},\"total_laps\":{},\"standings\":[{}]}}",
+            json_float(Some(self.session_time), 3),
+            json_option(self.total_laps),
+            standings.join(",")
+        )
+    }
+
+    fn driver_by_index_json(&self, idx: u8) -> Option<String> {
+        let timing = self
+            .timing
+            .get(idx as usize)
+            .filter(|t| t.is_classified())?;
+
+        let ahead = self
+            .timing
+            .iter()
+            .position(|other| other.is_classified() && other.position + 1 == timing.position);
+        let interval = ahead.and_then(|a| self.gaps.interval(a as u8, idx));
+
+        Some(self.driver_json(idx, timing, interval))
+    }
+
+    fn driver_json(&self, idx: u8, timing: &Timing, interval: Option<f32>) -> String {
+        let participant = self.participants.get(idx as usize);
+        let tyres = self.tyres.get(idx as usize).copied().flatten();
+
+        let mut out = String::new();
+        let _ = write!(
+            out,
+            "{{\"index\":{},\"position\":{},\"name\":{},\"team\":{},\"race_number\":{},\
+             \"country\":{},\"flag\":{},\"lap\":{},\"gap_to_leader\":{},\"interval\":{},\
+             \"last_lap\":{},\"best_lap\":{},\"current_lap_time\":{},\"sector\":{},\
+             \"sector_1_time\":{},\"sector_2_time\":{},\"in_pits\":{},\"penalties\":{},\
+             \"status\":\"{:?}\",\"tyres\":",
+            idx,
+            timing.position,
+            participant
+                .map(|p| json_string(&p.name))
+                .unwrap_or_else(|| String::from("null")),
+            participant
+                .map(|p| json_string(&format!("{:?}", p.team)))
+                .unwrap_or_else(|| String::from("null")),
+            json_option(participant.map(|p| p.race_number)),
+            participant
+                .and_then(|p| p.nationality.country_code())
+                .map(json_string)
+                .unwrap_or_else(|| String::from("null")),
+            participant
+                .and_then(|p| p.nationality.flag())
+                .map(|f| json_string(&f))
+                .unwrap_or_else(|| String::from("null")),
+            timing.lap,
+            json_float(self.gaps.gap_to_leader(idx), 3),
+            json_float(interval, 3),
+            json_float(Some(timing.last_lap_time).filter(|t| *t > 0.0), 3),
+            json_float(Some(timing.best_lap_time).filter(|t| *t > 0.0), 3),
+            json_float(Some(timing.current_lap_time), 3),
+            timing.sector,
+            json_float(Some(timing.sector_1_time).filter(|t| *t > 0.0), 3),
+            json_float(Some(timing.sector_2_time).filter(|t| *t > 0.0), 3),
+            timing.in_pits,
+            timing.penalties,
+            timing.status,
+        );
+
+        match tyres {
+            Some(tyres) => {
+                let _ = write!(
+                    out,
+                    "{{\"compound\":\"{:?}\",\"wear\":{}}}}}",
+                    tyres.compound,
+                    json_float(Some(tyres.wear), 1)
+                );
+            }
+            None => out.push_str("null}"),
+        }
+
+        out
+    }
+}
+
+/// Standings of a session, fed from its packets and served as JSON.
+///
+/// `Overlay` is cheap to clone: clones share the same state, so one can be
+/// kept by the receive loop while another is served with [`serve`].
+///
+/// [`serve`]: #method.serve
+#[derive(Debug, Clone, Default)]
+pub struct Overlay {
+    state: Arc<Mutex<State>>,
+}
+
+impl Overlay {
+    pub fn new() -> Overlay {
+        Overlay::default()
+    }
+
+    /// Update the standings with the Session, Participants, Lap Data, Car
+    /// Status and Car Damage packets, ignoring the others.
+    pub fn record(&self, packet: &Packet) {
+        self.state.lock().unwrap().update(packet);
+    }
+
+    /// The `/standings` document.
+    pub fn standings_json(&self) -> String {
+        self.state.lock().unwrap().standings_json()
+    }
+
+    /// The `/driver/{index}` document, if the driver is taking part.
+    pub fn driver_json(&self, index: u8) -> Option<String> {
+        self.state.lock().unwrap().driver_by_index_json(index)
+    }
+
+    /// Serve the API over HTTP on `addr`. Overlays on other origins are
+    /// allowed to read it. Each connection is served by a thread of its own,
+    /// so that a slow client or an event stream does not hold the others up.
+    pub fn serve<A: ToSocketAddrs>(&self, addr: A) -> std::io::Result<JoinHandle<()>> {
+        let listener = TcpListener::bind(addr)?;
+        let overlay = self.clone();
+
+        Ok(thread::spawn(move || {
+            for conn in listener.incoming().flatten() {
+                let overlay = overlay.clone();
+                thread::spawn(move || overlay.handle(conn));
+            }
+        }))
+    }
+
+    fn handle(&self, mut conn: TcpStream) {
+        // Clients that stop sending their request, or reading the response,
+        // get disconnected.
+        if conn.set_read_timeout(Some(TIMEOUT)).is_err()
+            || conn.set_write_timeout(Some(TIMEOUT)).is_err()
+        {
+            return;
+        }
+
+        // Keep the request line, and read the headers before answering.
+        let mut reader = BufReader::new((&conn).take(MAX_REQUEST_SIZE));
+        let mut request = String::new();
+        let mut line = String::new();
+        loop {
+            match reader.read_line(&mut line) {
+                Ok(0) => break,
+                Ok(_) if line.trim().is_empty() => break,
+                Ok(_) => {}
+                // Timed out, or sent something else than text.
+                Err(_) => return,
+            }
+            if request.is_empty() {
+                request = line.clone();
+            }
+            line.clear();
+        }
+
+        if is_event_stream(&request) {
+            let (id, events) = self.subscribe();
+
+            // Clients send nothing more on an event stream: the end of the
+            // reads is the client disconnecting, even while no event is sent.
+            if let Ok(mut client) = conn.try_clone() {
+                let overlay = self.clone();
+                thread::spawn(move || {
+                    let _ = client.set_read_timeout(None);
+                    let mut buf = [0; 512];
+                    while let Ok(n) = client.read(&mut buf) {
+                        if n == 0 {
+                            break;
+                        }
+                    }
+                    overlay.unsubscribe(id);
+                });
+            }
+
+            stream_events(&mut conn, events);
+            // The client is gone or too slow, stop queuing its events.
+            self.unsubscribe(id);
+            let _ = conn.shutdown(Shutdown::Both);
+            return;
+        }
+
+        let (status, body) = self.respond(&request);
+        let _ = write!(
+            conn,
+            "HTTP/1.1 {}\r\n\
+             Content-Type: application/json\r\n\
+             Access-Control-Allow-Origin: *\r\n\
+             Cache-Control: no-store\r\n\
+             Content-Length: {}\r\n\
+             Connection: close\r\n\r\n{}",
+            status,
+            body.len(),
+            body
+        );
+    }
+
+    fn subscribe(&self) -> (u64, Receiver<String>) {
+        let (sender, receiver) = mpsc::channel();
+        let mut state = self.state.lock().unwrap();
+        let id = state.next_subscriber;
+        state.next_subscriber += 1;
+        state.subscribers.push((id, sender));
+
+        (id, receiver)
+    }
+
+    fn unsubscribe(&self, id: u64) {
+        self.state
+            .lock()
+            .unwrap()
+            .subscribers
+            .retain(|(subscriber, _)| *subscriber != id);
+    }
+
+    /// Number of the open event streams.
+    pub fn subscribers(&self) -> usize {
+        self.state.lock().unwrap().subscribers.len()
+    }
+
+    fn respond(&self, request: &str) -> (&'static str, String) {
+        let mut parts = request.split_whitespace();
+        let method = parts.next().unwrap_or("");
+        // Overlays add query strings to bust caches.
+        let path = parts.next().unwrap_or("").split('?').next().unwrap_or("");
+
+        if method != "GET" {
+            return (
+                "405 Method Not Allowed",
+                error_json("Only GET is supported"),
+            );
+        }
+
+        let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+        match segments.as_slice() {
+            ["standings"] => ("200 OK", self.standings_json()),
+            ["driver", index] => match index.parse::<u8>().ok().and_then(|i| self.driver_json(i)) {
+                Some(body) => ("200 OK", body),
+                None => ("404 Not Found", error_json("No such driver")),
+            },
+            _ => ("404 Not Found", error_json("No such endpoint")),
+        }
+    }
+}
+
+fn is_event_stream(request: &str) -> bool {
+    let mut parts = request.split_whitespace();
+    let method = parts.next().unwrap_or("");
+    let path = parts.next().unwrap_or("").split('?').next().unwrap_or("");
+
+    method == "GET" && path.trim_end_matches('/') == "/events"
+}
+
+fn stream_events(conn: &mut TcpStream, events: Receiver<String>) {
+    let headers = "HTTP/1.1 200 OK\r\n\
+                   Content-Type: text/event-stream\r\n\
+                   Access-Control-Allow-Origin: *\r\n\
+                   Cache-Control: no-store\r\n\
+                   Connection: keep-alive\r\n\r\n";
+    if conn.write_all(headers.as_bytes()).is_err() {
+        return;
+    }
+
+    loop {
+        let written = match events.recv_timeout(KEEP_ALIVE) {
+            Ok(message) => conn.write_all(message.as_bytes()),
+            Err(RecvTimeoutError::Timeout) => conn.write_all(b": keep-alive\n\n"),
+            Err(RecvTimeoutError::Disconnected) => return,
+        };
+
+        // The client is gone, or stopped reading.
+        if written.is_err() {
+            return;
+        }
+    }
+}
+
+fn error_json(message: &str) -> String {
+    format!("{{\"error\":{}}}", json_string(message))
+}
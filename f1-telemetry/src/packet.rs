@@ -1,31 +1,63 @@
-use std::convert::TryFrom;
-use std::io::Cursor;
-use std::mem;
+use alloc::format;
+use alloc::string::String;
+use core::convert::TryFrom;
 
+use crate::io::Cursor;
+
+use car_damage::PacketCarDamageData;
 use car_setup::PacketCarSetupData;
 use car_status::PacketCarStatusData;
 use car_telemetry::PacketCarTelemetryData;
 use event::PacketEventData;
-use header::PacketHeader;
+use final_classification::PacketFinalClassificationData;
+use header::{PacketHeader, SessionIdentifier};
 use lap::PacketLapData;
+use lobby_info::PacketLobbyInfoData;
 use motion::PacketMotionData;
+use motion_ex::PacketMotionExData;
 use participants::PacketParticipantsData;
+use raw::RawPacket;
 use session::PacketSessionData;
+use time_trial::PacketTimeTrialData;
+use tyre_sets::PacketTyreSetsData;
 
+pub mod car_damage;
 pub mod car_setup;
 pub mod car_status;
 pub mod car_telemetry;
+pub mod encode;
 pub mod event;
+pub mod final_classification;
 pub mod generic;
 pub mod header;
 pub mod lap;
+pub mod lobby_info;
 pub mod motion;
+pub mod motion_ex;
 pub mod participants;
+pub mod raw;
 pub mod session;
+pub mod time_trial;
+pub mod tyre_sets;
+
+mod bytes;
+
+/// Size of the largest packet of the supported formats (Motion in F1 2020 to 2022,
+/// 1464 bytes), rounded up to leave room for slightly larger packets.
+pub const MAX_PACKET_SIZE: usize = 2048;
 
 #[derive(Debug)]
 pub struct UnpackError(pub String);
 
+/// Number of cars sent in the per-car arrays, which went from 20 to 22 in F1 2020.
+pub fn num_cars(packet_format: u16) -> usize {
+    if packet_format >= 2020 {
+        22
+    } else {
+        20
+    }
+}
+
 #[derive(Debug)]
 pub enum Packet {
     Motion(PacketMotionData),
@@ -36,18 +68,98 @@ pub enum Packet {
     CarSetups(PacketCarSetupData),
     CarTelemetry(PacketCarTelemetryData),
     CarStatus(PacketCarStatusData),
+    FinalClassification(PacketFinalClassificationData),
+    LobbyInfo(PacketLobbyInfoData),
+    CarDamage(PacketCarDamageData),
+    TyreSets(PacketTyreSetsData),
+    MotionEx(PacketMotionExData),
+    TimeTrial(PacketTimeTrialData),
+    Raw(RawPacket),
 }
 
-#[derive(Debug)]
-enum PacketType {
-    Motion,
-    Session,
-    LapData,
-    Event,
-    Participants,
-    CarSetups,
-    CarTelemetry,
-    CarStatus,
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum PacketType {
+    Motion = 0,
+    Session = 1,
+    LapData = 2,
+    Event = 3,
+    Participants = 4,
+    CarSetups = 5,
+    CarTelemetry = 6,
+    CarStatus = 7,
+    FinalClassification = 8,
+    LobbyInfo = 9,
+    CarDamage = 10,
+    TyreSets = 12,
+    MotionEx = 13,
+    TimeTrial = 14,
+}
+
+impl PacketType {
+    pub fn id(self) -> u8 {
+        self as u8
+    }
+
+    /// Expected size in bytes of this type of packet, for a given packet format,
+    /// or `None` when the format does not have this type.
+    pub fn size(self, packet_format: u16) -> Option<usize> {
+        let size = match (self, packet_format) {
+            (PacketType::Motion, 2019) => 1343,
+            (PacketType::Motion, 2020..=2022) => 1464,
+            (PacketType::Motion, 2023..=2024) => 1349,
+            (PacketType::Session, 2019) => 149,
+            (PacketType::Session, 2020) => 251,
+            (PacketType::Session, 2021) => 625,
+            (PacketType::Session, 2022) => 632,
+            (PacketType::Session, 2023) => 644,
+            (PacketType::Session, 2024) => 753,
+            (PacketType::LapData, 2019) => 843,
+            (PacketType::LapData, 2020) => 1190,
+            (PacketType::LapData, 2021) => 970,
+            (PacketType::LapData, 2022) => 972,
+            (PacketType::LapData, 2023) => 1131,
+            (PacketType::LapData, 2024) => 1285,
+            (PacketType::Event, 2019) => 32,
+            (PacketType::Event, 2020) => 35,
+            (PacketType::Event, 2021) => 36,
+            (PacketType::Event, 2022) => 40,
+            (PacketType::Event, 2023..=2024) => 45,
+            (PacketType::Participants, 2019) => 1104,
+            (PacketType::Participants, 2020) => 1213,
+            (PacketType::Participants, 2021..=2022) => 1257,
+            (PacketType::Participants, 2023) => 1306,
+            (PacketType::Participants, 2024) => 1350,
+            (PacketType::CarSetups, 2019) => 843,
+            (PacketType::CarSetups, 2020..=2022) => 1102,
+            (PacketType::CarSetups, 2023) => 1107,
+            (PacketType::CarSetups, 2024) => 1133,
+            (PacketType::CarTelemetry, 2019) => 1347,
+            (PacketType::CarTelemetry, 2020) => 1307,
+            (PacketType::CarTelemetry, 2021..=2022) => 1347,
+            (PacketType::CarTelemetry, 2023..=2024) => 1352,
+            (PacketType::CarStatus, 2019) => 1143,
+            (PacketType::CarStatus, 2020) => 1344,
+            (PacketType::CarStatus, 2021..=2022) => 1058,
+            (PacketType::CarStatus, 2023..=2024) => 1239,
+            (PacketType::FinalClassification, 2020..=2021) => 839,
+            (PacketType::FinalClassification, 2022) => 1015,
+            (PacketType::FinalClassification, 2023..=2024) => 1020,
+            (PacketType::LobbyInfo, 2020) => 1169,
+            (PacketType::LobbyInfo, 2021..=2022) => 1191,
+            (PacketType::LobbyInfo, 2023) => 1218,
+            (PacketType::LobbyInfo, 2024) => 1306,
+            (PacketType::CarDamage, 2021) => 882,
+            (PacketType::CarDamage, 2022) => 948,
+            (PacketType::CarDamage, 2023..=2024) => 953,
+            (PacketType::TyreSets, 2023..=2024) => 231,
+            (PacketType::MotionEx, 2023) => 217,
+            (PacketType::MotionEx, 2024) => 237,
+            (PacketType::TimeTrial, 2024) => 101,
+            _ => return None,
+        };
+
+        Some(size)
+    }
 }
 
 impl TryFrom<u8> for PacketType {
@@ -63,66 +175,211 @@ impl TryFrom<u8> for PacketType {
             5 => Ok(PacketType::CarSetups),
             6 => Ok(PacketType::CarTelemetry),
             7 => Ok(PacketType::CarStatus),
+            8 => Ok(PacketType::FinalClassification),
+            9 => Ok(PacketType::LobbyInfo),
+            10 => Ok(PacketType::CarDamage),
+            12 => Ok(PacketType::TyreSets),
+            13 => Ok(PacketType::MotionEx),
+            14 => Ok(PacketType::TimeTrial),
             _ => Err(UnpackError(format!("Invalid PacketType: {}", value))),
         }
     }
 }
 
-pub(crate) fn parse_packet(size: usize, packet: &[u8]) -> Result<Packet, UnpackError> {
-    let header_size = mem::size_of::<PacketHeader>();
+/// Size in bytes of the header of a packet format. F1 2020 added the secondary
+/// player car index, and F1 2023 the game year and overall frame identifier.
+pub fn header_size(packet_format: u16) -> usize {
+    if packet_format >= 2023 {
+        29
+    } else if packet_format >= 2020 {
+        24
+    } else {
+        23
+    }
+}
+
+/// Offset of the `packet_id` field in the header, moved by the `game_year`
+/// field in F1 2023. The `session_uid`, `session_time` and `frame_identifier`
+/// fields follow it at a fixed distance in every format.
+pub(crate) fn packet_id_offset(packet_format: u16) -> usize {
+    if packet_format >= 2023 {
+        6
+    } else {
+        5
+    }
+}
+
+/// Read the packet format straight from the raw bytes, without parsing the header.
+pub fn peek_packet_format(packet: &[u8]) -> Option<u16> {
+    match packet {
+        [a, b, ..] => Some(u16::from_le_bytes([*a, *b])),
+        _ => None,
+    }
+}
+
+/// Read the packet id straight from the raw bytes, without parsing the header.
+pub fn peek_packet_id(packet: &[u8]) -> Option<u8> {
+    let packet_format = peek_packet_format(packet)?;
+    packet.get(packet_id_offset(packet_format)).copied()
+}
+
+/// Read the session identifier straight from the raw bytes, without parsing the header.
+pub fn peek_session_id(packet: &[u8]) -> Option<SessionIdentifier> {
+    let offset = packet_id_offset(peek_packet_format(packet)?) + 1;
+    let bytes = packet.get(offset..offset + 8)?;
+    let mut session_uid = [0; 8];
+    session_uid.copy_from_slice(bytes);
+
+    Some(SessionIdentifier::new(u64::from_le_bytes(session_uid)))
+}
+
+/// Set of packet types to parse. Packets of the other types are dropped by
+/// only looking at their id.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct PacketFilter(u32);
+
+impl PacketFilter {
+    pub fn all() -> PacketFilter {
+        PacketFilter(u32::MAX)
+    }
+
+    pub fn none() -> PacketFilter {
+        PacketFilter(0)
+    }
 
-    if size < header_size {
+    pub fn with(self, packet_type: PacketType) -> PacketFilter {
+        PacketFilter(self.0 | 1 << packet_type.id())
+    }
+
+    pub fn without(self, packet_type: PacketType) -> PacketFilter {
+        PacketFilter(self.0 & !(1 << packet_type.id()))
+    }
+
+    pub fn accepts(self, packet_type: PacketType) -> bool {
+        self.accepts_id(packet_type.id())
+    }
+
+    /// Whether packets with this id should be parsed. Unknown ids are only
+    /// accepted when all packets are, so that they are reported as errors.
+    pub fn accepts_id(self, packet_id: u8) -> bool {
+        if packet_id >= 32 {
+            return self == PacketFilter::all();
+        }
+
+        self.0 & (1 << packet_id) != 0
+    }
+}
+
+impl Default for PacketFilter {
+    fn default() -> Self {
+        PacketFilter::all()
+    }
+}
+
+/// Parse a packet from untrusted bytes, e.g. fuzzer inputs or received
+/// datagrams. Unlike the other parsers, this never panics: packets shorter than
+/// the layout of their type are reported as errors.
+pub fn parse_packet_fuzz(data: &[u8]) -> Result<Packet, UnpackError> {
+    // The parsers read the whole layout of a packet, so they are given a buffer
+    // long enough for any of them and the bytes actually read are checked after.
+    let mut padded = [0; MAX_PACKET_SIZE];
+    let buf = if data.len() < MAX_PACKET_SIZE {
+        padded[..data.len()].copy_from_slice(data);
+        &padded[..]
+    } else {
+        data
+    };
+
+    let mut cursor = Cursor::new(buf);
+    let packet = parse_from_cursor(&mut cursor)?;
+
+    let read = cursor.position() as usize;
+    if read > data.len() {
         return Err(UnpackError(format!(
-            "Invalid packet: too small ({} bytes)",
-            size
+            "Invalid packet: too small ({} bytes, expected {})",
+            data.len(),
+            read
         )));
     }
 
-    let mut cursor = Cursor::new(packet);
-    let header = PacketHeader::new(&mut cursor);
+    Ok(packet)
+}
+
+fn parse_from_cursor(cursor: &mut Cursor<&[u8]>) -> Result<Packet, UnpackError> {
+    let header = PacketHeader::new(cursor);
 
     let packet_id: PacketType = PacketType::try_from(header.packet_id())?;
 
     match packet_id {
         PacketType::Motion => {
-            let packet = PacketMotionData::new(&mut cursor, header)?;
+            let packet = PacketMotionData::new(cursor, header)?;
 
             Ok(Packet::Motion(packet))
         }
         PacketType::Session => {
-            let packet = PacketSessionData::new(&mut cursor, header)?;
+            let packet = PacketSessionData::new(cursor, header)?;
 
             Ok(Packet::Session(packet))
         }
         PacketType::LapData => {
-            let packet = PacketLapData::new(&mut cursor, header)?;
+            let packet = PacketLapData::new(cursor, header)?;
 
             Ok(Packet::Lap(packet))
         }
         PacketType::Event => {
-            let packet = PacketEventData::new(&mut cursor, header)?;
+            let packet = PacketEventData::new(cursor, header)?;
 
             Ok(Packet::Event(packet))
         }
         PacketType::Participants => {
-            let packet = PacketParticipantsData::new(&mut cursor, header)?;
+            let packet = PacketParticipantsData::new(cursor, header)?;
 
             Ok(Packet::Participants(packet))
         }
         PacketType::CarSetups => {
-            let packet = PacketCarSetupData::new(&mut cursor, header)?;
+            let packet = PacketCarSetupData::new(cursor, header)?;
 
             Ok(Packet::CarSetups(packet))
         }
         PacketType::CarTelemetry => {
-            let packet = PacketCarTelemetryData::new(&mut cursor, header)?;
+            let packet = PacketCarTelemetryData::new(cursor, header)?;
 
             Ok(Packet::CarTelemetry(packet))
         }
         PacketType::CarStatus => {
-            let packet = PacketCarStatusData::new(&mut cursor, header)?;
+            let packet = PacketCarStatusData::new(cursor, header)?;
 
             Ok(Packet::CarStatus(packet))
         }
+        PacketType::FinalClassification => {
+            let packet = PacketFinalClassificationData::new(cursor, header)?;
+
+            Ok(Packet::FinalClassification(packet))
+        }
+        PacketType::LobbyInfo => {
+            let packet = PacketLobbyInfoData::new(cursor, header)?;
+
+            Ok(Packet::LobbyInfo(packet))
+        }
+        PacketType::CarDamage => {
+            let packet = PacketCarDamageData::new(cursor, header)?;
+
+            Ok(Packet::CarDamage(packet))
+        }
+        PacketType::TyreSets => {
+            let packet = PacketTyreSetsData::new(cursor, header)?;
+
+            Ok(Packet::TyreSets(packet))
+        }
+        PacketType::MotionEx => {
+            let packet = PacketMotionExData::new(cursor, header)?;
+
+            Ok(Packet::MotionEx(packet))
+        }
+        PacketType::TimeTrial => {
+            let packet = PacketTimeTrialData::new(cursor, header)?;
+
+            Ok(Packet::TimeTrial(packet))
+        }
     }
 }
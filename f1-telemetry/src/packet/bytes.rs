@@ -0,0 +1,62 @@
+use core::convert::TryInto;
+
+use crate::io::BufRead;
+
+/// Little endian decoder over a fixed-size block of a packet.
+///
+/// The hot parsers read a whole block (e.g. the motion data of a car) with a
+/// single call to the reader, then decode its fields from the slice with
+/// `from_le_bytes` rather than going through the reader for every field.
+pub(crate) struct LeBytes<'a> {
+    buf: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> LeBytes<'a> {
+    #[inline]
+    pub(crate) fn new(buf: &'a [u8]) -> LeBytes<'a> {
+        LeBytes { buf, pos: 0 }
+    }
+
+    #[inline]
+    fn take<const N: usize>(&mut self) -> [u8; N] {
+        let bytes = self.buf[self.pos..self.pos + N].try_into().unwrap();
+        self.pos += N;
+        bytes
+    }
+
+    #[inline]
+    pub(crate) fn u8(&mut self) -> u8 {
+        let value = self.buf[self.pos];
+        self.pos += 1;
+        value
+    }
+
+    #[inline]
+    pub(crate) fn i8(&mut self) -> i8 {
+        self.u8() as i8
+    }
+
+    #[inline]
+    pub(crate) fn u16(&mut self) -> u16 {
+        u16::from_le_bytes(self.take())
+    }
+
+    #[inline]
+    pub(crate) fn i16(&mut self) -> i16 {
+        i16::from_le_bytes(self.take())
+    }
+
+    #[inline]
+    pub(crate) fn f32(&mut self) -> f32 {
+        f32::from_le_bytes(self.take())
+    }
+}
+
+/// Read the next `N` bytes of a packet in one call.
+#[inline]
+pub(crate) fn read_block<T: BufRead, const N: usize>(reader: &mut T) -> [u8; N] {
+    let mut buf = [0u8; N];
+    reader.read_exact(&mut buf).unwrap();
+    buf
+}
@@ -0,0 +1,249 @@
+use alloc::vec::Vec;
+use byteorder::LittleEndian;
+use getset::{CopyGetters, Getters};
+
+use super::header::PacketHeader;
+use crate::io::{self, BufRead, ReadBytesExt, Write, WriteBytesExt};
+use crate::packet::encode::Encode;
+use crate::packet::generic::WheelData;
+use crate::packet::{num_cars, UnpackError};
+use crate::units::Percent;
+
+/// This type is used for the 22-element `car_damage_data` array of the [`PacketCarDamageData`] type.
+///
+/// Damage and wear values are percentages.
+///
+/// Size: 39 bytes (F1 2021), 42 bytes (F1 2022+)
+///
+/// ## Specification
+/// ```text
+/// tyres_wear:              Tyre wear (percentage)
+/// tyres_damage:            Tyre damage (percentage)
+/// brakes_damage:           Brakes damage (percentage)
+/// front_left_wing_damage:  Front left wing damage (percentage)
+/// front_right_wing_damage: Front right wing damage (percentage)
+/// rear_wing_damage:        Rear wing damage (percentage)
+/// floor_damage:            Floor damage (percentage)
+/// diffuser_damage:         Diffuser damage (percentage)
+/// sidepod_damage:          Sidepod damage (percentage)
+/// drs_fault:               Indicator for DRS fault
+/// ers_fault:               Indicator for ERS fault (F1 2022+)
+/// gear_box_damage:         Gear box damage (percentage)
+/// engine_damage:           Engine damage (percentage)
+/// engine_mguh_wear:        Engine wear MGU-H (percentage)
+/// engine_es_wear:          Engine wear ES (percentage)
+/// engine_ce_wear:          Engine wear CE (percentage)
+/// engine_ice_wear:         Engine wear ICE (percentage)
+/// engine_mguk_wear:        Engine wear MGU-K (percentage)
+/// engine_tc_wear:          Engine wear TC (percentage)
+/// engine_blown:            Engine blown (F1 2022+)
+/// engine_seized:           Engine seized (F1 2022+)
+/// ```
+///
+/// [`PacketCarDamageData`]: ./struct.PacketCarDamageData.html
+#[derive(Debug, Clone, Copy, CopyGetters)]
+#[getset(get_copy = "pub")]
+pub struct CarDamageData {
+    tyres_wear: WheelData<f32>,
+    tyres_damage: WheelData<u8>,
+    brakes_damage: WheelData<u8>,
+    front_left_wing_damage: u8,
+    front_right_wing_damage: u8,
+    rear_wing_damage: u8,
+    floor_damage: u8,
+    diffuser_damage: u8,
+    sidepod_damage: u8,
+    drs_fault: bool,
+    ers_fault: bool,
+    gear_box_damage: u8,
+    engine_damage: u8,
+    engine_mguh_wear: u8,
+    engine_es_wear: u8,
+    engine_ce_wear: u8,
+    engine_ice_wear: u8,
+    engine_mguk_wear: u8,
+    engine_tc_wear: u8,
+    engine_blown: bool,
+    engine_seized: bool,
+}
+
+impl CarDamageData {
+    pub fn new<T: BufRead>(
+        reader: &mut T,
+        packet_format: u16,
+    ) -> Result<CarDamageData, UnpackError> {
+        let tyres_wear = WheelData::new(
+            reader.read_f32::<LittleEndian>().unwrap(),
+            reader.read_f32::<LittleEndian>().unwrap(),
+            reader.read_f32::<LittleEndian>().unwrap(),
+            reader.read_f32::<LittleEndian>().unwrap(),
+        );
+        let tyres_damage = WheelData::new(
+            reader.read_u8().unwrap(),
+            reader.read_u8().unwrap(),
+            reader.read_u8().unwrap(),
+            reader.read_u8().unwrap(),
+        );
+        let brakes_damage = WheelData::new(
+            reader.read_u8().unwrap(),
+            reader.read_u8().unwrap(),
+            reader.read_u8().unwrap(),
+            reader.read_u8().unwrap(),
+        );
+        let front_left_wing_damage = reader.read_u8().unwrap();
+        let front_right_wing_damage = reader.read_u8().unwrap();
+        let rear_wing_damage = reader.read_u8().unwrap();
+        let floor_damage = reader.read_u8().unwrap();
+        let diffuser_damage = reader.read_u8().unwrap();
+        let sidepod_damage = reader.read_u8().unwrap();
+        let drs_fault = reader.read_u8().unwrap() == 1;
+        let ers_fault = if packet_format >= 2022 {
+            reader.read_u8().unwrap() == 1
+        } else {
+            false
+        };
+        let gear_box_damage = reader.read_u8().unwrap();
+        let engine_damage = reader.read_u8().unwrap();
+        let engine_mguh_wear = reader.read_u8().unwrap();
+        let engine_es_wear = reader.read_u8().unwrap();
+        let engine_ce_wear = reader.read_u8().unwrap();
+        let engine_ice_wear = reader.read_u8().unwrap();
+        let engine_mguk_wear = reader.read_u8().unwrap();
+        let engine_tc_wear = reader.read_u8().unwrap();
+        let (engine_blown, engine_seized) = if packet_format >= 2022 {
+            (
+                reader.read_u8().unwrap() == 1,
+                reader.read_u8().unwrap() == 1,
+            )
+        } else {
+            (false, false)
+        };
+
+        Ok(CarDamageData {
+            tyres_wear,
+            tyres_damage,
+            brakes_damage,
+            front_left_wing_damage,
+            front_right_wing_damage,
+            rear_wing_damage,
+            floor_damage,
+            diffuser_damage,
+            sidepod_damage,
+            drs_fault,
+            ers_fault,
+            gear_box_damage,
+            engine_damage,
+            engine_mguh_wear,
+            engine_es_wear,
+            engine_ce_wear,
+            engine_ice_wear,
+            engine_mguk_wear,
+            engine_tc_wear,
+            engine_blown,
+            engine_seized,
+        })
+    }
+
+    pub(crate) fn encode<W: Write>(&self, writer: &mut W, packet_format: u16) -> io::Result<()> {
+        for v in self.tyres_wear.to_array().iter() {
+            writer.write_f32::<LittleEndian>(*v)?;
+        }
+        for v in self.tyres_damage.to_array().iter() {
+            writer.write_u8(*v)?;
+        }
+        for v in self.brakes_damage.to_array().iter() {
+            writer.write_u8(*v)?;
+        }
+        writer.write_u8(self.front_left_wing_damage)?;
+        writer.write_u8(self.front_right_wing_damage)?;
+        writer.write_u8(self.rear_wing_damage)?;
+        writer.write_u8(self.floor_damage)?;
+        writer.write_u8(self.diffuser_damage)?;
+        writer.write_u8(self.sidepod_damage)?;
+        writer.write_u8(self.drs_fault as u8)?;
+        if packet_format >= 2022 {
+            writer.write_u8(self.ers_fault as u8)?;
+        }
+        writer.write_u8(self.gear_box_damage)?;
+        writer.write_u8(self.engine_damage)?;
+        writer.write_u8(self.engine_mguh_wear)?;
+        writer.write_u8(self.engine_es_wear)?;
+        writer.write_u8(self.engine_ce_wear)?;
+        writer.write_u8(self.engine_ice_wear)?;
+        writer.write_u8(self.engine_mguk_wear)?;
+        writer.write_u8(self.engine_tc_wear)?;
+        if packet_format >= 2022 {
+            writer.write_u8(self.engine_blown as u8)?;
+            writer.write_u8(self.engine_seized as u8)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn tyres_wear_percent(&self) -> WheelData<Percent> {
+        self.tyres_wear.map(Percent)
+    }
+
+    pub fn tyres_damage_percent(&self) -> WheelData<Percent> {
+        self.tyres_damage.map(|d| Percent(f32::from(d)))
+    }
+}
+
+/// This packet details car damage parameters for all the cars in the race (F1 2021+).
+///
+/// Frequency: 2 per second
+///
+/// Size: 882 bytes (F1 2021), 948 bytes (F1 2022), 953 bytes (F1 2023+)
+///
+/// Version: 1
+///
+/// ## Specification
+/// ```text
+/// header:          Header
+/// car_damage_data: List of car damage data (22)
+/// ```
+#[derive(Debug, Getters)]
+#[getset(get = "pub")]
+pub struct PacketCarDamageData {
+    header: PacketHeader,
+    car_damage_data: Vec<CarDamageData>,
+}
+
+impl PacketCarDamageData {
+    pub fn new<T: BufRead>(
+        mut reader: &mut T,
+        header: PacketHeader,
+    ) -> Result<PacketCarDamageData, UnpackError> {
+        let packet_format = header.packet_format();
+        let num_cars = num_cars(packet_format);
+
+        let mut car_damage_data = Vec::with_capacity(num_cars);
+        for _ in 0..num_cars {
+            let cdd = CarDamageData::new(&mut reader, packet_format)?;
+            car_damage_data.push(cdd);
+        }
+
+        Ok(PacketCarDamageData {
+            header,
+            car_damage_data,
+        })
+    }
+
+    /// Damage of the player's car.
+    pub fn player_car_damage(&self) -> Option<&CarDamageData> {
+        self.car_damage_data
+            .get(self.header.player_car_index() as usize)
+    }
+}
+
+impl Encode for PacketCarDamageData {
+    fn encode<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        let packet_format = self.header.packet_format();
+        self.header.encode(writer)?;
+        for data in &self.car_damage_data {
+            data.encode(writer, packet_format)?;
+        }
+
+        Ok(())
+    }
+}
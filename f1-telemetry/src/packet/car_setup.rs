@@ -1,37 +1,54 @@
-use byteorder::{LittleEndian, ReadBytesExt};
+use alloc::vec::Vec;
+use byteorder::LittleEndian;
 use getset::{CopyGetters, Getters};
-use std::convert::TryFrom;
-use std::io::BufRead;
 
 use super::header::PacketHeader;
-use crate::packet::UnpackError;
+use crate::io::{self, BufRead, ReadBytesExt, Write, WriteBytesExt};
+use crate::packet::encode::Encode;
+use crate::packet::generic::WheelData;
+use crate::packet::{num_cars, UnpackError};
+use crate::units::Psi;
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
 pub enum TractionControl {
     Off,
     Low,
     High,
+    Unknown(u8),
 }
 
-impl TryFrom<u8> for TractionControl {
-    type Error = UnpackError;
+impl From<u8> for TractionControl {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => TractionControl::Off,
+            1 => TractionControl::Low,
+            2 => TractionControl::High,
+            _ => TractionControl::Unknown(value),
+        }
+    }
+}
 
-    fn try_from(value: u8) -> Result<Self, Self::Error> {
+impl From<TractionControl> for u8 {
+    fn from(value: TractionControl) -> Self {
         match value {
-            0 => Ok(TractionControl::Off),
-            1 => Ok(TractionControl::Low),
-            2 => Ok(TractionControl::High),
-            _ => Err(UnpackError(format!(
-                "Invalid TractionControl value: {}",
-                value
-            ))),
+            TractionControl::Off => 0,
+            TractionControl::Low => 1,
+            TractionControl::High => 2,
+            TractionControl::Unknown(value) => value,
         }
     }
 }
 
-/// This type is used for the 20-element `car_setups` array of the [`PacketCarSetupData`] type.
+/// This type is used for the 20-element (22 from F1 2020) `car_setups` array of the
+/// [`PacketCarSetupData`] type.
+///
+/// Up to F1 2019, a single tyre pressure is sent for each axle. It is used for
+/// both wheels of the axle.
+///
+/// Size: 41 bytes (F1 2019), 49 bytes (F1 2020-2023), 50 bytes (F1 2024+)
 ///
 /// ## Specification
+/// ```text
 /// front_wing              Front wing aero
 /// rear_wing               Rear wing aero
 /// on_throttle             Differential adjustment on throttle (percentage)
@@ -48,10 +65,11 @@ impl TryFrom<u8> for TractionControl {
 /// rear_suspension_height  Rear ride height
 /// brake_pressure          Brake pressure (percentage)
 /// brake_bias              Brake bias (percentage)
-/// front_tyre_pressure     Front tyre pressure (PSI)
-/// rear_tyre_pressure      Rear tyre pressure (PSI)
+/// engine_braking          Engine braking (percentage, F1 2024+)
+/// tyres_pressure          Tyre pressures (PSI)
 /// ballast                 Ballast
 /// fuel_load               Fuel load
+/// ```
 ///
 /// [`PacketCarSetupData`]: ./struct.CarSetupData.html
 #[derive(Debug, CopyGetters)]
@@ -73,14 +91,17 @@ pub struct CarSetupData {
     rear_suspension_height: u8,
     brake_pressure: u8,
     brake_bias: u8,
-    front_tyre_pressure: f32,
-    rear_tyre_pressure: f32,
+    engine_braking: Option<u8>,
+    tyres_pressure: WheelData<f32>,
     ballast: u8,
     fuel_load: f32,
 }
 
 impl CarSetupData {
-    pub fn new<T: BufRead>(reader: &mut T) -> Result<CarSetupData, UnpackError> {
+    pub fn new<T: BufRead>(
+        reader: &mut T,
+        packet_format: u16,
+    ) -> Result<CarSetupData, UnpackError> {
         let front_wing = reader.read_u8().unwrap();
         let rear_wing = reader.read_u8().unwrap();
         let on_throttle = reader.read_u8().unwrap();
@@ -97,8 +118,23 @@ impl CarSetupData {
         let rear_suspension_height = reader.read_u8().unwrap();
         let brake_pressure = reader.read_u8().unwrap();
         let brake_bias = reader.read_u8().unwrap();
-        let front_tyre_pressure = reader.read_f32::<LittleEndian>().unwrap();
-        let rear_tyre_pressure = reader.read_f32::<LittleEndian>().unwrap();
+        let engine_braking = if packet_format >= 2024 {
+            Some(reader.read_u8().unwrap())
+        } else {
+            None
+        };
+        let tyres_pressure = if packet_format >= 2020 {
+            WheelData::new(
+                reader.read_f32::<LittleEndian>().unwrap(),
+                reader.read_f32::<LittleEndian>().unwrap(),
+                reader.read_f32::<LittleEndian>().unwrap(),
+                reader.read_f32::<LittleEndian>().unwrap(),
+            )
+        } else {
+            let front = reader.read_f32::<LittleEndian>().unwrap();
+            let rear = reader.read_f32::<LittleEndian>().unwrap();
+            WheelData::new(rear, rear, front, front)
+        };
         let ballast = reader.read_u8().unwrap();
         let fuel_load = reader.read_f32::<LittleEndian>().unwrap();
 
@@ -119,12 +155,64 @@ impl CarSetupData {
             rear_suspension_height,
             brake_pressure,
             brake_bias,
-            front_tyre_pressure,
-            rear_tyre_pressure,
+            engine_braking,
+            tyres_pressure,
             ballast,
             fuel_load,
         })
     }
+
+    pub(crate) fn encode<W: Write>(&self, writer: &mut W, packet_format: u16) -> io::Result<()> {
+        writer.write_u8(self.front_wing)?;
+        writer.write_u8(self.rear_wing)?;
+        writer.write_u8(self.on_throttle)?;
+        writer.write_u8(self.off_throttle)?;
+        writer.write_f32::<LittleEndian>(self.front_camber)?;
+        writer.write_f32::<LittleEndian>(self.rear_camber)?;
+        writer.write_f32::<LittleEndian>(self.front_toe)?;
+        writer.write_f32::<LittleEndian>(self.rear_toe)?;
+        writer.write_u8(self.front_suspension)?;
+        writer.write_u8(self.rear_suspension)?;
+        writer.write_u8(self.front_anti_roll_bar)?;
+        writer.write_u8(self.rear_anti_roll_bar)?;
+        writer.write_u8(self.front_suspension_height)?;
+        writer.write_u8(self.rear_suspension_height)?;
+        writer.write_u8(self.brake_pressure)?;
+        writer.write_u8(self.brake_bias)?;
+        if packet_format >= 2024 {
+            writer.write_u8(self.engine_braking.unwrap_or(0))?;
+        }
+        if packet_format >= 2020 {
+            for v in self.tyres_pressure.to_array().iter() {
+                writer.write_f32::<LittleEndian>(*v)?;
+            }
+        } else {
+            writer.write_f32::<LittleEndian>(self.tyres_pressure.front_left())?;
+            writer.write_f32::<LittleEndian>(self.tyres_pressure.rear_left())?;
+        }
+        writer.write_u8(self.ballast)?;
+        writer.write_f32::<LittleEndian>(self.fuel_load)?;
+
+        Ok(())
+    }
+
+    /// Average pressure of the front tyres (PSI).
+    pub fn front_tyre_pressure(&self) -> f32 {
+        (self.tyres_pressure.front_left() + self.tyres_pressure.front_right()) / 2.0
+    }
+
+    /// Average pressure of the rear tyres (PSI).
+    pub fn rear_tyre_pressure(&self) -> f32 {
+        (self.tyres_pressure.rear_left() + self.tyres_pressure.rear_right()) / 2.0
+    }
+
+    pub fn front_tyre_pressure_psi(&self) -> Psi {
+        Psi(self.front_tyre_pressure())
+    }
+
+    pub fn rear_tyre_pressure_psi(&self) -> Psi {
+        Psi(self.rear_tyre_pressure())
+    }
 }
 
 /// This packet details the car setups for each vehicle in the session.
@@ -133,20 +221,26 @@ impl CarSetupData {
 ///
 /// Frequency: 2 per second
 ///
-/// Size: 843 bytes
+/// Size: 843 bytes (F1 2019), 1102 bytes (F1 2020-2022), 1107 bytes (F1 2023),
+/// 1133 bytes (F1 2024)
 ///
 /// Version: 1
 ///
 /// ## Specification
 /// ```text
-/// header:     Header
-/// car_setups: List of car setups (20)
+/// header:                Header
+/// car_setups:            List of car setups (20, 22 from F1 2020)
+/// next_front_wing_value: Value of front wing after next pit stop - player only
+///                        (F1 2024+)
 /// ```
-#[derive(Debug, Getters)]
-#[getset(get = "pub")]
+#[derive(Debug, CopyGetters, Getters)]
 pub struct PacketCarSetupData {
+    #[getset(get = "pub")]
     header: PacketHeader,
+    #[getset(get = "pub")]
     car_setups: Vec<CarSetupData>,
+    #[getset(get_copy = "pub")]
+    next_front_wing_value: Option<f32>,
 }
 
 impl PacketCarSetupData {
@@ -154,12 +248,41 @@ impl PacketCarSetupData {
         mut reader: &mut T,
         header: PacketHeader,
     ) -> Result<PacketCarSetupData, UnpackError> {
-        let mut car_setups = Vec::with_capacity(20);
-        for _ in 0..20 {
-            let csd = CarSetupData::new(&mut reader)?;
+        let packet_format = header.packet_format();
+        let num_cars = num_cars(packet_format);
+
+        let mut car_setups = Vec::with_capacity(num_cars);
+        for _ in 0..num_cars {
+            let csd = CarSetupData::new(&mut reader, packet_format)?;
             car_setups.push(csd);
         }
 
-        Ok(PacketCarSetupData { header, car_setups })
+        let next_front_wing_value = if packet_format >= 2024 {
+            Some(reader.read_f32::<LittleEndian>().unwrap())
+        } else {
+            None
+        };
+
+        Ok(PacketCarSetupData {
+            header,
+            car_setups,
+            next_front_wing_value,
+        })
+    }
+}
+
+impl Encode for PacketCarSetupData {
+    fn encode<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.header.encode(writer)?;
+        let packet_format = self.header.packet_format();
+        for data in &self.car_setups {
+            data.encode(writer, packet_format)?;
+        }
+
+        if packet_format >= 2024 {
+            writer.write_f32::<LittleEndian>(self.next_front_wing_value.unwrap_or(0.0))?;
+        }
+
+        Ok(())
     }
 }
@@ -1,31 +1,39 @@
-use byteorder::{LittleEndian, ReadBytesExt};
+use alloc::vec::Vec;
+use byteorder::LittleEndian;
 use getset::{CopyGetters, Getters};
-use std::convert::TryFrom;
-use std::io::BufRead;
 
-use super::header::PacketHeader;
+use super::header::{PacketHeader, Player};
+use crate::io::{self, BufRead, ReadBytesExt, Write, WriteBytesExt};
+use crate::packet::encode::Encode;
 use crate::packet::generic::{Flag, WheelData};
-use crate::packet::UnpackError;
+use crate::packet::{num_cars, UnpackError};
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
 pub enum TractionControl {
     Off,
     Low,
     High,
+    Unknown(u8),
 }
 
-impl TryFrom<u8> for TractionControl {
-    type Error = UnpackError;
+impl From<u8> for TractionControl {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => TractionControl::Off,
+            1 => TractionControl::Low,
+            2 => TractionControl::High,
+            _ => TractionControl::Unknown(value),
+        }
+    }
+}
 
-    fn try_from(value: u8) -> Result<Self, Self::Error> {
+impl From<TractionControl> for u8 {
+    fn from(value: TractionControl) -> Self {
         match value {
-            0 => Ok(TractionControl::Off),
-            1 => Ok(TractionControl::Low),
-            2 => Ok(TractionControl::High),
-            _ => Err(UnpackError(format!(
-                "Invalid TractionControl value: {}",
-                value
-            ))),
+            TractionControl::Off => 0,
+            TractionControl::Low => 1,
+            TractionControl::High => 2,
+            TractionControl::Unknown(value) => value,
         }
     }
 }
@@ -36,18 +44,29 @@ pub enum FuelMix {
     Standard,
     Rich,
     Max,
+    Unknown(u8),
 }
 
-impl TryFrom<u8> for FuelMix {
-    type Error = UnpackError;
+impl From<u8> for FuelMix {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => FuelMix::Lean,
+            1 => FuelMix::Standard,
+            2 => FuelMix::Rich,
+            3 => FuelMix::Max,
+            _ => FuelMix::Unknown(value),
+        }
+    }
+}
 
-    fn try_from(value: u8) -> Result<Self, Self::Error> {
+impl From<FuelMix> for u8 {
+    fn from(value: FuelMix) -> Self {
         match value {
-            0 => Ok(FuelMix::Lean),
-            1 => Ok(FuelMix::Standard),
-            2 => Ok(FuelMix::Rich),
-            3 => Ok(FuelMix::Max),
-            _ => Err(UnpackError(format!("Invalid FuelMix value: {}", value))),
+            FuelMix::Lean => 0,
+            FuelMix::Standard => 1,
+            FuelMix::Rich => 2,
+            FuelMix::Max => 3,
+            FuelMix::Unknown(value) => value,
         }
     }
 }
@@ -56,22 +75,34 @@ impl TryFrom<u8> for FuelMix {
 pub enum DRS {
     NotAllowed,
     Allowed,
-    Unknown,
+    Unknown(i8),
 }
 
-impl TryFrom<i8> for DRS {
-    type Error = UnpackError;
+impl From<i8> for DRS {
+    fn from(value: i8) -> Self {
+        match value {
+            0 => DRS::NotAllowed,
+            1 => DRS::Allowed,
+            _ => DRS::Unknown(value),
+        }
+    }
+}
 
-    fn try_from(value: i8) -> Result<Self, Self::Error> {
+impl From<DRS> for i8 {
+    fn from(value: DRS) -> Self {
         match value {
-            0 => Ok(DRS::NotAllowed),
-            1 => Ok(DRS::Allowed),
-            -1 => Ok(DRS::Unknown),
-            _ => Err(UnpackError(format!("Invalid DRS value: {}", value))),
+            DRS::NotAllowed => 0,
+            DRS::Allowed => 1,
+            DRS::Unknown(value) => value,
         }
     }
 }
 
+/// Actual compound of a tyre, as chosen by Pirelli for the race weekend.
+///
+/// The dry compounds of Formula 1 range from C0 (hardest) to C5 (softest), the
+/// visual compound of the weekend telling which of them are soft, medium and
+/// hard.
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
 pub enum TyreCompound {
     C5,
@@ -79,6 +110,7 @@ pub enum TyreCompound {
     C3,
     C2,
     C1,
+    C0,
     Inter,
     Wet,
     ClassicDry,
@@ -88,35 +120,109 @@ pub enum TyreCompound {
     F2Medium,
     F2Hard,
     F2Wet,
+    Unknown(u8),
 }
 
-impl TryFrom<u8> for TyreCompound {
-    type Error = UnpackError;
+impl From<u8> for TyreCompound {
+    fn from(value: u8) -> Self {
+        match value {
+            16 => TyreCompound::C5,
+            17 => TyreCompound::C4,
+            18 => TyreCompound::C3,
+            19 => TyreCompound::C2,
+            20 => TyreCompound::C1,
+            21 => TyreCompound::C0,
+            7 => TyreCompound::Inter,
+            8 => TyreCompound::Wet,
+            9 => TyreCompound::ClassicDry,
+            10 => TyreCompound::ClassicWet,
+            11 => TyreCompound::F2SuperSoft,
+            12 => TyreCompound::F2Soft,
+            13 => TyreCompound::F2Medium,
+            14 => TyreCompound::F2Hard,
+            15 => TyreCompound::F2Wet,
+            _ => TyreCompound::Unknown(value),
+        }
+    }
+}
+
+impl From<TyreCompound> for u8 {
+    fn from(value: TyreCompound) -> Self {
+        match value {
+            TyreCompound::C5 => 16,
+            TyreCompound::C4 => 17,
+            TyreCompound::C3 => 18,
+            TyreCompound::C2 => 19,
+            TyreCompound::C1 => 20,
+            TyreCompound::C0 => 21,
+            TyreCompound::Inter => 7,
+            TyreCompound::Wet => 8,
+            TyreCompound::ClassicDry => 9,
+            TyreCompound::ClassicWet => 10,
+            TyreCompound::F2SuperSoft => 11,
+            TyreCompound::F2Soft => 12,
+            TyreCompound::F2Medium => 13,
+            TyreCompound::F2Hard => 14,
+            TyreCompound::F2Wet => 15,
+            TyreCompound::Unknown(value) => value,
+        }
+    }
+}
 
-    fn try_from(value: u8) -> Result<Self, Self::Error> {
+impl TyreCompound {
+    /// Decode an actual compound for a given packet format. C0 was added in
+    /// F1 2023, the value it uses being unknown before.
+    pub fn from_format(value: u8, packet_format: u16) -> TyreCompound {
         match value {
-            16 => Ok(TyreCompound::C5),
-            17 => Ok(TyreCompound::C4),
-            18 => Ok(TyreCompound::C3),
-            19 => Ok(TyreCompound::C2),
-            20 => Ok(TyreCompound::C1),
-            7 => Ok(TyreCompound::Inter),
-            8 => Ok(TyreCompound::Wet),
-            9 => Ok(TyreCompound::ClassicDry),
-            10 => Ok(TyreCompound::ClassicWet),
-            11 => Ok(TyreCompound::F2SuperSoft),
-            12 => Ok(TyreCompound::F2Soft),
-            13 => Ok(TyreCompound::F2Medium),
-            14 => Ok(TyreCompound::F2Hard),
-            15 => Ok(TyreCompound::F2Wet),
-            _ => Err(UnpackError(format!(
-                "Invalid TyreCompound value: {}",
-                value
-            ))),
+            21 if packet_format < 2023 => TyreCompound::Unknown(value),
+            _ => TyreCompound::from(value),
         }
     }
+
+    /// Encode an actual compound for a given packet format, the inverse of
+    /// [`from_format`].
+    ///
+    /// [`from_format`]: #method.from_format
+    pub fn to_format(self, _packet_format: u16) -> u8 {
+        u8::from(self)
+    }
+
+    /// Name of the compound, e.g. `"C3"`.
+    pub fn name(self) -> &'static str {
+        match self {
+            TyreCompound::C5 => "C5",
+            TyreCompound::C4 => "C4",
+            TyreCompound::C3 => "C3",
+            TyreCompound::C2 => "C2",
+            TyreCompound::C1 => "C1",
+            TyreCompound::C0 => "C0",
+            TyreCompound::Inter => "Intermediate",
+            TyreCompound::Wet => "Wet",
+            TyreCompound::ClassicDry => "Classic Dry",
+            TyreCompound::ClassicWet => "Classic Wet",
+            TyreCompound::F2SuperSoft => "F2 Super Soft",
+            TyreCompound::F2Soft => "F2 Soft",
+            TyreCompound::F2Medium => "F2 Medium",
+            TyreCompound::F2Hard => "F2 Hard",
+            TyreCompound::F2Wet => "F2 Wet",
+            TyreCompound::Unknown(_) => "Unknown",
+        }
+    }
+
+    /// Whether the compound is for a wet track.
+    pub fn is_wet(self) -> bool {
+        matches!(
+            self,
+            TyreCompound::Inter
+                | TyreCompound::Wet
+                | TyreCompound::ClassicWet
+                | TyreCompound::F2Wet
+        )
+    }
 }
 
+/// Compound of a tyre as shown on the TV graphics: soft, medium or hard,
+/// whatever the actual compound is.
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
 pub enum TyreCompoundVisual {
     Soft,
@@ -131,29 +237,143 @@ pub enum TyreCompoundVisual {
     F2Medium,
     F2Hard,
     F2Wet,
+    Unknown(u8),
+}
+
+impl From<u8> for TyreCompoundVisual {
+    fn from(value: u8) -> Self {
+        match value {
+            16 => TyreCompoundVisual::Soft,
+            17 => TyreCompoundVisual::Medium,
+            18 => TyreCompoundVisual::Hard,
+            7 => TyreCompoundVisual::Inter,
+            8 => TyreCompoundVisual::Wet,
+            9 => TyreCompoundVisual::ClassicDry,
+            10 => TyreCompoundVisual::ClassicWet,
+            11 => TyreCompoundVisual::F2SuperSoft,
+            12 => TyreCompoundVisual::F2Soft,
+            13 => TyreCompoundVisual::F2Medium,
+            14 => TyreCompoundVisual::F2Hard,
+            15 => TyreCompoundVisual::F2Wet,
+            _ => TyreCompoundVisual::Unknown(value),
+        }
+    }
 }
 
-impl TryFrom<u8> for TyreCompoundVisual {
-    type Error = UnpackError;
+impl From<TyreCompoundVisual> for u8 {
+    fn from(value: TyreCompoundVisual) -> Self {
+        match value {
+            TyreCompoundVisual::Soft => 16,
+            TyreCompoundVisual::Medium => 17,
+            TyreCompoundVisual::Hard => 18,
+            TyreCompoundVisual::Inter => 7,
+            TyreCompoundVisual::Wet => 8,
+            TyreCompoundVisual::ClassicDry => 9,
+            TyreCompoundVisual::ClassicWet => 10,
+            TyreCompoundVisual::F2SuperSoft => 11,
+            TyreCompoundVisual::F2Soft => 12,
+            TyreCompoundVisual::F2Medium => 13,
+            TyreCompoundVisual::F2Hard => 14,
+            TyreCompoundVisual::F2Wet => 15,
+            TyreCompoundVisual::Unknown(value) => value,
+        }
+    }
+}
+
+impl TyreCompoundVisual {
+    /// Decode a visual compound for a given packet format. From F1 2020, the
+    /// F2 compounds are sent as 19 (super soft) to 22 (hard) instead of 11 to
+    /// 14.
+    pub fn from_format(value: u8, packet_format: u16) -> TyreCompoundVisual {
+        if packet_format < 2020 {
+            return TyreCompoundVisual::from(value);
+        }
 
-    fn try_from(value: u8) -> Result<Self, Self::Error> {
         match value {
-            16 => Ok(TyreCompoundVisual::Soft),
-            17 => Ok(TyreCompoundVisual::Medium),
-            18 => Ok(TyreCompoundVisual::Hard),
-            7 => Ok(TyreCompoundVisual::Inter),
-            8 => Ok(TyreCompoundVisual::Wet),
-            9 => Ok(TyreCompoundVisual::ClassicDry),
-            10 => Ok(TyreCompoundVisual::ClassicWet),
-            11 => Ok(TyreCompoundVisual::F2SuperSoft),
-            12 => Ok(TyreCompoundVisual::F2Soft),
-            13 => Ok(TyreCompoundVisual::F2Medium),
-            14 => Ok(TyreCompoundVisual::F2Hard),
-            15 => Ok(TyreCompoundVisual::F2Wet),
-            _ => Err(UnpackError(format!(
-                "Invalid TyreCompoundVisual value: {}",
-                value
-            ))),
+            19 => TyreCompoundVisual::F2SuperSoft,
+            20 => TyreCompoundVisual::F2Soft,
+            21 => TyreCompoundVisual::F2Medium,
+            22 => TyreCompoundVisual::F2Hard,
+            11..=14 => TyreCompoundVisual::Unknown(value),
+            _ => TyreCompoundVisual::from(value),
+        }
+    }
+
+    /// Encode a visual compound for a given packet format, the inverse of
+    /// [`from_format`].
+    ///
+    /// [`from_format`]: #method.from_format
+    pub fn to_format(self, packet_format: u16) -> u8 {
+        if packet_format < 2020 {
+            return u8::from(self);
+        }
+
+        match self {
+            TyreCompoundVisual::F2SuperSoft => 19,
+            TyreCompoundVisual::F2Soft => 20,
+            TyreCompoundVisual::F2Medium => 21,
+            TyreCompoundVisual::F2Hard => 22,
+            _ => u8::from(self),
+        }
+    }
+
+    /// Name of the compound, e.g. `"Soft"`.
+    pub fn name(self) -> &'static str {
+        match self {
+            TyreCompoundVisual::Soft => "Soft",
+            TyreCompoundVisual::Medium => "Medium",
+            TyreCompoundVisual::Hard => "Hard",
+            TyreCompoundVisual::Inter => "Intermediate",
+            TyreCompoundVisual::Wet => "Wet",
+            TyreCompoundVisual::ClassicDry => "Classic Dry",
+            TyreCompoundVisual::ClassicWet => "Classic Wet",
+            TyreCompoundVisual::F2SuperSoft => "F2 Super Soft",
+            TyreCompoundVisual::F2Soft => "F2 Soft",
+            TyreCompoundVisual::F2Medium => "F2 Medium",
+            TyreCompoundVisual::F2Hard => "F2 Hard",
+            TyreCompoundVisual::F2Wet => "F2 Wet",
+            TyreCompoundVisual::Unknown(_) => "Unknown",
+        }
+    }
+
+    /// Letter of the compound, as shown on the TV graphics.
+    pub fn abbreviation(self) -> &'static str {
+        match self {
+            TyreCompoundVisual::Soft
+            | TyreCompoundVisual::F2SuperSoft
+            | TyreCompoundVisual::F2Soft => "S",
+            TyreCompoundVisual::Medium | TyreCompoundVisual::F2Medium => "M",
+            TyreCompoundVisual::Hard | TyreCompoundVisual::F2Hard => "H",
+            TyreCompoundVisual::Inter => "I",
+            TyreCompoundVisual::Wet
+            | TyreCompoundVisual::ClassicWet
+            | TyreCompoundVisual::F2Wet => "W",
+            TyreCompoundVisual::ClassicDry => "D",
+            TyreCompoundVisual::Unknown(_) => "?",
+        }
+    }
+
+    /// Colour of the sidewall of the compound, in RGB.
+    ///
+    /// ```
+    /// use f1_telemetry::packet::car_status::TyreCompoundVisual;
+    ///
+    /// let compound = TyreCompoundVisual::from_format(20, 2020);
+    /// assert_eq!(compound, TyreCompoundVisual::F2Soft);
+    /// assert_eq!(compound.color(), Some((255, 210, 0)));
+    /// ```
+    pub fn color(self) -> Option<(u8, u8, u8)> {
+        match self {
+            TyreCompoundVisual::Soft | TyreCompoundVisual::F2SuperSoft => Some((218, 41, 28)),
+            TyreCompoundVisual::Medium | TyreCompoundVisual::F2Soft => Some((255, 210, 0)),
+            TyreCompoundVisual::Hard | TyreCompoundVisual::F2Medium => Some((240, 240, 240)),
+            TyreCompoundVisual::F2Hard => Some((255, 135, 0)),
+            TyreCompoundVisual::Inter => Some((67, 176, 42)),
+            TyreCompoundVisual::Wet
+            | TyreCompoundVisual::ClassicWet
+            | TyreCompoundVisual::F2Wet => Some((0, 103, 173)),
+            TyreCompoundVisual::ClassicDry => Some((40, 40, 40)),
+            TyreCompoundVisual::Unknown(_) => None,
         }
     }
 }
@@ -166,56 +386,78 @@ pub enum ERSDeployMode {
     High,
     Overtake,
     Hotlap,
+    Unknown(u8),
 }
 
-impl TryFrom<u8> for ERSDeployMode {
-    type Error = UnpackError;
+impl From<u8> for ERSDeployMode {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => ERSDeployMode::None,
+            1 => ERSDeployMode::Low,
+            2 => ERSDeployMode::Medium,
+            3 => ERSDeployMode::High,
+            4 => ERSDeployMode::Overtake,
+            5 => ERSDeployMode::Hotlap,
+            _ => ERSDeployMode::Unknown(value),
+        }
+    }
+}
 
-    fn try_from(value: u8) -> Result<Self, Self::Error> {
+impl From<ERSDeployMode> for u8 {
+    fn from(value: ERSDeployMode) -> Self {
         match value {
-            0 => Ok(ERSDeployMode::None),
-            1 => Ok(ERSDeployMode::Low),
-            2 => Ok(ERSDeployMode::Medium),
-            3 => Ok(ERSDeployMode::High),
-            4 => Ok(ERSDeployMode::Overtake),
-            5 => Ok(ERSDeployMode::Hotlap),
-            _ => Err(UnpackError(format!(
-                "Invalid ERSDeployMode value: {}",
-                value
-            ))),
+            ERSDeployMode::None => 0,
+            ERSDeployMode::Low => 1,
+            ERSDeployMode::Medium => 2,
+            ERSDeployMode::High => 3,
+            ERSDeployMode::Overtake => 4,
+            ERSDeployMode::Hotlap => 5,
+            ERSDeployMode::Unknown(value) => value,
         }
     }
 }
 
-/// This type is used for the 20-element `car_status_data` array of the [`PacketCarStatusData`] type.
+/// This type is used for the 20-element (22 from F1 2020) `car_status_data` array of the
+/// [`PacketCarStatusData`] type.
 ///
 /// There is some data in the Car Status packets that you may not want other players seeing if you are in a multiplayer game.
 /// This is controlled by the "Your Telemetry" setting in the Telemetry options. The options are:
 ///
-///     Restricted (Default) – other players viewing the UDP data will not see values for your car;
-///     Public – all other players can see all the data for your car.
+/// ```text
+/// Restricted (Default) – other players viewing the UDP data will not see values for your car;
+/// Public – all other players can see all the data for your car.
+/// ```
 ///
 /// Note: You can always see the data for the car you are driving regardless of the setting.
 ///
 /// The following data items are set to zero if the player driving the car in question has their "Your Telemetry" set to "Restricted":
 ///
-///     fuelInTank
-///     fuelCapacity
-///     fuelMix
-///     fuelRemainingLaps
-///     frontBrakeBias
-///     frontLeftWingDamage
-///     frontRightWingDamage
-///     rearWingDamage
-///     engineDamage
-///     gearBoxDamage
-///     tyresWear (All four wheels)
-///     tyresDamage (All four wheels)
-///     ersDeployMode
-///     ersStoreEnergy
-///     ersDeployedThisLap
-///     ersHarvestedThisLapMGUK
-///     ersHarvestedThisLapMGUH
+/// ```text
+/// fuelInTank
+/// fuelCapacity
+/// fuelMix
+/// fuelRemainingLaps
+/// frontBrakeBias
+/// frontLeftWingDamage
+/// frontRightWingDamage
+/// rearWingDamage
+/// engineDamage
+/// gearBoxDamage
+/// tyresWear (All four wheels)
+/// tyresDamage (All four wheels)
+/// ersDeployMode
+/// ersStoreEnergy
+/// ersDeployedThisLap
+/// ersHarvestedThisLapMGUK
+/// ersHarvestedThisLapMGUH
+/// ```
+///
+/// F1 2021 moved the wear and damage to the car damage packet: they are zero
+/// from that format onwards. The other fields only sent in some formats are
+/// `None` in the others.
+///
+/// Size: 56 bytes (F1 2019), 60 bytes (F1 2020), 47 bytes (F1 2021-2022),
+/// 55 bytes (F1 2023+)
 ///
 /// ## Specification
 /// ```text
@@ -231,30 +473,39 @@ impl TryFrom<u8> for ERSDeployMode {
 /// idle_rpm:                    cars idle RPM
 /// max_gears:                   maximum number of gears
 /// drs_allowed:                 0 = not allowed, 1 = allowed, -1 = unknown
-/// tyres_wear:                  tyre wear percentage
+/// drs_activation_distance:     0 = DRS not available, non-zero - DRS will be available
+///                              in [X] metres (F1 2020+)
+/// tyres_wear:                  tyre wear percentage (up to F1 2020)
 /// actual_tyre_compound:        f1 modern - 16 = c5, 17 = c4, 18 = c3, 19 = c2, 20 = c1
-///                              7 = inter, 8 = wet
+///                              21 = c0 (F1 2023+), 7 = inter, 8 = wet
 ///                              f1 classic - 9 = dry, 10 = wet
 ///                              f2 – 11 = super soft, 12 = soft, 13 = medium, 14 = hard
 ///                              15 = wet
 /// tyre_visual_compound:        f1 visual (can be different from actual compound)
 ///                              16 = soft, 17 = medium, 18 = hard, 7 = inter, 8 = wet
 ///                              f1 classic – same as above
-///                              f2 – same as above
-/// tyres_damage:                tyre damage (percentage)
-/// front_left_wing_damage:      front left wing damage (percentage)
-/// front_right_wing_damage:     front right wing damage (percentage)
-/// rear_wing_damage:            rear wing damage (percentage)
-/// engine_damage:               engine damage (percentage)
-/// gear_box_damage:             gear box damage (percentage)
+///                              f2 – same as actual compound (F1 2019)
+///                              f2 – 19 = super soft, 20 = soft, 21 = medium
+///                              22 = hard, 15 = wet (F1 2020+)
+/// tyres_age_laps:              age in laps of the current set of tyres (F1 2020+)
+/// tyres_damage:                tyre damage (percentage, up to F1 2020)
+/// front_left_wing_damage:      front left wing damage (percentage, up to F1 2020)
+/// front_right_wing_damage:     front right wing damage (percentage, up to F1 2020)
+/// rear_wing_damage:            rear wing damage (percentage, up to F1 2020)
+/// drs_fault:                   0 = OK, 1 = fault (F1 2020)
+/// engine_damage:               engine damage (percentage, up to F1 2020)
+/// gear_box_damage:             gear box damage (percentage, up to F1 2020)
 /// vehicle_fia_flags:           -1 = invalid/unknown, 0 = none, 1 = green
 ///                              2 = blue, 3 = yellow, 4 = red
+/// engine_power_ice:            engine power output of ICE in watts (F1 2023+)
+/// engine_power_mguk:           engine power output of MGU-K in watts (F1 2023+)
 /// ers_store_energy:            ERS energy store in joules
 /// ers_deploy_mode:             ERS deployment mode, 0 = none, 1 = low, 2 = medium
 ///                              3 = high, 4 = overtake, 5 = hotlap
 /// ers_harvested_this_lap_mguk: ERS energy harvested this lap by MGU-k
 /// ers_harvested_this_lap_mguh: ERS energy harvested this lap by MGU-h
 /// ers_deployed_this_lap:       ERS energy deployed this lap
+/// network_paused:              whether the car is paused in a network game (F1 2021+)
 /// ```
 ///
 /// [`PacketCarStatusData`]: ./struct.CarStatusData.html
@@ -273,28 +524,37 @@ pub struct CarStatusData {
     idle_rpm: u16,
     max_gears: u8,
     drs_allowed: DRS,
+    drs_activation_distance: Option<u16>,
     tyres_wear: WheelData<u8>,
     actual_tyre_compound: TyreCompound,
     visual_tyre_compound: TyreCompoundVisual,
+    tyres_age_laps: Option<u8>,
     tyres_damage: WheelData<u8>,
     front_left_wing_damage: u8,
     front_right_wing_damage: u8,
     rear_wing_damage: u8,
+    drs_fault: Option<bool>,
     engine_damage: u8,
     gear_box_damage: u8,
     vehicle_fia_flags: Flag,
+    engine_power_ice: Option<f32>,
+    engine_power_mguk: Option<f32>,
     ers_store_energy: f32,
     ers_deploy_mode: ERSDeployMode,
     ers_harvested_this_lap_mguk: f32,
     ers_harvested_this_lap_mguh: f32,
     ers_deployed_this_lap: f32,
+    network_paused: Option<bool>,
 }
 
 impl CarStatusData {
-    pub fn new<T: BufRead>(reader: &mut T) -> Result<CarStatusData, UnpackError> {
-        let traction_control = TractionControl::try_from(reader.read_u8().unwrap())?;
+    pub fn new<T: BufRead>(
+        reader: &mut T,
+        packet_format: u16,
+    ) -> Result<CarStatusData, UnpackError> {
+        let traction_control = TractionControl::from(reader.read_u8().unwrap());
         let anti_lock_brakes = reader.read_u8().unwrap() == 1;
-        let fuel_mix = FuelMix::try_from(reader.read_u8().unwrap())?;
+        let fuel_mix = FuelMix::from(reader.read_u8().unwrap());
         let front_brake_bias = reader.read_u8().unwrap();
         let pit_limiter = reader.read_u8().unwrap() == 1;
         let fuel_in_tank = reader.read_f32::<LittleEndian>().unwrap();
@@ -303,32 +563,66 @@ impl CarStatusData {
         let max_rpm = reader.read_u16::<LittleEndian>().unwrap();
         let idle_rpm = reader.read_u16::<LittleEndian>().unwrap();
         let max_gears = reader.read_u8().unwrap();
-        let drs_allowed = DRS::try_from(reader.read_i8().unwrap())?;
-        let tyres_wear = WheelData::new(
-            reader.read_u8().unwrap(),
-            reader.read_u8().unwrap(),
-            reader.read_u8().unwrap(),
-            reader.read_u8().unwrap(),
-        );
-        let actual_tyre_compound = TyreCompound::try_from(reader.read_u8().unwrap())?;
-        let visual_tyre_compound = TyreCompoundVisual::try_from(reader.read_u8().unwrap())?;
-        let tyres_damage = WheelData::new(
-            reader.read_u8().unwrap(),
-            reader.read_u8().unwrap(),
-            reader.read_u8().unwrap(),
-            reader.read_u8().unwrap(),
-        );
-        let front_left_wing_damage = reader.read_u8().unwrap();
-        let front_right_wing_damage = reader.read_u8().unwrap();
-        let rear_wing_damage = reader.read_u8().unwrap();
-        let engine_damage = reader.read_u8().unwrap();
-        let gear_box_damage = reader.read_u8().unwrap();
-        let vehicle_fia_flags = Flag::try_from(reader.read_i8().unwrap())?;
+        let drs_allowed = DRS::from(reader.read_i8().unwrap());
+        let drs_activation_distance = if packet_format >= 2020 {
+            Some(reader.read_u16::<LittleEndian>().unwrap())
+        } else {
+            None
+        };
+        let has_damage = packet_format < 2021;
+        let tyres_wear = if has_damage {
+            read_wheel_data(reader)
+        } else {
+            WheelData::new(0, 0, 0, 0)
+        };
+        let actual_tyre_compound =
+            TyreCompound::from_format(reader.read_u8().unwrap(), packet_format);
+        let visual_tyre_compound =
+            TyreCompoundVisual::from_format(reader.read_u8().unwrap(), packet_format);
+        let tyres_age_laps = if packet_format >= 2020 {
+            Some(reader.read_u8().unwrap())
+        } else {
+            None
+        };
+
+        let mut tyres_damage = WheelData::new(0, 0, 0, 0);
+        let mut front_left_wing_damage = 0;
+        let mut front_right_wing_damage = 0;
+        let mut rear_wing_damage = 0;
+        let mut drs_fault = None;
+        let mut engine_damage = 0;
+        let mut gear_box_damage = 0;
+        if has_damage {
+            tyres_damage = read_wheel_data(reader);
+            front_left_wing_damage = reader.read_u8().unwrap();
+            front_right_wing_damage = reader.read_u8().unwrap();
+            rear_wing_damage = reader.read_u8().unwrap();
+            if packet_format >= 2020 {
+                drs_fault = Some(reader.read_u8().unwrap() == 1);
+            }
+            engine_damage = reader.read_u8().unwrap();
+            gear_box_damage = reader.read_u8().unwrap();
+        }
+
+        let vehicle_fia_flags = Flag::from(reader.read_i8().unwrap());
+        let (engine_power_ice, engine_power_mguk) = if packet_format >= 2023 {
+            (
+                Some(reader.read_f32::<LittleEndian>().unwrap()),
+                Some(reader.read_f32::<LittleEndian>().unwrap()),
+            )
+        } else {
+            (None, None)
+        };
         let ers_store_energy = reader.read_f32::<LittleEndian>().unwrap();
-        let ers_deploy_mode = ERSDeployMode::try_from(reader.read_u8().unwrap())?;
+        let ers_deploy_mode = ERSDeployMode::from(reader.read_u8().unwrap());
         let ers_harvested_this_lap_mguk = reader.read_f32::<LittleEndian>().unwrap();
         let ers_harvested_this_lap_mguh = reader.read_f32::<LittleEndian>().unwrap();
         let ers_deployed_this_lap = reader.read_f32::<LittleEndian>().unwrap();
+        let network_paused = if packet_format >= 2021 {
+            Some(reader.read_u8().unwrap() == 1)
+        } else {
+            None
+        };
 
         Ok(CarStatusData {
             traction_control,
@@ -343,37 +637,110 @@ impl CarStatusData {
             idle_rpm,
             max_gears,
             drs_allowed,
+            drs_activation_distance,
             tyres_wear,
             actual_tyre_compound,
             visual_tyre_compound,
+            tyres_age_laps,
             tyres_damage,
             front_left_wing_damage,
             front_right_wing_damage,
             rear_wing_damage,
+            drs_fault,
             engine_damage,
             gear_box_damage,
             vehicle_fia_flags,
+            engine_power_ice,
+            engine_power_mguk,
             ers_store_energy,
             ers_deploy_mode,
             ers_harvested_this_lap_mguk,
             ers_harvested_this_lap_mguh,
             ers_deployed_this_lap,
+            network_paused,
         })
     }
+
+    pub(crate) fn encode<W: Write>(&self, writer: &mut W, packet_format: u16) -> io::Result<()> {
+        writer.write_u8(self.traction_control.into())?;
+        writer.write_u8(self.anti_lock_brakes as u8)?;
+        writer.write_u8(self.fuel_mix.into())?;
+        writer.write_u8(self.front_brake_bias)?;
+        writer.write_u8(self.pit_limiter as u8)?;
+        writer.write_f32::<LittleEndian>(self.fuel_in_tank)?;
+        writer.write_f32::<LittleEndian>(self.fuel_capacity)?;
+        writer.write_f32::<LittleEndian>(self.fuel_remaining_laps)?;
+        writer.write_u16::<LittleEndian>(self.max_rpm)?;
+        writer.write_u16::<LittleEndian>(self.idle_rpm)?;
+        writer.write_u8(self.max_gears)?;
+        writer.write_i8(self.drs_allowed.into())?;
+        if packet_format >= 2020 {
+            writer.write_u16::<LittleEndian>(self.drs_activation_distance.unwrap_or(0))?;
+        }
+        let has_damage = packet_format < 2021;
+        if has_damage {
+            for v in self.tyres_wear.to_array().iter() {
+                writer.write_u8(*v)?;
+            }
+        }
+        writer.write_u8(self.actual_tyre_compound.to_format(packet_format))?;
+        writer.write_u8(self.visual_tyre_compound.to_format(packet_format))?;
+        if packet_format >= 2020 {
+            writer.write_u8(self.tyres_age_laps.unwrap_or(0))?;
+        }
+        if has_damage {
+            for v in self.tyres_damage.to_array().iter() {
+                writer.write_u8(*v)?;
+            }
+            writer.write_u8(self.front_left_wing_damage)?;
+            writer.write_u8(self.front_right_wing_damage)?;
+            writer.write_u8(self.rear_wing_damage)?;
+            if packet_format >= 2020 {
+                writer.write_u8(self.drs_fault.unwrap_or(false) as u8)?;
+            }
+            writer.write_u8(self.engine_damage)?;
+            writer.write_u8(self.gear_box_damage)?;
+        }
+        writer.write_i8(self.vehicle_fia_flags.into())?;
+        if packet_format >= 2023 {
+            writer.write_f32::<LittleEndian>(self.engine_power_ice.unwrap_or(0.0))?;
+            writer.write_f32::<LittleEndian>(self.engine_power_mguk.unwrap_or(0.0))?;
+        }
+        writer.write_f32::<LittleEndian>(self.ers_store_energy)?;
+        writer.write_u8(self.ers_deploy_mode.into())?;
+        writer.write_f32::<LittleEndian>(self.ers_harvested_this_lap_mguk)?;
+        writer.write_f32::<LittleEndian>(self.ers_harvested_this_lap_mguh)?;
+        writer.write_f32::<LittleEndian>(self.ers_deployed_this_lap)?;
+        if packet_format >= 2021 {
+            writer.write_u8(self.network_paused.unwrap_or(false) as u8)?;
+        }
+
+        Ok(())
+    }
+}
+
+fn read_wheel_data<T: BufRead>(reader: &mut T) -> WheelData<u8> {
+    WheelData::new(
+        reader.read_u8().unwrap(),
+        reader.read_u8().unwrap(),
+        reader.read_u8().unwrap(),
+        reader.read_u8().unwrap(),
+    )
 }
 
 /// This packet details car statuses for all the cars in the race. It includes values such as the damage readings on the car.
 ///
 /// Frequency: Rate as specified in menus
 ///
-/// Size: 1143 bytes
+/// Size: 1143 bytes (F1 2019), 1344 bytes (F1 2020), 1058 bytes (F1 2021-2022),
+/// 1239 bytes (F1 2023+)
 ///
 /// Version: 1
 ///
 /// ## Specification
 /// ```text
 /// header:          Header
-/// car_status_data: List of cars (20)
+/// car_status_data: List of cars (20, 22 from F1 2020)
 /// ```
 #[derive(Debug, Getters)]
 #[getset(get = "pub")]
@@ -387,9 +754,10 @@ impl PacketCarStatusData {
         mut reader: &mut T,
         header: PacketHeader,
     ) -> Result<PacketCarStatusData, UnpackError> {
-        let mut car_status_data = Vec::with_capacity(20);
-        for _ in 0..20 {
-            let csd = CarStatusData::new(&mut reader)?;
+        let num_cars = num_cars(header.packet_format());
+        let mut car_status_data = Vec::with_capacity(num_cars);
+        for _ in 0..num_cars {
+            let csd = CarStatusData::new(&mut reader, header.packet_format())?;
             car_status_data.push(csd);
         }
 
@@ -398,4 +766,49 @@ impl PacketCarStatusData {
             car_status_data,
         })
     }
+
+    /// Status of a player's car, if they are in the session.
+    pub fn player_car_status(&self, player: Player) -> Option<&CarStatusData> {
+        let idx = self.header.car_index(player)?;
+        self.car_status_data.get(idx as usize)
+    }
+
+    /// Floating point values of each car, by index and field name, e.g. to
+    /// check them.
+    #[cfg(feature = "std")]
+    pub(crate) fn floats_mut(&mut self) -> impl Iterator<Item = (usize, &'static str, &mut f32)> {
+        self.car_status_data
+            .iter_mut()
+            .enumerate()
+            .flat_map(|(i, s)| {
+                vec![
+                    (i, "fuel_in_tank", &mut s.fuel_in_tank),
+                    (i, "fuel_capacity", &mut s.fuel_capacity),
+                    (i, "fuel_remaining_laps", &mut s.fuel_remaining_laps),
+                    (i, "ers_store_energy", &mut s.ers_store_energy),
+                    (
+                        i,
+                        "ers_harvested_this_lap_mguk",
+                        &mut s.ers_harvested_this_lap_mguk,
+                    ),
+                    (
+                        i,
+                        "ers_harvested_this_lap_mguh",
+                        &mut s.ers_harvested_this_lap_mguh,
+                    ),
+                    (i, "ers_deployed_this_lap", &mut s.ers_deployed_this_lap),
+                ]
+            })
+    }
+}
+
+impl Encode for PacketCarStatusData {
+    fn encode<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.header.encode(writer)?;
+        for data in &self.car_status_data {
+            data.encode(writer, self.header.packet_format())?;
+        }
+
+        Ok(())
+    }
 }
@@ -1,11 +1,16 @@
-use byteorder::{LittleEndian, ReadBytesExt};
+use alloc::vec::Vec;
+use byteorder::LittleEndian;
+use core::fmt;
+use core::ops::{BitAnd, BitOr, BitOrAssign, Not};
 use getset::{CopyGetters, Getters};
-use std::convert::TryFrom;
-use std::io::BufRead;
 
 use super::header::PacketHeader;
+use crate::io::{self, BufRead, ReadBytesExt, Write, WriteBytesExt};
+use crate::packet::bytes::{read_block, LeBytes};
+use crate::packet::encode::Encode;
 use crate::packet::generic::WheelData;
-use crate::packet::UnpackError;
+use crate::packet::{num_cars, UnpackError};
+use crate::units::{Celsius, KilometresPerHour, Psi};
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
 pub enum SurfaceType {
@@ -21,31 +26,56 @@ pub enum SurfaceType {
     Cobblestone,
     Metal,
     Ridged,
+    Unknown(u8),
 }
 
-impl TryFrom<u8> for SurfaceType {
-    type Error = UnpackError;
+impl From<u8> for SurfaceType {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => SurfaceType::Tarmac,
+            1 => SurfaceType::RumbleStrip,
+            2 => SurfaceType::Concrete,
+            3 => SurfaceType::Rock,
+            4 => SurfaceType::Gravel,
+            5 => SurfaceType::Mud,
+            6 => SurfaceType::Sand,
+            7 => SurfaceType::Grass,
+            8 => SurfaceType::Water,
+            9 => SurfaceType::Cobblestone,
+            10 => SurfaceType::Metal,
+            11 => SurfaceType::Ridged,
+            _ => SurfaceType::Unknown(value),
+        }
+    }
+}
 
-    fn try_from(value: u8) -> Result<Self, Self::Error> {
+impl From<SurfaceType> for u8 {
+    fn from(value: SurfaceType) -> Self {
         match value {
-            0 => Ok(SurfaceType::Tarmac),
-            1 => Ok(SurfaceType::RumbleStrip),
-            2 => Ok(SurfaceType::Concrete),
-            3 => Ok(SurfaceType::Rock),
-            4 => Ok(SurfaceType::Gravel),
-            5 => Ok(SurfaceType::Mud),
-            6 => Ok(SurfaceType::Sand),
-            7 => Ok(SurfaceType::Grass),
-            8 => Ok(SurfaceType::Water),
-            9 => Ok(SurfaceType::Cobblestone),
-            10 => Ok(SurfaceType::Metal),
-            11 => Ok(SurfaceType::Ridged),
-            _ => Err(UnpackError(format!("Invalid SurfaceType value: {}", value))),
+            SurfaceType::Tarmac => 0,
+            SurfaceType::RumbleStrip => 1,
+            SurfaceType::Concrete => 2,
+            SurfaceType::Rock => 3,
+            SurfaceType::Gravel => 4,
+            SurfaceType::Mud => 5,
+            SurfaceType::Sand => 6,
+            SurfaceType::Grass => 7,
+            SurfaceType::Water => 8,
+            SurfaceType::Cobblestone => 9,
+            SurfaceType::Metal => 10,
+            SurfaceType::Ridged => 11,
+            SurfaceType::Unknown(value) => value,
         }
     }
 }
 
-/// This type is used for the 20-element `car_telemetry_data` array of the [`PacketCarTelemetryData`] type.
+/// This type is used for the 20-element (22 from F1 2020) `car_telemetry_data` array of the
+/// [`PacketCarTelemetryData`] type.
+///
+/// The tyre temperatures are sent as single bytes from F1 2020, and the rev
+/// lights as a bit field too from F1 2021.
+///
+/// Size: 66 bytes (F1 2019), 58 bytes (F1 2020), 60 bytes (F1 2021+)
 ///
 /// ## Specification
 /// speed                     Speed of car in kilometres per hour
@@ -57,6 +87,8 @@ impl TryFrom<u8> for SurfaceType {
 /// engine_rpm                Engine RPM
 /// drs                       0 = off, 1 = on
 /// rev_lights_percent        Rev lights indicator (percentage)
+/// rev_lights_bit_value      Rev lights (bit 0 = leftmost LED, bit 14 = rightmost
+///                           LED) (F1 2021+)
 /// brakes_temperature        Brakes temperature (celsius)
 /// tyres_surface_temperature Tyres surface temperature (celsius)
 /// tyres_inner_temperature   Tyres inner temperature (celsius)
@@ -77,6 +109,7 @@ pub struct CarTelemetryData {
     engine_rpm: u16,
     drs: bool,
     rev_lights_percent: u8,
+    rev_lights_bit_value: Option<u16>,
     brakes_temperature: WheelData<u16>,
     tyres_surface_temperature: WheelData<u16>,
     tyres_inner_temperature: WheelData<u16>,
@@ -86,46 +119,73 @@ pub struct CarTelemetryData {
 }
 
 impl CarTelemetryData {
-    pub fn new<T: BufRead>(reader: &mut T) -> Result<CarTelemetryData, UnpackError> {
-        let speed = reader.read_u16::<LittleEndian>().unwrap();
-        let throttle = reader.read_f32::<LittleEndian>().unwrap();
-        let steer = reader.read_f32::<LittleEndian>().unwrap();
-        let brake = reader.read_f32::<LittleEndian>().unwrap();
-        let clutch = reader.read_u8().unwrap();
-        let gear = reader.read_i8().unwrap();
-        let engine_rpm = reader.read_u16::<LittleEndian>().unwrap();
-        let drs = reader.read_u8().unwrap() == 1;
-        let rev_lights_percent = reader.read_u8().unwrap();
-        let brakes_temperature = WheelData::new(
-            reader.read_u16::<LittleEndian>().unwrap(),
-            reader.read_u16::<LittleEndian>().unwrap(),
-            reader.read_u16::<LittleEndian>().unwrap(),
-            reader.read_u16::<LittleEndian>().unwrap(),
-        );
+    /// Size of the telemetry of a car, with 16-bit tyre temperatures.
+    const SIZE_2019: usize = 66;
+    /// Size of the telemetry of a car in F1 2020, with 8-bit tyre temperatures.
+    const SIZE_2020: usize = 58;
+    /// Size of the telemetry of a car from F1 2021.
+    const SIZE: usize = 60;
+
+    pub fn new<T: BufRead>(
+        reader: &mut T,
+        packet_format: u16,
+    ) -> Result<CarTelemetryData, UnpackError> {
+        if packet_format >= 2021 {
+            let block: [u8; CarTelemetryData::SIZE] = read_block(reader);
+            CarTelemetryData::decode(&mut LeBytes::new(&block), packet_format)
+        } else if packet_format >= 2020 {
+            let block: [u8; CarTelemetryData::SIZE_2020] = read_block(reader);
+            CarTelemetryData::decode(&mut LeBytes::new(&block), packet_format)
+        } else {
+            let block: [u8; CarTelemetryData::SIZE_2019] = read_block(reader);
+            CarTelemetryData::decode(&mut LeBytes::new(&block), packet_format)
+        }
+    }
+
+    #[inline(always)]
+    fn decode(bytes: &mut LeBytes, packet_format: u16) -> Result<CarTelemetryData, UnpackError> {
+        let read_tyre_temperature = |bytes: &mut LeBytes| {
+            if packet_format >= 2020 {
+                u16::from(bytes.u8())
+            } else {
+                bytes.u16()
+            }
+        };
+
+        let speed = bytes.u16();
+        let throttle = bytes.f32();
+        let steer = bytes.f32();
+        let brake = bytes.f32();
+        let clutch = bytes.u8();
+        let gear = bytes.i8();
+        let engine_rpm = bytes.u16();
+        let drs = bytes.u8() == 1;
+        let rev_lights_percent = bytes.u8();
+        let rev_lights_bit_value = if packet_format >= 2021 {
+            Some(bytes.u16())
+        } else {
+            None
+        };
+        let brakes_temperature = WheelData::new(bytes.u16(), bytes.u16(), bytes.u16(), bytes.u16());
         let tyres_surface_temperature = WheelData::new(
-            reader.read_u16::<LittleEndian>().unwrap(),
-            reader.read_u16::<LittleEndian>().unwrap(),
-            reader.read_u16::<LittleEndian>().unwrap(),
-            reader.read_u16::<LittleEndian>().unwrap(),
+            read_tyre_temperature(bytes),
+            read_tyre_temperature(bytes),
+            read_tyre_temperature(bytes),
+            read_tyre_temperature(bytes),
         );
         let tyres_inner_temperature = WheelData::new(
-            reader.read_u16::<LittleEndian>().unwrap(),
-            reader.read_u16::<LittleEndian>().unwrap(),
-            reader.read_u16::<LittleEndian>().unwrap(),
-            reader.read_u16::<LittleEndian>().unwrap(),
-        );
-        let engine_temperature = reader.read_u16::<LittleEndian>().unwrap();
-        let tyre_pressures = WheelData::new(
-            reader.read_f32::<LittleEndian>().unwrap(),
-            reader.read_f32::<LittleEndian>().unwrap(),
-            reader.read_f32::<LittleEndian>().unwrap(),
-            reader.read_f32::<LittleEndian>().unwrap(),
+            read_tyre_temperature(bytes),
+            read_tyre_temperature(bytes),
+            read_tyre_temperature(bytes),
+            read_tyre_temperature(bytes),
         );
+        let engine_temperature = bytes.u16();
+        let tyre_pressures = WheelData::new(bytes.f32(), bytes.f32(), bytes.f32(), bytes.f32());
         let surface_types = WheelData::new(
-            SurfaceType::try_from(reader.read_u8().unwrap())?,
-            SurfaceType::try_from(reader.read_u8().unwrap())?,
-            SurfaceType::try_from(reader.read_u8().unwrap())?,
-            SurfaceType::try_from(reader.read_u8().unwrap())?,
+            SurfaceType::from(bytes.u8()),
+            SurfaceType::from(bytes.u8()),
+            SurfaceType::from(bytes.u8()),
+            SurfaceType::from(bytes.u8()),
         );
 
         Ok(CarTelemetryData {
@@ -138,6 +198,7 @@ impl CarTelemetryData {
             engine_rpm,
             drs,
             rev_lights_percent,
+            rev_lights_bit_value,
             brakes_temperature,
             tyres_surface_temperature,
             tyres_inner_temperature,
@@ -146,6 +207,72 @@ impl CarTelemetryData {
             surface_types,
         })
     }
+
+    pub(crate) fn encode<W: Write>(&self, writer: &mut W, packet_format: u16) -> io::Result<()> {
+        writer.write_u16::<LittleEndian>(self.speed)?;
+        writer.write_f32::<LittleEndian>(self.throttle)?;
+        writer.write_f32::<LittleEndian>(self.steer)?;
+        writer.write_f32::<LittleEndian>(self.brake)?;
+        writer.write_u8(self.clutch)?;
+        writer.write_i8(self.gear)?;
+        writer.write_u16::<LittleEndian>(self.engine_rpm)?;
+        writer.write_u8(self.drs as u8)?;
+        writer.write_u8(self.rev_lights_percent)?;
+        if packet_format >= 2021 {
+            writer.write_u16::<LittleEndian>(self.rev_lights_bit_value.unwrap_or(0))?;
+        }
+        for v in self.brakes_temperature.to_array().iter() {
+            writer.write_u16::<LittleEndian>(*v)?;
+        }
+        for v in self.tyres_surface_temperature.to_array().iter() {
+            if packet_format >= 2020 {
+                writer.write_u8(*v as u8)?;
+            } else {
+                writer.write_u16::<LittleEndian>(*v)?;
+            }
+        }
+        for v in self.tyres_inner_temperature.to_array().iter() {
+            if packet_format >= 2020 {
+                writer.write_u8(*v as u8)?;
+            } else {
+                writer.write_u16::<LittleEndian>(*v)?;
+            }
+        }
+        writer.write_u16::<LittleEndian>(self.engine_temperature)?;
+        for v in self.tyre_pressures.to_array().iter() {
+            writer.write_f32::<LittleEndian>(*v)?;
+        }
+        for v in self.surface_types.to_array().iter() {
+            writer.write_u8((*v).into())?;
+        }
+
+        Ok(())
+    }
+
+    pub fn speed_kmh(&self) -> KilometresPerHour {
+        KilometresPerHour(f32::from(self.speed))
+    }
+
+    pub fn brakes_temperature_celsius(&self) -> WheelData<Celsius> {
+        self.brakes_temperature.map(|t| Celsius(f32::from(t)))
+    }
+
+    pub fn tyres_surface_temperature_celsius(&self) -> WheelData<Celsius> {
+        self.tyres_surface_temperature
+            .map(|t| Celsius(f32::from(t)))
+    }
+
+    pub fn tyres_inner_temperature_celsius(&self) -> WheelData<Celsius> {
+        self.tyres_inner_temperature.map(|t| Celsius(f32::from(t)))
+    }
+
+    pub fn engine_temperature_celsius(&self) -> Celsius {
+        Celsius(f32::from(self.engine_temperature))
+    }
+
+    pub fn tyre_pressures_psi(&self) -> WheelData<Psi> {
+        self.tyre_pressures.map(Psi)
+    }
 }
 
 /// Bit-mask values for the `button_status` field in [`PacketCarTelemetryData`]
@@ -170,22 +297,257 @@ pub enum ButtonFlag {
     RightStickClick = 0x4000,
 }
 
+/// Set of buttons pressed, as sent in the `button_status` field of
+/// [`PacketCarTelemetryData`].
+///
+/// The UDP actions are the buttons that can be bound to UDP actions in the
+/// controls of the game, sent from F1 2021.
+///
+/// ```
+/// use f1_telemetry::packet::car_telemetry::ButtonFlags;
+///
+/// let previous = ButtonFlags::CROSS;
+/// let current = ButtonFlags::CROSS | ButtonFlags::UDP_ACTION_1;
+/// assert_eq!(current.pressed_since(previous), ButtonFlags::UDP_ACTION_1);
+/// ```
+///
+/// [`PacketCarTelemetryData`]: ./struct.PacketCarTelemetryData.html
+#[derive(Clone, Copy, Default, Eq, PartialEq, Hash)]
+pub struct ButtonFlags(u32);
+
+impl ButtonFlags {
+    pub const CROSS: ButtonFlags = ButtonFlags(0x0001);
+    pub const TRIANGLE: ButtonFlags = ButtonFlags(0x0002);
+    pub const CIRCLE: ButtonFlags = ButtonFlags(0x0004);
+    pub const SQUARE: ButtonFlags = ButtonFlags(0x0008);
+    pub const DPAD_LEFT: ButtonFlags = ButtonFlags(0x0010);
+    pub const DPAD_RIGHT: ButtonFlags = ButtonFlags(0x0020);
+    pub const DPAD_UP: ButtonFlags = ButtonFlags(0x0040);
+    pub const DPAD_DOWN: ButtonFlags = ButtonFlags(0x0080);
+    pub const OPTIONS: ButtonFlags = ButtonFlags(0x0100);
+    pub const L1: ButtonFlags = ButtonFlags(0x0200);
+    pub const R1: ButtonFlags = ButtonFlags(0x0400);
+    pub const L2: ButtonFlags = ButtonFlags(0x0800);
+    pub const R2: ButtonFlags = ButtonFlags(0x1000);
+    pub const LEFT_STICK_CLICK: ButtonFlags = ButtonFlags(0x2000);
+    pub const RIGHT_STICK_CLICK: ButtonFlags = ButtonFlags(0x4000);
+    pub const RIGHT_STICK_LEFT: ButtonFlags = ButtonFlags(0x8000);
+    pub const RIGHT_STICK_RIGHT: ButtonFlags = ButtonFlags(0x0001_0000);
+    pub const RIGHT_STICK_UP: ButtonFlags = ButtonFlags(0x0002_0000);
+    pub const RIGHT_STICK_DOWN: ButtonFlags = ButtonFlags(0x0004_0000);
+    pub const SPECIAL: ButtonFlags = ButtonFlags(0x0008_0000);
+    pub const UDP_ACTION_1: ButtonFlags = ButtonFlags(0x0010_0000);
+    pub const UDP_ACTION_2: ButtonFlags = ButtonFlags(0x0020_0000);
+    pub const UDP_ACTION_3: ButtonFlags = ButtonFlags(0x0040_0000);
+    pub const UDP_ACTION_4: ButtonFlags = ButtonFlags(0x0080_0000);
+    pub const UDP_ACTION_5: ButtonFlags = ButtonFlags(0x0100_0000);
+    pub const UDP_ACTION_6: ButtonFlags = ButtonFlags(0x0200_0000);
+    pub const UDP_ACTION_7: ButtonFlags = ButtonFlags(0x0400_0000);
+    pub const UDP_ACTION_8: ButtonFlags = ButtonFlags(0x0800_0000);
+    pub const UDP_ACTION_9: ButtonFlags = ButtonFlags(0x1000_0000);
+    pub const UDP_ACTION_10: ButtonFlags = ButtonFlags(0x2000_0000);
+    pub const UDP_ACTION_11: ButtonFlags = ButtonFlags(0x4000_0000);
+    pub const UDP_ACTION_12: ButtonFlags = ButtonFlags(0x8000_0000);
+
+    const NAMES: [(ButtonFlags, &'static str); 32] = [
+        (ButtonFlags::CROSS, "CROSS"),
+        (ButtonFlags::TRIANGLE, "TRIANGLE"),
+        (ButtonFlags::CIRCLE, "CIRCLE"),
+        (ButtonFlags::SQUARE, "SQUARE"),
+        (ButtonFlags::DPAD_LEFT, "DPAD_LEFT"),
+        (ButtonFlags::DPAD_RIGHT, "DPAD_RIGHT"),
+        (ButtonFlags::DPAD_UP, "DPAD_UP"),
+        (ButtonFlags::DPAD_DOWN, "DPAD_DOWN"),
+        (ButtonFlags::OPTIONS, "OPTIONS"),
+        (ButtonFlags::L1, "L1"),
+        (ButtonFlags::R1, "R1"),
+        (ButtonFlags::L2, "L2"),
+        (ButtonFlags::R2, "R2"),
+        (ButtonFlags::LEFT_STICK_CLICK, "LEFT_STICK_CLICK"),
+        (ButtonFlags::RIGHT_STICK_CLICK, "RIGHT_STICK_CLICK"),
+        (ButtonFlags::RIGHT_STICK_LEFT, "RIGHT_STICK_LEFT"),
+        (ButtonFlags::RIGHT_STICK_RIGHT, "RIGHT_STICK_RIGHT"),
+        (ButtonFlags::RIGHT_STICK_UP, "RIGHT_STICK_UP"),
+        (ButtonFlags::RIGHT_STICK_DOWN, "RIGHT_STICK_DOWN"),
+        (ButtonFlags::SPECIAL, "SPECIAL"),
+        (ButtonFlags::UDP_ACTION_1, "UDP_ACTION_1"),
+        (ButtonFlags::UDP_ACTION_2, "UDP_ACTION_2"),
+        (ButtonFlags::UDP_ACTION_3, "UDP_ACTION_3"),
+        (ButtonFlags::UDP_ACTION_4, "UDP_ACTION_4"),
+        (ButtonFlags::UDP_ACTION_5, "UDP_ACTION_5"),
+        (ButtonFlags::UDP_ACTION_6, "UDP_ACTION_6"),
+        (ButtonFlags::UDP_ACTION_7, "UDP_ACTION_7"),
+        (ButtonFlags::UDP_ACTION_8, "UDP_ACTION_8"),
+        (ButtonFlags::UDP_ACTION_9, "UDP_ACTION_9"),
+        (ButtonFlags::UDP_ACTION_10, "UDP_ACTION_10"),
+        (ButtonFlags::UDP_ACTION_11, "UDP_ACTION_11"),
+        (ButtonFlags::UDP_ACTION_12, "UDP_ACTION_12"),
+    ];
+
+    pub fn empty() -> ButtonFlags {
+        ButtonFlags(0)
+    }
+
+    pub fn from_bits(bits: u32) -> ButtonFlags {
+        ButtonFlags(bits)
+    }
+
+    pub fn bits(self) -> u32 {
+        self.0
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Whether all the buttons of `other` are pressed.
+    pub fn contains(self, other: ButtonFlags) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Whether any of the buttons of `other` is pressed.
+    pub fn intersects(self, other: ButtonFlags) -> bool {
+        self.0 & other.0 != 0
+    }
+
+    /// Buttons pressed now that were not in `previous`.
+    pub fn pressed_since(self, previous: ButtonFlags) -> ButtonFlags {
+        ButtonFlags(self.0 & !previous.0)
+    }
+
+    /// Buttons pressed in `previous` that are not anymore.
+    pub fn released_since(self, previous: ButtonFlags) -> ButtonFlags {
+        ButtonFlags(previous.0 & !self.0)
+    }
+
+    /// Each of the buttons pressed, as single flags.
+    pub fn iter(self) -> impl Iterator<Item = ButtonFlags> {
+        (0..32)
+            .map(|bit| ButtonFlags(1 << bit))
+            .filter(move |flag| self.contains(*flag))
+    }
+}
+
+impl BitOr for ButtonFlags {
+    type Output = ButtonFlags;
+
+    fn bitor(self, other: ButtonFlags) -> ButtonFlags {
+        ButtonFlags(self.0 | other.0)
+    }
+}
+
+impl BitOrAssign for ButtonFlags {
+    fn bitor_assign(&mut self, other: ButtonFlags) {
+        self.0 |= other.0;
+    }
+}
+
+impl BitAnd for ButtonFlags {
+    type Output = ButtonFlags;
+
+    fn bitand(self, other: ButtonFlags) -> ButtonFlags {
+        ButtonFlags(self.0 & other.0)
+    }
+}
+
+impl Not for ButtonFlags {
+    type Output = ButtonFlags;
+
+    fn not(self) -> ButtonFlags {
+        ButtonFlags(!self.0)
+    }
+}
+
+impl From<ButtonFlag> for ButtonFlags {
+    fn from(flag: ButtonFlag) -> Self {
+        ButtonFlags(flag as u32)
+    }
+}
+
+impl fmt::Debug for ButtonFlags {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut names = ButtonFlags::NAMES
+            .iter()
+            .filter(|(flag, _)| self.contains(*flag))
+            .map(|(_, name)| name);
+
+        write!(f, "ButtonFlags(")?;
+        if let Some(name) = names.next() {
+            write!(f, "{}", name)?;
+            for name in names {
+                write!(f, " | {}", name)?;
+            }
+        }
+        write!(f, ")")
+    }
+}
+
+/// Panel of the multi-function display (MFD) open in the game.
+///
+/// The panels depend on the game mode; these are the ones of a single player
+/// race.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum MfdPanel {
+    CarSetup,
+    Pits,
+    Damage,
+    Engine,
+    Temperatures,
+    Closed,
+    Unknown(u8),
+}
+
+impl From<u8> for MfdPanel {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => MfdPanel::CarSetup,
+            1 => MfdPanel::Pits,
+            2 => MfdPanel::Damage,
+            3 => MfdPanel::Engine,
+            4 => MfdPanel::Temperatures,
+            255 => MfdPanel::Closed,
+            _ => MfdPanel::Unknown(value),
+        }
+    }
+}
+
+impl From<MfdPanel> for u8 {
+    fn from(value: MfdPanel) -> Self {
+        match value {
+            MfdPanel::CarSetup => 0,
+            MfdPanel::Pits => 1,
+            MfdPanel::Damage => 2,
+            MfdPanel::Engine => 3,
+            MfdPanel::Temperatures => 4,
+            MfdPanel::Closed => 255,
+            MfdPanel::Unknown(value) => value,
+        }
+    }
+}
+
 /// This packet details telemetry for all the cars in the race.
 ///
 /// It details various values that would be recorded on the car such as speed, throttle application, DRS etc.
 ///
 /// Frequency: Rate as specified in menus
 ///
-/// Size: 1347 bytes
+/// Size: 1347 bytes (F1 2019), 1307 bytes (F1 2020), 1347 bytes (F1 2021-2022),
+/// 1352 bytes (F1 2023+)
 ///
 /// Version: 1
 ///
 /// ## Specification
 /// ```text
 /// header:             Header
-/// car_telemetry_data: List of car telemetry (20)
+/// car_telemetry_data: List of car telemetry (20, 22 from F1 2020)
 /// button_status:      Bit flags specifying which buttons are being
-///                     pressed currently - see appendices
+///                     pressed currently - see appendices (until F1 2020)
+/// mfd_panel:          Index of MFD panel open - 255 = MFD closed
+///                     (F1 2020+)
+/// mfd_panel_secondary_player:
+///                     See above (F1 2020+)
+/// suggested_gear:     Suggested gear for the player (1-8), 0 if no gear
+///                     suggested (F1 2020+)
 /// ```
 #[derive(Debug, CopyGetters, Getters)]
 pub struct PacketCarTelemetryData {
@@ -194,31 +556,83 @@ pub struct PacketCarTelemetryData {
     #[getset(get = "pub")]
     car_telemetry_data: Vec<CarTelemetryData>,
     #[getset(get_copy = "pub")]
-    button_status: u32,
+    button_status: Option<u32>,
+    #[getset(get_copy = "pub")]
+    mfd_panel: Option<MfdPanel>,
+    mfd_panel_secondary_player: Option<MfdPanel>,
+    raw_suggested_gear: Option<i8>,
 }
 
 impl PacketCarTelemetryData {
     pub fn new<T: BufRead>(
-        mut reader: &mut T,
+        reader: &mut T,
         header: PacketHeader,
     ) -> Result<PacketCarTelemetryData, UnpackError> {
-        let mut car_telemetry_data = Vec::with_capacity(20);
-        for _ in 0..20 {
-            let ctd = CarTelemetryData::new(&mut reader)?;
+        let packet_format = header.packet_format();
+        let num_cars = num_cars(packet_format);
+        let mut car_telemetry_data = Vec::with_capacity(num_cars);
+        for _ in 0..num_cars {
+            let ctd = CarTelemetryData::new(reader, packet_format)?;
             car_telemetry_data.push(ctd);
         }
 
-        let button_status = reader.read_u32::<LittleEndian>().unwrap();
+        // The buttons are sent in Event packets from F1 2021.
+        let button_status = if packet_format < 2021 {
+            Some(reader.read_u32::<LittleEndian>().unwrap())
+        } else {
+            None
+        };
+
+        let (mfd_panel, mfd_panel_secondary_player, raw_suggested_gear) = if packet_format >= 2020 {
+            (
+                Some(MfdPanel::from(reader.read_u8().unwrap())),
+                Some(MfdPanel::from(reader.read_u8().unwrap())),
+                Some(reader.read_i8().unwrap()),
+            )
+        } else {
+            (None, None, None)
+        };
 
         Ok(PacketCarTelemetryData {
             header,
             car_telemetry_data,
             button_status,
+            mfd_panel,
+            mfd_panel_secondary_player,
+            raw_suggested_gear,
         })
     }
 
+    /// Telemetry of the player's car.
+    pub fn player_car_telemetry(&self) -> Option<&CarTelemetryData> {
+        self.car_telemetry_data
+            .get(self.header.player_car_index() as usize)
+    }
+
+    /// Telemetry of the secondary player's car, in split-screen sessions.
+    pub fn secondary_player_car_telemetry(&self) -> Option<&CarTelemetryData> {
+        let idx = self.header.secondary_player_car_index()?;
+        self.car_telemetry_data.get(idx as usize)
+    }
+
+    /// MFD panel open for the secondary player, in split-screen sessions.
+    pub fn mfd_panel_secondary_player(&self) -> Option<MfdPanel> {
+        self.header.secondary_player_car_index()?;
+        self.mfd_panel_secondary_player
+    }
+
+    /// Gear the game suggests the player to shift to, if any.
+    pub fn suggested_gear(&self) -> Option<i8> {
+        self.raw_suggested_gear.filter(|&gear| gear > 0)
+    }
+
+    /// Buttons pressed by the player, until F1 2020.
+    pub fn buttons(&self) -> Option<ButtonFlags> {
+        self.button_status.map(ButtonFlags)
+    }
+
     pub fn get_pressed_buttons(&self) -> Vec<ButtonFlag> {
-        let mask = self.button_status;
+        let mask = self.button_status.unwrap_or(0);
         let mut buttons = Vec::new();
 
         if mask & (ButtonFlag::Cross as u32) > 0 {
@@ -269,4 +683,50 @@ impl PacketCarTelemetryData {
 
         buttons
     }
+
+    /// Floating point values of each car, by index and field name, e.g. to
+    /// check them.
+    #[cfg(feature = "std")]
+    pub(crate) fn floats_mut(&mut self) -> impl Iterator<Item = (usize, &'static str, &mut f32)> {
+        self.car_telemetry_data
+            .iter_mut()
+            .enumerate()
+            .flat_map(|(i, t)| {
+                vec![
+                    (i, "throttle", &mut t.throttle),
+                    (i, "steer", &mut t.steer),
+                    (i, "brake", &mut t.brake),
+                ]
+            })
+    }
+
+    /// Speed of each car, by index.
+    #[cfg(feature = "std")]
+    pub(crate) fn speeds_mut(&mut self) -> impl Iterator<Item = (usize, &mut u16)> {
+        self.car_telemetry_data
+            .iter_mut()
+            .enumerate()
+            .map(|(i, t)| (i, &mut t.speed))
+    }
+}
+
+impl Encode for PacketCarTelemetryData {
+    fn encode<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        let packet_format = self.header.packet_format();
+        self.header.encode(writer)?;
+        for data in &self.car_telemetry_data {
+            data.encode(writer, packet_format)?;
+        }
+
+        if packet_format < 2021 {
+            writer.write_u32::<LittleEndian>(self.button_status.unwrap_or(0))?;
+        }
+        if packet_format >= 2020 {
+            writer.write_u8(self.mfd_panel.map_or(255, u8::from))?;
+            writer.write_u8(self.mfd_panel_secondary_player.map_or(255, u8::from))?;
+            writer.write_i8(self.raw_suggested_gear.unwrap_or(0))?;
+        }
+
+        Ok(())
+    }
 }
@@ -0,0 +1,42 @@
+//! Serialization of the packets back into the wire format of the game.
+//!
+//! Encoding a parsed packet gives back the bytes it was parsed from, which allows
+//! round-trip tests, fuzzing harnesses and synthetic sessions to be built without
+//! the game running. Values that are not kept by the parser, such as the padding
+//! after a name, are written as zeros.
+
+use crate::io::{self, Write};
+use alloc::vec::Vec;
+
+use crate::packet::Packet;
+
+/// A packet that can be written in the wire format of its packet format.
+pub trait Encode {
+    fn encode<W: Write>(&self, writer: &mut W) -> io::Result<()>;
+}
+
+/// Serialize a packet into a datagram, as the game would send it.
+pub fn encode_packet(packet: &Packet) -> Vec<u8> {
+    let mut buf = Vec::new();
+
+    let result = match packet {
+        Packet::Motion(p) => p.encode(&mut buf),
+        Packet::Session(p) => p.encode(&mut buf),
+        Packet::Lap(p) => p.encode(&mut buf),
+        Packet::Event(p) => p.encode(&mut buf),
+        Packet::Participants(p) => p.encode(&mut buf),
+        Packet::CarSetups(p) => p.encode(&mut buf),
+        Packet::CarTelemetry(p) => p.encode(&mut buf),
+        Packet::CarStatus(p) => p.encode(&mut buf),
+        Packet::FinalClassification(p) => p.encode(&mut buf),
+        Packet::LobbyInfo(p) => p.encode(&mut buf),
+        Packet::CarDamage(p) => p.encode(&mut buf),
+        Packet::TyreSets(p) => p.encode(&mut buf),
+        Packet::MotionEx(p) => p.encode(&mut buf),
+        Packet::TimeTrial(p) => p.encode(&mut buf),
+        Packet::Raw(p) => buf.write_all(p.data()),
+    };
+    result.expect("Writing to a Vec cannot fail");
+
+    buf
+}
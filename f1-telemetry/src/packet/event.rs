@@ -1,9 +1,12 @@
-use byteorder::{LittleEndian, ReadBytesExt};
+use alloc::format;
+use alloc::vec::Vec;
+use byteorder::LittleEndian;
+use core::convert::TryFrom;
 use getset::{CopyGetters, Getters};
-use std::convert::TryFrom;
-use std::io::BufRead;
 
 use super::header::PacketHeader;
+use crate::io::{self, BufRead, ReadBytesExt, Write, WriteBytesExt};
+use crate::packet::encode::Encode;
 use crate::packet::UnpackError;
 
 /// List of possible events.
@@ -19,6 +22,10 @@ use crate::packet::UnpackError;
 /// TeamMateInPits: Your team mate has entered the pits
 /// ChequeredFlag:  The chequered flag has been waved
 /// RaceWinner:     The race winner is announced
+/// Penalty:        A penalty has been issued – details in event (F1 2020+)
+/// SpeedTrap:      Speed trap has been triggered by fastest speed (F1 2020+)
+/// Unknown:        Any other event, with its four letter code, e.g. the button
+///                 status, flashback or overtake events of F1 2021+
 /// ```
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
 pub enum Event {
@@ -31,6 +38,40 @@ pub enum Event {
     TeamMateInPits,
     ChequeredFlag,
     RaceWinner,
+    Penalty,
+    SpeedTrap,
+    Unknown([u8; 4]),
+}
+
+impl Event {
+    /// Four letter code of the event in the wire format.
+    pub fn code(&self) -> &str {
+        match self {
+            Event::SessionStarted => "SSTA",
+            Event::SessionEnded => "SEND",
+            Event::FastestLap => "FTLP",
+            Event::Retirement => "RTMT",
+            Event::DRSEnabled => "DRSE",
+            Event::DRSDisabled => "DRSD",
+            Event::TeamMateInPits => "TMPT",
+            Event::ChequeredFlag => "CHQF",
+            Event::RaceWinner => "RCWN",
+            Event::Penalty => "PENA",
+            Event::SpeedTrap => "SPTP",
+            Event::Unknown(code) => core::str::from_utf8(code).unwrap_or("????"),
+        }
+    }
+
+    fn code_bytes(&self) -> [u8; 4] {
+        match self {
+            Event::Unknown(code) => *code,
+            event => {
+                let mut code = [0; 4];
+                code.copy_from_slice(event.code().as_bytes());
+                code
+            }
+        }
+    }
 }
 
 impl TryFrom<&str> for Event {
@@ -47,11 +88,351 @@ impl TryFrom<&str> for Event {
             "TMPT" => Ok(Event::TeamMateInPits),
             "CHQF" => Ok(Event::ChequeredFlag),
             "RCWN" => Ok(Event::RaceWinner),
-            _ => Err(UnpackError(format!("Invalid Event value: {}", value))),
+            "PENA" => Ok(Event::Penalty),
+            "SPTP" => Ok(Event::SpeedTrap),
+            _ => match <[u8; 4]>::try_from(value.as_bytes()) {
+                Ok(code) => Ok(Event::Unknown(code)),
+                Err(_) => Err(UnpackError(format!("Invalid Event value: {}", value))),
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum PenaltyType {
+    DriveThrough,
+    StopGo,
+    GridPenalty,
+    PenaltyReminder,
+    TimePenalty,
+    Warning,
+    Disqualified,
+    RemovedFromFormationLap,
+    ParkedTooLongTimer,
+    TyreRegulations,
+    ThisLapInvalidated,
+    ThisAndNextLapInvalidated,
+    ThisLapInvalidatedWithoutReason,
+    ThisAndNextLapInvalidatedWithoutReason,
+    ThisAndPreviousLapInvalidated,
+    ThisAndPreviousLapInvalidatedWithoutReason,
+    Retired,
+    BlackFlagTimer,
+    Unknown(u8),
+}
+
+impl From<u8> for PenaltyType {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => PenaltyType::DriveThrough,
+            1 => PenaltyType::StopGo,
+            2 => PenaltyType::GridPenalty,
+            3 => PenaltyType::PenaltyReminder,
+            4 => PenaltyType::TimePenalty,
+            5 => PenaltyType::Warning,
+            6 => PenaltyType::Disqualified,
+            7 => PenaltyType::RemovedFromFormationLap,
+            8 => PenaltyType::ParkedTooLongTimer,
+            9 => PenaltyType::TyreRegulations,
+            10 => PenaltyType::ThisLapInvalidated,
+            11 => PenaltyType::ThisAndNextLapInvalidated,
+            12 => PenaltyType::ThisLapInvalidatedWithoutReason,
+            13 => PenaltyType::ThisAndNextLapInvalidatedWithoutReason,
+            14 => PenaltyType::ThisAndPreviousLapInvalidated,
+            15 => PenaltyType::ThisAndPreviousLapInvalidatedWithoutReason,
+            16 => PenaltyType::Retired,
+            17 => PenaltyType::BlackFlagTimer,
+            _ => PenaltyType::Unknown(value),
+        }
+    }
+}
+
+impl From<PenaltyType> for u8 {
+    fn from(value: PenaltyType) -> Self {
+        match value {
+            PenaltyType::DriveThrough => 0,
+            PenaltyType::StopGo => 1,
+            PenaltyType::GridPenalty => 2,
+            PenaltyType::PenaltyReminder => 3,
+            PenaltyType::TimePenalty => 4,
+            PenaltyType::Warning => 5,
+            PenaltyType::Disqualified => 6,
+            PenaltyType::RemovedFromFormationLap => 7,
+            PenaltyType::ParkedTooLongTimer => 8,
+            PenaltyType::TyreRegulations => 9,
+            PenaltyType::ThisLapInvalidated => 10,
+            PenaltyType::ThisAndNextLapInvalidated => 11,
+            PenaltyType::ThisLapInvalidatedWithoutReason => 12,
+            PenaltyType::ThisAndNextLapInvalidatedWithoutReason => 13,
+            PenaltyType::ThisAndPreviousLapInvalidated => 14,
+            PenaltyType::ThisAndPreviousLapInvalidatedWithoutReason => 15,
+            PenaltyType::Retired => 16,
+            PenaltyType::BlackFlagTimer => 17,
+            PenaltyType::Unknown(value) => value,
         }
     }
 }
 
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum InfringementType {
+    BlockingBySlowDriving,
+    BlockingByWrongWayDriving,
+    ReversingOffTheStartLine,
+    BigCollision,
+    SmallCollision,
+    CollisionFailedToHandBackPositionSingle,
+    CollisionFailedToHandBackPositionMultiple,
+    CornerCuttingGainedTime,
+    CornerCuttingOvertakeSingle,
+    CornerCuttingOvertakeMultiple,
+    CrossedPitExitLane,
+    IgnoringBlueFlags,
+    IgnoringYellowFlags,
+    IgnoringDriveThrough,
+    TooManyDriveThroughs,
+    DriveThroughReminderServeWithinNLaps,
+    DriveThroughReminderServeThisLap,
+    PitLaneSpeeding,
+    ParkedForTooLong,
+    IgnoringTyreRegulations,
+    TooManyPenalties,
+    MultipleWarnings,
+    ApproachingDisqualification,
+    TyreRegulationsSelectSingle,
+    TyreRegulationsSelectMultiple,
+    LapInvalidatedCornerCutting,
+    LapInvalidatedRunningWide,
+    CornerCuttingRanWideGainedTimeMinor,
+    CornerCuttingRanWideGainedTimeSignificant,
+    CornerCuttingRanWideGainedTimeExtreme,
+    LapInvalidatedWallRiding,
+    LapInvalidatedFlashbackUsed,
+    LapInvalidatedResetToTrack,
+    BlockingThePitlane,
+    JumpStart,
+    SafetyCarToCarCollision,
+    SafetyCarIllegalOvertake,
+    SafetyCarExceedingAllowedPace,
+    VirtualSafetyCarExceedingAllowedPace,
+    FormationLapBelowAllowedSpeed,
+    RetiredMechanicalFailure,
+    RetiredTerminallyDamaged,
+    SafetyCarFallingTooFarBack,
+    BlackFlagTimer,
+    UnservedStopGoPenalty,
+    UnservedDriveThroughPenalty,
+    EngineComponentChange,
+    GearboxChange,
+    LeagueGridPenalty,
+    RetryPenalty,
+    IllegalTimeGain,
+    MandatoryPitstop,
+    Unknown(u8),
+}
+
+impl InfringementType {
+    /// Whether the infringement is about cutting corners or running wide.
+    pub fn is_corner_cutting(self) -> bool {
+        matches!(
+            self,
+            InfringementType::CornerCuttingGainedTime
+                | InfringementType::CornerCuttingOvertakeSingle
+                | InfringementType::CornerCuttingOvertakeMultiple
+                | InfringementType::LapInvalidatedCornerCutting
+                | InfringementType::LapInvalidatedRunningWide
+                | InfringementType::CornerCuttingRanWideGainedTimeMinor
+                | InfringementType::CornerCuttingRanWideGainedTimeSignificant
+                | InfringementType::CornerCuttingRanWideGainedTimeExtreme
+        )
+    }
+}
+
+impl From<u8> for InfringementType {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => InfringementType::BlockingBySlowDriving,
+            1 => InfringementType::BlockingByWrongWayDriving,
+            2 => InfringementType::ReversingOffTheStartLine,
+            3 => InfringementType::BigCollision,
+            4 => InfringementType::SmallCollision,
+            5 => InfringementType::CollisionFailedToHandBackPositionSingle,
+            6 => InfringementType::CollisionFailedToHandBackPositionMultiple,
+            7 => InfringementType::CornerCuttingGainedTime,
+            8 => InfringementType::CornerCuttingOvertakeSingle,
+            9 => InfringementType::CornerCuttingOvertakeMultiple,
+            10 => InfringementType::CrossedPitExitLane,
+            11 => InfringementType::IgnoringBlueFlags,
+            12 => InfringementType::IgnoringYellowFlags,
+            13 => InfringementType::IgnoringDriveThrough,
+            14 => InfringementType::TooManyDriveThroughs,
+            15 => InfringementType::DriveThroughReminderServeWithinNLaps,
+            16 => InfringementType::DriveThroughReminderServeThisLap,
+            17 => InfringementType::PitLaneSpeeding,
+            18 => InfringementType::ParkedForTooLong,
+            19 => InfringementType::IgnoringTyreRegulations,
+            20 => InfringementType::TooManyPenalties,
+            21 => InfringementType::MultipleWarnings,
+            22 => InfringementType::ApproachingDisqualification,
+            23 => InfringementType::TyreRegulationsSelectSingle,
+            24 => InfringementType::TyreRegulationsSelectMultiple,
+            25 => InfringementType::LapInvalidatedCornerCutting,
+            26 => InfringementType::LapInvalidatedRunningWide,
+            27 => InfringementType::CornerCuttingRanWideGainedTimeMinor,
+            28 => InfringementType::CornerCuttingRanWideGainedTimeSignificant,
+            29 => InfringementType::CornerCuttingRanWideGainedTimeExtreme,
+            30 => InfringementType::LapInvalidatedWallRiding,
+            31 => InfringementType::LapInvalidatedFlashbackUsed,
+            32 => InfringementType::LapInvalidatedResetToTrack,
+            33 => InfringementType::BlockingThePitlane,
+            34 => InfringementType::JumpStart,
+            35 => InfringementType::SafetyCarToCarCollision,
+            36 => InfringementType::SafetyCarIllegalOvertake,
+            37 => InfringementType::SafetyCarExceedingAllowedPace,
+            38 => InfringementType::VirtualSafetyCarExceedingAllowedPace,
+            39 => InfringementType::FormationLapBelowAllowedSpeed,
+            40 => InfringementType::RetiredMechanicalFailure,
+            41 => InfringementType::RetiredTerminallyDamaged,
+            42 => InfringementType::SafetyCarFallingTooFarBack,
+            43 => InfringementType::BlackFlagTimer,
+            44 => InfringementType::UnservedStopGoPenalty,
+            45 => InfringementType::UnservedDriveThroughPenalty,
+            46 => InfringementType::EngineComponentChange,
+            47 => InfringementType::GearboxChange,
+            48 => InfringementType::LeagueGridPenalty,
+            49 => InfringementType::RetryPenalty,
+            50 => InfringementType::IllegalTimeGain,
+            51 => InfringementType::MandatoryPitstop,
+            _ => InfringementType::Unknown(value),
+        }
+    }
+}
+
+impl From<InfringementType> for u8 {
+    fn from(value: InfringementType) -> Self {
+        match value {
+            InfringementType::BlockingBySlowDriving => 0,
+            InfringementType::BlockingByWrongWayDriving => 1,
+            InfringementType::ReversingOffTheStartLine => 2,
+            InfringementType::BigCollision => 3,
+            InfringementType::SmallCollision => 4,
+            InfringementType::CollisionFailedToHandBackPositionSingle => 5,
+            InfringementType::CollisionFailedToHandBackPositionMultiple => 6,
+            InfringementType::CornerCuttingGainedTime => 7,
+            InfringementType::CornerCuttingOvertakeSingle => 8,
+            InfringementType::CornerCuttingOvertakeMultiple => 9,
+            InfringementType::CrossedPitExitLane => 10,
+            InfringementType::IgnoringBlueFlags => 11,
+            InfringementType::IgnoringYellowFlags => 12,
+            InfringementType::IgnoringDriveThrough => 13,
+            InfringementType::TooManyDriveThroughs => 14,
+            InfringementType::DriveThroughReminderServeWithinNLaps => 15,
+            InfringementType::DriveThroughReminderServeThisLap => 16,
+            InfringementType::PitLaneSpeeding => 17,
+            InfringementType::ParkedForTooLong => 18,
+            InfringementType::IgnoringTyreRegulations => 19,
+            InfringementType::TooManyPenalties => 20,
+            InfringementType::MultipleWarnings => 21,
+            InfringementType::ApproachingDisqualification => 22,
+            InfringementType::TyreRegulationsSelectSingle => 23,
+            InfringementType::TyreRegulationsSelectMultiple => 24,
+            InfringementType::LapInvalidatedCornerCutting => 25,
+            InfringementType::LapInvalidatedRunningWide => 26,
+            InfringementType::CornerCuttingRanWideGainedTimeMinor => 27,
+            InfringementType::CornerCuttingRanWideGainedTimeSignificant => 28,
+            InfringementType::CornerCuttingRanWideGainedTimeExtreme => 29,
+            InfringementType::LapInvalidatedWallRiding => 30,
+            InfringementType::LapInvalidatedFlashbackUsed => 31,
+            InfringementType::LapInvalidatedResetToTrack => 32,
+            InfringementType::BlockingThePitlane => 33,
+            InfringementType::JumpStart => 34,
+            InfringementType::SafetyCarToCarCollision => 35,
+            InfringementType::SafetyCarIllegalOvertake => 36,
+            InfringementType::SafetyCarExceedingAllowedPace => 37,
+            InfringementType::VirtualSafetyCarExceedingAllowedPace => 38,
+            InfringementType::FormationLapBelowAllowedSpeed => 39,
+            InfringementType::RetiredMechanicalFailure => 40,
+            InfringementType::RetiredTerminallyDamaged => 41,
+            InfringementType::SafetyCarFallingTooFarBack => 42,
+            InfringementType::BlackFlagTimer => 43,
+            InfringementType::UnservedStopGoPenalty => 44,
+            InfringementType::UnservedDriveThroughPenalty => 45,
+            InfringementType::EngineComponentChange => 46,
+            InfringementType::GearboxChange => 47,
+            InfringementType::LeagueGridPenalty => 48,
+            InfringementType::RetryPenalty => 49,
+            InfringementType::IllegalTimeGain => 50,
+            InfringementType::MandatoryPitstop => 51,
+            InfringementType::Unknown(value) => value,
+        }
+    }
+}
+
+/// Details of a [`Penalty`] event.
+///
+/// ## Specification
+/// ```text
+/// penalty_type:      Penalty type – see Appendices
+/// infringement_type: Infringement type – see Appendices
+/// vehicle_idx:       Vehicle index of the car the penalty is applied to
+/// other_vehicle_idx: Vehicle index of the other car involved
+/// time:              Time gained, or time spent doing action in seconds
+/// lap_num:           Lap the penalty occurred on
+/// places_gained:     Number of places gained by this
+/// ```
+///
+/// [`Penalty`]: ./enum.Event.html#variant.Penalty
+#[derive(Debug, Clone, Copy, PartialEq, CopyGetters)]
+#[getset(get_copy = "pub")]
+pub struct Penalty {
+    penalty_type: PenaltyType,
+    infringement_type: InfringementType,
+    vehicle_idx: u8,
+    other_vehicle_idx: Option<u8>,
+    time: Option<u8>,
+    lap_num: u8,
+    places_gained: u8,
+}
+
+impl Penalty {
+    pub fn new<T: BufRead>(reader: &mut T) -> Result<Penalty, UnpackError> {
+        let penalty_type = PenaltyType::from(reader.read_u8().unwrap());
+        let infringement_type = InfringementType::from(reader.read_u8().unwrap());
+        let vehicle_idx = reader.read_u8().unwrap();
+        let other_vehicle_idx = match reader.read_u8().unwrap() {
+            255 => None,
+            idx => Some(idx),
+        };
+        let time = match reader.read_u8().unwrap() {
+            255 => None,
+            t => Some(t),
+        };
+        let lap_num = reader.read_u8().unwrap();
+        let places_gained = reader.read_u8().unwrap();
+
+        Ok(Penalty {
+            penalty_type,
+            infringement_type,
+            vehicle_idx,
+            other_vehicle_idx,
+            time,
+            lap_num,
+            places_gained,
+        })
+    }
+
+    pub(crate) fn encode<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_u8(self.penalty_type.into())?;
+        writer.write_u8(self.infringement_type.into())?;
+        writer.write_u8(self.vehicle_idx)?;
+        writer.write_u8(self.other_vehicle_idx.unwrap_or(255))?;
+        writer.write_u8(self.time.unwrap_or(255))?;
+        writer.write_u8(self.lap_num)?;
+        writer.write_u8(self.places_gained)?;
+
+        Ok(())
+    }
+}
+
 /// This packet gives details of events that happen during the course of a session.
 ///
 /// Frequency: When the event occurs
@@ -66,8 +447,11 @@ impl TryFrom<&str> for Event {
 /// event_string_code: Event string code
 ///
 /// # Event details - should be interpreted differently for each type
-/// vehicle_idx:       Vehicle index of car (valid for events: FTLP, RTMT, TMPT, RCWN)
+/// vehicle_idx:       Vehicle index of car (valid for events: FTLP, RTMT, TMPT, RCWN, PENA,
+///                    SPTP)
 /// lap_time:          Lap time is in seconds (valid for events: FTLP)
+/// penalty:           Penalty details (valid for events: PENA)
+/// speed:             Top speed achieved in kilometres per hour (valid for events: SPTP)
 /// ```
 #[derive(Debug, CopyGetters, Getters)]
 pub struct PacketEventData {
@@ -79,6 +463,10 @@ pub struct PacketEventData {
     vehicle_idx: Option<u8>,
     #[getset(get_copy = "pub")]
     lap_time: Option<f32>,
+    #[getset(get_copy = "pub")]
+    penalty: Option<Penalty>,
+    #[getset(get_copy = "pub")]
+    speed: Option<f32>,
 }
 
 impl PacketEventData {
@@ -88,6 +476,33 @@ impl PacketEventData {
     ) -> Result<PacketEventData, UnpackError> {
         let event = read_event(reader)?;
 
+        if event == Event::Penalty {
+            let penalty = Penalty::new(reader)?;
+
+            return Ok(PacketEventData {
+                header,
+                event,
+                vehicle_idx: Some(penalty.vehicle_idx),
+                lap_time: None,
+                penalty: Some(penalty),
+                speed: None,
+            });
+        }
+
+        if event == Event::SpeedTrap {
+            let vehicle_idx = reader.read_u8().unwrap();
+            let speed = reader.read_f32::<LittleEndian>().unwrap();
+
+            return Ok(PacketEventData {
+                header,
+                event,
+                vehicle_idx: Some(vehicle_idx),
+                lap_time: None,
+                penalty: None,
+                speed: Some(speed),
+            });
+        }
+
         let vehicle_idx = reader.read_u8().unwrap();
         let vehicle_idx = match event {
             Event::FastestLap | Event::Retirement | Event::TeamMateInPits | Event::RaceWinner => {
@@ -107,19 +522,50 @@ impl PacketEventData {
             event,
             vehicle_idx,
             lap_time,
+            penalty: None,
+            speed: None,
         })
     }
 }
 
+impl Encode for PacketEventData {
+    fn encode<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.header.encode(writer)?;
+        writer.write_all(&self.event.code_bytes())?;
+
+        // The event details are a union, sent with the size of its largest member.
+        let mut details = Vec::with_capacity(7);
+        match (self.penalty, self.speed) {
+            (Some(penalty), _) => penalty.encode(&mut details)?,
+            (None, Some(speed)) => {
+                details.write_u8(self.vehicle_idx.unwrap_or(0))?;
+                details.write_f32::<LittleEndian>(speed)?;
+            }
+            (None, None) => {
+                details.write_u8(self.vehicle_idx.unwrap_or(0))?;
+                details.write_f32::<LittleEndian>(self.lap_time.unwrap_or(0.0))?;
+            }
+        }
+
+        let details_size = if self.header.packet_format() >= 2020 {
+            7
+        } else {
+            5
+        };
+        details.resize(details_size.max(details.len()), 0);
+
+        writer.write_all(&details)
+    }
+}
+
 fn read_event<T: BufRead>(reader: &mut T) -> Result<Event, UnpackError> {
-    let code_str: String = vec![
-        reader.read_u8().unwrap() as char,
-        reader.read_u8().unwrap() as char,
-        reader.read_u8().unwrap() as char,
-        reader.read_u8().unwrap() as char,
-    ]
-    .iter()
-    .collect();
-
-    Event::try_from(code_str.as_str())
+    let mut code = [0; 4];
+    reader
+        .read_exact(&mut code)
+        .map_err(|e| UnpackError(format!("Invalid Event code: {:?}", e)))?;
+
+    match core::str::from_utf8(&code) {
+        Ok(code_str) => Event::try_from(code_str),
+        Err(_) => Ok(Event::Unknown(code)),
+    }
 }
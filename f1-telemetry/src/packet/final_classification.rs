@@ -0,0 +1,250 @@
+use alloc::vec::Vec;
+use byteorder::LittleEndian;
+use getset::{CopyGetters, Getters};
+
+use super::header::PacketHeader;
+#[cfg(not(feature = "std"))]
+use crate::float::F32Ext;
+use crate::io::{self, BufRead, ReadBytesExt, Write, WriteBytesExt};
+use crate::packet::car_status::{TyreCompound, TyreCompoundVisual};
+use crate::packet::encode::Encode;
+use crate::packet::lap::ResultStatus;
+use crate::packet::{num_cars, UnpackError};
+
+/// A stint on one set of tyres during the session.
+///
+/// ## Specification
+/// ```text
+/// actual_compound: Actual tyre compound used
+/// visual_compound: Visual tyre compound used
+/// end_lap:         Lap the stint ended on (F1 2022+)
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, CopyGetters)]
+#[getset(get_copy = "pub")]
+pub struct TyreStint {
+    actual_compound: TyreCompound,
+    visual_compound: TyreCompoundVisual,
+    end_lap: Option<u8>,
+}
+
+/// This type is used for the 22-element `classification_data` array of the
+/// [`PacketFinalClassificationData`] type.
+///
+/// The best lap time is sent in seconds in F1 2020, and in milliseconds from F1 2021;
+/// it is always exposed in seconds.
+///
+/// ## Specification
+/// ```text
+/// position:            Finishing position
+/// num_laps:            Number of laps completed
+/// grid_position:       Grid position of the car
+/// points:              Number of points scored
+/// num_pit_stops:       Number of pit stops made
+/// result_status:       Result status - 0 = invalid, 1 = inactive, 2 = active
+///                      3 = finished, 4 = disqualified, 5 = not classified
+///                      6 = retired
+///                      From F1 2021: 4 = did not finish, 5 = disqualified
+///                      6 = not classified, 7 = retired
+/// best_lap_time:       Best lap time of the session in seconds
+/// total_race_time:     Total race time in seconds without penalties
+/// penalties_time:      Total penalties accumulated in seconds
+/// num_penalties:       Number of penalties applied to this driver
+/// tyre_stints:         Tyre stints history (up to 8)
+/// ```
+///
+/// [`PacketFinalClassificationData`]: ./struct.PacketFinalClassificationData.html
+#[derive(Debug, Clone, CopyGetters, Getters)]
+pub struct FinalClassificationData {
+    #[getset(get_copy = "pub")]
+    position: u8,
+    #[getset(get_copy = "pub")]
+    num_laps: u8,
+    #[getset(get_copy = "pub")]
+    grid_position: u8,
+    #[getset(get_copy = "pub")]
+    points: u8,
+    #[getset(get_copy = "pub")]
+    num_pit_stops: u8,
+    #[getset(get_copy = "pub")]
+    result_status: ResultStatus,
+    #[getset(get_copy = "pub")]
+    best_lap_time: f32,
+    #[getset(get_copy = "pub")]
+    total_race_time: f64,
+    #[getset(get_copy = "pub")]
+    penalties_time: u8,
+    #[getset(get_copy = "pub")]
+    num_penalties: u8,
+    #[getset(get = "pub")]
+    tyre_stints: Vec<TyreStint>,
+}
+
+impl FinalClassificationData {
+    pub fn new<T: BufRead>(
+        reader: &mut T,
+        packet_format: u16,
+    ) -> Result<FinalClassificationData, UnpackError> {
+        let position = reader.read_u8().unwrap();
+        let num_laps = reader.read_u8().unwrap();
+        let grid_position = reader.read_u8().unwrap();
+        let points = reader.read_u8().unwrap();
+        let num_pit_stops = reader.read_u8().unwrap();
+        let result_status = ResultStatus::from_format(reader.read_u8().unwrap(), packet_format);
+        let best_lap_time = if packet_format >= 2021 {
+            reader.read_u32::<LittleEndian>().unwrap() as f32 / 1000.0
+        } else {
+            reader.read_f32::<LittleEndian>().unwrap()
+        };
+        let total_race_time = reader.read_f64::<LittleEndian>().unwrap();
+        let penalties_time = reader.read_u8().unwrap();
+        let num_penalties = reader.read_u8().unwrap();
+        let num_tyre_stints = reader.read_u8().unwrap() as usize;
+
+        let mut actual = [0u8; 8];
+        let mut visual = [0u8; 8];
+        let mut end_laps = [0u8; 8];
+        reader.read_exact(&mut actual).unwrap();
+        reader.read_exact(&mut visual).unwrap();
+        if packet_format >= 2022 {
+            reader.read_exact(&mut end_laps).unwrap();
+        }
+
+        let mut tyre_stints = Vec::with_capacity(num_tyre_stints);
+        for i in 0..num_tyre_stints.min(8) {
+            tyre_stints.push(TyreStint {
+                actual_compound: TyreCompound::from_format(actual[i], packet_format),
+                visual_compound: TyreCompoundVisual::from_format(visual[i], packet_format),
+                end_lap: if packet_format >= 2022 {
+                    Some(end_laps[i])
+                } else {
+                    None
+                },
+            });
+        }
+
+        Ok(FinalClassificationData {
+            position,
+            num_laps,
+            grid_position,
+            points,
+            num_pit_stops,
+            result_status,
+            best_lap_time,
+            total_race_time,
+            penalties_time,
+            num_penalties,
+            tyre_stints,
+        })
+    }
+
+    pub(crate) fn encode<W: Write>(&self, writer: &mut W, packet_format: u16) -> io::Result<()> {
+        writer.write_u8(self.position)?;
+        writer.write_u8(self.num_laps)?;
+        writer.write_u8(self.grid_position)?;
+        writer.write_u8(self.points)?;
+        writer.write_u8(self.num_pit_stops)?;
+        writer.write_u8(self.result_status.to_format(packet_format))?;
+        if packet_format >= 2021 {
+            writer.write_u32::<LittleEndian>((self.best_lap_time * 1000.0).round() as u32)?;
+        } else {
+            writer.write_f32::<LittleEndian>(self.best_lap_time)?;
+        }
+        writer.write_f64::<LittleEndian>(self.total_race_time)?;
+        writer.write_u8(self.penalties_time)?;
+        writer.write_u8(self.num_penalties)?;
+        writer.write_u8(self.tyre_stints.len() as u8)?;
+
+        let mut actual = [0u8; 8];
+        let mut visual = [0u8; 8];
+        let mut end_laps = [0u8; 8];
+        for (i, stint) in self.tyre_stints.iter().enumerate() {
+            actual[i] = stint.actual_compound.to_format(packet_format);
+            visual[i] = stint.visual_compound.to_format(packet_format);
+            end_laps[i] = stint.end_lap.unwrap_or(0);
+        }
+        writer.write_all(&actual)?;
+        writer.write_all(&visual)?;
+        if packet_format >= 2022 {
+            writer.write_all(&end_laps)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// This packet details the final classification at the end of the race (F1 2020+).
+///
+/// Frequency: Once at the end of a race
+///
+/// Size: 839 bytes
+///
+/// Version: 1
+///
+/// ## Specification
+/// ```text
+/// header:              Header
+/// num_cars:            Number of cars in the final classification
+/// classification_data: List of final classification data (22)
+/// ```
+#[derive(Debug, CopyGetters, Getters)]
+pub struct PacketFinalClassificationData {
+    #[getset(get = "pub")]
+    header: PacketHeader,
+    #[getset(get_copy = "pub")]
+    num_cars: u8,
+    #[getset(get = "pub")]
+    classification_data: Vec<FinalClassificationData>,
+}
+
+impl PacketFinalClassificationData {
+    pub fn new<T: BufRead>(
+        mut reader: &mut T,
+        header: PacketHeader,
+    ) -> Result<PacketFinalClassificationData, UnpackError> {
+        let packet_format = header.packet_format();
+        let array_size = num_cars(packet_format);
+
+        let num_cars = reader.read_u8().unwrap();
+
+        let mut classification_data = Vec::with_capacity(array_size);
+        for _ in 0..array_size {
+            let fcd = FinalClassificationData::new(&mut reader, packet_format)?;
+            classification_data.push(fcd);
+        }
+
+        Ok(PacketFinalClassificationData {
+            header,
+            num_cars,
+            classification_data,
+        })
+    }
+
+    /// Classification of the cars that took part in the session, ordered by
+    /// finishing position.
+    pub fn classification(&self) -> Vec<&FinalClassificationData> {
+        let mut classification: Vec<&FinalClassificationData> = self
+            .classification_data
+            .iter()
+            .filter(|c| {
+                c.position > 0
+                    && c.result_status != ResultStatus::Invalid
+                    && c.result_status != ResultStatus::Inactive
+            })
+            .collect();
+        classification.sort_by_key(|c| c.position);
+        classification
+    }
+}
+
+impl Encode for PacketFinalClassificationData {
+    fn encode<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        let packet_format = self.header.packet_format();
+        self.header.encode(writer)?;
+        writer.write_u8(self.num_cars)?;
+        for data in &self.classification_data {
+            data.encode(writer, packet_format)?;
+        }
+
+        Ok(())
+    }
+}
@@ -1,7 +1,8 @@
+use core::ops::{Add, Mul, Neg, Sub};
 use getset::CopyGetters;
-use std::convert::TryFrom;
 
-use crate::packet::UnpackError;
+#[cfg(not(feature = "std"))]
+use crate::float::F32Ext;
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
 pub enum Flag {
@@ -11,20 +12,33 @@ pub enum Flag {
     Yellow,
     Red,
     Invalid,
+    Unknown(i8),
 }
 
-impl TryFrom<i8> for Flag {
-    type Error = UnpackError;
+impl From<i8> for Flag {
+    fn from(value: i8) -> Self {
+        match value {
+            0 => Flag::None,
+            1 => Flag::Green,
+            2 => Flag::Blue,
+            3 => Flag::Yellow,
+            4 => Flag::Red,
+            -1 => Flag::Invalid,
+            _ => Flag::Unknown(value),
+        }
+    }
+}
 
-    fn try_from(value: i8) -> Result<Self, Self::Error> {
+impl From<Flag> for i8 {
+    fn from(value: Flag) -> Self {
         match value {
-            0 => Ok(Flag::None),
-            1 => Ok(Flag::Green),
-            2 => Ok(Flag::Blue),
-            3 => Ok(Flag::Yellow),
-            4 => Ok(Flag::Red),
-            -1 => Ok(Flag::Invalid),
-            _ => Err(UnpackError(format!("Invalid Flag value: {}", value))),
+            Flag::None => 0,
+            Flag::Green => 1,
+            Flag::Blue => 2,
+            Flag::Yellow => 3,
+            Flag::Red => 4,
+            Flag::Invalid => -1,
+            Flag::Unknown(value) => value,
         }
     }
 }
@@ -53,4 +67,168 @@ where
             front_right,
         }
     }
+
+    /// Apply `f` to the value of every wheel.
+    pub fn map<U: Clone + Copy, F: Fn(T) -> U>(self, f: F) -> WheelData<U> {
+        WheelData {
+            rear_left: f(self.rear_left),
+            rear_right: f(self.rear_right),
+            front_left: f(self.front_left),
+            front_right: f(self.front_right),
+        }
+    }
+
+    /// Values in the order of the wire format: RL, RR, FL, FR.
+    pub fn to_array(self) -> [T; 4] {
+        [
+            self.rear_left,
+            self.rear_right,
+            self.front_left,
+            self.front_right,
+        ]
+    }
+}
+
+/// Vector in 3D space, e.g. a world space position or a G-force.
+#[derive(Debug, Clone, Copy, Default, PartialEq, CopyGetters)]
+#[getset(get_copy = "pub")]
+pub struct Vec3 {
+    x: f32,
+    y: f32,
+    z: f32,
+}
+
+impl Vec3 {
+    pub fn new(x: f32, y: f32, z: f32) -> Vec3 {
+        Vec3 { x, y, z }
+    }
+
+    pub fn length(self) -> f32 {
+        self.dot(self).sqrt()
+    }
+
+    /// Vector of length 1 in the same direction, or the zero vector itself.
+    pub fn normalized(self) -> Vec3 {
+        let length = self.length();
+        if length == 0.0 {
+            return self;
+        }
+
+        self * (1.0 / length)
+    }
+
+    pub fn dot(self, other: Vec3) -> f32 {
+        self.x * other.x + self.y * other.y + self.z * other.z
+    }
+
+    pub fn cross(self, other: Vec3) -> Vec3 {
+        Vec3::new(
+            self.y * other.z - self.z * other.y,
+            self.z * other.x - self.x * other.z,
+            self.x * other.y - self.y * other.x,
+        )
+    }
+
+    pub fn distance(self, other: Vec3) -> f32 {
+        (self - other).length()
+    }
+
+    pub fn to_array(self) -> [f32; 3] {
+        [self.x, self.y, self.z]
+    }
+}
+
+impl From<[f32; 3]> for Vec3 {
+    fn from(v: [f32; 3]) -> Self {
+        Vec3::new(v[0], v[1], v[2])
+    }
+}
+
+impl From<Vec3> for [f32; 3] {
+    fn from(v: Vec3) -> Self {
+        v.to_array()
+    }
+}
+
+impl Add for Vec3 {
+    type Output = Vec3;
+
+    fn add(self, other: Vec3) -> Vec3 {
+        Vec3::new(self.x + other.x, self.y + other.y, self.z + other.z)
+    }
+}
+
+impl Sub for Vec3 {
+    type Output = Vec3;
+
+    fn sub(self, other: Vec3) -> Vec3 {
+        Vec3::new(self.x - other.x, self.y - other.y, self.z - other.z)
+    }
+}
+
+impl Mul<f32> for Vec3 {
+    type Output = Vec3;
+
+    fn mul(self, k: f32) -> Vec3 {
+        Vec3::new(self.x * k, self.y * k, self.z * k)
+    }
+}
+
+impl Neg for Vec3 {
+    type Output = Vec3;
+
+    fn neg(self) -> Vec3 {
+        Vec3::new(-self.x, -self.y, -self.z)
+    }
+}
+
+/// Rotation in 3D space, as a unit quaternion.
+#[derive(Debug, Clone, Copy, PartialEq, CopyGetters)]
+#[getset(get_copy = "pub")]
+pub struct Quaternion {
+    w: f32,
+    x: f32,
+    y: f32,
+    z: f32,
+}
+
+impl Quaternion {
+    pub fn new(w: f32, x: f32, y: f32, z: f32) -> Quaternion {
+        Quaternion { w, x, y, z }
+    }
+
+    /// Rotation taking the X, Y and Z axes to `right`, `up` and `forward`, which
+    /// should be orthonormal.
+    pub fn from_basis(right: Vec3, up: Vec3, forward: Vec3) -> Quaternion {
+        // Rotation matrix with the basis vectors as columns
+        let [m00, m10, m20] = right.to_array();
+        let [m01, m11, m21] = up.to_array();
+        let [m02, m12, m22] = forward.to_array();
+
+        let trace = m00 + m11 + m22;
+        let (w, x, y, z) = if trace > 0.0 {
+            let s = (trace + 1.0).sqrt() * 2.0;
+            (0.25 * s, (m21 - m12) / s, (m02 - m20) / s, (m10 - m01) / s)
+        } else if m00 > m11 && m00 > m22 {
+            let s = (1.0 + m00 - m11 - m22).sqrt() * 2.0;
+            ((m21 - m12) / s, 0.25 * s, (m01 + m10) / s, (m02 + m20) / s)
+        } else if m11 > m22 {
+            let s = (1.0 + m11 - m00 - m22).sqrt() * 2.0;
+            ((m02 - m20) / s, (m01 + m10) / s, 0.25 * s, (m12 + m21) / s)
+        } else {
+            let s = (1.0 + m22 - m00 - m11).sqrt() * 2.0;
+            ((m10 - m01) / s, (m02 + m20) / s, (m12 + m21) / s, 0.25 * s)
+        };
+
+        let norm = (w * w + x * x + y * y + z * z).sqrt();
+        Quaternion::new(w / norm, x / norm, y / norm, z / norm)
+    }
+
+    /// Vector rotated by the rotation.
+    pub fn rotate(self, v: Vec3) -> Vec3 {
+        let q = Vec3::new(self.x, self.y, self.z);
+        let t = q.cross(v) * 2.0;
+
+        v + t * self.w + q.cross(t)
+    }
 }
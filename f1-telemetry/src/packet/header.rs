@@ -1,20 +1,57 @@
-use byteorder::{LittleEndian, ReadBytesExt};
+use crate::io::{self, BufRead, ReadBytesExt, Write, WriteBytesExt};
+use byteorder::LittleEndian;
+use core::fmt;
 use getset::CopyGetters;
-use std::io::BufRead;
+
+/// Unique identifier of a session, as found in every packet header.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct SessionIdentifier(u64);
+
+impl SessionIdentifier {
+    pub fn new(session_uid: u64) -> SessionIdentifier {
+        SessionIdentifier(session_uid)
+    }
+
+    pub fn value(self) -> u64 {
+        self.0
+    }
+}
+
+impl fmt::Display for SessionIdentifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:016x}", self.0)
+    }
+}
+
+/// One of the players of a session. The secondary player only exists in
+/// split-screen sessions.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum Player {
+    Primary,
+    Secondary,
+}
 
 /// The header for each of the UDP telemetry packets.
 ///
 /// ## Specification
 /// ```text
-/// packet_format:      2019
-/// game_major_version: game major version - "x.00"
-/// game_minor_version: game minor version - "1.xX"
-/// packet_version:     version of this packet type, all start from 1
-/// packet_id:          identifier for the packet type
-/// session_uid:        unique identifier for the session
-/// session_time:       session timestamp
-/// frame_identifier:   identifier for the frame the data was retrieved on
-/// player_car_index:   index of player's car in the array
+/// packet_format:              2019
+/// game_year:                  last two digits of the game year, e.g. 23. Only
+///                             sent from F1 2023 onwards.
+/// game_major_version:         game major version - "x.00"
+/// game_minor_version:         game minor version - "1.xX"
+/// packet_version:             version of this packet type, all start from 1
+/// packet_id:                  identifier for the packet type
+/// session_uid:                unique identifier for the session
+/// session_time:               session timestamp
+/// frame_identifier:           identifier for the frame the data was retrieved on
+/// overall_frame_identifier:   overall identifier for the frame the data was
+///                             retrieved on, that doesn't go back after
+///                             flashbacks. Only sent from F1 2023 onwards.
+/// player_car_index:           index of player's car in the array
+/// secondary_player_car_index: index of secondary player's car in the array
+///                             (splitscreen), 255 if no second player. Only sent
+///                             from F1 2020 onwards.
 /// ```
 ///
 /// Possible `packet_id` values: [`PacketType`].
@@ -24,6 +61,7 @@ use std::io::BufRead;
 #[getset(get_copy = "pub")]
 pub struct PacketHeader {
     packet_format: u16,
+    game_year: Option<u8>,
     game_major_version: u8,
     game_minor_version: u8,
     packet_version: u8,
@@ -31,12 +69,19 @@ pub struct PacketHeader {
     session_uid: u64,
     session_time: f32,
     frame_identifier: u32,
+    overall_frame_identifier: Option<u32>,
     player_car_index: u8,
+    secondary_player_car_index: Option<u8>,
 }
 
 impl PacketHeader {
     pub fn new<T: BufRead>(reader: &mut T) -> PacketHeader {
         let packet_format = reader.read_u16::<LittleEndian>().unwrap();
+        let game_year = if packet_format >= 2023 {
+            Some(reader.read_u8().unwrap())
+        } else {
+            None
+        };
         let game_major_version = reader.read_u8().unwrap();
         let game_minor_version = reader.read_u8().unwrap();
         let packet_version = reader.read_u8().unwrap();
@@ -44,10 +89,24 @@ impl PacketHeader {
         let session_uid = reader.read_u64::<LittleEndian>().unwrap();
         let session_time = reader.read_f32::<LittleEndian>().unwrap();
         let frame_identifier = reader.read_u32::<LittleEndian>().unwrap();
+        let overall_frame_identifier = if packet_format >= 2023 {
+            Some(reader.read_u32::<LittleEndian>().unwrap())
+        } else {
+            None
+        };
         let player_car_index = reader.read_u8().unwrap();
+        let secondary_player_car_index = if packet_format >= 2020 {
+            match reader.read_u8().unwrap() {
+                255 => None,
+                idx => Some(idx),
+            }
+        } else {
+            None
+        };
 
         PacketHeader {
             packet_format,
+            game_year,
             game_major_version,
             game_minor_version,
             packet_version,
@@ -55,7 +114,54 @@ impl PacketHeader {
             session_uid,
             session_time,
             frame_identifier,
+            overall_frame_identifier,
             player_car_index,
+            secondary_player_car_index,
+        }
+    }
+
+    pub fn session_id(&self) -> SessionIdentifier {
+        SessionIdentifier(self.session_uid)
+    }
+
+    /// Whether both packets were sent during the same session.
+    pub fn is_same_session(&self, other: &PacketHeader) -> bool {
+        self.session_uid == other.session_uid
+    }
+
+    /// Index of a player's car in the per-car arrays, if that player is in the session.
+    pub fn car_index(&self, player: Player) -> Option<u8> {
+        match player {
+            Player::Primary => Some(self.player_car_index),
+            Player::Secondary => self.secondary_player_car_index,
         }
     }
+
+    /// Whether two players share the screen in this session.
+    pub fn is_split_screen(&self) -> bool {
+        self.secondary_player_car_index.is_some()
+    }
+
+    pub(crate) fn encode<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_u16::<LittleEndian>(self.packet_format)?;
+        if self.packet_format >= 2023 {
+            writer.write_u8(self.game_year.unwrap_or(0))?;
+        }
+        writer.write_u8(self.game_major_version)?;
+        writer.write_u8(self.game_minor_version)?;
+        writer.write_u8(self.packet_version)?;
+        writer.write_u8(self.packet_id)?;
+        writer.write_u64::<LittleEndian>(self.session_uid)?;
+        writer.write_f32::<LittleEndian>(self.session_time)?;
+        writer.write_u32::<LittleEndian>(self.frame_identifier)?;
+        if self.packet_format >= 2023 {
+            writer.write_u32::<LittleEndian>(self.overall_frame_identifier.unwrap_or(0))?;
+        }
+        writer.write_u8(self.player_car_index)?;
+        if self.packet_format >= 2020 {
+            writer.write_u8(self.secondary_player_car_index.unwrap_or(255))?;
+        }
+
+        Ok(())
+    }
 }
@@ -1,27 +1,40 @@
-use byteorder::{LittleEndian, ReadBytesExt};
+use alloc::vec::Vec;
+use byteorder::LittleEndian;
 use getset::{CopyGetters, Getters};
-use std::convert::TryFrom;
-use std::io::BufRead;
 
-use super::header::PacketHeader;
-use crate::packet::UnpackError;
+use super::header::{PacketHeader, Player};
+#[cfg(not(feature = "std"))]
+use crate::float::F32Ext;
+use crate::io::{self, BufRead, ReadBytesExt, Write, WriteBytesExt};
+use crate::packet::encode::Encode;
+use crate::packet::{num_cars, UnpackError};
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
 pub enum PitStatus {
     None,
     Pitting,
     PitLane,
+    Unknown(u8),
 }
 
-impl TryFrom<u8> for PitStatus {
-    type Error = UnpackError;
+impl From<u8> for PitStatus {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => PitStatus::None,
+            1 => PitStatus::Pitting,
+            2 => PitStatus::PitLane,
+            _ => PitStatus::Unknown(value),
+        }
+    }
+}
 
-    fn try_from(value: u8) -> Result<Self, Self::Error> {
+impl From<PitStatus> for u8 {
+    fn from(value: PitStatus) -> Self {
         match value {
-            0 => Ok(PitStatus::None),
-            1 => Ok(PitStatus::Pitting),
-            2 => Ok(PitStatus::PitLane),
-            _ => Err(UnpackError(format!("Invalid PitStatus value: {}", value))),
+            PitStatus::None => 0,
+            PitStatus::Pitting => 1,
+            PitStatus::PitLane => 2,
+            PitStatus::Unknown(value) => value,
         }
     }
 }
@@ -33,22 +46,31 @@ pub enum DriverStatus {
     InLap,
     OutLap,
     OnTrack,
+    Unknown(u8),
 }
 
-impl TryFrom<u8> for DriverStatus {
-    type Error = UnpackError;
+impl From<u8> for DriverStatus {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => DriverStatus::Garage,
+            1 => DriverStatus::FlyingLap,
+            2 => DriverStatus::InLap,
+            3 => DriverStatus::OutLap,
+            4 => DriverStatus::OnTrack,
+            _ => DriverStatus::Unknown(value),
+        }
+    }
+}
 
-    fn try_from(value: u8) -> Result<Self, Self::Error> {
+impl From<DriverStatus> for u8 {
+    fn from(value: DriverStatus) -> Self {
         match value {
-            0 => Ok(DriverStatus::Garage),
-            1 => Ok(DriverStatus::FlyingLap),
-            2 => Ok(DriverStatus::InLap),
-            3 => Ok(DriverStatus::OutLap),
-            4 => Ok(DriverStatus::OnTrack),
-            _ => Err(UnpackError(format!(
-                "Invalid DriverStatus value: {}",
-                value
-            ))),
+            DriverStatus::Garage => 0,
+            DriverStatus::FlyingLap => 1,
+            DriverStatus::InLap => 2,
+            DriverStatus::OutLap => 3,
+            DriverStatus::OnTrack => 4,
+            DriverStatus::Unknown(value) => value,
         }
     }
 }
@@ -59,61 +81,143 @@ pub enum ResultStatus {
     Inactive,
     Active,
     Finished,
+    DidNotFinish,
     Disqualified,
     NotClassified,
     Retired,
+    Unknown(u8),
+}
+
+impl ResultStatus {
+    /// Decode a result status for a given packet format. `DidNotFinish` was
+    /// added in F1 2021, shifting the values that follow it.
+    pub fn from_format(value: u8, packet_format: u16) -> ResultStatus {
+        if packet_format < 2021 {
+            return ResultStatus::from(value);
+        }
+
+        match value {
+            4 => ResultStatus::DidNotFinish,
+            5 => ResultStatus::Disqualified,
+            6 => ResultStatus::NotClassified,
+            7 => ResultStatus::Retired,
+            _ => ResultStatus::from(value),
+        }
+    }
+
+    /// Encode a result status for a given packet format, the inverse of
+    /// [`from_format`]. `DidNotFinish` does not exist before F1 2021 and is
+    /// encoded as retired.
+    ///
+    /// [`from_format`]: #method.from_format
+    pub fn to_format(self, packet_format: u16) -> u8 {
+        if packet_format < 2021 {
+            return u8::from(self);
+        }
+
+        match self {
+            ResultStatus::DidNotFinish => 4,
+            ResultStatus::Disqualified => 5,
+            ResultStatus::NotClassified => 6,
+            ResultStatus::Retired => 7,
+            _ => u8::from(self),
+        }
+    }
 }
 
-impl TryFrom<u8> for ResultStatus {
-    type Error = UnpackError;
+impl From<u8> for ResultStatus {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => ResultStatus::Invalid,
+            1 => ResultStatus::Inactive,
+            2 => ResultStatus::Active,
+            3 => ResultStatus::Finished,
+            4 => ResultStatus::Disqualified,
+            5 => ResultStatus::NotClassified,
+            6 => ResultStatus::Retired,
+            _ => ResultStatus::Unknown(value),
+        }
+    }
+}
 
-    fn try_from(value: u8) -> Result<Self, Self::Error> {
+impl From<ResultStatus> for u8 {
+    fn from(value: ResultStatus) -> Self {
         match value {
-            0 => Ok(ResultStatus::Invalid),
-            1 => Ok(ResultStatus::Inactive),
-            2 => Ok(ResultStatus::Active),
-            3 => Ok(ResultStatus::Finished),
-            4 => Ok(ResultStatus::Disqualified),
-            5 => Ok(ResultStatus::NotClassified),
-            6 => Ok(ResultStatus::Retired),
-            _ => Err(UnpackError(format!(
-                "Invalid ResultStatus value: {}",
-                value
-            ))),
+            ResultStatus::Invalid => 0,
+            ResultStatus::Inactive => 1,
+            ResultStatus::Active => 2,
+            ResultStatus::Finished => 3,
+            ResultStatus::Disqualified => 4,
+            ResultStatus::NotClassified => 5,
+            ResultStatus::DidNotFinish | ResultStatus::Retired => 6,
+            ResultStatus::Unknown(value) => value,
         }
     }
 }
 
-/// This type is used for the 20-element `lap_data` array of the [`PacketLapData`] type.
+/// This type is used for the 20-element (22 from F1 2020) `lap_data` array of the
+/// [`PacketLapData`] type.
+///
+/// Times are sent in seconds up to F1 2020 and in milliseconds from F1 2021,
+/// with a separate minutes part for the sector times from F1 2023. They are
+/// all exposed in seconds.
+///
+/// Fields that are only sent in some formats are `None` in the others, except
+/// for the best lap time that F1 2021 moved to the session history packet,
+/// which is 0.
 ///
-/// Size: 41 bytes
+/// Size: 41 bytes (F1 2019), 53 bytes (F1 2020), 43 bytes (F1 2021-2022),
+/// 50 bytes (F1 2023), 57 bytes (F1 2024)
 ///
 /// Version: 1
 ///
 /// ## Specification
 /// ```text
-/// last_lap_time:       Last lap time in seconds
-/// current_lap_time:    Current time around the lap in seconds
-/// best_lap_time:       Best lap time of the session in seconds
-/// sector_1_time:       Sector 1 time in seconds
-/// sector_2_time:       Sector 2 time in seconds
-/// lap_distance:        Distance vehicle is around current lap in metres – could
-///                      be negative if line hasn’t been crossed yet
-/// total_distance:      Total distance travelled in session in metres – could
-///                      be negative if line hasn’t been crossed yet
-/// safety_car_delta:    Delta in seconds for safety car
-/// car_position:        Car race position
-/// current_lap_num:     Current lap number
-/// pit_status:          0 = none, 1 = pitting, 2 = in pit area
-/// sector:              0 = sector1, 1 = sector2, 2 = sector3
-/// current_lap_invalid: Current lap invalid - 0 = valid, 1 = invalid
-/// penalties:           Accumulated time penalties in seconds to be added
-/// grid_position:       Grid position the vehicle started the race in
-/// driver_status:       Status of driver - 0 = in garage, 1 = flying lap
-///                      2 = in lap, 3 = out lap, 4 = on track
-/// result_status:       Result status - 0 = invalid, 1 = inactive, 2 = active
-///                      3 = finished, 4 = disqualified, 5 = not classified
-///                      6 = retired
+/// last_lap_time:                   Last lap time in seconds
+/// current_lap_time:                Current time around the lap in seconds
+/// best_lap_time:                   Best lap time of the session in seconds (up to F1 2020)
+/// sector_1_time:                   Sector 1 time in seconds
+/// sector_2_time:                   Sector 2 time in seconds
+/// best_lap_num:                    Lap number best time achieved on (F1 2020)
+/// best_lap_sector_times:           Sector times of the best lap in seconds (F1 2020)
+/// best_overall_sector_times:       Best sector times of the session in seconds (F1 2020)
+/// best_overall_sector_lap_nums:    Lap numbers of the best sector times (F1 2020)
+/// delta_to_car_in_front:           Time delta to car in front in seconds (F1 2023+)
+/// delta_to_race_leader:            Time delta to race leader in seconds (F1 2023+)
+/// lap_distance:                    Distance vehicle is around current lap in metres – could
+///                                  be negative if line hasn’t been crossed yet
+/// total_distance:                  Total distance travelled in session in metres – could
+///                                  be negative if line hasn’t been crossed yet
+/// safety_car_delta:                Delta in seconds for safety car
+/// car_position:                    Car race position
+/// current_lap_num:                 Current lap number
+/// pit_status:                      0 = none, 1 = pitting, 2 = in pit area
+/// num_pit_stops:                   Number of pit stops taken in this race (F1 2021+)
+/// sector:                          0 = sector1, 1 = sector2, 2 = sector3
+/// current_lap_invalid:             Current lap invalid - 0 = valid, 1 = invalid
+/// penalties:                       Accumulated time penalties in seconds to be added
+/// warnings:                        Accumulated number of warnings issued (F1 2021+)
+/// corner_cutting_warnings:         Accumulated number of corner cutting warnings
+///                                  issued (F1 2023+)
+/// num_unserved_drive_through_pens: Number of drive through penalties left to serve (F1 2021+)
+/// num_unserved_stop_go_pens:       Number of stop go penalties left to serve (F1 2021+)
+/// grid_position:                   Grid position the vehicle started the race in
+/// driver_status:                   Status of driver - 0 = in garage, 1 = flying lap
+///                                  2 = in lap, 3 = out lap, 4 = on track
+/// result_status:                   Result status - 0 = invalid, 1 = inactive, 2 = active
+///                                  3 = finished, 4 = did not finish (F1 2021+)
+///                                  4/5 = disqualified, 5/6 = not classified
+///                                  6/7 = retired
+/// pit_lane_timer_active:           Pit lane timing, 0 = inactive, 1 = active (F1 2021+)
+/// pit_lane_time_in_lane:           If active, the current time spent in the pit lane
+///                                  in seconds (F1 2021+)
+/// pit_stop_timer:                  Time of the actual pit stop in seconds (F1 2021+)
+/// pit_stop_should_serve_pen:       Whether the car should serve a penalty at this
+///                                  stop (F1 2021+)
+/// speed_trap_fastest_speed:        Fastest speed through speed trap for this car in
+///                                  kmph (F1 2024+)
+/// speed_trap_fastest_lap:          Lap number of the fastest speed, 255 if not set
+///                                  (F1 2024+)
 /// ```
 /// [`PacketLapData`]: ./struct.PacketLapData.html
 #[derive(Debug, CopyGetters)]
@@ -124,39 +228,142 @@ pub struct LapData {
     best_lap_time: f32,
     sector_1_time: f32,
     sector_2_time: f32,
+    best_lap_num: Option<u8>,
+    best_lap_sector_times: Option<[f32; 3]>,
+    best_overall_sector_times: Option<[f32; 3]>,
+    best_overall_sector_lap_nums: Option<[u8; 3]>,
+    delta_to_car_in_front: Option<f32>,
+    delta_to_race_leader: Option<f32>,
     lap_distance: f32,
     total_distance: f32,
     safety_car_delta: f32,
     car_position: u8,
     current_lap_num: u8,
     pit_status: PitStatus,
+    num_pit_stops: Option<u8>,
     sector: u8,
     current_lap_invalid: bool,
     penalties: u8,
+    warnings: Option<u8>,
+    corner_cutting_warnings: Option<u8>,
+    num_unserved_drive_through_pens: Option<u8>,
+    num_unserved_stop_go_pens: Option<u8>,
     grid_position: u8,
     driver_status: DriverStatus,
     result_status: ResultStatus,
+    pit_lane_timer_active: Option<bool>,
+    pit_lane_time_in_lane: Option<f32>,
+    pit_stop_timer: Option<f32>,
+    pit_stop_should_serve_pen: Option<bool>,
+    speed_trap_fastest_speed: Option<f32>,
+    speed_trap_fastest_lap: Option<u8>,
 }
 
 impl LapData {
-    pub fn new<T: BufRead>(reader: &mut T) -> Result<LapData, UnpackError> {
-        let last_lap_time = reader.read_f32::<LittleEndian>().unwrap();
-        let current_lap_time = reader.read_f32::<LittleEndian>().unwrap();
-        let best_lap_time = reader.read_f32::<LittleEndian>().unwrap();
-        let sector_1_time = reader.read_f32::<LittleEndian>().unwrap();
-        let sector_2_time = reader.read_f32::<LittleEndian>().unwrap();
+    pub fn new<T: BufRead>(reader: &mut T, packet_format: u16) -> Result<LapData, UnpackError> {
+        let (last_lap_time, current_lap_time) = if packet_format >= 2021 {
+            (read_millis(reader), read_millis(reader))
+        } else {
+            (
+                reader.read_f32::<LittleEndian>().unwrap(),
+                reader.read_f32::<LittleEndian>().unwrap(),
+            )
+        };
+
+        let mut best_lap_time = 0.0;
+        let mut best_lap_num = None;
+        let mut best_lap_sector_times = None;
+        let mut best_overall_sector_times = None;
+        let mut best_overall_sector_lap_nums = None;
+        let mut delta_to_car_in_front = None;
+        let mut delta_to_race_leader = None;
+
+        let (sector_1_time, sector_2_time) = if packet_format >= 2023 {
+            let sector_1_time = read_minutes_millis(reader);
+            let sector_2_time = read_minutes_millis(reader);
+            if packet_format >= 2024 {
+                delta_to_car_in_front = Some(read_minutes_millis(reader));
+                delta_to_race_leader = Some(read_minutes_millis(reader));
+            } else {
+                delta_to_car_in_front = Some(read_short_millis(reader));
+                delta_to_race_leader = Some(read_short_millis(reader));
+            }
+
+            (sector_1_time, sector_2_time)
+        } else if packet_format >= 2020 {
+            let sector_1_time = read_short_millis(reader);
+            let sector_2_time = read_short_millis(reader);
+            if packet_format == 2020 {
+                best_lap_time = reader.read_f32::<LittleEndian>().unwrap();
+                best_lap_num = Some(reader.read_u8().unwrap());
+                best_lap_sector_times = Some([
+                    read_short_millis(reader),
+                    read_short_millis(reader),
+                    read_short_millis(reader),
+                ]);
+
+                let mut sector_times = [0.0; 3];
+                let mut lap_nums = [0; 3];
+                for i in 0..3 {
+                    sector_times[i] = read_short_millis(reader);
+                    lap_nums[i] = reader.read_u8().unwrap();
+                }
+                best_overall_sector_times = Some(sector_times);
+                best_overall_sector_lap_nums = Some(lap_nums);
+            }
+
+            (sector_1_time, sector_2_time)
+        } else {
+            best_lap_time = reader.read_f32::<LittleEndian>().unwrap();
+
+            (
+                reader.read_f32::<LittleEndian>().unwrap(),
+                reader.read_f32::<LittleEndian>().unwrap(),
+            )
+        };
+
         let lap_distance = reader.read_f32::<LittleEndian>().unwrap();
         let total_distance = reader.read_f32::<LittleEndian>().unwrap();
         let safety_car_delta = reader.read_f32::<LittleEndian>().unwrap();
         let car_position = reader.read_u8().unwrap();
         let current_lap_num = reader.read_u8().unwrap();
-        let pit_status = PitStatus::try_from(reader.read_u8().unwrap())?;
+        let pit_status = PitStatus::from(reader.read_u8().unwrap());
+        let num_pit_stops = read_u8_since(reader, packet_format, 2021);
         let sector = reader.read_u8().unwrap();
         let current_lap_invalid = reader.read_u8().unwrap() == 1;
         let penalties = reader.read_u8().unwrap();
+        let warnings = read_u8_since(reader, packet_format, 2021);
+        let corner_cutting_warnings = read_u8_since(reader, packet_format, 2023);
+        let num_unserved_drive_through_pens = read_u8_since(reader, packet_format, 2021);
+        let num_unserved_stop_go_pens = read_u8_since(reader, packet_format, 2021);
         let grid_position = reader.read_u8().unwrap();
-        let driver_status = DriverStatus::try_from(reader.read_u8().unwrap())?;
-        let result_status = ResultStatus::try_from(reader.read_u8().unwrap())?;
+        let driver_status = DriverStatus::from(reader.read_u8().unwrap());
+        let result_status = ResultStatus::from_format(reader.read_u8().unwrap(), packet_format);
+
+        let (
+            pit_lane_timer_active,
+            pit_lane_time_in_lane,
+            pit_stop_timer,
+            pit_stop_should_serve_pen,
+        ) = if packet_format >= 2021 {
+            (
+                Some(reader.read_u8().unwrap() == 1),
+                Some(read_short_millis(reader)),
+                Some(read_short_millis(reader)),
+                Some(reader.read_u8().unwrap() == 1),
+            )
+        } else {
+            (None, None, None, None)
+        };
+
+        let (speed_trap_fastest_speed, speed_trap_fastest_lap) = if packet_format >= 2024 {
+            (
+                Some(reader.read_f32::<LittleEndian>().unwrap()),
+                Some(reader.read_u8().unwrap()),
+            )
+        } else {
+            (None, None)
+        };
 
         Ok(LapData {
             last_lap_time,
@@ -164,40 +371,191 @@ impl LapData {
             best_lap_time,
             sector_1_time,
             sector_2_time,
+            best_lap_num,
+            best_lap_sector_times,
+            best_overall_sector_times,
+            best_overall_sector_lap_nums,
+            delta_to_car_in_front,
+            delta_to_race_leader,
             lap_distance,
             total_distance,
             safety_car_delta,
             car_position,
             current_lap_num,
             pit_status,
+            num_pit_stops,
             sector,
             current_lap_invalid,
             penalties,
+            warnings,
+            corner_cutting_warnings,
+            num_unserved_drive_through_pens,
+            num_unserved_stop_go_pens,
             grid_position,
             driver_status,
             result_status,
+            pit_lane_timer_active,
+            pit_lane_time_in_lane,
+            pit_stop_timer,
+            pit_stop_should_serve_pen,
+            speed_trap_fastest_speed,
+            speed_trap_fastest_lap,
         })
     }
+
+    pub fn position_context(&self) -> PositionContext {
+        PositionContext::from(self)
+    }
+
+    pub(crate) fn encode<W: Write>(&self, writer: &mut W, packet_format: u16) -> io::Result<()> {
+        if packet_format >= 2021 {
+            write_millis(writer, self.last_lap_time)?;
+            write_millis(writer, self.current_lap_time)?;
+        } else {
+            writer.write_f32::<LittleEndian>(self.last_lap_time)?;
+            writer.write_f32::<LittleEndian>(self.current_lap_time)?;
+        }
+
+        if packet_format >= 2023 {
+            write_minutes_millis(writer, self.sector_1_time)?;
+            write_minutes_millis(writer, self.sector_2_time)?;
+            let deltas = [
+                self.delta_to_car_in_front.unwrap_or(0.0),
+                self.delta_to_race_leader.unwrap_or(0.0),
+            ];
+            for delta in deltas.iter() {
+                if packet_format >= 2024 {
+                    write_minutes_millis(writer, *delta)?;
+                } else {
+                    write_short_millis(writer, *delta)?;
+                }
+            }
+        } else if packet_format >= 2020 {
+            write_short_millis(writer, self.sector_1_time)?;
+            write_short_millis(writer, self.sector_2_time)?;
+            if packet_format == 2020 {
+                writer.write_f32::<LittleEndian>(self.best_lap_time)?;
+                writer.write_u8(self.best_lap_num.unwrap_or(0))?;
+                for time in self.best_lap_sector_times.unwrap_or_default().iter() {
+                    write_short_millis(writer, *time)?;
+                }
+
+                let sector_times = self.best_overall_sector_times.unwrap_or_default();
+                let lap_nums = self.best_overall_sector_lap_nums.unwrap_or_default();
+                for i in 0..3 {
+                    write_short_millis(writer, sector_times[i])?;
+                    writer.write_u8(lap_nums[i])?;
+                }
+            }
+        } else {
+            writer.write_f32::<LittleEndian>(self.best_lap_time)?;
+            writer.write_f32::<LittleEndian>(self.sector_1_time)?;
+            writer.write_f32::<LittleEndian>(self.sector_2_time)?;
+        }
+
+        writer.write_f32::<LittleEndian>(self.lap_distance)?;
+        writer.write_f32::<LittleEndian>(self.total_distance)?;
+        writer.write_f32::<LittleEndian>(self.safety_car_delta)?;
+        writer.write_u8(self.car_position)?;
+        writer.write_u8(self.current_lap_num)?;
+        writer.write_u8(self.pit_status.into())?;
+        write_u8_since(writer, packet_format, 2021, self.num_pit_stops)?;
+        writer.write_u8(self.sector)?;
+        writer.write_u8(self.current_lap_invalid as u8)?;
+        writer.write_u8(self.penalties)?;
+        write_u8_since(writer, packet_format, 2021, self.warnings)?;
+        write_u8_since(writer, packet_format, 2023, self.corner_cutting_warnings)?;
+        write_u8_since(
+            writer,
+            packet_format,
+            2021,
+            self.num_unserved_drive_through_pens,
+        )?;
+        write_u8_since(writer, packet_format, 2021, self.num_unserved_stop_go_pens)?;
+        writer.write_u8(self.grid_position)?;
+        writer.write_u8(self.driver_status.into())?;
+        writer.write_u8(self.result_status.to_format(packet_format))?;
+
+        if packet_format >= 2021 {
+            writer.write_u8(self.pit_lane_timer_active.unwrap_or(false) as u8)?;
+            write_short_millis(writer, self.pit_lane_time_in_lane.unwrap_or(0.0))?;
+            write_short_millis(writer, self.pit_stop_timer.unwrap_or(0.0))?;
+            writer.write_u8(self.pit_stop_should_serve_pen.unwrap_or(false) as u8)?;
+        }
+
+        if packet_format >= 2024 {
+            writer.write_f32::<LittleEndian>(self.speed_trap_fastest_speed.unwrap_or(0.0))?;
+            writer.write_u8(self.speed_trap_fastest_lap.unwrap_or(255))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Compact snapshot of where a driver is in the race, built from their [`LapData`].
+///
+/// ## Specification
+/// ```text
+/// position:     Car race position
+/// lap:          Current lap number
+/// sector:       0 = sector1, 1 = sector2, 2 = sector3
+/// lap_distance: Distance vehicle is around current lap in metres
+/// pit_status:   0 = none, 1 = pitting, 2 = in pit area
+/// last_lap:     Last lap time in seconds
+/// ```
+/// [`LapData`]: ./struct.LapData.html
+#[derive(Debug, Clone, Copy, PartialEq, CopyGetters)]
+#[getset(get_copy = "pub")]
+pub struct PositionContext {
+    position: u8,
+    lap: u8,
+    sector: u8,
+    lap_distance: f32,
+    pit_status: PitStatus,
+    last_lap: f32,
+}
+
+impl From<&LapData> for PositionContext {
+    fn from(ld: &LapData) -> Self {
+        PositionContext {
+            position: ld.car_position,
+            lap: ld.current_lap_num,
+            sector: ld.sector,
+            lap_distance: ld.lap_distance,
+            pit_status: ld.pit_status,
+            last_lap: ld.last_lap_time,
+        }
+    }
 }
 
 /// The lap data packet gives details of all the cars in the session.
 ///
 /// Frequency: Rate as specified in menus
 ///
-/// Size: 843 bytes
+/// Size: 843 bytes (F1 2019), 1190 bytes (F1 2020), 970 bytes (F1 2021),
+/// 972 bytes (F1 2022), 1131 bytes (F1 2023), 1285 bytes (F1 2024)
 ///
 /// Version: 1
 ///
 /// ## Specification
 /// ```text
-/// header:   Header
-/// lap_data: Lap data for all cars on track
+/// header:                   Header
+/// lap_data:                 Lap data for all cars on track
+/// time_trial_pb_car_idx:    Index of personal best car in time trial, None if
+///                           invalid (F1 2022+)
+/// time_trial_rival_car_idx: Index of rival car in time trial, None if invalid
+///                           (F1 2022+)
 /// ```
-#[derive(Debug, Getters)]
-#[getset(get = "pub")]
+#[derive(Debug, CopyGetters, Getters)]
 pub struct PacketLapData {
+    #[getset(get = "pub")]
     header: PacketHeader,
+    #[getset(get = "pub")]
     lap_data: Vec<LapData>,
+    #[getset(get_copy = "pub")]
+    time_trial_pb_car_idx: Option<u8>,
+    #[getset(get_copy = "pub")]
+    time_trial_rival_car_idx: Option<u8>,
 }
 
 impl PacketLapData {
@@ -205,13 +563,133 @@ impl PacketLapData {
         mut reader: &mut T,
         header: PacketHeader,
     ) -> Result<PacketLapData, UnpackError> {
-        let mut lap_data = Vec::with_capacity(20);
+        let packet_format = header.packet_format();
+        let num_cars = num_cars(packet_format);
 
-        for _ in 0..20 {
-            let ld = LapData::new(&mut reader)?;
+        let mut lap_data = Vec::with_capacity(num_cars);
+        for _ in 0..num_cars {
+            let ld = LapData::new(&mut reader, packet_format)?;
             lap_data.push(ld);
         }
 
-        Ok(PacketLapData { header, lap_data })
+        let (time_trial_pb_car_idx, time_trial_rival_car_idx) = if packet_format >= 2022 {
+            (read_car_idx(reader), read_car_idx(reader))
+        } else {
+            (None, None)
+        };
+
+        Ok(PacketLapData {
+            header,
+            lap_data,
+            time_trial_pb_car_idx,
+            time_trial_rival_car_idx,
+        })
+    }
+
+    /// Lap data of a player's car, if they are in the session.
+    pub fn player_lap_data(&self, player: Player) -> Option<&LapData> {
+        let idx = self.header.car_index(player)?;
+        self.lap_data.get(idx as usize)
+    }
+
+    /// Position context of every car, in the same order as `lap_data`.
+    pub fn position_contexts(&self) -> Vec<PositionContext> {
+        self.lap_data.iter().map(PositionContext::from).collect()
+    }
+
+    /// Floating point values of each car, by index and field name, e.g. to
+    /// check them.
+    #[cfg(feature = "std")]
+    pub(crate) fn floats_mut(&mut self) -> impl Iterator<Item = (usize, &'static str, &mut f32)> {
+        self.lap_data.iter_mut().enumerate().flat_map(|(i, l)| {
+            vec![
+                (i, "last_lap_time", &mut l.last_lap_time),
+                (i, "current_lap_time", &mut l.current_lap_time),
+                (i, "best_lap_time", &mut l.best_lap_time),
+                (i, "sector_1_time", &mut l.sector_1_time),
+                (i, "sector_2_time", &mut l.sector_2_time),
+                (i, "lap_distance", &mut l.lap_distance),
+                (i, "total_distance", &mut l.total_distance),
+                (i, "safety_car_delta", &mut l.safety_car_delta),
+            ]
+        })
+    }
+}
+
+impl Encode for PacketLapData {
+    fn encode<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.header.encode(writer)?;
+        let packet_format = self.header.packet_format();
+        for data in &self.lap_data {
+            data.encode(writer, packet_format)?;
+        }
+
+        if packet_format >= 2022 {
+            writer.write_u8(self.time_trial_pb_car_idx.unwrap_or(255))?;
+            writer.write_u8(self.time_trial_rival_car_idx.unwrap_or(255))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Time sent as a 32 bits number of milliseconds, in seconds.
+fn read_millis<T: BufRead>(reader: &mut T) -> f32 {
+    reader.read_u32::<LittleEndian>().unwrap() as f32 / 1000.0
+}
+
+fn write_millis<W: Write>(writer: &mut W, time: f32) -> io::Result<()> {
+    writer.write_u32::<LittleEndian>((time * 1000.0).round() as u32)
+}
+
+/// Time sent as a 16 bits number of milliseconds, in seconds.
+fn read_short_millis<T: BufRead>(reader: &mut T) -> f32 {
+    reader.read_u16::<LittleEndian>().unwrap() as f32 / 1000.0
+}
+
+fn write_short_millis<W: Write>(writer: &mut W, time: f32) -> io::Result<()> {
+    writer.write_u16::<LittleEndian>((time * 1000.0).round() as u16)
+}
+
+/// Time sent as a milliseconds part followed by a minutes part, in seconds.
+fn read_minutes_millis<T: BufRead>(reader: &mut T) -> f32 {
+    let millis = reader.read_u16::<LittleEndian>().unwrap() as u32;
+    let minutes = reader.read_u8().unwrap() as u32;
+
+    (minutes * 60_000 + millis) as f32 / 1000.0
+}
+
+fn write_minutes_millis<W: Write>(writer: &mut W, time: f32) -> io::Result<()> {
+    let millis = (time * 1000.0).round() as u32;
+    writer.write_u16::<LittleEndian>((millis % 60_000) as u16)?;
+    writer.write_u8((millis / 60_000) as u8)
+}
+
+/// Byte only sent from the given packet format.
+fn read_u8_since<T: BufRead>(reader: &mut T, packet_format: u16, since: u16) -> Option<u8> {
+    if packet_format >= since {
+        Some(reader.read_u8().unwrap())
+    } else {
+        None
+    }
+}
+
+fn write_u8_since<W: Write>(
+    writer: &mut W,
+    packet_format: u16,
+    since: u16,
+    value: Option<u8>,
+) -> io::Result<()> {
+    if packet_format >= since {
+        writer.write_u8(value.unwrap_or(0))?;
+    }
+
+    Ok(())
+}
+
+fn read_car_idx<T: BufRead>(reader: &mut T) -> Option<u8> {
+    match reader.read_u8().unwrap() {
+        255 => None,
+        idx => Some(idx),
     }
 }
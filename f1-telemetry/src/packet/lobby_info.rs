@@ -0,0 +1,238 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use byteorder::LittleEndian;
+use getset::{CopyGetters, Getters};
+
+use super::header::PacketHeader;
+use crate::io::{self, BufRead, ReadBytesExt, Write, WriteBytesExt};
+use crate::packet::encode::Encode;
+use crate::packet::participants::{read_name, write_name, Nationality, Team, Telemetry};
+use crate::packet::{num_cars, UnpackError};
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum ReadyStatus {
+    NotReady,
+    Ready,
+    Spectating,
+    Unknown(u8),
+}
+
+impl From<u8> for ReadyStatus {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => ReadyStatus::NotReady,
+            1 => ReadyStatus::Ready,
+            2 => ReadyStatus::Spectating,
+            _ => ReadyStatus::Unknown(value),
+        }
+    }
+}
+
+impl From<ReadyStatus> for u8 {
+    fn from(value: ReadyStatus) -> Self {
+        match value {
+            ReadyStatus::NotReady => 0,
+            ReadyStatus::Ready => 1,
+            ReadyStatus::Spectating => 2,
+            ReadyStatus::Unknown(value) => value,
+        }
+    }
+}
+
+/// This type is used for the 22-element `lobby_players` array of the [`PacketLobbyInfoData`] type.
+///
+/// The fields only sent in some formats are `None` in the others.
+///
+/// Size: 52 bytes (F1 2020), 53 bytes (F1 2021-2022), 54 bytes (F1 2023),
+/// 58 bytes (F1 2024+)
+///
+/// ## Specification
+/// ```text
+/// ai_controlled:     Whether the vehicle is AI (1) or Human (0) controlled
+/// team:              Team id - see appendix (255 if no team currently selected)
+/// nationality:       Nationality of the driver (0 if not set)
+/// platform:          1 = Steam, 3 = PlayStation, 4 = Xbox, 6 = Origin,
+///                    255 = unknown (F1 2023+)
+/// name:              Name of participant in UTF-8 format – null terminated
+///                    Will be truncated with ... (U+2026) if too long
+/// car_number:        Car number of the player (F1 2021+)
+/// your_telemetry:    The player's UDP setting, 0 = restricted, 1 = public
+///                    (F1 2024+)
+/// show_online_names: The player's show online names setting, 0 = off, 1 = on
+///                    (F1 2024+)
+/// tech_level:        F1 World tech level (F1 2024+)
+/// ready_status:      0 = not ready, 1 = ready, 2 = spectating
+/// ```
+///
+/// [`PacketLobbyInfoData`]: ./struct.PacketLobbyInfoData.html
+#[derive(Debug, CopyGetters, Getters)]
+pub struct LobbyInfoData {
+    #[getset(get_copy = "pub")]
+    ai_controlled: bool,
+    #[getset(get_copy = "pub")]
+    team: Option<Team>,
+    #[getset(get_copy = "pub")]
+    nationality: Option<Nationality>,
+    #[getset(get_copy = "pub")]
+    platform: Option<u8>,
+    #[getset(get = "pub")]
+    name: String,
+    #[getset(get_copy = "pub")]
+    car_number: Option<u8>,
+    #[getset(get_copy = "pub")]
+    your_telemetry: Option<Telemetry>,
+    #[getset(get_copy = "pub")]
+    show_online_names: Option<bool>,
+    #[getset(get_copy = "pub")]
+    tech_level: Option<u16>,
+    #[getset(get_copy = "pub")]
+    ready_status: ReadyStatus,
+}
+
+impl LobbyInfoData {
+    pub fn new<T: BufRead>(
+        reader: &mut T,
+        packet_format: u16,
+    ) -> Result<LobbyInfoData, UnpackError> {
+        let ai_controlled = reader.read_u8().unwrap() == 1;
+        let team = match reader.read_u8().unwrap() {
+            255 => None,
+            id => Some(Team::from(id)),
+        };
+        let nationality = match reader.read_u8().unwrap() {
+            0 => None,
+            id => Some(Nationality::from(id)),
+        };
+        let platform = if packet_format >= 2023 {
+            Some(reader.read_u8().unwrap())
+        } else {
+            None
+        };
+        let name = read_name(reader)?;
+        let car_number = if packet_format >= 2021 {
+            Some(reader.read_u8().unwrap())
+        } else {
+            None
+        };
+        let (your_telemetry, show_online_names, tech_level) = if packet_format >= 2024 {
+            (
+                Some(Telemetry::from(reader.read_u8().unwrap())),
+                Some(reader.read_u8().unwrap() == 1),
+                Some(reader.read_u16::<LittleEndian>().unwrap()),
+            )
+        } else {
+            (None, None, None)
+        };
+        let ready_status = ReadyStatus::from(reader.read_u8().unwrap());
+
+        Ok(LobbyInfoData {
+            ai_controlled,
+            team,
+            nationality,
+            platform,
+            name,
+            car_number,
+            your_telemetry,
+            show_online_names,
+            tech_level,
+            ready_status,
+        })
+    }
+
+    pub(crate) fn encode<W: Write>(&self, writer: &mut W, packet_format: u16) -> io::Result<()> {
+        writer.write_u8(self.ai_controlled as u8)?;
+        writer.write_u8(self.team.map(u8::from).unwrap_or(255))?;
+        writer.write_u8(self.nationality.map(u8::from).unwrap_or(0))?;
+        if packet_format >= 2023 {
+            writer.write_u8(self.platform.unwrap_or(255))?;
+        }
+        write_name(writer, &self.name)?;
+        if packet_format >= 2021 {
+            writer.write_u8(self.car_number.unwrap_or(0))?;
+        }
+        if packet_format >= 2024 {
+            writer.write_u8(self.your_telemetry.map_or(0, u8::from))?;
+            writer.write_u8(self.show_online_names.unwrap_or(false) as u8)?;
+            writer.write_u16::<LittleEndian>(self.tech_level.unwrap_or(0))?;
+        }
+        writer.write_u8(self.ready_status.into())?;
+
+        Ok(())
+    }
+}
+
+/// This packet details the players currently in a multiplayer lobby (F1 2020+).
+///
+/// Frequency: Two every second when in the lobby
+///
+/// Size: 1169 bytes (F1 2020), 1191 bytes (F1 2021-2022), 1218 bytes (F1 2023),
+/// 1306 bytes (F1 2024+)
+///
+/// Version: 1
+///
+/// ## Specification
+/// ```text
+/// header:        Header
+/// num_players:   Number of players in the lobby data
+/// lobby_players: List of lobby players (22)
+/// ```
+#[derive(Debug, CopyGetters, Getters)]
+pub struct PacketLobbyInfoData {
+    #[getset(get = "pub")]
+    header: PacketHeader,
+    #[getset(get_copy = "pub")]
+    num_players: u8,
+    #[getset(get = "pub")]
+    lobby_players: Vec<LobbyInfoData>,
+}
+
+impl PacketLobbyInfoData {
+    pub fn new<T: BufRead>(
+        mut reader: &mut T,
+        header: PacketHeader,
+    ) -> Result<PacketLobbyInfoData, UnpackError> {
+        let packet_format = header.packet_format();
+        let array_size = num_cars(packet_format);
+
+        let num_players = reader.read_u8().unwrap();
+
+        let mut lobby_players = Vec::with_capacity(array_size);
+        for _ in 0..array_size {
+            let lid = LobbyInfoData::new(&mut reader, packet_format)?;
+            lobby_players.push(lid);
+        }
+
+        Ok(PacketLobbyInfoData {
+            header,
+            num_players,
+            lobby_players,
+        })
+    }
+
+    /// Players currently in the lobby.
+    pub fn players(&self) -> &[LobbyInfoData] {
+        let num_players = (self.num_players as usize).min(self.lobby_players.len());
+        &self.lobby_players[..num_players]
+    }
+
+    /// Names of the lobby players, with whether they are AI controlled.
+    #[cfg(feature = "std")]
+    pub(crate) fn names_mut(&mut self) -> impl Iterator<Item = (bool, &mut String)> {
+        self.lobby_players
+            .iter_mut()
+            .map(|p| (p.ai_controlled, &mut p.name))
+    }
+}
+
+impl Encode for PacketLobbyInfoData {
+    fn encode<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        let packet_format = self.header.packet_format();
+        self.header.encode(writer)?;
+        writer.write_u8(self.num_players)?;
+        for data in &self.lobby_players {
+            data.encode(writer, packet_format)?;
+        }
+
+        Ok(())
+    }
+}
@@ -1,12 +1,15 @@
-use byteorder::{LittleEndian, ReadBytesExt};
+use alloc::vec::Vec;
+use byteorder::LittleEndian;
 use getset::{CopyGetters, Getters};
-use std::io::BufRead;
 
 use super::header::PacketHeader;
-use crate::packet::generic::WheelData;
-use crate::packet::UnpackError;
+use crate::io::{self, BufRead, Write, WriteBytesExt};
+use crate::packet::bytes::{read_block, LeBytes};
+use crate::packet::encode::Encode;
+use crate::packet::generic::{Quaternion, Vec3, WheelData};
+use crate::packet::{num_cars, UnpackError};
 
-/// This type is used for the 20-element `motion_data` array of the [`PacketMotionData`] type.
+/// This type is used for the 20-element (22 from F1 2020) `motion_data` array of the [`PacketMotionData`] type.
 ///
 /// ## Specification
 /// ```text
@@ -55,25 +58,34 @@ pub struct MotionData {
 }
 
 impl MotionData {
+    /// Size of the motion data of a car.
+    pub(crate) const SIZE: usize = 60;
+
     pub fn new<T: BufRead>(reader: &mut T) -> Result<MotionData, UnpackError> {
-        let world_position_x = reader.read_f32::<LittleEndian>().unwrap();
-        let world_position_y = reader.read_f32::<LittleEndian>().unwrap();
-        let world_position_z = reader.read_f32::<LittleEndian>().unwrap();
-        let world_velocity_x = reader.read_f32::<LittleEndian>().unwrap();
-        let world_velocity_y = reader.read_f32::<LittleEndian>().unwrap();
-        let world_velocity_z = reader.read_f32::<LittleEndian>().unwrap();
-        let world_forward_dir_x = reader.read_i16::<LittleEndian>().unwrap();
-        let world_forward_dir_y = reader.read_i16::<LittleEndian>().unwrap();
-        let world_forward_dir_z = reader.read_i16::<LittleEndian>().unwrap();
-        let world_right_dir_x = reader.read_i16::<LittleEndian>().unwrap();
-        let world_right_dir_y = reader.read_i16::<LittleEndian>().unwrap();
-        let world_right_dir_z = reader.read_i16::<LittleEndian>().unwrap();
-        let g_force_lateral = reader.read_f32::<LittleEndian>().unwrap();
-        let g_force_longitudinal = reader.read_f32::<LittleEndian>().unwrap();
-        let g_force_vertical = reader.read_f32::<LittleEndian>().unwrap();
-        let yaw = reader.read_f32::<LittleEndian>().unwrap();
-        let pitch = reader.read_f32::<LittleEndian>().unwrap();
-        let roll = reader.read_f32::<LittleEndian>().unwrap();
+        let block: [u8; MotionData::SIZE] = read_block(reader);
+        MotionData::decode(&mut LeBytes::new(&block))
+    }
+
+    #[inline(always)]
+    fn decode(bytes: &mut LeBytes) -> Result<MotionData, UnpackError> {
+        let world_position_x = bytes.f32();
+        let world_position_y = bytes.f32();
+        let world_position_z = bytes.f32();
+        let world_velocity_x = bytes.f32();
+        let world_velocity_y = bytes.f32();
+        let world_velocity_z = bytes.f32();
+        let world_forward_dir_x = bytes.i16();
+        let world_forward_dir_y = bytes.i16();
+        let world_forward_dir_z = bytes.i16();
+        let world_right_dir_x = bytes.i16();
+        let world_right_dir_y = bytes.i16();
+        let world_right_dir_z = bytes.i16();
+        let g_force_lateral = bytes.f32();
+        let g_force_longitudinal = bytes.f32();
+        let g_force_vertical = bytes.f32();
+        let yaw = bytes.f32();
+        let pitch = bytes.f32();
+        let roll = bytes.f32();
 
         Ok(MotionData {
             world_position_x,
@@ -96,6 +108,100 @@ impl MotionData {
             roll,
         })
     }
+
+    /// World space position.
+    pub fn world_position(&self) -> Vec3 {
+        Vec3::new(
+            self.world_position_x,
+            self.world_position_y,
+            self.world_position_z,
+        )
+    }
+
+    /// Velocity in world space.
+    pub fn world_velocity(&self) -> Vec3 {
+        Vec3::new(
+            self.world_velocity_x,
+            self.world_velocity_y,
+            self.world_velocity_z,
+        )
+    }
+
+    /// G-force in the car's frame: lateral (X), vertical (Y) and longitudinal (Z).
+    pub fn g_force(&self) -> Vec3 {
+        Vec3::new(
+            self.g_force_lateral,
+            self.g_force_vertical,
+            self.g_force_longitudinal,
+        )
+    }
+
+    /// World space forward direction, with components between -1 and 1.
+    pub fn world_forward_dir(&self) -> Vec3 {
+        Vec3::new(
+            normalised(self.world_forward_dir_x),
+            normalised(self.world_forward_dir_y),
+            normalised(self.world_forward_dir_z),
+        )
+    }
+
+    /// World space right direction, with components between -1 and 1.
+    pub fn world_right_dir(&self) -> Vec3 {
+        Vec3::new(
+            normalised(self.world_right_dir_x),
+            normalised(self.world_right_dir_y),
+            normalised(self.world_right_dir_z),
+        )
+    }
+
+    /// Unit vector of the direction the car points to. Unlike
+    /// [`world_forward_dir`], it is rescaled to undo the rounding of the wire format.
+    ///
+    /// [`world_forward_dir`]: #method.world_forward_dir
+    pub fn heading(&self) -> Vec3 {
+        self.world_forward_dir().normalized()
+    }
+
+    /// Orientation of the car in world space, rotating the X (right), Y (up) and
+    /// Z (forward) axes onto the car's.
+    pub fn orientation(&self) -> Quaternion {
+        let forward = self.world_forward_dir().normalized();
+        let right = self.world_right_dir().normalized();
+        let up = forward.cross(right).normalized();
+
+        Quaternion::from_basis(right, up, forward)
+    }
+
+    pub(crate) fn encode<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_f32::<LittleEndian>(self.world_position_x)?;
+        writer.write_f32::<LittleEndian>(self.world_position_y)?;
+        writer.write_f32::<LittleEndian>(self.world_position_z)?;
+        writer.write_f32::<LittleEndian>(self.world_velocity_x)?;
+        writer.write_f32::<LittleEndian>(self.world_velocity_y)?;
+        writer.write_f32::<LittleEndian>(self.world_velocity_z)?;
+        writer.write_i16::<LittleEndian>(self.world_forward_dir_x)?;
+        writer.write_i16::<LittleEndian>(self.world_forward_dir_y)?;
+        writer.write_i16::<LittleEndian>(self.world_forward_dir_z)?;
+        writer.write_i16::<LittleEndian>(self.world_right_dir_x)?;
+        writer.write_i16::<LittleEndian>(self.world_right_dir_y)?;
+        writer.write_i16::<LittleEndian>(self.world_right_dir_z)?;
+        writer.write_f32::<LittleEndian>(self.g_force_lateral)?;
+        writer.write_f32::<LittleEndian>(self.g_force_longitudinal)?;
+        writer.write_f32::<LittleEndian>(self.g_force_vertical)?;
+        writer.write_f32::<LittleEndian>(self.yaw)?;
+        writer.write_f32::<LittleEndian>(self.pitch)?;
+        writer.write_f32::<LittleEndian>(self.roll)?;
+
+        Ok(())
+    }
+}
+
+/// Size of the extra player car data: 30 floats.
+const EXTRA_DATA_SIZE: usize = 120;
+
+/// Convert a normalised direction component from its 16-bit wire format.
+fn normalised(value: i16) -> f32 {
+    value as f32 / 32767.0
 }
 
 /// The motion packet gives physics data for all the cars being driven.
@@ -114,9 +220,9 @@ impl MotionData {
 /// ## Specification
 /// ```text
 /// header:          Header
-/// motion_data: List of motion data (20)
+/// motion_data: List of motion data (20, 22 from F1 2020)
 ///
-/// # Extra player car ONLY data
+/// # Extra player car ONLY data, until F1 2022 (see the motion ex packet)
 /// suspension_position:     Note: All wheel arrays have the following order:
 /// suspension_velocity:     RL, RR, FL, FR
 /// suspension_acceleration: RL, RR, FL, FR
@@ -175,60 +281,57 @@ pub struct PacketMotionData {
 
 impl PacketMotionData {
     pub fn new<T: BufRead>(
-        mut reader: &mut T,
+        reader: &mut T,
         header: PacketHeader,
     ) -> Result<PacketMotionData, UnpackError> {
-        let mut motion_data = Vec::with_capacity(20);
-        for _ in 0..20 {
-            let md = MotionData::new(&mut reader)?;
+        let num_cars = num_cars(header.packet_format());
+        let mut motion_data = Vec::with_capacity(num_cars);
+        for _ in 0..num_cars {
+            let md = MotionData::new(reader)?;
             motion_data.push(md);
         }
 
-        let suspension_position = WheelData::new(
-            reader.read_f32::<LittleEndian>().unwrap(),
-            reader.read_f32::<LittleEndian>().unwrap(),
-            reader.read_f32::<LittleEndian>().unwrap(),
-            reader.read_f32::<LittleEndian>().unwrap(),
-        );
-
-        let suspension_velocity = WheelData::new(
-            reader.read_f32::<LittleEndian>().unwrap(),
-            reader.read_f32::<LittleEndian>().unwrap(),
-            reader.read_f32::<LittleEndian>().unwrap(),
-            reader.read_f32::<LittleEndian>().unwrap(),
-        );
-
-        let suspension_acceleration = WheelData::new(
-            reader.read_f32::<LittleEndian>().unwrap(),
-            reader.read_f32::<LittleEndian>().unwrap(),
-            reader.read_f32::<LittleEndian>().unwrap(),
-            reader.read_f32::<LittleEndian>().unwrap(),
-        );
-
-        let wheel_speed = WheelData::new(
-            reader.read_f32::<LittleEndian>().unwrap(),
-            reader.read_f32::<LittleEndian>().unwrap(),
-            reader.read_f32::<LittleEndian>().unwrap(),
-            reader.read_f32::<LittleEndian>().unwrap(),
-        );
-
-        let wheel_slip = WheelData::new(
-            reader.read_f32::<LittleEndian>().unwrap(),
-            reader.read_f32::<LittleEndian>().unwrap(),
-            reader.read_f32::<LittleEndian>().unwrap(),
-            reader.read_f32::<LittleEndian>().unwrap(),
-        );
-
-        let local_velocity_x = reader.read_f32::<LittleEndian>().unwrap();
-        let local_velocity_y = reader.read_f32::<LittleEndian>().unwrap();
-        let local_velocity_z = reader.read_f32::<LittleEndian>().unwrap();
-        let angular_velocity_x = reader.read_f32::<LittleEndian>().unwrap();
-        let angular_velocity_y = reader.read_f32::<LittleEndian>().unwrap();
-        let angular_velocity_z = reader.read_f32::<LittleEndian>().unwrap();
-        let angular_acceleration_x = reader.read_f32::<LittleEndian>().unwrap();
-        let angular_acceleration_y = reader.read_f32::<LittleEndian>().unwrap();
-        let angular_acceleration_z = reader.read_f32::<LittleEndian>().unwrap();
-        let front_wheels_angle = reader.read_f32::<LittleEndian>().unwrap();
+        // From F1 2023 the extra player car data moved to the motion ex packet.
+        if header.packet_format() < 2023 {
+            let extra: [u8; EXTRA_DATA_SIZE] = read_block(reader);
+            PacketMotionData::decode(&mut LeBytes::new(&extra), header, motion_data)
+        } else {
+            let extra = [0; EXTRA_DATA_SIZE];
+            PacketMotionData::decode(&mut LeBytes::new(&extra), header, motion_data)
+        }
+    }
+
+    #[inline(always)]
+    fn decode(
+        bytes: &mut LeBytes,
+        header: PacketHeader,
+        motion_data: Vec<MotionData>,
+    ) -> Result<PacketMotionData, UnpackError> {
+        let mut read_extra = || bytes.f32();
+
+        let suspension_position =
+            WheelData::new(read_extra(), read_extra(), read_extra(), read_extra());
+
+        let suspension_velocity =
+            WheelData::new(read_extra(), read_extra(), read_extra(), read_extra());
+
+        let suspension_acceleration =
+            WheelData::new(read_extra(), read_extra(), read_extra(), read_extra());
+
+        let wheel_speed = WheelData::new(read_extra(), read_extra(), read_extra(), read_extra());
+
+        let wheel_slip = WheelData::new(read_extra(), read_extra(), read_extra(), read_extra());
+
+        let local_velocity_x = read_extra();
+        let local_velocity_y = read_extra();
+        let local_velocity_z = read_extra();
+        let angular_velocity_x = read_extra();
+        let angular_velocity_y = read_extra();
+        let angular_velocity_z = read_extra();
+        let angular_acceleration_x = read_extra();
+        let angular_acceleration_y = read_extra();
+        let angular_acceleration_z = read_extra();
+        let front_wheels_angle = read_extra();
 
         Ok(PacketMotionData {
             header,
@@ -250,4 +353,100 @@ impl PacketMotionData {
             front_wheels_angle,
         })
     }
+
+    /// Whether the extra player car data was sent. From F1 2023 it is sent in the
+    /// motion ex packet instead, and is zeroed here.
+    pub fn has_extra_data(&self) -> bool {
+        self.header.packet_format() < 2023
+    }
+
+    /// Index of the car the extra player car data belongs to.
+    ///
+    /// In split-screen sessions the extra data is only sent for the primary player.
+    pub fn extra_data_car_index(&self) -> u8 {
+        self.header.player_car_index()
+    }
+
+    /// Motion data of the player's car.
+    pub fn player_motion_data(&self) -> Option<&MotionData> {
+        self.motion_data
+            .get(self.header.player_car_index() as usize)
+    }
+
+    /// Motion data of the secondary player's car, in split-screen sessions.
+    ///
+    /// Note that the extra player car data is only sent for the main player, see
+    /// [`extra_data_car_index`].
+    ///
+    /// [`extra_data_car_index`]: #method.extra_data_car_index
+    pub fn secondary_player_motion_data(&self) -> Option<&MotionData> {
+        let idx = self.header.secondary_player_car_index()?;
+        self.motion_data.get(idx as usize)
+    }
+
+    /// Floating point values of each car, by index and field name, e.g. to
+    /// check them.
+    #[cfg(feature = "std")]
+    pub(crate) fn floats_mut(&mut self) -> impl Iterator<Item = (usize, &'static str, &mut f32)> {
+        self.motion_data.iter_mut().enumerate().flat_map(|(i, m)| {
+            vec![
+                (i, "world_position_x", &mut m.world_position_x),
+                (i, "world_position_y", &mut m.world_position_y),
+                (i, "world_position_z", &mut m.world_position_z),
+                (i, "world_velocity_x", &mut m.world_velocity_x),
+                (i, "world_velocity_y", &mut m.world_velocity_y),
+                (i, "world_velocity_z", &mut m.world_velocity_z),
+                (i, "g_force_lateral", &mut m.g_force_lateral),
+                (i, "g_force_longitudinal", &mut m.g_force_longitudinal),
+                (i, "g_force_vertical", &mut m.g_force_vertical),
+                (i, "yaw", &mut m.yaw),
+                (i, "pitch", &mut m.pitch),
+                (i, "roll", &mut m.roll),
+            ]
+        })
+    }
+}
+
+impl Encode for PacketMotionData {
+    fn encode<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.header.encode(writer)?;
+        for data in &self.motion_data {
+            data.encode(writer)?;
+        }
+
+        if !self.has_extra_data() {
+            return Ok(());
+        }
+
+        let wheel_data = [
+            self.suspension_position,
+            self.suspension_velocity,
+            self.suspension_acceleration,
+            self.wheel_speed,
+            self.wheel_slip,
+        ];
+        for wd in wheel_data.iter() {
+            for v in wd.to_array().iter() {
+                writer.write_f32::<LittleEndian>(*v)?;
+            }
+        }
+
+        let values = [
+            self.local_velocity_x,
+            self.local_velocity_y,
+            self.local_velocity_z,
+            self.angular_velocity_x,
+            self.angular_velocity_y,
+            self.angular_velocity_z,
+            self.angular_acceleration_x,
+            self.angular_acceleration_y,
+            self.angular_acceleration_z,
+            self.front_wheels_angle,
+        ];
+        for v in values.iter() {
+            writer.write_f32::<LittleEndian>(*v)?;
+        }
+
+        Ok(())
+    }
 }
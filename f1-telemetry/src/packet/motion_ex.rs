@@ -0,0 +1,244 @@
+use byteorder::LittleEndian;
+use getset::{CopyGetters, Getters};
+
+use super::header::PacketHeader;
+use crate::io::{self, BufRead, ReadBytesExt, Write, WriteBytesExt};
+use crate::packet::encode::Encode;
+use crate::packet::generic::WheelData;
+use crate::packet::UnpackError;
+
+/// The motion ex packet gives the extended physics data of the player's car only
+/// (F1 2023+). It replaces the extra player car data of the motion packet.
+///
+/// Frequency: Rate as specified in menus
+///
+/// Size: 217 bytes (F1 2023), 237 bytes (F1 2024)
+///
+/// Version: 1
+///
+/// ## Specification
+/// ```text
+/// header:                  Header
+/// suspension_position:     Note: All wheel arrays have the following order:
+/// suspension_velocity:     RL, RR, FL, FR
+/// suspension_acceleration: RL, RR, FL, FR
+/// wheel_speed:             Speed of each wheel
+/// wheel_slip_ratio:        Slip ratio for each wheel
+/// wheel_slip_angle:        Slip angles for each wheel
+/// wheel_lat_force:         Lateral forces for each wheel
+/// wheel_long_force:        Longitudinal forces for each wheel
+/// height_of_cog:           Height of centre of gravity above ground
+/// local_velocity_x:        Velocity in local space
+/// local_velocity_y:        Velocity in local space
+/// local_velocity_z:        Velocity in local space
+/// angular_velocity_x:      Angular velocity x-component
+/// angular_velocity_y:      Angular velocity y-component
+/// angular_velocity_z:      Angular velocity z-component
+/// angular_acceleration_x:  Angular acceleration x-component
+/// angular_acceleration_y:  Angular acceleration y-component
+/// angular_acceleration_z:  Angular acceleration z-component
+/// front_wheels_angle:      Current front wheels angle in radians
+/// wheel_vert_force:        Vertical forces for each wheel
+/// front_aero_height:       Front plank edge height above road surface (F1 2024+)
+/// rear_aero_height:        Rear plank edge height above road surface (F1 2024+)
+/// front_roll_angle:        Roll angle of the front suspension (F1 2024+)
+/// rear_roll_angle:         Roll angle of the rear suspension (F1 2024+)
+/// chassis_yaw:             Yaw angle of the chassis relative to the direction
+///                          of motion - radians (F1 2024+)
+/// ```
+#[derive(Debug, CopyGetters, Getters)]
+pub struct PacketMotionExData {
+    #[getset(get = "pub")]
+    header: PacketHeader,
+    #[getset(get_copy = "pub")]
+    suspension_position: WheelData<f32>,
+    #[getset(get_copy = "pub")]
+    suspension_velocity: WheelData<f32>,
+    #[getset(get_copy = "pub")]
+    suspension_acceleration: WheelData<f32>,
+    #[getset(get_copy = "pub")]
+    wheel_speed: WheelData<f32>,
+    #[getset(get_copy = "pub")]
+    wheel_slip_ratio: WheelData<f32>,
+    #[getset(get_copy = "pub")]
+    wheel_slip_angle: WheelData<f32>,
+    #[getset(get_copy = "pub")]
+    wheel_lat_force: WheelData<f32>,
+    #[getset(get_copy = "pub")]
+    wheel_long_force: WheelData<f32>,
+    #[getset(get_copy = "pub")]
+    height_of_cog: f32,
+    #[getset(get_copy = "pub")]
+    local_velocity_x: f32,
+    #[getset(get_copy = "pub")]
+    local_velocity_y: f32,
+    #[getset(get_copy = "pub")]
+    local_velocity_z: f32,
+    #[getset(get_copy = "pub")]
+    angular_velocity_x: f32,
+    #[getset(get_copy = "pub")]
+    angular_velocity_y: f32,
+    #[getset(get_copy = "pub")]
+    angular_velocity_z: f32,
+    #[getset(get_copy = "pub")]
+    angular_acceleration_x: f32,
+    #[getset(get_copy = "pub")]
+    angular_acceleration_y: f32,
+    #[getset(get_copy = "pub")]
+    angular_acceleration_z: f32,
+    #[getset(get_copy = "pub")]
+    front_wheels_angle: f32,
+    #[getset(get_copy = "pub")]
+    wheel_vert_force: WheelData<f32>,
+    #[getset(get_copy = "pub")]
+    front_aero_height: Option<f32>,
+    #[getset(get_copy = "pub")]
+    rear_aero_height: Option<f32>,
+    #[getset(get_copy = "pub")]
+    front_roll_angle: Option<f32>,
+    #[getset(get_copy = "pub")]
+    rear_roll_angle: Option<f32>,
+    #[getset(get_copy = "pub")]
+    chassis_yaw: Option<f32>,
+}
+
+impl PacketMotionExData {
+    pub fn new<T: BufRead>(
+        reader: &mut T,
+        header: PacketHeader,
+    ) -> Result<PacketMotionExData, UnpackError> {
+        let suspension_position = read_wheel_data(reader);
+        let suspension_velocity = read_wheel_data(reader);
+        let suspension_acceleration = read_wheel_data(reader);
+        let wheel_speed = read_wheel_data(reader);
+        let wheel_slip_ratio = read_wheel_data(reader);
+        let wheel_slip_angle = read_wheel_data(reader);
+        let wheel_lat_force = read_wheel_data(reader);
+        let wheel_long_force = read_wheel_data(reader);
+        let height_of_cog = reader.read_f32::<LittleEndian>().unwrap();
+        let local_velocity_x = reader.read_f32::<LittleEndian>().unwrap();
+        let local_velocity_y = reader.read_f32::<LittleEndian>().unwrap();
+        let local_velocity_z = reader.read_f32::<LittleEndian>().unwrap();
+        let angular_velocity_x = reader.read_f32::<LittleEndian>().unwrap();
+        let angular_velocity_y = reader.read_f32::<LittleEndian>().unwrap();
+        let angular_velocity_z = reader.read_f32::<LittleEndian>().unwrap();
+        let angular_acceleration_x = reader.read_f32::<LittleEndian>().unwrap();
+        let angular_acceleration_y = reader.read_f32::<LittleEndian>().unwrap();
+        let angular_acceleration_z = reader.read_f32::<LittleEndian>().unwrap();
+        let front_wheels_angle = reader.read_f32::<LittleEndian>().unwrap();
+        let wheel_vert_force = read_wheel_data(reader);
+
+        let mut read_2024 = || {
+            if header.packet_format() >= 2024 {
+                Some(reader.read_f32::<LittleEndian>().unwrap())
+            } else {
+                None
+            }
+        };
+        let front_aero_height = read_2024();
+        let rear_aero_height = read_2024();
+        let front_roll_angle = read_2024();
+        let rear_roll_angle = read_2024();
+        let chassis_yaw = read_2024();
+
+        Ok(PacketMotionExData {
+            header,
+            suspension_position,
+            suspension_velocity,
+            suspension_acceleration,
+            wheel_speed,
+            wheel_slip_ratio,
+            wheel_slip_angle,
+            wheel_lat_force,
+            wheel_long_force,
+            height_of_cog,
+            local_velocity_x,
+            local_velocity_y,
+            local_velocity_z,
+            angular_velocity_x,
+            angular_velocity_y,
+            angular_velocity_z,
+            angular_acceleration_x,
+            angular_acceleration_y,
+            angular_acceleration_z,
+            front_wheels_angle,
+            wheel_vert_force,
+            front_aero_height,
+            rear_aero_height,
+            front_roll_angle,
+            rear_roll_angle,
+            chassis_yaw,
+        })
+    }
+}
+
+impl Encode for PacketMotionExData {
+    fn encode<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.header.encode(writer)?;
+
+        let wheel_data = [
+            self.suspension_position,
+            self.suspension_velocity,
+            self.suspension_acceleration,
+            self.wheel_speed,
+            self.wheel_slip_ratio,
+            self.wheel_slip_angle,
+            self.wheel_lat_force,
+            self.wheel_long_force,
+        ];
+        for wd in wheel_data.iter() {
+            write_wheel_data(writer, *wd)?;
+        }
+
+        let values = [
+            self.height_of_cog,
+            self.local_velocity_x,
+            self.local_velocity_y,
+            self.local_velocity_z,
+            self.angular_velocity_x,
+            self.angular_velocity_y,
+            self.angular_velocity_z,
+            self.angular_acceleration_x,
+            self.angular_acceleration_y,
+            self.angular_acceleration_z,
+            self.front_wheels_angle,
+        ];
+        for v in values.iter() {
+            writer.write_f32::<LittleEndian>(*v)?;
+        }
+
+        write_wheel_data(writer, self.wheel_vert_force)?;
+
+        if self.header.packet_format() >= 2024 {
+            let values = [
+                self.front_aero_height,
+                self.rear_aero_height,
+                self.front_roll_angle,
+                self.rear_roll_angle,
+                self.chassis_yaw,
+            ];
+            for v in values.iter() {
+                writer.write_f32::<LittleEndian>(v.unwrap_or(0.0))?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn read_wheel_data<T: BufRead>(reader: &mut T) -> WheelData<f32> {
+    WheelData::new(
+        reader.read_f32::<LittleEndian>().unwrap(),
+        reader.read_f32::<LittleEndian>().unwrap(),
+        reader.read_f32::<LittleEndian>().unwrap(),
+        reader.read_f32::<LittleEndian>().unwrap(),
+    )
+}
+
+fn write_wheel_data<W: Write>(writer: &mut W, wheel_data: WheelData<f32>) -> io::Result<()> {
+    for v in wheel_data.to_array().iter() {
+        writer.write_f32::<LittleEndian>(*v)?;
+    }
+
+    Ok(())
+}
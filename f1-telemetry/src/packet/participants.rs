@@ -1,10 +1,13 @@
-use byteorder::ReadBytesExt;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use byteorder::LittleEndian;
 use getset::{CopyGetters, Getters};
-use std::convert::TryFrom;
-use std::io::BufRead;
 
 use super::header::PacketHeader;
-use crate::packet::UnpackError;
+use crate::io::{self, BufRead, ReadBytesExt, Write, WriteBytesExt};
+use crate::packet::encode::Encode;
+use crate::packet::{num_cars, UnpackError};
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
 pub enum Driver {
@@ -85,91 +88,175 @@ pub enum Driver {
     AnthoineHubert,
     GuilianoAlesi,
     RalphBoschung,
+    Unknown(u8),
 }
 
-impl TryFrom<u8> for Driver {
-    type Error = UnpackError;
+impl From<u8> for Driver {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => Driver::CarlosSainz,
+            1 => Driver::DaniilKvyat,
+            2 => Driver::DanielRicciardo,
+            6 => Driver::KimiRaikkonen,
+            7 => Driver::LewisHamilton,
+            9 => Driver::MaxVerstappen,
+            10 => Driver::NicoHulkenburg,
+            11 => Driver::KevinMagnussen,
+            12 => Driver::RomainGrosjean,
+            13 => Driver::SebastianVettel,
+            14 => Driver::SergioPerez,
+            15 => Driver::ValtteriBottas,
+            19 => Driver::LanceStroll,
+            20 => Driver::ArronBarnes,
+            21 => Driver::MartinGiles,
+            22 => Driver::AlexMurray,
+            23 => Driver::LucasRoth,
+            24 => Driver::IgorCorreia,
+            25 => Driver::SophieLevasseur,
+            26 => Driver::JonasSchiffer,
+            27 => Driver::AlainForest,
+            28 => Driver::JayLetourneau,
+            29 => Driver::EstoSaari,
+            30 => Driver::YasarAtiyeh,
+            31 => Driver::CallistoCalabresi,
+            32 => Driver::NaotaIzum,
+            33 => Driver::HowardClarke,
+            34 => Driver::WilhelmKaufmann,
+            35 => Driver::MarieLaursen,
+            36 => Driver::FlavioNieves,
+            37 => Driver::PeterBelousov,
+            38 => Driver::KlimekMichalski,
+            39 => Driver::SantiagoMoreno,
+            40 => Driver::BenjaminCoppens,
+            41 => Driver::NoahVisser,
+            42 => Driver::GertWaldmuller,
+            43 => Driver::JulianQuesada,
+            44 => Driver::DanielJones,
+            45 => Driver::ArtemMarkelov,
+            46 => Driver::TadasukeMakino,
+            47 => Driver::SeanGelael,
+            48 => Driver::NyckDeVries,
+            49 => Driver::JackAitken,
+            50 => Driver::GeorgeRussell,
+            51 => Driver::MaximilianGunther,
+            52 => Driver::NireiFukuzumi,
+            53 => Driver::LucaGhiotto,
+            54 => Driver::LandoNorris,
+            55 => Driver::SergioSetteCamara,
+            56 => Driver::LouisDeletraz,
+            57 => Driver::AntonioFuoco,
+            58 => Driver::CharlesLeclerc,
+            59 => Driver::PierreGasly,
+            62 => Driver::AlexanderAlbon,
+            63 => Driver::NicholasLatifi,
+            64 => Driver::DorianBoccolacci,
+            65 => Driver::NikoKari,
+            66 => Driver::RobertoMerhi,
+            67 => Driver::ArjunMaini,
+            68 => Driver::AlessioLorandi,
+            69 => Driver::RubenMeijer,
+            70 => Driver::RashidNair,
+            71 => Driver::JackTremblay,
+            74 => Driver::AntonioGiovinazzi,
+            75 => Driver::RobertKubica,
+            78 => Driver::NobuharuMatsushita,
+            79 => Driver::NikitaMazepin,
+            80 => Driver::GuanyaZhou,
+            81 => Driver::MickSchumacher,
+            82 => Driver::CallumIlott,
+            83 => Driver::JuanManuelCorrea,
+            84 => Driver::JordanKing,
+            85 => Driver::MahaveerRaghunathan,
+            86 => Driver::TatianaCalderon,
+            87 => Driver::AnthoineHubert,
+            88 => Driver::GuilianoAlesi,
+            89 => Driver::RalphBoschung,
+            _ => Driver::Unknown(value),
+        }
+    }
+}
 
-    fn try_from(value: u8) -> Result<Self, Self::Error> {
+impl From<Driver> for u8 {
+    fn from(value: Driver) -> Self {
         match value {
-            0 => Ok(Driver::CarlosSainz),
-            1 => Ok(Driver::DaniilKvyat),
-            2 => Ok(Driver::DanielRicciardo),
-            6 => Ok(Driver::KimiRaikkonen),
-            7 => Ok(Driver::LewisHamilton),
-            9 => Ok(Driver::MaxVerstappen),
-            10 => Ok(Driver::NicoHulkenburg),
-            11 => Ok(Driver::KevinMagnussen),
-            12 => Ok(Driver::RomainGrosjean),
-            13 => Ok(Driver::SebastianVettel),
-            14 => Ok(Driver::SergioPerez),
-            15 => Ok(Driver::ValtteriBottas),
-            19 => Ok(Driver::LanceStroll),
-            20 => Ok(Driver::ArronBarnes),
-            21 => Ok(Driver::MartinGiles),
-            22 => Ok(Driver::AlexMurray),
-            23 => Ok(Driver::LucasRoth),
-            24 => Ok(Driver::IgorCorreia),
-            25 => Ok(Driver::SophieLevasseur),
-            26 => Ok(Driver::JonasSchiffer),
-            27 => Ok(Driver::AlainForest),
-            28 => Ok(Driver::JayLetourneau),
-            29 => Ok(Driver::EstoSaari),
-            30 => Ok(Driver::YasarAtiyeh),
-            31 => Ok(Driver::CallistoCalabresi),
-            32 => Ok(Driver::NaotaIzum),
-            33 => Ok(Driver::HowardClarke),
-            34 => Ok(Driver::WilhelmKaufmann),
-            35 => Ok(Driver::MarieLaursen),
-            36 => Ok(Driver::FlavioNieves),
-            37 => Ok(Driver::PeterBelousov),
-            38 => Ok(Driver::KlimekMichalski),
-            39 => Ok(Driver::SantiagoMoreno),
-            40 => Ok(Driver::BenjaminCoppens),
-            41 => Ok(Driver::NoahVisser),
-            42 => Ok(Driver::GertWaldmuller),
-            43 => Ok(Driver::JulianQuesada),
-            44 => Ok(Driver::DanielJones),
-            45 => Ok(Driver::ArtemMarkelov),
-            46 => Ok(Driver::TadasukeMakino),
-            47 => Ok(Driver::SeanGelael),
-            48 => Ok(Driver::NyckDeVries),
-            49 => Ok(Driver::JackAitken),
-            50 => Ok(Driver::GeorgeRussell),
-            51 => Ok(Driver::MaximilianGunther),
-            52 => Ok(Driver::NireiFukuzumi),
-            53 => Ok(Driver::LucaGhiotto),
-            54 => Ok(Driver::LandoNorris),
-            55 => Ok(Driver::SergioSetteCamara),
-            56 => Ok(Driver::LouisDeletraz),
-            57 => Ok(Driver::AntonioFuoco),
-            58 => Ok(Driver::CharlesLeclerc),
-            59 => Ok(Driver::PierreGasly),
-            62 => Ok(Driver::AlexanderAlbon),
-            63 => Ok(Driver::NicholasLatifi),
-            64 => Ok(Driver::DorianBoccolacci),
-            65 => Ok(Driver::NikoKari),
-            66 => Ok(Driver::RobertoMerhi),
-            67 => Ok(Driver::ArjunMaini),
-            68 => Ok(Driver::AlessioLorandi),
-            69 => Ok(Driver::RubenMeijer),
-            70 => Ok(Driver::RashidNair),
-            71 => Ok(Driver::JackTremblay),
-            74 => Ok(Driver::AntonioGiovinazzi),
-            75 => Ok(Driver::RobertKubica),
-            78 => Ok(Driver::NobuharuMatsushita),
-            79 => Ok(Driver::NikitaMazepin),
-            80 => Ok(Driver::GuanyaZhou),
-            81 => Ok(Driver::MickSchumacher),
-            82 => Ok(Driver::CallumIlott),
-            83 => Ok(Driver::JuanManuelCorrea),
-            84 => Ok(Driver::JordanKing),
-            85 => Ok(Driver::MahaveerRaghunathan),
-            86 => Ok(Driver::TatianaCalderon),
-            87 => Ok(Driver::AnthoineHubert),
-            88 => Ok(Driver::GuilianoAlesi),
-            89 => Ok(Driver::RalphBoschung),
-            _ => Err(UnpackError(format!("Invalid Driver value: {}", value))),
+            Driver::CarlosSainz => 0,
+            Driver::DaniilKvyat => 1,
+            Driver::DanielRicciardo => 2,
+            Driver::KimiRaikkonen => 6,
+            Driver::LewisHamilton => 7,
+            Driver::MaxVerstappen => 9,
+            Driver::NicoHulkenburg => 10,
+            Driver::KevinMagnussen => 11,
+            Driver::RomainGrosjean => 12,
+            Driver::SebastianVettel => 13,
+            Driver::SergioPerez => 14,
+            Driver::ValtteriBottas => 15,
+            Driver::LanceStroll => 19,
+            Driver::ArronBarnes => 20,
+            Driver::MartinGiles => 21,
+            Driver::AlexMurray => 22,
+            Driver::LucasRoth => 23,
+            Driver::IgorCorreia => 24,
+            Driver::SophieLevasseur => 25,
+            Driver::JonasSchiffer => 26,
+            Driver::AlainForest => 27,
+            Driver::JayLetourneau => 28,
+            Driver::EstoSaari => 29,
+            Driver::YasarAtiyeh => 30,
+            Driver::CallistoCalabresi => 31,
+            Driver::NaotaIzum => 32,
+            Driver::HowardClarke => 33,
+            Driver::WilhelmKaufmann => 34,
+            Driver::MarieLaursen => 35,
+            Driver::FlavioNieves => 36,
+            Driver::PeterBelousov => 37,
+            Driver::KlimekMichalski => 38,
+            Driver::SantiagoMoreno => 39,
+            Driver::BenjaminCoppens => 40,
+            Driver::NoahVisser => 41,
+            Driver::GertWaldmuller => 42,
+            Driver::JulianQuesada => 43,
+            Driver::DanielJones => 44,
+            Driver::ArtemMarkelov => 45,
+            Driver::TadasukeMakino => 46,
+            Driver::SeanGelael => 47,
+            Driver::NyckDeVries => 48,
+            Driver::JackAitken => 49,
+            Driver::GeorgeRussell => 50,
+            Driver::MaximilianGunther => 51,
+            Driver::NireiFukuzumi => 52,
+            Driver::LucaGhiotto => 53,
+            Driver::LandoNorris => 54,
+            Driver::SergioSetteCamara => 55,
+            Driver::LouisDeletraz => 56,
+            Driver::AntonioFuoco => 57,
+            Driver::CharlesLeclerc => 58,
+            Driver::PierreGasly => 59,
+            Driver::AlexanderAlbon => 62,
+            Driver::NicholasLatifi => 63,
+            Driver::DorianBoccolacci => 64,
+            Driver::NikoKari => 65,
+            Driver::RobertoMerhi => 66,
+            Driver::ArjunMaini => 67,
+            Driver::AlessioLorandi => 68,
+            Driver::RubenMeijer => 69,
+            Driver::RashidNair => 70,
+            Driver::JackTremblay => 71,
+            Driver::AntonioGiovinazzi => 74,
+            Driver::RobertKubica => 75,
+            Driver::NobuharuMatsushita => 78,
+            Driver::NikitaMazepin => 79,
+            Driver::GuanyaZhou => 80,
+            Driver::MickSchumacher => 81,
+            Driver::CallumIlott => 82,
+            Driver::JuanManuelCorrea => 83,
+            Driver::JordanKing => 84,
+            Driver::MahaveerRaghunathan => 85,
+            Driver::TatianaCalderon => 86,
+            Driver::AnthoineHubert => 87,
+            Driver::GuilianoAlesi => 88,
+            Driver::RalphBoschung => 89,
+            Driver::Unknown(value) => value,
         }
     }
 }
@@ -196,6 +283,7 @@ pub enum Team {
     Ferrari2004,
     Renault2006,
     Ferrari2007,
+    McLaren2008,
     RedBull2010,
     Ferrari1976,
     ARTGrandPrix,
@@ -216,6 +304,7 @@ pub enum Team {
     Williams2003,
     Brawn2009,
     Lotus1978,
+    F1Generic,
     ArtGP2019,
     Campos2019,
     Carlin2019,
@@ -226,9 +315,26 @@ pub enum Team {
     Prema2019,
     Trident2019,
     Arden2019,
+    Benetton1994,
+    Benetton1995,
+    Ferrari2000,
+    Jordan1991,
     Ferrari1990,
     McLaren2010,
     Ferrari2010,
+    ArtGP2020,
+    Campos2020,
+    Carlin2020,
+    Charouz2020,
+    Dams2020,
+    UniVirtuosi2020,
+    MPMotorsport2020,
+    Prema2020,
+    Trident2020,
+    BWT2020,
+    Hitech2020,
+    MyTeam,
+    Unknown(u8),
 }
 
 impl Team {
@@ -254,6 +360,7 @@ impl Team {
             Team::Ferrari2004 => 17,
             Team::Renault2006 => 18,
             Team::Ferrari2007 => 19,
+            Team::McLaren2008 => 20,
             Team::RedBull2010 => 21,
             Team::Ferrari1976 => 22,
             Team::ARTGrandPrix => 23,
@@ -274,6 +381,7 @@ impl Team {
             Team::Williams2003 => 38,
             Team::Brawn2009 => 39,
             Team::Lotus1978 => 40,
+            Team::F1Generic => 41,
             Team::ArtGP2019 => 42,
             Team::Campos2019 => 43,
             Team::Carlin2019 => 44,
@@ -284,72 +392,184 @@ impl Team {
             Team::Prema2019 => 49,
             Team::Trident2019 => 50,
             Team::Arden2019 => 51,
+            Team::Benetton1994 => 53,
+            Team::Benetton1995 => 54,
+            Team::Ferrari2000 => 55,
+            Team::Jordan1991 => 56,
             Team::Ferrari1990 => 63,
             Team::McLaren2010 => 64,
             Team::Ferrari2010 => 65,
+            Team::ArtGP2020 => 70,
+            Team::Campos2020 => 71,
+            Team::Carlin2020 => 72,
+            Team::Charouz2020 => 73,
+            Team::Dams2020 => 74,
+            Team::UniVirtuosi2020 => 75,
+            Team::MPMotorsport2020 => 76,
+            Team::Prema2020 => 77,
+            Team::Trident2020 => 78,
+            Team::BWT2020 => 79,
+            Team::Hitech2020 => 80,
+            Team::MyTeam => 255,
+            Team::Unknown(id) => id,
         }
     }
 }
 
-impl TryFrom<u8> for Team {
-    type Error = UnpackError;
+impl From<u8> for Team {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => Team::Mercedes,
+            1 => Team::Ferrari,
+            2 => Team::RedBullRacing,
+            3 => Team::Williams,
+            4 => Team::RacingPoint,
+            5 => Team::Renault,
+            6 => Team::ToroRosso,
+            7 => Team::Haas,
+            8 => Team::McLaren,
+            9 => Team::AlfaRomeo,
+            10 => Team::McLaren1988,
+            11 => Team::McLaren1991,
+            12 => Team::Williams1992,
+            13 => Team::Ferrari1995,
+            14 => Team::Williams1996,
+            15 => Team::McLaren1998,
+            16 => Team::Ferrari2002,
+            17 => Team::Ferrari2004,
+            18 => Team::Renault2006,
+            19 => Team::Ferrari2007,
+            20 => Team::McLaren2008,
+            21 => Team::RedBull2010,
+            22 => Team::Ferrari1976,
+            23 => Team::ARTGrandPrix,
+            24 => Team::CamposVexatecRacing,
+            25 => Team::Carlin,
+            26 => Team::CharouzRacingSystem,
+            27 => Team::DAMS,
+            28 => Team::RussianTime,
+            29 => Team::MPMotorsport,
+            30 => Team::Pertamina,
+            31 => Team::McLaren1990,
+            32 => Team::Trident,
+            33 => Team::BWTArden,
+            34 => Team::McLaren1976,
+            35 => Team::Lotus1972,
+            36 => Team::Ferrari1979,
+            37 => Team::McLaren1982,
+            38 => Team::Williams2003,
+            39 => Team::Brawn2009,
+            40 => Team::Lotus1978,
+            41 => Team::F1Generic,
+            42 => Team::ArtGP2019,
+            43 => Team::Campos2019,
+            44 => Team::Carlin2019,
+            45 => Team::SauberJuniorCharouz2019,
+            46 => Team::Dams2019,
+            47 => Team::UniVirtuosi2019,
+            48 => Team::MPMotorsport2019,
+            49 => Team::Prema2019,
+            50 => Team::Trident2019,
+            51 => Team::Arden2019,
+            53 => Team::Benetton1994,
+            54 => Team::Benetton1995,
+            55 => Team::Ferrari2000,
+            56 => Team::Jordan1991,
+            63 => Team::Ferrari1990,
+            64 => Team::McLaren2010,
+            65 => Team::Ferrari2010,
+            70 => Team::ArtGP2020,
+            71 => Team::Campos2020,
+            72 => Team::Carlin2020,
+            73 => Team::Charouz2020,
+            74 => Team::Dams2020,
+            75 => Team::UniVirtuosi2020,
+            76 => Team::MPMotorsport2020,
+            77 => Team::Prema2020,
+            78 => Team::Trident2020,
+            79 => Team::BWT2020,
+            80 => Team::Hitech2020,
+            255 => Team::MyTeam,
+            _ => Team::Unknown(value),
+        }
+    }
+}
 
-    fn try_from(value: u8) -> Result<Self, Self::Error> {
+impl From<Team> for u8 {
+    fn from(value: Team) -> Self {
         match value {
-            0 => Ok(Team::Mercedes),
-            1 => Ok(Team::Ferrari),
-            2 => Ok(Team::RedBullRacing),
-            3 => Ok(Team::Williams),
-            4 => Ok(Team::RacingPoint),
-            5 => Ok(Team::Renault),
-            6 => Ok(Team::ToroRosso),
-            7 => Ok(Team::Haas),
-            8 => Ok(Team::McLaren),
-            9 => Ok(Team::AlfaRomeo),
-            10 => Ok(Team::McLaren1988),
-            11 => Ok(Team::McLaren1991),
-            12 => Ok(Team::Williams1992),
-            13 => Ok(Team::Ferrari1995),
-            14 => Ok(Team::Williams1996),
-            15 => Ok(Team::McLaren1998),
-            16 => Ok(Team::Ferrari2002),
-            17 => Ok(Team::Ferrari2004),
-            18 => Ok(Team::Renault2006),
-            19 => Ok(Team::Ferrari2007),
-            21 => Ok(Team::RedBull2010),
-            22 => Ok(Team::Ferrari1976),
-            23 => Ok(Team::ARTGrandPrix),
-            24 => Ok(Team::CamposVexatecRacing),
-            25 => Ok(Team::Carlin),
-            26 => Ok(Team::CharouzRacingSystem),
-            27 => Ok(Team::DAMS),
-            28 => Ok(Team::RussianTime),
-            29 => Ok(Team::MPMotorsport),
-            30 => Ok(Team::Pertamina),
-            31 => Ok(Team::McLaren1990),
-            32 => Ok(Team::Trident),
-            33 => Ok(Team::BWTArden),
-            34 => Ok(Team::McLaren1976),
-            35 => Ok(Team::Lotus1972),
-            36 => Ok(Team::Ferrari1979),
-            37 => Ok(Team::McLaren1982),
-            38 => Ok(Team::Williams2003),
-            39 => Ok(Team::Brawn2009),
-            40 => Ok(Team::Lotus1978),
-            42 => Ok(Team::ArtGP2019),
-            43 => Ok(Team::Campos2019),
-            44 => Ok(Team::Carlin2019),
-            45 => Ok(Team::SauberJuniorCharouz2019),
-            46 => Ok(Team::Dams2019),
-            47 => Ok(Team::UniVirtuosi2019),
-            48 => Ok(Team::MPMotorsport2019),
-            49 => Ok(Team::Prema2019),
-            50 => Ok(Team::Trident2019),
-            51 => Ok(Team::Arden2019),
-            63 => Ok(Team::Ferrari1990),
-            64 => Ok(Team::McLaren2010),
-            65 => Ok(Team::Ferrari2010),
-            _ => Err(UnpackError(format!("Invalid Team value: {}", value))),
+            Team::Mercedes => 0,
+            Team::Ferrari => 1,
+            Team::RedBullRacing => 2,
+            Team::Williams => 3,
+            Team::RacingPoint => 4,
+            Team::Renault => 5,
+            Team::ToroRosso => 6,
+            Team::Haas => 7,
+            Team::McLaren => 8,
+            Team::AlfaRomeo => 9,
+            Team::McLaren1988 => 10,
+            Team::McLaren1991 => 11,
+            Team::Williams1992 => 12,
+            Team::Ferrari1995 => 13,
+            Team::Williams1996 => 14,
+            Team::McLaren1998 => 15,
+            Team::Ferrari2002 => 16,
+            Team::Ferrari2004 => 17,
+            Team::Renault2006 => 18,
+            Team::Ferrari2007 => 19,
+            Team::McLaren2008 => 20,
+            Team::RedBull2010 => 21,
+            Team::Ferrari1976 => 22,
+            Team::ARTGrandPrix => 23,
+            Team::CamposVexatecRacing => 24,
+            Team::Carlin => 25,
+            Team::CharouzRacingSystem => 26,
+            Team::DAMS => 27,
+            Team::RussianTime => 28,
+            Team::MPMotorsport => 29,
+            Team::Pertamina => 30,
+            Team::McLaren1990 => 31,
+            Team::Trident => 32,
+            Team::BWTArden => 33,
+            Team::McLaren1976 => 34,
+            Team::Lotus1972 => 35,
+            Team::Ferrari1979 => 36,
+            Team::McLaren1982 => 37,
+            Team::Williams2003 => 38,
+            Team::Brawn2009 => 39,
+            Team::Lotus1978 => 40,
+            Team::F1Generic => 41,
+            Team::ArtGP2019 => 42,
+            Team::Campos2019 => 43,
+            Team::Carlin2019 => 44,
+            Team::SauberJuniorCharouz2019 => 45,
+            Team::Dams2019 => 46,
+            Team::UniVirtuosi2019 => 47,
+            Team::MPMotorsport2019 => 48,
+            Team::Prema2019 => 49,
+            Team::Trident2019 => 50,
+            Team::Arden2019 => 51,
+            Team::Benetton1994 => 53,
+            Team::Benetton1995 => 54,
+            Team::Ferrari2000 => 55,
+            Team::Jordan1991 => 56,
+            Team::Ferrari1990 => 63,
+            Team::McLaren2010 => 64,
+            Team::Ferrari2010 => 65,
+            Team::ArtGP2020 => 70,
+            Team::Campos2020 => 71,
+            Team::Carlin2020 => 72,
+            Team::Charouz2020 => 73,
+            Team::Dams2020 => 74,
+            Team::UniVirtuosi2020 => 75,
+            Team::MPMotorsport2020 => 76,
+            Team::Prema2020 => 77,
+            Team::Trident2020 => 78,
+            Team::BWT2020 => 79,
+            Team::Hitech2020 => 80,
+            Team::MyTeam => 255,
+            Team::Unknown(value) => value,
         }
     }
 }
@@ -442,100 +662,355 @@ pub enum Nationality {
     Ukrainian,
     Venezuelan,
     Welsh,
+    Unknown(u8),
+}
+
+impl Nationality {
+    /// ISO 3166-1 alpha-2 code of the country, e.g. `"FR"`. The nations of
+    /// the United Kingdom share `"GB"`.
+    pub fn country_code(self) -> Option<&'static str> {
+        Some(match self {
+            Nationality::American => "US",
+            Nationality::Argentinean => "AR",
+            Nationality::Australian => "AU",
+            Nationality::Austrian => "AT",
+            Nationality::Azerbaijani => "AZ",
+            Nationality::Bahraini => "BH",
+            Nationality::Belgian => "BE",
+            Nationality::Bolivian => "BO",
+            Nationality::Brazilian => "BR",
+            Nationality::British => "GB",
+            Nationality::Bulgarian => "BG",
+            Nationality::Cameroonian => "CM",
+            Nationality::Canadian => "CA",
+            Nationality::Chilean => "CL",
+            Nationality::Chinese => "CN",
+            Nationality::Colombian => "CO",
+            Nationality::CostaRican => "CR",
+            Nationality::Croatian => "HR",
+            Nationality::Cypriot => "CY",
+            Nationality::Czech => "CZ",
+            Nationality::Danish => "DK",
+            Nationality::Dutch => "NL",
+            Nationality::Ecuadorian => "EC",
+            Nationality::English => "GB",
+            Nationality::Emirian => "AE",
+            Nationality::Estonian => "EE",
+            Nationality::Finnish => "FI",
+            Nationality::French => "FR",
+            Nationality::German => "DE",
+            Nationality::Ghanaian => "GH",
+            Nationality::Greek => "GR",
+            Nationality::Guatemalan => "GT",
+            Nationality::Honduran => "HN",
+            Nationality::HongKonger => "HK",
+            Nationality::Hungarian => "HU",
+            Nationality::Icelander => "IS",
+            Nationality::Indian => "IN",
+            Nationality::Indonesian => "ID",
+            Nationality::Irish => "IE",
+            Nationality::Israeli => "IL",
+            Nationality::Italian => "IT",
+            Nationality::Jamaican => "JM",
+            Nationality::Japanese => "JP",
+            Nationality::Jordanian => "JO",
+            Nationality::Kuwaiti => "KW",
+            Nationality::Latvian => "LV",
+            Nationality::Lebanese => "LB",
+            Nationality::Lithuanian => "LT",
+            Nationality::Luxembourger => "LU",
+            Nationality::Malaysian => "MY",
+            Nationality::Maltese => "MT",
+            Nationality::Mexican => "MX",
+            Nationality::Monegasque => "MC",
+            Nationality::NewZealander => "NZ",
+            Nationality::Nicaraguan => "NI",
+            Nationality::NorthKorean => "KP",
+            Nationality::NorthernIrish => "GB",
+            Nationality::Norwegian => "NO",
+            Nationality::Omani => "OM",
+            Nationality::Pakistani => "PK",
+            Nationality::Panamanian => "PA",
+            Nationality::Paraguayan => "PY",
+            Nationality::Peruvian => "PE",
+            Nationality::Polish => "PL",
+            Nationality::Portuguese => "PT",
+            Nationality::Qatari => "QA",
+            Nationality::Romanian => "RO",
+            Nationality::Russian => "RU",
+            Nationality::Salvadoran => "SV",
+            Nationality::Saudi => "SA",
+            Nationality::Scottish => "GB",
+            Nationality::Serbian => "RS",
+            Nationality::Singaporean => "SG",
+            Nationality::Slovakian => "SK",
+            Nationality::Slovenian => "SI",
+            Nationality::SouthKorean => "KR",
+            Nationality::SouthAfrican => "ZA",
+            Nationality::Spanish => "ES",
+            Nationality::Swedish => "SE",
+            Nationality::Swiss => "CH",
+            Nationality::Thai => "TH",
+            Nationality::Turkish => "TR",
+            Nationality::Uruguayan => "UY",
+            Nationality::Ukrainian => "UA",
+            Nationality::Venezuelan => "VE",
+            Nationality::Welsh => "GB",
+            Nationality::Unknown(_) => return None,
+        })
+    }
+
+    /// ISO 3166-2 code of the nations of the United Kingdom, e.g. `"GB-SCT"`.
+    pub fn subdivision_code(self) -> Option<&'static str> {
+        match self {
+            Nationality::English => Some("GB-ENG"),
+            Nationality::NorthernIrish => Some("GB-NIR"),
+            Nationality::Scottish => Some("GB-SCT"),
+            Nationality::Welsh => Some("GB-WLS"),
+            _ => None,
+        }
+    }
+
+    /// Emoji flag, e.g. 🇫🇷.
+    ///
+    /// England, Scotland and Wales have flags of their own, shown by fonts
+    /// with tag sequences; Northern Ireland has the flag of the United
+    /// Kingdom.
+    ///
+    /// ```
+    /// use f1_telemetry::packet::participants::Nationality;
+    ///
+    /// assert_eq!(Nationality::French.flag().unwrap(), "\u{1F1EB}\u{1F1F7}");
+    /// assert_eq!(Nationality::Welsh.ascii_flag(), "[WLS]");
+    /// assert_eq!(Nationality::Unknown(200).ascii_flag(), "[??]");
+    /// ```
+    pub fn flag(self) -> Option<String> {
+        let subdivision = match self {
+            Nationality::NorthernIrish => None,
+            _ => self.subdivision_code(),
+        };
+
+        match subdivision {
+            // Black flag, the subdivision in tag characters, and a cancel tag.
+            Some(code) => {
+                let tags = code
+                    .chars()
+                    .filter(|c| c.is_ascii_alphabetic())
+                    .filter_map(|c| char::from_u32(0xE0000 + c.to_ascii_lowercase() as u32));
+
+                Some(
+                    core::iter::once('\u{1F3F4}')
+                        .chain(tags)
+                        .chain(core::iter::once('\u{E007F}'))
+                        .collect(),
+                )
+            }
+            // Pair of regional indicator symbols.
+            None => self
+                .country_code()?
+                .chars()
+                .map(|c| char::from_u32(0x1F1E6 + (c as u32 - 'A' as u32)))
+                .collect(),
+        }
+    }
+
+    /// Flag for terminals without emoji: the country code in brackets, e.g.
+    /// `"[FR]"`, or the nation for the United Kingdom, e.g. `"[SCT]"`.
+    pub fn ascii_flag(self) -> String {
+        let code = match (self.subdivision_code(), self.country_code()) {
+            (Some(subdivision), _) => &subdivision[3..],
+            (None, Some(country)) => country,
+            (None, None) => "??",
+        };
+
+        format!("[{}]", code)
+    }
 }
 
-impl TryFrom<u8> for Nationality {
-    type Error = UnpackError;
+impl From<u8> for Nationality {
+    fn from(value: u8) -> Self {
+        match value {
+            1 => Nationality::American,
+            2 => Nationality::Argentinean,
+            3 => Nationality::Australian,
+            4 => Nationality::Austrian,
+            5 => Nationality::Azerbaijani,
+            6 => Nationality::Bahraini,
+            7 => Nationality::Belgian,
+            8 => Nationality::Bolivian,
+            9 => Nationality::Brazilian,
+            10 => Nationality::British,
+            11 => Nationality::Bulgarian,
+            12 => Nationality::Cameroonian,
+            13 => Nationality::Canadian,
+            14 => Nationality::Chilean,
+            15 => Nationality::Chinese,
+            16 => Nationality::Colombian,
+            17 => Nationality::CostaRican,
+            18 => Nationality::Croatian,
+            19 => Nationality::Cypriot,
+            20 => Nationality::Czech,
+            21 => Nationality::Danish,
+            22 => Nationality::Dutch,
+            23 => Nationality::Ecuadorian,
+            24 => Nationality::English,
+            25 => Nationality::Emirian,
+            26 => Nationality::Estonian,
+            27 => Nationality::Finnish,
+            28 => Nationality::French,
+            29 => Nationality::German,
+            30 => Nationality::Ghanaian,
+            31 => Nationality::Greek,
+            32 => Nationality::Guatemalan,
+            33 => Nationality::Honduran,
+            34 => Nationality::HongKonger,
+            35 => Nationality::Hungarian,
+            36 => Nationality::Icelander,
+            37 => Nationality::Indian,
+            38 => Nationality::Indonesian,
+            39 => Nationality::Irish,
+            40 => Nationality::Israeli,
+            41 => Nationality::Italian,
+            42 => Nationality::Jamaican,
+            43 => Nationality::Japanese,
+            44 => Nationality::Jordanian,
+            45 => Nationality::Kuwaiti,
+            46 => Nationality::Latvian,
+            47 => Nationality::Lebanese,
+            48 => Nationality::Lithuanian,
+            49 => Nationality::Luxembourger,
+            50 => Nationality::Malaysian,
+            51 => Nationality::Maltese,
+            52 => Nationality::Mexican,
+            53 => Nationality::Monegasque,
+            54 => Nationality::NewZealander,
+            55 => Nationality::Nicaraguan,
+            56 => Nationality::NorthKorean,
+            57 => Nationality::NorthernIrish,
+            58 => Nationality::Norwegian,
+            59 => Nationality::Omani,
+            60 => Nationality::Pakistani,
+            61 => Nationality::Panamanian,
+            62 => Nationality::Paraguayan,
+            63 => Nationality::Peruvian,
+            64 => Nationality::Polish,
+            65 => Nationality::Portuguese,
+            66 => Nationality::Qatari,
+            67 => Nationality::Romanian,
+            68 => Nationality::Russian,
+            69 => Nationality::Salvadoran,
+            70 => Nationality::Saudi,
+            71 => Nationality::Scottish,
+            72 => Nationality::Serbian,
+            73 => Nationality::Singaporean,
+            74 => Nationality::Slovakian,
+            75 => Nationality::Slovenian,
+            76 => Nationality::SouthKorean,
+            77 => Nationality::SouthAfrican,
+            78 => Nationality::Spanish,
+            79 => Nationality::Swedish,
+            80 => Nationality::Swiss,
+            81 => Nationality::Thai,
+            82 => Nationality::Turkish,
+            83 => Nationality::Uruguayan,
+            84 => Nationality::Ukrainian,
+            85 => Nationality::Venezuelan,
+            86 => Nationality::Welsh,
+            _ => Nationality::Unknown(value),
+        }
+    }
+}
 
-    fn try_from(value: u8) -> Result<Self, Self::Error> {
+impl From<Nationality> for u8 {
+    fn from(value: Nationality) -> Self {
         match value {
-            1 => Ok(Nationality::American),
-            2 => Ok(Nationality::Argentinean),
-            3 => Ok(Nationality::Australian),
-            4 => Ok(Nationality::Austrian),
-            5 => Ok(Nationality::Azerbaijani),
-            6 => Ok(Nationality::Bahraini),
-            7 => Ok(Nationality::Belgian),
-            8 => Ok(Nationality::Bolivian),
-            9 => Ok(Nationality::Brazilian),
-            10 => Ok(Nationality::British),
-            11 => Ok(Nationality::Bulgarian),
-            12 => Ok(Nationality::Cameroonian),
-            13 => Ok(Nationality::Canadian),
-            14 => Ok(Nationality::Chilean),
-            15 => Ok(Nationality::Chinese),
-            16 => Ok(Nationality::Colombian),
-            17 => Ok(Nationality::CostaRican),
-            18 => Ok(Nationality::Croatian),
-            19 => Ok(Nationality::Cypriot),
-            20 => Ok(Nationality::Czech),
-            21 => Ok(Nationality::Danish),
-            22 => Ok(Nationality::Dutch),
-            23 => Ok(Nationality::Ecuadorian),
-            24 => Ok(Nationality::English),
-            25 => Ok(Nationality::Emirian),
-            26 => Ok(Nationality::Estonian),
-            27 => Ok(Nationality::Finnish),
-            28 => Ok(Nationality::French),
-            29 => Ok(Nationality::German),
-            30 => Ok(Nationality::Ghanaian),
-            31 => Ok(Nationality::Greek),
-            32 => Ok(Nationality::Guatemalan),
-            33 => Ok(Nationality::Honduran),
-            34 => Ok(Nationality::HongKonger),
-            35 => Ok(Nationality::Hungarian),
-            36 => Ok(Nationality::Icelander),
-            37 => Ok(Nationality::Indian),
-            38 => Ok(Nationality::Indonesian),
-            39 => Ok(Nationality::Irish),
-            40 => Ok(Nationality::Israeli),
-            41 => Ok(Nationality::Italian),
-            42 => Ok(Nationality::Jamaican),
-            43 => Ok(Nationality::Japanese),
-            44 => Ok(Nationality::Jordanian),
-            45 => Ok(Nationality::Kuwaiti),
-            46 => Ok(Nationality::Latvian),
-            47 => Ok(Nationality::Lebanese),
-            48 => Ok(Nationality::Lithuanian),
-            49 => Ok(Nationality::Luxembourger),
-            50 => Ok(Nationality::Malaysian),
-            51 => Ok(Nationality::Maltese),
-            52 => Ok(Nationality::Mexican),
-            53 => Ok(Nationality::Monegasque),
-            54 => Ok(Nationality::NewZealander),
-            55 => Ok(Nationality::Nicaraguan),
-            56 => Ok(Nationality::NorthKorean),
-            57 => Ok(Nationality::NorthernIrish),
-            58 => Ok(Nationality::Norwegian),
-            59 => Ok(Nationality::Omani),
-            60 => Ok(Nationality::Pakistani),
-            61 => Ok(Nationality::Panamanian),
-            62 => Ok(Nationality::Paraguayan),
-            63 => Ok(Nationality::Peruvian),
-            64 => Ok(Nationality::Polish),
-            65 => Ok(Nationality::Portuguese),
-            66 => Ok(Nationality::Qatari),
-            67 => Ok(Nationality::Romanian),
-            68 => Ok(Nationality::Russian),
-            69 => Ok(Nationality::Salvadoran),
-            70 => Ok(Nationality::Saudi),
-            71 => Ok(Nationality::Scottish),
-            72 => Ok(Nationality::Serbian),
-            73 => Ok(Nationality::Singaporean),
-            74 => Ok(Nationality::Slovakian),
-            75 => Ok(Nationality::Slovenian),
-            76 => Ok(Nationality::SouthKorean),
-            77 => Ok(Nationality::SouthAfrican),
-            78 => Ok(Nationality::Spanish),
-            79 => Ok(Nationality::Swedish),
-            80 => Ok(Nationality::Swiss),
-            81 => Ok(Nationality::Thai),
-            82 => Ok(Nationality::Turkish),
-            83 => Ok(Nationality::Uruguayan),
-            84 => Ok(Nationality::Ukrainian),
-            85 => Ok(Nationality::Venezuelan),
-            86 => Ok(Nationality::Welsh),
-            _ => Err(UnpackError(format!("Invalid Nationality value: {}", value))),
+            Nationality::American => 1,
+            Nationality::Argentinean => 2,
+            Nationality::Australian => 3,
+            Nationality::Austrian => 4,
+            Nationality::Azerbaijani => 5,
+            Nationality::Bahraini => 6,
+            Nationality::Belgian => 7,
+            Nationality::Bolivian => 8,
+            Nationality::Brazilian => 9,
+            Nationality::British => 10,
+            Nationality::Bulgarian => 11,
+            Nationality::Cameroonian => 12,
+            Nationality::Canadian => 13,
+            Nationality::Chilean => 14,
+            Nationality::Chinese => 15,
+            Nationality::Colombian => 16,
+            Nationality::CostaRican => 17,
+            Nationality::Croatian => 18,
+            Nationality::Cypriot => 19,
+            Nationality::Czech => 20,
+            Nationality::Danish => 21,
+            Nationality::Dutch => 22,
+            Nationality::Ecuadorian => 23,
+            Nationality::English => 24,
+            Nationality::Emirian => 25,
+            Nationality::Estonian => 26,
+            Nationality::Finnish => 27,
+            Nationality::French => 28,
+            Nationality::German => 29,
+            Nationality::Ghanaian => 30,
+            Nationality::Greek => 31,
+            Nationality::Guatemalan => 32,
+            Nationality::Honduran => 33,
+            Nationality::HongKonger => 34,
+            Nationality::Hungarian => 35,
+            Nationality::Icelander => 36,
+            Nationality::Indian => 37,
+            Nationality::Indonesian => 38,
+            Nationality::Irish => 39,
+            Nationality::Israeli => 40,
+            Nationality::Italian => 41,
+            Nationality::Jamaican => 42,
+            Nationality::Japanese => 43,
+            Nationality::Jordanian => 44,
+            Nationality::Kuwaiti => 45,
+            Nationality::Latvian => 46,
+            Nationality::Lebanese => 47,
+            Nationality::Lithuanian => 48,
+            Nationality::Luxembourger => 49,
+            Nationality::Malaysian => 50,
+            Nationality::Maltese => 51,
+            Nationality::Mexican => 52,
+            Nationality::Monegasque => 53,
+            Nationality::NewZealander => 54,
+            Nationality::Nicaraguan => 55,
+            Nationality::NorthKorean => 56,
+            Nationality::NorthernIrish => 57,
+            Nationality::Norwegian => 58,
+            Nationality::Omani => 59,
+            Nationality::Pakistani => 60,
+            Nationality::Panamanian => 61,
+            Nationality::Paraguayan => 62,
+            Nationality::Peruvian => 63,
+            Nationality::Polish => 64,
+            Nationality::Portuguese => 65,
+            Nationality::Qatari => 66,
+            Nationality::Romanian => 67,
+            Nationality::Russian => 68,
+            Nationality::Salvadoran => 69,
+            Nationality::Saudi => 70,
+            Nationality::Scottish => 71,
+            Nationality::Serbian => 72,
+            Nationality::Singaporean => 73,
+            Nationality::Slovakian => 74,
+            Nationality::Slovenian => 75,
+            Nationality::SouthKorean => 76,
+            Nationality::SouthAfrican => 77,
+            Nationality::Spanish => 78,
+            Nationality::Swedish => 79,
+            Nationality::Swiss => 80,
+            Nationality::Thai => 81,
+            Nationality::Turkish => 82,
+            Nationality::Uruguayan => 83,
+            Nationality::Ukrainian => 84,
+            Nationality::Venezuelan => 85,
+            Nationality::Welsh => 86,
+            Nationality::Unknown(value) => value,
         }
     }
 }
@@ -544,47 +1019,72 @@ impl TryFrom<u8> for Nationality {
 pub enum Telemetry {
     Restricted,
     Public,
+    Unknown(u8),
 }
 
-impl TryFrom<u8> for Telemetry {
-    type Error = UnpackError;
+impl From<u8> for Telemetry {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => Telemetry::Restricted,
+            1 => Telemetry::Public,
+            _ => Telemetry::Unknown(value),
+        }
+    }
+}
 
-    fn try_from(value: u8) -> Result<Self, Self::Error> {
+impl From<Telemetry> for u8 {
+    fn from(value: Telemetry) -> Self {
         match value {
-            0 => Ok(Telemetry::Restricted),
-            1 => Ok(Telemetry::Public),
-            _ => Err(UnpackError(format!("Invalid Telemetry value: {}", value))),
+            Telemetry::Restricted => 0,
+            Telemetry::Public => 1,
+            Telemetry::Unknown(value) => value,
         }
     }
 }
 
-/// This type is used for the 20-element `participants` array of the `PacketParticipantsData` type.
+/// This type is used for the 20-element (22 from F1 2020) `participants` array of the
+/// `PacketParticipantsData` type.
+///
+/// The fields only sent in some formats are `None` in the others.
 ///
-/// Size: 54 bytes
+/// Size: 54 bytes (F1 2019-2020), 56 bytes (F1 2021-2022), 58 bytes (F1 2023),
+/// 60 bytes (F1 2024+)
 ///
 /// Version: 1
 ///
 /// ## Specification
 /// ```text
-/// ai_controlled:  Whether the vehicle is AI (1) or Human (0) controlled
-/// driver_id:      Driver id - see appendix
-/// team_id:        Team id - see appendix
-/// race_number:    Race number of the car
-/// nationality:    Nationality of the driver
-/// name:           Name of participant in UTF-8 format – null terminated
-///                 Will be truncated with … (U+2026) if too long
-/// your_telemetry: The player's UDP setting, 0 = restricted, 1 = public
+/// ai_controlled:     Whether the vehicle is AI (1) or Human (0) controlled
+/// driver_id:         Driver id - see appendix
+/// network_id:        Network id – unique identifier for network players (F1 2021+)
+/// team_id:           Team id - see appendix
+/// my_team:           My team flag – 1 = My Team, 0 = otherwise (F1 2021+)
+/// race_number:       Race number of the car
+/// nationality:       Nationality of the driver
+/// name:              Name of participant in UTF-8 format – null terminated
+///                    Will be truncated with … (U+2026) if too long
+/// your_telemetry:    The player's UDP setting, 0 = restricted, 1 = public
+/// show_online_names: The player's show online names setting, 0 = off, 1 = on
+///                    (F1 2023+)
+/// tech_level:        F1 World tech level (F1 2024+)
+/// platform:          1 = Steam, 3 = PlayStation, 4 = Xbox, 6 = Origin,
+///                    255 = unknown (F1 2023+)
 /// ```
 ///
 /// [`PacketParticipantsData`]: ./struct.PacketParticipantsData.html
 #[derive(Debug, CopyGetters, Getters)]
 pub struct ParticipantData {
+    #[getset(get_copy = "pub")]
     ai_controlled: bool,
     #[getset(get_copy = "pub")]
     driver: Driver,
     #[getset(get_copy = "pub")]
+    network_id: Option<u8>,
+    #[getset(get_copy = "pub")]
     team: Team,
     #[getset(get_copy = "pub")]
+    my_team: Option<bool>,
+    #[getset(get_copy = "pub")]
     race_number: u8,
     #[getset(get_copy = "pub")]
     nationality: Nationality,
@@ -592,28 +1092,94 @@ pub struct ParticipantData {
     name: String,
     #[getset(get_copy = "pub")]
     telemetry: Telemetry,
+    #[getset(get_copy = "pub")]
+    show_online_names: Option<bool>,
+    #[getset(get_copy = "pub")]
+    tech_level: Option<u16>,
+    #[getset(get_copy = "pub")]
+    platform: Option<u8>,
 }
 
 impl ParticipantData {
-    pub fn new<T: BufRead>(reader: &mut T) -> Result<ParticipantData, UnpackError> {
+    pub fn new<T: BufRead>(
+        reader: &mut T,
+        packet_format: u16,
+    ) -> Result<ParticipantData, UnpackError> {
         let ai_controlled = reader.read_u8().unwrap() == 1;
-        let driver = Driver::try_from(reader.read_u8().unwrap())?;
-        let team = Team::try_from(reader.read_u8().unwrap())?;
+        let driver = Driver::from(reader.read_u8().unwrap());
+        let network_id = if packet_format >= 2021 {
+            Some(reader.read_u8().unwrap())
+        } else {
+            None
+        };
+        let team = Team::from(reader.read_u8().unwrap());
+        let my_team = if packet_format >= 2021 {
+            Some(reader.read_u8().unwrap() == 1)
+        } else {
+            None
+        };
         let race_number = reader.read_u8().unwrap();
-        let nationality = Nationality::try_from(reader.read_u8().unwrap())?;
+        let nationality = Nationality::from(reader.read_u8().unwrap());
         let name = read_name(reader)?;
-        let telemetry = Telemetry::try_from(reader.read_u8().unwrap())?;
+        let telemetry = Telemetry::from(reader.read_u8().unwrap());
+        let show_online_names = if packet_format >= 2023 {
+            Some(reader.read_u8().unwrap() == 1)
+        } else {
+            None
+        };
+        let tech_level = if packet_format >= 2024 {
+            Some(reader.read_u16::<LittleEndian>().unwrap())
+        } else {
+            None
+        };
+        let platform = if packet_format >= 2023 {
+            Some(reader.read_u8().unwrap())
+        } else {
+            None
+        };
 
         Ok(ParticipantData {
             ai_controlled,
             driver,
+            network_id,
             team,
+            my_team,
             race_number,
             nationality,
             name,
             telemetry,
+            show_online_names,
+            tech_level,
+            platform,
         })
     }
+
+    pub(crate) fn encode<W: Write>(&self, writer: &mut W, packet_format: u16) -> io::Result<()> {
+        writer.write_u8(self.ai_controlled as u8)?;
+        writer.write_u8(self.driver.into())?;
+        if packet_format >= 2021 {
+            writer.write_u8(self.network_id.unwrap_or(0))?;
+        }
+        writer.write_u8(self.team.into())?;
+        if packet_format >= 2021 {
+            writer.write_u8(self.my_team.unwrap_or(false) as u8)?;
+        }
+        writer.write_u8(self.race_number)?;
+        writer.write_u8(self.nationality.into())?;
+        write_name(writer, &self.name)?;
+        writer.write_u8(self.telemetry.into())?;
+        if packet_format >= 2023 {
+            writer.write_u8(self.show_online_names.unwrap_or(false) as u8)?;
+        }
+        if packet_format >= 2024 {
+            writer.write_u16::<LittleEndian>(self.tech_level.unwrap_or(0))?;
+        }
+        if packet_format >= 2023 {
+            writer.write_u8(self.platform.unwrap_or(255))?;
+        }
+
+        Ok(())
+    }
 }
 
 /// This is a list of participants in the race.
@@ -625,7 +1191,8 @@ impl ParticipantData {
 ///
 /// Frequency: Every 5 seconds
 ///
-/// Size: 1104 bytes
+/// Size: 1104 bytes (F1 2019), 1213 bytes (F1 2020), 1257 bytes (F1 2021-2022),
+/// 1306 bytes (F1 2023), 1350 bytes (F1 2024)
 ///
 /// Version: 1
 ///
@@ -634,7 +1201,7 @@ impl ParticipantData {
 /// header:          Header
 /// num_active_cars: Number of active cars in the data – should match number of
 ///                  cars on HUD
-/// participants:    List of participants, max 20.
+/// participants:    List of participants, max 20 (22 from F1 2020).
 /// ```
 #[derive(Debug, Getters, CopyGetters)]
 pub struct PacketParticipantsData {
@@ -653,9 +1220,11 @@ impl PacketParticipantsData {
     ) -> Result<PacketParticipantsData, UnpackError> {
         let num_active_cars = reader.read_u8().unwrap();
 
-        let mut participants = Vec::with_capacity(20);
-        for _ in 0..20 {
-            let p = ParticipantData::new(&mut reader)?;
+        let packet_format = header.packet_format();
+        let num_cars = num_cars(packet_format);
+        let mut participants = Vec::with_capacity(num_cars);
+        for _ in 0..num_cars {
+            let p = ParticipantData::new(&mut reader, packet_format)?;
             participants.push(p);
         }
 
@@ -665,9 +1234,29 @@ impl PacketParticipantsData {
             participants,
         })
     }
+
+    /// Names of the participants, with whether they are AI controlled.
+    #[cfg(feature = "std")]
+    pub(crate) fn names_mut(&mut self) -> impl Iterator<Item = (bool, &mut String)> {
+        self.participants
+            .iter_mut()
+            .map(|p| (p.ai_controlled, &mut p.name))
+    }
+}
+
+impl Encode for PacketParticipantsData {
+    fn encode<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.header.encode(writer)?;
+        writer.write_u8(self.num_active_cars)?;
+        for data in &self.participants {
+            data.encode(writer, self.header.packet_format())?;
+        }
+
+        Ok(())
+    }
 }
 
-fn read_name<T: BufRead>(reader: &mut T) -> Result<String, UnpackError> {
+pub(crate) fn read_name<T: BufRead>(reader: &mut T) -> Result<String, UnpackError> {
     let mut nb_read: u8 = 0;
 
     let mut chars = Vec::with_capacity(48);
@@ -694,3 +1283,13 @@ fn read_name<T: BufRead>(reader: &mut T) -> Result<String, UnpackError> {
         Err(e) => Err(UnpackError(format!("Error decoding name: {}", e))),
     }
 }
+
+pub(crate) fn write_name<W: Write>(writer: &mut W, name: &str) -> io::Result<()> {
+    let mut bytes = [0u8; 48];
+
+    // Keep the last byte for the null terminator.
+    let len = name.len().min(47);
+    bytes[..len].copy_from_slice(&name.as_bytes()[..len]);
+
+    writer.write_all(&bytes)
+}
@@ -0,0 +1,35 @@
+use alloc::vec::Vec;
+use getset::{CopyGetters, Getters};
+
+use crate::packet::{peek_packet_format, peek_packet_id};
+
+/// A packet that was kept as raw bytes instead of being parsed.
+///
+/// Raw packets are produced in lenient mode, when a packet could not be parsed,
+/// for example because it was sent by an unsupported version of the game.
+///
+/// ## Specification
+/// ```text
+/// packet_format: Packet format read from the header, if the packet is long enough
+/// packet_id:     Packet id read from the header, if the packet is long enough
+/// data:          Raw bytes of the packet, header included
+/// ```
+#[derive(Debug, Clone, CopyGetters, Getters)]
+pub struct RawPacket {
+    #[getset(get_copy = "pub")]
+    packet_format: Option<u16>,
+    #[getset(get_copy = "pub")]
+    packet_id: Option<u8>,
+    #[getset(get = "pub")]
+    data: Vec<u8>,
+}
+
+impl RawPacket {
+    pub fn new(data: &[u8]) -> RawPacket {
+        RawPacket {
+            packet_format: peek_packet_format(data),
+            packet_id: peek_packet_id(data),
+            data: data.to_vec(),
+        }
+    }
+}
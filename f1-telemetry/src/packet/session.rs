@@ -1,11 +1,13 @@
-use byteorder::{LittleEndian, ReadBytesExt};
+use alloc::vec::Vec;
+use byteorder::LittleEndian;
 use getset::{CopyGetters, Getters};
-use std::convert::TryFrom;
-use std::io::BufRead;
 
 use super::header::PacketHeader;
+use crate::io::{self, BufRead, ReadBytesExt, Write, WriteBytesExt};
+use crate::packet::encode::Encode;
 use crate::packet::generic::Flag;
 use crate::packet::UnpackError;
+use crate::units::{Celsius, KilometresPerHour};
 
 /// This type is used for the 21-element `marshal_zones` array of the [`PacketSessionData`] type.
 ///
@@ -29,13 +31,20 @@ pub struct MarshalZone {
 impl MarshalZone {
     pub fn new<T: BufRead>(reader: &mut T) -> Result<MarshalZone, UnpackError> {
         let zone_start = reader.read_f32::<LittleEndian>().unwrap();
-        let zone_flag = Flag::try_from(reader.read_i8().unwrap())?;
+        let zone_flag = Flag::from(reader.read_i8().unwrap());
 
         Ok(MarshalZone {
             zone_start,
             zone_flag,
         })
     }
+
+    pub(crate) fn encode<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_f32::<LittleEndian>(self.zone_start)?;
+        writer.write_i8(self.zone_flag.into())?;
+
+        Ok(())
+    }
 }
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
@@ -46,27 +55,50 @@ pub enum Weather {
     LightRain,
     HeavyRain,
     Storm,
+    Unknown(u8),
+}
+
+impl Weather {
+    /// Whether it rains with this weather.
+    pub fn is_wet(self) -> bool {
+        matches!(
+            self,
+            Weather::LightRain | Weather::HeavyRain | Weather::Storm
+        )
+    }
 }
 
-impl TryFrom<u8> for Weather {
-    type Error = UnpackError;
+impl From<u8> for Weather {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => Weather::Clear,
+            1 => Weather::LightCloud,
+            2 => Weather::Overcast,
+            3 => Weather::LightRain,
+            4 => Weather::HeavyRain,
+            5 => Weather::Storm,
+            _ => Weather::Unknown(value),
+        }
+    }
+}
 
-    fn try_from(value: u8) -> Result<Self, Self::Error> {
+impl From<Weather> for u8 {
+    fn from(value: Weather) -> Self {
         match value {
-            0 => Ok(Weather::Clear),
-            1 => Ok(Weather::LightCloud),
-            2 => Ok(Weather::Overcast),
-            3 => Ok(Weather::LightRain),
-            4 => Ok(Weather::HeavyRain),
-            5 => Ok(Weather::Storm),
-            _ => Err(UnpackError(format!("Invalid Weather value: {}", value))),
+            Weather::Clear => 0,
+            Weather::LightCloud => 1,
+            Weather::Overcast => 2,
+            Weather::LightRain => 3,
+            Weather::HeavyRain => 4,
+            Weather::Storm => 5,
+            Weather::Unknown(value) => value,
         }
     }
 }
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
 pub enum SessionType {
-    Unknown,
+    Unknown(u8),
     Practice1,
     Practice2,
     Practice3,
@@ -84,7 +116,7 @@ pub enum SessionType {
 impl SessionType {
     pub fn name<'a>(self) -> &'a str {
         match self {
-            SessionType::Unknown => "Unknown",
+            SessionType::Unknown(_) => "Unknown",
             SessionType::Practice1 => "Free Practice 1",
             SessionType::Practice2 => "Free Practice 2",
             SessionType::Practice3 => "Free Practice 3",
@@ -101,25 +133,42 @@ impl SessionType {
     }
 }
 
-impl TryFrom<u8> for SessionType {
-    type Error = UnpackError;
+impl From<u8> for SessionType {
+    fn from(value: u8) -> Self {
+        match value {
+            1 => SessionType::Practice1,
+            2 => SessionType::Practice2,
+            3 => SessionType::Practice3,
+            4 => SessionType::PracticeShort,
+            5 => SessionType::Qualifying1,
+            6 => SessionType::Qualifying2,
+            7 => SessionType::Qualifying3,
+            8 => SessionType::QualifyingShort,
+            9 => SessionType::OneShotQualifying,
+            10 => SessionType::Race,
+            11 => SessionType::Race2,
+            12 => SessionType::TimeTrial,
+            _ => SessionType::Unknown(value),
+        }
+    }
+}
 
-    fn try_from(value: u8) -> Result<Self, Self::Error> {
+impl From<SessionType> for u8 {
+    fn from(value: SessionType) -> Self {
         match value {
-            0 => Ok(SessionType::Unknown),
-            1 => Ok(SessionType::Practice1),
-            2 => Ok(SessionType::Practice2),
-            3 => Ok(SessionType::Practice3),
-            4 => Ok(SessionType::PracticeShort),
-            5 => Ok(SessionType::Qualifying1),
-            6 => Ok(SessionType::Qualifying2),
-            7 => Ok(SessionType::Qualifying3),
-            8 => Ok(SessionType::QualifyingShort),
-            9 => Ok(SessionType::OneShotQualifying),
-            10 => Ok(SessionType::Race),
-            11 => Ok(SessionType::Race2),
-            12 => Ok(SessionType::TimeTrial),
-            _ => Err(UnpackError(format!("Invalid SessionType value: {}", value))),
+            SessionType::Practice1 => 1,
+            SessionType::Practice2 => 2,
+            SessionType::Practice3 => 3,
+            SessionType::PracticeShort => 4,
+            SessionType::Qualifying1 => 5,
+            SessionType::Qualifying2 => 6,
+            SessionType::Qualifying3 => 7,
+            SessionType::QualifyingShort => 8,
+            SessionType::OneShotQualifying => 9,
+            SessionType::Race => 10,
+            SessionType::Race2 => 11,
+            SessionType::TimeTrial => 12,
+            SessionType::Unknown(value) => value,
         }
     }
 }
@@ -151,7 +200,7 @@ pub enum Track {
     SilverstoneShort,
     TexasShort,
     SuzukaShort,
-    Unknown,
+    Unknown(i8),
 }
 
 impl Track {
@@ -182,43 +231,73 @@ impl Track {
             Track::SilverstoneShort => "Silverstone Circuit (Short)",
             Track::TexasShort => "Circuit of the Americas (Short)",
             Track::SuzukaShort => "Suzuka International Racing Course (Short)",
-            Track::Unknown => "[UNKNOWN]",
+            Track::Unknown(_) => "[UNKNOWN]",
         }
     }
 }
 
-impl TryFrom<i8> for Track {
-    type Error = UnpackError;
+impl From<i8> for Track {
+    fn from(value: i8) -> Self {
+        match value {
+            0 => Track::Melbourne,
+            1 => Track::PaulRicard,
+            2 => Track::Shanghai,
+            3 => Track::Sakhir,
+            4 => Track::Catalunya,
+            5 => Track::Monaco,
+            6 => Track::Montreal,
+            7 => Track::Silverstone,
+            8 => Track::Hockenheim,
+            9 => Track::Hungaroring,
+            10 => Track::Spa,
+            11 => Track::Monza,
+            12 => Track::Singapore,
+            13 => Track::Suzuka,
+            14 => Track::AbuDhabi,
+            15 => Track::Texas,
+            16 => Track::Brazil,
+            17 => Track::Austria,
+            18 => Track::Sochi,
+            19 => Track::Mexico,
+            20 => Track::Baku,
+            21 => Track::SakhirShort,
+            22 => Track::SilverstoneShort,
+            23 => Track::TexasShort,
+            24 => Track::SuzukaShort,
+            _ => Track::Unknown(value),
+        }
+    }
+}
 
-    fn try_from(value: i8) -> Result<Self, Self::Error> {
+impl From<Track> for i8 {
+    fn from(value: Track) -> Self {
         match value {
-            0 => Ok(Track::Melbourne),
-            1 => Ok(Track::PaulRicard),
-            2 => Ok(Track::Shanghai),
-            3 => Ok(Track::Sakhir),
-            4 => Ok(Track::Catalunya),
-            5 => Ok(Track::Monaco),
-            6 => Ok(Track::Montreal),
-            7 => Ok(Track::Silverstone),
-            8 => Ok(Track::Hockenheim),
-            9 => Ok(Track::Hungaroring),
-            10 => Ok(Track::Spa),
-            11 => Ok(Track::Monza),
-            12 => Ok(Track::Singapore),
-            13 => Ok(Track::Suzuka),
-            14 => Ok(Track::AbuDhabi),
-            15 => Ok(Track::Texas),
-            16 => Ok(Track::Brazil),
-            17 => Ok(Track::Austria),
-            18 => Ok(Track::Sochi),
-            19 => Ok(Track::Mexico),
-            20 => Ok(Track::Baku),
-            21 => Ok(Track::SakhirShort),
-            22 => Ok(Track::SilverstoneShort),
-            23 => Ok(Track::TexasShort),
-            24 => Ok(Track::SuzukaShort),
-            -1 => Ok(Track::Unknown),
-            _ => Err(UnpackError(format!("Invalid Track value: {}", value))),
+            Track::Melbourne => 0,
+            Track::PaulRicard => 1,
+            Track::Shanghai => 2,
+            Track::Sakhir => 3,
+            Track::Catalunya => 4,
+            Track::Monaco => 5,
+            Track::Montreal => 6,
+            Track::Silverstone => 7,
+            Track::Hockenheim => 8,
+            Track::Hungaroring => 9,
+            Track::Spa => 10,
+            Track::Monza => 11,
+            Track::Singapore => 12,
+            Track::Suzuka => 13,
+            Track::AbuDhabi => 14,
+            Track::Texas => 15,
+            Track::Brazil => 16,
+            Track::Austria => 17,
+            Track::Sochi => 18,
+            Track::Mexico => 19,
+            Track::Baku => 20,
+            Track::SakhirShort => 21,
+            Track::SilverstoneShort => 22,
+            Track::TexasShort => 23,
+            Track::SuzukaShort => 24,
+            Track::Unknown(value) => value,
         }
     }
 }
@@ -229,18 +308,29 @@ pub enum Formula {
     F1Classic,
     F2,
     F1Generic,
+    Unknown(u8),
 }
 
-impl TryFrom<u8> for Formula {
-    type Error = UnpackError;
+impl From<u8> for Formula {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => Formula::F1Modern,
+            1 => Formula::F1Classic,
+            2 => Formula::F2,
+            3 => Formula::F1Generic,
+            _ => Formula::Unknown(value),
+        }
+    }
+}
 
-    fn try_from(value: u8) -> Result<Self, Self::Error> {
+impl From<Formula> for u8 {
+    fn from(value: Formula) -> Self {
         match value {
-            0 => Ok(Formula::F1Modern),
-            1 => Ok(Formula::F1Classic),
-            2 => Ok(Formula::F2),
-            3 => Ok(Formula::F1Generic),
-            _ => Err(UnpackError(format!("Invalid Formula value: {}", value))),
+            Formula::F1Modern => 0,
+            Formula::F1Classic => 1,
+            Formula::F2 => 2,
+            Formula::F1Generic => 3,
+            Formula::Unknown(value) => value,
         }
     }
 }
@@ -250,18 +340,159 @@ pub enum SafetyCar {
     None,
     Full,
     Virtual,
+    Unknown(u8),
+}
+
+impl From<u8> for SafetyCar {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => SafetyCar::None,
+            1 => SafetyCar::Full,
+            2 => SafetyCar::Virtual,
+            _ => SafetyCar::Unknown(value),
+        }
+    }
+}
+
+impl From<SafetyCar> for u8 {
+    fn from(value: SafetyCar) -> Self {
+        match value {
+            SafetyCar::None => 0,
+            SafetyCar::Full => 1,
+            SafetyCar::Virtual => 2,
+            SafetyCar::Unknown(value) => value,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum TemperatureChange {
+    Up,
+    Down,
+    NoChange,
+    Unknown(i8),
 }
 
-impl TryFrom<u8> for SafetyCar {
-    type Error = UnpackError;
+impl From<i8> for TemperatureChange {
+    fn from(value: i8) -> Self {
+        match value {
+            0 => TemperatureChange::Up,
+            1 => TemperatureChange::Down,
+            2 => TemperatureChange::NoChange,
+            _ => TemperatureChange::Unknown(value),
+        }
+    }
+}
 
-    fn try_from(value: u8) -> Result<Self, Self::Error> {
+impl From<TemperatureChange> for i8 {
+    fn from(value: TemperatureChange) -> Self {
         match value {
-            0 => Ok(SafetyCar::None),
-            1 => Ok(SafetyCar::Full),
-            2 => Ok(SafetyCar::Virtual),
-            _ => Err(UnpackError(format!("Invalid SafetyCar value: {}", value))),
+            TemperatureChange::Up => 0,
+            TemperatureChange::Down => 1,
+            TemperatureChange::NoChange => 2,
+            TemperatureChange::Unknown(value) => value,
+        }
+    }
+}
+
+/// Number of weather forecast samples sent in the session packet, for a given
+/// packet format.
+fn weather_forecast_array_size(packet_format: u16) -> usize {
+    match packet_format {
+        2020 => 20,
+        2021..=2023 => 56,
+        _ => 64,
+    }
+}
+
+/// This type is used for the `weather_forecast_samples` array of the
+/// [`PacketSessionData`] type (F1 2020+).
+///
+/// ## Specification
+/// ```text
+/// session_type:             Session the sample is for, see the session packet
+/// time_offset:              Time in minutes the forecast is for
+/// weather:                  Weather - 0 = clear, 1 = light cloud, 2 = overcast
+///                           3 = light rain, 4 = heavy rain, 5 = storm
+/// track_temperature:        Track temp. in degrees celsius
+/// track_temperature_change: Track temp. change - 0 = up, 1 = down, 2 = no change
+///                           (F1 2021+)
+/// air_temperature:          Air temp. in degrees celsius
+/// air_temperature_change:   Air temp. change - 0 = up, 1 = down, 2 = no change
+///                           (F1 2021+)
+/// rain_percentage:          Rain percentage (0-100) (F1 2021+)
+/// ```
+/// [`PacketSessionData`]: ./struct.PacketSessionData.html
+#[derive(Debug, Clone, Copy, PartialEq, CopyGetters)]
+#[getset(get_copy = "pub")]
+pub struct WeatherForecastSample {
+    session_type: SessionType,
+    time_offset: u8,
+    weather: Weather,
+    track_temperature: i8,
+    track_temperature_change: Option<TemperatureChange>,
+    air_temperature: i8,
+    air_temperature_change: Option<TemperatureChange>,
+    rain_percentage: Option<u8>,
+}
+
+impl WeatherForecastSample {
+    pub fn new<T: BufRead>(
+        reader: &mut T,
+        packet_format: u16,
+    ) -> Result<WeatherForecastSample, UnpackError> {
+        let extended = packet_format >= 2021;
+
+        let session_type = SessionType::from(reader.read_u8().unwrap());
+        let time_offset = reader.read_u8().unwrap();
+        let weather = Weather::from(reader.read_u8().unwrap());
+        let track_temperature = reader.read_i8().unwrap();
+        let track_temperature_change = if extended {
+            Some(TemperatureChange::from(reader.read_i8().unwrap()))
+        } else {
+            None
+        };
+        let air_temperature = reader.read_i8().unwrap();
+        let air_temperature_change = if extended {
+            Some(TemperatureChange::from(reader.read_i8().unwrap()))
+        } else {
+            None
+        };
+        let rain_percentage = if extended {
+            Some(reader.read_u8().unwrap())
+        } else {
+            None
+        };
+
+        Ok(WeatherForecastSample {
+            session_type,
+            time_offset,
+            weather,
+            track_temperature,
+            track_temperature_change,
+            air_temperature,
+            air_temperature_change,
+            rain_percentage,
+        })
+    }
+
+    pub(crate) fn encode<W: Write>(&self, writer: &mut W, packet_format: u16) -> io::Result<()> {
+        let extended = packet_format >= 2021;
+
+        writer.write_u8(self.session_type.into())?;
+        writer.write_u8(self.time_offset)?;
+        writer.write_u8(self.weather.into())?;
+        writer.write_i8(self.track_temperature)?;
+        if extended {
+            writer.write_i8(self.track_temperature_change.map(i8::from).unwrap_or(2))?;
+        }
+        writer.write_i8(self.air_temperature)?;
+        if extended {
+            writer.write_i8(self.air_temperature_change.map(i8::from).unwrap_or(2))?;
+            writer.write_u8(self.rain_percentage.unwrap_or(0))?;
         }
+
+        Ok(())
     }
 }
 
@@ -300,6 +531,11 @@ impl TryFrom<u8> for SafetyCar {
 /// safety_car_status:      0 = no safety car, 1 = full safety car
 ///                         2 = virtual safety car
 /// network_game:           0 = offline, 1 = online
+///
+/// # From F1 2020
+/// num_weather_forecast_samples: Number of weather samples to follow
+/// weather_forecast_samples:     List of weather forecast samples - 20 in F1 2020,
+///                               56 in F1 2021 to 2023, 64 from F1 2024
 /// ```
 #[derive(Debug, CopyGetters, Getters)]
 pub struct PacketSessionData {
@@ -343,6 +579,10 @@ pub struct PacketSessionData {
     safety_car_status: SafetyCar,
     #[getset(get_copy = "pub")]
     network_game: bool,
+    #[getset(get_copy = "pub")]
+    num_weather_forecast_samples: u8,
+    #[getset(get = "pub")]
+    weather_forecast_samples: Vec<WeatherForecastSample>,
 }
 
 impl PacketSessionData {
@@ -350,14 +590,14 @@ impl PacketSessionData {
         mut reader: &mut T,
         header: PacketHeader,
     ) -> Result<PacketSessionData, UnpackError> {
-        let weather = Weather::try_from(reader.read_u8().unwrap())?;
+        let weather = Weather::from(reader.read_u8().unwrap());
         let track_temperature = reader.read_i8().unwrap();
         let air_temperature = reader.read_i8().unwrap();
         let total_laps = reader.read_u8().unwrap();
         let track_length = reader.read_u16::<LittleEndian>().unwrap();
-        let session_type = SessionType::try_from(reader.read_u8().unwrap())?;
-        let track = Track::try_from(reader.read_i8().unwrap())?;
-        let formula = Formula::try_from(reader.read_u8().unwrap())?;
+        let session_type = SessionType::from(reader.read_u8().unwrap());
+        let track = Track::from(reader.read_i8().unwrap());
+        let formula = Formula::from(reader.read_u8().unwrap());
         let session_time_left = reader.read_u16::<LittleEndian>().unwrap();
         let session_duration = reader.read_u16::<LittleEndian>().unwrap();
         let pit_speed_limit = reader.read_u8().unwrap();
@@ -373,9 +613,23 @@ impl PacketSessionData {
             marshal_zones.push(mz);
         }
 
-        let safety_car_status = SafetyCar::try_from(reader.read_u8().unwrap())?;
+        let safety_car_status = SafetyCar::from(reader.read_u8().unwrap());
         let network_game = reader.read_u8().unwrap() == 1;
 
+        let packet_format = header.packet_format();
+        let mut num_weather_forecast_samples = 0;
+        let mut weather_forecast_samples = Vec::new();
+        if packet_format >= 2020 {
+            num_weather_forecast_samples = reader.read_u8().unwrap();
+
+            let array_size = weather_forecast_array_size(packet_format);
+            weather_forecast_samples.reserve(array_size);
+            for _ in 0..array_size {
+                let wfs = WeatherForecastSample::new(&mut reader, packet_format)?;
+                weather_forecast_samples.push(wfs);
+            }
+        }
+
         Ok(PacketSessionData {
             header,
             weather,
@@ -397,6 +651,70 @@ impl PacketSessionData {
             marshal_zones,
             safety_car_status,
             network_game,
+            num_weather_forecast_samples,
+            weather_forecast_samples,
         })
     }
+
+    /// Marshal zones of the track, without the unused ones.
+    pub fn active_marshal_zones(&self) -> &[MarshalZone] {
+        let num_zones = (self.num_marshal_zones as usize).min(self.marshal_zones.len());
+        &self.marshal_zones[..num_zones]
+    }
+
+    /// Weather forecast samples sent by the game (F1 2020+).
+    pub fn weather_forecast(&self) -> &[WeatherForecastSample] {
+        let num_samples =
+            (self.num_weather_forecast_samples as usize).min(self.weather_forecast_samples.len());
+        &self.weather_forecast_samples[..num_samples]
+    }
+
+    pub fn track_temperature_celsius(&self) -> Celsius {
+        Celsius(f32::from(self.track_temperature))
+    }
+
+    pub fn air_temperature_celsius(&self) -> Celsius {
+        Celsius(f32::from(self.air_temperature))
+    }
+
+    pub fn pit_speed_limit_kmh(&self) -> KilometresPerHour {
+        KilometresPerHour(f32::from(self.pit_speed_limit))
+    }
+}
+
+impl Encode for PacketSessionData {
+    fn encode<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.header.encode(writer)?;
+        writer.write_u8(self.weather.into())?;
+        writer.write_i8(self.track_temperature)?;
+        writer.write_i8(self.air_temperature)?;
+        writer.write_u8(self.total_laps)?;
+        writer.write_u16::<LittleEndian>(self.track_length)?;
+        writer.write_u8(self.session_type.into())?;
+        writer.write_i8(self.track.into())?;
+        writer.write_u8(self.formula.into())?;
+        writer.write_u16::<LittleEndian>(self.session_time_left)?;
+        writer.write_u16::<LittleEndian>(self.session_duration)?;
+        writer.write_u8(self.pit_speed_limit)?;
+        writer.write_u8(self.game_paused)?;
+        writer.write_u8(self.is_spectating as u8)?;
+        writer.write_u8(self.spectator_car_index)?;
+        writer.write_u8(self.sli_pro_native_support as u8)?;
+        writer.write_u8(self.num_marshal_zones)?;
+        for mz in &self.marshal_zones {
+            mz.encode(writer)?;
+        }
+        writer.write_u8(self.safety_car_status.into())?;
+        writer.write_u8(self.network_game as u8)?;
+
+        let packet_format = self.header.packet_format();
+        if packet_format >= 2020 {
+            writer.write_u8(self.num_weather_forecast_samples)?;
+            for wfs in &self.weather_forecast_samples {
+                wfs.encode(writer, packet_format)?;
+            }
+        }
+
+        Ok(())
+    }
 }
@@ -0,0 +1,199 @@
+use byteorder::LittleEndian;
+use getset::{CopyGetters, Getters};
+
+use super::header::PacketHeader;
+#[cfg(not(feature = "std"))]
+use crate::float::F32Ext;
+use crate::io::{self, BufRead, ReadBytesExt, Write, WriteBytesExt};
+use crate::packet::encode::Encode;
+use crate::packet::UnpackError;
+
+/// This type is used for the three data sets of the [`PacketTimeTrialData`] type.
+///
+/// Times are sent in milliseconds, and exposed in seconds.
+///
+/// ## Specification
+/// ```text
+/// car_idx:               Index of the car this data relates to
+/// team_id:               Team id - see appendix
+/// lap_time:              Lap time in seconds
+/// sector1_time:          Sector 1 time in seconds
+/// sector2_time:          Sector 2 time in seconds
+/// sector3_time:          Sector 3 time in seconds
+/// traction_control:      0 = off, 1 = medium, 2 = full
+/// gearbox_assist:        1 = manual, 2 = manual & suggested gear, 3 = auto
+/// anti_lock_brakes:      0 (off) - 1 (on)
+/// equal_car_performance: 0 = realistic, 1 = equal
+/// custom_setup:          0 = no, 1 = yes
+/// valid:                 0 = invalid, 1 = valid
+/// ```
+///
+/// [`PacketTimeTrialData`]: ./struct.PacketTimeTrialData.html
+#[derive(Debug, Clone, Copy, CopyGetters)]
+#[getset(get_copy = "pub")]
+pub struct TimeTrialDataSet {
+    car_idx: u8,
+    team_id: u8,
+    lap_time: f32,
+    sector1_time: f32,
+    sector2_time: f32,
+    sector3_time: f32,
+    traction_control: u8,
+    gearbox_assist: u8,
+    anti_lock_brakes: bool,
+    equal_car_performance: bool,
+    custom_setup: bool,
+    valid: bool,
+}
+
+impl TimeTrialDataSet {
+    pub fn new<T: BufRead>(reader: &mut T) -> Result<TimeTrialDataSet, UnpackError> {
+        let car_idx = reader.read_u8().unwrap();
+        let team_id = reader.read_u8().unwrap();
+        let lap_time = read_time(reader);
+        let sector1_time = read_time(reader);
+        let sector2_time = read_time(reader);
+        let sector3_time = read_time(reader);
+        let traction_control = reader.read_u8().unwrap();
+        let gearbox_assist = reader.read_u8().unwrap();
+        let anti_lock_brakes = reader.read_u8().unwrap() == 1;
+        let equal_car_performance = reader.read_u8().unwrap() == 1;
+        let custom_setup = reader.read_u8().unwrap() == 1;
+        let valid = reader.read_u8().unwrap() == 1;
+
+        Ok(TimeTrialDataSet {
+            car_idx,
+            team_id,
+            lap_time,
+            sector1_time,
+            sector2_time,
+            sector3_time,
+            traction_control,
+            gearbox_assist,
+            anti_lock_brakes,
+            equal_car_performance,
+            custom_setup,
+            valid,
+        })
+    }
+
+    /// Whether a lap has been set for this data set.
+    pub fn has_lap(&self) -> bool {
+        self.lap_time > 0.0
+    }
+
+    pub fn sector_times(&self) -> [f32; 3] {
+        [self.sector1_time, self.sector2_time, self.sector3_time]
+    }
+
+    pub(crate) fn encode<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_u8(self.car_idx)?;
+        writer.write_u8(self.team_id)?;
+        write_time(writer, self.lap_time)?;
+        write_time(writer, self.sector1_time)?;
+        write_time(writer, self.sector2_time)?;
+        write_time(writer, self.sector3_time)?;
+        writer.write_u8(self.traction_control)?;
+        writer.write_u8(self.gearbox_assist)?;
+        writer.write_u8(self.anti_lock_brakes as u8)?;
+        writer.write_u8(self.equal_car_performance as u8)?;
+        writer.write_u8(self.custom_setup as u8)?;
+        writer.write_u8(self.valid as u8)?;
+
+        Ok(())
+    }
+}
+
+/// This packet details the best laps of the current time trial session (F1 2024+).
+///
+/// Frequency: 1 per second
+///
+/// Size: 101 bytes
+///
+/// Version: 1
+///
+/// ## Specification
+/// ```text
+/// header:                       Header
+/// player_session_best_data_set: Player session best data set
+/// personal_best_data_set:       Personal best data set
+/// rival_data_set:               Rival data set
+/// ```
+#[derive(Debug, Getters)]
+#[getset(get = "pub")]
+pub struct PacketTimeTrialData {
+    header: PacketHeader,
+    player_session_best_data_set: TimeTrialDataSet,
+    personal_best_data_set: TimeTrialDataSet,
+    rival_data_set: TimeTrialDataSet,
+}
+
+impl PacketTimeTrialData {
+    pub fn new<T: BufRead>(
+        mut reader: &mut T,
+        header: PacketHeader,
+    ) -> Result<PacketTimeTrialData, UnpackError> {
+        let player_session_best_data_set = TimeTrialDataSet::new(&mut reader)?;
+        let personal_best_data_set = TimeTrialDataSet::new(&mut reader)?;
+        let rival_data_set = TimeTrialDataSet::new(&mut reader)?;
+
+        Ok(PacketTimeTrialData {
+            header,
+            player_session_best_data_set,
+            personal_best_data_set,
+            rival_data_set,
+        })
+    }
+
+    /// Gap in seconds from the player's session best to their personal best.
+    /// Negative when the session best is faster.
+    pub fn session_best_to_personal_best(&self) -> Option<f32> {
+        gap(
+            &self.player_session_best_data_set,
+            &self.personal_best_data_set,
+        )
+    }
+
+    /// Gap in seconds from the player's personal best to the rival's lap.
+    /// Negative when the player is faster.
+    pub fn personal_best_to_rival(&self) -> Option<f32> {
+        gap(&self.personal_best_data_set, &self.rival_data_set)
+    }
+
+    /// Sector by sector gaps from the player's personal best to the rival's lap.
+    pub fn sector_gaps_to_rival(&self) -> Option<[f32; 3]> {
+        if !self.personal_best_data_set.has_lap() || !self.rival_data_set.has_lap() {
+            return None;
+        }
+
+        let pb = self.personal_best_data_set.sector_times();
+        let rival = self.rival_data_set.sector_times();
+
+        Some([pb[0] - rival[0], pb[1] - rival[1], pb[2] - rival[2]])
+    }
+}
+
+impl Encode for PacketTimeTrialData {
+    fn encode<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.header.encode(writer)?;
+        self.player_session_best_data_set.encode(writer)?;
+        self.personal_best_data_set.encode(writer)?;
+        self.rival_data_set.encode(writer)
+    }
+}
+
+fn gap(a: &TimeTrialDataSet, b: &TimeTrialDataSet) -> Option<f32> {
+    if a.has_lap() && b.has_lap() {
+        Some(a.lap_time - b.lap_time)
+    } else {
+        None
+    }
+}
+
+fn read_time<T: BufRead>(reader: &mut T) -> f32 {
+    reader.read_u32::<LittleEndian>().unwrap() as f32 / 1000.0
+}
+
+fn write_time<W: Write>(writer: &mut W, time: f32) -> io::Result<()> {
+    writer.write_u32::<LittleEndian>((time * 1000.0).round() as u32)
+}
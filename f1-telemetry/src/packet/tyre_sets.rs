@@ -0,0 +1,168 @@
+use alloc::vec::Vec;
+use byteorder::LittleEndian;
+use getset::{CopyGetters, Getters};
+
+use super::header::PacketHeader;
+use crate::io::{self, BufRead, ReadBytesExt, Write, WriteBytesExt};
+use crate::packet::car_status::{TyreCompound, TyreCompoundVisual};
+use crate::packet::encode::Encode;
+use crate::packet::UnpackError;
+
+/// Number of tyre sets in the packet: 13 dry and 7 wet.
+const NUM_TYRE_SETS: usize = 20;
+
+/// This type is used for the 20-element `tyre_set_data` array of the [`PacketTyreSetsData`] type.
+///
+/// ## Specification
+/// ```text
+/// actual_tyre_compound: Actual tyre compound used
+/// visual_tyre_compound: Visual tyre compound used
+/// wear:                 Tyre wear (percentage)
+/// available:            Whether this set is currently available
+/// recommended_session:  Recommended session id for this set
+/// life_span:            Laps left in this tyre set
+/// usable_life:          Max number of laps recommended for this compound
+/// lap_delta_time:       Lap delta time in milliseconds compared to fitted set
+/// fitted:               Whether the set is fitted or not
+/// ```
+///
+/// [`PacketTyreSetsData`]: ./struct.PacketTyreSetsData.html
+#[derive(Debug, Clone, Copy, CopyGetters)]
+#[getset(get_copy = "pub")]
+pub struct TyreSetData {
+    actual_tyre_compound: TyreCompound,
+    visual_tyre_compound: TyreCompoundVisual,
+    wear: u8,
+    available: bool,
+    recommended_session: u8,
+    life_span: u8,
+    usable_life: u8,
+    lap_delta_time: i16,
+    fitted: bool,
+}
+
+impl TyreSetData {
+    pub fn new<T: BufRead>(reader: &mut T, packet_format: u16) -> Result<TyreSetData, UnpackError> {
+        let actual_tyre_compound =
+            TyreCompound::from_format(reader.read_u8().unwrap(), packet_format);
+        let visual_tyre_compound =
+            TyreCompoundVisual::from_format(reader.read_u8().unwrap(), packet_format);
+        let wear = reader.read_u8().unwrap();
+        let available = reader.read_u8().unwrap() == 1;
+        let recommended_session = reader.read_u8().unwrap();
+        let life_span = reader.read_u8().unwrap();
+        let usable_life = reader.read_u8().unwrap();
+        let lap_delta_time = reader.read_i16::<LittleEndian>().unwrap();
+        let fitted = reader.read_u8().unwrap() == 1;
+
+        Ok(TyreSetData {
+            actual_tyre_compound,
+            visual_tyre_compound,
+            wear,
+            available,
+            recommended_session,
+            life_span,
+            usable_life,
+            lap_delta_time,
+            fitted,
+        })
+    }
+
+    pub(crate) fn encode<W: Write>(&self, writer: &mut W, packet_format: u16) -> io::Result<()> {
+        writer.write_u8(self.actual_tyre_compound.to_format(packet_format))?;
+        writer.write_u8(self.visual_tyre_compound.to_format(packet_format))?;
+        writer.write_u8(self.wear)?;
+        writer.write_u8(self.available as u8)?;
+        writer.write_u8(self.recommended_session)?;
+        writer.write_u8(self.life_span)?;
+        writer.write_u8(self.usable_life)?;
+        writer.write_i16::<LittleEndian>(self.lap_delta_time)?;
+        writer.write_u8(self.fitted as u8)?;
+
+        Ok(())
+    }
+}
+
+/// This packet gives a more in-depth details about tyre sets assigned to a vehicle
+/// during the session (F1 2023+).
+///
+/// Frequency: 20 per second but cycling through cars
+///
+/// Size: 231 bytes
+///
+/// Version: 1
+///
+/// ## Specification
+/// ```text
+/// header:        Header
+/// car_idx:       Index of the car this data relates to
+/// tyre_set_data: List of tyre sets (13 dry + 7 wet)
+/// fitted_idx:    Index into array of fitted tyre
+/// ```
+#[derive(Debug, CopyGetters, Getters)]
+pub struct PacketTyreSetsData {
+    #[getset(get = "pub")]
+    header: PacketHeader,
+    #[getset(get_copy = "pub")]
+    car_idx: u8,
+    #[getset(get = "pub")]
+    tyre_set_data: Vec<TyreSetData>,
+    #[getset(get_copy = "pub")]
+    fitted_idx: u8,
+}
+
+impl PacketTyreSetsData {
+    pub fn new<T: BufRead>(
+        mut reader: &mut T,
+        header: PacketHeader,
+    ) -> Result<PacketTyreSetsData, UnpackError> {
+        let car_idx = reader.read_u8().unwrap();
+
+        let mut tyre_set_data = Vec::with_capacity(NUM_TYRE_SETS);
+        for _ in 0..NUM_TYRE_SETS {
+            let tsd = TyreSetData::new(&mut reader, header.packet_format())?;
+            tyre_set_data.push(tsd);
+        }
+
+        let fitted_idx = reader.read_u8().unwrap();
+
+        Ok(PacketTyreSetsData {
+            header,
+            car_idx,
+            tyre_set_data,
+            fitted_idx,
+        })
+    }
+
+    /// Whether this packet relates to the player's car.
+    pub fn is_player_car(&self) -> bool {
+        self.car_idx == self.header.player_car_index()
+    }
+
+    /// Tyre set currently fitted to the car.
+    pub fn fitted(&self) -> Option<&TyreSetData> {
+        self.tyre_set_data.get(self.fitted_idx as usize)
+    }
+
+    /// Tyre sets that can still be fitted, with the most laps left first.
+    pub fn available(&self) -> Vec<&TyreSetData> {
+        let mut sets: Vec<&TyreSetData> =
+            self.tyre_set_data.iter().filter(|s| s.available).collect();
+        sets.sort_by_key(|s| core::cmp::Reverse(s.life_span));
+        sets
+    }
+}
+
+impl Encode for PacketTyreSetsData {
+    fn encode<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.header.encode(writer)?;
+        writer.write_u8(self.car_idx)?;
+        for data in &self.tyre_set_data {
+            data.encode(writer, self.header.packet_format())?;
+        }
+
+        writer.write_u8(self.fitted_idx)?;
+
+        Ok(())
+    }
+}
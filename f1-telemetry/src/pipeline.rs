@@ -0,0 +1,354 @@
+//! Fan-out of the parsed packets to several consumers.
+//!
+//! A [`Pipeline`] holds a list of [`PacketSink`]s, such as a recorder, a CSV
+//! exporter or some live analysis, and hands every packet to all of them. Sinks
+//! can run inline, on a worker thread of their own, or behind a channel that
+//! another thread (e.g. a UI) reads from.
+//!
+//! Workers queue a bounded number of packets. A [`DropPolicy`] sets what
+//! happens when the queue of a slow sink, e.g. a network database, is full, so
+//! that it does not stall the other sinks.
+//!
+//! Sinks can also be attached to a running pipeline, e.g. a UI connecting
+//! mid-session, and first be handed the packets of a [`SessionCache`] so that
+//! they know the session and its drivers straight away.
+//!
+//! [`Pipeline`]: ./struct.Pipeline.html
+//! [`PacketSink`]: ./trait.PacketSink.html
+//! [`DropPolicy`]: ./enum.DropPolicy.html
+//! [`SessionCache`]: ../session/struct.SessionCache.html
+
+use std::collections::VecDeque;
+use std::sync::mpsc::Sender;
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::thread::{self, JoinHandle};
+
+use crate::packet::Packet;
+use crate::session::SessionCache;
+use crate::{Stream, StreamError};
+
+/// A consumer of parsed packets.
+///
+/// Closures taking a `&Packet` are sinks too.
+pub trait PacketSink: Send {
+    fn handle(&mut self, packet: &Packet);
+
+    /// Called once when the pipeline shuts down, e.g. to flush a file.
+    fn finish(&mut self) {}
+}
+
+impl<F: FnMut(&Packet) + Send> PacketSink for F {
+    fn handle(&mut self, packet: &Packet) {
+        self(packet)
+    }
+}
+
+/// What a worker does with a packet when its queue is full.
+///
+/// ## Specification
+/// ```text
+/// Block:       Wait for the sink to make room, stalling the dispatch to the other sinks
+/// DropNewest:  Drop the packet
+/// DropOldest:  Drop the oldest queued packet to make room for this one
+/// ```
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default)]
+pub enum DropPolicy {
+    #[default]
+    Block,
+    DropNewest,
+    DropOldest,
+}
+
+#[derive(Default)]
+struct QueueState {
+    packets: VecDeque<Arc<Packet>>,
+    /// The pipeline stopped sending packets.
+    closed: bool,
+    /// The worker thread is gone.
+    disconnected: bool,
+    dropped: u64,
+}
+
+/// Bounded queue of packets between the pipeline and a worker thread.
+#[derive(Default)]
+struct Queue {
+    state: Mutex<QueueState>,
+    not_empty: Condvar,
+    not_full: Condvar,
+}
+
+impl Queue {
+    fn lock(&self) -> MutexGuard<'_, QueueState> {
+        // The state is consistent after every operation, even if a thread panicked.
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Queue a packet, returning false if the worker is gone.
+    fn push(&self, packet: Arc<Packet>, capacity: usize, policy: DropPolicy) -> bool {
+        let mut state = self.lock();
+
+        if state.packets.len() >= capacity {
+            match policy {
+                DropPolicy::Block => {
+                    while state.packets.len() >= capacity && !state.disconnected {
+                        state = self.not_full.wait(state).unwrap_or_else(|e| e.into_inner());
+                    }
+                }
+                DropPolicy::DropNewest => {
+                    state.dropped += 1;
+                    return !state.disconnected;
+                }
+                DropPolicy::DropOldest => {
+                    state.packets.pop_front();
+                    state.dropped += 1;
+                }
+            }
+        }
+
+        if state.disconnected {
+            return false;
+        }
+
+        state.packets.push_back(packet);
+        self.not_empty.notify_one();
+        true
+    }
+
+    /// Next packet for the worker, or `None` once the pipeline is closed and
+    /// the queue is empty.
+    fn pop(&self) -> Option<Arc<Packet>> {
+        let mut state = self.lock();
+
+        loop {
+            if let Some(packet) = state.packets.pop_front() {
+                self.not_full.notify_one();
+                return Some(packet);
+            }
+            if state.closed {
+                return None;
+            }
+
+            state = self
+                .not_empty
+                .wait(state)
+                .unwrap_or_else(|e| e.into_inner());
+        }
+    }
+
+    fn close(&self) {
+        self.lock().closed = true;
+        self.not_empty.notify_all();
+    }
+}
+
+/// Marks the queue as disconnected when the worker thread ends, even by a
+/// panic, so that the pipeline does not block on it.
+struct Disconnect(Arc<Queue>);
+
+impl Drop for Disconnect {
+    fn drop(&mut self) {
+        let mut state = self.0.lock();
+        state.disconnected = true;
+        state.packets.clear();
+        self.0.not_full.notify_all();
+    }
+}
+
+struct Worker {
+    queue: Arc<Queue>,
+    capacity: usize,
+    policy: DropPolicy,
+    handle: JoinHandle<()>,
+}
+
+impl Worker {
+    fn spawn<S: PacketSink + 'static>(mut sink: S, capacity: usize, policy: DropPolicy) -> Worker {
+        let queue = Arc::new(Queue::default());
+        let disconnect = Disconnect(Arc::clone(&queue));
+
+        let handle = thread::Builder::new()
+            .name(String::from("f1-telemetry-sink"))
+            .spawn(move || {
+                while let Some(packet) = disconnect.0.pop() {
+                    sink.handle(&packet);
+                }
+                sink.finish();
+            })
+            .expect("Failed to spawn sink thread");
+
+        Worker {
+            queue,
+            capacity: capacity.max(1),
+            policy,
+            handle,
+        }
+    }
+}
+
+enum Sink {
+    Inline(Box<dyn PacketSink>),
+    Worker(Worker),
+    Channel(Sender<Arc<Packet>>),
+}
+
+/// Dispatches every packet to a list of sinks, in the order they were added.
+///
+/// Sinks on worker threads and channels are dropped from the pipeline once
+/// their thread or receiver is gone. The sinks are finished when the pipeline
+/// is finished or dropped.
+#[derive(Default)]
+pub struct Pipeline {
+    sinks: Vec<Sink>,
+}
+
+impl Pipeline {
+    pub fn new() -> Pipeline {
+        Pipeline::default()
+    }
+
+    /// Add a sink called on the thread dispatching the packets.
+    pub fn with_sink<S: PacketSink + 'static>(mut self, sink: S) -> Pipeline {
+        self.sinks.push(Sink::Inline(Box::new(sink)));
+        self
+    }
+
+    /// Add a sink running on its own thread. Up to `capacity` packets are
+    /// queued for it; dispatching blocks when its queue is full.
+    pub fn with_worker<S: PacketSink + 'static>(self, sink: S, capacity: usize) -> Pipeline {
+        self.with_worker_policy(sink, capacity, DropPolicy::Block)
+    }
+
+    /// Add a sink running on its own thread, with up to `capacity` packets
+    /// queued for it and the given policy when its queue is full.
+    pub fn with_worker_policy<S: PacketSink + 'static>(
+        mut self,
+        sink: S,
+        capacity: usize,
+        policy: DropPolicy,
+    ) -> Pipeline {
+        self.sinks
+            .push(Sink::Worker(Worker::spawn(sink, capacity, policy)));
+        self
+    }
+
+    /// Send every packet on a channel, e.g. to a UI thread.
+    pub fn with_channel(mut self, sender: Sender<Arc<Packet>>) -> Pipeline {
+        self.sinks.push(Sink::Channel(sender));
+        self
+    }
+
+    /// Add a sink to a running pipeline, handing it the cached packets of the
+    /// session first.
+    pub fn attach<S: PacketSink + 'static>(&mut self, mut sink: S, cache: &SessionCache) {
+        cache.replay(&mut sink);
+        self.sinks.push(Sink::Inline(Box::new(sink)));
+    }
+
+    /// Add a sink running on its own thread to a running pipeline, queueing
+    /// the cached packets of the session for it first.
+    pub fn attach_worker<S: PacketSink + 'static>(
+        &mut self,
+        sink: S,
+        capacity: usize,
+        policy: DropPolicy,
+        cache: &SessionCache,
+    ) {
+        let worker = Worker::spawn(sink, capacity, policy);
+        for packet in cache.snapshot() {
+            worker
+                .queue
+                .push(Arc::new(packet), worker.capacity, worker.policy);
+        }
+
+        self.sinks.push(Sink::Worker(worker));
+    }
+
+    /// Send every packet on a channel from now on, e.g. to a UI connecting
+    /// mid-session, after the cached packets of the session.
+    pub fn attach_channel(&mut self, sender: Sender<Arc<Packet>>, cache: &SessionCache) {
+        for packet in cache.snapshot() {
+            if sender.send(Arc::new(packet)).is_err() {
+                return;
+            }
+        }
+
+        self.sinks.push(Sink::Channel(sender));
+    }
+
+    pub fn len(&self) -> usize {
+        self.sinks.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sinks.is_empty()
+    }
+
+    /// Number of packets dropped by the workers still in the pipeline since
+    /// they were added, because their queue was full.
+    pub fn dropped(&self) -> u64 {
+        self.sinks
+            .iter()
+            .map(|sink| match sink {
+                Sink::Worker(worker) => worker.queue.lock().dropped,
+                _ => 0,
+            })
+            .sum()
+    }
+
+    /// Hand a packet to all the sinks.
+    pub fn dispatch(&mut self, packet: Packet) {
+        let packet = Arc::new(packet);
+
+        self.sinks.retain_mut(|sink| match sink {
+            Sink::Inline(sink) => {
+                sink.handle(&packet);
+                true
+            }
+            Sink::Worker(worker) => {
+                worker
+                    .queue
+                    .push(Arc::clone(&packet), worker.capacity, worker.policy)
+            }
+            Sink::Channel(sender) => sender.send(Arc::clone(&packet)).is_ok(),
+        });
+    }
+
+    /// Dispatch all the packets the stream has received, returning how many were
+    /// dispatched. Stops at the first error.
+    pub fn pump(&mut self, stream: &Stream) -> Result<usize, StreamError> {
+        let mut count = 0;
+
+        while let Some(packet) = stream.next()? {
+            self.dispatch(packet);
+            count += 1;
+        }
+
+        Ok(count)
+    }
+
+    /// Finish all the sinks, waiting for the worker threads to handle their
+    /// queued packets.
+    pub fn finish(mut self) {
+        self.shutdown();
+    }
+
+    fn shutdown(&mut self) {
+        for sink in self.sinks.drain(..) {
+            match sink {
+                Sink::Inline(mut sink) => sink.finish(),
+                Sink::Worker(Worker { queue, handle, .. }) => {
+                    queue.close();
+                    // A panic in the sink has already been reported by its thread
+                    let _ = handle.join();
+                }
+                Sink::Channel(_) => {}
+            }
+        }
+    }
+}
+
+impl Drop for Pipeline {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
@@ -0,0 +1,102 @@
+//! Redaction of the online player names, e.g. for streamers.
+//!
+//! A [`Redactor`] replaces the names in the participants and lobby info packets,
+//! always with the same replacement for a given name, so that a player can still
+//! be followed across packets and exports without their name being shown.
+//!
+//! [`Redactor`]: ./struct.Redactor.html
+
+use std::collections::HashMap;
+
+use crate::packet::Packet;
+
+/// How a name is replaced.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Redaction {
+    /// Replace names with a template, where `{}` is the number of the player in
+    /// the order they were first seen, e.g. `Player {}`.
+    Placeholder(String),
+    /// Replace names with a prefix followed by a hash of the name and a salt, e.g.
+    /// `Player-3fa2c1`. The same name gets the same hash across runs.
+    Hash { prefix: String, salt: String },
+}
+
+impl Default for Redaction {
+    fn default() -> Self {
+        Redaction::Placeholder(String::from("Player {}"))
+    }
+}
+
+/// Replaces the names of the human players in the packets it is given.
+///
+/// AI drivers are named after real drivers and are left as is, unless
+/// `redact_ai` is set.
+#[derive(Debug, Default)]
+pub struct Redactor {
+    redaction: Redaction,
+    redact_ai: bool,
+    names: HashMap<String, String>,
+}
+
+impl Redactor {
+    pub fn new(redaction: Redaction) -> Redactor {
+        Redactor {
+            redaction,
+            redact_ai: false,
+            names: HashMap::new(),
+        }
+    }
+
+    /// Also replace the names of the AI drivers.
+    pub fn redact_ai(mut self, redact_ai: bool) -> Redactor {
+        self.redact_ai = redact_ai;
+        self
+    }
+
+    /// Replacement of a name, the same for every call with that name.
+    pub fn replacement(&mut self, name: &str) -> String {
+        if let Some(replacement) = self.names.get(name) {
+            return replacement.clone();
+        }
+
+        let replacement = match &self.redaction {
+            Redaction::Placeholder(template) => {
+                template.replace("{}", &(self.names.len() + 1).to_string())
+            }
+            Redaction::Hash { prefix, salt } => {
+                format!("{}{:06x}", prefix, fnv1a(salt, name) & 0xff_ffff)
+            }
+        };
+
+        self.names.insert(name.to_owned(), replacement.clone());
+        replacement
+    }
+
+    /// Replace the names in a packet. Packets without names, and raw packets,
+    /// are left untouched.
+    pub fn redact(&mut self, packet: &mut Packet) {
+        let names: Vec<(bool, &mut String)> = match packet {
+            Packet::Participants(p) => p.names_mut().collect(),
+            Packet::LobbyInfo(p) => p.names_mut().collect(),
+            _ => return,
+        };
+
+        for (ai_controlled, name) in names {
+            if name.is_empty() || (ai_controlled && !self.redact_ai) {
+                continue;
+            }
+
+            *name = self.replacement(name);
+        }
+    }
+}
+
+/// 64 bits FNV-1a hash, which is stable across runs and platforms unlike the
+/// hasher of the standard library.
+fn fnv1a(salt: &str, name: &str) -> u64 {
+    salt.bytes()
+        .chain(name.bytes())
+        .fold(0xcbf2_9ce4_8422_2325, |hash, b| {
+            (hash ^ b as u64).wrapping_mul(0x0100_0000_01b3)
+        })
+}
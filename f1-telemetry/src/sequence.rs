@@ -0,0 +1,182 @@
+//! Detection of the duplicate and out of order packets.
+//!
+//! The game sends the telemetry over UDP, which can deliver a datagram twice or
+//! after a later one. A [`Sequencer`] tracks the frame identifier and session
+//! time of each packet type, straight from the raw bytes, and tells the stale
+//! packets apart so that they can be dropped before being parsed.
+//!
+//! ```
+//! use f1_telemetry::sequence::{Sequence, SequencePolicy, Sequencer};
+//!
+//! let mut sequencer = Sequencer::new(SequencePolicy::DropStale);
+//!
+//! let mut packet = [0u8; 24];
+//! packet[5] = 6; // Car telemetry
+//! packet[18..22].copy_from_slice(&100u32.to_le_bytes());
+//! assert_eq!(sequencer.check(&packet), Sequence::InOrder);
+//! assert_eq!(sequencer.check(&packet), Sequence::Duplicate);
+//!
+//! packet[18..22].copy_from_slice(&99u32.to_le_bytes());
+//! assert_eq!(sequencer.check(&packet), Sequence::OutOfOrder);
+//! assert!(sequencer.should_drop(Sequence::OutOfOrder));
+//! ```
+//!
+//! [`Sequencer`]: ./struct.Sequencer.html
+
+use crate::packet::{packet_id_offset, peek_packet_format, peek_packet_id, peek_session_id};
+
+/// Offset of the `session_time` field in the header, from the `packet_id` one.
+const SESSION_TIME_OFFSET: usize = 9;
+/// Offset of the `frame_identifier` field in the header, from the `packet_id` one.
+const FRAME_IDENTIFIER_OFFSET: usize = 13;
+
+/// Number of frames a packet can be late by before it is taken for a
+/// flashback, which rewinds the frame identifiers of every packet type.
+const REORDER_WINDOW: u32 = 120;
+
+/// Order of a packet relative to the previous ones of its type.
+///
+/// ## Specification
+/// ```text
+/// InOrder:     A newer frame, or the first packet of its type in the session
+/// Duplicate:   The same packet as the previous one of its type
+/// OutOfOrder:  An older frame than the previous packet of its type
+/// ```
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Sequence {
+    InOrder,
+    Duplicate,
+    OutOfOrder,
+}
+
+/// Which stale packets a sequencer drops.
+///
+/// ## Specification
+/// ```text
+/// Detect:          Only count the stale packets
+/// DropDuplicates:  Drop the duplicate packets
+/// DropStale:       Drop the duplicate and out of order packets
+/// ```
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default)]
+pub enum SequencePolicy {
+    #[default]
+    Detect,
+    DropDuplicates,
+    DropStale,
+}
+
+/// Last packet of a type.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+struct Last {
+    session_uid: u64,
+    frame_identifier: u32,
+    session_time: u32,
+    hash: u64,
+}
+
+/// Tracks the order of the packets of each type.
+#[derive(Debug, Clone, Default)]
+pub struct Sequencer {
+    policy: SequencePolicy,
+    last: [Option<Last>; 32],
+    duplicates: u64,
+    out_of_order: u64,
+}
+
+impl Sequencer {
+    pub fn new(policy: SequencePolicy) -> Sequencer {
+        Sequencer {
+            policy,
+            ..Sequencer::default()
+        }
+    }
+
+    pub fn policy(&self) -> SequencePolicy {
+        self.policy
+    }
+
+    /// Number of duplicate packets seen.
+    pub fn duplicates(&self) -> u64 {
+        self.duplicates
+    }
+
+    /// Number of out of order packets seen.
+    pub fn out_of_order(&self) -> u64 {
+        self.out_of_order
+    }
+
+    /// Forget the previous packets, e.g. when switching sources.
+    pub fn reset(&mut self) {
+        self.last = [None; 32];
+    }
+
+    /// Order of a packet, from its raw bytes. Packets too short to hold a
+    /// header are in order, leaving it to the parser to reject them.
+    pub fn check(&mut self, packet: &[u8]) -> Sequence {
+        let offset = packet_id_offset(peek_packet_format(packet).unwrap_or(0));
+        let (packet_id, session_uid, frame_identifier, session_time) = match (
+            peek_packet_id(packet),
+            peek_session_id(packet),
+            read_u32(packet, offset + FRAME_IDENTIFIER_OFFSET),
+            read_u32(packet, offset + SESSION_TIME_OFFSET),
+        ) {
+            (Some(id), Some(session), Some(frame), Some(time)) if id < 32 => {
+                (id as usize, session.value(), frame, time)
+            }
+            _ => return Sequence::InOrder,
+        };
+
+        let current = Last {
+            session_uid,
+            frame_identifier,
+            session_time,
+            hash: fnv1a(packet),
+        };
+
+        let sequence = match self.last[packet_id] {
+            Some(last) if last.session_uid == session_uid => {
+                if last == current {
+                    Sequence::Duplicate
+                } else if frame_identifier < last.frame_identifier
+                    && last.frame_identifier - frame_identifier <= REORDER_WINDOW
+                {
+                    Sequence::OutOfOrder
+                } else {
+                    Sequence::InOrder
+                }
+            }
+            _ => Sequence::InOrder,
+        };
+
+        match sequence {
+            Sequence::InOrder => self.last[packet_id] = Some(current),
+            Sequence::Duplicate => self.duplicates += 1,
+            Sequence::OutOfOrder => self.out_of_order += 1,
+        }
+
+        sequence
+    }
+
+    /// Whether a packet of this order should be dropped with the policy of
+    /// the sequencer.
+    pub fn should_drop(&self, sequence: Sequence) -> bool {
+        match sequence {
+            Sequence::InOrder => false,
+            Sequence::Duplicate => self.policy != SequencePolicy::Detect,
+            Sequence::OutOfOrder => self.policy == SequencePolicy::DropStale,
+        }
+    }
+}
+
+fn read_u32(packet: &[u8], offset: usize) -> Option<u32> {
+    let bytes = packet.get(offset..offset + 4)?;
+    Some(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+/// 64 bits FNV-1a hash of a packet, telling the packets of the same frame
+/// apart, e.g. several events.
+fn fnv1a(packet: &[u8]) -> u64 {
+    packet.iter().fold(0xcbf2_9ce4_8422_2325, |hash, b| {
+        (hash ^ *b as u64).wrapping_mul(0x0100_0000_01b3)
+    })
+}
@@ -0,0 +1,23 @@
+pub use cache::SessionCache;
+pub use flags::{FlagMap, FlagNotification, FlagZone};
+pub use laps::{LapRecord, LapTracker};
+pub use merger::{MergedCar, Merger};
+pub use penalties::{PenaltyLedger, PenaltyRecord};
+pub use pit_stops::{PitStop, PitTracker};
+pub use safety_car::{DeltaCompliance, SafetyCarMonitor, SafetyCarNotification};
+pub use speed_trap::{SpeedTrapBoard, SpeedTrapChange};
+pub use timing::{TimingNotification, TimingNotifier};
+pub use tracker::{FocusContext, SessionTracker, ViewTarget};
+pub use weather::{WeatherChange, WeatherTracker};
+
+pub mod cache;
+pub mod flags;
+pub mod laps;
+pub mod merger;
+pub mod penalties;
+pub mod pit_stops;
+pub mod safety_car;
+pub mod speed_trap;
+pub mod timing;
+pub mod tracker;
+pub mod weather;
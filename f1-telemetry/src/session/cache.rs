@@ -0,0 +1,116 @@
+use std::sync::{Arc, Mutex};
+
+use crate::packet::encode::encode_packet;
+use crate::packet::header::SessionIdentifier;
+use crate::packet::{parse_packet_fuzz, Packet};
+use crate::pipeline::PacketSink;
+
+#[derive(Debug, Default)]
+struct Snapshot {
+    session: Option<SessionIdentifier>,
+    session_data: Option<Vec<u8>>,
+    participants: Option<Vec<u8>>,
+    car_setups: Option<Vec<u8>>,
+}
+
+/// Latest Session, Participants and Car Setups packets of the session, so that
+/// a consumer attaching mid-session knows the track and the drivers without
+/// waiting for the game to send them again.
+///
+/// The game only sends these packets every few seconds at most. The cache
+/// records them as a [`PacketSink`], and hands a copy of them to late sinks
+/// with [`snapshot`], or when they are attached to a pipeline with e.g.
+/// [`Pipeline::attach`]. The packets of a previous session are dropped as soon
+/// as one of these packets is received for a new one.
+///
+/// `SessionCache` is cheap to clone: clones share the same packets, so one can
+/// be kept in a pipeline while another is used to attach sinks.
+///
+/// [`PacketSink`]: ../../pipeline/trait.PacketSink.html
+/// [`snapshot`]: #method.snapshot
+/// [`Pipeline::attach`]: ../../pipeline/struct.Pipeline.html#method.attach
+#[derive(Debug, Clone, Default)]
+pub struct SessionCache {
+    snapshot: Arc<Mutex<Snapshot>>,
+}
+
+impl SessionCache {
+    pub fn new() -> SessionCache {
+        SessionCache::default()
+    }
+
+    /// Record the packet if it is one of the cached types.
+    pub fn update(&self, packet: &Packet) {
+        let header = match packet {
+            Packet::Session(p) => p.header(),
+            Packet::Participants(p) => p.header(),
+            Packet::CarSetups(p) => p.header(),
+            _ => return,
+        };
+
+        let mut snapshot = self.snapshot.lock().unwrap();
+
+        let session = header.session_id();
+        if snapshot.session != Some(session) {
+            *snapshot = Snapshot::default();
+            snapshot.session = Some(session);
+        }
+
+        let datagram = Some(encode_packet(packet));
+        match packet {
+            Packet::Session(_) => snapshot.session_data = datagram,
+            Packet::Participants(_) => snapshot.participants = datagram,
+            Packet::CarSetups(_) => snapshot.car_setups = datagram,
+            _ => {}
+        }
+    }
+
+    /// Forget the cached packets.
+    pub fn clear(&self) {
+        *self.snapshot.lock().unwrap() = Snapshot::default();
+    }
+
+    /// Session of the cached packets.
+    pub fn session(&self) -> Option<SessionIdentifier> {
+        self.snapshot.lock().unwrap().session
+    }
+
+    /// Whether a packet of each of the cached types was received.
+    pub fn is_complete(&self) -> bool {
+        let snapshot = self.snapshot.lock().unwrap();
+        snapshot.session_data.is_some()
+            && snapshot.participants.is_some()
+            && snapshot.car_setups.is_some()
+    }
+
+    /// Copies of the cached packets, Session first, then Participants and Car
+    /// Setups.
+    pub fn snapshot(&self) -> Vec<Packet> {
+        let snapshot = self.snapshot.lock().unwrap();
+
+        [
+            &snapshot.session_data,
+            &snapshot.participants,
+            &snapshot.car_setups,
+        ]
+        .iter()
+        .filter_map(|datagram| datagram.as_ref())
+        // The datagrams were encoded from parsed packets, they parse again.
+        .filter_map(|datagram| parse_packet_fuzz(datagram).ok())
+        .collect()
+    }
+
+    /// Hand copies of the cached packets to a sink, e.g. before adding it to
+    /// a running pipeline.
+    pub fn replay<S: PacketSink + ?Sized>(&self, sink: &mut S) {
+        for packet in self.snapshot() {
+            sink.handle(&packet);
+        }
+    }
+}
+
+impl PacketSink for SessionCache {
+    fn handle(&mut self, packet: &Packet) {
+        self.update(packet);
+    }
+}
@@ -0,0 +1,169 @@
+use getset::CopyGetters;
+
+use crate::packet::generic::Flag;
+use crate::packet::lap::PacketLapData;
+use crate::packet::session::PacketSessionData;
+
+/// A marshal zone of the track, with the part of the lap it covers.
+///
+/// ## Specification
+/// ```text
+/// index: Index of the zone in the marshal zones of the Session packet
+/// start: Fraction (0..1) of way through the lap the zone starts
+/// end:   Fraction (0..1) of way through the lap the zone ends, lower than the
+///        start for the zone crossing the start line
+/// flag:  Flag currently shown in the zone
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, CopyGetters)]
+#[getset(get_copy = "pub")]
+pub struct FlagZone {
+    index: usize,
+    start: f32,
+    end: f32,
+    flag: Flag,
+}
+
+impl FlagZone {
+    /// Whether the zone covers a fraction of the lap.
+    pub fn contains(&self, fraction: f32) -> bool {
+        if self.start == self.end {
+            // Single zone covering the whole lap
+            true
+        } else if self.start < self.end {
+            fraction >= self.start && fraction < self.end
+        } else {
+            fraction >= self.start || fraction < self.end
+        }
+    }
+
+    pub fn is_yellow(&self) -> bool {
+        self.flag == Flag::Yellow
+    }
+
+    pub fn is_red(&self) -> bool {
+        self.flag == Flag::Red
+    }
+}
+
+/// Notification sent when the player's car moves through the flagged zones.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum FlagNotification {
+    EnteredYellowZone { zone: usize },
+    LeftYellowZone { zone: usize },
+}
+
+/// Map of the flags shown in the marshal zones of the track, from the Session
+/// packets, and of where the player's car is relative to them, from the Lap Data
+/// packets.
+#[derive(Debug, Default)]
+pub struct FlagMap {
+    zones: Vec<FlagZone>,
+    track_length: f32,
+    player_fraction: Option<f32>,
+    player_zone: Option<usize>,
+    player_in_yellow: bool,
+}
+
+impl FlagMap {
+    pub fn new() -> FlagMap {
+        FlagMap::default()
+    }
+
+    pub fn update_session(&mut self, packet: &PacketSessionData) {
+        let zones = packet.active_marshal_zones();
+        self.track_length = packet.track_length() as f32;
+
+        self.zones = zones
+            .iter()
+            .enumerate()
+            .map(|(index, zone)| FlagZone {
+                index,
+                start: zone.zone_start(),
+                end: zones[(index + 1) % zones.len()].zone_start(),
+                flag: zone.zone_flag(),
+            })
+            .collect();
+    }
+
+    /// Move the player's car, returning the notifications for the zones it
+    /// entered or left.
+    pub fn update_lap_data(&mut self, packet: &PacketLapData) -> Vec<FlagNotification> {
+        let mut notifications = Vec::new();
+        if self.track_length <= 0.0 {
+            return notifications;
+        }
+
+        let ld = match packet
+            .lap_data()
+            .get(packet.header().player_car_index() as usize)
+        {
+            Some(ld) => ld,
+            None => return notifications,
+        };
+
+        let fraction = (ld.lap_distance() / self.track_length).rem_euclid(1.0);
+        self.player_fraction = Some(fraction);
+
+        // A zone turning yellow while the car is in it counts as entering it.
+        let zone = self.zone_at(fraction).copied();
+        let index = zone.map(|z| z.index);
+        let in_yellow = zone.is_some_and(|z| z.is_yellow());
+        let changed = index != self.player_zone;
+
+        if let Some(previous) = self.player_zone {
+            if self.player_in_yellow && (changed || !in_yellow) {
+                notifications.push(FlagNotification::LeftYellowZone { zone: previous });
+            }
+        }
+        if let Some(current) = index {
+            if in_yellow && (changed || !self.player_in_yellow) {
+                notifications.push(FlagNotification::EnteredYellowZone { zone: current });
+            }
+        }
+
+        self.player_zone = index;
+        self.player_in_yellow = in_yellow;
+
+        notifications
+    }
+
+    pub fn zones(&self) -> &[FlagZone] {
+        &self.zones
+    }
+
+    /// Zones currently showing a yellow or red flag.
+    pub fn flagged_zones(&self) -> Vec<&FlagZone> {
+        self.zones
+            .iter()
+            .filter(|z| z.is_yellow() || z.is_red())
+            .collect()
+    }
+
+    /// Zone covering a fraction of the lap.
+    pub fn zone_at(&self, fraction: f32) -> Option<&FlagZone> {
+        self.zones.iter().find(|z| z.contains(fraction))
+    }
+
+    /// Zone the player's car is in.
+    pub fn player_zone(&self) -> Option<&FlagZone> {
+        self.zones.get(self.player_zone?)
+    }
+
+    /// Distance in metres from the player's car to the start of the next yellow
+    /// zone ahead, 0 when the car is in one.
+    pub fn distance_to_next_yellow(&self) -> Option<f32> {
+        let fraction = self.player_fraction?;
+
+        self.zones
+            .iter()
+            .filter(|z| z.is_yellow())
+            .map(|z| {
+                if z.contains(fraction) {
+                    0.0
+                } else {
+                    (z.start - fraction).rem_euclid(1.0) * self.track_length
+                }
+            })
+            .min_by(|a, b| a.total_cmp(b))
+    }
+}
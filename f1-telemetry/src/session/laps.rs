@@ -0,0 +1,166 @@
+use getset::CopyGetters;
+use std::collections::HashMap;
+
+use crate::packet::header::SessionIdentifier;
+use crate::packet::lap::PacketLapData;
+
+/// A lap completed by a driver.
+///
+/// ## Specification
+/// ```text
+/// lap:          Lap number
+/// lap_time:     Lap time in seconds
+/// sector_1_time: Sector 1 time in seconds
+/// sector_2_time: Sector 2 time in seconds
+/// sector_3_time: Sector 3 time in seconds
+/// valid:        Whether the lap was valid
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, CopyGetters)]
+#[getset(get_copy = "pub")]
+pub struct LapRecord {
+    lap: u8,
+    lap_time: f32,
+    sector_1_time: f32,
+    sector_2_time: f32,
+    sector_3_time: f32,
+    valid: bool,
+}
+
+impl LapRecord {
+    pub fn sector_times(&self) -> [f32; 3] {
+        [self.sector_1_time, self.sector_2_time, self.sector_3_time]
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct LapState {
+    lap: u8,
+    sector_1_time: f32,
+    sector_2_time: f32,
+    invalid: bool,
+}
+
+/// Records the laps completed by every driver during a session, from the Lap
+/// Data packets.
+///
+/// A lap is recorded when the lap number of a car increases; laps completed
+/// while no packet was received are missed. Drivers are identified by their
+/// vehicle index.
+#[derive(Debug, Default)]
+pub struct LapTracker {
+    session: Option<SessionIdentifier>,
+    laps: HashMap<u8, Vec<LapRecord>>,
+    current: Vec<Option<LapState>>,
+}
+
+impl LapTracker {
+    pub fn new() -> LapTracker {
+        LapTracker::default()
+    }
+
+    pub fn reset(&mut self) {
+        self.session = None;
+        self.laps.clear();
+        self.current.clear();
+    }
+
+    pub fn update(&mut self, packet: &PacketLapData) {
+        let session = packet.header().session_id();
+        if self.session != Some(session) {
+            self.reset();
+            self.session = Some(session);
+        }
+
+        let lap_data = packet.lap_data();
+        self.current.resize(lap_data.len(), None);
+
+        for (idx, ld) in lap_data.iter().enumerate() {
+            let state = LapState {
+                lap: ld.current_lap_num(),
+                sector_1_time: ld.sector_1_time(),
+                sector_2_time: ld.sector_2_time(),
+                invalid: ld.current_lap_invalid(),
+            };
+
+            if let Some(previous) = self.current[idx] {
+                if state.lap == previous.lap + 1 && previous.lap > 0 {
+                    let lap_time = ld.last_lap_time();
+                    // The sector times are reset when the line is crossed, keep the last ones seen.
+                    let sector_3_time = lap_time - previous.sector_1_time - previous.sector_2_time;
+
+                    self.laps.entry(idx as u8).or_default().push(LapRecord {
+                        lap: previous.lap,
+                        lap_time,
+                        sector_1_time: previous.sector_1_time,
+                        sector_2_time: previous.sector_2_time,
+                        sector_3_time,
+                        valid: !previous.invalid,
+                    });
+                }
+            }
+
+            self.current[idx] = Some(state);
+        }
+    }
+
+    /// All the laps completed by a driver, in order.
+    pub fn laps_for(&self, driver_index: u8) -> &[LapRecord] {
+        self.laps
+            .get(&driver_index)
+            .map(Vec::as_slice)
+            .unwrap_or(&[])
+    }
+
+    pub fn last_lap(&self, driver_index: u8) -> Option<&LapRecord> {
+        self.laps_for(driver_index).last()
+    }
+
+    /// Fastest valid lap of a driver.
+    pub fn best_lap(&self, driver_index: u8) -> Option<&LapRecord> {
+        self.laps_for(driver_index)
+            .iter()
+            .filter(|l| l.valid && l.lap_time > 0.0)
+            .min_by(|a, b| a.lap_time.total_cmp(&b.lap_time))
+    }
+
+    /// Time of the last lap of a driver compared to their fastest valid lap
+    /// before it, negative when the last lap is faster.
+    pub fn last_lap_delta(&self, driver_index: u8) -> Option<f32> {
+        let (last, previous) = self.laps_for(driver_index).split_last()?;
+        let best = previous
+            .iter()
+            .filter(|l| l.valid && l.lap_time > 0.0)
+            .map(|l| l.lap_time)
+            .min_by(f32::total_cmp)?;
+
+        Some(last.lap_time - best).filter(|_| last.lap_time > 0.0)
+    }
+
+    /// Fastest time of a driver in each sector, over their valid laps.
+    pub fn best_sectors(&self, driver_index: u8) -> [Option<f32>; 3] {
+        let mut best = [None; 3];
+
+        for lap in self.laps_for(driver_index).iter().filter(|l| l.valid) {
+            for (best, time) in best.iter_mut().zip(lap.sector_times().iter()) {
+                if *time <= 0.0 {
+                    continue;
+                }
+                match best {
+                    Some(b) if *b <= *time => {}
+                    _ => *best = Some(*time),
+                }
+            }
+        }
+
+        best
+    }
+
+    /// Lap a driver is currently on.
+    pub fn current_lap(&self, driver_index: u8) -> Option<u8> {
+        self.current
+            .get(driver_index as usize)
+            .copied()
+            .flatten()
+            .map(|s| s.lap)
+    }
+}
@@ -0,0 +1,238 @@
+use getset::{CopyGetters, Getters};
+use std::collections::BTreeMap;
+
+use crate::packet::header::{PacketHeader, SessionIdentifier};
+use crate::packet::participants::{PacketParticipantsData, Team};
+use crate::packet::Packet;
+use crate::SourceId;
+
+/// A car of the merged session, seen by one or more clients.
+///
+/// ## Specification
+/// ```text
+/// name:         Name of the driver, as sent in the Participants packets
+/// race_number:  Race number of the car
+/// team:         Team of the car
+/// owner:        Client driving the car, if it forwards its telemetry
+/// views:        Clients seeing the car, with its vehicle index in their packets
+/// ```
+#[derive(Debug, Clone, PartialEq, CopyGetters, Getters)]
+pub struct MergedCar {
+    #[getset(get = "pub")]
+    name: String,
+    #[getset(get_copy = "pub")]
+    race_number: u8,
+    #[getset(get_copy = "pub")]
+    team: Team,
+    #[getset(get_copy = "pub")]
+    owner: Option<SourceId>,
+    #[getset(get = "pub")]
+    views: Vec<(SourceId, u8)>,
+}
+
+impl MergedCar {
+    /// Client whose data is used for the car, with the vehicle index of the car
+    /// in its packets: the owner if it sees the car, else the client that has
+    /// seen it the longest.
+    pub fn authority(&self) -> Option<(SourceId, u8)> {
+        self.owner
+            .and_then(|owner| self.views.iter().find(|(s, _)| *s == owner))
+            .or_else(|| self.views.first())
+            .copied()
+    }
+
+    fn index_in(&self, source: SourceId) -> Option<u8> {
+        self.views
+            .iter()
+            .find(|(s, _)| *s == source)
+            .map(|(_, idx)| *idx)
+    }
+}
+
+/// Reconciles the packets of several clients of the same online session, e.g.
+/// received by a [`MultiStream`], into a single list of cars.
+///
+/// Every client numbers the cars in its own way, so the cars are matched by the
+/// driver name and race number of the Participants packets. The data of a car
+/// is best taken from the client driving it, whose own telemetry is never
+/// restricted: [`is_authoritative`] tells whether the data of a car in the
+/// packets of a client should be used.
+///
+/// The merger follows the session of the first packet it is updated with;
+/// packets of other sessions are ignored until it is [`reset`].
+///
+/// [`MultiStream`]: ../../struct.MultiStream.html
+/// [`is_authoritative`]: #method.is_authoritative
+/// [`reset`]: #method.reset
+#[derive(Debug, Default)]
+pub struct Merger {
+    session: Option<SessionIdentifier>,
+    cars: Vec<MergedCar>,
+    players: BTreeMap<SourceId, u8>,
+}
+
+impl Merger {
+    pub fn new() -> Merger {
+        Merger::default()
+    }
+
+    /// Forget the session, its cars and clients.
+    pub fn reset(&mut self) {
+        self.session = None;
+        self.cars.clear();
+        self.players.clear();
+    }
+
+    /// Session being merged, once a packet was received.
+    pub fn session(&self) -> Option<SessionIdentifier> {
+        self.session
+    }
+
+    /// Cars of the session, in the order they were first seen. Their index in
+    /// this list is their merged index, which does not change.
+    pub fn cars(&self) -> &[MergedCar] {
+        &self.cars
+    }
+
+    /// Clients that sent packets of the session.
+    pub fn sources(&self) -> Vec<SourceId> {
+        self.players.keys().copied().collect()
+    }
+
+    /// Update the merger with a packet of a client, returning whether it
+    /// belongs to the merged session.
+    pub fn update(&mut self, source: SourceId, packet: &Packet) -> bool {
+        let header = match header(packet) {
+            Some(header) => header,
+            None => return false,
+        };
+
+        let session = header.session_id();
+        match self.session {
+            Some(s) if s != session => return false,
+            Some(_) => {}
+            None => self.session = Some(session),
+        }
+
+        let player = header.player_car_index();
+        if self.players.insert(source, player) != Some(player) {
+            self.update_owners();
+        }
+
+        if let Packet::Participants(p) = packet {
+            self.update_participants(source, p);
+        }
+
+        true
+    }
+
+    fn update_participants(&mut self, source: SourceId, packet: &PacketParticipantsData) {
+        for car in &mut self.cars {
+            car.views.retain(|(s, _)| *s != source);
+        }
+
+        let participants = packet.participants();
+        let active = (packet.num_active_cars() as usize).min(participants.len());
+
+        for (idx, participant) in participants[..active].iter().enumerate() {
+            let position = self.cars.iter().position(|car| {
+                car.race_number == participant.race_number() && car.name == *participant.name()
+            });
+
+            let car = match position {
+                Some(position) => &mut self.cars[position],
+                None => {
+                    self.cars.push(MergedCar {
+                        name: participant.name().clone(),
+                        race_number: participant.race_number(),
+                        team: participant.team(),
+                        owner: None,
+                        views: Vec::new(),
+                    });
+                    self.cars.last_mut().unwrap()
+                }
+            };
+
+            car.team = participant.team();
+            car.views.push((source, idx as u8));
+        }
+
+        self.update_owners();
+    }
+
+    fn update_owners(&mut self) {
+        for car in &mut self.cars {
+            car.owner = self
+                .players
+                .iter()
+                .find(|(source, player)| car.index_in(**source) == Some(**player))
+                .map(|(source, _)| *source);
+        }
+    }
+
+    /// Forget a client, e.g. one that disconnected. Its cars are kept, and
+    /// taken from the other clients.
+    pub fn remove_source(&mut self, source: SourceId) {
+        self.players.remove(&source);
+        for car in &mut self.cars {
+            car.views.retain(|(s, _)| *s != source);
+        }
+        self.update_owners();
+    }
+
+    /// Merged index of the car with vehicle index `idx` in the packets of a
+    /// client.
+    pub fn merged_index(&self, source: SourceId, idx: u8) -> Option<usize> {
+        self.cars
+            .iter()
+            .position(|car| car.index_in(source) == Some(idx))
+    }
+
+    /// Vehicle index of a merged car in the packets of a client.
+    pub fn vehicle_index(&self, merged: usize, source: SourceId) -> Option<u8> {
+        self.cars.get(merged)?.index_in(source)
+    }
+
+    /// Whether the data of the car with vehicle index `idx` in the packets of
+    /// a client is the one to use for it.
+    pub fn is_authoritative(&self, source: SourceId, idx: u8) -> bool {
+        self.merged_index(source, idx)
+            .and_then(|merged| self.cars[merged].authority())
+            == Some((source, idx))
+    }
+
+    /// Cars whose data is taken from a client, as pairs of their vehicle index
+    /// in its packets and their merged index.
+    pub fn authoritative_cars(&self, source: SourceId) -> Vec<(u8, usize)> {
+        self.cars
+            .iter()
+            .enumerate()
+            .filter_map(|(merged, car)| match car.authority() {
+                Some((s, idx)) if s == source => Some((idx, merged)),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+fn header(packet: &Packet) -> Option<&PacketHeader> {
+    let header = match packet {
+        Packet::Motion(p) => p.header(),
+        Packet::Session(p) => p.header(),
+        Packet::Lap(p) => p.header(),
+        Packet::Event(p) => p.header(),
+        Packet::Participants(p) => p.header(),
+        Packet::CarSetups(p) => p.header(),
+        Packet::CarTelemetry(p) => p.header(),
+        Packet::CarStatus(p) => p.header(),
+        Packet::FinalClassification(p) => p.header(),
+        Packet::LobbyInfo(p) => p.header(),
+        Packet::CarDamage(p) => p.header(),
+        Packet::TyreSets(p) => p.header(),
+        Packet::MotionEx(p) => p.header(),
+        Packet::TimeTrial(p) => p.header(),
+        Packet::Raw(_) => return None,
+    };
+
+    Some(header)
+}
@@ -0,0 +1,114 @@
+use getset::CopyGetters;
+use std::collections::HashMap;
+
+use crate::packet::event::{Event, InfringementType, PacketEventData, Penalty, PenaltyType};
+
+/// A penalty or warning issued to a driver, along with when it happened.
+///
+/// ## Specification
+/// ```text
+/// session_time: Session timestamp of the Penalty event
+/// penalty:      Details of the penalty
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, CopyGetters)]
+#[getset(get_copy = "pub")]
+pub struct PenaltyRecord {
+    session_time: f32,
+    penalty: Penalty,
+}
+
+impl PenaltyRecord {
+    pub fn penalty_type(&self) -> PenaltyType {
+        self.penalty.penalty_type()
+    }
+
+    pub fn infringement_type(&self) -> InfringementType {
+        self.penalty.infringement_type()
+    }
+}
+
+/// Accumulates the penalties, warnings and infringements of every driver during
+/// a session, from the Penalty events.
+///
+/// Drivers are identified by their vehicle index.
+#[derive(Debug, Default)]
+pub struct PenaltyLedger {
+    records: HashMap<u8, Vec<PenaltyRecord>>,
+}
+
+impl PenaltyLedger {
+    pub fn new() -> PenaltyLedger {
+        PenaltyLedger::default()
+    }
+
+    pub fn reset(&mut self) {
+        self.records.clear();
+    }
+
+    /// Record the penalty carried by an event packet, if there is one.
+    pub fn update(&mut self, packet: &PacketEventData) {
+        if packet.event() != Event::Penalty {
+            return;
+        }
+
+        if let Some(penalty) = packet.penalty() {
+            self.records
+                .entry(penalty.vehicle_idx())
+                .or_default()
+                .push(PenaltyRecord {
+                    session_time: packet.header().session_time(),
+                    penalty,
+                });
+        }
+    }
+
+    /// All the penalties issued to a driver, in the order they were received.
+    pub fn penalties_for(&self, driver_index: u8) -> &[PenaltyRecord] {
+        self.records
+            .get(&driver_index)
+            .map(Vec::as_slice)
+            .unwrap_or(&[])
+    }
+
+    fn count(&self, driver_index: u8, f: impl Fn(&PenaltyRecord) -> bool) -> usize {
+        self.penalties_for(driver_index)
+            .iter()
+            .filter(|r| f(r))
+            .count()
+    }
+
+    pub fn warnings(&self, driver_index: u8) -> usize {
+        self.count(driver_index, |r| r.penalty_type() == PenaltyType::Warning)
+    }
+
+    pub fn corner_cutting_infringements(&self, driver_index: u8) -> usize {
+        self.count(driver_index, |r| r.infringement_type().is_corner_cutting())
+    }
+
+    pub fn drive_throughs(&self, driver_index: u8) -> usize {
+        self.count(driver_index, |r| {
+            r.penalty_type() == PenaltyType::DriveThrough
+        })
+    }
+
+    pub fn stop_gos(&self, driver_index: u8) -> usize {
+        self.count(driver_index, |r| r.penalty_type() == PenaltyType::StopGo)
+    }
+
+    /// Total of the time penalties issued to a driver, in seconds.
+    pub fn time_penalties(&self, driver_index: u8) -> u32 {
+        self.penalties_for(driver_index)
+            .iter()
+            .filter(|r| r.penalty_type() == PenaltyType::TimePenalty)
+            .filter_map(|r| r.penalty.time())
+            .map(u32::from)
+            .sum()
+    }
+
+    /// Indices of the drivers who received at least one penalty.
+    pub fn drivers(&self) -> Vec<u8> {
+        let mut drivers: Vec<u8> = self.records.keys().copied().collect();
+        drivers.sort_unstable();
+        drivers
+    }
+}
@@ -0,0 +1,176 @@
+use getset::CopyGetters;
+use std::collections::HashMap;
+
+use crate::packet::car_status::{PacketCarStatusData, TyreCompoundVisual};
+use crate::packet::header::SessionIdentifier;
+use crate::packet::lap::{PacketLapData, PitStatus};
+use crate::packet::Packet;
+
+/// A visit of a driver to the pit lane.
+///
+/// ## Specification
+/// ```text
+/// lap:             Lap the car entered the pit lane on
+/// entry_time:      Session timestamp of the pit entry
+/// exit_time:       Session timestamp of the pit exit, if the car left the pit lane
+/// compound_before: Visual tyre compound fitted when entering the pit lane
+/// compound_after:  Visual tyre compound fitted when leaving the pit lane
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, CopyGetters)]
+#[getset(get_copy = "pub")]
+pub struct PitStop {
+    lap: u8,
+    entry_time: f32,
+    exit_time: Option<f32>,
+    compound_before: Option<TyreCompoundVisual>,
+    compound_after: Option<TyreCompoundVisual>,
+}
+
+impl PitStop {
+    /// Time spent in the pit lane in seconds, once the car left it.
+    pub fn duration(&self) -> Option<f32> {
+        self.exit_time.map(|exit| exit - self.entry_time)
+    }
+
+    /// Whether the tyre compound changed during the stop. A change to another
+    /// set of the same compound cannot be told apart from a drive-through.
+    pub fn compound_changed(&self) -> bool {
+        match (self.compound_before, self.compound_after) {
+            (Some(before), Some(after)) => before != after,
+            _ => false,
+        }
+    }
+}
+
+/// Records the pit stops of every driver during a session, from the pit status
+/// of the Lap Data packets and the tyre compounds of the Car Status packets.
+///
+/// Drivers are identified by their vehicle index.
+#[derive(Debug, Default)]
+pub struct PitTracker {
+    session: Option<SessionIdentifier>,
+    stops: HashMap<u8, Vec<PitStop>>,
+    in_pits: Vec<bool>,
+    compounds: Vec<TyreCompoundVisual>,
+}
+
+impl PitTracker {
+    pub fn new() -> PitTracker {
+        PitTracker::default()
+    }
+
+    pub fn reset(&mut self) {
+        self.session = None;
+        self.stops.clear();
+        self.in_pits.clear();
+        self.compounds.clear();
+    }
+
+    /// Update the tracker with the Lap Data and Car Status packets, ignoring the others.
+    pub fn update(&mut self, packet: &Packet) {
+        match packet {
+            Packet::Lap(p) => self.update_lap_data(p),
+            Packet::CarStatus(p) => self.update_car_status(p),
+            _ => {}
+        }
+    }
+
+    pub fn update_lap_data(&mut self, packet: &PacketLapData) {
+        self.check_session(packet.header().session_id());
+        let session_time = packet.header().session_time();
+
+        let lap_data = packet.lap_data();
+        self.in_pits.resize(lap_data.len(), false);
+
+        for (idx, ld) in lap_data.iter().enumerate() {
+            let in_pits = ld.pit_status() != PitStatus::None;
+            let was_in_pits = self.in_pits[idx];
+            self.in_pits[idx] = in_pits;
+
+            let compound = self.compounds.get(idx).copied();
+            let vehicle_idx = idx as u8;
+
+            if in_pits && !was_in_pits {
+                self.stops.entry(vehicle_idx).or_default().push(PitStop {
+                    lap: ld.current_lap_num(),
+                    entry_time: session_time,
+                    exit_time: None,
+                    compound_before: compound,
+                    compound_after: None,
+                });
+            } else if !in_pits && was_in_pits {
+                if let Some(stop) = self.current_stop(vehicle_idx) {
+                    stop.exit_time = Some(session_time);
+                    stop.compound_after = stop.compound_after.or(compound);
+                }
+            }
+        }
+    }
+
+    pub fn update_car_status(&mut self, packet: &PacketCarStatusData) {
+        self.check_session(packet.header().session_id());
+
+        self.compounds = packet
+            .car_status_data()
+            .iter()
+            .map(|cs| cs.visual_tyre_compound())
+            .collect();
+
+        for idx in 0..self.compounds.len() {
+            if !self.in_pits.get(idx).copied().unwrap_or(false) {
+                continue;
+            }
+
+            let compound = self.compounds[idx];
+            if let Some(stop) = self.current_stop(idx as u8) {
+                // The tyres are changed in the box, keep the last compound seen in the pit lane.
+                stop.compound_after = Some(compound);
+            }
+        }
+    }
+
+    /// All the pit stops of a driver, in order.
+    pub fn stops_for(&self, driver_index: u8) -> &[PitStop] {
+        self.stops
+            .get(&driver_index)
+            .map(Vec::as_slice)
+            .unwrap_or(&[])
+    }
+
+    /// Number of completed pit stops of a driver.
+    pub fn total_stops(&self, driver_index: u8) -> usize {
+        self.stops_for(driver_index)
+            .iter()
+            .filter(|s| s.exit_time.is_some())
+            .count()
+    }
+
+    /// Whether a driver is currently in the pit lane.
+    pub fn is_in_pits(&self, driver_index: u8) -> bool {
+        self.in_pits
+            .get(driver_index as usize)
+            .copied()
+            .unwrap_or(false)
+    }
+
+    /// Indices of the drivers who entered the pit lane at least once.
+    pub fn drivers(&self) -> Vec<u8> {
+        let mut drivers: Vec<u8> = self.stops.keys().copied().collect();
+        drivers.sort_unstable();
+        drivers
+    }
+
+    fn current_stop(&mut self, driver_index: u8) -> Option<&mut PitStop> {
+        self.stops
+            .get_mut(&driver_index)
+            .and_then(|stops| stops.last_mut())
+            .filter(|stop| stop.exit_time.is_none())
+    }
+
+    fn check_session(&mut self, session: SessionIdentifier) {
+        if self.session != Some(session) {
+            self.reset();
+            self.session = Some(session);
+        }
+    }
+}
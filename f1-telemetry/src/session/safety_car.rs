@@ -0,0 +1,174 @@
+use crate::packet::lap::{PacketLapData, PitStatus};
+use crate::packet::session::{PacketSessionData, SafetyCar};
+
+/// Margin to the safety car delta below which the player risks a penalty, in
+/// seconds.
+pub const DEFAULT_WARNING_MARGIN: f32 = 0.5;
+
+/// How the player's pace complies with the safety car delta.
+///
+/// ## Specification
+/// ```text
+/// Compliant:  The player is slower than the reference by more than the warning margin
+/// Warning:    The player is slower than the reference, but by less than the warning margin
+/// Violation:  The player is faster than the reference, and will be penalised if they stay ahead
+/// ```
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum DeltaCompliance {
+    Compliant,
+    Warning,
+    Violation,
+}
+
+/// Notification sent when a safety car period starts or ends, or when the
+/// compliance of the player with the delta changes during one.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SafetyCarNotification {
+    PeriodStarted {
+        safety_car: SafetyCar,
+    },
+    PeriodEnded,
+    ComplianceChanged {
+        compliance: DeltaCompliance,
+        margin: f32,
+    },
+}
+
+/// Monitors the player's time against the safety car delta during safety car
+/// and virtual safety car periods, from the safety car status of the Session
+/// packets and the delta of the Lap Data packets.
+///
+/// The margin is the delta sent by the game, positive while the player is
+/// slower than the reference time. Deployments are only sent at 2 Hz by the
+/// Session packets, so a period can start up to half a second late. The delta
+/// is not monitored while the player is in the pit lane.
+#[derive(Debug)]
+pub struct SafetyCarMonitor {
+    warning_margin: f32,
+    safety_car: SafetyCar,
+    margin: Option<f32>,
+    min_margin: Option<f32>,
+    compliance: Option<DeltaCompliance>,
+    violations: usize,
+}
+
+impl Default for SafetyCarMonitor {
+    fn default() -> SafetyCarMonitor {
+        SafetyCarMonitor {
+            warning_margin: DEFAULT_WARNING_MARGIN,
+            safety_car: SafetyCar::None,
+            margin: None,
+            min_margin: None,
+            compliance: None,
+            violations: 0,
+        }
+    }
+}
+
+impl SafetyCarMonitor {
+    pub fn new() -> SafetyCarMonitor {
+        SafetyCarMonitor::default()
+    }
+
+    /// Warn the player when their margin drops below `warning_margin` seconds.
+    pub fn with_warning_margin(mut self, warning_margin: f32) -> SafetyCarMonitor {
+        self.warning_margin = warning_margin;
+        self
+    }
+
+    /// Update the safety car status, returning the notification for the
+    /// period that started or ended.
+    pub fn update_session(&mut self, packet: &PacketSessionData) -> Option<SafetyCarNotification> {
+        let safety_car = packet.safety_car_status();
+        if safety_car == self.safety_car {
+            return None;
+        }
+
+        let was_active = self.is_active();
+        self.safety_car = safety_car;
+        self.margin = None;
+        self.compliance = None;
+
+        match (was_active, self.is_active()) {
+            (_, true) => {
+                self.min_margin = None;
+                self.violations = 0;
+                Some(SafetyCarNotification::PeriodStarted { safety_car })
+            }
+            (true, false) => Some(SafetyCarNotification::PeriodEnded),
+            (false, false) => None,
+        }
+    }
+
+    /// Update the player's delta, returning a notification when their
+    /// compliance changed.
+    pub fn update_lap_data(&mut self, packet: &PacketLapData) -> Option<SafetyCarNotification> {
+        if !self.is_active() {
+            return None;
+        }
+
+        let ld = packet
+            .lap_data()
+            .get(packet.header().player_car_index() as usize)?;
+        if ld.pit_status() != PitStatus::None {
+            self.margin = None;
+            self.compliance = None;
+            return None;
+        }
+
+        let margin = ld.safety_car_delta();
+        let compliance = if margin < 0.0 {
+            DeltaCompliance::Violation
+        } else if margin < self.warning_margin {
+            DeltaCompliance::Warning
+        } else {
+            DeltaCompliance::Compliant
+        };
+
+        self.margin = Some(margin);
+        self.min_margin = Some(self.min_margin.map_or(margin, |m| m.min(margin)));
+
+        if self.compliance == Some(compliance) {
+            return None;
+        }
+        // The first delta of a period only notifies the player if at risk.
+        let notify = self.compliance.is_some() || compliance != DeltaCompliance::Compliant;
+        self.compliance = Some(compliance);
+
+        if compliance == DeltaCompliance::Violation {
+            self.violations += 1;
+        }
+
+        notify.then_some(SafetyCarNotification::ComplianceChanged { compliance, margin })
+    }
+
+    /// Safety car deployed, if any.
+    pub fn safety_car(&self) -> SafetyCar {
+        self.safety_car
+    }
+
+    /// Whether a safety car or virtual safety car is deployed.
+    pub fn is_active(&self) -> bool {
+        matches!(self.safety_car, SafetyCar::Full | SafetyCar::Virtual)
+    }
+
+    /// Current margin to the safety car delta in seconds, during a period.
+    pub fn margin(&self) -> Option<f32> {
+        self.margin
+    }
+
+    /// Lowest margin of the current or last period, in seconds.
+    pub fn min_margin(&self) -> Option<f32> {
+        self.min_margin
+    }
+
+    pub fn compliance(&self) -> Option<DeltaCompliance> {
+        self.compliance
+    }
+
+    /// Number of times the player went faster than the reference during the
+    /// current or last period.
+    pub fn violations(&self) -> usize {
+        self.violations
+    }
+}
@@ -0,0 +1,75 @@
+use std::collections::HashMap;
+
+use crate::packet::event::{Event, PacketEventData};
+
+/// Change to the speed trap leaderboard caused by a Speed Trap event.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SpeedTrapChange {
+    /// The driver improved their own best trap speed.
+    PersonalBest { vehicle_idx: u8, speed: f32 },
+    /// The driver set the fastest trap speed of the session.
+    SessionBest { vehicle_idx: u8, speed: f32 },
+}
+
+/// Keeps the best speed trap speed of every driver and the fastest of the session,
+/// from the Speed Trap events.
+#[derive(Debug, Default)]
+pub struct SpeedTrapBoard {
+    best: HashMap<u8, f32>,
+    fastest: Option<(u8, f32)>,
+}
+
+impl SpeedTrapBoard {
+    pub fn new() -> SpeedTrapBoard {
+        SpeedTrapBoard::default()
+    }
+
+    pub fn reset(&mut self) {
+        self.best.clear();
+        self.fastest = None;
+    }
+
+    /// Record the speed carried by an event packet, returning how the leaderboard
+    /// changed, if it did.
+    pub fn update(&mut self, packet: &PacketEventData) -> Option<SpeedTrapChange> {
+        if packet.event() != Event::SpeedTrap {
+            return None;
+        }
+
+        let vehicle_idx = packet.vehicle_idx()?;
+        let speed = packet.speed()?;
+
+        let best = self.best.entry(vehicle_idx).or_insert(0.0);
+        if speed <= *best {
+            return None;
+        }
+        *best = speed;
+
+        match self.fastest {
+            Some((_, fastest)) if speed <= fastest => {
+                Some(SpeedTrapChange::PersonalBest { vehicle_idx, speed })
+            }
+            _ => {
+                self.fastest = Some((vehicle_idx, speed));
+                Some(SpeedTrapChange::SessionBest { vehicle_idx, speed })
+            }
+        }
+    }
+
+    /// Best trap speed of a driver, in kilometres per hour.
+    pub fn best_for(&self, vehicle_idx: u8) -> Option<f32> {
+        self.best.get(&vehicle_idx).copied()
+    }
+
+    /// Vehicle index and speed of the fastest driver through the speed trap.
+    pub fn fastest(&self) -> Option<(u8, f32)> {
+        self.fastest
+    }
+
+    /// Best trap speed of every driver, fastest first.
+    pub fn leaderboard(&self) -> Vec<(u8, f32)> {
+        let mut board: Vec<(u8, f32)> = self.best.iter().map(|(&i, &s)| (i, s)).collect();
+        board.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        board
+    }
+}
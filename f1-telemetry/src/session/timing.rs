@@ -0,0 +1,218 @@
+use std::sync::mpsc::{self, Receiver, Sender};
+
+use crate::packet::header::SessionIdentifier;
+use crate::packet::lap::{LapData, PacketLapData};
+use crate::packet::Packet;
+use crate::pipeline::PacketSink;
+
+/// Notification sent when a driver sets a record, derived from the Lap Data
+/// packets.
+///
+/// Records are only set on valid laps. A record of the session is not also
+/// notified as a personal best.
+///
+/// ## Specification
+/// ```text
+/// PurpleSector:        Fastest time of the session in a sector
+/// PersonalBestSector:  Fastest time of the driver in a sector
+/// NewFastestLap:       Fastest lap of the session
+/// PersonalBest:        Fastest lap of the driver
+///
+/// driver:  Vehicle index of the driver
+/// sector:  0 = sector1, 1 = sector2, 2 = sector3
+/// lap:     Lap number the record was set on
+/// time:    Sector or lap time in seconds
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TimingNotification {
+    PurpleSector { driver: u8, sector: u8, time: f32 },
+    PersonalBestSector { driver: u8, sector: u8, time: f32 },
+    NewFastestLap { driver: u8, lap: u8, time: f32 },
+    PersonalBest { driver: u8, lap: u8, time: f32 },
+}
+
+#[derive(Debug, Clone, Copy)]
+struct DriverState {
+    lap: u8,
+    sector: u8,
+    sector_1_time: f32,
+    sector_2_time: f32,
+    invalid: bool,
+}
+
+impl DriverState {
+    fn new(ld: &LapData) -> DriverState {
+        DriverState {
+            lap: ld.current_lap_num(),
+            sector: ld.sector(),
+            sector_1_time: ld.sector_1_time(),
+            sector_2_time: ld.sector_2_time(),
+            invalid: ld.current_lap_invalid(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct Bests {
+    lap: Option<f32>,
+    sectors: [Option<f32>; 3],
+}
+
+/// Derives the [`TimingNotification`]s of every driver from the Lap Data
+/// packets.
+///
+/// The notifications are returned by [`update`], and sent to the receivers
+/// of [`subscribe`] so that UIs on other threads can flash alerts. The
+/// notifier is a [`PacketSink`], to be added to a pipeline.
+///
+/// Sectors are timed when a car enters the next sector, and the last one
+/// when it crosses the line; records set while no packet was received are
+/// missed. Drivers are identified by their vehicle index.
+///
+/// [`TimingNotification`]: ./enum.TimingNotification.html
+/// [`update`]: #method.update
+/// [`subscribe`]: #method.subscribe
+/// [`PacketSink`]: ../../pipeline/trait.PacketSink.html
+#[derive(Debug, Default)]
+pub struct TimingNotifier {
+    session: Option<SessionIdentifier>,
+    drivers: Vec<Option<DriverState>>,
+    personal: Vec<Bests>,
+    session_bests: Bests,
+    subscribers: Vec<Sender<TimingNotification>>,
+}
+
+impl TimingNotifier {
+    pub fn new() -> TimingNotifier {
+        TimingNotifier::default()
+    }
+
+    /// Forget the records, keeping the subscribers.
+    pub fn reset(&mut self) {
+        self.session = None;
+        self.drivers.clear();
+        self.personal.clear();
+        self.session_bests = Bests::default();
+    }
+
+    /// A channel receiving all the notifications from now on.
+    pub fn subscribe(&mut self) -> Receiver<TimingNotification> {
+        let (sender, receiver) = mpsc::channel();
+        self.subscribers.push(sender);
+        receiver
+    }
+
+    /// Compare a Lap Data packet to the previous one, returning the records set
+    /// in between.
+    pub fn update(&mut self, packet: &PacketLapData) -> Vec<TimingNotification> {
+        let session = packet.header().session_id();
+        if self.session != Some(session) {
+            self.reset();
+            self.session = Some(session);
+        }
+
+        let lap_data = packet.lap_data();
+        self.drivers.resize(lap_data.len(), None);
+        self.personal.resize(lap_data.len(), Bests::default());
+
+        let mut notifications = Vec::new();
+        for (idx, ld) in lap_data.iter().enumerate() {
+            let state = DriverState::new(ld);
+            if let Some(previous) = self.drivers[idx] {
+                self.compare(idx as u8, &previous, &state, ld, &mut notifications);
+            }
+            self.drivers[idx] = Some(state);
+        }
+
+        self.subscribers
+            .retain(|s| notifications.iter().all(|n| s.send(*n).is_ok()));
+
+        notifications
+    }
+
+    fn compare(
+        &mut self,
+        driver: u8,
+        previous: &DriverState,
+        state: &DriverState,
+        ld: &LapData,
+        notifications: &mut Vec<TimingNotification>,
+    ) {
+        if state.lap == previous.lap {
+            if !state.invalid && previous.sector == 0 && state.sector == 1 {
+                self.sector(driver, 0, state.sector_1_time, notifications);
+            } else if !state.invalid && previous.sector == 1 && state.sector == 2 {
+                self.sector(driver, 1, state.sector_2_time, notifications);
+            }
+        } else if state.lap == previous.lap + 1 && previous.lap > 0 && !previous.invalid {
+            // The sector times are reset when the line is crossed, use the last ones seen.
+            let lap_time = ld.last_lap_time();
+            if previous.sector == 2 && previous.sector_1_time > 0.0 && previous.sector_2_time > 0.0
+            {
+                let sector_3_time = lap_time - previous.sector_1_time - previous.sector_2_time;
+                self.sector(driver, 2, sector_3_time, notifications);
+            }
+            self.lap(driver, previous.lap, lap_time, notifications);
+        }
+    }
+
+    fn sector(
+        &mut self,
+        driver: u8,
+        sector: u8,
+        time: f32,
+        notifications: &mut Vec<TimingNotification>,
+    ) {
+        if time <= 0.0 {
+            return;
+        }
+
+        let i = sector as usize;
+        if improves(&mut self.session_bests.sectors[i], time) {
+            self.personal[driver as usize].sectors[i] = Some(time);
+            notifications.push(TimingNotification::PurpleSector {
+                driver,
+                sector,
+                time,
+            });
+        } else if improves(&mut self.personal[driver as usize].sectors[i], time) {
+            notifications.push(TimingNotification::PersonalBestSector {
+                driver,
+                sector,
+                time,
+            });
+        }
+    }
+
+    fn lap(&mut self, driver: u8, lap: u8, time: f32, notifications: &mut Vec<TimingNotification>) {
+        if time <= 0.0 {
+            return;
+        }
+
+        if improves(&mut self.session_bests.lap, time) {
+            self.personal[driver as usize].lap = Some(time);
+            notifications.push(TimingNotification::NewFastestLap { driver, lap, time });
+        } else if improves(&mut self.personal[driver as usize].lap, time) {
+            notifications.push(TimingNotification::PersonalBest { driver, lap, time });
+        }
+    }
+}
+
+impl PacketSink for TimingNotifier {
+    fn handle(&mut self, packet: &Packet) {
+        if let Packet::Lap(p) = packet {
+            self.update(p);
+        }
+    }
+}
+
+/// Replace the best time if `time` is faster, returning whether it was.
+fn improves(best: &mut Option<f32>, time: f32) -> bool {
+    match best {
+        Some(b) if *b <= time => false,
+        _ => {
+            *best = Some(time);
+            true
+        }
+    }
+}
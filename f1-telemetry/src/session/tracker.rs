@@ -0,0 +1,253 @@
+use getset::CopyGetters;
+
+use crate::packet::car_telemetry::PacketCarTelemetryData;
+use crate::packet::generic::{Vec3, WheelData};
+use crate::packet::header::{PacketHeader, Player, SessionIdentifier};
+use crate::packet::lap::{PacketLapData, PositionContext};
+use crate::packet::motion::PacketMotionData;
+use crate::packet::motion_ex::PacketMotionExData;
+use crate::packet::session::PacketSessionData;
+use crate::packet::Packet;
+
+/// Car the camera of the game follows.
+///
+/// ## Specification
+/// ```text
+/// Player:      Car of the primary player
+/// Spectating:  Car the player is spectating, e.g. in an online lobby or after
+///              retiring
+/// ```
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum ViewTarget {
+    Player(u8),
+    Spectating(u8),
+}
+
+impl ViewTarget {
+    /// Car followed in a session.
+    pub fn from_session(session: &PacketSessionData) -> ViewTarget {
+        if session.is_spectating() {
+            ViewTarget::Spectating(session.spectator_car_index())
+        } else {
+            ViewTarget::Player(session.header().player_car_index())
+        }
+    }
+
+    /// Index of the car in the per-car arrays.
+    pub fn car_index(self) -> u8 {
+        match self {
+            ViewTarget::Player(idx) | ViewTarget::Spectating(idx) => idx,
+        }
+    }
+
+    pub fn is_spectating(self) -> bool {
+        matches!(self, ViewTarget::Spectating(_))
+    }
+}
+
+/// Latest known state of the car a player is driving.
+///
+/// ## Specification
+/// ```text
+/// car_index:      Index of the player's car in the per-car arrays
+/// position:       Position context from the last Lap Data packet
+/// world_position: World space X, Y and Z position from the last Motion packet
+/// speed:          Speed in kilometres per hour from the last Car Telemetry packet
+/// gear:           Gear from the last Car Telemetry packet
+/// wheel_speed:    Speed of each wheel, from the extra player car data of the
+///                 Motion packet, or the Motion Ex packet from F1 2023. Only known
+///                 for the car the extra data belongs to.
+/// ```
+#[derive(Debug, Clone, Copy, CopyGetters)]
+#[getset(get_copy = "pub")]
+pub struct FocusContext {
+    car_index: u8,
+    position: Option<PositionContext>,
+    world_position: Option<Vec3>,
+    speed: Option<u16>,
+    gear: Option<i8>,
+    wheel_speed: Option<WheelData<f32>>,
+}
+
+impl FocusContext {
+    fn new(car_index: u8) -> FocusContext {
+        FocusContext {
+            car_index,
+            position: None,
+            world_position: None,
+            speed: None,
+            gear: None,
+            wheel_speed: None,
+        }
+    }
+}
+
+/// Follows the cars of the players of a session, keeping a separate focused
+/// context for each of them in split-screen sessions.
+///
+/// The contexts are reset when a new session starts, or when a player's car changes.
+/// The Session packets tell whether the player is spectating another car, which
+/// is then the [`ViewTarget`].
+///
+/// [`ViewTarget`]: ./enum.ViewTarget.html
+#[derive(Debug, Default)]
+pub struct SessionTracker {
+    session: Option<SessionIdentifier>,
+    primary: Option<FocusContext>,
+    secondary: Option<FocusContext>,
+    spectated: Option<u8>,
+    network_game: Option<bool>,
+}
+
+impl SessionTracker {
+    pub fn new() -> SessionTracker {
+        SessionTracker::default()
+    }
+
+    pub fn reset(&mut self) {
+        self.session = None;
+        self.primary = None;
+        self.secondary = None;
+        self.spectated = None;
+        self.network_game = None;
+    }
+
+    /// Identifier of the session being tracked.
+    pub fn session(&self) -> Option<SessionIdentifier> {
+        self.session
+    }
+
+    /// Whether a second player is being tracked.
+    pub fn is_split_screen(&self) -> bool {
+        self.secondary.is_some()
+    }
+
+    /// Context of a player, if they are in the session.
+    pub fn focus(&self, player: Player) -> Option<&FocusContext> {
+        match player {
+            Player::Primary => self.primary.as_ref(),
+            Player::Secondary => self.secondary.as_ref(),
+        }
+    }
+
+    /// Car the camera follows: the spectated one when spectating, the primary
+    /// player's otherwise.
+    pub fn view_target(&self) -> Option<ViewTarget> {
+        match self.spectated {
+            Some(idx) => Some(ViewTarget::Spectating(idx)),
+            None => self.primary.map(|f| ViewTarget::Player(f.car_index)),
+        }
+    }
+
+    /// Whether the session is online, once a Session packet was received.
+    pub fn network_game(&self) -> Option<bool> {
+        self.network_game
+    }
+
+    /// Contexts of the players in the session, primary player first.
+    pub fn focused(&self) -> Vec<(Player, &FocusContext)> {
+        [Player::Primary, Player::Secondary]
+            .iter()
+            .filter_map(|&p| self.focus(p).map(|f| (p, f)))
+            .collect()
+    }
+
+    pub fn update(&mut self, packet: &Packet) {
+        match packet {
+            Packet::Session(p) => self.update_session(p),
+            Packet::Lap(p) => self.update_lap(p),
+            Packet::Motion(p) => self.update_motion(p),
+            Packet::MotionEx(p) => self.update_motion_ex(p),
+            Packet::CarTelemetry(p) => self.update_telemetry(p),
+            _ => {}
+        }
+    }
+
+    fn update_session(&mut self, packet: &PacketSessionData) {
+        self.sync(packet.header());
+        self.spectated = match ViewTarget::from_session(packet) {
+            ViewTarget::Spectating(idx) => Some(idx),
+            ViewTarget::Player(_) => None,
+        };
+        self.network_game = Some(packet.network_game());
+    }
+
+    fn update_lap(&mut self, packet: &PacketLapData) {
+        for player in self.sync(packet.header()) {
+            let focus = self.focus_mut(player).unwrap();
+            if let Some(ld) = packet.lap_data().get(focus.car_index as usize) {
+                focus.position = Some(ld.position_context());
+            }
+        }
+    }
+
+    fn update_motion(&mut self, packet: &PacketMotionData) {
+        let extra_data_car_index = packet.extra_data_car_index();
+
+        for player in self.sync(packet.header()) {
+            let focus = self.focus_mut(player).unwrap();
+            if let Some(md) = packet.motion_data().get(focus.car_index as usize) {
+                focus.world_position = Some(md.world_position());
+            }
+            if packet.has_extra_data() && focus.car_index == extra_data_car_index {
+                focus.wheel_speed = Some(packet.wheel_speed());
+            }
+        }
+    }
+
+    fn update_motion_ex(&mut self, packet: &PacketMotionExData) {
+        let car_index = packet.header().player_car_index();
+
+        for player in self.sync(packet.header()) {
+            let focus = self.focus_mut(player).unwrap();
+            if focus.car_index == car_index {
+                focus.wheel_speed = Some(packet.wheel_speed());
+            }
+        }
+    }
+
+    fn update_telemetry(&mut self, packet: &PacketCarTelemetryData) {
+        for player in self.sync(packet.header()) {
+            let focus = self.focus_mut(player).unwrap();
+            if let Some(td) = packet.car_telemetry_data().get(focus.car_index as usize) {
+                focus.speed = Some(td.speed());
+                focus.gear = Some(td.gear());
+            }
+        }
+    }
+
+    /// Follow the session and player cars of a header, returning the players in it.
+    fn sync(&mut self, header: &PacketHeader) -> Vec<Player> {
+        if self.session != Some(header.session_id()) {
+            self.reset();
+            self.session = Some(header.session_id());
+        }
+
+        let mut players = Vec::with_capacity(2);
+        for &player in &[Player::Primary, Player::Secondary] {
+            let focus = match player {
+                Player::Primary => &mut self.primary,
+                Player::Secondary => &mut self.secondary,
+            };
+
+            match header.car_index(player) {
+                Some(idx) => {
+                    if focus.map(|f| f.car_index) != Some(idx) {
+                        *focus = Some(FocusContext::new(idx));
+                    }
+                    players.push(player);
+                }
+                None => *focus = None,
+            }
+        }
+
+        players
+    }
+
+    fn focus_mut(&mut self, player: Player) -> Option<&mut FocusContext> {
+        match player {
+            Player::Primary => self.primary.as_mut(),
+            Player::Secondary => self.secondary.as_mut(),
+        }
+    }
+}
@@ -0,0 +1,104 @@
+use getset::CopyGetters;
+
+use crate::packet::session::{PacketSessionData, SessionType, Weather, WeatherForecastSample};
+
+/// A change of weather announced by the forecast.
+///
+/// ## Specification
+/// ```text
+/// time_offset:     Time in minutes until the change
+/// from:            Weather before the change
+/// to:              Weather after the change
+/// rain_percentage: Rain percentage (0-100) after the change (F1 2021+)
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, CopyGetters)]
+#[getset(get_copy = "pub")]
+pub struct WeatherChange {
+    time_offset: u8,
+    from: Weather,
+    to: Weather,
+    rain_percentage: Option<u8>,
+}
+
+/// Keeps the weather forecast of the current session, from the Session packets
+/// (F1 2020+).
+///
+/// The game sends the forecast of all the sessions of the weekend; only the
+/// samples of the current session are kept.
+#[derive(Debug, Default)]
+pub struct WeatherTracker {
+    session_type: Option<SessionType>,
+    weather: Option<Weather>,
+    forecast: Vec<WeatherForecastSample>,
+}
+
+impl WeatherTracker {
+    pub fn new() -> WeatherTracker {
+        WeatherTracker::default()
+    }
+
+    pub fn update(&mut self, packet: &PacketSessionData) {
+        let session_type = packet.session_type();
+
+        self.session_type = Some(session_type);
+        self.weather = Some(packet.weather());
+        self.forecast = packet
+            .weather_forecast()
+            .iter()
+            .filter(|s| s.session_type() == session_type)
+            .copied()
+            .collect();
+        self.forecast.sort_by_key(|s| s.time_offset());
+    }
+
+    pub fn current_weather(&self) -> Option<Weather> {
+        self.weather
+    }
+
+    /// Forecast samples of the current session, by increasing time offset.
+    pub fn forecast(&self) -> &[WeatherForecastSample] {
+        &self.forecast
+    }
+
+    /// Forecast sample for `minutes` from now, i.e. the latest sample that is not
+    /// after it.
+    pub fn forecast_at(&self, minutes: u8) -> Option<&WeatherForecastSample> {
+        self.forecast
+            .iter()
+            .take_while(|s| s.time_offset() <= minutes)
+            .last()
+    }
+
+    /// Chance of rain (0-100) `minutes` from now (F1 2021+).
+    pub fn rain_probability(&self, minutes: u8) -> Option<u8> {
+        self.forecast_at(minutes)?.rain_percentage()
+    }
+
+    /// Changes of weather announced by the forecast, in order.
+    pub fn upcoming_changes(&self) -> Vec<WeatherChange> {
+        let mut changes = Vec::new();
+        let mut weather = match self.weather {
+            Some(weather) => weather,
+            None => return changes,
+        };
+
+        for sample in &self.forecast {
+            if sample.weather() != weather {
+                changes.push(WeatherChange {
+                    time_offset: sample.time_offset(),
+                    from: weather,
+                    to: sample.weather(),
+                    rain_percentage: sample.rain_percentage(),
+                });
+                weather = sample.weather();
+            }
+        }
+
+        changes
+    }
+
+    /// First forecast sample with rain, if rain is expected.
+    pub fn next_rain(&self) -> Option<&WeatherForecastSample> {
+        self.forecast.iter().find(|s| s.weather().is_wet())
+    }
+}
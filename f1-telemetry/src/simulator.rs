@@ -0,0 +1,744 @@
+//! Synthetic race sessions, sent in the wire format of F1 2019.
+//!
+//! [`Simulator`] drives a field of cars around a circuit with plausible lap
+//! times, a pit stop for each car and changing weather, and serializes the
+//! Motion, Session, Lap Data, Event, Participants, Car Telemetry and Car Status
+//! packets the game would send. UI and analysis code can be developed against
+//! it without the game running.
+//!
+//! [`Simulator`]: ./struct.Simulator.html
+
+use byteorder::{LittleEndian, WriteBytesExt};
+use std::f32::consts::PI;
+use std::io::{self, Write};
+use std::net::{ToSocketAddrs, UdpSocket};
+use std::thread::sleep;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::packet::session::{Track, Weather};
+use crate::packet::PacketType;
+
+const PACKET_FORMAT: u16 = 2019;
+const MAX_CARS: usize = 20;
+
+/// Relative amplitude of the speed variations around the lap.
+const SPEED_VARIATION: f32 = 0.3;
+/// Number of slow and fast sections around the lap.
+const CORNERS: f32 = 6.0;
+/// Length of the pit lane on each side of the line, in metres.
+const PIT_LANE_HALF_LENGTH: f32 = 300.0;
+const PIT_SPEED_LIMIT: f32 = 80.0;
+const PIT_STOP_DURATION: f32 = 2.5;
+
+const COMPOUND_SOFT: u8 = 16;
+const COMPOUND_MEDIUM: u8 = 17;
+
+/// Small xorshift generator, so that sessions can be reproduced from a seed.
+#[derive(Debug, Clone)]
+struct Rng(u64);
+
+impl Rng {
+    fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    /// Uniform value between -1.0 and 1.0.
+    fn signed(&mut self) -> f32 {
+        (self.next_u64() >> 40) as f32 / (1u64 << 23) as f32 - 1.0
+    }
+
+    fn range(&mut self, low: f32, high: f32) -> f32 {
+        low + (high - low) * (self.signed() + 1.0) / 2.0
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum PitPhase {
+    None,
+    Entry,
+    Stopped(f32),
+    Exit,
+}
+
+#[derive(Debug, Clone)]
+struct Car {
+    pace: f32,
+    lap_pace: f32,
+    distance: f32,
+    speed: f32,
+    acceleration: f32,
+    current_lap_time: f32,
+    last_lap_time: f32,
+    best_lap_time: f32,
+    sector_1_time: f32,
+    sector_2_time: f32,
+    grid_position: u8,
+    position: u8,
+    pit_lap: u8,
+    pit: PitPhase,
+    compound: u8,
+    tyre_wear: f32,
+    finished: bool,
+}
+
+impl Car {
+    fn lap(&self, track_length: f32) -> u8 {
+        (self.distance.max(0.0) / track_length) as u8 + 1
+    }
+
+    fn lap_distance(&self, track_length: f32) -> f32 {
+        if self.distance < 0.0 {
+            self.distance
+        } else {
+            self.distance % track_length
+        }
+    }
+
+    fn gear(&self) -> i8 {
+        (1 + (self.speed / 42.0) as i8).min(8)
+    }
+
+    fn engine_rpm(&self) -> u16 {
+        let gear_speed = self.speed % 42.0 / 42.0;
+        (6000.0 + 6000.0 * gear_speed) as u16
+    }
+
+    fn pit_status(&self) -> u8 {
+        match self.pit {
+            PitPhase::None => 0,
+            PitPhase::Entry | PitPhase::Exit => 1,
+            PitPhase::Stopped(_) => 2,
+        }
+    }
+}
+
+/// Generates a synthetic race and serializes it as F1 2019 packets.
+///
+/// The player drives the first car. Every car makes one pit stop around
+/// mid-race, changing from soft to medium tyres; the weather changes every few
+/// minutes unless disabled.
+#[derive(Debug, Clone)]
+pub struct Simulator {
+    rng: Rng,
+    session_uid: u64,
+    track: Track,
+    track_length: f32,
+    total_laps: u8,
+    base_lap_time: f32,
+    rate: u32,
+    weather_changes: bool,
+    weather: Weather,
+    next_weather_change: f32,
+    cars: Vec<Car>,
+    time: f32,
+    frame: u32,
+    fastest_lap: Option<f32>,
+    chequered_flag: bool,
+    ended: bool,
+}
+
+impl Default for Simulator {
+    fn default() -> Simulator {
+        Simulator::new()
+    }
+}
+
+impl Simulator {
+    /// A 20-car race of 5 laps around Melbourne, sent 20 times per second.
+    pub fn new() -> Simulator {
+        let seed = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or(0);
+
+        let mut simulator = Simulator {
+            rng: Rng(1),
+            session_uid: 0,
+            track: Track::Melbourne,
+            track_length: 5303.0,
+            total_laps: 5,
+            base_lap_time: 85.0,
+            rate: 20,
+            weather_changes: true,
+            weather: Weather::Clear,
+            next_weather_change: 0.0,
+            cars: Vec::new(),
+            time: 0.0,
+            frame: 0,
+            fastest_lap: None,
+            chequered_flag: false,
+            ended: false,
+        };
+        simulator.reset(seed, MAX_CARS);
+        simulator
+    }
+
+    /// Generate the same session every time for a given seed.
+    pub fn with_seed(mut self, seed: u64) -> Simulator {
+        let num_cars = self.cars.len();
+        self.reset(seed, num_cars);
+        self
+    }
+
+    /// Track of the session, with its length in metres and the lap time of
+    /// the fastest car in seconds.
+    pub fn with_track(mut self, track: Track, track_length: u16, lap_time: f32) -> Simulator {
+        self.track = track;
+        self.track_length = track_length as f32;
+        self.base_lap_time = lap_time;
+        self.reset_cars();
+        self
+    }
+
+    pub fn with_laps(mut self, total_laps: u8) -> Simulator {
+        self.total_laps = total_laps.max(1);
+        self.reset_cars();
+        self
+    }
+
+    /// Number of cars, up to 20.
+    pub fn with_cars(mut self, num_cars: usize) -> Simulator {
+        let seed = self.rng.0;
+        self.reset(seed, num_cars.clamp(1, MAX_CARS));
+        self
+    }
+
+    /// Number of Motion, Lap Data, Car Telemetry and Car Status packets sent
+    /// per second.
+    pub fn with_rate(mut self, rate: u32) -> Simulator {
+        self.rate = rate.max(1);
+        self
+    }
+
+    pub fn with_weather_changes(mut self, weather_changes: bool) -> Simulator {
+        self.weather_changes = weather_changes;
+        self
+    }
+
+    fn reset(&mut self, seed: u64, num_cars: usize) {
+        // Xorshift is stuck on zero.
+        self.rng = Rng(seed | 1);
+        self.session_uid = self.rng.next_u64();
+        self.cars = vec![new_car(); num_cars];
+        self.reset_cars();
+    }
+
+    fn reset_cars(&mut self) {
+        let track_length = self.track_length;
+        let total_laps = self.total_laps;
+        let rng = &mut self.rng;
+
+        for (i, car) in self.cars.iter_mut().enumerate() {
+            let pace = 1.0 + i as f32 * 0.002 + rng.range(0.0, 0.004);
+            let mid_race = (total_laps / 2).max(1) as f32;
+
+            *car = Car {
+                pace,
+                lap_pace: pace,
+                // Two cars per row, 8 metres between rows, behind the line.
+                distance: -(10.0 + (i / 2) as f32 * 8.0 + (i % 2) as f32 * 4.0),
+                grid_position: i as u8 + 1,
+                position: i as u8 + 1,
+                pit_lap: (mid_race + rng.range(-1.0, 1.0)).round().max(1.0) as u8,
+                ..new_car()
+            };
+            car.distance = car.distance.max(-track_length);
+        }
+
+        self.weather = Weather::Clear;
+        self.next_weather_change = self.rng.range(300.0, 600.0);
+        self.time = 0.0;
+        self.frame = 0;
+        self.fastest_lap = None;
+        self.chequered_flag = false;
+        self.ended = false;
+    }
+
+    /// Whether the session ended, once every car crossed the line after the
+    /// chequered flag.
+    pub fn is_finished(&self) -> bool {
+        self.ended
+    }
+
+    pub fn session_time(&self) -> f32 {
+        self.time
+    }
+
+    pub fn weather(&self) -> Weather {
+        self.weather
+    }
+
+    /// Advance the session by one frame, returning the datagrams sent during it.
+    pub fn tick(&mut self) -> Vec<Vec<u8>> {
+        let mut datagrams = Vec::new();
+        if self.ended {
+            return datagrams;
+        }
+
+        if self.frame == 0 {
+            datagrams.push(self.event(b"SSTA", 0, 0.0));
+        }
+
+        let dt = 1.0 / self.rate as f32;
+        self.time += dt;
+        self.frame += 1;
+
+        self.update_weather();
+        let mut events = self.update_cars(dt);
+        self.update_positions();
+
+        datagrams.push(self.motion());
+        datagrams.push(self.lap_data());
+        datagrams.push(self.car_telemetry());
+        datagrams.push(self.car_status());
+
+        let frames_per_second = self.rate;
+        if self.frame % (frames_per_second / 2).max(1) == 1 {
+            datagrams.push(self.session());
+        }
+        if self.frame % (frames_per_second * 5) == 1 {
+            datagrams.push(self.participants());
+        }
+
+        datagrams.append(&mut events);
+
+        if self.cars.iter().all(|c| c.finished) {
+            datagrams.push(self.event(b"SEND", 0, 0.0));
+            self.ended = true;
+        }
+
+        datagrams
+    }
+
+    /// Send the session to `addr` until it ends, `speed` times faster than
+    /// real time.
+    pub fn run<A: ToSocketAddrs>(&mut self, addr: A, speed: f32) -> io::Result<()> {
+        let socket = UdpSocket::bind("0.0.0.0:0")?;
+        socket.connect(addr)?;
+
+        let frame_duration = Duration::from_secs_f32(1.0 / (self.rate as f32 * speed.max(0.01)));
+        let start = Instant::now();
+        let mut frame = 0;
+
+        while !self.is_finished() {
+            for datagram in self.tick() {
+                socket.send(&datagram)?;
+            }
+
+            frame += 1;
+            let next = start + frame_duration * frame;
+            if let Some(wait) = next.checked_duration_since(Instant::now()) {
+                sleep(wait);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn update_weather(&mut self) {
+        if !self.weather_changes || self.time < self.next_weather_change {
+            return;
+        }
+
+        let current: u8 = self.weather.into();
+        let next = if current == 0 || (current < 4 && self.rng.signed() > 0.0) {
+            current + 1
+        } else {
+            current - 1
+        };
+
+        self.weather = Weather::from(next);
+        self.next_weather_change = self.time + self.rng.range(300.0, 600.0);
+    }
+
+    fn update_cars(&mut self, dt: f32) -> Vec<Vec<u8>> {
+        let mut events = Vec::new();
+        let track_length = self.track_length;
+        let total_laps = self.total_laps;
+        let weather: u8 = self.weather.into();
+        // Wet tracks are slower, without changing to wet tyres.
+        let weather_pace = 1.0 + 0.03 * weather.saturating_sub(2) as f32;
+        let base_speed =
+            track_length / self.base_lap_time * 3.6 / (1.0 - SPEED_VARIATION.powi(2)).sqrt();
+
+        for idx in 0..self.cars.len() {
+            let car = &mut self.cars[idx];
+            if car.finished {
+                continue;
+            }
+
+            let lap_before = car.lap(track_length);
+            let lap_distance = car.lap_distance(track_length);
+            let phase = 2.0 * PI * CORNERS * lap_distance.max(0.0) / track_length;
+            let racing_speed =
+                base_speed / (car.lap_pace * weather_pace) * (1.0 + SPEED_VARIATION * phase.sin());
+
+            let speed = match car.pit {
+                PitPhase::None => racing_speed,
+                PitPhase::Stopped(_) => 0.0,
+                PitPhase::Entry | PitPhase::Exit => racing_speed.min(PIT_SPEED_LIMIT),
+            };
+
+            car.acceleration = (speed - car.speed) / 3.6 / dt;
+            car.speed = speed;
+            car.distance += speed / 3.6 * dt;
+            car.current_lap_time += dt;
+            car.tyre_wear += dt / self.base_lap_time * 2.0;
+
+            let lap_distance = car.lap_distance(track_length);
+            if car.sector_1_time == 0.0 && lap_distance >= track_length / 3.0 {
+                car.sector_1_time = car.current_lap_time;
+            }
+            if car.sector_2_time == 0.0 && lap_distance >= 2.0 * track_length / 3.0 {
+                car.sector_2_time = car.current_lap_time - car.sector_1_time;
+            }
+
+            Simulator::update_pit(car, lap_distance, track_length, dt);
+
+            if car.lap(track_length) > lap_before && lap_before >= 1 && car.distance > 0.0 {
+                let lap_time = car.current_lap_time;
+                car.last_lap_time = lap_time;
+                if car.best_lap_time == 0.0 || lap_time < car.best_lap_time {
+                    car.best_lap_time = lap_time;
+                }
+                car.current_lap_time = 0.0;
+                car.sector_1_time = 0.0;
+                car.sector_2_time = 0.0;
+                car.lap_pace = car.pace * (1.0 + 0.004 * self.rng.signed());
+
+                let fastest = match self.fastest_lap {
+                    Some(fastest_lap) => lap_time < fastest_lap,
+                    None => true,
+                };
+                if fastest {
+                    self.fastest_lap = Some(lap_time);
+                    events.push((b"FTLP", idx as u8, lap_time));
+                }
+
+                let car = &mut self.cars[idx];
+                if self.chequered_flag {
+                    car.finished = true;
+                } else if lap_before >= total_laps {
+                    car.finished = true;
+                    self.chequered_flag = true;
+                    events.push((b"CHQF", 0, 0.0));
+                    events.push((b"RCWN", idx as u8, 0.0));
+                }
+            } else if car.lap(track_length) > lap_before {
+                // Crossing the line from the grid starts the first lap.
+                car.current_lap_time = 0.0;
+            }
+        }
+
+        events
+            .into_iter()
+            .map(|(code, vehicle_idx, lap_time)| self.event(code, vehicle_idx, lap_time))
+            .collect()
+    }
+
+    fn update_pit(car: &mut Car, lap_distance: f32, track_length: f32, dt: f32) {
+        let lap = car.lap(track_length);
+
+        car.pit = match car.pit {
+            PitPhase::None
+                if lap == car.pit_lap && lap_distance > track_length - PIT_LANE_HALF_LENGTH =>
+            {
+                PitPhase::Entry
+            }
+            // The pit box is just after the line.
+            PitPhase::Entry if lap == car.pit_lap + 1 && lap_distance >= 0.0 => {
+                PitPhase::Stopped(PIT_STOP_DURATION)
+            }
+            PitPhase::Stopped(left) if left > dt => PitPhase::Stopped(left - dt),
+            PitPhase::Stopped(_) => {
+                car.compound = COMPOUND_MEDIUM;
+                car.tyre_wear = 0.0;
+                PitPhase::Exit
+            }
+            PitPhase::Exit if lap_distance > PIT_LANE_HALF_LENGTH => PitPhase::None,
+            pit => pit,
+        };
+    }
+
+    fn update_positions(&mut self) {
+        let mut order: Vec<usize> = (0..self.cars.len()).collect();
+        order.sort_by(|&a, &b| self.cars[b].distance.total_cmp(&self.cars[a].distance));
+
+        for (position, idx) in order.into_iter().enumerate() {
+            self.cars[idx].position = position as u8 + 1;
+        }
+    }
+
+    fn header(&self, packet_type: PacketType) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(packet_type.size(PACKET_FORMAT).unwrap_or(0));
+
+        buf.write_u16::<LittleEndian>(PACKET_FORMAT).unwrap();
+        buf.write_u8(1).unwrap();
+        buf.write_u8(0).unwrap();
+        buf.write_u8(1).unwrap();
+        buf.write_u8(packet_type.id()).unwrap();
+        buf.write_u64::<LittleEndian>(self.session_uid).unwrap();
+        buf.write_f32::<LittleEndian>(self.time).unwrap();
+        buf.write_u32::<LittleEndian>(self.frame).unwrap();
+        // The player drives the first car.
+        buf.write_u8(0).unwrap();
+
+        buf
+    }
+
+    /// Pad a datagram to the size of its packet type, for the unused cars.
+    fn finish(packet_type: PacketType, mut buf: Vec<u8>) -> Vec<u8> {
+        if let Some(size) = packet_type.size(PACKET_FORMAT) {
+            buf.resize(size, 0);
+        }
+        buf
+    }
+
+    fn motion(&self) -> Vec<u8> {
+        let mut buf = self.header(PacketType::Motion);
+        let radius = self.track_length / (2.0 * PI);
+
+        for car in &self.cars {
+            let angle = car.lap_distance(self.track_length) / radius;
+            let (sin, cos) = angle.sin_cos();
+            let speed = car.speed / 3.6;
+
+            write_f32s(&mut buf, &[radius * cos, 0.0, radius * sin]);
+            write_f32s(&mut buf, &[-speed * sin, 0.0, speed * cos]);
+            for dir in &[-sin, 0.0, cos, cos, 0.0, sin] {
+                buf.write_i16::<LittleEndian>((dir * 32767.0) as i16)
+                    .unwrap();
+            }
+            let lateral = speed * speed / radius / 9.81;
+            let longitudinal = car.acceleration / 9.81;
+            write_f32s(&mut buf, &[lateral, longitudinal, 1.0]);
+            // Yaw, pitch and roll.
+            write_f32s(&mut buf, &[-angle, 0.0, 0.0]);
+        }
+        for _ in self.cars.len()..MAX_CARS {
+            buf.write_all(&[0; 60]).unwrap();
+        }
+
+        // Extra player car data: suspension, wheels and local velocities.
+        let player_speed = self.cars[0].speed / 3.6;
+        write_f32s(&mut buf, &[0.0; 12]);
+        write_f32s(&mut buf, &[player_speed; 4]);
+        write_f32s(&mut buf, &[0.0; 4]);
+        write_f32s(&mut buf, &[0.0, 0.0, player_speed]);
+        write_f32s(&mut buf, &[0.0; 7]);
+
+        Simulator::finish(PacketType::Motion, buf)
+    }
+
+    fn session(&self) -> Vec<u8> {
+        let mut buf = self.header(PacketType::Session);
+        let weather: u8 = self.weather.into();
+        let track: i8 = self.track.into();
+
+        buf.write_u8(weather).unwrap();
+        buf.write_i8(32 - 3 * weather as i8).unwrap();
+        buf.write_i8(24 - weather as i8).unwrap();
+        buf.write_u8(self.total_laps).unwrap();
+        buf.write_u16::<LittleEndian>(self.track_length as u16)
+            .unwrap();
+        // Race, with modern F1 cars.
+        buf.write_u8(10).unwrap();
+        buf.write_i8(track).unwrap();
+        buf.write_u8(0).unwrap();
+        buf.write_u16::<LittleEndian>(7200u16.saturating_sub(self.time as u16))
+            .unwrap();
+        buf.write_u16::<LittleEndian>(7200).unwrap();
+        buf.write_u8(PIT_SPEED_LIMIT as u8).unwrap();
+        // Not paused, not spectating, spectated car, no SLI Pro support.
+        buf.write_all(&[0, 0, 0, 0]).unwrap();
+        // No marshal zones.
+        buf.write_u8(0).unwrap();
+        buf.write_all(&[0; 21 * 5]).unwrap();
+        // No safety car, offline game.
+        buf.write_all(&[0, 0]).unwrap();
+
+        Simulator::finish(PacketType::Session, buf)
+    }
+
+    fn lap_data(&self) -> Vec<u8> {
+        let mut buf = self.header(PacketType::LapData);
+
+        for car in &self.cars {
+            let lap = car.lap(self.track_length);
+            let lap_distance = car.lap_distance(self.track_length);
+            let sector = if car.sector_2_time > 0.0 {
+                2
+            } else if car.sector_1_time > 0.0 {
+                1
+            } else {
+                0
+            };
+
+            write_f32s(
+                &mut buf,
+                &[
+                    car.last_lap_time,
+                    car.current_lap_time,
+                    car.best_lap_time,
+                    car.sector_1_time,
+                    car.sector_2_time,
+                    lap_distance,
+                    car.distance,
+                    0.0,
+                ],
+            );
+            buf.write_u8(car.position).unwrap();
+            buf.write_u8(lap.min(self.total_laps)).unwrap();
+            buf.write_u8(car.pit_status()).unwrap();
+            buf.write_u8(sector).unwrap();
+            // Valid lap, no penalties.
+            buf.write_all(&[0, 0]).unwrap();
+            buf.write_u8(car.grid_position).unwrap();
+            // Driver status: on track (4) or in lap (2) once finished.
+            buf.write_u8(if car.finished { 2 } else { 4 }).unwrap();
+            // Result status: active (2) or finished (3).
+            buf.write_u8(if car.finished { 3 } else { 2 }).unwrap();
+        }
+
+        Simulator::finish(PacketType::LapData, buf)
+    }
+
+    fn event(&self, code: &[u8; 4], vehicle_idx: u8, lap_time: f32) -> Vec<u8> {
+        let mut buf = self.header(PacketType::Event);
+
+        buf.write_all(code).unwrap();
+        buf.write_u8(vehicle_idx).unwrap();
+        buf.write_f32::<LittleEndian>(lap_time).unwrap();
+
+        Simulator::finish(PacketType::Event, buf)
+    }
+
+    fn participants(&self) -> Vec<u8> {
+        let mut buf = self.header(PacketType::Participants);
+
+        buf.write_u8(self.cars.len() as u8).unwrap();
+        for i in 0..self.cars.len() {
+            // AI controlled, except for the player.
+            buf.write_u8((i != 0) as u8).unwrap();
+            buf.write_u8(i as u8).unwrap();
+            buf.write_u8((i / 2) as u8).unwrap();
+            buf.write_u8(i as u8 + 2).unwrap();
+            buf.write_u8(0).unwrap();
+
+            let mut name = [0u8; 48];
+            let driver = format!("Driver {}", i + 1);
+            name[..driver.len()].copy_from_slice(driver.as_bytes());
+            buf.write_all(&name).unwrap();
+            // Public telemetry.
+            buf.write_u8(1).unwrap();
+        }
+
+        Simulator::finish(PacketType::Participants, buf)
+    }
+
+    fn car_telemetry(&self) -> Vec<u8> {
+        let mut buf = self.header(PacketType::CarTelemetry);
+
+        for car in &self.cars {
+            let braking = car.acceleration < 0.0;
+            let brake = if braking {
+                (-car.acceleration / 40.0).min(1.0)
+            } else {
+                0.0
+            };
+            let throttle = if braking || car.speed == 0.0 {
+                0.0
+            } else {
+                1.0
+            };
+
+            buf.write_u16::<LittleEndian>(car.speed as u16).unwrap();
+            write_f32s(&mut buf, &[throttle, 0.0, brake]);
+            buf.write_u8(0).unwrap();
+            buf.write_i8(car.gear()).unwrap();
+            buf.write_u16::<LittleEndian>(car.engine_rpm()).unwrap();
+            // DRS closed, rev lights.
+            buf.write_u8(0).unwrap();
+            buf.write_u8(((car.engine_rpm() - 6000) / 60) as u8)
+                .unwrap();
+            for temperature in &[500u16, 90, 95, 105] {
+                for _ in 0..4 {
+                    buf.write_u16::<LittleEndian>(*temperature).unwrap();
+                }
+            }
+            write_f32s(&mut buf, &[23.0; 4]);
+            // Tarmac under every wheel.
+            buf.write_all(&[0; 4]).unwrap();
+        }
+        for _ in self.cars.len()..MAX_CARS {
+            buf.write_all(&[0; 66]).unwrap();
+        }
+        buf.write_u32::<LittleEndian>(0).unwrap();
+
+        Simulator::finish(PacketType::CarTelemetry, buf)
+    }
+
+    fn car_status(&self) -> Vec<u8> {
+        let mut buf = self.header(PacketType::CarStatus);
+
+        for car in &self.cars {
+            let laps_done = (car.distance.max(0.0) / self.track_length).min(self.total_laps as f32);
+            let fuel = 1.6 * (self.total_laps as f32 - laps_done) + 1.0;
+            let wear = car.tyre_wear.min(100.0) as u8;
+
+            // Traction control off, ABS off, standard fuel mix, 56% brake bias,
+            // pit limiter in the pit lane.
+            buf.write_all(&[0, 0, 1, 56]).unwrap();
+            buf.write_u8((car.pit != PitPhase::None) as u8).unwrap();
+            write_f32s(&mut buf, &[fuel, 110.0, fuel / 1.6]);
+            buf.write_u16::<LittleEndian>(13000).unwrap();
+            buf.write_u16::<LittleEndian>(4000).unwrap();
+            buf.write_u8(8).unwrap();
+            buf.write_u8(1).unwrap();
+            buf.write_all(&[wear; 4]).unwrap();
+            // Actual compound C3 or C2 for the soft and medium.
+            buf.write_u8(car.compound + 2).unwrap();
+            buf.write_u8(car.compound).unwrap();
+            buf.write_all(&[wear; 4]).unwrap();
+            // No wing, engine or gearbox damage, green flag.
+            buf.write_all(&[0; 5]).unwrap();
+            buf.write_i8(1).unwrap();
+            buf.write_f32::<LittleEndian>(4_000_000.0).unwrap();
+            buf.write_u8(1).unwrap();
+            write_f32s(&mut buf, &[0.0; 3]);
+        }
+
+        Simulator::finish(PacketType::CarStatus, buf)
+    }
+}
+
+fn new_car() -> Car {
+    Car {
+        pace: 1.0,
+        lap_pace: 1.0,
+        distance: 0.0,
+        speed: 0.0,
+        acceleration: 0.0,
+        current_lap_time: 0.0,
+        last_lap_time: 0.0,
+        best_lap_time: 0.0,
+        sector_1_time: 0.0,
+        sector_2_time: 0.0,
+        grid_position: 0,
+        position: 0,
+        pit_lap: 0,
+        pit: PitPhase::None,
+        compound: COMPOUND_SOFT,
+        tyre_wear: 0.0,
+        finished: false,
+    }
+}
+
+fn write_f32s(buf: &mut Vec<u8>, values: &[f32]) {
+    for value in values {
+        buf.write_f32::<LittleEndian>(*value).unwrap();
+    }
+}
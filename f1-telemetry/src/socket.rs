@@ -0,0 +1,83 @@
+use socket2::{Domain, Protocol, SockAddr, Socket, Type};
+use std::io;
+use std::net::{Ipv4Addr, ToSocketAddrs, UdpSocket};
+
+/// Options of the socket a [`Stream`] listens on, so that several tools on the
+/// same machine can receive the game's telemetry at once.
+///
+/// The game can send its packets to a broadcast address, or to a multicast group
+/// when relayed; every socket bound with `reuse_address` then gets them all. A
+/// unicast packet is still only received by one of the sockets.
+///
+/// [`Stream`]: ../struct.Stream.html
+#[derive(Debug, Clone, Default)]
+pub struct SocketOptions {
+    reuse_address: bool,
+    broadcast: bool,
+    recv_buffer_size: Option<usize>,
+    multicast_v4: Vec<(Ipv4Addr, Ipv4Addr)>,
+}
+
+impl SocketOptions {
+    pub fn new() -> SocketOptions {
+        SocketOptions::default()
+    }
+
+    /// Allow other sockets to bind to the same address (`SO_REUSEADDR`).
+    pub fn reuse_address(mut self, reuse_address: bool) -> SocketOptions {
+        self.reuse_address = reuse_address;
+        self
+    }
+
+    /// Receive packets sent to a broadcast address (`SO_BROADCAST`).
+    pub fn broadcast(mut self, broadcast: bool) -> SocketOptions {
+        self.broadcast = broadcast;
+        self
+    }
+
+    /// Size of the socket receive buffer (`SO_RCVBUF`), which holds the packets
+    /// until they are read. A larger one avoids dropping packets when they are
+    /// read late, e.g. by a busy application.
+    pub fn recv_buffer_size(mut self, size: usize) -> SocketOptions {
+        self.recv_buffer_size = Some(size);
+        self
+    }
+
+    /// Join a multicast group on the given interface, `0.0.0.0` letting the
+    /// system pick it.
+    pub fn join_multicast_v4(mut self, group: Ipv4Addr, interface: Ipv4Addr) -> SocketOptions {
+        self.multicast_v4.push((group, interface));
+        self
+    }
+
+    pub(crate) fn bind<T: ToSocketAddrs>(&self, addr: T) -> io::Result<UdpSocket> {
+        let mut last_error = None;
+
+        for addr in addr.to_socket_addrs()? {
+            let socket = Socket::new(Domain::for_address(addr), Type::DGRAM, Some(Protocol::UDP))?;
+            socket.set_reuse_address(self.reuse_address)?;
+            socket.set_broadcast(self.broadcast)?;
+            if let Some(size) = self.recv_buffer_size {
+                socket.set_recv_buffer_size(size)?;
+            }
+
+            match socket.bind(&SockAddr::from(addr)) {
+                Ok(()) => {
+                    for (group, interface) in &self.multicast_v4 {
+                        socket.join_multicast_v4(group, interface)?;
+                    }
+
+                    return Ok(socket.into());
+                }
+                Err(e) => last_error = Some(e),
+            }
+        }
+
+        Err(last_error.unwrap_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "could not resolve to any addresses",
+            )
+        }))
+    }
+}
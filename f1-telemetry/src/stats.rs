@@ -0,0 +1,168 @@
+use getset::CopyGetters;
+use std::collections::VecDeque;
+use std::convert::TryFrom;
+use std::time::{Duration, Instant};
+
+use crate::packet::PacketType;
+
+/// Window over which the rates are computed.
+const WINDOW: Duration = Duration::from_secs(1);
+
+/// Connection health of a [`Stream`], as returned by [`Stream::stats`].
+///
+/// The rates are computed over the last second; the counts are totals since the
+/// stream was created. All received datagrams are counted, including those
+/// skipped by the filter.
+///
+/// ## Specification
+/// ```text
+/// packets:            Number of datagrams received
+/// bytes:              Number of bytes received
+/// filtered:           Number of datagrams skipped by the filter
+/// errors:             Number of datagrams that were truncated or could not be parsed
+/// invalid:            Number of packets with impossible values, when validated
+/// duplicates:         Number of duplicate datagrams, when sequenced
+/// out_of_order:       Number of datagrams older than the previous one of their type,
+///                     when sequenced
+/// packets_per_second: Datagrams received per second
+/// bytes_per_second:   Bytes received per second
+/// last_packet_age:    Time since the last datagram was received, if any was
+/// ```
+///
+/// [`Stream`]: ../struct.Stream.html
+/// [`Stream::stats`]: ../struct.Stream.html#method.stats
+#[derive(Debug, Clone, CopyGetters)]
+pub struct StreamStats {
+    #[getset(get_copy = "pub")]
+    packets: u64,
+    #[getset(get_copy = "pub")]
+    bytes: u64,
+    #[getset(get_copy = "pub")]
+    filtered: u64,
+    #[getset(get_copy = "pub")]
+    errors: u64,
+    #[getset(get_copy = "pub")]
+    invalid: u64,
+    #[getset(get_copy = "pub")]
+    duplicates: u64,
+    #[getset(get_copy = "pub")]
+    out_of_order: u64,
+    #[getset(get_copy = "pub")]
+    packets_per_second: f32,
+    #[getset(get_copy = "pub")]
+    bytes_per_second: f32,
+    #[getset(get_copy = "pub")]
+    last_packet_age: Option<Duration>,
+    rates: [f32; 32],
+}
+
+impl StreamStats {
+    /// Packets of a type received per second.
+    pub fn rate(&self, packet_type: PacketType) -> f32 {
+        self.rates[packet_type.id() as usize]
+    }
+
+    /// Rate of every packet type received during the last second.
+    pub fn rates(&self) -> Vec<(PacketType, f32)> {
+        self.rates
+            .iter()
+            .enumerate()
+            .filter(|(_, &rate)| rate > 0.0)
+            .filter_map(|(id, &rate)| PacketType::try_from(id as u8).ok().map(|t| (t, rate)))
+            .collect()
+    }
+
+    /// Whether no datagram was received for `timeout`, e.g. because the game was
+    /// paused or closed.
+    pub fn is_stale(&self, timeout: Duration) -> bool {
+        match self.last_packet_age {
+            Some(age) => age > timeout,
+            None => true,
+        }
+    }
+}
+
+/// Counts the datagrams received by a stream.
+#[derive(Debug, Default)]
+pub(crate) struct StatsRecorder {
+    window: VecDeque<(Instant, Option<u8>, usize)>,
+    packets: u64,
+    bytes: u64,
+    filtered: u64,
+    errors: u64,
+    invalid: u64,
+    duplicates: u64,
+    out_of_order: u64,
+    last_packet: Option<Instant>,
+}
+
+impl StatsRecorder {
+    pub(crate) fn record(&mut self, packet_id: Option<u8>, size: usize) {
+        let now = Instant::now();
+        self.prune(now);
+
+        self.window.push_back((now, packet_id, size));
+        self.packets += 1;
+        self.bytes += size as u64;
+        self.last_packet = Some(now);
+    }
+
+    pub(crate) fn record_filtered(&mut self) {
+        self.filtered += 1;
+    }
+
+    pub(crate) fn record_error(&mut self) {
+        self.errors += 1;
+    }
+
+    pub(crate) fn record_invalid(&mut self) {
+        self.invalid += 1;
+    }
+
+    pub(crate) fn record_duplicate(&mut self) {
+        self.duplicates += 1;
+    }
+
+    pub(crate) fn record_out_of_order(&mut self) {
+        self.out_of_order += 1;
+    }
+
+    pub(crate) fn snapshot(&mut self) -> StreamStats {
+        let now = Instant::now();
+        self.prune(now);
+
+        let seconds = WINDOW.as_secs_f32();
+        let mut rates = [0.0; 32];
+        let mut bytes = 0;
+        for &(_, packet_id, size) in &self.window {
+            if let Some(id) = packet_id.filter(|&id| id < 32) {
+                rates[id as usize] += 1.0 / seconds;
+            }
+            bytes += size;
+        }
+
+        StreamStats {
+            packets: self.packets,
+            bytes: self.bytes,
+            filtered: self.filtered,
+            errors: self.errors,
+            invalid: self.invalid,
+            duplicates: self.duplicates,
+            out_of_order: self.out_of_order,
+            packets_per_second: self.window.len() as f32 / seconds,
+            bytes_per_second: bytes as f32 / seconds,
+            last_packet_age: self.last_packet.map(|t| now.duration_since(t)),
+            rates,
+        }
+    }
+
+    fn prune(&mut self, now: Instant) {
+        while let Some(&(t, _, _)) = self.window.front() {
+            if now.duration_since(t) > WINDOW {
+                self.window.pop_front();
+            } else {
+                break;
+            }
+        }
+    }
+}
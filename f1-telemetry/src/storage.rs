@@ -0,0 +1,4 @@
+//! Persistent storage of recorded sessions.
+
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
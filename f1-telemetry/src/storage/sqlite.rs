@@ -0,0 +1,528 @@
+//! Local history of sessions in a SQLite database.
+//!
+//! [`SqliteStore`] keeps the sessions, laps, stints, (downsampled) telemetry
+//! and final classifications of every session in a single database file,
+//! created on first use.
+//!
+//! Enabled with the `sqlite` feature.
+//!
+//! ## Schema
+//! ```text
+//! sessions:   session_uid INTEGER PRIMARY KEY  Unique identifier of the session
+//!             track TEXT                       Name of the track
+//!             session_type TEXT                Name of the session type
+//!             total_laps INTEGER               Laps in the race
+//!             track_length INTEGER             Length of the track in metres
+//!             recorded_at INTEGER              Unix timestamp of the first save
+//!
+//! laps:       session_uid INTEGER              Session of the lap
+//!             vehicle_idx INTEGER              Index of the car
+//!             lap INTEGER                      Lap number
+//!             lap_time REAL                    Lap time in seconds
+//!             sector_1_time REAL               Sector 1 time in seconds
+//!             sector_2_time REAL               Sector 2 time in seconds
+//!             sector_3_time REAL               Sector 3 time in seconds
+//!             valid INTEGER                    Whether the lap was valid (0 or 1)
+//!             PRIMARY KEY (session_uid, vehicle_idx, lap)
+//!
+//! stints:     session_uid INTEGER              Session of the stint
+//!             vehicle_idx INTEGER              Index of the car
+//!             stint INTEGER                    Stint number, from 1
+//!             start_lap INTEGER                First lap of the stint
+//!             end_lap INTEGER NULL             Lap the car pitted on, NULL if running
+//!             compound TEXT NULL               Visual tyre compound, if known
+//!             PRIMARY KEY (session_uid, vehicle_idx, stint)
+//!
+//! telemetry:  session_uid INTEGER              Session of the sample
+//!             vehicle_idx INTEGER              Index of the car
+//!             session_time REAL                Session timestamp in seconds
+//!             lap INTEGER                      Lap number
+//!             lap_distance REAL                Distance around the lap in metres
+//!             speed REAL                       Speed in kilometres per hour
+//!             throttle REAL                    Throttle (0.0 to 1.0)
+//!             brake REAL                       Brake (0.0 to 1.0)
+//!             steer REAL                       Steering (-1.0 to 1.0)
+//!             gear INTEGER                     Gear (1-8, N=0, R=-1)
+//!             engine_rpm REAL                  Engine RPM
+//!             drs INTEGER                      Whether DRS is open (0 or 1)
+//!             x, y, z REAL                     World space position
+//!
+//! results:    session_uid INTEGER              Session of the classification
+//!             vehicle_idx INTEGER              Index of the car
+//!             position INTEGER                 Finishing position
+//!             driver TEXT                      Name of the driver
+//!             team INTEGER NULL                Team identifier, if known
+//!             grid_position INTEGER            Grid position
+//!             laps INTEGER                     Laps completed
+//!             result_status INTEGER            Result status, as sent from F1 2021
+//!             race_time REAL                   Race time in seconds, penalties included
+//!             best_lap_time REAL NULL          Best lap time in seconds, if set
+//!             fastest_lap INTEGER              Whether it is the fastest lap (0 or 1)
+//!             pit_stops INTEGER                Number of pit stops
+//!             penalties_time INTEGER           Time penalties in seconds
+//!             penalties INTEGER                Number of penalties
+//!             points INTEGER                   Points given by the game
+//!             PRIMARY KEY (session_uid, vehicle_idx)
+//! ```
+//!
+//! [`SqliteStore`]: ./struct.SqliteStore.html
+
+use getset::{CopyGetters, Getters};
+use rusqlite::{params, Connection, Result};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::export::results::{ClassifiedDriver, Gap, SessionResults};
+use crate::packet::header::SessionIdentifier;
+use crate::packet::lap::ResultStatus;
+use crate::packet::participants::Team;
+use crate::packet::session::PacketSessionData;
+use crate::session::{LapRecord, PitStop};
+use crate::transform::sample::TelemetrySample;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS sessions (
+    session_uid INTEGER PRIMARY KEY,
+    track TEXT NOT NULL,
+    session_type TEXT NOT NULL,
+    total_laps INTEGER NOT NULL,
+    track_length INTEGER NOT NULL,
+    recorded_at INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS laps (
+    session_uid INTEGER NOT NULL,
+    vehicle_idx INTEGER NOT NULL,
+    lap INTEGER NOT NULL,
+    lap_time REAL NOT NULL,
+    sector_1_time REAL NOT NULL,
+    sector_2_time REAL NOT NULL,
+    sector_3_time REAL NOT NULL,
+    valid INTEGER NOT NULL,
+    PRIMARY KEY (session_uid, vehicle_idx, lap)
+);
+CREATE TABLE IF NOT EXISTS stints (
+    session_uid INTEGER NOT NULL,
+    vehicle_idx INTEGER NOT NULL,
+    stint INTEGER NOT NULL,
+    start_lap INTEGER NOT NULL,
+    end_lap INTEGER,
+    compound TEXT,
+    PRIMARY KEY (session_uid, vehicle_idx, stint)
+);
+CREATE TABLE IF NOT EXISTS telemetry (
+    session_uid INTEGER NOT NULL,
+    vehicle_idx INTEGER NOT NULL,
+    session_time REAL NOT NULL,
+    lap INTEGER NOT NULL,
+    lap_distance REAL NOT NULL,
+    speed REAL NOT NULL,
+    throttle REAL NOT NULL,
+    brake REAL NOT NULL,
+    steer REAL NOT NULL,
+    gear INTEGER NOT NULL,
+    engine_rpm REAL NOT NULL,
+    drs INTEGER NOT NULL,
+    x REAL NOT NULL,
+    y REAL NOT NULL,
+    z REAL NOT NULL
+);
+CREATE INDEX IF NOT EXISTS telemetry_lap ON telemetry (session_uid, vehicle_idx, lap);
+CREATE TABLE IF NOT EXISTS results (
+    session_uid INTEGER NOT NULL,
+    vehicle_idx INTEGER NOT NULL,
+    position INTEGER NOT NULL,
+    driver TEXT NOT NULL,
+    team INTEGER,
+    grid_position INTEGER NOT NULL,
+    laps INTEGER NOT NULL,
+    result_status INTEGER NOT NULL,
+    race_time REAL NOT NULL,
+    best_lap_time REAL,
+    fastest_lap INTEGER NOT NULL,
+    pit_stops INTEGER NOT NULL,
+    penalties_time INTEGER NOT NULL,
+    penalties INTEGER NOT NULL,
+    points INTEGER NOT NULL,
+    PRIMARY KEY (session_uid, vehicle_idx)
+);
+";
+
+/// Packet format the result statuses are stored in.
+const RESULT_STATUS_FORMAT: u16 = 2021;
+
+/// A session saved in the store.
+#[derive(Debug, Clone, PartialEq, Getters, CopyGetters)]
+pub struct StoredSession {
+    #[getset(get_copy = "pub")]
+    session_id: SessionIdentifier,
+    #[getset(get = "pub")]
+    track: String,
+    #[getset(get = "pub")]
+    session_type: String,
+    #[getset(get_copy = "pub")]
+    total_laps: u8,
+    #[getset(get_copy = "pub")]
+    track_length: u16,
+    #[getset(get_copy = "pub")]
+    recorded_at: i64,
+}
+
+/// A lap saved in the store.
+#[derive(Debug, Clone, Copy, PartialEq, CopyGetters)]
+#[getset(get_copy = "pub")]
+pub struct StoredLap {
+    vehicle_idx: u8,
+    lap: u8,
+    lap_time: f32,
+    sector_1_time: f32,
+    sector_2_time: f32,
+    sector_3_time: f32,
+    valid: bool,
+}
+
+/// A stint on one set of tyres saved in the store.
+#[derive(Debug, Clone, PartialEq, Getters, CopyGetters)]
+pub struct StoredStint {
+    #[getset(get_copy = "pub")]
+    vehicle_idx: u8,
+    #[getset(get_copy = "pub")]
+    stint: u8,
+    #[getset(get_copy = "pub")]
+    start_lap: u8,
+    #[getset(get_copy = "pub")]
+    end_lap: Option<u8>,
+    #[getset(get = "pub")]
+    compound: Option<String>,
+}
+
+/// Sessions, laps, stints and telemetry stored in a SQLite database.
+pub struct SqliteStore {
+    connection: Connection,
+}
+
+impl SqliteStore {
+    /// Open the database at `path`, creating it and its tables if needed.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<SqliteStore> {
+        SqliteStore::from_connection(Connection::open(path)?)
+    }
+
+    /// Database kept in memory, lost when the store is dropped.
+    pub fn open_in_memory() -> Result<SqliteStore> {
+        SqliteStore::from_connection(Connection::open_in_memory()?)
+    }
+
+    fn from_connection(connection: Connection) -> Result<SqliteStore> {
+        connection.execute_batch(SCHEMA)?;
+        Ok(SqliteStore { connection })
+    }
+
+    pub fn connection(&self) -> &Connection {
+        &self.connection
+    }
+
+    /// Save the details of a session, keeping the time it was first recorded.
+    pub fn save_session(&self, packet: &PacketSessionData) -> Result<()> {
+        let recorded_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or(0);
+
+        self.connection.execute(
+            "INSERT INTO sessions (session_uid, track, session_type, total_laps, track_length, recorded_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+             ON CONFLICT (session_uid) DO UPDATE SET
+                track = excluded.track,
+                session_type = excluded.session_type,
+                total_laps = excluded.total_laps,
+                track_length = excluded.track_length",
+            params![
+                uid(packet.header().session_id()),
+                packet.track().name(),
+                packet.session_type().name(),
+                packet.total_laps(),
+                packet.track_length(),
+                recorded_at,
+            ],
+        )?;
+
+        Ok(())
+    }
+
+    /// Save a lap of a car, replacing it if it was already saved.
+    pub fn save_lap(
+        &self,
+        session: SessionIdentifier,
+        vehicle_idx: u8,
+        lap: &LapRecord,
+    ) -> Result<()> {
+        self.connection.execute(
+            "INSERT OR REPLACE INTO laps
+             (session_uid, vehicle_idx, lap, lap_time, sector_1_time, sector_2_time, sector_3_time, valid)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                uid(session),
+                vehicle_idx,
+                lap.lap(),
+                lap.lap_time(),
+                lap.sector_1_time(),
+                lap.sector_2_time(),
+                lap.sector_3_time(),
+                lap.valid(),
+            ],
+        )?;
+
+        Ok(())
+    }
+
+    /// Save the stints of a car, split by its pit stops, replacing the ones
+    /// already saved. Every stop is assumed to change the tyres.
+    pub fn save_stints(
+        &mut self,
+        session: SessionIdentifier,
+        vehicle_idx: u8,
+        pit_stops: &[PitStop],
+    ) -> Result<()> {
+        let tx = self.connection.transaction()?;
+
+        tx.execute(
+            "DELETE FROM stints WHERE session_uid = ?1 AND vehicle_idx = ?2",
+            params![uid(session), vehicle_idx],
+        )?;
+
+        let mut start_lap = 1;
+        let mut compound = pit_stops
+            .first()
+            .and_then(|s| s.compound_before())
+            .map(|c| format!("{:?}", c));
+
+        for (i, stop) in pit_stops.iter().enumerate() {
+            tx.execute(
+                "INSERT INTO stints (session_uid, vehicle_idx, stint, start_lap, end_lap, compound)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    uid(session),
+                    vehicle_idx,
+                    i as u8 + 1,
+                    start_lap,
+                    Some(stop.lap()),
+                    compound,
+                ],
+            )?;
+
+            start_lap = stop.lap();
+            compound = stop.compound_after().map(|c| format!("{:?}", c));
+        }
+
+        tx.execute(
+            "INSERT INTO stints (session_uid, vehicle_idx, stint, start_lap, end_lap, compound)
+             VALUES (?1, ?2, ?3, ?4, NULL, ?5)",
+            params![
+                uid(session),
+                vehicle_idx,
+                pit_stops.len() as u8 + 1,
+                start_lap,
+                compound,
+            ],
+        )?;
+
+        tx.commit()
+    }
+
+    /// Save telemetry samples of a car, downsampled beforehand to keep the
+    /// database small.
+    pub fn save_telemetry(
+        &mut self,
+        session: SessionIdentifier,
+        vehicle_idx: u8,
+        samples: &[TelemetrySample],
+    ) -> Result<()> {
+        let tx = self.connection.transaction()?;
+
+        {
+            let mut statement = tx.prepare(
+                "INSERT INTO telemetry
+                 (session_uid, vehicle_idx, session_time, lap, lap_distance, speed, throttle,
+                  brake, steer, gear, engine_rpm, drs, x, y, z)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)",
+            )?;
+
+            for s in samples {
+                let position = s.world_position();
+                statement.execute(params![
+                    uid(session),
+                    vehicle_idx,
+                    s.session_time(),
+                    s.lap(),
+                    s.lap_distance(),
+                    s.speed(),
+                    s.throttle(),
+                    s.brake(),
+                    s.steer(),
+                    s.gear(),
+                    s.engine_rpm(),
+                    s.drs(),
+                    position.x(),
+                    position.y(),
+                    position.z(),
+                ])?;
+            }
+        }
+
+        tx.commit()
+    }
+
+    /// Save the final classification of a session, replacing the one already
+    /// saved.
+    pub fn save_results(
+        &mut self,
+        session: SessionIdentifier,
+        results: &SessionResults,
+    ) -> Result<()> {
+        let tx = self.connection.transaction()?;
+
+        tx.execute(
+            "DELETE FROM results WHERE session_uid = ?1",
+            params![uid(session)],
+        )?;
+
+        {
+            let mut statement = tx.prepare(
+                "INSERT INTO results
+                 (session_uid, vehicle_idx, position, driver, team, grid_position, laps,
+                  result_status, race_time, best_lap_time, fastest_lap, pit_stops,
+                  penalties_time, penalties, points)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)",
+            )?;
+
+            for d in results.drivers() {
+                statement.execute(params![
+                    uid(session),
+                    d.vehicle_index,
+                    d.position,
+                    d.name,
+                    d.team.map(u8::from),
+                    d.grid_position,
+                    d.num_laps,
+                    d.result_status.to_format(RESULT_STATUS_FORMAT),
+                    d.race_time,
+                    d.best_lap_time,
+                    d.fastest_lap,
+                    d.num_pit_stops,
+                    d.penalties_time,
+                    d.num_penalties,
+                    d.points,
+                ])?;
+            }
+        }
+
+        tx.commit()
+    }
+
+    /// The saved final classification of a session, if any.
+    pub fn results_for_session(
+        &self,
+        session: SessionIdentifier,
+    ) -> Result<Option<SessionResults>> {
+        let mut statement = self.connection.prepare(
+            "SELECT vehicle_idx, position, driver, team, grid_position, laps, result_status,
+                    race_time, best_lap_time, fastest_lap, pit_stops, penalties_time,
+                    penalties, points
+             FROM results WHERE session_uid = ?1 ORDER BY position",
+        )?;
+
+        let rows = statement.query_map(params![uid(session)], |row| {
+            Ok(ClassifiedDriver {
+                vehicle_index: row.get(0)?,
+                position: row.get(1)?,
+                name: row.get(2)?,
+                team: row.get::<_, Option<u8>>(3)?.map(Team::from),
+                grid_position: row.get(4)?,
+                num_laps: row.get(5)?,
+                result_status: ResultStatus::from_format(row.get(6)?, RESULT_STATUS_FORMAT),
+                race_time: row.get(7)?,
+                gap: Gap::Leader,
+                best_lap_time: row.get(8)?,
+                fastest_lap: row.get(9)?,
+                num_pit_stops: row.get(10)?,
+                penalties_time: row.get(11)?,
+                num_penalties: row.get(12)?,
+                points: row.get(13)?,
+            })
+        })?;
+
+        let drivers = rows.collect::<Result<Vec<ClassifiedDriver>>>()?;
+        if drivers.is_empty() {
+            return Ok(None);
+        }
+
+        Ok(Some(SessionResults::from_drivers(drivers)))
+    }
+
+    /// All the saved sessions, most recent first.
+    pub fn sessions(&self) -> Result<Vec<StoredSession>> {
+        let mut statement = self.connection.prepare(
+            "SELECT session_uid, track, session_type, total_laps, track_length, recorded_at
+             FROM sessions ORDER BY recorded_at DESC",
+        )?;
+
+        let rows = statement.query_map(params![], |row| {
+            Ok(StoredSession {
+                session_id: SessionIdentifier::new(row.get::<_, i64>(0)? as u64),
+                track: row.get(1)?,
+                session_type: row.get(2)?,
+                total_laps: row.get(3)?,
+                track_length: row.get(4)?,
+                recorded_at: row.get(5)?,
+            })
+        })?;
+
+        rows.collect()
+    }
+
+    /// All the saved laps of a session, ordered by car and lap number.
+    pub fn laps_for_session(&self, session: SessionIdentifier) -> Result<Vec<StoredLap>> {
+        let mut statement = self.connection.prepare(
+            "SELECT vehicle_idx, lap, lap_time, sector_1_time, sector_2_time, sector_3_time, valid
+             FROM laps WHERE session_uid = ?1 ORDER BY vehicle_idx, lap",
+        )?;
+
+        let rows = statement.query_map(params![uid(session)], |row| {
+            Ok(StoredLap {
+                vehicle_idx: row.get(0)?,
+                lap: row.get(1)?,
+                lap_time: row.get(2)?,
+                sector_1_time: row.get(3)?,
+                sector_2_time: row.get(4)?,
+                sector_3_time: row.get(5)?,
+                valid: row.get(6)?,
+            })
+        })?;
+
+        rows.collect()
+    }
+
+    /// All the saved stints of a session, ordered by car and stint number.
+    pub fn stints_for_session(&self, session: SessionIdentifier) -> Result<Vec<StoredStint>> {
+        let mut statement = self.connection.prepare(
+            "SELECT vehicle_idx, stint, start_lap, end_lap, compound
+             FROM stints WHERE session_uid = ?1 ORDER BY vehicle_idx, stint",
+        )?;
+
+        let rows = statement.query_map(params![uid(session)], |row| {
+            Ok(StoredStint {
+                vehicle_idx: row.get(0)?,
+                stint: row.get(1)?,
+                start_lap: row.get(2)?,
+                end_lap: row.get(3)?,
+                compound: row.get(4)?,
+            })
+        })?;
+
+        rows.collect()
+    }
+}
+
+/// SQLite integers are signed, the session identifier is stored with the same bits.
+fn uid(session: SessionIdentifier) -> i64 {
+    session.value() as i64
+}
@@ -0,0 +1,626 @@
+//! Race strategy simulation.
+//!
+//! [`StrategyTracker`] follows a car through a race and measures its tyre
+//! wear rate, fuel consumption and lap time trend on the current stint.
+//! [`StrategySimulator`] turns this [`RaceState`] into lap times for the rest
+//! of the race on each dry compound, and ranks the pit strategies finishing
+//! the race the soonest: no stop, one stop or two stops, with every choice of
+//! compounds and the best laps to stop on.
+//!
+//! [`StrategyTracker`]: ./struct.StrategyTracker.html
+//! [`StrategySimulator`]: ./struct.StrategySimulator.html
+//! [`RaceState`]: ./struct.RaceState.html
+
+use getset::CopyGetters;
+use std::fmt;
+
+use crate::packet::car_damage::PacketCarDamageData;
+use crate::packet::car_status::{PacketCarStatusData, TyreCompoundVisual};
+use crate::packet::header::SessionIdentifier;
+use crate::packet::lap::PacketLapData;
+use crate::packet::Packet;
+
+/// Time lost by a stop compared to driving past the pit lane, in seconds.
+pub const DEFAULT_PIT_LOSS: f32 = 22.0;
+/// Lap time per kilogram of fuel, in seconds.
+const FUEL_EFFECT: f32 = 0.03;
+/// Wear beyond which a set of tyres is considered unsafe, in percent.
+const MAX_WEAR: f32 = 75.0;
+/// Bounds of the ratio between the measured and the default wear or
+/// degradation, so that a few noisy laps cannot make a compound absurd.
+const CALIBRATION_BOUNDS: (f32, f32) = (0.25, 4.0);
+/// Laps of a stint needed to measure the lap time trend.
+const MIN_TREND_LAPS: usize = 3;
+
+const DRY_COMPOUNDS: [TyreCompoundVisual; 3] = [
+    TyreCompoundVisual::Soft,
+    TyreCompoundVisual::Medium,
+    TyreCompoundVisual::Hard,
+];
+
+/// Pace and wear of a tyre compound.
+///
+/// ## Specification
+/// ```text
+/// compound:       Visual tyre compound
+/// pace_offset:    Lap time on new tyres compared to the medium in seconds
+/// wear_per_lap:   Tyre wear per lap in percent
+/// time_per_wear:  Lap time lost per percent of wear in seconds
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, CopyGetters)]
+#[getset(get_copy = "pub")]
+pub struct CompoundModel {
+    compound: TyreCompoundVisual,
+    pace_offset: f32,
+    wear_per_lap: f32,
+    time_per_wear: f32,
+}
+
+impl CompoundModel {
+    pub fn new(
+        compound: TyreCompoundVisual,
+        pace_offset: f32,
+        wear_per_lap: f32,
+        time_per_wear: f32,
+    ) -> CompoundModel {
+        CompoundModel {
+            compound,
+            pace_offset,
+            wear_per_lap,
+            time_per_wear,
+        }
+    }
+
+    /// Typical model of a dry compound, before calibration with the wear and
+    /// pace of the car.
+    pub fn default_for(compound: TyreCompoundVisual) -> Option<CompoundModel> {
+        match compound {
+            TyreCompoundVisual::Soft => Some(CompoundModel::new(compound, -0.6, 3.0, 0.045)),
+            TyreCompoundVisual::Medium => Some(CompoundModel::new(compound, 0.0, 2.0, 0.04)),
+            TyreCompoundVisual::Hard => Some(CompoundModel::new(compound, 0.4, 1.4, 0.035)),
+            _ => None,
+        }
+    }
+
+    fn scaled(self, wear_scale: f32, time_scale: f32) -> CompoundModel {
+        CompoundModel {
+            wear_per_lap: self.wear_per_lap * wear_scale,
+            time_per_wear: self.time_per_wear * time_scale,
+            ..self
+        }
+    }
+
+    fn lap_time(&self, wear: f32, fuel: f32) -> f32 {
+        self.pace_offset + self.time_per_wear * wear + FUEL_EFFECT * fuel
+    }
+}
+
+/// State of a car's race, from which the strategies are simulated.
+///
+/// ## Specification
+/// ```text
+/// current_lap:     Lap the car is on
+/// total_laps:      Laps of the race
+/// lap_time:        Current pace in seconds
+/// compound:        Visual compound of the tyres fitted
+/// compounds_used:  Visual compounds of the previous stints
+/// tyre_wear:       Wear of the most worn tyre in percent
+/// wear_per_lap:    Measured tyre wear per lap on the current stint in percent
+/// lap_time_trend:  Measured lap time change per lap on the current stint in seconds
+/// fuel:            Fuel in the tank in kilograms
+/// fuel_per_lap:    Fuel used per lap in kilograms
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct RaceState {
+    pub current_lap: u8,
+    pub total_laps: u8,
+    pub lap_time: f32,
+    pub compound: TyreCompoundVisual,
+    pub compounds_used: Vec<TyreCompoundVisual>,
+    pub tyre_wear: f32,
+    pub wear_per_lap: Option<f32>,
+    pub lap_time_trend: Option<f32>,
+    pub fuel: f32,
+    pub fuel_per_lap: f32,
+}
+
+impl RaceState {
+    /// Laps left to drive, including the current one.
+    pub fn laps_remaining(&self) -> u8 {
+        (self.total_laps + 1).saturating_sub(self.current_lap)
+    }
+}
+
+/// A pit stop at the end of a lap, fitting another compound.
+#[derive(Debug, Clone, Copy, PartialEq, CopyGetters)]
+#[getset(get_copy = "pub")]
+pub struct PlannedStop {
+    lap: u8,
+    compound: TyreCompoundVisual,
+}
+
+impl PlannedStop {
+    pub fn new(lap: u8, compound: TyreCompoundVisual) -> PlannedStop {
+        PlannedStop { lap, compound }
+    }
+}
+
+/// The pit stops planned for the rest of a race.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Strategy {
+    stops: Vec<PlannedStop>,
+}
+
+impl Strategy {
+    /// Stay out until the end of the race.
+    pub fn no_stop() -> Strategy {
+        Strategy::default()
+    }
+
+    pub fn with_stop(mut self, lap: u8, compound: TyreCompoundVisual) -> Strategy {
+        self.stops.push(PlannedStop::new(lap, compound));
+        self
+    }
+
+    pub fn stops(&self) -> &[PlannedStop] {
+        &self.stops
+    }
+}
+
+impl fmt::Display for Strategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.stops.is_empty() {
+            return write!(f, "No stop");
+        }
+
+        for (i, stop) in self.stops.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{:?} on lap {}", stop.compound, stop.lap)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Result of a simulated strategy.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StrategyOutcome {
+    strategy: Strategy,
+    race_time: f32,
+    max_wear: f32,
+}
+
+impl StrategyOutcome {
+    pub fn strategy(&self) -> &Strategy {
+        &self.strategy
+    }
+
+    /// Time to the end of the race, from the start of the current lap, in seconds.
+    pub fn race_time(&self) -> f32 {
+        self.race_time
+    }
+
+    /// Highest tyre wear reached, in percent.
+    pub fn max_wear(&self) -> f32 {
+        self.max_wear
+    }
+}
+
+/// Simulates the rest of a race for pit strategies.
+///
+/// The dry compounds are modelled with typical values, scaled to the wear rate
+/// and degradation measured on the current stint. Strategies running tyres
+/// beyond 75% wear are rejected, as are dry races not using two different
+/// dry compounds.
+#[derive(Debug, Clone)]
+pub struct StrategySimulator {
+    state: RaceState,
+    compounds: Vec<CompoundModel>,
+    pit_loss: f32,
+    max_stops: usize,
+}
+
+impl StrategySimulator {
+    pub fn new(state: RaceState) -> StrategySimulator {
+        StrategySimulator {
+            state,
+            compounds: DRY_COMPOUNDS
+                .iter()
+                .filter_map(|c| CompoundModel::default_for(*c))
+                .collect(),
+            pit_loss: DEFAULT_PIT_LOSS,
+            max_stops: 2,
+        }
+    }
+
+    /// Time lost by a stop compared to driving past the pit lane, in seconds.
+    pub fn with_pit_loss(mut self, pit_loss: f32) -> StrategySimulator {
+        self.pit_loss = pit_loss;
+        self
+    }
+
+    /// Replace the uncalibrated model of a compound.
+    pub fn with_compound(mut self, model: CompoundModel) -> StrategySimulator {
+        self.compounds.retain(|c| c.compound != model.compound);
+        self.compounds.push(model);
+        self
+    }
+
+    /// Only rank strategies with up to this number of stops.
+    pub fn with_max_stops(mut self, max_stops: usize) -> StrategySimulator {
+        self.max_stops = max_stops;
+        self
+    }
+
+    pub fn state(&self) -> &RaceState {
+        &self.state
+    }
+
+    /// Simulate a strategy, or `None` when it cannot be run: a stop outside of
+    /// the race, worn out tyres or a single dry compound.
+    pub fn simulate(&self, strategy: &Strategy) -> Option<StrategyOutcome> {
+        let models = self.calibrated();
+        self.simulate_with(&models, strategy)
+    }
+
+    /// The fastest strategy for each sequence of compounds, fastest first.
+    pub fn rank(&self) -> Vec<StrategyOutcome> {
+        let models = self.calibrated();
+        let state = &self.state;
+        let compounds: Vec<TyreCompoundVisual> = models.iter().map(|m| m.compound).collect();
+        // Stops on the last lap are pointless.
+        let stop_laps = state.current_lap..state.total_laps;
+
+        let mut outcomes: Vec<StrategyOutcome> = Vec::new();
+        let keep = |outcome: Option<StrategyOutcome>, best: &mut Option<StrategyOutcome>| {
+            if let Some(outcome) = outcome {
+                if best
+                    .as_ref()
+                    .is_none_or(|b| outcome.race_time < b.race_time)
+                {
+                    *best = Some(outcome);
+                }
+            }
+        };
+
+        let mut best = None;
+        keep(self.simulate_with(&models, &Strategy::no_stop()), &mut best);
+        outcomes.extend(best);
+
+        if self.max_stops >= 1 {
+            for &first in &compounds {
+                let mut best = None;
+                for lap in stop_laps.clone() {
+                    let strategy = Strategy::no_stop().with_stop(lap, first);
+                    keep(self.simulate_with(&models, &strategy), &mut best);
+                }
+                outcomes.extend(best);
+            }
+        }
+
+        if self.max_stops >= 2 {
+            for &first in &compounds {
+                for &second in &compounds {
+                    let mut best = None;
+                    for lap_1 in stop_laps.clone() {
+                        for lap_2 in lap_1 + 1..state.total_laps {
+                            let strategy = Strategy::no_stop()
+                                .with_stop(lap_1, first)
+                                .with_stop(lap_2, second);
+                            keep(self.simulate_with(&models, &strategy), &mut best);
+                        }
+                    }
+                    outcomes.extend(best);
+                }
+            }
+        }
+
+        outcomes.sort_by(|a, b| a.race_time.total_cmp(&b.race_time));
+        outcomes
+    }
+
+    /// The compound models scaled to the wear rate and lap time trend measured
+    /// on the current compound.
+    fn calibrated(&self) -> Vec<CompoundModel> {
+        let state = &self.state;
+        let current = match self.model(state.compound) {
+            Some(current) => current,
+            None => return self.compounds.clone(),
+        };
+
+        let (min, max) = CALIBRATION_BOUNDS;
+        let wear_per_lap = state.wear_per_lap.filter(|w| *w > 0.0);
+        let wear_scale = wear_per_lap
+            .map(|w| (w / current.wear_per_lap).clamp(min, max))
+            .unwrap_or(1.0);
+
+        // Burning fuel makes the car faster, which hides part of the degradation.
+        let time_scale = match (state.lap_time_trend, wear_per_lap) {
+            (Some(trend), Some(wear)) => {
+                let degradation = trend + FUEL_EFFECT * state.fuel_per_lap;
+                (degradation / wear / current.time_per_wear).clamp(min, max)
+            }
+            _ => 1.0,
+        };
+
+        self.compounds
+            .iter()
+            .map(|m| m.scaled(wear_scale, time_scale))
+            .collect()
+    }
+
+    fn model(&self, compound: TyreCompoundVisual) -> Option<CompoundModel> {
+        self.compounds
+            .iter()
+            .find(|m| m.compound == compound)
+            .copied()
+    }
+
+    fn simulate_with(
+        &self,
+        models: &[CompoundModel],
+        strategy: &Strategy,
+    ) -> Option<StrategyOutcome> {
+        let state = &self.state;
+        let find = |compound| models.iter().find(|m| m.compound == compound).copied();
+
+        let mut lap = state.current_lap;
+        for stop in &strategy.stops {
+            if stop.lap < lap || stop.lap >= state.total_laps {
+                return None;
+            }
+            lap = stop.lap + 1;
+        }
+
+        if DRY_COMPOUNDS.contains(&state.compound) {
+            let mut used: Vec<TyreCompoundVisual> = state.compounds_used.clone();
+            used.push(state.compound);
+            used.extend(strategy.stops.iter().map(|s| s.compound));
+            used.retain(|c| DRY_COMPOUNDS.contains(c));
+            used.sort_by_key(|c| format!("{:?}", c));
+            used.dedup();
+            if used.len() < 2 {
+                return None;
+            }
+        }
+
+        // Lap times are modelled relative to the current one, so that the
+        // pace of the car and the track cancel out.
+        let mut model = find(state.compound).unwrap_or_else(|| {
+            CompoundModel::new(state.compound, 0.0, state.wear_per_lap.unwrap_or(0.0), 0.0)
+        });
+        let reference = state.lap_time - model.lap_time(state.tyre_wear, state.fuel);
+
+        let mut stops = strategy.stops.iter().peekable();
+        let mut wear = state.tyre_wear;
+        let mut fuel = state.fuel;
+        let mut race_time = 0.0;
+        let mut max_wear = wear;
+
+        for lap in state.current_lap..=state.total_laps {
+            race_time += reference + model.lap_time(wear, fuel);
+            wear += model.wear_per_lap;
+            fuel = (fuel - state.fuel_per_lap).max(0.0);
+            max_wear = max_wear.max(wear);
+
+            if max_wear > MAX_WEAR {
+                return None;
+            }
+
+            if let Some(stop) = stops.next_if(|s| s.lap == lap) {
+                model = find(stop.compound)?;
+                wear = 0.0;
+                race_time += self.pit_loss;
+            }
+        }
+
+        Some(StrategyOutcome {
+            strategy: strategy.clone(),
+            race_time,
+            max_wear,
+        })
+    }
+}
+
+/// Tyres and fuel of the car at the start of a lap, and its lap time.
+#[derive(Debug, Clone, Copy)]
+struct StintLap {
+    lap_time: f32,
+    tyre_wear: f32,
+    fuel: f32,
+}
+
+/// Measures the state of a car's race from the Session, Lap Data, Car Status
+/// and Car Damage packets.
+///
+/// A new stint starts when the compound changes or the wear drops, e.g. after
+/// a stop fitting another set of the same compound.
+#[derive(Debug, Default)]
+pub struct StrategyTracker {
+    vehicle_idx: Option<u8>,
+    session: Option<SessionIdentifier>,
+    total_laps: Option<u8>,
+    current_lap: Option<u8>,
+    compound: Option<TyreCompoundVisual>,
+    compounds_used: Vec<TyreCompoundVisual>,
+    tyre_wear: f32,
+    fuel: f32,
+    lap_start: Option<(f32, f32)>,
+    stint: Vec<StintLap>,
+}
+
+impl StrategyTracker {
+    /// Tracker of the player's car.
+    pub fn new() -> StrategyTracker {
+        StrategyTracker::default()
+    }
+
+    /// Follow the car with the given index instead of the player's car.
+    pub fn with_vehicle(mut self, vehicle_idx: u8) -> StrategyTracker {
+        self.vehicle_idx = Some(vehicle_idx);
+        self
+    }
+
+    pub fn reset(&mut self) {
+        *self = StrategyTracker {
+            vehicle_idx: self.vehicle_idx,
+            ..StrategyTracker::default()
+        };
+    }
+
+    pub fn update(&mut self, packet: &Packet) {
+        match packet {
+            Packet::Session(p) => {
+                self.check_session(p.header().session_id());
+                self.total_laps = Some(p.total_laps());
+            }
+            Packet::Lap(p) => self.update_lap_data(p),
+            Packet::CarStatus(p) => self.update_car_status(p),
+            Packet::CarDamage(p) => self.update_car_damage(p),
+            _ => {}
+        }
+    }
+
+    pub fn update_lap_data(&mut self, packet: &PacketLapData) {
+        self.check_session(packet.header().session_id());
+        let idx = self.index(packet.header().player_car_index());
+        let ld = match packet.lap_data().get(idx) {
+            Some(ld) => ld,
+            None => return,
+        };
+
+        let lap = ld.current_lap_num();
+        if self.current_lap.is_some_and(|current| lap > current) {
+            if let Some((tyre_wear, fuel)) = self.lap_start {
+                if ld.last_lap_time() > 0.0 {
+                    self.stint.push(StintLap {
+                        lap_time: ld.last_lap_time(),
+                        tyre_wear,
+                        fuel,
+                    });
+                }
+            }
+        }
+
+        if self.current_lap != Some(lap) {
+            self.current_lap = Some(lap);
+            self.lap_start = Some((self.tyre_wear, self.fuel));
+        }
+    }
+
+    pub fn update_car_status(&mut self, packet: &PacketCarStatusData) {
+        self.check_session(packet.header().session_id());
+        let idx = self.index(packet.header().player_car_index());
+        let cs = match packet.car_status_data().get(idx) {
+            Some(cs) => cs,
+            None => return,
+        };
+
+        let compound = cs.visual_tyre_compound();
+        if self.compound.is_some_and(|c| c != compound) {
+            self.new_stint();
+        }
+        self.compound = Some(compound);
+        self.fuel = cs.fuel_in_tank();
+
+        // Formats before the Car Damage packet send the wear here.
+        let wear = cs.tyres_wear().to_array();
+        self.update_wear(wear.iter().map(|w| f32::from(*w)).fold(0.0, f32::max));
+    }
+
+    pub fn update_car_damage(&mut self, packet: &PacketCarDamageData) {
+        self.check_session(packet.header().session_id());
+        let idx = self.index(packet.header().player_car_index());
+        if let Some(cd) = packet.car_damage_data().get(idx) {
+            self.update_wear(
+                cd.tyres_wear()
+                    .to_array()
+                    .iter()
+                    .copied()
+                    .fold(0.0, f32::max),
+            );
+        }
+    }
+
+    /// State of the race, once the race length, the tyres and a lap time are known.
+    pub fn race_state(&self) -> Option<RaceState> {
+        let last = self.stint.last()?;
+        let laps = self.stint.len() as f32;
+        let first = self.stint.first()?;
+
+        let wear_per_lap =
+            (self.tyre_wear > first.tyre_wear).then(|| (self.tyre_wear - first.tyre_wear) / laps);
+        let fuel_per_lap = ((first.fuel - self.fuel) / laps).max(0.0);
+
+        let (lap_time, lap_time_trend) = if self.stint.len() >= MIN_TREND_LAPS {
+            let times: Vec<f32> = self.stint.iter().map(|l| l.lap_time).collect();
+            let (intercept, slope) = linear_fit(&times);
+            (intercept + slope * (laps - 1.0), Some(slope))
+        } else {
+            (last.lap_time, None)
+        };
+
+        Some(RaceState {
+            current_lap: self.current_lap?,
+            total_laps: self.total_laps?,
+            lap_time,
+            compound: self.compound?,
+            compounds_used: self.compounds_used.clone(),
+            tyre_wear: self.tyre_wear,
+            wear_per_lap,
+            lap_time_trend,
+            fuel: self.fuel,
+            fuel_per_lap,
+        })
+    }
+
+    fn index(&self, player_car_index: u8) -> usize {
+        self.vehicle_idx.unwrap_or(player_car_index) as usize
+    }
+
+    fn update_wear(&mut self, wear: f32) {
+        // New tyres of the same compound.
+        if wear + 5.0 < self.tyre_wear {
+            self.new_stint();
+        }
+        self.tyre_wear = wear;
+    }
+
+    fn new_stint(&mut self) {
+        if let Some(compound) = self.compound {
+            self.compounds_used.push(compound);
+        }
+        self.stint.clear();
+        self.lap_start = None;
+    }
+
+    fn check_session(&mut self, session: SessionIdentifier) {
+        if self.session != Some(session) {
+            self.reset();
+            self.session = Some(session);
+        }
+    }
+}
+
+/// Least squares line through the values, as `(intercept, slope)` over their index.
+fn linear_fit(values: &[f32]) -> (f32, f32) {
+    let n = values.len() as f32;
+    let mean_x = (n - 1.0) / 2.0;
+    let mean_y = values.iter().sum::<f32>() / n;
+
+    let (mut covariance, mut variance) = (0.0, 0.0);
+    for (x, y) in values.iter().enumerate() {
+        let dx = x as f32 - mean_x;
+        covariance += dx * (y - mean_y);
+        variance += dx * dx;
+    }
+
+    let slope = if variance > 0.0 {
+        covariance / variance
+    } else {
+        0.0
+    };
+    (mean_y - slope * mean_x, slope)
+}
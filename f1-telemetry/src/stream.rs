@@ -0,0 +1,334 @@
+use std::cell::{Cell, RefCell};
+use std::io::ErrorKind;
+use std::net::{ToSocketAddrs, UdpSocket};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use crate::packet::raw::RawPacket;
+use crate::packet::{
+    parse_packet_fuzz, peek_packet_id, Packet, PacketFilter, UnpackError, MAX_PACKET_SIZE,
+};
+use crate::redact::Redactor;
+use crate::sequence::{Sequence, Sequencer};
+use crate::socket::SocketOptions;
+use crate::stats::{StatsRecorder, StreamStats};
+use crate::validate::{Validator, Violation};
+use crate::watchdog::{Diagnostic, Watchdog};
+
+/// Largest payload a UDP datagram can carry.
+const MAX_DATAGRAM_SIZE: usize = 65507;
+
+/// How long a spawned stream waits for a packet before checking for shutdown.
+const SPAWN_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+#[derive(Debug)]
+pub enum StreamError {
+    /// The socket returned an error.
+    Io(std::io::Error),
+    /// The datagram filled the whole receive buffer and was most likely
+    /// truncated. The buffer has been grown for the next packets.
+    Truncated(usize),
+    /// The packet could not be parsed.
+    Unpack(UnpackError),
+    /// The watchdog detected too many errors, the game most likely sends a
+    /// format that is not supported.
+    Desync(Diagnostic),
+}
+
+impl From<UnpackError> for StreamError {
+    fn from(e: UnpackError) -> Self {
+        StreamError::Unpack(e)
+    }
+}
+
+pub struct Stream {
+    socket: UdpSocket,
+    buf: RefCell<Vec<u8>>,
+    filter: PacketFilter,
+    watchdog: RefCell<Option<Watchdog>>,
+    lenient: Cell<bool>,
+    stats: RefCell<StatsRecorder>,
+    redactor: RefCell<Option<Redactor>>,
+    sequencer: RefCell<Option<Sequencer>>,
+    validator: RefCell<Option<Validator>>,
+    violations: RefCell<Vec<Violation>>,
+}
+
+impl Stream {
+    pub fn new<T: ToSocketAddrs>(addr: T) -> std::io::Result<Stream> {
+        Stream::from_socket(UdpSocket::bind(addr)?)
+    }
+
+    /// Listen on `addr` with the given socket options, e.g. to share the
+    /// telemetry with other tools.
+    pub fn with_options<T: ToSocketAddrs>(
+        addr: T,
+        options: &SocketOptions,
+    ) -> std::io::Result<Stream> {
+        Stream::from_socket(options.bind(addr)?)
+    }
+
+    fn from_socket(socket: UdpSocket) -> std::io::Result<Stream> {
+        socket.set_nonblocking(true)?;
+
+        // One spare byte lets us tell a packet of the maximum size from a truncated one.
+        let buf = RefCell::new(vec![0; MAX_PACKET_SIZE + 1]);
+
+        Ok(Stream {
+            socket,
+            buf,
+            filter: PacketFilter::all(),
+            watchdog: RefCell::new(None),
+            lenient: Cell::new(false),
+            stats: RefCell::new(StatsRecorder::default()),
+            redactor: RefCell::new(None),
+            sequencer: RefCell::new(None),
+            validator: RefCell::new(None),
+            violations: RefCell::new(Vec::new()),
+        })
+    }
+
+    /// Watch the parse errors of this stream.
+    pub fn set_watchdog(&mut self, watchdog: Watchdog) {
+        self.watchdog = RefCell::new(Some(watchdog));
+    }
+
+    /// In lenient mode, packets that cannot be parsed are returned as
+    /// [`Packet::Raw`] instead of errors.
+    ///
+    /// [`Packet::Raw`]: ./packet/enum.Packet.html#variant.Raw
+    pub fn set_lenient(&mut self, lenient: bool) {
+        self.lenient.set(lenient);
+    }
+
+    pub fn is_lenient(&self) -> bool {
+        self.lenient.get()
+    }
+
+    /// Replace the player names in every packet returned by this stream.
+    pub fn set_redactor(&mut self, redactor: Redactor) {
+        self.redactor = RefCell::new(Some(redactor));
+    }
+
+    /// Detect the duplicate and out of order packets, dropping them before
+    /// they are parsed as set by the sequencer's policy.
+    pub fn set_sequencer(&mut self, sequencer: Sequencer) {
+        self.sequencer = RefCell::new(Some(sequencer));
+    }
+
+    /// Check the values of every packet, dropping or fixing the impossible
+    /// ones as set by the validator.
+    pub fn set_validator(&mut self, validator: Validator) {
+        self.validator = RefCell::new(Some(validator));
+    }
+
+    /// Impossible values of the last packet returned, when validated.
+    pub fn violations(&self) -> Vec<Violation> {
+        self.violations.borrow().clone()
+    }
+
+    /// Only parse the packets accepted by `filter`; the others are skipped
+    /// after reading their id.
+    pub fn set_filter(&mut self, filter: PacketFilter) {
+        self.filter = filter;
+    }
+
+    pub fn filter(&self) -> PacketFilter {
+        self.filter
+    }
+
+    pub fn next(&self) -> Result<Option<Packet>, StreamError> {
+        let mut buf = self.buf.borrow_mut();
+        let mut stats = self.stats.borrow_mut();
+
+        loop {
+            match self.socket.recv(&mut buf) {
+                Ok(len) if len == buf.len() && len < MAX_DATAGRAM_SIZE => {
+                    stats.record(peek_packet_id(&buf[..len]), len);
+                    stats.record_error();
+
+                    let new_len = (buf.len() * 2).min(MAX_DATAGRAM_SIZE);
+                    buf.resize(new_len, 0);
+
+                    return Err(StreamError::Truncated(len));
+                }
+                Ok(len) => {
+                    let packet_id = peek_packet_id(&buf[..len]);
+                    stats.record(packet_id, len);
+
+                    if let Some(id) = packet_id {
+                        if !self.filter.accepts_id(id) {
+                            stats.record_filtered();
+                            continue;
+                        }
+                    }
+
+                    if let Some(sequencer) = self.sequencer.borrow_mut().as_mut() {
+                        let sequence = sequencer.check(&buf[..len]);
+                        match sequence {
+                            Sequence::InOrder => {}
+                            Sequence::Duplicate => stats.record_duplicate(),
+                            Sequence::OutOfOrder => stats.record_out_of_order(),
+                        }
+                        if sequencer.should_drop(sequence) {
+                            continue;
+                        }
+                    }
+
+                    let result = parse_packet_fuzz(&buf[..len]);
+                    if result.is_err() {
+                        stats.record_error();
+                    }
+
+                    if let Some(watchdog) = self.watchdog.borrow_mut().as_mut() {
+                        let diagnostic = watchdog.check(&buf[..len], result.as_ref().map(|_| ()));
+
+                        if let Some(d) = diagnostic {
+                            if watchdog.is_auto_lenient() {
+                                self.lenient.set(true);
+                            }
+
+                            return Err(StreamError::Desync(d));
+                        }
+                    }
+
+                    return match result {
+                        Ok(mut p) => {
+                            if let Some(redactor) = self.redactor.borrow_mut().as_mut() {
+                                redactor.redact(&mut p);
+                            }
+
+                            let mut violations = self.violations.borrow_mut();
+                            violations.clear();
+                            if let Some(validator) = self.validator.borrow_mut().as_mut() {
+                                let validation = validator.validate(&mut p);
+                                if !validation.violations().is_empty() {
+                                    stats.record_invalid();
+                                }
+                                if !validation.keep() {
+                                    continue;
+                                }
+                                violations.extend_from_slice(validation.violations());
+                            }
+
+                            Ok(Some(p))
+                        }
+                        Err(_) if self.lenient.get() => {
+                            Ok(Some(Packet::Raw(RawPacket::new(&buf[..len]))))
+                        }
+                        Err(e) => Err(e.into()),
+                    };
+                }
+                Err(e) => {
+                    return if e.kind() == ErrorKind::WouldBlock || e.kind() == ErrorKind::TimedOut {
+                        Ok(None)
+                    } else {
+                        Err(StreamError::Io(e))
+                    };
+                }
+            }
+        }
+    }
+
+    /// Rates and counts of the packets received so far.
+    pub fn stats(&self) -> StreamStats {
+        self.stats.borrow_mut().snapshot()
+    }
+
+    /// Current size of the receive buffer.
+    pub fn buffer_size(&self) -> usize {
+        self.buf.borrow().len()
+    }
+
+    /// Set the size of the receive buffer, for formats with packets larger than
+    /// [`MAX_PACKET_SIZE`]. A datagram filling the whole buffer is reported as
+    /// [`StreamError::Truncated`], so it should be at least one byte larger than
+    /// the largest packet; it still grows when such a datagram is received.
+    ///
+    /// [`MAX_PACKET_SIZE`]: ./packet/constant.MAX_PACKET_SIZE.html
+    /// [`StreamError::Truncated`]: ./enum.StreamError.html#variant.Truncated
+    pub fn set_buffer_size(&mut self, size: usize) {
+        let size = size.clamp(1, MAX_DATAGRAM_SIZE);
+        self.buf = RefCell::new(vec![0; size]);
+    }
+
+    pub fn socket(&self) -> &UdpSocket {
+        &self.socket
+    }
+
+    /// Read the socket on a background thread, which sends the packets and errors
+    /// on the returned channel. The thread stops when the receiver is dropped or
+    /// the handle is shut down.
+    pub fn spawn(self) -> std::io::Result<(Receiver<Result<Packet, StreamError>>, StreamHandle)> {
+        // Block with a timeout rather than spin, and still notice a shutdown.
+        self.socket.set_nonblocking(false)?;
+        self.socket.set_read_timeout(Some(SPAWN_POLL_INTERVAL))?;
+
+        let (sender, receiver) = mpsc::channel();
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let stop = Arc::clone(&shutdown);
+
+        let thread = thread::Builder::new()
+            .name(String::from("f1-telemetry-stream"))
+            .spawn(move || {
+                while !stop.load(Ordering::Relaxed) {
+                    let result = match self.next() {
+                        Ok(Some(p)) => Ok(p),
+                        Ok(None) => continue,
+                        Err(e) => Err(e),
+                    };
+
+                    if sender.send(result).is_err() {
+                        break;
+                    }
+                }
+
+                self
+            })?;
+
+        let handle = StreamHandle {
+            shutdown,
+            thread: Some(thread),
+        };
+
+        Ok((receiver, handle))
+    }
+}
+
+/// Handle on a stream read by a background thread, see [`Stream::spawn`].
+///
+/// Dropping the handle stops the thread without waiting for it.
+///
+/// [`Stream::spawn`]: ./struct.Stream.html#method.spawn
+pub struct StreamHandle {
+    shutdown: Arc<AtomicBool>,
+    thread: Option<JoinHandle<Stream>>,
+}
+
+impl StreamHandle {
+    /// Stop the background thread and give the stream back, switched to
+    /// non-blocking mode again.
+    pub fn shutdown(mut self) -> std::io::Result<Stream> {
+        self.shutdown.store(true, Ordering::Relaxed);
+
+        let thread = self.thread.take().expect("Stream thread already joined");
+        let stream = match thread.join() {
+            Ok(stream) => stream,
+            Err(e) => std::panic::resume_unwind(e),
+        };
+
+        stream.socket.set_read_timeout(None)?;
+        stream.socket.set_nonblocking(true)?;
+
+        Ok(stream)
+    }
+}
+
+impl Drop for StreamHandle {
+    fn drop(&mut self) {
+        self.shutdown.store(true, Ordering::Relaxed);
+    }
+}
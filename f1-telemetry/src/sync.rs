@@ -0,0 +1,199 @@
+use getset::CopyGetters;
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::packet::header::{PacketHeader, SessionIdentifier};
+
+/// SMPTE-style timecode, as used by LTC and most video editors.
+///
+/// ## Specification
+/// ```text
+/// hours:   Hours
+/// minutes: Minutes, 0-59
+/// seconds: Seconds, 0-59
+/// frames:  Video frame within the second, 0 to fps - 1
+/// ```
+#[derive(Debug, Clone, Copy, Eq, PartialEq, CopyGetters)]
+#[getset(get_copy = "pub")]
+pub struct Timecode {
+    hours: u32,
+    minutes: u8,
+    seconds: u8,
+    frames: u8,
+}
+
+impl Timecode {
+    /// Timecode of a time in seconds, for a video running at `fps` frames per second.
+    /// Negative times are clamped to zero.
+    pub fn from_seconds(seconds: f64, fps: f64) -> Timecode {
+        let total_frames = (seconds.max(0.0) * fps).floor() as u64;
+        let frames_per_second = fps.round().max(1.0) as u64;
+
+        let frames = total_frames % frames_per_second;
+        let total_seconds = total_frames / frames_per_second;
+
+        Timecode {
+            hours: (total_seconds / 3600) as u32,
+            minutes: ((total_seconds / 60) % 60) as u8,
+            seconds: (total_seconds % 60) as u8,
+            frames: frames as u8,
+        }
+    }
+}
+
+impl fmt::Display for Timecode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:02}:{:02}:{:02}:{:02}",
+            self.hours, self.minutes, self.seconds, self.frames
+        )
+    }
+}
+
+/// Known correspondence between the game clock and an external clock, such as
+/// the position in a recording or an OBS timestamp.
+///
+/// ## Specification
+/// ```text
+/// session_time:  Session timestamp, from the packet header
+/// external_time: Time of the same instant on the external clock, in seconds
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, CopyGetters)]
+#[getset(get_copy = "pub")]
+pub struct SyncPoint {
+    session_time: f32,
+    external_time: f64,
+}
+
+/// Maps packets to an external clock so that telemetry can be overlaid on a video
+/// frame-accurately.
+///
+/// The mapping is built from sync points. With a single sync point the external
+/// clock is assumed to run at the same rate as the game. With more, the rate is
+/// fitted to all of them, which corrects the drift between both clocks over long
+/// sessions.
+///
+/// Packets passed to [`observe`] are used to map frame identifiers to session times.
+///
+/// [`observe`]: #method.observe
+#[derive(Debug, Default)]
+pub struct FrameSync {
+    session: Option<SessionIdentifier>,
+    points: Vec<SyncPoint>,
+    frames: BTreeMap<u32, f32>,
+}
+
+impl FrameSync {
+    pub fn new() -> FrameSync {
+        FrameSync::default()
+    }
+
+    /// Forget the sync points and the observed frames.
+    pub fn reset(&mut self) {
+        self.session = None;
+        self.points.clear();
+        self.frames.clear();
+    }
+
+    /// Record that `session_time` happened at `external_time` on the external clock.
+    pub fn add_sync_point(&mut self, session_time: f32, external_time: f64) {
+        self.points.push(SyncPoint {
+            session_time,
+            external_time,
+        });
+    }
+
+    /// Record that the packet with this header happened at `external_time` on the
+    /// external clock.
+    pub fn sync_header(&mut self, header: &PacketHeader, external_time: f64) {
+        self.observe(header);
+        self.add_sync_point(header.session_time(), external_time);
+    }
+
+    pub fn sync_points(&self) -> &[SyncPoint] {
+        &self.points
+    }
+
+    /// Remember the session time of the frame of a packet. The observed frames are
+    /// forgotten when a new session starts, along with the sync points.
+    pub fn observe(&mut self, header: &PacketHeader) {
+        if self.session != Some(header.session_id()) {
+            self.reset();
+            self.session = Some(header.session_id());
+        }
+
+        self.frames
+            .insert(header.frame_identifier(), header.session_time());
+    }
+
+    /// Offset and rate of the external clock relative to the session time, so that
+    /// `external = offset + rate * session_time`.
+    pub fn fit(&self) -> Option<(f64, f64)> {
+        let n = self.points.len() as f64;
+
+        match self.points.as_slice() {
+            [] => None,
+            [p] => Some((p.external_time - f64::from(p.session_time), 1.0)),
+            points => {
+                let mean_s = points
+                    .iter()
+                    .map(|p| f64::from(p.session_time))
+                    .sum::<f64>()
+                    / n;
+                let mean_e = points.iter().map(|p| p.external_time).sum::<f64>() / n;
+
+                let (cov, var) = points.iter().fold((0.0, 0.0), |(cov, var), p| {
+                    let ds = f64::from(p.session_time) - mean_s;
+                    (cov + ds * (p.external_time - mean_e), var + ds * ds)
+                });
+
+                let rate = if var > 0.0 { cov / var } else { 1.0 };
+
+                Some((mean_e - rate * mean_s, rate))
+            }
+        }
+    }
+
+    /// Drift of the external clock, in parts per million. Positive when the
+    /// external clock runs faster than the game.
+    pub fn drift_ppm(&self) -> Option<f64> {
+        self.fit().map(|(_, rate)| (rate - 1.0) * 1_000_000.0)
+    }
+
+    /// Time on the external clock of a session time.
+    pub fn to_external(&self, session_time: f32) -> Option<f64> {
+        self.fit()
+            .map(|(offset, rate)| offset + rate * f64::from(session_time))
+    }
+
+    /// Session time of an observed frame. Frames between two observed frames are
+    /// interpolated.
+    pub fn session_time_at_frame(&self, frame_identifier: u32) -> Option<f32> {
+        if let Some(&t) = self.frames.get(&frame_identifier) {
+            return Some(t);
+        }
+
+        let (&f0, &t0) = self.frames.range(..frame_identifier).next_back()?;
+        let (&f1, &t1) = self.frames.range(frame_identifier..).next()?;
+
+        let ratio = (frame_identifier - f0) as f32 / (f1 - f0) as f32;
+        Some(t0 + (t1 - t0) * ratio)
+    }
+
+    /// Time on the external clock of an observed frame.
+    pub fn frame_to_external(&self, frame_identifier: u32) -> Option<f64> {
+        self.to_external(self.session_time_at_frame(frame_identifier)?)
+    }
+
+    /// Time on the external clock of a packet.
+    pub fn header_to_external(&self, header: &PacketHeader) -> Option<f64> {
+        self.to_external(header.session_time())
+    }
+
+    /// Timecode of a packet in a video running at `fps` frames per second.
+    pub fn timecode(&self, header: &PacketHeader, fps: f64) -> Option<Timecode> {
+        self.header_to_external(header)
+            .map(|t| Timecode::from_seconds(t, fps))
+    }
+}
@@ -0,0 +1,8 @@
+//! Transforms the telemetry into other shapes for consumers.
+
+pub mod downsample;
+pub mod frames;
+pub mod g_force;
+pub mod gap_fill;
+pub mod lap_buffer;
+pub mod sample;
@@ -0,0 +1,170 @@
+use crate::packet::car_telemetry::PacketCarTelemetryData;
+use crate::packet::lap::PacketLapData;
+use crate::packet::motion::PacketMotionData;
+use crate::packet::Packet;
+use crate::transform::sample::{Sampler, TelemetrySample};
+
+/// Grid on which the samples are resampled.
+///
+/// ## Specification
+/// ```text
+/// Time:      One sample every given number of seconds of session time
+/// Distance:  One sample every given number of metres around the lap, starting
+///            again from the line on every lap
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ResampleGrid {
+    Time(f32),
+    Distance(f32),
+}
+
+impl ResampleGrid {
+    /// Grid at a fixed rate, in samples per second.
+    pub fn rate(hz: f32) -> ResampleGrid {
+        ResampleGrid::Time(1.0 / hz)
+    }
+
+    fn position(&self, sample: &TelemetrySample) -> f32 {
+        match self {
+            ResampleGrid::Time(_) => sample.session_time(),
+            ResampleGrid::Distance(_) => sample.lap_distance(),
+        }
+    }
+
+    fn step(&self) -> f32 {
+        match *self {
+            ResampleGrid::Time(step) | ResampleGrid::Distance(step) => step,
+        }
+    }
+
+    /// Whether two consecutive samples cannot be interpolated between.
+    fn is_break(&self, previous: &TelemetrySample, sample: &TelemetrySample) -> bool {
+        match self {
+            ResampleGrid::Time(_) => sample.session_time() < previous.session_time(),
+            ResampleGrid::Distance(_) => sample.lap() != previous.lap(),
+        }
+    }
+}
+
+/// Resamples the telemetry of a car onto a fixed time or lap distance grid,
+/// interpolating linearly between the received samples.
+///
+/// Feed it with the Lap, Motion and Car Telemetry packets, which are sampled by
+/// a [`Sampler`]. Samples before the line at the start of a session (negative lap distance) are
+/// ignored on a distance grid.
+///
+/// [`Sampler`]: ../sample/struct.Sampler.html
+#[derive(Debug)]
+pub struct Downsampler {
+    grid: ResampleGrid,
+    sampler: Sampler,
+    previous: Option<TelemetrySample>,
+    next: f32,
+}
+
+impl Downsampler {
+    /// Downsampler for the player's car.
+    pub fn new(grid: ResampleGrid) -> Downsampler {
+        Downsampler {
+            grid,
+            sampler: Sampler::new(),
+            previous: None,
+            next: 0.0,
+        }
+    }
+
+    /// Follow the car with the given index instead of the player's car.
+    pub fn with_vehicle(mut self, vehicle_idx: u8) -> Downsampler {
+        self.sampler = self.sampler.with_vehicle(vehicle_idx);
+        self
+    }
+
+    pub fn grid(&self) -> ResampleGrid {
+        self.grid
+    }
+
+    pub fn reset(&mut self) {
+        self.sampler.reset();
+        self.previous = None;
+        self.next = 0.0;
+    }
+
+    /// Update the downsampler with a packet, returning the samples on the grid
+    /// reached since the previous packet.
+    pub fn update(&mut self, packet: &Packet) -> Vec<TelemetrySample> {
+        match self.sampler.update(packet) {
+            Some(sample) => self.push(sample),
+            None => Vec::new(),
+        }
+    }
+
+    pub fn update_lap(&mut self, packet: &PacketLapData) {
+        self.sampler.update_lap(packet);
+    }
+
+    pub fn update_motion(&mut self, packet: &PacketMotionData) {
+        self.sampler.update_motion(packet);
+    }
+
+    /// Take a sample of the car's telemetry, returning the samples on the grid
+    /// reached since the previous one.
+    pub fn update_telemetry(&mut self, packet: &PacketCarTelemetryData) -> Vec<TelemetrySample> {
+        match self.sampler.update_telemetry(packet) {
+            Some(sample) => self.push(sample),
+            None => Vec::new(),
+        }
+    }
+
+    /// Add a raw sample, returning the samples on the grid reached since the
+    /// previous one.
+    pub fn push(&mut self, sample: TelemetrySample) -> Vec<TelemetrySample> {
+        let grid = self.grid;
+        let step = grid.step();
+        let position = grid.position(&sample);
+
+        if step <= 0.0 || (matches!(grid, ResampleGrid::Distance(_)) && position < 0.0) {
+            return Vec::new();
+        }
+
+        let previous = match self.previous {
+            Some(previous) if !grid.is_break(&previous, &sample) => previous,
+            _ => {
+                // Start a new grid, on the first point at or after this sample.
+                self.previous = Some(sample);
+                self.next = (position / step).ceil() * step;
+                return self.take_exact(sample, position);
+            }
+        };
+
+        let start = grid.position(&previous);
+        if position <= start {
+            return Vec::new();
+        }
+
+        let mut samples = Vec::new();
+        while self.next <= position {
+            let f = (self.next - start) / (position - start);
+            samples.push(previous.interpolate(&sample, f));
+            self.next += step;
+        }
+
+        self.previous = Some(sample);
+        samples
+    }
+
+    fn take_exact(&mut self, sample: TelemetrySample, position: f32) -> Vec<TelemetrySample> {
+        if self.next <= position {
+            self.next += self.grid.step();
+            vec![sample]
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+/// Resample recorded samples onto a grid in one go.
+pub fn resample(samples: &[TelemetrySample], grid: ResampleGrid) -> Vec<TelemetrySample> {
+    let mut downsampler = Downsampler::new(grid);
+
+    samples.iter().flat_map(|s| downsampler.push(*s)).collect()
+}
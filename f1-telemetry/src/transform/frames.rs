@@ -0,0 +1,193 @@
+use getset::CopyGetters;
+
+use crate::packet::generic::Vec3;
+use crate::packet::motion::MotionData;
+use crate::units::MetresPerSecond;
+
+/// Coordinate convention of a consumer of the positions and velocities.
+///
+/// The game's world space is left-handed with Y up: X points right and Z
+/// forward when looking down at the track from above. The conventions below
+/// take the game's X axis as east and its Z axis as north.
+///
+/// ## Specification
+/// ```text
+/// Game:           (x, y, z) as sent by the game, Y up, left-handed
+/// Enu:            East, north, up, i.e. Z-up right-handed as in ROS, GIS or
+///                 Blender: (x, z, y)
+/// Ned:            North, east, down, as in aerospace: (z, x, -y)
+/// YUpRightHanded: Y-up right-handed, as in OpenGL or three.js: (x, y, -z)
+/// ```
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default)]
+pub enum Frame {
+    #[default]
+    Game,
+    Enu,
+    Ned,
+    YUpRightHanded,
+}
+
+impl Frame {
+    /// Vector of the game's world space in this convention.
+    pub fn from_game(self, v: Vec3) -> Vec3 {
+        let (x, y, z) = (v.x(), v.y(), v.z());
+
+        match self {
+            Frame::Game => v,
+            Frame::Enu => Vec3::new(x, z, y),
+            Frame::Ned => Vec3::new(z, x, -y),
+            Frame::YUpRightHanded => Vec3::new(x, y, -z),
+        }
+    }
+
+    /// Vector of this convention in the game's world space.
+    pub fn to_game(self, v: Vec3) -> Vec3 {
+        let (x, y, z) = (v.x(), v.y(), v.z());
+
+        match self {
+            Frame::Game => v,
+            Frame::Enu => Vec3::new(x, z, y),
+            Frame::Ned => Vec3::new(y, -z, x),
+            Frame::YUpRightHanded => Vec3::new(x, y, -z),
+        }
+    }
+}
+
+/// Derived motion values of a car.
+///
+/// ## Specification
+/// ```text
+/// ground_speed: Speed over the ground, ignoring the vertical velocity
+/// heading:      Compass heading of the car in degrees, 0 to 360, clockwise
+///               from north
+/// course:       Compass heading of the velocity in degrees, 0 to 360
+/// slip_angle:   Angle between the heading and the velocity in degrees,
+///               -180 to 180, positive when the car slides to its right;
+///               0 when the car is stopped
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, CopyGetters)]
+#[getset(get_copy = "pub")]
+pub struct Kinematics {
+    ground_speed: MetresPerSecond,
+    heading: f32,
+    course: f32,
+    slip_angle: f32,
+}
+
+/// Speed under which the course and slip angle are noise.
+const MIN_SPEED: f32 = 0.5;
+
+/// Converts the motion of the cars into the frame of a consumer, e.g. a map
+/// or a 3D view.
+///
+/// Positions are converted relative to an origin, e.g. the start line, and
+/// the frame can be turned around the vertical axis so that north points
+/// elsewhere than the game's Z axis.
+///
+/// ```
+/// use f1_telemetry::packet::generic::Vec3;
+/// use f1_telemetry::transform::frames::{Frame, FrameTransform};
+///
+/// let transform = FrameTransform::new(Frame::Enu).with_origin(Vec3::new(10.0, 0.0, 20.0));
+///
+/// let position = transform.position(Vec3::new(10.0, 5.0, 30.0));
+/// assert_eq!(position, Vec3::new(0.0, 10.0, 5.0));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct FrameTransform {
+    frame: Frame,
+    origin: Vec3,
+    rotation: f32,
+}
+
+impl FrameTransform {
+    pub fn new(frame: Frame) -> FrameTransform {
+        FrameTransform {
+            frame,
+            ..FrameTransform::default()
+        }
+    }
+
+    /// Game world position at the origin of the frame.
+    pub fn with_origin(mut self, origin: Vec3) -> FrameTransform {
+        self.origin = origin;
+        self
+    }
+
+    /// Compass heading of the game's Z axis, in degrees clockwise from north.
+    pub fn with_rotation(mut self, degrees: f32) -> FrameTransform {
+        self.rotation = degrees;
+        self
+    }
+
+    pub fn frame(&self) -> Frame {
+        self.frame
+    }
+
+    pub fn origin(&self) -> Vec3 {
+        self.origin
+    }
+
+    pub fn rotation(&self) -> f32 {
+        self.rotation
+    }
+
+    /// Position of the game's world space in the frame.
+    pub fn position(&self, position: Vec3) -> Vec3 {
+        self.direction(position - self.origin)
+    }
+
+    /// Velocity or direction of the game's world space in the frame.
+    pub fn direction(&self, direction: Vec3) -> Vec3 {
+        self.frame.from_game(self.rotate(direction))
+    }
+
+    /// Derived motion values of a car.
+    pub fn kinematics(&self, motion: &MotionData) -> Kinematics {
+        let velocity = self.rotate(motion.world_velocity());
+        let forward = self.rotate(motion.world_forward_dir());
+
+        let ground_speed = Vec3::new(velocity.x(), 0.0, velocity.z()).length();
+        let heading = compass(forward);
+
+        let (course, slip_angle) = if ground_speed < MIN_SPEED {
+            (heading, 0.0)
+        } else {
+            let course = compass(velocity);
+            (course, signed_angle(course - heading))
+        };
+
+        Kinematics {
+            ground_speed: MetresPerSecond(ground_speed),
+            heading,
+            course,
+            slip_angle,
+        }
+    }
+
+    /// Turn a vector of the game's world space clockwise around the vertical
+    /// axis by the rotation.
+    fn rotate(&self, v: Vec3) -> Vec3 {
+        if self.rotation == 0.0 {
+            return v;
+        }
+
+        let (sin, cos) = self.rotation.to_radians().sin_cos();
+        Vec3::new(v.x() * cos + v.z() * sin, v.y(), v.z() * cos - v.x() * sin)
+    }
+}
+
+/// Compass heading of a horizontal direction of the game's world space, Z
+/// being north and X east.
+fn compass(direction: Vec3) -> f32 {
+    direction
+        .x()
+        .atan2(direction.z())
+        .to_degrees()
+        .rem_euclid(360.0)
+}
+
+/// Angle in degrees between -180 and 180.
+fn signed_angle(degrees: f32) -> f32 {
+    (degrees + 180.0).rem_euclid(360.0) - 180.0
+}
@@ -0,0 +1,134 @@
+use getset::CopyGetters;
+
+use crate::packet::generic::Vec3;
+use crate::packet::motion::{MotionData, PacketMotionData};
+
+/// Standard gravity, in m/s².
+pub const STANDARD_GRAVITY: f32 = 9.806_65;
+
+/// G-forces of a car in a motion frame, combined for traction circle plots.
+///
+/// ## Specification
+/// ```text
+/// lateral:            Lateral g-force, in the car's frame
+/// longitudinal:       Longitudinal g-force, in the car's frame
+/// vertical:           Vertical g-force, in the car's frame
+/// combined:           Magnitude of the lateral and longitudinal g-forces, i.e.
+///                     the distance to the centre of the traction circle
+/// direction:          Angle of the combined g-force in degrees, -180 to 180,
+///                     0 along the longitudinal axis and 90 along the lateral
+///                     one
+/// world_acceleration: The g-forces rotated into world space with the
+///                     orientation of the car, in m/s², reading like an
+///                     accelerometer: 1 G up at rest
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, CopyGetters)]
+#[getset(get_copy = "pub")]
+pub struct GForce {
+    lateral: f32,
+    longitudinal: f32,
+    vertical: f32,
+    combined: f32,
+    direction: f32,
+    world_acceleration: Vec3,
+}
+
+impl GForce {
+    pub fn new(motion: &MotionData) -> GForce {
+        let lateral = motion.g_force_lateral();
+        let longitudinal = motion.g_force_longitudinal();
+
+        GForce {
+            lateral,
+            longitudinal,
+            vertical: motion.g_force_vertical(),
+            combined: lateral.hypot(longitudinal),
+            direction: lateral.atan2(longitudinal).to_degrees(),
+            world_acceleration: motion.orientation().rotate(motion.g_force()) * STANDARD_GRAVITY,
+        }
+    }
+
+    /// Point of the traction circle, lateral g-force as X and longitudinal as Y.
+    pub fn traction_circle(&self) -> (f32, f32) {
+        (self.lateral, self.longitudinal)
+    }
+
+    /// Share of the grip used, given the most combined g-force the car can
+    /// take, e.g. the [`TractionCircle::peak`] of previous laps.
+    ///
+    /// [`TractionCircle::peak`]: ./struct.TractionCircle.html#method.peak
+    pub fn utilisation(&self, peak: f32) -> f32 {
+        if peak <= 0.0 {
+            return 0.0;
+        }
+
+        self.combined / peak
+    }
+}
+
+/// G-forces of every car of a motion packet, indexed by vehicle.
+pub fn g_forces(packet: &PacketMotionData) -> Vec<GForce> {
+    packet.motion_data().iter().map(GForce::new).collect()
+}
+
+/// Number of directions the envelope of a traction circle is kept for.
+const SECTORS: usize = 72;
+
+/// Envelope of the traction circle of a car: the most combined g-force
+/// reached in each direction, every 5 degrees.
+#[derive(Debug, Clone)]
+pub struct TractionCircle {
+    envelope: [f32; SECTORS],
+}
+
+impl Default for TractionCircle {
+    fn default() -> Self {
+        TractionCircle {
+            envelope: [0.0; SECTORS],
+        }
+    }
+}
+
+impl TractionCircle {
+    pub fn new() -> TractionCircle {
+        TractionCircle::default()
+    }
+
+    pub fn reset(&mut self) {
+        self.envelope = [0.0; SECTORS];
+    }
+
+    /// Widen the envelope with a frame.
+    pub fn update(&mut self, g_force: &GForce) {
+        let sector = &mut self.envelope[sector(g_force.direction())];
+        if g_force.combined() > *sector {
+            *sector = g_force.combined();
+        }
+    }
+
+    /// Most combined g-force reached in a direction, in degrees.
+    pub fn envelope(&self, direction: f32) -> f32 {
+        self.envelope[sector(direction)]
+    }
+
+    /// Most combined g-force reached in any direction.
+    pub fn peak(&self) -> f32 {
+        self.envelope.iter().copied().fold(0.0, f32::max)
+    }
+
+    /// Points of the envelope, as (direction in degrees, combined g-force),
+    /// e.g. to draw it as a polygon.
+    pub fn points(&self) -> impl Iterator<Item = (f32, f32)> + '_ {
+        let width = 360.0 / SECTORS as f32;
+
+        self.envelope
+            .iter()
+            .enumerate()
+            .map(move |(i, g)| (i as f32 * width - 180.0 + width / 2.0, *g))
+    }
+}
+
+fn sector(direction: f32) -> usize {
+    let fraction = (direction + 180.0).rem_euclid(360.0) / 360.0;
+    ((fraction * SECTORS as f32) as usize).min(SECTORS - 1)
+}
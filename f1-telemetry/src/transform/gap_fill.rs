@@ -0,0 +1,201 @@
+use std::collections::VecDeque;
+
+use crate::packet::car_telemetry::PacketCarTelemetryData;
+use crate::packet::lap::PacketLapData;
+use crate::packet::motion::PacketMotionData;
+use crate::packet::Packet;
+use crate::transform::sample::{Sampler, TelemetrySample};
+
+/// Number of intervals between samples the interval is estimated from.
+const INTERVALS: usize = 16;
+
+/// Gap as a multiple of the interval from which samples are missing.
+const GAP_FACTOR: f32 = 1.5;
+
+/// Longest gap filled by default, in seconds. Longer ones are pauses, e.g. in
+/// the menus, rather than lost packets.
+const MAX_GAP: f32 = 1.0;
+
+/// How the missing samples are synthesized.
+///
+/// ## Specification
+/// ```text
+/// Linear:   Straight lines between the received samples
+/// Hermite:  Cubic curves following the trend of the samples before the gap
+/// ```
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default)]
+pub enum Interpolation {
+    #[default]
+    Linear,
+    Hermite,
+}
+
+/// Fills the gaps left by lost packets in the telemetry of a car, so that its
+/// samples are evenly spaced in time.
+///
+/// Feed it with the Lap, Motion and Car Telemetry packets, which are sampled by
+/// a [`Sampler`]. The interval between samples is the telemetry rate set in
+/// the game, estimated from the shortest recent intervals unless set. The
+/// synthesized samples are flagged as [`interpolated`]. Gaps across a new lap,
+/// a flashback or longer than a second are left as is.
+///
+/// [`Sampler`]: ../sample/struct.Sampler.html
+/// [`interpolated`]: ../sample/struct.TelemetrySample.html#method.interpolated
+#[derive(Debug)]
+pub struct GapFiller {
+    interpolation: Interpolation,
+    sampler: Sampler,
+    interval: Option<f32>,
+    max_gap: f32,
+    intervals: VecDeque<f32>,
+    before: Option<TelemetrySample>,
+    previous: Option<TelemetrySample>,
+    filled: u64,
+}
+
+impl GapFiller {
+    /// Gap filler for the player's car.
+    pub fn new(interpolation: Interpolation) -> GapFiller {
+        GapFiller {
+            interpolation,
+            sampler: Sampler::new(),
+            interval: None,
+            max_gap: MAX_GAP,
+            intervals: VecDeque::with_capacity(INTERVALS),
+            before: None,
+            previous: None,
+            filled: 0,
+        }
+    }
+
+    /// Follow the car with the given index instead of the player's car.
+    pub fn with_vehicle(mut self, vehicle_idx: u8) -> GapFiller {
+        self.sampler = self.sampler.with_vehicle(vehicle_idx);
+        self
+    }
+
+    /// Interval between samples in seconds, e.g. 1/60 for a telemetry rate of
+    /// 60 Hz, instead of estimating it.
+    pub fn with_interval(mut self, interval: f32) -> GapFiller {
+        self.interval = Some(interval);
+        self
+    }
+
+    /// Longest gap filled, in seconds.
+    pub fn with_max_gap(mut self, max_gap: f32) -> GapFiller {
+        self.max_gap = max_gap;
+        self
+    }
+
+    pub fn interpolation(&self) -> Interpolation {
+        self.interpolation
+    }
+
+    /// Interval between samples, as set or estimated so far.
+    pub fn interval(&self) -> Option<f32> {
+        self.interval
+            .or_else(|| self.intervals.iter().copied().min_by(|a, b| a.total_cmp(b)))
+    }
+
+    /// Number of samples synthesized.
+    pub fn filled(&self) -> u64 {
+        self.filled
+    }
+
+    pub fn reset(&mut self) {
+        self.sampler.reset();
+        self.intervals.clear();
+        self.before = None;
+        self.previous = None;
+    }
+
+    /// Update the gap filler with a packet, returning the samples synthesized
+    /// before the one of the packet, followed by it.
+    pub fn update(&mut self, packet: &Packet) -> Vec<TelemetrySample> {
+        match self.sampler.update(packet) {
+            Some(sample) => self.push(sample),
+            None => Vec::new(),
+        }
+    }
+
+    pub fn update_lap(&mut self, packet: &PacketLapData) {
+        self.sampler.update_lap(packet);
+    }
+
+    pub fn update_motion(&mut self, packet: &PacketMotionData) {
+        self.sampler.update_motion(packet);
+    }
+
+    /// Take a sample of the car's telemetry, returning the samples synthesized
+    /// before it, followed by it.
+    pub fn update_telemetry(&mut self, packet: &PacketCarTelemetryData) -> Vec<TelemetrySample> {
+        match self.sampler.update_telemetry(packet) {
+            Some(sample) => self.push(sample),
+            None => Vec::new(),
+        }
+    }
+
+    /// Add a received sample, returning the samples synthesized before it,
+    /// followed by it.
+    pub fn push(&mut self, sample: TelemetrySample) -> Vec<TelemetrySample> {
+        let previous = match self.previous {
+            Some(previous) if sample.session_time() > previous.session_time() => previous,
+            // A flashback or a new session, nothing to fill.
+            Some(previous) if sample.session_time() < previous.session_time() => {
+                self.reset_history(sample);
+                return vec![sample];
+            }
+            // The same frame, e.g. a duplicate packet.
+            Some(_) => return Vec::new(),
+            None => {
+                self.reset_history(sample);
+                return vec![sample];
+            }
+        };
+
+        let gap = sample.session_time() - previous.session_time();
+        let mut samples = Vec::new();
+
+        if let Some(interval) = self.interval().filter(|&i| i > 0.0) {
+            if gap > interval * GAP_FACTOR && gap <= self.max_gap && sample.lap() == previous.lap()
+            {
+                let steps = (gap / interval).round() as usize;
+                for step in 1..steps {
+                    let f = step as f32 / steps as f32;
+                    samples.push(match self.interpolation {
+                        Interpolation::Linear => previous.interpolate(&sample, f),
+                        Interpolation::Hermite => {
+                            previous.interpolate_hermite(&sample, self.before.as_ref(), f)
+                        }
+                    });
+                }
+                self.filled += samples.len() as u64;
+            }
+        }
+
+        if self.intervals.len() == INTERVALS {
+            self.intervals.pop_front();
+        }
+        self.intervals.push_back(gap);
+
+        self.before = Some(previous);
+        self.previous = Some(sample);
+        samples.push(sample);
+        samples
+    }
+
+    fn reset_history(&mut self, sample: TelemetrySample) {
+        self.before = None;
+        self.previous = Some(sample);
+    }
+}
+
+/// Fill the gaps of recorded samples in one go.
+pub fn fill_gaps(
+    samples: &[TelemetrySample],
+    interpolation: Interpolation,
+) -> Vec<TelemetrySample> {
+    let mut filler = GapFiller::new(interpolation);
+
+    samples.iter().flat_map(|s| filler.push(*s)).collect()
+}
@@ -0,0 +1,135 @@
+use std::collections::VecDeque;
+
+use crate::packet::Packet;
+use crate::transform::sample::{Sampler, TelemetrySample};
+
+/// The telemetry samples of a finished lap.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TelemetryLap {
+    lap: u8,
+    samples: Vec<TelemetrySample>,
+}
+
+impl TelemetryLap {
+    pub fn lap(&self) -> u8 {
+        self.lap
+    }
+
+    /// Samples of the lap, in the order they were received.
+    pub fn samples(&self) -> &[TelemetrySample] {
+        &self.samples
+    }
+
+    /// Session time between the first and the last sample of the lap, in seconds.
+    pub fn duration(&self) -> f32 {
+        match (self.samples.first(), self.samples.last()) {
+            (Some(first), Some(last)) => last.session_time() - first.session_time(),
+            _ => 0.0,
+        }
+    }
+}
+
+/// Groups the telemetry samples of a car by lap, so that laps can be processed
+/// as units.
+///
+/// A lap is finished when the lap number of the samples changes. Laps that are
+/// left before crossing the line (e.g. on a restart or a flashback to the
+/// previous lap) are finished the same way.
+#[derive(Debug, Default)]
+pub struct TelemetryLapBuffer {
+    sampler: Sampler,
+    max_laps: Option<usize>,
+    laps: VecDeque<TelemetryLap>,
+    current: Vec<TelemetrySample>,
+    current_lap: Option<u8>,
+}
+
+impl TelemetryLapBuffer {
+    /// Buffer for the player's car, keeping every finished lap.
+    pub fn new() -> TelemetryLapBuffer {
+        TelemetryLapBuffer::default()
+    }
+
+    /// Follow the car with the given index instead of the player's car.
+    pub fn with_vehicle(mut self, vehicle_idx: u8) -> TelemetryLapBuffer {
+        self.sampler = self.sampler.with_vehicle(vehicle_idx);
+        self
+    }
+
+    /// Only keep the given number of finished laps, dropping the oldest ones.
+    pub fn with_max_laps(mut self, max_laps: usize) -> TelemetryLapBuffer {
+        self.max_laps = Some(max_laps);
+        self
+    }
+
+    pub fn reset(&mut self) {
+        self.sampler.reset();
+        self.laps.clear();
+        self.current.clear();
+        self.current_lap = None;
+    }
+
+    /// Update the buffer with a packet, returning the lap finished by it, if any.
+    pub fn update(&mut self, packet: &Packet) -> Option<&TelemetryLap> {
+        let sample = self.sampler.update(packet)?;
+        self.push(sample)
+    }
+
+    /// Add a sample to the lap in progress, returning the lap finished by it, if any.
+    pub fn push(&mut self, sample: TelemetrySample) -> Option<&TelemetryLap> {
+        let finished = match self.current_lap {
+            Some(lap) if lap != sample.lap() => {
+                let samples = std::mem::take(&mut self.current);
+                self.finish(TelemetryLap { lap, samples })
+            }
+            _ => false,
+        };
+
+        self.current_lap = Some(sample.lap());
+        self.current.push(sample);
+
+        if finished {
+            self.laps.back()
+        } else {
+            None
+        }
+    }
+
+    fn finish(&mut self, lap: TelemetryLap) -> bool {
+        if lap.samples.is_empty() || self.max_laps == Some(0) {
+            return false;
+        }
+
+        if let Some(max_laps) = self.max_laps {
+            while self.laps.len() >= max_laps {
+                self.laps.pop_front();
+            }
+        }
+
+        self.laps.push_back(lap);
+        true
+    }
+
+    /// Finished laps, oldest first.
+    pub fn laps(&self) -> impl Iterator<Item = &TelemetryLap> {
+        self.laps.iter()
+    }
+
+    /// The most recent finished lap with the given number.
+    pub fn lap(&self, lap: u8) -> Option<&TelemetryLap> {
+        self.laps.iter().rev().find(|l| l.lap == lap)
+    }
+
+    pub fn last_lap(&self) -> Option<&TelemetryLap> {
+        self.laps.back()
+    }
+
+    /// Samples of the lap in progress.
+    pub fn current_lap(&self) -> &[TelemetrySample] {
+        &self.current
+    }
+
+    pub fn current_lap_num(&self) -> Option<u8> {
+        self.current_lap
+    }
+}
@@ -0,0 +1,235 @@
+use getset::CopyGetters;
+use std::ops::{Add, Mul, Sub};
+
+use crate::packet::car_telemetry::PacketCarTelemetryData;
+use crate::packet::generic::Vec3;
+use crate::packet::lap::PacketLapData;
+use crate::packet::motion::PacketMotionData;
+use crate::packet::Packet;
+
+/// A point of a car's telemetry and motion channels.
+///
+/// ## Specification
+/// ```text
+/// session_time:          Session timestamp in seconds
+/// lap:                   Lap number
+/// lap_distance:          Distance around the lap in metres
+/// speed:                 Speed of car in kilometres per hour
+/// throttle:              Amount of throttle applied (0.0 to 1.0)
+/// brake:                 Amount of brake applied (0.0 to 1.0)
+/// steer:                 Steering (-1.0 (full lock left) to 1.0 (full lock right))
+/// gear:                  Gear selected (1-8, N=0, R=-1)
+/// engine_rpm:            Engine RPM
+/// drs:                   Whether DRS is open
+/// world_position:        World space position
+/// g_force:               Lateral (X), vertical (Y) and longitudinal (Z) g-force
+/// interpolated:          Whether the sample was synthesized between received ones
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, CopyGetters)]
+#[getset(get_copy = "pub")]
+pub struct TelemetrySample {
+    session_time: f32,
+    lap: u8,
+    lap_distance: f32,
+    speed: f32,
+    throttle: f32,
+    brake: f32,
+    steer: f32,
+    gear: i8,
+    engine_rpm: f32,
+    drs: bool,
+    world_position: Vec3,
+    g_force: Vec3,
+    interpolated: bool,
+}
+
+impl TelemetrySample {
+    /// Sample between `self` and `other`, `f` being the fraction of the way to
+    /// `other` (0.0 to 1.0).
+    ///
+    /// The continuous channels are interpolated linearly, the gear, lap and DRS
+    /// state are taken from the nearest sample.
+    pub fn interpolate(&self, other: &TelemetrySample, f: f32) -> TelemetrySample {
+        let nearest = if f < 0.5 { self } else { other };
+
+        TelemetrySample {
+            session_time: lerp(self.session_time, other.session_time, f),
+            lap: nearest.lap,
+            lap_distance: lerp(self.lap_distance, other.lap_distance, f),
+            speed: lerp(self.speed, other.speed, f),
+            throttle: lerp(self.throttle, other.throttle, f),
+            brake: lerp(self.brake, other.brake, f),
+            steer: lerp(self.steer, other.steer, f),
+            gear: nearest.gear,
+            engine_rpm: lerp(self.engine_rpm, other.engine_rpm, f),
+            drs: nearest.drs,
+            world_position: self.world_position + (other.world_position - self.world_position) * f,
+            g_force: self.g_force + (other.g_force - self.g_force) * f,
+            interpolated: f > 0.0 && f < 1.0,
+        }
+    }
+
+    /// Sample between `self` and `other` on a cubic Hermite spline, `f` being
+    /// the fraction of the way to `other` (0.0 to 1.0).
+    ///
+    /// The slope at `self` is taken from `before`, the sample before it, if
+    /// any: the curve then follows the trend of the samples across a long gap,
+    /// e.g. the braking into a corner. The pedals and steering are kept in
+    /// their range.
+    pub fn interpolate_hermite(
+        &self,
+        other: &TelemetrySample,
+        before: Option<&TelemetrySample>,
+        f: f32,
+    ) -> TelemetrySample {
+        let before = before.filter(|b| b.session_time < self.session_time && b.lap == self.lap);
+        let curve = |value: fn(&TelemetrySample) -> f32| {
+            hermite(
+                before.map(|b| (value(b), b.session_time)),
+                (value(self), self.session_time),
+                (value(other), other.session_time),
+                f,
+            )
+        };
+        let curve_vec = |value: fn(&TelemetrySample) -> Vec3| {
+            hermite(
+                before.map(|b| (value(b), b.session_time)),
+                (value(self), self.session_time),
+                (value(other), other.session_time),
+                f,
+            )
+        };
+
+        TelemetrySample {
+            lap_distance: curve(|s| s.lap_distance),
+            speed: curve(|s| s.speed).max(0.0),
+            throttle: curve(|s| s.throttle).clamp(0.0, 1.0),
+            brake: curve(|s| s.brake).clamp(0.0, 1.0),
+            steer: curve(|s| s.steer).clamp(-1.0, 1.0),
+            engine_rpm: curve(|s| s.engine_rpm).max(0.0),
+            world_position: curve_vec(|s| s.world_position),
+            g_force: curve_vec(|s| s.g_force),
+            ..self.interpolate(other, f)
+        }
+    }
+}
+
+/// Builds [`TelemetrySample`]s of a car from the Lap, Motion and Car Telemetry
+/// packets.
+///
+/// A sample is taken on every Car Telemetry packet, with the latest lap and
+/// motion data, once a Lap Data packet has been received.
+///
+/// [`TelemetrySample`]: ./struct.TelemetrySample.html
+#[derive(Debug, Default)]
+pub struct Sampler {
+    vehicle_idx: Option<u8>,
+    lap: Option<(u8, f32)>,
+    motion: Option<(Vec3, Vec3)>,
+}
+
+impl Sampler {
+    /// Sampler for the player's car.
+    pub fn new() -> Sampler {
+        Sampler::default()
+    }
+
+    /// Follow the car with the given index instead of the player's car.
+    pub fn with_vehicle(mut self, vehicle_idx: u8) -> Sampler {
+        self.vehicle_idx = Some(vehicle_idx);
+        self
+    }
+
+    pub fn reset(&mut self) {
+        self.lap = None;
+        self.motion = None;
+    }
+
+    /// Update the sampler with a packet, returning a sample for Car Telemetry packets.
+    pub fn update(&mut self, packet: &Packet) -> Option<TelemetrySample> {
+        match packet {
+            Packet::Lap(p) => self.update_lap(p),
+            Packet::Motion(p) => self.update_motion(p),
+            Packet::CarTelemetry(p) => return self.update_telemetry(p),
+            _ => {}
+        }
+
+        None
+    }
+
+    /// Keep the car's latest lap and lap distance.
+    pub fn update_lap(&mut self, packet: &PacketLapData) {
+        let idx = self
+            .vehicle_idx
+            .unwrap_or_else(|| packet.header().player_car_index());
+
+        if let Some(ld) = packet.lap_data().get(idx as usize) {
+            self.lap = Some((ld.current_lap_num(), ld.lap_distance()));
+        }
+    }
+
+    /// Keep the car's latest position and g-force.
+    pub fn update_motion(&mut self, packet: &PacketMotionData) {
+        let idx = self
+            .vehicle_idx
+            .unwrap_or_else(|| packet.header().player_car_index());
+
+        if let Some(md) = packet.motion_data().get(idx as usize) {
+            self.motion = Some((md.world_position(), md.g_force()));
+        }
+    }
+
+    /// Take a sample of the car's telemetry.
+    pub fn update_telemetry(&mut self, packet: &PacketCarTelemetryData) -> Option<TelemetrySample> {
+        let idx = self
+            .vehicle_idx
+            .unwrap_or_else(|| packet.header().player_car_index());
+
+        let t = packet.car_telemetry_data().get(idx as usize)?;
+        let (lap, lap_distance) = self.lap?;
+        let (world_position, g_force) = self.motion.unwrap_or_default();
+
+        Some(TelemetrySample {
+            session_time: packet.header().session_time(),
+            lap,
+            lap_distance,
+            speed: f32::from(t.speed()),
+            throttle: t.throttle(),
+            brake: t.brake(),
+            steer: t.steer(),
+            gear: t.gear(),
+            engine_rpm: f32::from(t.engine_rpm()),
+            drs: t.drs(),
+            world_position,
+            g_force,
+            interpolated: false,
+        })
+    }
+}
+
+fn lerp(a: f32, b: f32, f: f32) -> f32 {
+    a + (b - a) * f
+}
+
+/// Cubic Hermite interpolation between the values `a` and `b` at their time,
+/// with the slope at `a` from the value `before` it, if any, and the slope at
+/// `b` from `a`.
+fn hermite<T>(before: Option<(T, f32)>, a: (T, f32), b: (T, f32), f: f32) -> T
+where
+    T: Copy + Add<Output = T> + Sub<Output = T> + Mul<f32, Output = T>,
+{
+    let dt = b.1 - a.1;
+    let secant = b.0 - a.0;
+    let m0 = match before {
+        Some((value, time)) if b.1 > time => (b.0 - value) * (dt / (b.1 - time)),
+        _ => secant,
+    };
+    let m1 = secant;
+
+    let f2 = f * f;
+    let f3 = f2 * f;
+    a.0 * (2.0 * f3 - 3.0 * f2 + 1.0)
+        + m0 * (f3 - 2.0 * f2 + f)
+        + b.0 * (3.0 * f2 - 2.0 * f3)
+        + m1 * (f3 - f2)
+}
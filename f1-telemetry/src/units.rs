@@ -0,0 +1,170 @@
+//! Units of the physical values of the telemetry.
+//!
+//! The packets keep the values as sent by the game, e.g. a speed in km/h as a
+//! `u16`. The getters with a unit suffix, such as
+//! [`CarTelemetryData::speed_kmh`], return them wrapped in one of these types
+//! instead, so that a speed in km/h cannot be mistaken for one in mph. The
+//! wrapped value is public, and the types of a same quantity convert into each
+//! other with `From`:
+//!
+//! ```
+//! use f1_telemetry::units::{KilometresPerHour, MilesPerHour};
+//!
+//! let speed = KilometresPerHour(300.0);
+//! assert_eq!(MilesPerHour::from(speed).0.round(), 186.0);
+//! ```
+//!
+//! [`CarTelemetryData::speed_kmh`]: ../packet/car_telemetry/struct.CarTelemetryData.html#method.speed_kmh
+
+use core::fmt;
+use core::ops::{Add, Mul, Sub};
+
+const MILES_PER_KILOMETRE: f32 = 0.621_371;
+const BAR_PER_PSI: f32 = 0.068_947_6;
+
+macro_rules! unit {
+    ($(#[$doc:meta])* $name:ident, $symbol:expr) => {
+        $(#[$doc])*
+        #[derive(Debug, Clone, Copy, Default, PartialEq, PartialOrd)]
+        pub struct $name(pub f32);
+
+        impl $name {
+            pub fn value(self) -> f32 {
+                self.0
+            }
+        }
+
+        impl fmt::Display for $name {
+            /// The value, honouring the precision, followed by the symbol.
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                fmt::Display::fmt(&self.0, f)?;
+                write!(f, " {}", $symbol)
+            }
+        }
+
+        impl Add for $name {
+            type Output = $name;
+
+            fn add(self, other: $name) -> $name {
+                $name(self.0 + other.0)
+            }
+        }
+
+        impl Sub for $name {
+            type Output = $name;
+
+            fn sub(self, other: $name) -> $name {
+                $name(self.0 - other.0)
+            }
+        }
+
+        impl Mul<f32> for $name {
+            type Output = $name;
+
+            fn mul(self, factor: f32) -> $name {
+                $name(self.0 * factor)
+            }
+        }
+    };
+}
+
+unit!(
+    /// Speed in kilometres per hour, as sent by the game.
+    KilometresPerHour,
+    "km/h"
+);
+unit!(
+    /// Speed in miles per hour.
+    MilesPerHour,
+    "mph"
+);
+unit!(
+    /// Speed in metres per second.
+    MetresPerSecond,
+    "m/s"
+);
+unit!(
+    /// Temperature in degrees Celsius, as sent by the game.
+    Celsius,
+    "°C"
+);
+unit!(
+    /// Temperature in degrees Fahrenheit.
+    Fahrenheit,
+    "°F"
+);
+unit!(
+    /// Pressure in pounds per square inch, as sent by the game.
+    Psi,
+    "psi"
+);
+unit!(
+    /// Pressure in bar.
+    Bar,
+    "bar"
+);
+unit!(
+    /// Percentage, from 0 to 100.
+    Percent,
+    "%"
+);
+
+impl From<KilometresPerHour> for MilesPerHour {
+    fn from(speed: KilometresPerHour) -> Self {
+        MilesPerHour(speed.0 * MILES_PER_KILOMETRE)
+    }
+}
+
+impl From<MilesPerHour> for KilometresPerHour {
+    fn from(speed: MilesPerHour) -> Self {
+        KilometresPerHour(speed.0 / MILES_PER_KILOMETRE)
+    }
+}
+
+impl From<KilometresPerHour> for MetresPerSecond {
+    fn from(speed: KilometresPerHour) -> Self {
+        MetresPerSecond(speed.0 / 3.6)
+    }
+}
+
+impl From<MetresPerSecond> for KilometresPerHour {
+    fn from(speed: MetresPerSecond) -> Self {
+        KilometresPerHour(speed.0 * 3.6)
+    }
+}
+
+impl From<Celsius> for Fahrenheit {
+    fn from(temperature: Celsius) -> Self {
+        Fahrenheit(temperature.0 * 1.8 + 32.0)
+    }
+}
+
+impl From<Fahrenheit> for Celsius {
+    fn from(temperature: Fahrenheit) -> Self {
+        Celsius((temperature.0 - 32.0) / 1.8)
+    }
+}
+
+impl From<Psi> for Bar {
+    fn from(pressure: Psi) -> Self {
+        Bar(pressure.0 * BAR_PER_PSI)
+    }
+}
+
+impl From<Bar> for Psi {
+    fn from(pressure: Bar) -> Self {
+        Psi(pressure.0 / BAR_PER_PSI)
+    }
+}
+
+impl Percent {
+    /// Percentage of a fraction from 0 to 1, e.g. the throttle.
+    pub fn from_fraction(fraction: f32) -> Percent {
+        Percent(fraction * 100.0)
+    }
+
+    /// Fraction from 0 to 1.
+    pub fn fraction(self) -> f32 {
+        self.0 / 100.0
+    }
+}
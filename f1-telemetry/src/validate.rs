@@ -0,0 +1,325 @@
+//! Sanity checks of the telemetry, e.g. against the corrupt frames the game
+//! sometimes sends.
+//!
+//! A [`Validator`] looks for values no car can reach: floats that are not
+//! numbers, speeds over 400 km/h, pedals out of their range, or a car going
+//! backwards around the lap. What it does with them is set by a
+//! [`ValidationAction`]: fix the values in place, drop the packet, or only
+//! report them.
+//!
+//! ```
+//! use f1_telemetry::validate::{ValidationAction, Validator};
+//!
+//! let validator = Validator::new(ValidationAction::Clamp).with_max_speed(380);
+//! assert_eq!(validator.max_speed(), 380);
+//! ```
+//!
+//! [`Validator`]: ./struct.Validator.html
+//! [`ValidationAction`]: ./enum.ValidationAction.html
+
+use core::fmt;
+
+use crate::packet::lap::PacketLapData;
+use crate::packet::{Packet, PacketType};
+
+/// Speed no car reaches, in km/h.
+const MAX_SPEED: u16 = 400;
+
+/// Distance a car may go backwards around the lap between two packets, in
+/// metres, e.g. when spinning.
+const LAP_DISTANCE_TOLERANCE: f32 = 50.0;
+
+/// What a validator does with a packet holding impossible values.
+///
+/// ## Specification
+/// ```text
+/// Clamp:     Replace the values with the closest possible ones
+/// Drop:      Drop the packet
+/// Annotate:  Leave the packet as is and only report the values
+/// ```
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default)]
+pub enum ValidationAction {
+    #[default]
+    Clamp,
+    Drop,
+    Annotate,
+}
+
+/// Why a value is impossible.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ViolationKind {
+    /// The value is NaN or infinite.
+    NotFinite,
+    /// The value is out of the range of the field.
+    OutOfRange { value: f32, min: f32, max: f32 },
+    /// The car went backwards around the lap, without a new lap or a
+    /// flashback.
+    Backwards { from: f32, to: f32 },
+}
+
+/// An impossible value of a car.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Violation {
+    pub packet_type: PacketType,
+    pub vehicle_index: usize,
+    pub field: &'static str,
+    pub kind: ViolationKind,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:?} of car {}: {} ",
+            self.packet_type, self.vehicle_index, self.field
+        )?;
+        match self.kind {
+            ViolationKind::NotFinite => write!(f, "is not a number"),
+            ViolationKind::OutOfRange { value, min, max } => {
+                write!(f, "{} is out of {}..{}", value, min, max)
+            }
+            ViolationKind::Backwards { from, to } => {
+                write!(f, "went back from {:.1} to {:.1}", from, to)
+            }
+        }
+    }
+}
+
+/// Outcome of the validation of a packet.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Validation {
+    Valid,
+    /// The values were fixed in place.
+    Clamped(Vec<Violation>),
+    /// The packet should be dropped.
+    Dropped(Vec<Violation>),
+    /// The packet was left as is.
+    Annotated(Vec<Violation>),
+}
+
+impl Validation {
+    pub fn violations(&self) -> &[Violation] {
+        match self {
+            Validation::Valid => &[],
+            Validation::Clamped(v) | Validation::Dropped(v) | Validation::Annotated(v) => v,
+        }
+    }
+
+    /// Whether the packet can be used.
+    pub fn keep(&self) -> bool {
+        !matches!(self, Validation::Dropped(_))
+    }
+}
+
+/// Last known position around the lap of a car.
+#[derive(Debug, Clone, Copy)]
+struct LapPosition {
+    lap: u8,
+    distance: f32,
+    session_time: f32,
+}
+
+/// Checks the values of the motion, lap data, telemetry and status packets.
+#[derive(Debug)]
+pub struct Validator {
+    action: ValidationAction,
+    max_speed: u16,
+    lap_distance_tolerance: f32,
+    positions: Vec<Option<LapPosition>>,
+    checked: u64,
+    invalid: u64,
+}
+
+impl Default for Validator {
+    /// Clamp the impossible values.
+    fn default() -> Validator {
+        Validator::new(ValidationAction::default())
+    }
+}
+
+impl Validator {
+    pub fn new(action: ValidationAction) -> Validator {
+        Validator {
+            action,
+            max_speed: MAX_SPEED,
+            lap_distance_tolerance: LAP_DISTANCE_TOLERANCE,
+            positions: Vec::new(),
+            checked: 0,
+            invalid: 0,
+        }
+    }
+
+    /// Highest possible speed in km/h, 400 by default.
+    pub fn with_max_speed(mut self, max_speed: u16) -> Validator {
+        self.max_speed = max_speed;
+        self
+    }
+
+    /// Distance in metres a car may go backwards around the lap between two
+    /// packets, 50 by default.
+    pub fn with_lap_distance_tolerance(mut self, tolerance: f32) -> Validator {
+        self.lap_distance_tolerance = tolerance;
+        self
+    }
+
+    pub fn action(&self) -> ValidationAction {
+        self.action
+    }
+
+    pub fn max_speed(&self) -> u16 {
+        self.max_speed
+    }
+
+    /// Number of packets checked.
+    pub fn checked(&self) -> u64 {
+        self.checked
+    }
+
+    /// Number of packets with impossible values.
+    pub fn invalid(&self) -> u64 {
+        self.invalid
+    }
+
+    /// Forget the positions of the cars, e.g. for a new session.
+    pub fn reset(&mut self) {
+        self.positions.clear();
+    }
+
+    /// Check a packet, fixing its values in place with
+    /// [`ValidationAction::Clamp`].
+    ///
+    /// [`ValidationAction::Clamp`]: ./enum.ValidationAction.html#variant.Clamp
+    pub fn validate(&mut self, packet: &mut Packet) -> Validation {
+        let clamp = self.action == ValidationAction::Clamp;
+        let mut violations = Vec::new();
+
+        let backwards = match packet {
+            Packet::Lap(p) => self.check_lap_positions(p),
+            _ => Vec::new(),
+        };
+
+        let (packet_type, floats): (PacketType, Vec<(usize, &'static str, &mut f32)>) = match packet
+        {
+            Packet::Motion(p) => (PacketType::Motion, p.floats_mut().collect()),
+            Packet::Lap(p) => (PacketType::LapData, p.floats_mut().collect()),
+            Packet::CarTelemetry(p) => {
+                for (i, speed) in p.speeds_mut() {
+                    if *speed > self.max_speed {
+                        violations.push(Violation {
+                            packet_type: PacketType::CarTelemetry,
+                            vehicle_index: i,
+                            field: "speed",
+                            kind: ViolationKind::OutOfRange {
+                                value: f32::from(*speed),
+                                min: 0.0,
+                                max: f32::from(self.max_speed),
+                            },
+                        });
+                        if clamp {
+                            *speed = self.max_speed;
+                        }
+                    }
+                }
+                (PacketType::CarTelemetry, p.floats_mut().collect())
+            }
+            Packet::CarStatus(p) => (PacketType::CarStatus, p.floats_mut().collect()),
+            _ => return Validation::Valid,
+        };
+
+        for (i, field, value) in floats {
+            let range = range(field);
+            let previous = match field {
+                "lap_distance" => backwards.get(i).copied().flatten(),
+                _ => None,
+            };
+
+            let kind = if !value.is_finite() {
+                ViolationKind::NotFinite
+            } else if let Some(from) = previous {
+                ViolationKind::Backwards { from, to: *value }
+            } else {
+                match range {
+                    Some((min, max)) if *value < min || *value > max => ViolationKind::OutOfRange {
+                        value: *value,
+                        min,
+                        max,
+                    },
+                    _ => continue,
+                }
+            };
+
+            violations.push(Violation {
+                packet_type,
+                vehicle_index: i,
+                field,
+                kind,
+            });
+            if clamp {
+                *value = match kind {
+                    ViolationKind::NotFinite => {
+                        range.map_or(0.0, |(min, max)| 0.0f32.clamp(min, max))
+                    }
+                    ViolationKind::OutOfRange { min, max, .. } => value.clamp(min, max),
+                    ViolationKind::Backwards { from, .. } => from,
+                };
+            }
+        }
+
+        self.checked += 1;
+        if violations.is_empty() {
+            return Validation::Valid;
+        }
+
+        self.invalid += 1;
+        match self.action {
+            ValidationAction::Clamp => Validation::Clamped(violations),
+            ValidationAction::Drop => Validation::Dropped(violations),
+            ValidationAction::Annotate => Validation::Annotated(violations),
+        }
+    }
+
+    /// Previous lap distance of the cars that went backwards around the lap,
+    /// by index. Only the jump is reported, the new positions are remembered.
+    fn check_lap_positions(&mut self, packet: &PacketLapData) -> Vec<Option<f32>> {
+        let session_time = packet.header().session_time();
+        let lap_data = packet.lap_data();
+        if self.positions.len() < lap_data.len() {
+            self.positions.resize(lap_data.len(), None);
+        }
+
+        let mut backwards = vec![None; lap_data.len()];
+        for (i, ld) in lap_data.iter().enumerate() {
+            let position = LapPosition {
+                lap: ld.current_lap_num(),
+                distance: ld.lap_distance(),
+                session_time,
+            };
+            if !position.distance.is_finite() {
+                continue;
+            }
+
+            // A flashback rewinds the session, the car can then be anywhere.
+            if let Some(previous) = self.positions[i] {
+                if previous.session_time <= session_time
+                    && previous.lap == position.lap
+                    && position.distance < previous.distance - self.lap_distance_tolerance
+                {
+                    backwards[i] = Some(previous.distance);
+                }
+            }
+            self.positions[i] = Some(position);
+        }
+
+        backwards
+    }
+}
+
+/// Range of the values of a field, if it has one.
+fn range(field: &str) -> Option<(f32, f32)> {
+    match field {
+        "throttle" | "brake" => Some((0.0, 1.0)),
+        "steer" => Some((-1.0, 1.0)),
+        "fuel_in_tank" | "fuel_capacity" | "ers_store_energy" => Some((0.0, f32::MAX)),
+        _ => None,
+    }
+}
@@ -0,0 +1,145 @@
+use getset::{CopyGetters, Getters};
+use std::collections::VecDeque;
+use std::convert::TryFrom;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use crate::packet::{peek_packet_format, peek_packet_id, PacketType, UnpackError};
+
+const WINDOW: Duration = Duration::from_secs(1);
+
+/// Report emitted by the [`Watchdog`] when packets stop making sense, which
+/// usually means the game sends a format this library does not support.
+///
+/// ## Specification
+/// ```text
+/// packet_format:     Packet format of the last failing packet
+/// packet_id:         Packet id of the last failing packet
+/// size:              Size in bytes of the last failing packet
+/// expected_size:     Size expected for this packet format and id, if known
+/// errors_per_second: Number of failures during the last second
+/// last_error:        Error of the last failing packet
+/// ```
+///
+/// [`Watchdog`]: ./struct.Watchdog.html
+#[derive(Debug, Clone, CopyGetters, Getters)]
+pub struct Diagnostic {
+    #[getset(get_copy = "pub")]
+    packet_format: Option<u16>,
+    #[getset(get_copy = "pub")]
+    packet_id: Option<u8>,
+    #[getset(get_copy = "pub")]
+    size: usize,
+    #[getset(get_copy = "pub")]
+    expected_size: Option<usize>,
+    #[getset(get_copy = "pub")]
+    errors_per_second: usize,
+    #[getset(get = "pub")]
+    last_error: String,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Possible unsupported game version/patch: {} errors in the last second \
+             (packet format: {:?}, packet id: {:?}, size: {} bytes, expected: {:?}). \
+             Last error: {}",
+            self.errors_per_second,
+            self.packet_format,
+            self.packet_id,
+            self.size,
+            self.expected_size,
+            self.last_error,
+        )
+    }
+}
+
+/// Watches the parse errors and packet size mismatches of a stream, and reports a
+/// [`Diagnostic`] when there are more than `threshold` of them in a second.
+///
+/// The diagnostic is reported once; the watchdog is re-armed when the error rate
+/// goes back under the threshold.
+///
+/// [`Diagnostic`]: ./struct.Diagnostic.html
+#[derive(Debug)]
+pub struct Watchdog {
+    threshold: usize,
+    auto_lenient: bool,
+    errors: VecDeque<Instant>,
+    reported: bool,
+}
+
+impl Watchdog {
+    pub fn new(threshold: usize) -> Watchdog {
+        Watchdog {
+            threshold,
+            auto_lenient: false,
+            errors: VecDeque::new(),
+            reported: false,
+        }
+    }
+
+    /// Switch the stream to lenient mode when the watchdog fires, so that
+    /// packets that cannot be parsed are returned as raw packets.
+    pub fn auto_lenient(mut self, auto_lenient: bool) -> Watchdog {
+        self.auto_lenient = auto_lenient;
+        self
+    }
+
+    pub fn is_auto_lenient(&self) -> bool {
+        self.auto_lenient
+    }
+
+    pub fn threshold(&self) -> usize {
+        self.threshold
+    }
+
+    /// Check the outcome of parsing `packet`, returning a diagnostic when the
+    /// threshold has just been exceeded.
+    pub fn check(&mut self, packet: &[u8], result: Result<(), &UnpackError>) -> Option<Diagnostic> {
+        let now = Instant::now();
+        while let Some(&t) = self.errors.front() {
+            if now.duration_since(t) > WINDOW {
+                self.errors.pop_front();
+            } else {
+                break;
+            }
+        }
+
+        let packet_format = peek_packet_format(packet);
+        let packet_id = peek_packet_id(packet);
+        let expected_size = match (packet_format, packet_id) {
+            (Some(format), Some(id)) => PacketType::try_from(id).ok().and_then(|t| t.size(format)),
+            _ => None,
+        };
+
+        let last_error = match (result, expected_size) {
+            (Err(e), _) => e.0.clone(),
+            (Ok(()), Some(expected)) if expected != packet.len() => String::from("Size mismatch"),
+            _ => {
+                if self.errors.len() <= self.threshold {
+                    self.reported = false;
+                }
+                return None;
+            }
+        };
+
+        self.errors.push_back(now);
+
+        if self.errors.len() <= self.threshold || self.reported {
+            return None;
+        }
+
+        self.reported = true;
+
+        Some(Diagnostic {
+            packet_format,
+            packet_id,
+            size: packet.len(),
+            expected_size,
+            errors_per_second: self.errors.len(),
+            last_error,
+        })
+    }
+}
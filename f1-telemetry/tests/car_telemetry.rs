@@ -0,0 +1,89 @@
+mod common;
+
+use f1_telemetry::packet::car_telemetry::{
+    ButtonFlags, MfdPanel, PacketCarTelemetryData, SurfaceType,
+};
+use f1_telemetry::packet::encode::encode_packet;
+use f1_telemetry::packet::{header_size, parse_packet_fuzz, Packet, PacketType};
+
+/// Car Telemetry packet of `size` bytes, with a zeroed body.
+fn zeroed(packet_format: u16, size: usize) -> Vec<u8> {
+    let mut buf = common::header(packet_format, PacketType::CarTelemetry.id());
+    buf.resize(size, 0);
+    buf
+}
+
+fn parse(buf: &[u8]) -> PacketCarTelemetryData {
+    match parse_packet_fuzz(buf).unwrap() {
+        Packet::CarTelemetry(p) => p,
+        p => panic!("Unexpected packet: {:?}", p),
+    }
+}
+
+#[test]
+fn parses_the_2020_trailer() {
+    let mut buf = zeroed(2020, 1307);
+    let trailer = header_size(2020) + 22 * 58;
+    buf[trailer..trailer + 4].copy_from_slice(&0x0010_0001u32.to_le_bytes()); // button_status
+    buf[trailer + 4] = 3; // mfd_panel
+    buf[trailer + 5] = 255; // mfd_panel_secondary_player
+    buf[trailer + 6] = 6; // suggested_gear
+
+    let packet = parse(&buf);
+
+    assert_eq!(
+        packet.buttons(),
+        Some(ButtonFlags::CROSS | ButtonFlags::UDP_ACTION_1)
+    );
+    assert_eq!(packet.mfd_panel(), Some(MfdPanel::Engine));
+    assert_eq!(packet.suggested_gear(), Some(6));
+    assert!(parse_packet_fuzz(&buf[..buf.len() - 1]).is_err());
+
+    assert_eq!(encode_packet(&Packet::CarTelemetry(packet)), buf);
+}
+
+#[test]
+fn parses_the_last_car_and_the_trailer_of_2021_packets() {
+    let sizes = [(2021, 1347), (2022, 1347), (2023, 1352), (2024, 1352)];
+    for &(packet_format, size) in sizes.iter() {
+        let mut buf = zeroed(packet_format, size);
+        let car = header_size(packet_format) + 21 * 60;
+        buf[car..car + 2].copy_from_slice(&312u16.to_le_bytes()); // speed
+        buf[car + 15] = 7; // gear
+        buf[car + 19] = 80; // rev_lights_percent
+        buf[car + 20..car + 22].copy_from_slice(&0x0fffu16.to_le_bytes()); // rev_lights_bit_value
+        buf[car + 30] = 95; // tyres_surface_temperature rear left
+        buf[car + 38..car + 40].copy_from_slice(&110u16.to_le_bytes()); // engine_temperature
+        buf[car + 59] = 7; // surface_types front right
+        let trailer = car + 60;
+        buf[trailer] = 1; // mfd_panel
+        buf[trailer + 1] = 255; // mfd_panel_secondary_player
+        buf[trailer + 2] = 8; // suggested_gear
+        assert_eq!(trailer + 3, size);
+
+        let packet = parse(&buf);
+        let telemetry = &packet.car_telemetry_data()[21];
+
+        assert_eq!(telemetry.speed(), 312, "{}", packet_format);
+        assert_eq!(telemetry.gear(), 7);
+        assert_eq!(telemetry.rev_lights_percent(), 80);
+        assert_eq!(telemetry.rev_lights_bit_value(), Some(0x0fff));
+        assert_eq!(telemetry.tyres_surface_temperature().rear_left(), 95);
+        assert_eq!(telemetry.engine_temperature(), 110);
+        assert_eq!(telemetry.surface_types().front_right(), SurfaceType::Grass);
+        assert_eq!(packet.button_status(), None);
+        assert_eq!(packet.mfd_panel(), Some(MfdPanel::Pits));
+        assert_eq!(packet.suggested_gear(), Some(8));
+        assert!(parse_packet_fuzz(&buf[..buf.len() - 1]).is_err());
+
+        assert_eq!(encode_packet(&Packet::CarTelemetry(packet)), buf);
+    }
+}
+
+#[test]
+fn older_telemetry_has_no_rev_lights_bit_value() {
+    let buf = zeroed(2020, 1307);
+
+    let packet = parse(&buf);
+    assert_eq!(packet.car_telemetry_data()[21].rev_lights_bit_value(), None);
+}
@@ -0,0 +1,40 @@
+#![allow(dead_code)]
+
+use f1_telemetry::packet::header_size;
+
+/// Header of a packet in the layout of its format, for a single player.
+pub fn header(packet_format: u16, packet_id: u8) -> Vec<u8> {
+    split_screen_header(packet_format, packet_id, 0, None)
+}
+
+/// Header of a packet in the layout of its format, with the car indexes of
+/// both players.
+pub fn split_screen_header(
+    packet_format: u16,
+    packet_id: u8,
+    player_car_index: u8,
+    secondary_player_car_index: Option<u8>,
+) -> Vec<u8> {
+    let mut buf = Vec::new();
+    buf.extend_from_slice(&packet_format.to_le_bytes());
+    if packet_format >= 2023 {
+        buf.push((packet_format % 100) as u8);
+    }
+    buf.push(1); // game_major_version
+    buf.push(0); // game_minor_version
+    buf.push(1); // packet_version
+    buf.push(packet_id);
+    buf.extend_from_slice(&0x0123_4567_89ab_cdefu64.to_le_bytes());
+    buf.extend_from_slice(&1.0f32.to_le_bytes());
+    buf.extend_from_slice(&60u32.to_le_bytes());
+    if packet_format >= 2023 {
+        buf.extend_from_slice(&60u32.to_le_bytes());
+    }
+    buf.push(player_car_index);
+    if packet_format >= 2020 {
+        buf.push(secondary_player_car_index.unwrap_or(255));
+    }
+
+    assert_eq!(buf.len(), header_size(packet_format));
+    buf
+}
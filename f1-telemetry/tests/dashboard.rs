@@ -0,0 +1,8 @@
+use f1_telemetry::output::dashboard::max_rate;
+
+#[test]
+fn fits_the_rate_in_the_serial_speed() {
+    assert_eq!(max_rate(9600), 45);
+    assert_eq!(max_rate(19200), 91);
+    assert_eq!(max_rate(115_200), 548);
+}
@@ -0,0 +1,114 @@
+mod common;
+
+use std::convert::TryFrom;
+
+use f1_telemetry::packet::encode::encode_packet;
+use f1_telemetry::packet::event::Event;
+use f1_telemetry::packet::{parse_packet_fuzz, Packet, PacketType};
+
+const EVENTS: [(&str, Event); 11] = [
+    ("SSTA", Event::SessionStarted),
+    ("SEND", Event::SessionEnded),
+    ("FTLP", Event::FastestLap),
+    ("RTMT", Event::Retirement),
+    ("DRSE", Event::DRSEnabled),
+    ("DRSD", Event::DRSDisabled),
+    ("TMPT", Event::TeamMateInPits),
+    ("CHQF", Event::ChequeredFlag),
+    ("RCWN", Event::RaceWinner),
+    ("PENA", Event::Penalty),
+    ("SPTP", Event::SpeedTrap),
+];
+
+/// Codes of the events added from F1 2021.
+const LATER_CODES: [&str; 11] = [
+    "STLG", "LGOT", "DTSV", "SGSV", "FLBK", "BUTN", "RDFL", "OVTK", "SCAR", "COLL", "RTMX",
+];
+
+fn event_packet(packet_format: u16, code: &[u8; 4]) -> Vec<u8> {
+    let mut buf = common::header(packet_format, PacketType::Event.id());
+    buf.extend_from_slice(code);
+    buf.resize(buf.len() + 12, 0);
+    buf
+}
+
+#[test]
+fn converts_the_event_codes() {
+    for &(code, event) in EVENTS.iter() {
+        assert_eq!(Event::try_from(code).unwrap(), event);
+        assert_eq!(event.code(), code);
+    }
+}
+
+#[test]
+fn converts_the_later_event_codes() {
+    for &code in LATER_CODES.iter() {
+        let event = Event::try_from(code).unwrap();
+
+        let mut bytes = [0; 4];
+        bytes.copy_from_slice(code.as_bytes());
+        assert_eq!(event, Event::Unknown(bytes));
+        assert_eq!(event.code(), code);
+    }
+}
+
+#[test]
+fn rejects_codes_of_the_wrong_length() {
+    assert!(Event::try_from("SST").is_err());
+    assert!(Event::try_from("SSTAR").is_err());
+}
+
+#[test]
+fn parses_the_later_events() {
+    for &packet_format in [2021, 2022, 2023, 2024].iter() {
+        for &code in LATER_CODES.iter() {
+            let mut bytes = [0; 4];
+            bytes.copy_from_slice(code.as_bytes());
+            let buf = event_packet(packet_format, &bytes);
+
+            let packet = parse_packet_fuzz(&buf)
+                .unwrap_or_else(|e| panic!("{} {}: {}", code, packet_format, e.0));
+            let p = match &packet {
+                Packet::Event(p) => p,
+                p => panic!("Unexpected packet: {:?}", p),
+            };
+            assert_eq!(p.event(), Event::Unknown(bytes));
+            assert_eq!(p.vehicle_idx(), None);
+            assert_eq!(p.lap_time(), None);
+
+            let encoded = encode_packet(&packet);
+            assert_eq!(&encoded[..buf.len() - 12], &buf[..buf.len() - 12]);
+        }
+    }
+}
+
+#[test]
+fn parses_codes_that_are_not_utf8() {
+    let buf = event_packet(2024, &[0xff, 0xfe, b'A', b'B']);
+
+    match parse_packet_fuzz(&buf).unwrap() {
+        Packet::Event(p) => {
+            assert_eq!(p.event(), Event::Unknown([0xff, 0xfe, b'A', b'B']));
+            assert_eq!(p.event().code(), "????");
+        }
+        p => panic!("Unexpected packet: {:?}", p),
+    }
+}
+
+#[test]
+fn round_trips_the_known_events() {
+    for &(code, event) in EVENTS.iter() {
+        let mut bytes = [0; 4];
+        bytes.copy_from_slice(code.as_bytes());
+        // The details of F1 2020 events are 7 bytes long
+        let mut buf = event_packet(2020, &bytes);
+        buf.truncate(buf.len() - 5);
+
+        let packet = parse_packet_fuzz(&buf).unwrap();
+        match &packet {
+            Packet::Event(p) => assert_eq!(p.event(), event),
+            p => panic!("Unexpected packet: {:?}", p),
+        }
+        assert_eq!(encode_packet(&packet), buf);
+    }
+}
@@ -0,0 +1,29 @@
+use f1_telemetry::fmt::{transliterate, truncate};
+
+#[test]
+fn truncates_with_an_ellipsis() {
+    assert_eq!(truncate("Räikkönen", 6, false), "Räikk…");
+    assert_eq!(truncate("Räikkönen", 9, false), "Räikkönen");
+    assert_eq!(truncate("Räikkönen", 0, false), "");
+}
+
+#[test]
+fn truncates_with_an_ascii_ellipsis() {
+    assert_eq!(truncate("Raikkonen", 6, true), "Rai...");
+    assert_eq!(truncate("Raikkonen", 2, true), "Ra");
+}
+
+#[test]
+fn truncates_wide_characters_by_columns() {
+    assert_eq!(truncate("周冠宇", 4, false), "周…");
+    assert_eq!(truncate("周冠宇", 6, false), "周冠宇");
+}
+
+#[test]
+fn transliterates_to_ascii() {
+    assert_eq!(transliterate("Räikkönen"), "Raikkonen");
+    assert_eq!(transliterate("Pérez"), "Perez");
+    assert_eq!(transliterate("Hülkenberg"), "Hulkenberg");
+    assert_eq!(transliterate("Łukasz Żyła"), "Lukasz Zyla");
+    assert_eq!(transliterate("周"), "?");
+}
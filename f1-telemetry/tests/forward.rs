@@ -0,0 +1,87 @@
+use f1_telemetry::forward::{Key, OpenError, Opener, Sealer, SEAL_OVERHEAD};
+
+const DATAGRAM: &[u8] = b"F1 telemetry datagram";
+
+fn key() -> Key {
+    Key::from_hex("000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f").unwrap()
+}
+
+#[test]
+fn opens_sealed_datagrams() {
+    let mut sealer = Sealer::new(key());
+    let mut opener = Opener::new(key());
+
+    for _ in 0..3 {
+        let sealed = sealer.seal(DATAGRAM);
+        assert_eq!(sealed.len(), DATAGRAM.len() + SEAL_OVERHEAD);
+        assert!(!sealed.windows(DATAGRAM.len()).any(|w| w == DATAGRAM));
+        assert_eq!(opener.open(&sealed).unwrap(), DATAGRAM);
+    }
+}
+
+#[test]
+fn rejects_altered_datagrams() {
+    let sealed = Sealer::new(key()).seal(DATAGRAM);
+
+    // The magic is checked before the tag, every other byte is authenticated.
+    for i in 4..sealed.len() {
+        let mut altered = sealed.clone();
+        altered[i] ^= 0x01;
+
+        let mut opener = Opener::new(key());
+        assert_eq!(opener.open(&altered), Err(OpenError::Forged), "byte {}", i);
+        // The forgery did not use up the counter of the sender.
+        assert_eq!(opener.open(&sealed).unwrap(), DATAGRAM, "byte {}", i);
+    }
+}
+
+#[test]
+fn rejects_truncated_datagrams() {
+    let sealed = Sealer::new(key()).seal(DATAGRAM);
+    let mut opener = Opener::new(key());
+
+    assert_eq!(
+        opener.open(&sealed[..sealed.len() - 1]),
+        Err(OpenError::Forged)
+    );
+    assert_eq!(
+        opener.open(&sealed[..SEAL_OVERHEAD - 1]),
+        Err(OpenError::NotSealed)
+    );
+}
+
+#[test]
+fn rejects_datagrams_sealed_with_another_key() {
+    let other = Key::new([0x42; 32]);
+    let sealed = Sealer::new(other).seal(DATAGRAM);
+
+    assert_eq!(Opener::new(key()).open(&sealed), Err(OpenError::Forged));
+}
+
+#[test]
+fn rejects_replayed_datagrams() {
+    let mut sealer = Sealer::new(key());
+    let mut opener = Opener::new(key());
+
+    let first = sealer.seal(DATAGRAM);
+    let second = sealer.seal(DATAGRAM);
+    assert!(opener.open(&second).is_ok());
+    assert!(opener.open(&first).is_ok());
+    assert_eq!(opener.open(&first), Err(OpenError::Replayed));
+    assert_eq!(opener.open(&second), Err(OpenError::Replayed));
+}
+
+#[test]
+fn rejects_datagrams_that_are_not_sealed() {
+    assert_eq!(Opener::new(key()).open(&[0; 64]), Err(OpenError::NotSealed));
+}
+
+#[test]
+fn draws_a_sender_id_per_sealer() {
+    let nonce_prefix = |sealed: &[u8]| sealed[4..12].to_vec();
+
+    let first = Sealer::new(key()).seal(DATAGRAM);
+    let second = Sealer::new(key()).seal(DATAGRAM);
+    assert_ne!(nonce_prefix(&first), nonce_prefix(&second));
+    assert_ne!(first, second);
+}
@@ -0,0 +1,68 @@
+mod common;
+
+use f1_telemetry::analysis::ghost::Ghost;
+use f1_telemetry::packet::car_telemetry::PacketCarTelemetryData;
+use f1_telemetry::packet::lap::PacketLapData;
+use f1_telemetry::packet::{header_size, parse_packet_fuzz, Packet, PacketType};
+
+/// Lap Data of F1 2019 for the player, on `lap` at `lap_distance`.
+fn lap_data(lap: u8, lap_distance: f32, last_lap_time: f32) -> PacketLapData {
+    let mut buf = common::header(2019, PacketType::LapData.id());
+    buf.resize(PacketType::LapData.size(2019).unwrap(), 0);
+    let car = header_size(2019);
+    buf[car..car + 4].copy_from_slice(&last_lap_time.to_le_bytes());
+    buf[car + 4..car + 8].copy_from_slice(&(lap_distance / 50.0).to_le_bytes()); // current_lap_time
+    buf[car + 20..car + 24].copy_from_slice(&lap_distance.to_le_bytes());
+    buf[car + 33] = lap; // current_lap_num
+
+    match parse_packet_fuzz(&buf).unwrap() {
+        Packet::Lap(p) => p,
+        p => panic!("Unexpected packet: {:?}", p),
+    }
+}
+
+fn car_telemetry() -> PacketCarTelemetryData {
+    let mut buf = common::header(2019, PacketType::CarTelemetry.id());
+    buf.resize(PacketType::CarTelemetry.size(2019).unwrap(), 0);
+
+    match parse_packet_fuzz(&buf).unwrap() {
+        Packet::CarTelemetry(p) => p,
+        p => panic!("Unexpected packet: {:?}", p),
+    }
+}
+
+/// Drive a lap from `start` to the end of a 5 km track, then cross the line.
+fn drive(ghost: &mut Ghost, lap: u8, start: f32, lap_time: f32) {
+    let mut distance = start;
+    while distance < 5000.0 {
+        ghost.update_lap(&lap_data(lap, distance, 0.0));
+        distance += 100.0;
+    }
+    ghost.update_lap(&lap_data(lap + 1, 0.5, lap_time));
+}
+
+#[test]
+fn discards_the_lap_joined_halfway() {
+    let mut ghost = Ghost::new();
+    ghost.update_telemetry(&car_telemetry());
+
+    // The recording starts halfway around the lap, which looks fast.
+    drive(&mut ghost, 3, 2500.0, 50.0);
+    assert_eq!(ghost.best_lap_time(), None);
+
+    drive(&mut ghost, 4, 0.5, 90.0);
+    assert_eq!(ghost.best_lap_time(), Some(90.0));
+    assert_eq!(ghost.best_lap().first().unwrap().lap_distance(), 0.5);
+}
+
+#[test]
+fn keeps_the_lap_started_at_the_line() {
+    let mut ghost = Ghost::new();
+    ghost.update_telemetry(&car_telemetry());
+
+    drive(&mut ghost, 1, 10.0, 92.0);
+    assert_eq!(ghost.best_lap_time(), Some(92.0));
+
+    drive(&mut ghost, 2, 0.5, 91.0);
+    assert_eq!(ghost.best_lap_time(), Some(91.0));
+}
@@ -0,0 +1,106 @@
+use std::io::Cursor;
+
+use f1_telemetry::packet::encode::encode_packet;
+use f1_telemetry::packet::header::PacketHeader;
+use f1_telemetry::packet::{
+    header_size, parse_packet_fuzz, peek_packet_id, peek_session_id, Packet, PacketType,
+};
+use f1_telemetry::sequence::{Sequence, SequencePolicy, Sequencer};
+
+const SESSION_UID: u64 = 0x0123_4567_89ab_cdef;
+
+/// Header of an F1 2023+ packet, with the game year and overall frame identifier.
+fn header_2023(packet_format: u16, packet_id: u8, frame_identifier: u32) -> Vec<u8> {
+    let mut buf = Vec::new();
+    buf.extend_from_slice(&packet_format.to_le_bytes());
+    buf.push((packet_format % 100) as u8);
+    buf.push(1); // game_major_version
+    buf.push(5); // game_minor_version
+    buf.push(1); // packet_version
+    buf.push(packet_id);
+    buf.extend_from_slice(&SESSION_UID.to_le_bytes());
+    buf.extend_from_slice(&12.5f32.to_le_bytes());
+    buf.extend_from_slice(&frame_identifier.to_le_bytes());
+    buf.extend_from_slice(&(frame_identifier + 1000).to_le_bytes());
+    buf.push(3); // player_car_index
+    buf.push(255); // secondary_player_car_index
+
+    buf
+}
+
+#[test]
+fn parses_the_2023_header() {
+    for &packet_format in [2023, 2024].iter() {
+        let buf = header_2023(packet_format, PacketType::TimeTrial.id(), 42);
+        assert_eq!(buf.len(), header_size(packet_format));
+
+        let mut cursor = Cursor::new(&buf[..]);
+        let header = PacketHeader::new(&mut cursor);
+
+        assert_eq!(cursor.position() as usize, buf.len());
+        assert_eq!(header.packet_format(), packet_format);
+        assert_eq!(header.game_year(), Some((packet_format % 100) as u8));
+        assert_eq!(header.game_major_version(), 1);
+        assert_eq!(header.game_minor_version(), 5);
+        assert_eq!(header.packet_version(), 1);
+        assert_eq!(header.packet_id(), PacketType::TimeTrial.id());
+        assert_eq!(header.session_id().value(), SESSION_UID);
+        assert_eq!(header.session_time(), 12.5);
+        assert_eq!(header.frame_identifier(), 42);
+        assert_eq!(header.overall_frame_identifier(), Some(1042));
+        assert_eq!(header.player_car_index(), 3);
+        assert_eq!(header.secondary_player_car_index(), None);
+    }
+}
+
+#[test]
+fn older_headers_have_no_game_year() {
+    let mut buf = vec![0u8; header_size(2022)];
+    buf[..2].copy_from_slice(&2022u16.to_le_bytes());
+    buf[5] = PacketType::LapData.id();
+    buf[23] = 255;
+
+    let mut cursor = Cursor::new(&buf[..]);
+    let header = PacketHeader::new(&mut cursor);
+
+    assert_eq!(cursor.position() as usize, buf.len());
+    assert_eq!(header.packet_id(), PacketType::LapData.id());
+    assert_eq!(header.game_year(), None);
+    assert_eq!(header.overall_frame_identifier(), None);
+}
+
+#[test]
+fn round_trips_the_2023_header() {
+    for &packet_format in [2023, 2024].iter() {
+        // Header followed by an empty time trial body
+        let mut buf = header_2023(packet_format, PacketType::TimeTrial.id(), 42);
+        buf.resize(101, 0);
+
+        let packet = parse_packet_fuzz(&buf).unwrap();
+        assert!(matches!(packet, Packet::TimeTrial(_)));
+        assert_eq!(encode_packet(&packet), buf);
+    }
+}
+
+#[test]
+fn peeks_the_2023_header() {
+    let buf = header_2023(2024, PacketType::CarTelemetry.id(), 42);
+
+    assert_eq!(peek_packet_id(&buf), Some(PacketType::CarTelemetry.id()));
+    assert_eq!(peek_session_id(&buf).map(|s| s.value()), Some(SESSION_UID));
+}
+
+#[test]
+fn sequences_the_2023_header() {
+    let mut sequencer = Sequencer::new(SequencePolicy::DropStale);
+
+    let packet = header_2023(2024, PacketType::CarTelemetry.id(), 100);
+    assert_eq!(sequencer.check(&packet), Sequence::InOrder);
+    assert_eq!(sequencer.check(&packet), Sequence::Duplicate);
+
+    let packet = header_2023(2024, PacketType::CarTelemetry.id(), 99);
+    assert_eq!(sequencer.check(&packet), Sequence::OutOfOrder);
+
+    let packet = header_2023(2024, PacketType::CarTelemetry.id(), 101);
+    assert_eq!(sequencer.check(&packet), Sequence::InOrder);
+}
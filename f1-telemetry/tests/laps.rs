@@ -0,0 +1,39 @@
+mod common;
+
+use f1_telemetry::packet::lap::PacketLapData;
+use f1_telemetry::packet::{header_size, parse_packet_fuzz, Packet, PacketType};
+use f1_telemetry::session::LapTracker;
+
+/// Lap Data of F1 2019, with the first car on `lap` and `last_lap_time` as the
+/// time of its previous lap.
+fn lap_data(lap: u8, last_lap_time: f32) -> PacketLapData {
+    let mut buf = common::header(2019, PacketType::LapData.id());
+    buf.resize(PacketType::LapData.size(2019).unwrap(), 0);
+    let car = header_size(2019);
+    buf[car..car + 4].copy_from_slice(&last_lap_time.to_le_bytes());
+    buf[car + 33] = lap; // current_lap_num
+
+    match parse_packet_fuzz(&buf).unwrap() {
+        Packet::Lap(p) => p,
+        p => panic!("Unexpected packet: {:?}", p),
+    }
+}
+
+#[test]
+fn compares_the_last_lap_to_the_previous_best() {
+    let mut tracker = LapTracker::new();
+    tracker.update(&lap_data(1, 0.0));
+    tracker.update(&lap_data(2, 92.0));
+    assert_eq!(tracker.last_lap_delta(0), None);
+
+    tracker.update(&lap_data(3, 93.0));
+    assert_eq!(tracker.last_lap_delta(0), Some(1.0));
+
+    // A new best lap is a gain over the previous one, not a zero delta.
+    tracker.update(&lap_data(4, 91.5));
+    assert_eq!(tracker.last_lap_delta(0), Some(-0.5));
+    assert_eq!(tracker.best_lap(0).unwrap().lap_time(), 91.5);
+
+    tracker.update(&lap_data(5, 91.75));
+    assert_eq!(tracker.last_lap_delta(0), Some(0.25));
+}
@@ -0,0 +1,113 @@
+#![cfg(feature = "overlay")]
+
+mod common;
+
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use f1_telemetry::overlay::Overlay;
+use f1_telemetry::packet::{header_size, parse_packet_fuzz, PacketType};
+
+/// Serve an overlay on a free local port, returning its address.
+fn serve(overlay: &Overlay) -> String {
+    let addr = TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap();
+    overlay.serve(addr).unwrap();
+
+    addr.to_string()
+}
+
+fn get(addr: &str, path: &str) -> String {
+    let mut conn = TcpStream::connect(addr).unwrap();
+    conn.set_read_timeout(Some(Duration::from_secs(2))).unwrap();
+    write!(conn, "GET {} HTTP/1.1\r\nHost: localhost\r\n\r\n", path).unwrap();
+
+    let mut response = String::new();
+    conn.read_to_string(&mut response).unwrap();
+    response
+}
+
+#[test]
+fn serves_while_a_client_is_silent() {
+    let overlay = Overlay::new();
+    let addr = serve(&overlay);
+
+    // Connected, but never sending its request
+    let _silent = TcpStream::connect(&addr).unwrap();
+    let mut partial = TcpStream::connect(&addr).unwrap();
+    partial.write_all(b"GET /standings HTTP/1.1\r\n").unwrap();
+
+    let response = get(&addr, "/standings");
+    assert!(response.starts_with("HTTP/1.1 200 OK"), "{}", response);
+}
+
+#[test]
+fn serves_while_an_event_stream_is_open() {
+    let overlay = Overlay::new();
+    let addr = serve(&overlay);
+
+    let mut events = TcpStream::connect(&addr).unwrap();
+    events.write_all(b"GET /events HTTP/1.1\r\n\r\n").unwrap();
+
+    let response = get(&addr, "/driver/30");
+    assert!(
+        response.starts_with("HTTP/1.1 404 Not Found"),
+        "{}",
+        response
+    );
+}
+
+#[test]
+fn writes_non_finite_times_as_null() {
+    let mut buf = common::header(2019, PacketType::LapData.id());
+    buf.resize(PacketType::LapData.size(2019).unwrap(), 0);
+    let car = header_size(2019);
+    buf[car + 4..car + 8].copy_from_slice(&f32::NAN.to_le_bytes()); // current_lap_time
+    buf[car + 8..car + 12].copy_from_slice(&f32::INFINITY.to_le_bytes()); // best_lap_time
+    buf[car + 32] = 1; // car_position
+    buf[car + 33] = 2; // current_lap_num
+    buf[car + 40] = 2; // result_status
+
+    let overlay = Overlay::new();
+    overlay.record(&parse_packet_fuzz(&buf).unwrap());
+
+    let standings = overlay.standings_json();
+    assert!(
+        standings.contains("\"current_lap_time\":null"),
+        "{}",
+        standings
+    );
+    assert!(standings.contains("\"best_lap\":null"), "{}", standings);
+    assert!(!standings.contains("NaN"), "{}", standings);
+    assert!(!standings.contains("inf"), "{}", standings);
+}
+
+#[test]
+fn unsubscribes_disconnected_event_streams() {
+    let overlay = Overlay::new();
+    let addr = serve(&overlay);
+
+    let mut events = TcpStream::connect(&addr).unwrap();
+    events.write_all(b"GET /events HTTP/1.1\r\n\r\n").unwrap();
+    wait_for_subscribers(&overlay, 1);
+
+    drop(events);
+    wait_for_subscribers(&overlay, 0);
+}
+
+fn wait_for_subscribers(overlay: &Overlay, subscribers: usize) {
+    let start = Instant::now();
+    while overlay.subscribers() != subscribers {
+        assert!(
+            start.elapsed() < Duration::from_secs(2),
+            "{} subscribers, expected {}",
+            overlay.subscribers(),
+            subscribers
+        );
+        thread::sleep(Duration::from_millis(10));
+    }
+}
@@ -0,0 +1,76 @@
+mod common;
+
+use f1_telemetry::packet::{parse_packet_fuzz, PacketType, MAX_PACKET_SIZE};
+
+const PACKET_FORMATS: [u16; 6] = [2019, 2020, 2021, 2022, 2023, 2024];
+
+const PACKET_TYPES: [PacketType; 14] = [
+    PacketType::Motion,
+    PacketType::Session,
+    PacketType::LapData,
+    PacketType::Event,
+    PacketType::Participants,
+    PacketType::CarSetups,
+    PacketType::CarTelemetry,
+    PacketType::CarStatus,
+    PacketType::FinalClassification,
+    PacketType::LobbyInfo,
+    PacketType::CarDamage,
+    PacketType::TyreSets,
+    PacketType::MotionEx,
+    PacketType::TimeTrial,
+];
+
+/// First format sending this type of packet.
+fn first_format(packet_type: PacketType) -> u16 {
+    match packet_type {
+        PacketType::FinalClassification | PacketType::LobbyInfo => 2020,
+        PacketType::CarDamage => 2021,
+        PacketType::TyreSets | PacketType::MotionEx => 2023,
+        PacketType::TimeTrial => 2024,
+        _ => 2019,
+    }
+}
+
+#[test]
+fn knows_the_size_of_every_packet() {
+    for &packet_type in PACKET_TYPES.iter() {
+        for &packet_format in PACKET_FORMATS.iter() {
+            let size = packet_type.size(packet_format);
+            assert_eq!(
+                size.is_some(),
+                packet_format >= first_format(packet_type),
+                "{:?} {}",
+                packet_type,
+                packet_format
+            );
+
+            let size = match size {
+                Some(size) => size,
+                None => continue,
+            };
+            assert!(size < MAX_PACKET_SIZE);
+
+            let mut buf = common::header(packet_format, packet_type.id());
+            buf.resize(size, 0);
+
+            assert!(
+                parse_packet_fuzz(&buf).is_ok(),
+                "{:?} {}",
+                packet_type,
+                packet_format
+            );
+
+            // The trailing fields of the session and the details of the events
+            // other than the one sent are not read.
+            if packet_type != PacketType::Session && packet_type != PacketType::Event {
+                assert!(
+                    parse_packet_fuzz(&buf[..size - 1]).is_err(),
+                    "{:?} {}",
+                    packet_type,
+                    packet_format
+                );
+            }
+        }
+    }
+}
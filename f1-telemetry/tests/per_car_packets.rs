@@ -0,0 +1,356 @@
+mod common;
+
+use f1_telemetry::packet::encode::encode_packet;
+use f1_telemetry::packet::lap::ResultStatus;
+use f1_telemetry::packet::lobby_info::ReadyStatus;
+use f1_telemetry::packet::participants::{Team, Telemetry};
+use f1_telemetry::packet::{header_size, num_cars, parse_packet_fuzz, Packet, PacketType};
+
+const PACKET_FORMATS: [u16; 6] = [2019, 2020, 2021, 2022, 2023, 2024];
+
+const PACKET_TYPES: [PacketType; 5] = [
+    PacketType::LapData,
+    PacketType::Participants,
+    PacketType::CarSetups,
+    PacketType::CarTelemetry,
+    PacketType::CarStatus,
+];
+
+/// Packet of the expected size for its type and format, with a zeroed body.
+fn zeroed(packet_format: u16, packet_type: PacketType) -> Vec<u8> {
+    let mut buf = common::header(packet_format, packet_type.id());
+    buf.resize(packet_type.size(packet_format).unwrap(), 0);
+    buf
+}
+
+fn num_parsed_cars(packet: &Packet) -> usize {
+    match packet {
+        Packet::Lap(p) => p.lap_data().len(),
+        Packet::Participants(p) => p.participants().len(),
+        Packet::CarSetups(p) => p.car_setups().len(),
+        Packet::CarTelemetry(p) => p.car_telemetry_data().len(),
+        Packet::CarStatus(p) => p.car_status_data().len(),
+        p => panic!("Unexpected packet: {:?}", p),
+    }
+}
+
+#[test]
+fn parses_packets_of_the_expected_size() {
+    for &packet_format in PACKET_FORMATS.iter() {
+        for &packet_type in PACKET_TYPES.iter() {
+            let buf = zeroed(packet_format, packet_type);
+
+            let packet = parse_packet_fuzz(&buf)
+                .unwrap_or_else(|e| panic!("{:?} {}: {}", packet_type, packet_format, e.0));
+            assert_eq!(
+                num_parsed_cars(&packet),
+                num_cars(packet_format),
+                "{:?} {}",
+                packet_type,
+                packet_format
+            );
+            assert_eq!(
+                encode_packet(&packet).len(),
+                buf.len(),
+                "{:?} {}",
+                packet_type,
+                packet_format
+            );
+
+            assert!(
+                parse_packet_fuzz(&buf[..buf.len() - 1]).is_err(),
+                "{:?} {}",
+                packet_type,
+                packet_format
+            );
+        }
+    }
+}
+
+#[test]
+fn parses_the_last_car_of_2021_lap_data() {
+    let mut buf = zeroed(2021, PacketType::LapData);
+    let car = header_size(2021) + 21 * 43;
+    buf[car..car + 4].copy_from_slice(&90_123u32.to_le_bytes()); // last_lap_time
+    buf[car + 8..car + 10].copy_from_slice(&30_500u16.to_le_bytes()); // sector_1_time
+    buf[car + 24] = 22; // car_position
+    buf[car + 27] = 2; // num_pit_stops
+    buf[car + 31] = 3; // warnings
+    buf[car + 36] = 4; // result_status
+    buf[car + 38..car + 40].copy_from_slice(&21_000u16.to_le_bytes()); // pit_lane_time_in_lane
+
+    let packet = parse_packet_fuzz(&buf).unwrap();
+    let lap = match &packet {
+        Packet::Lap(p) => &p.lap_data()[21],
+        p => panic!("Unexpected packet: {:?}", p),
+    };
+
+    assert_eq!(lap.last_lap_time(), 90.123);
+    assert_eq!(lap.sector_1_time(), 30.5);
+    assert_eq!(lap.best_lap_time(), 0.0);
+    assert_eq!(lap.car_position(), 22);
+    assert_eq!(lap.num_pit_stops(), Some(2));
+    assert_eq!(lap.warnings(), Some(3));
+    assert_eq!(lap.corner_cutting_warnings(), None);
+    assert_eq!(lap.result_status(), ResultStatus::DidNotFinish);
+    assert_eq!(lap.pit_lane_time_in_lane(), Some(21.0));
+    assert_eq!(lap.speed_trap_fastest_speed(), None);
+
+    match &packet {
+        Packet::Lap(p) => assert_eq!(p.time_trial_pb_car_idx(), None),
+        p => panic!("Unexpected packet: {:?}", p),
+    }
+
+    assert_eq!(encode_packet(&packet), buf);
+}
+
+#[test]
+fn parses_the_last_car_of_2024_lap_data() {
+    let mut buf = zeroed(2024, PacketType::LapData);
+    let car = header_size(2024) + 21 * 57;
+    buf[car + 8..car + 10].copy_from_slice(&5_250u16.to_le_bytes()); // sector_1_time
+    buf[car + 10] = 1; // sector_1_time minutes
+    buf[car + 14..car + 16].copy_from_slice(&1_500u16.to_le_bytes()); // delta_to_car_in_front
+    buf[car + 32] = 22; // car_position
+    buf[car + 40] = 5; // corner_cutting_warnings
+    buf[car + 52..car + 56].copy_from_slice(&330.5f32.to_le_bytes()); // speed_trap_fastest_speed
+    buf[car + 56] = 7; // speed_trap_fastest_lap
+    let len = buf.len();
+    buf[len - 2] = 3; // time_trial_pb_car_idx
+    buf[len - 1] = 255; // time_trial_rival_car_idx
+
+    let packet = parse_packet_fuzz(&buf).unwrap();
+    let p = match &packet {
+        Packet::Lap(p) => p,
+        p => panic!("Unexpected packet: {:?}", p),
+    };
+    let lap = &p.lap_data()[21];
+
+    assert_eq!(lap.sector_1_time(), 65.25);
+    assert_eq!(lap.delta_to_car_in_front(), Some(1.5));
+    assert_eq!(lap.car_position(), 22);
+    assert_eq!(lap.corner_cutting_warnings(), Some(5));
+    assert_eq!(lap.speed_trap_fastest_speed(), Some(330.5));
+    assert_eq!(lap.speed_trap_fastest_lap(), Some(7));
+    assert_eq!(p.time_trial_pb_car_idx(), Some(3));
+    assert_eq!(p.time_trial_rival_car_idx(), None);
+
+    assert_eq!(encode_packet(&packet), buf);
+}
+
+#[test]
+fn parses_the_last_car_of_2023_car_status() {
+    let mut buf = zeroed(2023, PacketType::CarStatus);
+    let car = header_size(2023) + 21 * 55;
+    buf[car + 5..car + 9].copy_from_slice(&42.5f32.to_le_bytes()); // fuel_in_tank
+    buf[car + 23..car + 25].copy_from_slice(&150u16.to_le_bytes()); // drs_activation_distance
+    buf[car + 25] = 18; // actual_tyre_compound
+    buf[car + 27] = 9; // tyres_age_laps
+    buf[car + 29..car + 33].copy_from_slice(&600_000f32.to_le_bytes()); // engine_power_ice
+    buf[car + 37..car + 41].copy_from_slice(&4_000_000f32.to_le_bytes()); // ers_store_energy
+    buf[car + 54] = 1; // network_paused
+
+    let packet = parse_packet_fuzz(&buf).unwrap();
+    let status = match &packet {
+        Packet::CarStatus(p) => &p.car_status_data()[21],
+        p => panic!("Unexpected packet: {:?}", p),
+    };
+
+    assert_eq!(status.fuel_in_tank(), 42.5);
+    assert_eq!(status.drs_activation_distance(), Some(150));
+    assert_eq!(status.actual_tyre_compound().name(), "C3");
+    assert_eq!(status.tyres_age_laps(), Some(9));
+    assert_eq!(status.engine_power_ice(), Some(600_000.0));
+    assert_eq!(status.ers_store_energy(), 4_000_000.0);
+    assert_eq!(status.network_paused(), Some(true));
+    assert_eq!(status.drs_fault(), None);
+
+    assert_eq!(encode_packet(&packet), buf);
+}
+
+#[test]
+fn parses_the_last_car_of_2024_car_setups() {
+    let mut buf = zeroed(2024, PacketType::CarSetups);
+    let car = header_size(2024) + 21 * 50;
+    buf[car] = 12; // front_wing
+    buf[car + 28] = 80; // engine_braking
+    buf[car + 29..car + 33].copy_from_slice(&21.0f32.to_le_bytes()); // rear_left
+    buf[car + 33..car + 37].copy_from_slice(&21.5f32.to_le_bytes()); // rear_right
+    buf[car + 37..car + 41].copy_from_slice(&23.0f32.to_le_bytes()); // front_left
+    buf[car + 41..car + 45].copy_from_slice(&24.0f32.to_le_bytes()); // front_right
+    buf[car + 46..car + 50].copy_from_slice(&10.0f32.to_le_bytes()); // fuel_load
+    let len = buf.len();
+    buf[len - 4..].copy_from_slice(&14.0f32.to_le_bytes()); // next_front_wing_value
+
+    let packet = parse_packet_fuzz(&buf).unwrap();
+    let p = match &packet {
+        Packet::CarSetups(p) => p,
+        p => panic!("Unexpected packet: {:?}", p),
+    };
+    let setup = &p.car_setups()[21];
+
+    assert_eq!(setup.front_wing(), 12);
+    assert_eq!(setup.engine_braking(), Some(80));
+    assert_eq!(setup.tyres_pressure().rear_right(), 21.5);
+    assert_eq!(setup.front_tyre_pressure(), 23.5);
+    assert_eq!(setup.rear_tyre_pressure(), 21.25);
+    assert_eq!(setup.fuel_load(), 10.0);
+    assert_eq!(p.next_front_wing_value(), Some(14.0));
+
+    assert_eq!(encode_packet(&packet), buf);
+}
+
+#[test]
+fn parses_the_last_car_of_car_damage() {
+    for &packet_format in [2022, 2023, 2024].iter() {
+        let mut buf = zeroed(packet_format, PacketType::CarDamage);
+        let car = header_size(packet_format) + 21 * 42;
+        buf[car + 12..car + 16].copy_from_slice(&35.5f32.to_le_bytes()); // front_right tyres_wear
+        buf[car + 24] = 20; // front_left_wing_damage
+        buf[car + 31] = 1; // ers_fault
+        buf[car + 39] = 9; // engine_tc_wear
+        buf[car + 41] = 1; // engine_seized
+
+        let packet = parse_packet_fuzz(&buf).unwrap();
+        let damage = match &packet {
+            Packet::CarDamage(p) => &p.car_damage_data()[21],
+            p => panic!("Unexpected packet: {:?}", p),
+        };
+
+        assert_eq!(damage.tyres_wear().front_right(), 35.5, "{}", packet_format);
+        assert_eq!(damage.front_left_wing_damage(), 20);
+        assert!(damage.ers_fault());
+        assert_eq!(damage.engine_tc_wear(), 9);
+        assert!(!damage.engine_blown());
+        assert!(damage.engine_seized());
+        assert!(parse_packet_fuzz(&buf[..buf.len() - 1]).is_err());
+
+        assert_eq!(encode_packet(&packet), buf);
+    }
+}
+
+#[test]
+fn parses_the_last_car_of_2021_car_damage() {
+    let mut buf = zeroed(2021, PacketType::CarDamage);
+    let car = header_size(2021) + 21 * 39;
+    buf[car + 31] = 15; // gear_box_damage
+    buf[car + 38] = 9; // engine_tc_wear
+
+    let packet = parse_packet_fuzz(&buf).unwrap();
+    let damage = match &packet {
+        Packet::CarDamage(p) => &p.car_damage_data()[21],
+        p => panic!("Unexpected packet: {:?}", p),
+    };
+
+    assert_eq!(damage.gear_box_damage(), 15);
+    assert_eq!(damage.engine_tc_wear(), 9);
+    assert!(!damage.ers_fault());
+
+    assert_eq!(encode_packet(&packet), buf);
+}
+
+#[test]
+fn parses_the_last_car_of_2024_participants() {
+    let mut buf = zeroed(2024, PacketType::Participants);
+    let header_size = header_size(2024);
+    buf[header_size] = 22; // num_active_cars
+    let car = header_size + 1 + 21 * 60;
+    buf[car + 2] = 17; // network_id
+    buf[car + 4] = 1; // my_team
+    buf[car + 5] = 44; // race_number
+    buf[car + 7..car + 11].copy_from_slice(b"Lula");
+    buf[car + 55] = 1; // your_telemetry
+    buf[car + 56] = 1; // show_online_names
+    buf[car + 57..car + 59].copy_from_slice(&1_234u16.to_le_bytes()); // tech_level
+    buf[car + 59] = 3; // platform
+
+    let packet = parse_packet_fuzz(&buf).unwrap();
+    let participant = match &packet {
+        Packet::Participants(p) => &p.participants()[21],
+        p => panic!("Unexpected packet: {:?}", p),
+    };
+
+    assert_eq!(participant.network_id(), Some(17));
+    assert_eq!(participant.my_team(), Some(true));
+    assert_eq!(participant.race_number(), 44);
+    assert_eq!(participant.name(), "Lula");
+    assert_eq!(participant.show_online_names(), Some(true));
+    assert_eq!(participant.tech_level(), Some(1_234));
+    assert_eq!(participant.platform(), Some(3));
+
+    assert_eq!(encode_packet(&packet), buf);
+}
+
+#[test]
+fn parses_the_last_player_of_2023_lobby_info() {
+    let mut buf = zeroed(2023, PacketType::LobbyInfo);
+    let header_size = header_size(2023);
+    buf[header_size] = 22; // num_players
+    let car = header_size + 1 + 21 * 54;
+    buf[car + 1] = 2; // team
+    buf[car + 3] = 4; // platform
+    buf[car + 4..car + 8].copy_from_slice(b"Lula");
+    buf[car + 52] = 44; // car_number
+    buf[car + 53] = 1; // ready_status
+
+    let packet = parse_packet_fuzz(&buf).unwrap();
+    let player = match &packet {
+        Packet::LobbyInfo(p) => &p.players()[21],
+        p => panic!("Unexpected packet: {:?}", p),
+    };
+
+    assert_eq!(player.team(), Some(Team::RedBullRacing));
+    assert_eq!(player.platform(), Some(4));
+    assert_eq!(player.name(), "Lula");
+    assert_eq!(player.car_number(), Some(44));
+    assert_eq!(player.tech_level(), None);
+    assert_eq!(player.ready_status(), ReadyStatus::Ready);
+    assert!(parse_packet_fuzz(&buf[..buf.len() - 1]).is_err());
+
+    assert_eq!(encode_packet(&packet), buf);
+}
+
+#[test]
+fn parses_the_last_player_of_2024_lobby_info() {
+    let mut buf = zeroed(2024, PacketType::LobbyInfo);
+    let header_size = header_size(2024);
+    buf[header_size] = 22; // num_players
+    let car = header_size + 1 + 21 * 58;
+    buf[car + 3] = 1; // platform
+    buf[car + 4..car + 8].copy_from_slice(b"Lula");
+    buf[car + 52] = 44; // car_number
+    buf[car + 53] = 1; // your_telemetry
+    buf[car + 54] = 1; // show_online_names
+    buf[car + 55..car + 57].copy_from_slice(&1_234u16.to_le_bytes()); // tech_level
+    buf[car + 57] = 2; // ready_status
+
+    let packet = parse_packet_fuzz(&buf).unwrap();
+    let player = match &packet {
+        Packet::LobbyInfo(p) => &p.players()[21],
+        p => panic!("Unexpected packet: {:?}", p),
+    };
+
+    assert_eq!(player.platform(), Some(1));
+    assert_eq!(player.name(), "Lula");
+    assert_eq!(player.car_number(), Some(44));
+    assert_eq!(player.your_telemetry(), Some(Telemetry::Public));
+    assert_eq!(player.show_online_names(), Some(true));
+    assert_eq!(player.tech_level(), Some(1_234));
+    assert_eq!(player.ready_status(), ReadyStatus::Spectating);
+    assert!(parse_packet_fuzz(&buf[..buf.len() - 1]).is_err());
+
+    assert_eq!(encode_packet(&packet), buf);
+}
+
+#[test]
+fn older_participants_have_no_network_id() {
+    let buf = zeroed(2019, PacketType::Participants);
+
+    match parse_packet_fuzz(&buf).unwrap() {
+        Packet::Participants(p) => {
+            assert_eq!(p.participants()[0].network_id(), None);
+            assert_eq!(p.participants()[0].platform(), None);
+        }
+        p => panic!("Unexpected packet: {:?}", p),
+    }
+}
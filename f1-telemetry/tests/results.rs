@@ -0,0 +1,56 @@
+use f1_telemetry::export::results::{ClassifiedDriver, Gap, SessionResults};
+use f1_telemetry::packet::lap::ResultStatus;
+use f1_telemetry::packet::participants::Team;
+
+fn driver(position: u8, name: &str, race_time: f64) -> ClassifiedDriver {
+    ClassifiedDriver {
+        position,
+        vehicle_index: position - 1,
+        name: String::from(name),
+        team: Some(Team::Ferrari),
+        grid_position: position,
+        num_laps: 10,
+        result_status: ResultStatus::Finished,
+        race_time,
+        gap: Gap::Leader,
+        best_lap_time: Some(80.0),
+        fastest_lap: position == 1,
+        num_pit_stops: 1,
+        penalties_time: 0,
+        num_penalties: 0,
+        points: 0,
+    }
+}
+
+fn results() -> SessionResults {
+    SessionResults::from_drivers(vec![
+        driver(1, "Räikkönen", 800.0),
+        driver(2, "Pérez, Sergio", 801.5),
+    ])
+}
+
+fn names(csv: &str) -> Vec<&str> {
+    csv.lines()
+        .skip(1)
+        .map(|line| line.split(",Ferrari,").next().unwrap())
+        .collect()
+}
+
+#[test]
+fn writes_the_names_as_sent_in_csv() {
+    let csv = results().to_csv();
+
+    assert_eq!(names(&csv), ["1,Räikkönen", "2,\"Pérez, Sergio\""]);
+}
+
+#[test]
+fn writes_the_names_in_ascii_in_csv() {
+    let csv = results().with_ascii(true).to_csv();
+
+    assert!(csv.is_ascii());
+    assert_eq!(names(&csv), ["1,Raikkonen", "2,\"Perez, Sergio\""]);
+    assert!(results()
+        .with_ascii(true)
+        .to_markdown()
+        .contains("Räikkönen"));
+}
@@ -0,0 +1,54 @@
+mod common;
+
+use f1_telemetry::packet::session::{PacketSessionData, Weather};
+use f1_telemetry::packet::{header_size, parse_packet_fuzz, Packet, PacketType};
+
+/// Offset of the weather forecast samples, after the marshal zones.
+const FORECAST_OFFSET: usize = 19 + 21 * 5 + 2 + 1;
+
+fn zeroed(packet_format: u16, size: usize) -> Vec<u8> {
+    let mut buf = common::header(packet_format, PacketType::Session.id());
+    buf.resize(size, 0);
+    buf
+}
+
+fn parse(buf: &[u8]) -> PacketSessionData {
+    match parse_packet_fuzz(buf).unwrap() {
+        Packet::Session(p) => p,
+        p => panic!("Unexpected packet: {:?}", p),
+    }
+}
+
+#[test]
+fn parses_the_forecast_of_a_2023_session() {
+    let mut buf = zeroed(2023, 644);
+    let samples = header_size(2023) + FORECAST_OFFSET;
+    buf[samples - 1] = 56; // num_weather_forecast_samples
+    let last = samples + 55 * 8;
+    buf[last + 1] = 60; // time_offset
+    buf[last + 2] = 4; // weather
+    buf[last + 3] = 31; // track_temperature
+    buf[last + 7] = 80; // rain_percentage
+
+    let packet = parse(&buf);
+    let forecast = packet.weather_forecast();
+
+    assert_eq!(forecast.len(), 56);
+    assert_eq!(forecast[55].time_offset(), 60);
+    assert_eq!(forecast[55].weather(), Weather::HeavyRain);
+    assert_eq!(forecast[55].track_temperature(), 31);
+    assert_eq!(forecast[55].rain_percentage(), Some(80));
+}
+
+#[test]
+fn parses_the_forecast_of_a_2024_session() {
+    let mut buf = zeroed(2024, 753);
+    let samples = header_size(2024) + FORECAST_OFFSET;
+    buf[samples - 1] = 64; // num_weather_forecast_samples
+    buf[samples + 63 * 8 + 7] = 25; // rain_percentage
+
+    let packet = parse(&buf);
+
+    assert_eq!(packet.weather_forecast().len(), 64);
+    assert_eq!(packet.weather_forecast()[63].rain_percentage(), Some(25));
+}
@@ -0,0 +1,102 @@
+mod common;
+
+use f1_telemetry::packet::header::Player;
+use f1_telemetry::packet::{header_size, parse_packet_fuzz, Packet, PacketType};
+use f1_telemetry::session::tracker::SessionTracker;
+
+const PACKET_FORMATS: [u16; 5] = [2020, 2021, 2022, 2023, 2024];
+
+/// Size of the lap data of one car, and offset of its car position.
+fn lap_data_layout(packet_format: u16) -> (usize, usize) {
+    match packet_format {
+        2020 => (53, 44),
+        2021 | 2022 => (43, 24),
+        2023 => (50, 30),
+        _ => (57, 32),
+    }
+}
+
+/// Size of the status of one car, and offset of its fuel in tank.
+fn car_status_layout(packet_format: u16) -> (usize, usize) {
+    match packet_format {
+        2020 => (60, 5),
+        2021 | 2022 => (47, 5),
+        _ => (55, 5),
+    }
+}
+
+fn split_screen_packet(packet_format: u16, packet_type: PacketType) -> Vec<u8> {
+    let mut buf = common::split_screen_header(packet_format, packet_type.id(), 0, Some(21));
+    buf.resize(packet_type.size(packet_format).unwrap(), 0);
+    buf
+}
+
+#[test]
+fn reads_the_secondary_player_lap_data() {
+    for &packet_format in PACKET_FORMATS.iter() {
+        let (car_size, car_position) = lap_data_layout(packet_format);
+        let mut buf = split_screen_packet(packet_format, PacketType::LapData);
+        buf[header_size(packet_format) + car_position] = 4;
+        buf[header_size(packet_format) + 21 * car_size + car_position] = 11;
+
+        let packet = parse_packet_fuzz(&buf).unwrap();
+        let p = match &packet {
+            Packet::Lap(p) => p,
+            p => panic!("Unexpected packet: {:?}", p),
+        };
+        let primary = p.player_lap_data(Player::Primary).unwrap();
+        let secondary = p.player_lap_data(Player::Secondary).unwrap();
+        assert_eq!(primary.car_position(), 4, "{}", packet_format);
+        assert_eq!(secondary.car_position(), 11, "{}", packet_format);
+
+        let mut tracker = SessionTracker::new();
+        tracker.update(&packet);
+        assert!(tracker.is_split_screen());
+
+        let focus = tracker.focus(Player::Secondary).unwrap();
+        assert_eq!(focus.car_index(), 21);
+        assert_eq!(
+            focus.position().unwrap().position(),
+            11,
+            "{}",
+            packet_format
+        );
+        let focus = tracker.focus(Player::Primary).unwrap();
+        assert_eq!(focus.position().unwrap().position(), 4, "{}", packet_format);
+    }
+}
+
+#[test]
+fn reads_the_secondary_player_car_status() {
+    for &packet_format in PACKET_FORMATS.iter() {
+        let (car_size, fuel_in_tank) = car_status_layout(packet_format);
+        let mut buf = split_screen_packet(packet_format, PacketType::CarStatus);
+        let car = header_size(packet_format) + 21 * car_size + fuel_in_tank;
+        buf[car..car + 4].copy_from_slice(&37.5f32.to_le_bytes());
+
+        let packet = parse_packet_fuzz(&buf).unwrap();
+        let p = match &packet {
+            Packet::CarStatus(p) => p,
+            p => panic!("Unexpected packet: {:?}", p),
+        };
+
+        let primary = p.player_car_status(Player::Primary).unwrap();
+        let secondary = p.player_car_status(Player::Secondary).unwrap();
+        assert_eq!(primary.fuel_in_tank(), 0.0, "{}", packet_format);
+        assert_eq!(secondary.fuel_in_tank(), 37.5, "{}", packet_format);
+    }
+}
+
+#[test]
+fn single_player_has_no_secondary_car() {
+    let mut buf = common::header(2024, PacketType::CarStatus.id());
+    buf.resize(PacketType::CarStatus.size(2024).unwrap(), 0);
+
+    match parse_packet_fuzz(&buf).unwrap() {
+        Packet::CarStatus(p) => {
+            assert!(p.player_car_status(Player::Primary).is_some());
+            assert!(p.player_car_status(Player::Secondary).is_none());
+        }
+        p => panic!("Unexpected packet: {:?}", p),
+    }
+}
@@ -0,0 +1,56 @@
+mod common;
+
+use std::net::UdpSocket;
+use std::time::{Duration, Instant};
+
+use f1_telemetry::packet::{Packet, PacketType};
+use f1_telemetry::{Stream, StreamError};
+
+fn send(stream: &Stream, datagram: &[u8]) {
+    let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+    socket
+        .send_to(datagram, stream.socket().local_addr().unwrap())
+        .unwrap();
+}
+
+fn receive(stream: &Stream) -> Result<Packet, StreamError> {
+    let start = Instant::now();
+    loop {
+        if let Some(packet) = stream.next().transpose() {
+            return packet;
+        }
+        assert!(
+            start.elapsed() < Duration::from_secs(5),
+            "No packet received"
+        );
+        std::thread::sleep(Duration::from_millis(1));
+    }
+}
+
+fn lap_data(packet_format: u16) -> Vec<u8> {
+    let mut buf = common::header(packet_format, PacketType::LapData.id());
+    buf.resize(PacketType::LapData.size(packet_format).unwrap(), 0);
+    buf
+}
+
+#[test]
+fn rejects_a_short_datagram_after_a_full_one() {
+    let stream = Stream::new("127.0.0.1:0").unwrap();
+    let buf = lap_data(2024);
+
+    send(&stream, &buf);
+    assert!(matches!(receive(&stream), Ok(Packet::Lap(_))));
+
+    // The end of the receive buffer still holds the previous packet
+    send(&stream, &buf[..buf.len() - 1]);
+    assert!(matches!(receive(&stream), Err(StreamError::Unpack(_))));
+}
+
+#[test]
+fn rejects_a_short_datagram_in_a_small_buffer() {
+    let mut stream = Stream::new("127.0.0.1:0").unwrap();
+    stream.set_buffer_size(64);
+
+    send(&stream, &lap_data(2024)[..60]);
+    assert!(matches!(receive(&stream), Err(StreamError::Unpack(_))));
+}
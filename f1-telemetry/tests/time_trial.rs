@@ -0,0 +1,96 @@
+mod common;
+
+use std::net::UdpSocket;
+use std::time::{Duration, Instant};
+
+use f1_telemetry::packet::encode::encode_packet;
+use f1_telemetry::packet::{parse_packet_fuzz, Packet, PacketType};
+use f1_telemetry::Stream;
+
+/// Time trial data set, with a lap and sector times in milliseconds.
+fn data_set(buf: &mut Vec<u8>, car_idx: u8, sectors: [u32; 3]) {
+    buf.push(car_idx);
+    buf.push(2); // team_id
+    buf.extend_from_slice(&sectors.iter().sum::<u32>().to_le_bytes());
+    for sector in sectors.iter() {
+        buf.extend_from_slice(&sector.to_le_bytes());
+    }
+    buf.push(0); // traction_control
+    buf.push(1); // gearbox_assist
+    buf.push(0); // anti_lock_brakes
+    buf.push(1); // equal_car_performance
+    buf.push(0); // custom_setup
+    buf.push(1); // valid
+}
+
+fn time_trial_packet() -> Vec<u8> {
+    let mut buf = common::header(2024, PacketType::TimeTrial.id());
+    data_set(&mut buf, 0, [30_500, 40_250, 20_000]);
+    data_set(&mut buf, 0, [30_000, 40_000, 20_000]);
+    data_set(&mut buf, 1, [29_800, 40_100, 19_900]);
+
+    buf
+}
+
+#[test]
+fn parses_a_2024_time_trial_datagram() {
+    let buf = time_trial_packet();
+    assert_eq!(Some(buf.len()), PacketType::TimeTrial.size(2024));
+
+    let packet = match parse_packet_fuzz(&buf).unwrap() {
+        Packet::TimeTrial(packet) => packet,
+        p => panic!("Unexpected packet: {:?}", p),
+    };
+
+    assert_eq!(packet.header().packet_id(), PacketType::TimeTrial.id());
+
+    let personal_best = packet.personal_best_data_set();
+    assert_eq!(personal_best.lap_time(), 90.0);
+    assert_eq!(personal_best.sector_times(), [30.0, 40.0, 20.0]);
+    assert!(personal_best.equal_car_performance());
+    assert!(personal_best.valid());
+
+    assert_eq!(packet.rival_data_set().car_idx(), 1);
+    assert!((packet.session_best_to_personal_best().unwrap() - 0.75).abs() < 1e-3);
+    assert!((packet.personal_best_to_rival().unwrap() - 0.2).abs() < 1e-3);
+
+    let gaps = packet.sector_gaps_to_rival().unwrap();
+    assert!((gaps[0] - 0.2).abs() < 1e-3);
+    assert!((gaps[1] + 0.1).abs() < 1e-3);
+    assert!((gaps[2] - 0.1).abs() < 1e-3);
+
+    assert_eq!(encode_packet(&Packet::TimeTrial(packet)), buf);
+}
+
+#[test]
+fn receives_a_2024_time_trial_datagram() {
+    let stream = Stream::new("127.0.0.1:0").unwrap();
+    let addr = stream.socket().local_addr().unwrap();
+
+    let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+    socket.send_to(&time_trial_packet(), addr).unwrap();
+
+    let start = Instant::now();
+    let packet = loop {
+        if let Some(packet) = stream.next().unwrap() {
+            break packet;
+        }
+        assert!(
+            start.elapsed() < Duration::from_secs(5),
+            "No packet received"
+        );
+        std::thread::sleep(Duration::from_millis(1));
+    };
+
+    match packet {
+        Packet::TimeTrial(packet) => assert_eq!(packet.rival_data_set().car_idx(), 1),
+        p => panic!("Unexpected packet: {:?}", p),
+    }
+}
+
+#[test]
+fn rejects_a_truncated_time_trial_datagram() {
+    let buf = time_trial_packet();
+
+    assert!(parse_packet_fuzz(&buf[..buf.len() - 1]).is_err());
+}
@@ -0,0 +1,160 @@
+mod common;
+
+use f1_telemetry::analysis::track_map::{prebaked_outline, TrackMap};
+use f1_telemetry::meta::tracks::TrackInfo;
+use f1_telemetry::packet::lap::PacketLapData;
+use f1_telemetry::packet::motion::PacketMotionData;
+use f1_telemetry::packet::session::Track;
+use f1_telemetry::packet::{header_size, parse_packet_fuzz, Packet, PacketType};
+
+/// Radius of a round track, in metres.
+const RADIUS: f32 = 500.0;
+
+const LAP_LENGTH: f32 = 2.0 * std::f32::consts::PI * RADIUS;
+
+/// A car on the track: its lap distance, and whether it is in the pit lane.
+type Car = (f32, bool);
+
+fn lap_data(cars: &[Car]) -> PacketLapData {
+    let mut buf = common::header(2019, PacketType::LapData.id());
+    buf.resize(PacketType::LapData.size(2019).unwrap(), 0);
+    for (i, &(lap_distance, in_pits)) in cars.iter().enumerate() {
+        let car = header_size(2019) + i * 41;
+        buf[car + 20..car + 24].copy_from_slice(&lap_distance.to_le_bytes());
+        buf[car + 34] = in_pits as u8; // pit_status
+    }
+
+    match parse_packet_fuzz(&buf).unwrap() {
+        Packet::Lap(p) => p,
+        p => panic!("Unexpected packet: {:?}", p),
+    }
+}
+
+/// Motion of cars driving around the round track, the pit lane being inside.
+fn motion(cars: &[Car]) -> PacketMotionData {
+    let mut buf = common::header(2019, PacketType::Motion.id());
+    buf.resize(PacketType::Motion.size(2019).unwrap(), 0);
+    for (i, &(lap_distance, in_pits)) in cars.iter().enumerate() {
+        let radius = if in_pits { RADIUS - 50.0 } else { RADIUS };
+        let angle = lap_distance / RADIUS;
+        let car = header_size(2019) + i * 60;
+        buf[car..car + 4].copy_from_slice(&(radius * angle.cos()).to_le_bytes());
+        buf[car + 8..car + 12].copy_from_slice(&(radius * angle.sin()).to_le_bytes());
+    }
+
+    match parse_packet_fuzz(&buf).unwrap() {
+        Packet::Motion(p) => p,
+        p => panic!("Unexpected packet: {:?}", p),
+    }
+}
+
+fn distance(a: (f32, f32), b: (f32, f32)) -> f32 {
+    ((a.0 - b.0).powi(2) + (a.1 - b.1).powi(2)).sqrt()
+}
+
+#[test]
+fn orders_the_outline_around_the_lap() {
+    let mut track_map = TrackMap::new();
+
+    // One car from the line, another from halfway around, and one in the pits.
+    let mut lap_distance = 0.0;
+    while lap_distance < LAP_LENGTH / 2.0 {
+        let cars = [
+            (lap_distance, false),
+            (lap_distance + LAP_LENGTH / 2.0, false),
+            (lap_distance, true),
+        ];
+        track_map.update_lap(&lap_data(&cars));
+        track_map.update(&motion(&cars));
+        lap_distance += 10.0;
+    }
+
+    assert!(track_map.is_complete());
+
+    // Consecutive points are next to each other on track, the pit lane left out.
+    let outline = track_map.outline();
+    assert_eq!(outline.len(), track_map.num_points());
+    for pair in outline.windows(2) {
+        assert!(distance(pair[0], pair[1]) < 0.02, "{:?}", pair);
+    }
+    for &point in &outline {
+        assert!(
+            (distance(point, (0.5, 0.5)) - 0.5).abs() < 0.01,
+            "{:?}",
+            point
+        );
+    }
+
+    // From the line, at the rightmost point of the track.
+    assert!(distance(outline[0], (1.0, 0.5)) < 0.01, "{:?}", outline[0]);
+}
+
+#[test]
+fn records_no_outline_without_lap_distances() {
+    let mut track_map = TrackMap::new();
+    track_map.update(&motion(&[(100.0, false)]));
+
+    assert!(track_map.outline().is_empty());
+    assert!(track_map.car_position(0).is_some());
+}
+
+#[test]
+fn falls_back_to_the_prebaked_outline() {
+    let mut track_map = TrackMap::new();
+    track_map.set_track(Track::Monza);
+
+    let prebaked = prebaked_outline(Track::Monza).unwrap();
+    assert!(track_map.is_prebaked());
+    assert_eq!(track_map.outline(), prebaked.to_vec());
+    assert_eq!(prebaked.first(), prebaked.last());
+
+    assert!(prebaked_outline(Track::Suzuka).is_none());
+    track_map.set_track(Track::Suzuka);
+    assert!(!track_map.is_prebaked());
+    assert!(track_map.outline().is_empty());
+}
+
+#[test]
+fn places_the_cars_along_the_prebaked_outline_by_lap_distance() {
+    let mut track_map = TrackMap::new();
+    track_map.set_track(Track::Monza);
+    let length = f32::from(TrackInfo::get(Track::Monza).unwrap().length());
+
+    let cars = [(0.0, false), (length / 2.0, false), (length / 4.0, true)];
+    track_map.update_lap(&lap_data(&cars));
+    track_map.update(&motion(&cars));
+
+    let outline = track_map.outline();
+    let on_outline = |point: (f32, f32)| {
+        outline.windows(2).any(|w| {
+            let along = distance(w[0], point) + distance(point, w[1]);
+            (along - distance(w[0], w[1])).abs() < 1e-4
+        })
+    };
+
+    assert_eq!(track_map.car_position(0), Some(outline[0]));
+    for i in 1..cars.len() {
+        let position = track_map.car_position(i).unwrap();
+        assert!(on_outline(position), "{:?}", position);
+        assert_ne!(position, outline[0]);
+    }
+    assert_eq!(track_map.car_position(3), None);
+}
+
+#[test]
+fn prefers_the_recorded_outline_once_complete() {
+    let mut track_map = TrackMap::new();
+    track_map.set_track(Track::Monza);
+
+    let mut lap_distance = 0.0;
+    while lap_distance < LAP_LENGTH {
+        let cars = [(lap_distance, false)];
+        track_map.update_lap(&lap_data(&cars));
+        track_map.update(&motion(&cars));
+        lap_distance += 10.0;
+    }
+
+    assert!(track_map.is_complete());
+    assert!(!track_map.is_prebaked());
+    assert_eq!(track_map.outline().len(), track_map.num_points());
+}
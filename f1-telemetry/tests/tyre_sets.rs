@@ -0,0 +1,71 @@
+mod common;
+
+use f1_telemetry::packet::car_status::{TyreCompound, TyreCompoundVisual};
+use f1_telemetry::packet::encode::encode_packet;
+use f1_telemetry::packet::{parse_packet_fuzz, Packet, PacketType};
+
+/// Tyre sets packet of the player's car, with the third set fitted.
+fn tyre_sets_packet(packet_format: u16) -> Vec<u8> {
+    let mut buf = common::header(packet_format, PacketType::TyreSets.id());
+    buf.push(0); // car_idx
+
+    for i in 0..20u8 {
+        let (actual, visual) = match i {
+            0..=12 => (16 + i % 3, 16 + i % 3),
+            13..=16 => (7, 7),
+            _ => (8, 8),
+        };
+        buf.push(actual);
+        buf.push(visual);
+        buf.push(i * 5); // wear
+        buf.push((i % 2 == 0) as u8); // available
+        buf.push(10); // recommended_session
+        buf.push(20 - i); // life_span
+        buf.push(25); // usable_life
+        buf.extend_from_slice(&(i as i16 * -100).to_le_bytes()); // lap_delta_time
+        buf.push((i == 2) as u8); // fitted
+    }
+
+    buf.push(2); // fitted_idx
+    buf
+}
+
+#[test]
+fn parses_a_2023_tyre_sets_packet() {
+    for &packet_format in [2023, 2024].iter() {
+        let buf = tyre_sets_packet(packet_format);
+        assert_eq!(Some(buf.len()), PacketType::TyreSets.size(packet_format));
+
+        let packet = match parse_packet_fuzz(&buf).unwrap() {
+            Packet::TyreSets(packet) => packet,
+            p => panic!("Unexpected packet: {:?}", p),
+        };
+
+        assert!(packet.is_player_car());
+        assert_eq!(packet.tyre_set_data().len(), 20);
+
+        let fitted = packet.fitted().unwrap();
+        assert!(fitted.fitted());
+        assert_eq!(fitted.actual_tyre_compound(), TyreCompound::C3);
+        assert_eq!(fitted.visual_tyre_compound(), TyreCompoundVisual::Hard);
+        assert_eq!(fitted.wear(), 10);
+        assert_eq!(fitted.life_span(), 18);
+        assert_eq!(fitted.lap_delta_time(), -200);
+
+        let wet = &packet.tyre_set_data()[19];
+        assert_eq!(wet.actual_tyre_compound(), TyreCompound::Wet);
+
+        let available = packet.available();
+        assert_eq!(available.len(), 10);
+        assert_eq!(available[0].life_span(), 20);
+
+        assert_eq!(encode_packet(&Packet::TyreSets(packet)), buf);
+    }
+}
+
+#[test]
+fn rejects_a_truncated_tyre_sets_packet() {
+    let buf = tyre_sets_packet(2023);
+
+    assert!(parse_packet_fuzz(&buf[..buf.len() - 1]).is_err());
+}
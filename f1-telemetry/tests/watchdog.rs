@@ -0,0 +1,74 @@
+mod common;
+
+use f1_telemetry::packet::{parse_packet_fuzz, PacketType};
+use f1_telemetry::watchdog::{Diagnostic, Watchdog};
+
+fn datagram(packet_format: u16, packet_type: PacketType, size: usize) -> Vec<u8> {
+    let mut buf = common::header(packet_format, packet_type.id());
+    buf.resize(size, 0);
+    buf
+}
+
+/// Parse `datagram` and check the outcome with a watchdog firing on the first
+/// failure.
+fn check(datagram: &[u8]) -> Option<Diagnostic> {
+    let result = parse_packet_fuzz(datagram);
+    Watchdog::new(0).check(datagram, result.as_ref().map(|_| ()))
+}
+
+#[test]
+fn accepts_2021_car_telemetry() {
+    let sizes = [(2021, 1347), (2022, 1347), (2023, 1352), (2024, 1352)];
+    for &(packet_format, size) in sizes.iter() {
+        let buf = datagram(packet_format, PacketType::CarTelemetry, size);
+
+        assert!(check(&buf).is_none(), "{}", packet_format);
+    }
+}
+
+#[test]
+fn reports_2021_car_telemetry_of_the_wrong_size() {
+    // A 2021 packet in the layout of F1 2023
+    let buf = datagram(2021, PacketType::CarTelemetry, 1352);
+
+    let diagnostic = check(&buf).unwrap();
+    assert_eq!(diagnostic.packet_format(), Some(2021));
+    assert_eq!(diagnostic.packet_id(), Some(PacketType::CarTelemetry.id()));
+    assert_eq!(diagnostic.size(), 1352);
+    assert_eq!(diagnostic.expected_size(), Some(1347));
+    assert_eq!(diagnostic.last_error(), "Size mismatch");
+}
+
+#[test]
+fn reports_2021_car_telemetry_in_the_2020_layout() {
+    let buf = datagram(2021, PacketType::CarTelemetry, 1307);
+
+    let diagnostic = check(&buf).unwrap();
+    assert_eq!(diagnostic.expected_size(), Some(1347));
+    assert!(diagnostic.last_error().contains("too small"));
+}
+
+#[test]
+fn reports_size_mismatches_of_the_other_2021_packets() {
+    let packets = [
+        (2021, PacketType::Motion),
+        (2022, PacketType::Session),
+        (2023, PacketType::Event),
+        (2021, PacketType::FinalClassification),
+        (2023, PacketType::LobbyInfo),
+        (2022, PacketType::CarDamage),
+        (2024, PacketType::MotionEx),
+    ];
+
+    for &(packet_format, packet_type) in packets.iter() {
+        let size = packet_type.size(packet_format).unwrap();
+
+        let buf = datagram(packet_format, packet_type, size);
+        assert!(check(&buf).is_none(), "{:?} {}", packet_type, packet_format);
+
+        let buf = datagram(packet_format, packet_type, size + 1);
+        let diagnostic = check(&buf).unwrap();
+        assert_eq!(diagnostic.expected_size(), Some(size));
+        assert_eq!(diagnostic.last_error(), "Size mismatch");
+    }
+}
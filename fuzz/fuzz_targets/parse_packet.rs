@@ -0,0 +1,14 @@
+#![no_main]
+use f1_telemetry::packet::encode::encode_packet;
+use f1_telemetry::packet::parse_packet_fuzz;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(packet) = parse_packet_fuzz(data) {
+        // Anything that parses must encode into a packet that parses back to
+        // the same bytes.
+        let encoded = encode_packet(&packet);
+        let reparsed = parse_packet_fuzz(&encoded).expect("Encoded packet does not parse");
+        assert_eq!(encoded, encode_packet(&reparsed));
+    }
+});